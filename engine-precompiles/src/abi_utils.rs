@@ -0,0 +1,373 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::prelude::parameters::AbiSchemaType;
+use crate::prelude::sdk::io::{StorageIntermediate, IO};
+use crate::prelude::storage::{bytes_to_key, KeyPrefix};
+use crate::prelude::types::EthGas;
+use crate::prelude::{str, Address, Borrowed, TryInto, Vec};
+use crate::PrecompileOutput;
+use evm::{Context, ExitError};
+
+/// ABI utilities precompile costs.
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// The base cost of the operation, covers reading the schema from storage.
+    pub(super) const ABI_UTILS_BASE: EthGas = EthGas::new(200);
+
+    /// The cost per word of input.
+    pub(super) const ABI_UTILS_PER_WORD: EthGas = EthGas::new(6);
+}
+
+mod consts {
+    /// Length of the ABI word.
+    pub(super) const WORD_LEN: usize = 32;
+    /// Length of the prefix of the input: operation byte and schema ID.
+    pub(super) const INPUT_PREFIX_LEN: usize = 5;
+    /// Length of the big-endian prefix of dynamic fields in compact layout.
+    pub(super) const COMPACT_LEN_PREFIX: usize = 4;
+
+    /// Convert compact layout into standard ABI encoding.
+    pub(super) const OP_COMPACT_TO_ABI: u8 = 0;
+    /// Convert standard ABI encoding into compact layout.
+    pub(super) const OP_ABI_TO_COMPACT: u8 = 1;
+}
+
+/// ABI utilities precompile address
+///
+/// Address: `0x5b8ab673577ab85c8d204cad5d700b3199ef9ceb`
+/// This address is computed as: `&keccak("abiUtils")[12..]`
+pub const ADDRESS: Address = super::make_address(0x5b8ab673, 0x577ab85c8d204cad5d700b3199ef9ceb);
+
+/// Converts data between the standard ABI encoding of a tuple and its compact layout
+/// for schemas registered on the engine with `register_abi_schema`.
+///
+/// Input: `operation (1 byte) || schema_id (4 bytes, big-endian) || data`, where
+/// operation `0` converts compact layout into ABI encoding and `1` does the opposite.
+///
+/// In compact layout integers use their exact size in big-endian, addresses use 20 bytes,
+/// booleans a single byte, fixed bytes their exact length and dynamic values
+/// (`bytes`, `string`) are prefixed by a 4-byte big-endian length.
+pub struct AbiUtils<I> {
+    io: I,
+}
+
+impl<I> AbiUtils<I> {
+    pub fn new(io: I) -> Self {
+        Self { io }
+    }
+}
+
+impl<I: IO> AbiUtils<I> {
+    /// Reads the schema from the storage of the engine.
+    fn get_schema(&self, schema_id: u32) -> Option<Vec<AbiSchemaType>> {
+        self.io
+            .read_storage(&bytes_to_key(
+                KeyPrefix::AbiSchema,
+                &schema_id.to_le_bytes(),
+            ))
+            .and_then(|value| value.to_value().ok())
+    }
+}
+
+impl<I: IO> Precompile for AbiUtils<I> {
+    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+        let words = (input.len() + consts::WORD_LEN - 1) / consts::WORD_LEN;
+        Ok(costs::ABI_UTILS_PER_WORD * words + costs::ABI_UTILS_BASE)
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = Self::required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        if input.len() < consts::INPUT_PREFIX_LEN {
+            return Err(ExitError::Other(Borrowed("ERR_INVALID_INPUT")));
+        }
+        let schema_id = u32::from_be_bytes(input[1..consts::INPUT_PREFIX_LEN].try_into().unwrap());
+        let schema = self
+            .get_schema(schema_id)
+            .ok_or(ExitError::Other(Borrowed("ERR_ABI_SCHEMA_NOT_FOUND")))?;
+        let data = &input[consts::INPUT_PREFIX_LEN..];
+
+        let output = match input[0] {
+            consts::OP_COMPACT_TO_ABI => compact_to_abi(&schema, data)?,
+            consts::OP_ABI_TO_COMPACT => abi_to_compact(&schema, data)?,
+            _ => return Err(ExitError::Other(Borrowed("ERR_ABI_INVALID_OPERATION"))),
+        };
+
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+fn invalid_data() -> ExitError {
+    ExitError::Other(Borrowed("ERR_ABI_INVALID_DATA"))
+}
+
+/// Takes `len` bytes of `data` starting at `*pos` and advances the position.
+fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Result<&'a [u8], ExitError> {
+    let end = pos.checked_add(len).ok_or_else(invalid_data)?;
+    let bytes = data.get(*pos..end).ok_or_else(invalid_data)?;
+    *pos = end;
+    Ok(bytes)
+}
+
+fn word_at(data: &[u8], offset: usize) -> Result<&[u8], ExitError> {
+    let end = offset
+        .checked_add(consts::WORD_LEN)
+        .ok_or_else(invalid_data)?;
+    data.get(offset..end).ok_or_else(invalid_data)
+}
+
+fn word_to_usize(word: &[u8]) -> Result<usize, ExitError> {
+    if word[..24].iter().any(|b| *b != 0) {
+        return Err(invalid_data());
+    }
+    let value = u64::from_be_bytes(word[24..].try_into().unwrap());
+    value.try_into().map_err(|_| invalid_data())
+}
+
+fn usize_to_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + consts::WORD_LEN - 1) / consts::WORD_LEN * consts::WORD_LEN
+}
+
+/// Converts the compact layout of the tuple described by `schema` into its ABI encoding.
+pub fn compact_to_abi(schema: &[AbiSchemaType], data: &[u8]) -> Result<Vec<u8>, ExitError> {
+    let head_len = schema.len() * consts::WORD_LEN;
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+    let mut pos = 0;
+
+    for field in schema {
+        let mut word = [0u8; 32];
+        match field {
+            AbiSchemaType::Uint(bits) => {
+                let len = (*bits / 8) as usize;
+                word[32 - len..].copy_from_slice(take(data, &mut pos, len)?);
+            }
+            AbiSchemaType::Int(bits) => {
+                let len = (*bits / 8) as usize;
+                let bytes = take(data, &mut pos, len)?;
+                if bytes[0] & 0x80 != 0 {
+                    word = [0xff; 32];
+                }
+                word[32 - len..].copy_from_slice(bytes);
+            }
+            AbiSchemaType::Address => {
+                word[12..].copy_from_slice(take(data, &mut pos, 20)?);
+            }
+            AbiSchemaType::Bool => {
+                let value = take(data, &mut pos, 1)?[0];
+                if value > 1 {
+                    return Err(invalid_data());
+                }
+                word[31] = value;
+            }
+            AbiSchemaType::FixedBytes(len) => {
+                let len = *len as usize;
+                word[..len].copy_from_slice(take(data, &mut pos, len)?);
+            }
+            AbiSchemaType::Bytes | AbiSchemaType::String => {
+                let len_prefix = take(data, &mut pos, consts::COMPACT_LEN_PREFIX)?;
+                let len = u32::from_be_bytes(len_prefix.try_into().unwrap()) as usize;
+                let bytes = take(data, &mut pos, len)?;
+                if field == &AbiSchemaType::String && str::from_utf8(bytes).is_err() {
+                    return Err(invalid_data());
+                }
+                word = usize_to_word(head_len + tail.len());
+                tail.extend_from_slice(&usize_to_word(len));
+                tail.extend_from_slice(bytes);
+                tail.resize(tail.len() + padded_len(len) - len, 0);
+            }
+        }
+        head.extend_from_slice(&word);
+    }
+
+    if pos != data.len() {
+        return Err(invalid_data());
+    }
+
+    head.extend_from_slice(&tail);
+    Ok(head)
+}
+
+/// Converts the ABI encoding of the tuple described by `schema` into its compact layout.
+pub fn abi_to_compact(schema: &[AbiSchemaType], data: &[u8]) -> Result<Vec<u8>, ExitError> {
+    let mut output = Vec::with_capacity(data.len());
+
+    for (i, field) in schema.iter().enumerate() {
+        let word = word_at(data, i * consts::WORD_LEN)?;
+        match field {
+            AbiSchemaType::Uint(bits) => {
+                let len = (*bits / 8) as usize;
+                if word[..32 - len].iter().any(|b| *b != 0) {
+                    return Err(invalid_data());
+                }
+                output.extend_from_slice(&word[32 - len..]);
+            }
+            AbiSchemaType::Int(bits) => {
+                let len = (*bits / 8) as usize;
+                let fill = if word[32 - len] & 0x80 != 0 { 0xff } else { 0 };
+                if word[..32 - len].iter().any(|b| *b != fill) {
+                    return Err(invalid_data());
+                }
+                output.extend_from_slice(&word[32 - len..]);
+            }
+            AbiSchemaType::Address => {
+                if word[..12].iter().any(|b| *b != 0) {
+                    return Err(invalid_data());
+                }
+                output.extend_from_slice(&word[12..]);
+            }
+            AbiSchemaType::Bool => {
+                if word[..31].iter().any(|b| *b != 0) || word[31] > 1 {
+                    return Err(invalid_data());
+                }
+                output.push(word[31]);
+            }
+            AbiSchemaType::FixedBytes(len) => {
+                let len = *len as usize;
+                if word[len..].iter().any(|b| *b != 0) {
+                    return Err(invalid_data());
+                }
+                output.extend_from_slice(&word[..len]);
+            }
+            AbiSchemaType::Bytes | AbiSchemaType::String => {
+                let offset = word_to_usize(word)?;
+                let len = word_to_usize(word_at(data, offset)?)?;
+                let start = offset + consts::WORD_LEN;
+                let end = start.checked_add(len).ok_or_else(invalid_data)?;
+                let bytes = data.get(start..end).ok_or_else(invalid_data)?;
+                if field == &AbiSchemaType::String && str::from_utf8(bytes).is_err() {
+                    return Err(invalid_data());
+                }
+                let len: u32 = len.try_into().map_err(|_| invalid_data())?;
+                output.extend_from_slice(&len.to_be_bytes());
+                output.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::sdk::memory;
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::vec;
+    use crate::utils::new_context;
+    use core::cell::RefCell;
+
+    fn schema() -> Vec<AbiSchemaType> {
+        vec![
+            AbiSchemaType::Uint(64),
+            AbiSchemaType::Int(8),
+            AbiSchemaType::Address,
+            AbiSchemaType::Bool,
+            AbiSchemaType::Bytes,
+            AbiSchemaType::FixedBytes(4),
+            AbiSchemaType::String,
+        ]
+    }
+
+    fn compact() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&1_000u64.to_be_bytes());
+        data.push(0xfe); // -2
+        data.extend_from_slice(&[0x11; 20]);
+        data.push(1);
+        data.extend_from_slice(&3u32.to_be_bytes());
+        data.extend_from_slice(&[1, 2, 3]);
+        data.extend_from_slice(&[0xaa, 0xbb, 0xcc, 0xdd]);
+        data.extend_from_slice(&5u32.to_be_bytes());
+        data.extend_from_slice(b"hello");
+        data
+    }
+
+    fn abi() -> Vec<u8> {
+        ethabi::encode(&[
+            ethabi::Token::Uint(1_000.into()),
+            ethabi::Token::Int(ethabi::Uint::MAX - 1),
+            ethabi::Token::Address([0x11; 20].into()),
+            ethabi::Token::Bool(true),
+            ethabi::Token::Bytes(vec![1, 2, 3]),
+            ethabi::Token::FixedBytes(vec![0xaa, 0xbb, 0xcc, 0xdd]),
+            ethabi::Token::String("hello".into()),
+        ])
+    }
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(ADDRESS, near_account_to_evm_address("abiUtils".as_bytes()));
+    }
+
+    #[test]
+    fn test_run() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        io.write_borsh(
+            &bytes_to_key(KeyPrefix::AbiSchema, &7u32.to_le_bytes()),
+            &schema(),
+        );
+        let precompile = AbiUtils::new(io);
+
+        let mut input = vec![consts::OP_COMPACT_TO_ABI];
+        input.extend_from_slice(&7u32.to_be_bytes());
+        input.extend_from_slice(&compact());
+        let output = precompile
+            .run(&input, None, &new_context(), true)
+            .unwrap()
+            .output;
+        assert_eq!(output, abi());
+
+        // the schema is read from the storage of the engine
+        input[1..consts::INPUT_PREFIX_LEN].copy_from_slice(&8u32.to_be_bytes());
+        assert!(precompile.run(&input, None, &new_context(), true).is_err());
+    }
+
+    #[test]
+    fn test_compact_to_abi() {
+        assert_eq!(compact_to_abi(&schema(), &compact()).unwrap(), abi());
+    }
+
+    #[test]
+    fn test_abi_to_compact() {
+        assert_eq!(abi_to_compact(&schema(), &abi()).unwrap(), compact());
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        // trailing bytes
+        let mut data = compact();
+        data.push(0);
+        assert!(compact_to_abi(&schema(), &data).is_err());
+
+        // truncated data
+        let data = compact();
+        assert!(compact_to_abi(&schema(), &data[..data.len() - 1]).is_err());
+        let data = abi();
+        assert!(abi_to_compact(&schema(), &data[..data.len() - 32]).is_err());
+
+        // value doesn't fit into the field
+        let data = ethabi::encode(&[ethabi::Token::Uint(ethabi::Uint::MAX)]);
+        assert!(abi_to_compact(&[AbiSchemaType::Uint(64)], &data).is_err());
+        let data = ethabi::encode(&[ethabi::Token::Uint(2.into())]);
+        assert!(abi_to_compact(&[AbiSchemaType::Bool], &data).is_err());
+    }
+}
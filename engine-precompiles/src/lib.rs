@@ -4,29 +4,37 @@
 #![cfg_attr(not(feature = "std"), feature(alloc_error_handler))]
 #![cfg_attr(feature = "log", feature(panic_info_message))]
 
+pub mod abi_utils;
 pub mod blake2;
 pub mod bn128;
 pub mod hash;
 pub mod identity;
+pub mod log_index;
 pub mod modexp;
 pub mod native;
+pub mod near_gas;
 mod prelude;
 pub mod random;
 pub mod secp256k1;
 #[cfg(test)]
 mod utils;
 
+use crate::abi_utils::AbiUtils;
 use crate::blake2::Blake2F;
 use crate::bn128::{Bn128Add, Bn128Mul, Bn128Pair};
 use crate::hash::{RIPEMD160, SHA256};
 use crate::identity::Identity;
+use crate::log_index::LogIndex;
 use crate::modexp::ModExp;
 use crate::native::{ExitToEthereum, ExitToNear};
+use crate::near_gas::NearGasInfo;
+use crate::prelude::sdk::env::Env;
+use crate::prelude::sdk::io::IO;
 use crate::prelude::types::EthGas;
-use crate::prelude::{Vec, H256};
+use crate::prelude::{BorshDeserialize, BorshSerialize, Vec, H256};
 use crate::random::RandomSeed;
 use crate::secp256k1::ECRecover;
-use aurora_engine_types::{account_id::AccountId, vec, Address, BTreeMap, Box};
+use aurora_engine_types::{account_id::AccountId, vec, Address, BTreeMap, Box, Cow};
 use evm::backend::Log;
 use evm::executor;
 use evm::{Context, ExitError, ExitSucceed};
@@ -101,9 +109,9 @@ impl HardFork for Istanbul {}
 
 impl HardFork for Berlin {}
 
-pub struct Precompiles(pub prelude::BTreeMap<Address, Box<dyn Precompile>>);
+pub struct Precompiles<'a>(pub prelude::BTreeMap<Address, Box<dyn Precompile + 'a>>);
 
-impl executor::PrecompileSet for Precompiles {
+impl<'a> executor::PrecompileSet for Precompiles<'a> {
     fn execute(
         &self,
         address: prelude::Address,
@@ -123,14 +131,68 @@ impl executor::PrecompileSet for Precompiles {
     }
 }
 
-pub struct PrecompileConstructorContext {
+/// Takes the place of a paused precompile: every call to it fails, instead of succeeding
+/// without effect as a call to an account without code would.
+struct Paused;
+
+impl Precompile for Paused {
+    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(EthGas::new(0))
+    }
+
+    fn run(
+        &self,
+        _input: &[u8],
+        _target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        Err(ExitError::Other(Cow::from("ERR_PAUSED")))
+    }
+}
+
+pub struct PrecompileConstructorContext<I, E> {
     pub current_account_id: AccountId,
     pub random_seed: H256,
+    pub host_functions: HostFunctions,
+    /// Storage of the engine, read by the precompiles exposing its state.
+    pub io: I,
+    /// Environment of the call, read by the precompiles exposing the NEAR context when they run.
+    pub env: E,
 }
 
-impl Precompiles {
+/// Optional host functions of the NEAR runtime the precompiles can be accelerated with.
+///
+/// The imports of a WASM contract are resolved when it is instantiated, so a missing host
+/// function can't be detected from the contract: a contract importing it doesn't run at all.
+/// Their availability is declared in the configuration of the engine instead, and the
+/// precompiles fall back to their pure-Rust implementation for the missing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct HostFunctions {
+    /// `ecrecover`, available on every NEAR runtime the engine was released for.
+    pub ecrecover: bool,
+    /// The `alt_bn128` operations. The bn128 precompiles have no host path yet, this flag
+    /// only records their availability.
+    pub alt_bn128: bool,
+    /// `ed25519_verify`, not used by any precompile yet.
+    pub ed25519: bool,
+}
+
+impl Default for HostFunctions {
+    fn default() -> Self {
+        Self {
+            ecrecover: true,
+            alt_bn128: false,
+            ed25519: false,
+        }
+    }
+}
+
+impl<'a> Precompiles<'a> {
     #[allow(dead_code)]
-    pub fn new_homestead(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_homestead<I: IO + Copy + 'a, E: Env + 'a>(
+        ctx: PrecompileConstructorContext<I, E>,
+    ) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -138,22 +200,31 @@ impl Precompiles {
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
             RandomSeed::ADDRESS,
+            near_gas::ADDRESS,
+            abi_utils::ADDRESS,
+            log_index::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
-            Box::new(ECRecover),
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
+            Box::new(ECRecover::new(ctx.host_functions.ecrecover)),
             Box::new(SHA256),
             Box::new(RIPEMD160),
             Box::new(ExitToNear::new(ctx.current_account_id.clone())),
             Box::new(ExitToEthereum::new(ctx.current_account_id)),
             Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(NearGasInfo::new(ctx.env)),
+            Box::new(AbiUtils::new(ctx.io)),
+            Box::new(LogIndex::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
         Precompiles(map)
     }
 
     #[allow(dead_code)]
-    pub fn new_byzantium(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_byzantium<I: IO + Copy + 'a, E: Env + 'a>(
+        ctx: PrecompileConstructorContext<I, E>,
+    ) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -166,9 +237,12 @@ impl Precompiles {
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
             RandomSeed::ADDRESS,
+            near_gas::ADDRESS,
+            abi_utils::ADDRESS,
+            log_index::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
-            Box::new(ECRecover),
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
+            Box::new(ECRecover::new(ctx.host_functions.ecrecover)),
             Box::new(SHA256),
             Box::new(RIPEMD160),
             Box::new(Identity),
@@ -179,13 +253,19 @@ impl Precompiles {
             Box::new(ExitToNear::new(ctx.current_account_id.clone())),
             Box::new(ExitToEthereum::new(ctx.current_account_id)),
             Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(NearGasInfo::new(ctx.env)),
+            Box::new(AbiUtils::new(ctx.io)),
+            Box::new(LogIndex::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
         Precompiles(map)
     }
 
-    pub fn new_istanbul(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_istanbul<I: IO + Copy + 'a, E: Env + 'a>(
+        ctx: PrecompileConstructorContext<I, E>,
+    ) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -199,9 +279,12 @@ impl Precompiles {
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
             RandomSeed::ADDRESS,
+            near_gas::ADDRESS,
+            abi_utils::ADDRESS,
+            log_index::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
-            Box::new(ECRecover),
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
+            Box::new(ECRecover::new(ctx.host_functions.ecrecover)),
             Box::new(SHA256),
             Box::new(RIPEMD160),
             Box::new(Identity),
@@ -213,13 +296,19 @@ impl Precompiles {
             Box::new(ExitToNear::new(ctx.current_account_id.clone())),
             Box::new(ExitToEthereum::new(ctx.current_account_id)),
             Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(NearGasInfo::new(ctx.env)),
+            Box::new(AbiUtils::new(ctx.io)),
+            Box::new(LogIndex::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
         Precompiles(map)
     }
 
-    pub fn new_berlin(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_berlin<I: IO + Copy + 'a, E: Env + 'a>(
+        ctx: PrecompileConstructorContext<I, E>,
+    ) -> Self {
         let addresses = vec![
             ECRecover::ADDRESS,
             SHA256::ADDRESS,
@@ -233,9 +322,12 @@ impl Precompiles {
             ExitToNear::ADDRESS,
             ExitToEthereum::ADDRESS,
             RandomSeed::ADDRESS,
+            near_gas::ADDRESS,
+            abi_utils::ADDRESS,
+            log_index::ADDRESS,
         ];
-        let fun: prelude::Vec<Box<dyn Precompile>> = vec![
-            Box::new(ECRecover),
+        let fun: prelude::Vec<Box<dyn Precompile + 'a>> = vec![
+            Box::new(ECRecover::new(ctx.host_functions.ecrecover)),
             Box::new(SHA256),
             Box::new(RIPEMD160),
             Box::new(Identity),
@@ -247,16 +339,32 @@ impl Precompiles {
             Box::new(ExitToNear::new(ctx.current_account_id.clone())),
             Box::new(ExitToEthereum::new(ctx.current_account_id)),
             Box::new(RandomSeed::new(ctx.random_seed)),
+            Box::new(NearGasInfo::new(ctx.env)),
+            Box::new(AbiUtils::new(ctx.io)),
+            Box::new(LogIndex::new(ctx.io)),
         ];
-        let map: BTreeMap<Address, Box<dyn Precompile>> = addresses.into_iter().zip(fun).collect();
+        let map: BTreeMap<Address, Box<dyn Precompile + 'a>> =
+            addresses.into_iter().zip(fun).collect();
 
         Precompiles(map)
     }
 
-    pub fn new_london(ctx: PrecompileConstructorContext) -> Self {
+    pub fn new_london<I: IO + Copy + 'a, E: Env + 'a>(
+        ctx: PrecompileConstructorContext<I, E>,
+    ) -> Self {
         // no precompile changes in London HF
         Self::new_berlin(ctx)
     }
+
+    /// Makes every call to the precompiles at the given addresses fail, the other
+    /// precompiles are unaffected.
+    pub fn pause(&mut self, addresses: &[Address]) {
+        for address in addresses {
+            if let Some(precompile) = self.0.get_mut(address) {
+                *precompile = Box::new(Paused);
+            }
+        }
+    }
 }
 
 /// const fn for making an address by concatenating the bytes from two given numbers,
@@ -360,6 +468,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pause() {
+        use evm::executor::PrecompileSet;
+
+        let storage = core::cell::RefCell::new(prelude::sdk::memory::Storage::default());
+        let mut precompiles = super::Precompiles::new_london(super::PrecompileConstructorContext {
+            current_account_id: "aurora".parse().unwrap(),
+            random_seed: prelude::H256::zero(),
+            host_functions: super::HostFunctions::default(),
+            io: prelude::sdk::memory::StoragePointer(&storage),
+            env: crate::utils::new_env(),
+        });
+        precompiles.pause(&[super::native::ExitToNear::ADDRESS]);
+        let context = evm::Context {
+            address: super::native::ExitToNear::ADDRESS,
+            caller: prelude::Address::zero(),
+            apparent_value: prelude::U256::zero(),
+        };
+
+        // A paused precompile is still one, so calls to it don't succeed as empty calls
+        assert!(precompiles.is_precompile(super::native::ExitToNear::ADDRESS));
+        assert!(matches!(
+            precompiles.execute(
+                super::native::ExitToNear::ADDRESS,
+                &[0],
+                None,
+                &context,
+                false
+            ),
+            Some(Err(_))
+        ));
+        let sha256 = precompiles
+            .execute(super::hash::SHA256::ADDRESS, &[], None, &context, false)
+            .unwrap();
+        assert!(sha256.is_ok());
+    }
+
     fn u8_to_address(x: u8) -> prelude::Address {
         let mut bytes = [0u8; 20];
         bytes[19] = x;
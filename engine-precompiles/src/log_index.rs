@@ -0,0 +1,255 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::prelude::sdk::io::{StorageIntermediate, IO};
+use crate::prelude::storage::{bytes_to_key, KeyPrefix};
+use crate::prelude::types::{EthGas, LogBloom};
+use crate::prelude::{Address, Borrowed, TryInto};
+use crate::PrecompileOutput;
+use evm::{Context, ExitError};
+
+/// Log index precompile costs.
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    /// The base cost of the operation.
+    pub(super) const LOG_INDEX_BASE: EthGas = EthGas::new(200);
+
+    /// The cost per block in the queried range, covers reading its bloom filter from storage.
+    pub(super) const LOG_INDEX_PER_BLOCK: EthGas = EthGas::new(100);
+}
+
+mod consts {
+    /// Length of the input: address, topic, first and last block heights, as ABI words.
+    pub(super) const INPUT_LEN: usize = 128;
+
+    /// Maximum number of blocks which can be queried in a single call.
+    pub(super) const MAX_BLOCK_RANGE: u64 = 256;
+}
+
+/// Log index precompile address
+///
+/// Address: `0xc3522058f17cae8892ea50fec0f1ed471e029dfc`
+/// This address is computed as: `&keccak("logIndex")[12..]`
+pub const ADDRESS: Address = super::make_address(0xc3522058, 0xf17cae8892ea50fec0f1ed471e029dfc);
+
+/// Returns a hint whether events with the given emitter address and topic were emitted
+/// in the given (inclusive) range of block heights, according to the bloom index kept
+/// by the engine in its storage. A zero result means there were definitely no such events,
+/// a non-zero result means there might have been.
+///
+/// Input: `address || topic || from_block || to_block`, each a 32-byte ABI word.
+/// Output: a 32-byte ABI encoded boolean.
+///
+/// The index is only maintained while it is enabled with `set_log_bloom_index_enabled`.
+pub struct LogIndex<I> {
+    io: I,
+}
+
+impl<I> LogIndex<I> {
+    pub fn new(io: I) -> Self {
+        Self { io }
+    }
+}
+
+impl<I: IO> LogIndex<I> {
+    /// Reads the bloom filter of the block from the storage of the engine.
+    fn get_logs_bloom(&self, block_height: u64) -> Option<LogBloom> {
+        self.io
+            .read_storage(&bytes_to_key(
+                KeyPrefix::LogBloom,
+                &block_height.to_le_bytes(),
+            ))
+            .and_then(|value| value.to_value().ok())
+    }
+}
+
+/// Parses the block range from the input, returns the first block height and the
+/// number of blocks in the range.
+fn parse_block_range(input: &[u8]) -> Result<(u64, u64), ExitError> {
+    if input.len() != consts::INPUT_LEN {
+        return Err(ExitError::Other(Borrowed("ERR_INVALID_INPUT")));
+    }
+    let from_block = word_to_u64(&input[64..96])?;
+    let to_block = word_to_u64(&input[96..128])?;
+    if to_block < from_block {
+        return Err(ExitError::Other(Borrowed("ERR_INVALID_BLOCK_RANGE")));
+    }
+    let blocks = to_block
+        .checked_sub(from_block)
+        .and_then(|range| range.checked_add(1))
+        .ok_or(ExitError::Other(Borrowed("ERR_BLOCK_RANGE_TOO_LARGE")))?;
+    if blocks > consts::MAX_BLOCK_RANGE {
+        return Err(ExitError::Other(Borrowed("ERR_BLOCK_RANGE_TOO_LARGE")));
+    }
+    Ok((from_block, blocks))
+}
+
+fn word_to_u64(word: &[u8]) -> Result<u64, ExitError> {
+    if word[..24].iter().any(|b| *b != 0) {
+        return Err(ExitError::Other(Borrowed("ERR_INVALID_BLOCK_RANGE")));
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into().unwrap()))
+}
+
+/// Checks whether the bloom filters of the given range may contain the address and topic.
+fn may_contain<F: Fn(u64) -> Option<LogBloom>>(
+    get_logs_bloom: F,
+    address: &[u8],
+    topic: &[u8],
+    from_block: u64,
+    blocks: u64,
+) -> bool {
+    (0..blocks)
+        .filter_map(|offset| from_block.checked_add(offset))
+        .any(|block_height| match get_logs_bloom(block_height) {
+            Some(bloom) => bloom.contains_input(address) && bloom.contains_input(topic),
+            None => false,
+        })
+}
+
+impl<I: IO> Precompile for LogIndex<I> {
+    fn required_gas(input: &[u8]) -> Result<EthGas, ExitError> {
+        let (_, blocks) = parse_block_range(input)?;
+        Ok(blocks * costs::LOG_INDEX_PER_BLOCK + costs::LOG_INDEX_BASE)
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = Self::required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let (from_block, blocks) = parse_block_range(input)?;
+        let address = &input[12..32];
+        let topic = &input[32..64];
+        let found = may_contain(
+            |block_height| self.get_logs_bloom(block_height),
+            address,
+            topic,
+            from_block,
+            blocks,
+        );
+
+        let mut output = [0u8; 32];
+        output[31] = found as u8;
+        Ok(PrecompileOutput::without_logs(cost, output.to_vec()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::sdk::memory;
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::prelude::{vec, Vec};
+    use crate::utils::new_context;
+    use core::cell::RefCell;
+
+    fn input(address: [u8; 20], topic: [u8; 32], from_block: u64, to_block: u64) -> Vec<u8> {
+        let mut input = vec![0u8; consts::INPUT_LEN];
+        input[12..32].copy_from_slice(&address);
+        input[32..64].copy_from_slice(&topic);
+        input[88..96].copy_from_slice(&from_block.to_be_bytes());
+        input[120..128].copy_from_slice(&to_block.to_be_bytes());
+        input
+    }
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(ADDRESS, near_account_to_evm_address("logIndex".as_bytes()));
+    }
+
+    #[test]
+    fn test_parse_block_range() {
+        assert_eq!(
+            parse_block_range(&input([0; 20], [0; 32], 10, 10)).unwrap(),
+            (10, 1)
+        );
+        assert_eq!(
+            parse_block_range(&input([0; 20], [0; 32], 10, 265)).unwrap(),
+            (10, 256)
+        );
+        assert!(parse_block_range(&input([0; 20], [0; 32], 10, 266)).is_err());
+        assert!(parse_block_range(&input([0; 20], [0; 32], 10, 9)).is_err());
+        assert!(parse_block_range(&[0u8; 127]).is_err());
+        assert!(parse_block_range(&input([0; 20], [0; 32], 0, u64::MAX)).is_err());
+        assert_eq!(
+            parse_block_range(&input([0; 20], [0; 32], u64::MAX, u64::MAX)).unwrap(),
+            (u64::MAX, 1)
+        );
+
+        let mut input = input([0; 20], [0; 32], 10, 10);
+        input[64] = 1;
+        assert!(parse_block_range(&input).is_err());
+    }
+
+    #[test]
+    fn test_may_contain() {
+        let address = [1u8; 20];
+        let topic = [2u8; 32];
+        let mut bloom = LogBloom::default();
+        bloom.accrue(&address);
+        bloom.accrue(&topic);
+        let get_logs_bloom = |block_height| {
+            if block_height == 15 {
+                Some(bloom)
+            } else {
+                None
+            }
+        };
+
+        assert!(may_contain(get_logs_bloom, &address, &topic, 10, 10));
+        assert!(may_contain(get_logs_bloom, &address, &topic, 15, 1));
+        assert!(!may_contain(get_logs_bloom, &address, &topic, 16, 10));
+        assert!(!may_contain(get_logs_bloom, &address, &[3u8; 32], 10, 10));
+        assert!(!may_contain(get_logs_bloom, &[3u8; 20], &topic, 10, 10));
+        // The range doesn't wrap around the last block height.
+        assert!(!may_contain(get_logs_bloom, &address, &topic, u64::MAX, 2));
+    }
+
+    #[test]
+    fn test_run() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let mut bloom = LogBloom::default();
+        bloom.accrue(&[1; 20]);
+        bloom.accrue(&[2; 32]);
+        io.write_borsh(
+            &bytes_to_key(KeyPrefix::LogBloom, &5u64.to_le_bytes()),
+            &bloom,
+        );
+        let log_index = LogIndex::new(io);
+
+        let later_input = input([1; 20], [2; 32], 6, 9);
+        let input = input([1; 20], [2; 32], 0, 9);
+        let expected_cost = 10_u64 * costs::LOG_INDEX_PER_BLOCK + costs::LOG_INDEX_BASE;
+        let result = log_index
+            .run(&input, Some(expected_cost), &new_context(), false)
+            .unwrap();
+        assert_eq!(result.cost, expected_cost.into_u64());
+        let mut expected_output = vec![0u8; 32];
+        expected_output[31] = 1;
+        assert_eq!(result.output, expected_output);
+
+        // Nothing is indexed after the block.
+        let result = log_index
+            .run(&later_input, None, &new_context(), false)
+            .unwrap();
+        assert_eq!(result.output, vec![0u8; 32]);
+
+        let result = log_index.run(
+            &input,
+            Some(EthGas::new(expected_cost.into_u64() - 1)),
+            &new_context(),
+            false,
+        );
+        assert!(matches!(result, Err(ExitError::OutOfGas)));
+    }
+}
@@ -2,15 +2,12 @@ use super::{EvmPrecompileResult, Precompile};
 #[cfg(feature = "contract")]
 use crate::prelude::{
     format,
-    parameters::{PromiseArgs, PromiseCreateArgs, WithdrawCallArgs},
+    parameters::{
+        PromiseArgs, PromiseCreateArgs, PromiseWithCallbackArgs, RefundCallArgs, WithdrawCallArgs,
+    },
     sdk,
-    storage::{bytes_to_key, KeyPrefix},
-    vec, BorshSerialize, Cow, String, ToString, TryFrom, TryInto, Vec, H160, U256,
-};
-#[cfg(all(feature = "error_refund", feature = "contract"))]
-use crate::prelude::{
-    parameters::{PromiseWithCallbackArgs, RefundCallArgs},
-    types,
+    storage::{self, bytes_to_key, KeyPrefix},
+    types, vec, BorshSerialize, Cow, String, ToString, TryFrom, TryInto, Vec, H160, U256,
 };
 
 use crate::prelude::types::EthGas;
@@ -36,9 +33,11 @@ mod costs {
     pub(super) const FT_TRANSFER_GAS: EthGas = EthGas::new(100_000_000_000_000);
 
     // TODO(#332): Determine the correct amount of gas
-    #[cfg(feature = "error_refund")]
     pub(super) const REFUND_ON_ERROR_GAS: EthGas = EthGas::new(60_000_000_000_000);
 
+    // TODO(#332): Determine the correct amount of gas
+    pub(super) const EXIT_RESULT_GAS: EthGas = EthGas::new(10_000_000_000_000);
+
     // TODO(#332): Determine the correct amount of gas
     pub(super) const WITHDRAWAL_GAS: EthGas = EthGas::new(100_000_000_000_000);
 }
@@ -220,6 +219,42 @@ fn get_nep141_from_erc20(erc20_token: &[u8]) -> AccountId {
     .unwrap()
 }
 
+/// Fails if the token is paused by the engine, its supplies on NEAR and Aurora having diverged.
+#[cfg(feature = "contract")]
+fn require_token_not_paused(nep141: &AccountId) -> Result<(), ExitError> {
+    use sdk::io::IO;
+    let key = storage::config_key(storage::ConfigKey::PausedToken, nep141.as_bytes());
+    if sdk::near_runtime::Runtime.storage_has_key(&key) {
+        return Err(ExitError::Other(Cow::from("ERR_TOKEN_PAUSED")));
+    }
+    Ok(())
+}
+
+/// Returns the first byte of the input of an exit, selecting its behavior.
+#[cfg(any(feature = "contract", test))]
+fn parse_flag(input: &[u8]) -> Result<u8, ExitError> {
+    input
+        .first()
+        .copied()
+        .ok_or_else(|| ExitError::Other(crate::prelude::Cow::from("ERR_INVALID_INPUT")))
+}
+
+/// Splits the amount of an ERC-20 exit, big-endian, from the recipient following it. The
+/// balances of NEP-141 tokens are 128-bit, larger amounts are rejected.
+#[cfg(any(feature = "contract", test))]
+fn parse_amount(input: &[u8]) -> Result<(crate::prelude::U256, &[u8]), ExitError> {
+    use crate::prelude::{Cow, U256};
+
+    if input.len() < 32 {
+        return Err(ExitError::Other(Cow::from("ERR_INVALID_AMOUNT")));
+    }
+    let amount = U256::from_big_endian(&input[..32]);
+    if amount > U256::from(u128::MAX) {
+        return Err(ExitError::Other(Cow::from("ERR_INVALID_AMOUNT")));
+    }
+    Ok((amount, &input[32..]))
+}
+
 impl Precompile for ExitToNear {
     fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
         Ok(costs::EXIT_TO_NEAR_GAS)
@@ -251,9 +286,12 @@ impl Precompile for ExitToNear {
         is_static: bool,
     ) -> EvmPrecompileResult {
         #[cfg(feature = "error_refund")]
-        fn parse_input(input: &[u8]) -> (Address, &[u8]) {
+        fn parse_input(input: &[u8]) -> Result<(Address, &[u8]), ExitError> {
+            if input.len() < 21 {
+                return Err(ExitError::Other(Cow::from("ERR_INVALID_INPUT")));
+            }
             let refund_address = Address::from_slice(&input[1..21]);
-            (refund_address, &input[21..])
+            Ok((refund_address, &input[21..]))
         }
         #[cfg(not(feature = "error_refund"))]
         fn parse_input(input: &[u8]) -> &[u8] {
@@ -274,14 +312,15 @@ impl Precompile for ExitToNear {
         // First byte of the input is a flag, selecting the behavior to be triggered:
         //      0x0 -> Eth transfer
         //      0x1 -> Erc20 transfer
-        let flag = input[0];
+        let flag = parse_flag(input)?;
         #[cfg(feature = "error_refund")]
-        let (refund_address, mut input) = parse_input(input);
+        let (refund_address, mut input) = parse_input(input)?;
+        // Without the address passed by the caller, the value of an ETH exit is refunded to
+        // the account it was sent from.
         #[cfg(not(feature = "error_refund"))]
-        let mut input = parse_input(input);
+        let (refund_address, mut input) = (context.caller, parse_input(input));
         let current_account_id = self.current_account_id.clone();
-        #[cfg(feature = "error_refund")]
-        let refund_on_error_target = current_account_id.clone();
+        let callback_target = current_account_id.clone();
 
         let (nep141_address, args, exit_event) = match flag {
             0x0 => {
@@ -330,9 +369,10 @@ impl Precompile for ExitToNear {
 
                 let erc20_address = context.caller;
                 let nep141_address = get_nep141_from_erc20(erc20_address.as_bytes());
+                require_token_not_paused(&nep141_address)?;
 
-                let amount = U256::from_big_endian(&input[..32]);
-                input = &input[32..];
+                let (amount, recipient) = parse_amount(input)?;
+                input = recipient;
 
                 if let Ok(receiver_account_id) = AccountId::try_from(input) {
                     (
@@ -360,25 +400,36 @@ impl Precompile for ExitToNear {
             _ => return Err(ExitError::Other(Cow::from("ERR_INVALID_FLAG"))),
         };
 
-        #[cfg(feature = "error_refund")]
-        let erc20_address = if flag == 0 {
-            None
+        // The ERC-20 tokens are burned by the token contract, which only passes the address
+        // of the account they are burned from with the `error_refund` feature. Otherwise there
+        // is nobody to re-mint the tokens to, and the callback only reports the result of the
+        // transfer.
+        let (callback_method, callback_gas, erc20_address) = if flag == 0 {
+            ("refund_on_error", costs::REFUND_ON_ERROR_GAS, None)
+        } else if cfg!(feature = "error_refund") {
+            (
+                "refund_on_error",
+                costs::REFUND_ON_ERROR_GAS,
+                Some(exit_event.erc20_address.0),
+            )
         } else {
-            Some(exit_event.erc20_address.0)
+            (
+                "exit_result",
+                costs::EXIT_RESULT_GAS,
+                Some(exit_event.erc20_address.0),
+            )
         };
-        #[cfg(feature = "error_refund")]
-        let refund_args = RefundCallArgs {
+        let callback_args = RefundCallArgs {
             recipient_address: refund_address.0,
             erc20_address,
             amount: types::u256_to_arr(&exit_event.amount),
         };
-        #[cfg(feature = "error_refund")]
-        let refund_promise = PromiseCreateArgs {
-            target_account_id: refund_on_error_target,
-            method: "refund_on_error".to_string(),
-            args: refund_args.try_to_vec().unwrap(),
+        let callback_promise = PromiseCreateArgs {
+            target_account_id: callback_target,
+            method: callback_method.to_string(),
+            args: callback_args.try_to_vec().unwrap(),
             attached_balance: 0,
-            attached_gas: costs::REFUND_ON_ERROR_GAS.into_u64(),
+            attached_gas: callback_gas.into_u64(),
         };
         let transfer_promise = PromiseCreateArgs {
             target_account_id: nep141_address,
@@ -388,13 +439,10 @@ impl Precompile for ExitToNear {
             attached_gas: costs::FT_TRANSFER_GAS.into_u64(),
         };
 
-        #[cfg(feature = "error_refund")]
         let promise = PromiseArgs::Callback(PromiseWithCallbackArgs {
             base: transfer_promise,
-            callback: refund_promise,
+            callback: callback_promise,
         });
-        #[cfg(not(feature = "error_refund"))]
-        let promise = PromiseArgs::Create(transfer_promise);
 
         let promise_log = Log {
             address: Self::ADDRESS,
@@ -478,7 +526,7 @@ impl Precompile for ExitToEthereum {
         //      0x0 -> Eth transfer
         //      0x1 -> Erc20 transfer
         let mut input = input;
-        let flag = input[0];
+        let flag = parse_flag(input)?;
         input = &input[1..];
 
         let (nep141_address, serialized_args, exit_event) = match flag {
@@ -526,9 +574,10 @@ impl Precompile for ExitToEthereum {
 
                 let erc20_address = context.caller;
                 let nep141_address = get_nep141_from_erc20(erc20_address.as_bytes());
+                require_token_not_paused(&nep141_address)?;
 
-                let amount = U256::from_big_endian(&input[..32]);
-                input = &input[32..];
+                let (amount, recipient) = parse_amount(input)?;
+                input = recipient;
 
                 if input.len() == 20 {
                     // Parse ethereum address in hex
@@ -573,7 +622,30 @@ impl Precompile for ExitToEthereum {
             attached_gas: costs::WITHDRAWAL_GAS.into_u64(),
         };
 
-        let promise = PromiseArgs::Create(withdraw_promise).try_to_vec().unwrap();
+        // The withdrawals are never refunded, the callback only reports their result.
+        let result_args = RefundCallArgs {
+            recipient_address: exit_event.sender.0,
+            erc20_address: if flag == 0 {
+                None
+            } else {
+                Some(exit_event.erc20_address.0)
+            },
+            amount: types::u256_to_arr(&exit_event.amount),
+        };
+        let result_promise = PromiseCreateArgs {
+            target_account_id: self.current_account_id.clone(),
+            method: "exit_result".to_string(),
+            args: result_args.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: costs::EXIT_RESULT_GAS.into_u64(),
+        };
+
+        let promise = PromiseArgs::Callback(PromiseWithCallbackArgs {
+            base: withdraw_promise,
+            callback: result_promise,
+        })
+        .try_to_vec()
+        .unwrap();
         let promise_log = Log {
             address: Self::ADDRESS,
             topics: Vec::new(),
@@ -611,6 +683,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_exit_input() {
+        assert!(super::parse_flag(&[]).is_err());
+        assert_eq!(super::parse_flag(&[1, 2]).unwrap(), 1);
+
+        assert!(super::parse_amount(&[0u8; 31]).is_err());
+        assert!(super::parse_amount(&[0xffu8; 32]).is_err());
+        let mut input = [0u8; 36];
+        input[31] = 7;
+        input[32..].copy_from_slice(b"a.io");
+        let (amount, recipient) = super::parse_amount(&input).unwrap();
+        assert_eq!(amount, crate::prelude::U256::from(7));
+        assert_eq!(recipient, b"a.io");
+    }
+
     #[test]
     fn test_exit_signatures() {
         let exit_to_near = super::events::exit_to_near_schema();
@@ -0,0 +1,104 @@
+use super::{EvmPrecompileResult, Precompile};
+use crate::prelude::sdk::env::Env;
+use crate::prelude::types::EthGas;
+use crate::prelude::{Address, Vec, U256};
+use crate::PrecompileOutput;
+use evm::{Context, ExitError};
+
+mod costs {
+    use crate::prelude::types::EthGas;
+
+    // TODO(#51): Determine the correct amount of gas
+    pub(super) const NEAR_GAS_INFO_GAS: EthGas = EthGas::new(0);
+}
+
+/// Conservative estimate of the NEAR gas burnt per unit of EVM gas. Contracts can use it to
+/// estimate how much EVM gas can still be executed with the remaining NEAR gas.
+pub const NEAR_GAS_PER_EVM_GAS: u64 = 500_000_000;
+
+/// NEAR gas information precompile address
+///
+/// Address: `0xd02a48ce3eee5a8169dbf65497e151126934d82a`
+/// This address is computed as: `&keccak("nearGas")[12..]`
+pub const ADDRESS: Address = super::make_address(0xd02a48ce, 0x3eee5a8169dbf65497e151126934d82a);
+
+/// Returns the NEAR gas remaining for the current transaction and the estimated number of
+/// NEAR gas units burnt per EVM gas unit, as two 32-byte big-endian words. Both the prepaid
+/// and the burnt gas are read from the environment of the call when the precompile runs.
+pub struct NearGasInfo<E> {
+    env: E,
+}
+
+impl<E> NearGasInfo<E> {
+    pub fn new(env: E) -> Self {
+        Self { env }
+    }
+}
+
+impl<E: Env> Precompile for NearGasInfo<E> {
+    fn required_gas(_input: &[u8]) -> Result<EthGas, ExitError> {
+        Ok(costs::NEAR_GAS_INFO_GAS)
+    }
+
+    fn run(
+        &self,
+        input: &[u8],
+        target_gas: Option<EthGas>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> EvmPrecompileResult {
+        let cost = Self::required_gas(input)?;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let remaining_gas = self
+            .env
+            .prepaid_gas()
+            .into_u64()
+            .saturating_sub(self.env.used_gas().into_u64());
+        let mut output = Vec::with_capacity(64);
+        output.extend_from_slice(&u256_to_bytes(U256::from(remaining_gas)));
+        output.extend_from_slice(&u256_to_bytes(U256::from(NEAR_GAS_PER_EVM_GAS)));
+
+        Ok(PrecompileOutput::without_logs(cost, output).into())
+    }
+}
+
+fn u256_to_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::sdk::types::near_account_to_evm_address;
+    use crate::utils::{new_context, new_env};
+
+    #[test]
+    fn test_precompile_id() {
+        assert_eq!(ADDRESS, near_account_to_evm_address("nearGas".as_bytes()));
+    }
+
+    #[test]
+    fn test_remaining_gas() {
+        let precompile = NearGasInfo::new(new_env());
+        let output = precompile
+            .run(&[], None, &new_context(), true)
+            .unwrap()
+            .output;
+        assert_eq!(output.len(), 64);
+        assert_eq!(
+            U256::from_big_endian(&output[..32]),
+            U256::from(300_000_000_000_000u64)
+        );
+        assert_eq!(
+            U256::from_big_endian(&output[32..]),
+            U256::from(NEAR_GAS_PER_EVM_GAS)
+        );
+    }
+}
@@ -29,9 +29,9 @@ pub fn ecrecover(hash: H256, signature: &[u8]) -> Result<Address, ExitError> {
     internal_impl(hash, signature)
 }
 
-#[cfg(not(feature = "contract"))]
+/// Pure-Rust implementation of `ecrecover`, for the runtimes without the host function.
 fn internal_impl(hash: H256, signature: &[u8]) -> Result<Address, ExitError> {
-    use sha3::Digest;
+    use crate::prelude::types::address_from_public_key;
 
     let hash = secp256k1::Message::parse_slice(hash.as_bytes()).unwrap();
     let v = signature[64];
@@ -44,18 +44,33 @@ fn internal_impl(hash: H256, signature: &[u8]) -> Result<Address, ExitError> {
     if let Ok(recovery_id) = secp256k1::RecoveryId::parse(bit) {
         if let Ok(public_key) = secp256k1::recover(&hash, &signature, &recovery_id) {
             // recover returns a 65-byte key, but addresses come from the raw 64-byte key
-            let r = sha3::Keccak256::digest(&public_key.serialize()[1..]);
-            return Ok(Address::from_slice(&r[12..]));
+            let mut raw_public_key = [0u8; 64];
+            raw_public_key.copy_from_slice(&public_key.serialize()[1..]);
+            return Ok(address_from_public_key(&raw_public_key));
         }
     }
 
     Err(ExitError::Other(Borrowed(sdk::ECRecoverErr.as_str())))
 }
 
-pub(super) struct ECRecover;
+pub(super) struct ECRecover {
+    use_host_function: bool,
+}
 
 impl ECRecover {
     pub(super) const ADDRESS: Address = super::make_address(0, 1);
+
+    pub(super) fn new(use_host_function: bool) -> Self {
+        Self { use_host_function }
+    }
+
+    fn recover(&self, hash: H256, signature: &[u8]) -> Result<Address, ExitError> {
+        if self.use_host_function {
+            ecrecover(hash, signature)
+        } else {
+            internal_impl(hash, signature)
+        }
+    }
 }
 
 impl Precompile for ECRecover {
@@ -98,7 +113,7 @@ impl Precompile for ECRecover {
         };
         signature[64] = v_bit; // v
 
-        let address_res = ecrecover(H256::from_slice(&hash), &signature);
+        let address_res = self.recover(H256::from_slice(&hash), &signature);
         let output = match address_res {
             Ok(a) => {
                 let mut output = [0u8; 32];
@@ -137,6 +152,20 @@ mod tests {
         assert!(ecverify(hash, &signature, signer));
     }
 
+    #[test]
+    fn test_ecrecover_without_host_function() {
+        let input = hex::decode("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001b650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03").unwrap();
+        let expected =
+            hex::decode("000000000000000000000000c08b5542d177ac6686946920409741463a15dddb")
+                .unwrap();
+
+        let res = ECRecover::new(false)
+            .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
+            .unwrap()
+            .output;
+        assert_eq!(res, expected);
+    }
+
     #[test]
     fn test_ecrecover() {
         let input = hex::decode("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001b650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03").unwrap();
@@ -144,7 +173,7 @@ mod tests {
             hex::decode("000000000000000000000000c08b5542d177ac6686946920409741463a15dddb")
                 .unwrap();
 
-        let res = ECRecover
+        let res = ECRecover::new(true)
             .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
             .unwrap()
             .output;
@@ -153,7 +182,7 @@ mod tests {
         // out of gas
         let input = hex::decode("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001b650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03").unwrap();
 
-        let res = ECRecover.run(&input, Some(EthGas::new(2_999)), &new_context(), false);
+        let res = ECRecover::new(true).run(&input, Some(EthGas::new(2_999)), &new_context(), false);
         assert!(matches!(res, Err(ExitError::OutOfGas)));
 
         // bad inputs
@@ -162,7 +191,7 @@ mod tests {
             hex::decode("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
                 .unwrap();
 
-        let res = ECRecover
+        let res = ECRecover::new(true)
             .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
             .unwrap()
             .output;
@@ -173,7 +202,7 @@ mod tests {
             hex::decode("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
                 .unwrap();
 
-        let res = ECRecover
+        let res = ECRecover::new(true)
             .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
             .unwrap()
             .output;
@@ -184,7 +213,7 @@ mod tests {
             hex::decode("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
                 .unwrap();
 
-        let res = ECRecover
+        let res = ECRecover::new(true)
             .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
             .unwrap()
             .output;
@@ -195,7 +224,7 @@ mod tests {
             hex::decode("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
                 .unwrap();
 
-        let res = ECRecover
+        let res = ECRecover::new(true)
             .run(&input, Some(EthGas::new(3_000)), &new_context(), false)
             .unwrap()
             .output;
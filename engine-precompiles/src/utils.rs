@@ -1,3 +1,5 @@
+use crate::prelude::sdk::env::{self, Timestamp, DEFAULT_PREPAID_GAS};
+use crate::prelude::H256;
 use evm::Context;
 
 pub fn new_context() -> Context {
@@ -7,3 +9,17 @@ pub fn new_context() -> Context {
         apparent_value: Default::default(),
     }
 }
+
+pub fn new_env() -> env::Fixed {
+    let account_id: crate::prelude::AccountId = "aurora".parse().unwrap();
+    env::Fixed {
+        signer_account_id: account_id.clone(),
+        current_account_id: account_id.clone(),
+        predecessor_account_id: account_id,
+        block_height: 0,
+        block_timestamp: Timestamp::new(0),
+        attached_deposit: 0,
+        random_seed: H256::zero(),
+        prepaid_gas: DEFAULT_PREPAID_GAS,
+    }
+}
@@ -47,6 +47,10 @@ pub trait Env {
     fn random_seed(&self) -> H256;
     /// Prepaid NEAR Gas
     fn prepaid_gas(&self) -> NearGas;
+    /// NEAR Gas burnt so far by the current call, none outside of the NEAR runtime.
+    fn used_gas(&self) -> NearGas {
+        NearGas::new(0)
+    }
 
     fn assert_private_call(&self) -> Result<(), PrivateCallError> {
         if self.predecessor_account_id() == self.current_account_id() {
@@ -112,3 +116,42 @@ impl Env for Fixed {
         self.prepaid_gas
     }
 }
+
+/// The environment of a trait object, e.g. `&dyn Env` picked at runtime by a simulation.
+impl<'a, E: Env + ?Sized> Env for &'a E {
+    fn signer_account_id(&self) -> AccountId {
+        (**self).signer_account_id()
+    }
+
+    fn current_account_id(&self) -> AccountId {
+        (**self).current_account_id()
+    }
+
+    fn predecessor_account_id(&self) -> AccountId {
+        (**self).predecessor_account_id()
+    }
+
+    fn block_height(&self) -> u64 {
+        (**self).block_height()
+    }
+
+    fn block_timestamp(&self) -> Timestamp {
+        (**self).block_timestamp()
+    }
+
+    fn attached_deposit(&self) -> u128 {
+        (**self).attached_deposit()
+    }
+
+    fn random_seed(&self) -> H256 {
+        (**self).random_seed()
+    }
+
+    fn prepaid_gas(&self) -> NearGas {
+        (**self).prepaid_gas()
+    }
+
+    fn used_gas(&self) -> NearGas {
+        (**self).used_gas()
+    }
+}
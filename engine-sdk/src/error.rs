@@ -46,6 +46,27 @@ impl AsRef<[u8]> for ReadU256Error {
     }
 }
 
+/// Reasons why a value of the state could not be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateCorruption {
+    /// Nothing is stored under the key.
+    MissingKey,
+    /// The stored value is shorter or longer than the encoding of a value of the type.
+    WrongLength,
+    /// The stored value is not a valid borsh encoding of a value of the type.
+    Borsh,
+}
+
+impl AsRef<[u8]> for StateCorruption {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::MissingKey => b"ERR_STATE_MISSING_KEY",
+            Self::WrongLength => b"ERR_STATE_WRONG_LENGTH",
+            Self::Borsh => b"ERR_STATE_BORSH",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct PrivateCallError;
 
@@ -1,5 +1,5 @@
 use crate::error;
-use crate::prelude::{vec, Vec};
+use crate::prelude::{vec, ToString, Vec};
 use aurora_engine_types::U256;
 use borsh::{BorshDeserialize, BorshSerialize};
 
@@ -26,6 +26,23 @@ pub trait StorageIntermediate: Sized {
     }
 }
 
+/// Message of the borsh error returned when the input ends before the value is decoded.
+const ERROR_UNEXPECTED_LENGTH_OF_INPUT: &str = "Unexpected length of input";
+
+/// Deserializes a value of the state, telling apart the values which don't have the length
+/// of an encoded value of the type from otherwise invalid values.
+pub fn deserialize_state<T: BorshDeserialize>(bytes: &[u8]) -> Result<T, error::StateCorruption> {
+    let mut buf = bytes;
+    match T::deserialize(&mut buf) {
+        Ok(_) if !buf.is_empty() => Err(error::StateCorruption::WrongLength),
+        Ok(value) => Ok(value),
+        Err(e) if e.to_string() == ERROR_UNEXPECTED_LENGTH_OF_INPUT => {
+            Err(error::StateCorruption::WrongLength)
+        }
+        Err(_) => Err(error::StateCorruption::Borsh),
+    }
+}
+
 /// Trait for reading/writing values from storage and a generalized `stdin`/`stdout`.
 pub trait IO {
     /// A type giving a reference to a value obtained by IO without loading it
@@ -75,6 +92,15 @@ pub trait IO {
         self.read_input().to_value()
     }
 
+    /// Convenience function to read a value of the state from storage and deserialize it
+    /// using borsh, reporting why it can't be loaded if it is missing or corrupted.
+    fn load_state<U: BorshDeserialize>(&self, key: &[u8]) -> Result<U, error::StateCorruption> {
+        let value = self
+            .read_storage(key)
+            .ok_or(error::StateCorruption::MissingKey)?;
+        deserialize_state(&value.to_vec())
+    }
+
     /// Convenience function to read the input into a 20-byte array.
     fn read_input_arr20(&self) -> Result<[u8; 20], error::IncorrectInputLength> {
         let value = self.read_input();
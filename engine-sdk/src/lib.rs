@@ -11,6 +11,7 @@ pub use types::keccak;
 pub mod env;
 pub mod error;
 pub mod io;
+pub mod memory;
 #[cfg(feature = "contract")]
 pub mod near_runtime;
 mod prelude;
@@ -35,6 +36,15 @@ pub fn panic_utf8(bytes: &[u8]) -> ! {
     unreachable!()
 }
 
+/// Outside of the contract there is nothing to abort, the message is panicked with.
+#[cfg(not(feature = "contract"))]
+pub fn panic_utf8(bytes: &[u8]) -> ! {
+    panic!(
+        "{}",
+        core::str::from_utf8(bytes).unwrap_or("ERR_INVALID_UTF8")
+    )
+}
+
 #[cfg(feature = "contract")]
 pub fn log_utf8(bytes: &[u8]) {
     unsafe {
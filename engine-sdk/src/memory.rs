@@ -0,0 +1,152 @@
+//! In-memory implementations of the host traits, to run the engine as a library outside of
+//! the NEAR runtime, for simulations and fuzzing.
+//!
+//! The engine and the connector only reach the host through `IO`, `Env` and
+//! `PromiseHandler`. `env::Fixed` provides the environment, this module the storage, the
+//! input and output of the calls, and a handler recording the promises instead of
+//! scheduling them. `Env` and `PromiseHandler` are also implemented for references to
+//! trait objects, so a simulation can pick its host at runtime.
+use crate::io::{StorageIntermediate, IO};
+use crate::promise::{PromiseHandler, PromiseId};
+use aurora_engine_types::parameters::{PromiseBatchAction, PromiseCreateArgs};
+use aurora_engine_types::types::PromiseResult;
+use aurora_engine_types::{BTreeMap, Vec};
+use core::cell::RefCell;
+
+/// Value read from the in-memory storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value(pub Vec<u8>);
+
+impl StorageIntermediate for Value {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn copy_to_slice(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.0)
+    }
+}
+
+/// Storage of the engine, with the input of the call and the output it returned.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Storage {
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+    pub kv_store: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+/// `IO` over an in-memory storage. The engine copies its `IO`, so every copy of the pointer
+/// shares the storage.
+#[derive(Debug, Clone, Copy)]
+pub struct StoragePointer<'a>(pub &'a RefCell<Storage>);
+
+impl<'a> IO for StoragePointer<'a> {
+    type StorageValue = Value;
+
+    fn read_input(&self) -> Self::StorageValue {
+        Value(self.0.borrow().input.clone())
+    }
+
+    fn return_output(&mut self, value: &[u8]) {
+        self.0.borrow_mut().output = value.to_vec();
+    }
+
+    fn read_storage(&self, key: &[u8]) -> Option<Self::StorageValue> {
+        self.0.borrow().kv_store.get(key).cloned().map(Value)
+    }
+
+    fn storage_has_key(&self, key: &[u8]) -> bool {
+        self.0.borrow().kv_store.contains_key(key)
+    }
+
+    fn write_storage(&mut self, key: &[u8], value: &[u8]) -> Option<Self::StorageValue> {
+        self.0
+            .borrow_mut()
+            .kv_store
+            .insert(key.to_vec(), value.to_vec())
+            .map(Value)
+    }
+
+    fn write_storage_direct(
+        &mut self,
+        key: &[u8],
+        value: Self::StorageValue,
+    ) -> Option<Self::StorageValue> {
+        self.0
+            .borrow_mut()
+            .kv_store
+            .insert(key.to_vec(), value.0)
+            .map(Value)
+    }
+
+    fn remove_storage(&mut self, key: &[u8]) -> Option<Self::StorageValue> {
+        self.0.borrow_mut().kv_store.remove(key).map(Value)
+    }
+}
+
+/// Promise recorded by `PromiseTracker`.
+#[derive(Debug, Clone)]
+pub enum ScheduledPromise {
+    Create(PromiseCreateArgs),
+    Callback {
+        base: PromiseId,
+        callback: PromiseCreateArgs,
+    },
+    Batch(PromiseBatchAction),
+}
+
+/// Doesn't actually schedule any promises, only tracks what promises should be scheduled.
+/// The results of the promises a callback reads are set by the caller.
+#[derive(Debug, Default)]
+pub struct PromiseTracker {
+    internal_index: u64,
+    pub promise_results: Vec<PromiseResult>,
+    pub scheduled_promises: BTreeMap<u64, ScheduledPromise>,
+    pub returned_promise: Option<PromiseId>,
+}
+
+impl PromiseTracker {
+    fn schedule(&mut self, promise: ScheduledPromise) -> PromiseId {
+        let id = self.internal_index;
+        self.internal_index += 1;
+        self.scheduled_promises.insert(id, promise);
+        PromiseId::new(id)
+    }
+}
+
+impl PromiseHandler for PromiseTracker {
+    fn promise_results_count(&self) -> u64 {
+        self.promise_results.len() as u64
+    }
+
+    fn promise_result(&self, index: u64) -> Option<PromiseResult> {
+        self.promise_results.get(index as usize).cloned()
+    }
+
+    fn promise_create_call(&mut self, args: &PromiseCreateArgs) -> PromiseId {
+        self.schedule(ScheduledPromise::Create(args.clone()))
+    }
+
+    fn promise_attach_callback(
+        &mut self,
+        base: PromiseId,
+        callback: &PromiseCreateArgs,
+    ) -> PromiseId {
+        self.schedule(ScheduledPromise::Callback {
+            base,
+            callback: callback.clone(),
+        })
+    }
+
+    fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId {
+        self.schedule(ScheduledPromise::Batch(args.clone()))
+    }
+
+    fn promise_return(&mut self, promise: PromiseId) {
+        self.returned_promise = Some(promise);
+    }
+}
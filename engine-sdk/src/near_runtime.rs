@@ -1,4 +1,4 @@
-use crate::io::StorageIntermediate;
+use crate::io::{StorageIntermediate, IO};
 use crate::prelude::NearGas;
 use crate::promise::PromiseId;
 use aurora_engine_types::account_id::AccountId;
@@ -190,6 +190,91 @@ impl crate::io::IO for Runtime {
     }
 }
 
+/// NEAR runtime reading and writing the storage through a register of its own, for the
+/// bookkeeping done in between the storage accesses of the engine (e.g. counters updated by a
+/// write), which must not replace a value the engine read or evicted and didn't load yet.
+#[derive(Copy, Clone, Default)]
+pub struct BookkeepingRuntime;
+
+impl BookkeepingRuntime {
+    const REGISTER_ID: RegisterIndex = RegisterIndex(6);
+}
+
+impl crate::io::IO for BookkeepingRuntime {
+    type StorageValue = RegisterIndex;
+
+    fn read_input(&self) -> Self::StorageValue {
+        Runtime.read_input()
+    }
+
+    fn return_output(&mut self, value: &[u8]) {
+        Runtime.return_output(value)
+    }
+
+    fn read_storage(&self, key: &[u8]) -> Option<Self::StorageValue> {
+        unsafe {
+            if exports::storage_read(key.len() as _, key.as_ptr() as _, Self::REGISTER_ID.0) == 1 {
+                Some(Self::REGISTER_ID)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn storage_has_key(&self, key: &[u8]) -> bool {
+        Runtime.storage_has_key(key)
+    }
+
+    fn write_storage(&mut self, key: &[u8], value: &[u8]) -> Option<Self::StorageValue> {
+        unsafe {
+            if exports::storage_write(
+                key.len() as _,
+                key.as_ptr() as _,
+                value.len() as _,
+                value.as_ptr() as _,
+                Self::REGISTER_ID.0,
+            ) == 1
+            {
+                Some(Self::REGISTER_ID)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn write_storage_direct(
+        &mut self,
+        key: &[u8],
+        value: Self::StorageValue,
+    ) -> Option<Self::StorageValue> {
+        unsafe {
+            if exports::storage_write(
+                key.len() as _,
+                key.as_ptr() as _,
+                u64::MAX,
+                value.0,
+                Self::REGISTER_ID.0,
+            ) == 1
+            {
+                Some(Self::REGISTER_ID)
+            } else {
+                None
+            }
+        }
+    }
+
+    fn remove_storage(&mut self, key: &[u8]) -> Option<Self::StorageValue> {
+        unsafe {
+            if exports::storage_remove(key.len() as _, key.as_ptr() as _, Self::REGISTER_ID.0) == 1
+            {
+                Some(Self::REGISTER_ID)
+            } else {
+                None
+            }
+        }
+    }
+}
+
 impl crate::env::Env for Runtime {
     fn signer_account_id(&self) -> AccountId {
         unsafe {
@@ -241,6 +326,10 @@ impl crate::env::Env for Runtime {
     fn prepaid_gas(&self) -> NearGas {
         NearGas::new(unsafe { exports::prepaid_gas() })
     }
+
+    fn used_gas(&self) -> NearGas {
+        NearGas::new(unsafe { exports::used_gas() })
+    }
 }
 
 impl crate::promise::PromiseHandler for Runtime {
@@ -392,7 +481,7 @@ pub(crate) mod exports {
         fn account_balance(balance_ptr: u64);
         pub(crate) fn attached_deposit(balance_ptr: u64);
         pub(crate) fn prepaid_gas() -> u64;
-        fn used_gas() -> u64;
+        pub(crate) fn used_gas() -> u64;
         // ############
         // # Math API #
         // ############
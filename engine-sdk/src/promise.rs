@@ -34,3 +34,39 @@ pub trait PromiseHandler {
         self.promise_attach_callback(base, &args.callback)
     }
 }
+
+/// The handler of a trait object, e.g. `&mut dyn PromiseHandler` picked at runtime by a
+/// simulation.
+impl<'a, P: PromiseHandler + ?Sized> PromiseHandler for &'a mut P {
+    fn promise_results_count(&self) -> u64 {
+        (**self).promise_results_count()
+    }
+
+    fn promise_result(&self, index: u64) -> Option<PromiseResult> {
+        (**self).promise_result(index)
+    }
+
+    fn promise_create_call(&mut self, args: &PromiseCreateArgs) -> PromiseId {
+        (**self).promise_create_call(args)
+    }
+
+    fn promise_attach_callback(
+        &mut self,
+        base: PromiseId,
+        callback: &PromiseCreateArgs,
+    ) -> PromiseId {
+        (**self).promise_attach_callback(base, callback)
+    }
+
+    fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId {
+        (**self).promise_create_batch(args)
+    }
+
+    fn promise_return(&mut self, promise: PromiseId) {
+        (**self).promise_return(promise)
+    }
+
+    fn promise_crate_with_callback(&mut self, args: &PromiseWithCallbackArgs) -> PromiseId {
+        (**self).promise_crate_with_callback(args)
+    }
+}
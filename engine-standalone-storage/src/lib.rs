@@ -12,6 +12,7 @@ pub mod error;
 pub mod json_snapshot;
 mod promise;
 pub mod relayer_db;
+pub mod state_proof;
 /// Functions for receiving new blocks and transactions to keep the storage up to date.
 pub mod sync;
 
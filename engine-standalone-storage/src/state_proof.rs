@@ -0,0 +1,361 @@
+//! Verification of the NEAR state proofs of the values stored by the engine contract.
+//!
+//! A proof is the list of the trie nodes on the path from the state root to the key, as returned
+//! by the `view_state` RPC query with `include_proof`. The state root must be taken from a
+//! chunk verified with a NEAR light client, only the path from the root to the value is checked
+//! here.
+use aurora_engine::fungible_token::account_balance_key;
+use aurora_engine_sdk::sha256;
+use aurora_engine_types::account_id::AccountId;
+use aurora_engine_types::types::Balance;
+use aurora_engine_types::H256;
+use borsh::BorshSerialize;
+use std::collections::HashMap;
+
+/// Column of the contract data in the keys of the NEAR trie.
+const CONTRACT_DATA_COLUMN: u8 = 9;
+/// Separator between the account id and the key of the contract data in the keys of the trie.
+const ACCOUNT_DATA_SEPARATOR: u8 = b',';
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateProofError {
+    /// A node on the path to the key is not part of the proof.
+    MissingNode(H256),
+    /// A node of the proof can't be decoded.
+    InvalidNode,
+    /// The proof leads to a value other than the expected one.
+    ValueMismatch,
+}
+
+/// Length and hash of a value stored in the trie.
+#[derive(Debug, PartialEq, Eq)]
+struct ValueRef {
+    length: u32,
+    hash: H256,
+}
+
+enum TrieNode {
+    Leaf {
+        key: Vec<u8>,
+        value: ValueRef,
+    },
+    Branch {
+        children: [Option<H256>; 16],
+        value: Option<ValueRef>,
+    },
+    Extension {
+        key: Vec<u8>,
+        child: H256,
+    },
+}
+
+/// Checks the proof of the value stored by the contract under `key`, `None` to prove that
+/// nothing is stored under it.
+pub fn verify_contract_data_proof(
+    state_root: H256,
+    contract_account_id: &AccountId,
+    key: &[u8],
+    expected_value: Option<&[u8]>,
+    proof: &[Vec<u8>],
+) -> Result<(), StateProofError> {
+    let nodes: HashMap<H256, &[u8]> = proof
+        .iter()
+        .map(|node| (sha256(node), node.as_slice()))
+        .collect();
+    let trie_key = [
+        &[CONTRACT_DATA_COLUMN],
+        contract_account_id.as_bytes(),
+        &[ACCOUNT_DATA_SEPARATOR],
+        key,
+    ]
+    .concat();
+    let value = lookup(&nodes, state_root, &to_nibbles(&trie_key))?;
+    let expected_value = expected_value.map(|value| ValueRef {
+        length: value.len() as u32,
+        hash: sha256(value),
+    });
+    if value == expected_value {
+        Ok(())
+    } else {
+        Err(StateProofError::ValueMismatch)
+    }
+}
+
+/// Checks the proof of the nETH balance of the account, `None` to prove that the account is
+/// not registered with the eth-connector.
+pub fn verify_balance_proof(
+    state_root: H256,
+    engine_account_id: &AccountId,
+    account_id: &AccountId,
+    balance: Option<Balance>,
+    proof: &[Vec<u8>],
+) -> Result<(), StateProofError> {
+    let value = balance.map(|balance| balance.try_to_vec().unwrap());
+    verify_contract_data_proof(
+        state_root,
+        engine_account_id,
+        &account_balance_key(account_id),
+        value.as_deref(),
+        proof,
+    )
+}
+
+fn lookup(
+    nodes: &HashMap<H256, &[u8]>,
+    state_root: H256,
+    mut key: &[u8],
+) -> Result<Option<ValueRef>, StateProofError> {
+    // The root of an empty trie.
+    if state_root == H256::zero() {
+        return Ok(None);
+    }
+    let mut hash = state_root;
+    loop {
+        let node = nodes.get(&hash).ok_or(StateProofError::MissingNode(hash))?;
+        match decode_node(node)? {
+            TrieNode::Leaf {
+                key: leaf_key,
+                value,
+            } => {
+                return Ok(if decode_nibbles(&leaf_key)? == key {
+                    Some(value)
+                } else {
+                    None
+                });
+            }
+            TrieNode::Extension {
+                key: extension_key,
+                child,
+            } => {
+                let extension_key = decode_nibbles(&extension_key)?;
+                if !key.starts_with(&extension_key) {
+                    return Ok(None);
+                }
+                key = &key[extension_key.len()..];
+                hash = child;
+            }
+            TrieNode::Branch { children, value } => match key.split_first() {
+                None => return Ok(value),
+                Some((nibble, rest)) => match children[*nibble as usize] {
+                    Some(child) => {
+                        key = rest;
+                        hash = child;
+                    }
+                    None => return Ok(None),
+                },
+            },
+        }
+    }
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], StateProofError> {
+        if self.0.len() < length {
+            return Err(StateProofError::InvalidNode);
+        }
+        let (taken, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, StateProofError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, StateProofError> {
+        let mut bytes = [0u8; 2];
+        bytes.copy_from_slice(self.take(2)?);
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn u32(&mut self) -> Result<u32, StateProofError> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn hash(&mut self) -> Result<H256, StateProofError> {
+        Ok(H256::from_slice(self.take(32)?))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, StateProofError> {
+        let length = self.u32()? as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+
+    fn value_ref(&mut self) -> Result<ValueRef, StateProofError> {
+        Ok(ValueRef {
+            length: self.u32()?,
+            hash: self.hash()?,
+        })
+    }
+
+    fn children(&mut self) -> Result<[Option<H256>; 16], StateProofError> {
+        let bitmap = self.u16()?;
+        let mut children = [None; 16];
+        for (i, child) in children.iter_mut().enumerate() {
+            if bitmap & (1 << i) != 0 {
+                *child = Some(self.hash()?);
+            }
+        }
+        Ok(children)
+    }
+}
+
+/// Decodes a node of the trie as serialized by nearcore, followed by its memory usage.
+fn decode_node(bytes: &[u8]) -> Result<TrieNode, StateProofError> {
+    let mut cursor = Cursor(bytes);
+    let node = match cursor.u8()? {
+        0 => TrieNode::Leaf {
+            key: cursor.bytes()?,
+            value: cursor.value_ref()?,
+        },
+        1 => TrieNode::Branch {
+            children: cursor.children()?,
+            value: None,
+        },
+        2 => {
+            let value = cursor.value_ref()?;
+            TrieNode::Branch {
+                children: cursor.children()?,
+                value: Some(value),
+            }
+        }
+        3 => TrieNode::Extension {
+            key: cursor.bytes()?,
+            child: cursor.hash()?,
+        },
+        _ => return Err(StateProofError::InvalidNode),
+    };
+    cursor.take(8)?;
+    if !cursor.0.is_empty() {
+        return Err(StateProofError::InvalidNode);
+    }
+    Ok(node)
+}
+
+fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+/// The first byte of the key of a leaf or an extension tells whether the number of nibbles is
+/// odd, in which case it holds the first nibble.
+fn decode_nibbles(encoded: &[u8]) -> Result<Vec<u8>, StateProofError> {
+    let (first, rest) = encoded.split_first().ok_or(StateProofError::InvalidNode)?;
+    let mut nibbles = Vec::with_capacity(2 * encoded.len());
+    if first & 0x10 != 0 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(to_nibbles(rest));
+    Ok(nibbles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_nibbles(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+        let leaf_flag = if is_leaf { 0x20 } else { 0 };
+        let (mut encoded, rest) = if nibbles.len() % 2 == 1 {
+            (vec![leaf_flag | 0x10 | nibbles[0]], &nibbles[1..])
+        } else {
+            (vec![leaf_flag], nibbles)
+        };
+        encoded.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+        encoded
+    }
+
+    fn leaf(nibbles: &[u8], value: &[u8]) -> Vec<u8> {
+        let key = encode_nibbles(nibbles, true);
+        [
+            &[0u8][..],
+            &(key.len() as u32).to_le_bytes(),
+            &key,
+            &(value.len() as u32).to_le_bytes(),
+            sha256(value).as_bytes(),
+            &0u64.to_le_bytes(),
+        ]
+        .concat()
+    }
+
+    fn branch(children: &[(u8, H256)]) -> Vec<u8> {
+        let bitmap = children
+            .iter()
+            .fold(0u16, |bitmap, (nibble, _)| bitmap | (1 << nibble));
+        let mut node = vec![1u8];
+        node.extend_from_slice(&bitmap.to_le_bytes());
+        for (_, child) in children.iter() {
+            node.extend_from_slice(child.as_bytes());
+        }
+        node.extend_from_slice(&0u64.to_le_bytes());
+        node
+    }
+
+    #[test]
+    fn test_verify_balance_proof() {
+        let engine_account_id: AccountId = "aurora".parse().unwrap();
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let trie_key = |account_id: &AccountId| {
+            to_nibbles(
+                &[
+                    &[CONTRACT_DATA_COLUMN],
+                    engine_account_id.as_bytes(),
+                    &[ACCOUNT_DATA_SEPARATOR],
+                    &account_balance_key(account_id),
+                ]
+                .concat(),
+            )
+        };
+
+        // A branch on the first nibble, which is the same for both keys, then the leaf of Alice.
+        let alice_key = trie_key(&alice);
+        let alice_leaf = leaf(&alice_key[1..], &100u128.try_to_vec().unwrap());
+        let root = branch(&[(alice_key[0], sha256(&alice_leaf))]);
+        let state_root = sha256(&root);
+        let proof = vec![root, alice_leaf];
+
+        assert_eq!(
+            verify_balance_proof(state_root, &engine_account_id, &alice, Some(100), &proof),
+            Ok(())
+        );
+        assert_eq!(
+            verify_balance_proof(state_root, &engine_account_id, &alice, Some(99), &proof),
+            Err(StateProofError::ValueMismatch)
+        );
+        // The leaf of Alice proves that Bob has no balance
+        assert_eq!(
+            verify_balance_proof(state_root, &engine_account_id, &bob, None, &proof),
+            Ok(())
+        );
+        assert_eq!(
+            verify_balance_proof(
+                state_root,
+                &engine_account_id,
+                &alice,
+                Some(100),
+                &proof[..1]
+            ),
+            Err(StateProofError::MissingNode(sha256(&proof[1])))
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_node() {
+        assert!(matches!(
+            decode_node(&[4u8; 41]),
+            Err(StateProofError::InvalidNode)
+        ));
+        let mut node = branch(&[]);
+        node.push(0);
+        assert!(matches!(
+            decode_node(&node),
+            Err(StateProofError::InvalidNode)
+        ));
+    }
+}
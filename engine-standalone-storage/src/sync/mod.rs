@@ -1,4 +1,4 @@
-use aurora_engine::{connector, engine, parameters};
+use aurora_engine::{connector, engine, parameters, storage_breakdown, test_chain};
 use aurora_engine_sdk::env::{self, Env, DEFAULT_PREPAID_GAS};
 use aurora_engine_types::TryFrom;
 use borsh::BorshDeserialize;
@@ -48,8 +48,9 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                 random_seed: block_metadata.random_seed,
                 prepaid_gas: DEFAULT_PREPAID_GAS,
             };
-            let io =
+            let engine_io =
                 storage.access_engine_storage_at_position(block_height, transaction_position, &[]);
+            let io = storage_breakdown::TrackedIO::new(engine_io);
 
             let tx_hash = match transaction_message.transaction {
                 TransactionKind::Submit(tx) => {
@@ -58,16 +59,24 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                     let engine_state = engine::get_state(&io)?;
                     let transaction_bytes: Vec<u8> = tx.into();
                     let tx_hash = aurora_engine_sdk::keccak(&transaction_bytes);
+                    // The transactions of an engine block opened by the tests see its number
+                    // and timestamp, as in the contract.
+                    let block_env = test_chain::TestBlockEnv::new(
+                        &env,
+                        test_chain::get_open_block(&io).as_ref(),
+                    );
 
-                    let _result = engine::submit(
+                    let result = engine::submit(
                         io,
-                        &env,
+                        &block_env,
                         &transaction_bytes,
                         engine_state,
                         env.current_account_id(),
                         relayer_address,
                         &mut handler,
                     )?;
+                    let mut io = io;
+                    test_chain::record_transaction(&mut io, &transaction_bytes, &result);
 
                     tx_hash
                 }
@@ -108,8 +117,11 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                         engine::Engine::new(relayer_address, env.current_account_id(), io, &env)?;
 
                     if env.predecessor_account_id == env.current_account_id {
-                        connector::EthConnectorContract::init_instance(io)
-                            .ft_on_transfer(&engine, &args)?;
+                        connector::EthConnectorContract::init_instance(io).ft_on_transfer(
+                            &mut engine,
+                            &args,
+                            &mut handler,
+                        )?;
                     } else {
                         engine.receive_erc20_tokens(
                             &env.predecessor_account_id,
@@ -136,31 +148,39 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
                     // it this far, so we will not worry about `promise_args.base` and move
                     // straight to the callback.
 
-                    let finish_args = parameters::FinishDepositCallArgs::try_from_slice(
+                    let finish_args = parameters::ResolveDepositVerificationArgs::try_from_slice(
                         &promise_args.callback.args,
                     )
-                    .expect("Connector deposit function must return valid args");
-                    let maybe_promise_args = connector_contract.finish_deposit(
+                    .expect("Connector deposit function must return valid args")
+                    .finish_args;
+                    let result = connector_contract.finish_deposit(
                         env.predecessor_account_id(),
                         env.current_account_id(),
                         finish_args,
                         env.prepaid_gas,
+                        env.block_height,
                     )?;
 
-                    if let Some(promise_args) = maybe_promise_args {
+                    if let connector::FinishDepositResult::Eth(promise_args) = result {
                         let on_transfer_args =
                             aurora_engine::json::parse_json(&promise_args.base.args)
                                 .and_then(|json| {
                                     parameters::NEP141FtOnTransferArgs::try_from(json).ok()
                                 })
                                 .expect("Connector finish_deposit function must return valid args");
-                        let engine = engine::Engine::new(
+                        // No promises can be created by `ft_on_transfer`
+                        let mut handler = crate::promise::Noop;
+                        let mut engine = engine::Engine::new(
                             relayer_address,
                             env.current_account_id(),
                             io,
                             &env,
                         )?;
-                        connector_contract.ft_on_transfer(&engine, &on_transfer_args)?;
+                        connector_contract.ft_on_transfer(
+                            &mut engine,
+                            &on_transfer_args,
+                            &mut handler,
+                        )?;
                         // `ft_on_transfer` always returns an unused amount of 0 if it executes
                         // successfully, meaning that `ft_resolve_transfer` will do nothing,
                         // so we skip the promise_args callback.
@@ -168,9 +188,27 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
 
                     near_tx_hash
                 }
+
+                TransactionKind::BeginBlock(args) => {
+                    let mut io = io;
+                    test_chain::begin_block(&mut io, &args)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::EndBlock => {
+                    let mut io = io;
+                    test_chain::end_block(&mut io)?;
+                    near_tx_hash
+                }
+
+                TransactionKind::SetStorageBreakdownEnabled(args) => {
+                    let mut io = io;
+                    storage_breakdown::set_storage_breakdown_enabled(&mut io, args.enabled);
+                    near_tx_hash
+                }
             };
 
-            let diff = io.get_transaction_diff();
+            let diff = engine_io.get_transaction_diff();
             let tx_included = crate::TransactionIncluded {
                 block_hash,
                 position: transaction_position,
@@ -183,7 +221,7 @@ pub fn consume_message(storage: &mut crate::Storage, message: Message) -> Result
 }
 
 pub mod error {
-    use aurora_engine::{connector, engine};
+    use aurora_engine::{connector, engine, test_chain};
 
     #[derive(Debug)]
     pub enum Error {
@@ -194,6 +232,7 @@ pub mod error {
         FtOnTransfer(connector::error::FtTransferCallError),
         Deposit(connector::error::DepositError),
         FinishDeposit(connector::error::FinishDepositError),
+        TestChain(test_chain::error::TestChainError),
     }
 
     impl From<crate::Error> for Error {
@@ -231,4 +270,9 @@ pub mod error {
             Self::FinishDeposit(e)
         }
     }
+    impl From<test_chain::error::TestChainError> for Error {
+        fn from(e: test_chain::error::TestChainError) -> Self {
+            Self::TestChain(e)
+        }
+    }
 }
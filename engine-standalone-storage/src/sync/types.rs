@@ -53,4 +53,10 @@ pub enum TransactionKind {
     FtOnTransfer(parameters::NEP141FtOnTransferArgs),
     /// Bytes here will be parsed into `aurora_engine::proof::Proof`
     Deposit(Vec<u8>),
+    /// Engine block opened by the tests
+    BeginBlock(parameters::BeginBlockArgs),
+    /// End of the engine block opened by the tests
+    EndBlock,
+    /// Tracking of the storage breakdown enabled or disabled by the owner
+    SetStorageBreakdownEnabled(parameters::SetStorageBreakdownEnabledArgs),
 }
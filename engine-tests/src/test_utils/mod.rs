@@ -10,6 +10,7 @@ use near_vm_logic::{VMContext, VMOutcome};
 use near_vm_runner::{MockCompiledContractCache, VMError};
 use rlp::RlpStream;
 use secp256k1::{self, Message, PublicKey, SecretKey};
+use std::collections::HashMap;
 
 use crate::prelude::fungible_token::{FungibleToken, FungibleTokenMetadata};
 use crate::prelude::parameters::{
@@ -182,6 +183,10 @@ impl AuroraRunner {
             signer_account_id,
             input,
         );
+        let trie_before_call = self
+            .standalone_runner
+            .as_ref()
+            .map(|_| self.ext.fake_trie.clone());
 
         let (maybe_outcome, maybe_error) = near_vm_runner::run(
             &self.code,
@@ -200,12 +205,18 @@ impl AuroraRunner {
         }
 
         if let Some(standalone_runner) = &mut self.standalone_runner {
-            if maybe_error.is_none()
-                && (method_name == SUBMIT || method_name == CALL || method_name == DEPLOY_ERC20)
-            {
-                standalone_runner
-                    .submit_raw(method_name, &self.context)
-                    .unwrap();
+            if maybe_error.is_none() {
+                if method_name == SUBMIT || method_name == CALL || method_name == DEPLOY_ERC20 {
+                    standalone_runner
+                        .submit_raw(method_name, &self.context)
+                        .unwrap();
+                } else {
+                    // The standalone engine doesn't execute the other methods, the changes of
+                    // the state they made are copied into it instead.
+                    let changes =
+                        Self::state_changes(&trie_before_call.unwrap(), &self.ext.fake_trie);
+                    standalone_runner.apply_state_changes(&self.context, changes);
+                }
                 self.validate_standalone();
             }
         }
@@ -464,12 +475,55 @@ impl AuroraRunner {
         self
     }
 
+    /// Writes a value to the storage of the engine, or removes it with `None`, bypassing the
+    /// contract. The value is written to the standalone engine as well.
+    pub fn set_storage(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        match &value {
+            Some(value) => self.ext.fake_trie.insert(key.clone(), value.clone()),
+            None => self.ext.fake_trie.remove(&key),
+        };
+        if let Some(standalone_runner) = &mut self.standalone_runner {
+            standalone_runner.apply_state_changes(&self.context, vec![(key, value)]);
+        }
+    }
+
+    /// Values written or removed (`None`) between the two states of the trie. The engine
+    /// sequence is only maintained by the contract, so it is left out.
+    fn state_changes(
+        before: &HashMap<Vec<u8>, Vec<u8>>,
+        after: &HashMap<Vec<u8>, Vec<u8>>,
+    ) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+        let sequence_key = aurora_engine_types::storage::config_key(
+            aurora_engine_types::storage::ConfigKey::EngineSequence,
+            &[],
+        );
+        let written = after
+            .iter()
+            .filter(|(key, value)| before.get(*key) != Some(*value))
+            .map(|(key, value)| (key.clone(), Some(value.clone())));
+        let removed = before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .map(|key| (key.clone(), None));
+        written
+            .chain(removed)
+            .filter(|(key, _)| key != &sequence_key)
+            .collect()
+    }
+
     fn validate_standalone(&self) {
         if let Some(standalone_runner) = &self.standalone_runner {
             let standalone_state = standalone_runner.get_current_state();
             // The number of keys in standalone_state may be larger because values are never deleted
             // (they are replaced with a Deleted identifier instead; this is important for replaying transactions).
-            assert!(self.ext.fake_trie.len() <= standalone_state.iter().count());
+            // The engine sequence is only maintained by the contract.
+            let sequence_key = aurora_engine_types::storage::config_key(
+                aurora_engine_types::storage::ConfigKey::EngineSequence,
+                &[],
+            );
+            let trie_len = self.ext.fake_trie.len()
+                - usize::from(self.ext.fake_trie.contains_key(&sequence_key));
+            assert!(trie_len <= standalone_state.iter().count());
             for (key, value) in standalone_state.iter() {
                 let trie_value = self.ext.fake_trie.get(key).map(|v| v.as_slice());
                 let standalone_value = value.value();
@@ -731,8 +785,8 @@ pub(crate) fn sign_eip_1559_transaction(
 
 pub(crate) fn address_from_secret_key(sk: &SecretKey) -> Address {
     let pk = PublicKey::from_secret_key(sk);
-    let hash = sdk::keccak(&pk.serialize()[1..]);
-    Address::from_slice(&hash[12..])
+    let public_key = crate::prelude::parse_public_key(&pk.serialize()).unwrap();
+    crate::prelude::address_from_public_key(&public_key)
 }
 
 pub(crate) fn parse_eth_gas(output: &VMOutcome) -> u64 {
@@ -11,8 +11,6 @@ use near_sdk_sim::DEFAULT_GAS;
 use crate::test_utils;
 
 pub mod block;
-pub mod promise;
-pub mod storage;
 pub mod tracing;
 
 pub const ETH_CUSTODIAN_ADDRESS: Address =
@@ -106,6 +104,8 @@ pub fn mint_evm_account<I: IO + Copy, E: Env>(
         relayer_id: aurora_account_id.clone(),
         fee: 0.into(),
         msg: None,
+        memo: None,
+        sender: None,
     };
 
     // Delete the fake proof so that we can use it again.
@@ -121,11 +121,40 @@ pub fn mint_evm_account<I: IO + Copy, E: Env>(
             aurora_account_id.clone(),
             deposit_args,
             NearGas::new(DEFAULT_GAS),
+            env.block_height(),
         )
         .map_err(unsafe_to_string)
         .unwrap();
 }
 
+/// Overwrite the state of an EVM account, including its storage. Unlike `mint_evm_account`
+/// this does not mint the corresponding nETH on the connector, so the total supply is not
+/// updated.
+pub fn set_evm_account_state<I: IO + Copy, E: Env>(
+    address: Address,
+    balance: Wei,
+    nonce: U256,
+    code: Vec<u8>,
+    storage: Vec<(H256, H256)>,
+    io: I,
+    env: &E,
+) {
+    use evm::backend::ApplyBackend;
+
+    let mut engine = engine::Engine::new(address, env.current_account_id(), io, env).unwrap();
+    let state_change = evm::backend::Apply::Modify {
+        address,
+        basic: evm::backend::Basic {
+            balance: balance.raw(),
+            nonce,
+        },
+        code: Some(code),
+        storage,
+        reset_storage: true,
+    };
+    engine.apply(std::iter::once(state_change), std::iter::empty(), false);
+}
+
 pub fn unsafe_to_string<E: AsRef<[u8]>>(e: E) -> String {
     String::from_utf8(e.as_ref().to_vec()).unwrap()
 }
@@ -1,10 +1,17 @@
 use aurora_engine::engine;
-use aurora_engine::parameters::{CallArgs, DeployErc20TokenArgs, SubmitResult, TransactionStatus};
+use aurora_engine::parameters::{
+    BeginBlockArgs, CallArgs, DeployErc20TokenArgs, FreezeTarget, SubmitResult, TestBlockSummary,
+    TransactionStatus,
+};
+use aurora_engine::storage_breakdown::TrackedIO;
+use aurora_engine::test_chain;
 use aurora_engine::transaction::legacy::{LegacyEthSignedTransaction, TransactionLegacy};
 use aurora_engine_sdk::env::{self, Env};
+use aurora_engine_sdk::io::IO;
+use aurora_engine_sdk::memory;
 use aurora_engine_types::types::NearGas;
 use aurora_engine_types::{types::Wei, Address, H256, U256};
-use borsh::BorshDeserialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use engine_standalone_storage::engine_state;
 use engine_standalone_storage::{BlockMetadata, Diff, Storage};
 use secp256k1::SecretKey;
@@ -66,6 +73,104 @@ impl StandaloneRunner {
         io.finish().commit(storage, &mut self.cumulative_diff);
     }
 
+    /// Sets the full state of the account, see `mocks::set_evm_account_state`.
+    pub fn set_account_state(
+        &mut self,
+        address: Address,
+        balance: Wei,
+        nonce: U256,
+        code: Vec<u8>,
+        account_storage: Vec<(H256, H256)>,
+    ) {
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        let transaction_hash = aurora_engine_sdk::keccak(address.as_ref());
+
+        env.block_height += 1;
+        let io = Self::get_engine_io(storage, env, 0, transaction_hash);
+
+        mocks::set_evm_account_state(
+            address,
+            balance,
+            nonce,
+            code,
+            account_storage,
+            io.engine_io,
+            env,
+        );
+
+        io.finish().commit(storage, &mut self.cumulative_diff);
+    }
+
+    /// Freezes the NEAR account or EVM address, as `freeze_account` does.
+    pub fn freeze(&mut self, target: &FreezeTarget) {
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        let transaction_hash = aurora_engine_sdk::keccak(&target.try_to_vec().unwrap());
+
+        env.block_height += 1;
+        let mut io = Self::get_engine_io(storage, env, 0, transaction_hash);
+        aurora_engine::freeze::freeze(&mut io.engine_io, target);
+        io.finish().commit(storage, &mut self.cumulative_diff);
+    }
+
+    /// Writes the changes of the state made by a call of the contract the standalone engine
+    /// doesn't execute (e.g. the configuration of the engine by its owner), as the values of
+    /// the storage to remove (`None`) or write.
+    pub fn apply_state_changes(
+        &mut self,
+        ctx: &near_vm_logic::VMContext,
+        changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    ) {
+        if changes.is_empty() {
+            return;
+        }
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        env.block_height = ctx.block_index;
+        let transaction_hash = aurora_engine_sdk::keccak(&changes.try_to_vec().unwrap());
+
+        let io = Self::get_engine_io(storage, env, 0, transaction_hash);
+        let mut engine_io = io.engine_io;
+        for (key, value) in changes {
+            match value {
+                Some(value) => engine_io.write_storage(&key, &value),
+                None => engine_io.remove_storage(&key),
+            };
+        }
+        io.finish().commit(storage, &mut self.cumulative_diff);
+    }
+
+    /// Opens an engine block, the transactions submitted until `end_block` are part of it.
+    pub fn begin_block(
+        &mut self,
+        args: &BeginBlockArgs,
+    ) -> Result<(), test_chain::error::TestChainError> {
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        let transaction_hash = aurora_engine_sdk::keccak(&args.try_to_vec().unwrap());
+
+        env.block_height += 1;
+        let io = Self::get_engine_io(storage, env, 0, transaction_hash);
+        let mut engine_io = io.engine_io;
+        let result = test_chain::begin_block(&mut engine_io, args);
+        io.finish().commit(storage, &mut self.cumulative_diff);
+        result
+    }
+
+    pub fn end_block(&mut self) -> Result<TestBlockSummary, test_chain::error::TestChainError> {
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        let transaction_hash = aurora_engine_sdk::keccak(&env.block_height.to_be_bytes());
+
+        env.block_height += 1;
+        let io = Self::get_engine_io(storage, env, 0, transaction_hash);
+        let mut engine_io = io.engine_io;
+        let result = test_chain::end_block(&mut engine_io);
+        io.finish().commit(storage, &mut self.cumulative_diff);
+        result
+    }
+
     pub fn submit_transaction(
         &mut self,
         account: &SecretKey,
@@ -105,6 +210,51 @@ impl StandaloneRunner {
         )
     }
 
+    /// Submits the borsh encoded `FeePayerMetaCallArgs` as `submit_with_fee_payer` does, the
+    /// fee payer being the EVM address of `fee_payer_account_id`.
+    #[cfg(feature = "meta-call")]
+    pub fn submit_with_fee_payer(
+        &mut self,
+        fee_payer_account_id: &str,
+        input: &[u8],
+    ) -> Result<SubmitResult, engine::EngineError> {
+        self.env.predecessor_account_id = fee_payer_account_id.parse().unwrap();
+        let storage = &mut self.storage;
+        let env = &mut self.env;
+        env.block_height += 1;
+        let transaction_hash = aurora_engine_sdk::keccak(input);
+        let fee_payer = Self::relayer_address(env);
+
+        let io = Self::get_engine_io(storage, env, 0, transaction_hash);
+        let engine_state = engine::get_state(&io.engine_io).unwrap();
+        let args = aurora_engine::parameters::FeePayerMetaCallArgs::try_from_slice(input).unwrap();
+        let domain_separator = aurora_engine::meta_parsing::domain_separator(
+            &io.engine_io,
+            U256::from(engine_state.chain_id),
+        );
+        let meta_call = aurora_engine::meta_parsing::verify_meta_call(
+            &domain_separator,
+            env.current_account_id().as_bytes(),
+            args.meta_call,
+        )
+        .unwrap_or_else(|_| panic!("ERR_META_TX_PARSE"));
+        let mut handler = memory::PromiseTracker::default();
+
+        let result = engine::submit_with_fee_payer(
+            io.engine_io,
+            env,
+            meta_call,
+            args.gas_limit,
+            U256::from_big_endian(&args.gas_price),
+            engine_state,
+            env.current_account_id(),
+            fee_payer,
+            &mut handler,
+        )?;
+        io.finish().commit(storage, &mut self.cumulative_diff);
+        Ok(result)
+    }
+
     /// Note: does not persist the diff in the DB.
     pub fn execute_transaction_at_position(
         &mut self,
@@ -122,7 +272,7 @@ impl StandaloneRunner {
 
         let io = Self::get_engine_io(storage, env, transaction_position, transaction_hash);
         let engine_state = engine::get_state(&io.engine_io).unwrap();
-        let mut handler = mocks::promise::PromiseTracker::default();
+        let mut handler = memory::PromiseTracker::default();
 
         engine::submit(
             io.engine_io,
@@ -163,23 +313,25 @@ impl StandaloneRunner {
             )
         } else if method_name == test_utils::CALL {
             let call_args = CallArgs::try_from_slice(&ctx.input).unwrap();
-            let mut handler = mocks::promise::PromiseTracker::default();
+            let mut handler = memory::PromiseTracker::default();
             let transaction_hash = aurora_engine_sdk::keccak(&ctx.input);
             let io = Self::get_engine_io(storage, &env, 0, transaction_hash);
             let origin = aurora_engine_sdk::types::near_account_to_evm_address(
                 env.predecessor_account_id.as_bytes(),
             );
+            let engine_io = TrackedIO::new(io.engine_io);
             let mut engine =
-                engine::Engine::new(origin, env.current_account_id(), io.engine_io, &env).unwrap();
+                engine::Engine::new(origin, env.current_account_id(), engine_io, &env).unwrap();
             let result = engine.call_with_args(call_args, &mut handler)?;
             io.finish().commit(storage, &mut self.cumulative_diff);
             Ok(result)
         } else if method_name == test_utils::DEPLOY_ERC20 {
             let deploy_args = DeployErc20TokenArgs::try_from_slice(&ctx.input).unwrap();
-            let mut handler = mocks::promise::PromiseTracker::default();
+            let mut handler = memory::PromiseTracker::default();
             let transaction_hash = aurora_engine_sdk::keccak(&ctx.input);
             let io = Self::get_engine_io(storage, &env, 0, transaction_hash);
-            let address = engine::deploy_erc20_token(deploy_args, io.engine_io, &env, &mut handler)
+            let engine_io = TrackedIO::new(io.engine_io);
+            let address = engine::deploy_erc20_token(deploy_args, engine_io, &env, &mut handler)
                 .map_err(mocks::unsafe_to_string)
                 .unwrap();
             io.finish().commit(storage, &mut self.cumulative_diff);
@@ -257,18 +409,22 @@ impl StandaloneRunner {
         let relayer_address = Self::relayer_address(env);
         let transaction_hash = aurora_engine_sdk::keccak(&transaction_bytes);
         let io = Self::get_engine_io(storage, env, transaction_position, transaction_hash);
-        let engine_state = engine::get_state(&io.engine_io).unwrap();
-        let mut handler = mocks::promise::PromiseTracker::default();
+        let mut engine_io = TrackedIO::new(io.engine_io);
+        let engine_state = engine::get_state(&engine_io).unwrap();
+        let mut handler = memory::PromiseTracker::default();
+        let block_env =
+            test_chain::TestBlockEnv::new(&*env, test_chain::get_open_block(&engine_io).as_ref());
 
         let result = engine::submit(
-            io.engine_io,
-            env,
+            engine_io,
+            &block_env,
             &transaction_bytes,
             engine_state,
             env.current_account_id(),
             relayer_address,
             &mut handler,
         )?;
+        test_chain::record_transaction(&mut engine_io, transaction_bytes, &result);
         io.finish().commit(storage, cumulative_diff);
 
         Ok(result)
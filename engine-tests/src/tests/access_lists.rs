@@ -1,8 +1,10 @@
 use crate::prelude::Wei;
 use crate::prelude::{H256, U256};
 use crate::test_utils;
+use aurora_engine::parameters::SubmitResult;
 use aurora_engine::transaction::eip_2930::{self, AccessTuple, Transaction2930};
-use aurora_engine::transaction::EthTransactionKind;
+use aurora_engine::transaction::{EthTransactionKind, ParseTransactionError};
+use borsh::BorshDeserialize;
 use std::convert::TryFrom;
 use std::iter;
 
@@ -58,6 +60,87 @@ fn test_access_list_tx_encoding_decoding() {
     )
 }
 
+#[test]
+fn test_access_list_tx_prewarms_storage() {
+    let mut runner = test_utils::deploy_evm();
+    let secret_key = secp256k1::SecretKey::parse_slice(
+        &hex::decode("45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8").unwrap(),
+    )
+    .unwrap();
+    let signer_address = test_utils::address_from_secret_key(&secret_key);
+    let contract_address =
+        test_utils::address_from_hex("0xcccccccccccccccccccccccccccccccccccccccc");
+    runner.create_address(signer_address, Wei::zero(), U256::zero());
+    // PUSH1 0 SLOAD STOP
+    runner.create_address_with_code(
+        contract_address,
+        Wei::zero(),
+        U256::one(),
+        hex::decode("60005400").unwrap(),
+    );
+
+    let mut submit = |nonce: u64, access_list: Vec<AccessTuple>| {
+        let transaction = Transaction2930 {
+            chain_id: runner.chain_id,
+            nonce: nonce.into(),
+            gas_price: U256::zero(),
+            gas_limit: U256::from(100_000),
+            to: Some(contract_address),
+            value: Wei::zero(),
+            data: Vec::new(),
+            access_list,
+        };
+        let signed_tx = test_utils::sign_access_list_transaction(transaction, &secret_key);
+        let bytes: Vec<u8> = iter::once(eip_2930::TYPE_BYTE)
+            .chain(rlp::encode(&signed_tx).into_iter())
+            .collect();
+        let (maybe_outcome, maybe_err) = runner.call(test_utils::SUBMIT, "relay.aurora", bytes);
+        assert!(maybe_err.is_none());
+        SubmitResult::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+            .gas_used
+    };
+
+    let cold_gas_used = submit(0, Vec::new());
+    let warm_gas_used = submit(
+        1,
+        vec![AccessTuple {
+            address: contract_address,
+            storage_keys: vec![H256::zero()],
+        }],
+    );
+    // The access list costs 2400 gas for the address and 1900 gas for the slot, and the SLOAD
+    // of the pre-warmed slot costs 100 gas instead of 2100.
+    assert_eq!(warm_gas_used, cold_gas_used + 2400 + 1900 - 2000);
+}
+
+#[test]
+fn test_access_list_tx_malformed_tuple() {
+    let mut stream = rlp::RlpStream::new_list(11);
+    for _ in 0..7 {
+        stream.append(&0u8);
+    }
+    // An access list tuple without its storage keys
+    stream.begin_list(1);
+    stream.begin_list(1);
+    stream.append(&test_utils::address_from_hex(
+        "0xcccccccccccccccccccccccccccccccccccccccc",
+    ));
+    for _ in 0..3 {
+        stream.append(&0u8);
+    }
+    let bytes: Vec<u8> = iter::once(eip_2930::TYPE_BYTE)
+        .chain(stream.out().into_iter())
+        .collect();
+
+    assert_eq!(
+        EthTransactionKind::try_from(bytes.as_slice()),
+        Err(ParseTransactionError::RlpDecodeError(
+            rlp::DecoderError::RlpIncorrectListLen
+        ))
+    );
+}
+
 fn one() -> H256 {
     let mut x = [0u8; 32];
     x[31] = 1;
@@ -4,7 +4,9 @@ use crate::test_utils;
 use aurora_engine::parameters::SubmitResult;
 use aurora_engine::transaction::eip_1559::{self, SignedTransaction1559, Transaction1559};
 use aurora_engine::transaction::eip_2930::AccessTuple;
-use aurora_engine::transaction::EthTransactionKind;
+use aurora_engine::transaction::{
+    EthTransactionKind, NormalizedEthTransaction, ParseTransactionError,
+};
 use borsh::BorshDeserialize;
 use std::convert::TryFrom;
 use std::iter;
@@ -106,6 +108,44 @@ fn test_eip_1559_example() {
     assert_eq!(runner.get_balance(coinbase), Wei::new_u64(0x73834));
 }
 
+#[test]
+fn test_eip_1559_effective_gas_price() {
+    let secret_key = exmaple_signer().secret_key;
+    let signed_tx = test_utils::sign_eip_1559_transaction(example_transaction(), &secret_key);
+    let transaction = NormalizedEthTransaction::from(EthTransactionKind::Eip1559(signed_tx));
+
+    // The priority fee is paid in full while the maximum fee covers it
+    assert_eq!(
+        transaction.effective_gas_price(U256::zero()),
+        U256::from(0x0a)
+    );
+    assert_eq!(
+        transaction.effective_gas_price(U256::from(0x07c6)),
+        U256::from(0x07d0)
+    );
+    // Then it is capped by the maximum fee
+    assert_eq!(
+        transaction.priority_fee_per_gas(U256::from(0x07cc)),
+        U256::from(0x04)
+    );
+    assert_eq!(
+        transaction.effective_gas_price(U256::from(0x07cc)),
+        U256::from(0x07d0)
+    );
+}
+
+#[test]
+fn test_empty_transaction() {
+    assert_eq!(
+        EthTransactionKind::try_from(&[][..]),
+        Err(ParseTransactionError::EmptyInput)
+    );
+
+    let mut runner = test_utils::deploy_evm();
+    let (_, maybe_err) = runner.call(test_utils::SUBMIT, "relay.aurora", Vec::new());
+    assert!(format!("{:?}", maybe_err.unwrap()).contains("ERR_EMPTY_TX"));
+}
+
 fn encode_tx(signed_tx: &SignedTransaction1559) -> Vec<u8> {
     iter::once(eip_1559::TYPE_BYTE)
         .chain(rlp::encode(signed_tx).into_iter())
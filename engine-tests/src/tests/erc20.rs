@@ -7,6 +7,8 @@ use crate::test_utils::{
 };
 use aurora_engine::parameters::TransactionStatus;
 use aurora_engine_sdk as sdk;
+use aurora_engine_types::types::LogBloom;
+use borsh::{BorshDeserialize, BorshSerialize};
 use bstr::ByteSlice;
 use secp256k1::SecretKey;
 
@@ -38,6 +40,43 @@ fn erc20_mint() {
     );
 }
 
+#[test]
+fn erc20_mint_log_bloom_index() {
+    let (mut runner, mut source_account, dest_address, contract) = initialize_erc20();
+
+    let args = aurora_engine::parameters::SetLogBloomIndexArgs { enabled: true };
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call(
+        "set_log_bloom_index_enabled",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    let outcome = runner.submit_with_signer(&mut source_account, |nonce| {
+        contract.mint(dest_address, 10u64.into(), nonce)
+    });
+    assert!(outcome.is_ok());
+    let block_height = runner.context.block_index;
+
+    let get_logs_bloom = |runner: &mut test_utils::AuroraRunner, block_height: u64| {
+        let (outcome, maybe_error) = runner.call(
+            "get_logs_bloom",
+            &account_id,
+            block_height.try_to_vec().unwrap(),
+        );
+        assert!(maybe_error.is_none());
+        let bytes = outcome.unwrap().return_data.as_value().unwrap();
+        Option::<LogBloom>::try_from_slice(&bytes).unwrap()
+    };
+
+    let bloom = get_logs_bloom(&mut runner, block_height).unwrap();
+    let transfer_topic = sdk::keccak(b"Transfer(address,address,uint256)");
+    assert!(bloom.contains_input(contract.0.address.as_bytes()));
+    assert!(bloom.contains_input(transfer_topic.as_bytes()));
+    assert!(get_logs_bloom(&mut runner, block_height - 1).is_none());
+}
+
 #[test]
 fn erc20_mint_out_of_gas() {
     let (mut runner, mut source_account, dest_address, contract) = initialize_erc20();
@@ -233,6 +233,39 @@ fn test_deploy_erc20_token() {
     runner.deploy_erc20_token(&"tt.testnet".to_string());
 }
 
+#[test]
+fn test_deploy_erc20_token_deterministic_address() {
+    let mut runner = AuroraRunner::new();
+    let expected_address = |runner: &mut AuroraRunner, nep141: &str| {
+        let result = runner.make_call(
+            "get_erc20_deploy_address",
+            origin(),
+            nep141.to_string().try_to_vec().unwrap(),
+        );
+        result.check_ok();
+        result.value()
+    };
+    let address = expected_address(&mut runner, "tt.testnet");
+    assert_eq!(address.len(), 20);
+    assert_ne!(address, expected_address(&mut runner, "other.testnet"));
+
+    let token = runner.deploy_erc20_token(&"tt.testnet".to_string());
+    assert_eq!(token.to_vec(), address);
+    // The address is the same on another engine with the same account id
+    let mut other_runner = AuroraRunner::new();
+    other_runner
+        .make_call(
+            "deploy_erc20_token",
+            origin(),
+            "other.testnet".to_string().try_to_vec().unwrap(),
+        )
+        .check_ok();
+    assert_eq!(
+        other_runner.deploy_erc20_token(&"tt.testnet".to_string()),
+        token
+    );
+}
+
 #[test]
 fn test_mint() {
     let mut runner = AuroraRunner::new();
@@ -281,6 +314,42 @@ fn test_ft_on_transfer() {
     assert_eq!(balance, U256::from(amount));
 }
 
+#[test]
+fn test_register_relayer_with_public_key() {
+    let mut runner = AuroraRunner::new();
+    let amount = 10;
+    let fee = 51;
+    let nep141 = "tt.testnet".to_string();
+    let alice = "alice".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let recipient = runner.create_account().address;
+
+    let secret_key = SecretKey::random(&mut rand::thread_rng());
+    let public_key = secp256k1::PublicKey::from_secret_key(&secret_key).serialize();
+    let mut off_curve = public_key;
+    off_curve[64] ^= 1;
+    let result = runner.make_call("register_relayer", alice.clone(), off_curve.to_vec());
+    assert!(result.error.is_some());
+    runner
+        .make_call("register_relayer", alice.clone(), public_key.to_vec())
+        .check_ok();
+
+    let fee_encoded = &mut [0; 32];
+    U256::from(fee).to_big_endian(fee_encoded);
+    runner.ft_on_transfer(
+        nep141,
+        alice.clone(),
+        alice,
+        amount,
+        hex::encode(recipient) + &hex::encode(fee_encoded),
+    );
+
+    let relayer = test_utils::address_from_secret_key(&secret_key);
+    assert_eq!(runner.get_balance(relayer), Wei::new_u64(fee));
+    let balance = runner.balance_of(token, recipient, origin());
+    assert_eq!(balance, U256::from(amount));
+}
+
 #[test]
 fn test_ft_on_transfer_fail() {
     let mut runner = AuroraRunner::new();
@@ -342,6 +411,100 @@ fn test_relayer_charge_fee() {
     assert_eq!(balance, U256::from(amount));
 }
 
+#[test]
+fn test_deposit_fee_swapped_for_base_token() {
+    use aurora_engine::deposit_event::FtTransferMessageData;
+    use aurora_engine::parameters::{DepositFeeCurrency, FeeConversionRoute};
+
+    let mut runner = AuroraRunner::new();
+    let aurora = runner.aurora_account_id.clone();
+    let alice = "alice".to_string();
+    let amount: Balance = 100;
+    let fee: Balance = 51;
+    let base_token = runner.deploy_erc20_token(&"base.testnet".to_string());
+    let deployer = runner.create_account();
+    let constructor = test_utils::solidity::ContractConstructor::compile_from_source(
+        "src/tests/res",
+        "target/solidity_build",
+        "fee_router.sol",
+        "FixedRateRouter",
+    );
+    let router = runner.deploy_contract(
+        &deployer.secret_key,
+        |c| c.deploy_with_args(INITIAL_NONCE.into(), &[Token::Uint(U256::from(3))]),
+        constructor,
+    );
+    runner.mint(base_token, router.address.0, 1_000, origin());
+    let relayer = create_ethereum_address();
+    runner.register_relayer(alice.clone(), relayer);
+    let recipient = runner.create_account().address;
+
+    let set_route = |runner: &mut AuroraRunner, min_amount_out_per_eth: U256| {
+        let currency = DepositFeeCurrency::BaseToken(FeeConversionRoute {
+            router: router.address.0,
+            path: vec![[0x43; 20], base_token],
+            min_amount_out_per_eth: crate::prelude::u256_to_arr(&min_amount_out_per_eth),
+            gas_limit: 1_000_000,
+        });
+        runner
+            .make_call(
+                "set_deposit_fee_currency",
+                aurora.clone(),
+                currency.try_to_vec().unwrap(),
+            )
+            .check_ok();
+    };
+    let deposit = |runner: &mut AuroraRunner| {
+        let message = FtTransferMessageData {
+            relayer: alice.parse().unwrap(),
+            recipient,
+            fee: fee.into(),
+        };
+        let res = runner.ft_on_transfer(
+            aurora.clone(),
+            alice.clone(),
+            alice.clone(),
+            amount,
+            message.encode(),
+        );
+        assert_eq!(res, "\"0\"");
+    };
+    let swap_topic = hex::encode(keccak256(b"Swap(address,uint256,uint256)"));
+    let has_swap_log = |runner: &AuroraRunner| {
+        runner.previous_logs.iter().any(|log| {
+            log.contains("\"evm_log\"")
+                && log.contains(&hex::encode(router.address.0))
+                && log.contains(&swap_topic)
+        })
+    };
+
+    // The router swaps the fee for 3 base tokens per wei, above the minimum of 2
+    set_route(&mut runner, U256::from(2) * U256::exp10(18));
+    deposit(&mut runner);
+    assert!(has_swap_log(&runner));
+    assert_eq!(
+        runner.balance_of(base_token, relayer.0, origin()),
+        U256::from(3 * fee)
+    );
+    assert_eq!(runner.get_balance(relayer), Wei::zero());
+    assert_eq!(runner.get_balance(router.address), Wei::new_u64(fee as u64));
+    assert_eq!(
+        runner.get_balance(recipient.into()),
+        Wei::new_u64(INITIAL_BALANCE.raw().as_u64() + (amount - fee) as u64)
+    );
+
+    // Below the minimum the swap reverts, the fee is paid in ETH
+    set_route(&mut runner, U256::from(4) * U256::exp10(18));
+    deposit(&mut runner);
+    assert!(!has_swap_log(&runner));
+    assert_eq!(
+        runner.balance_of(base_token, relayer.0, origin()),
+        U256::from(3 * fee)
+    );
+    assert_eq!(runner.get_balance(relayer), Wei::new_u64(fee as u64));
+    assert_eq!(runner.get_balance(router.address), Wei::new_u64(fee as u64));
+}
+
 #[test]
 fn test_transfer_erc20_token() {
     let mut runner = AuroraRunner::new();
@@ -422,13 +585,19 @@ mod sim_tests {
         } = test_exit_to_near_common();
 
         // Call exit function on ERC-20; observe ERC-20 burned + NEP-141 transferred
-        exit_to_near(
+        let logs = exit_to_near(
             &ft_owner,
             ft_owner.account_id.as_str(),
             FT_EXIT_AMOUNT,
             &erc20,
             &aurora,
         );
+        // The callback of the exit logs its result and completion
+        assert!(has_engine_log(&logs, "XccResult(address,bool,bytes)"));
+        assert!(has_engine_log(
+            &logs,
+            "ExitCompleted(address,address,uint256,bool)"
+        ));
 
         assert_eq!(
             nep_141_balance_of(ft_owner.account_id.as_str(), &nep_141, &aurora),
@@ -456,7 +625,7 @@ mod sim_tests {
         } = test_exit_to_near_common();
 
         // Call exit on ERC-20; ft_transfer promise fails; expect refund on Aurora;
-        exit_to_near(
+        let logs = exit_to_near(
             &ft_owner,
             // The ft_transfer will fail because this account is not registered with the NEP-141
             "unregistered.near",
@@ -464,6 +633,7 @@ mod sim_tests {
             &erc20,
             &aurora,
         );
+        assert!(has_engine_log(&logs, "XccResult(address,bool,bytes)"));
 
         assert_eq!(
             nep_141_balance_of(ft_owner.account_id.as_str(), &nep_141, &aurora),
@@ -587,12 +757,19 @@ mod sim_tests {
             eth_balance_of(signer_address, &aurora),
             Wei::new_u64(INITIAL_ETH_BALANCE)
         );
-        // If the refund feature is not enabled then there is no refund in the EVM
+        // If the refund feature is not enabled then the ETH is refunded to the contract
+        // which called the exit precompile
         #[cfg(not(feature = "error_refund"))]
-        assert_eq!(
-            eth_balance_of(signer_address, &aurora),
-            Wei::new_u64(INITIAL_ETH_BALANCE - ETH_EXIT_AMOUNT)
-        );
+        {
+            assert_eq!(
+                eth_balance_of(signer_address, &aurora),
+                Wei::new_u64(INITIAL_ETH_BALANCE - ETH_EXIT_AMOUNT)
+            );
+            assert_eq!(
+                eth_balance_of(tester_address, &aurora),
+                Wei::new_u64(ETH_EXIT_AMOUNT)
+            );
+        }
     }
 
     fn test_exit_to_near_eth_common() -> TestExitToNearEthContext {
@@ -709,7 +886,7 @@ mod sim_tests {
         amount: u128,
         erc20: &ERC20,
         aurora: &AuroraAccount,
-    ) {
+    ) -> Vec<String> {
         let input = super::build_input(
             "withdrawToNear(bytes,uint256)",
             &[
@@ -722,15 +899,26 @@ mod sim_tests {
             value: WeiU256::default(),
             input,
         });
-        source
-            .call(
-                aurora.contract.account_id(),
-                "call",
-                &call_args.try_to_vec().unwrap(),
-                near_sdk_sim::DEFAULT_GAS,
-                0,
-            )
-            .assert_success();
+        let outcome = source.call(
+            aurora.contract.account_id(),
+            "call",
+            &call_args.try_to_vec().unwrap(),
+            near_sdk_sim::DEFAULT_GAS,
+            0,
+        );
+        outcome.assert_success();
+        outcome
+            .promise_results()
+            .iter()
+            .flatten()
+            .flat_map(|p| p.logs().clone())
+            .collect()
+    }
+
+    fn has_engine_log(logs: &[String], signature: &str) -> bool {
+        let topic = hex::encode(aurora_engine_sdk::keccak(signature.as_bytes()));
+        logs.iter()
+            .any(|log| log.contains("\"evm_log\"") && log.contains(&topic))
     }
 
     fn transfer_nep_141_to_erc_20(
@@ -901,3 +1089,193 @@ mod sim_tests {
         aurora: AuroraAccount,
     }
 }
+
+#[test]
+fn test_get_balances() {
+    use aurora_engine::parameters::{AccountBalances, GetBalancesCallArgs};
+
+    let mut runner = AuroraRunner::new();
+    let nep141 = "tt.testnet".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let account_id = "alice.near";
+    let address = aurora_engine_sdk::types::near_account_to_evm_address(account_id.as_bytes());
+    runner.create_address(address, INITIAL_BALANCE, INITIAL_NONCE.into());
+    runner.mint(token, address.0, 10, origin());
+
+    let args = GetBalancesCallArgs {
+        account_id: account_id.parse().unwrap(),
+        tokens: vec![nep141.parse().unwrap(), "not-bridged.near".parse().unwrap()],
+    };
+    let result = runner.make_call("get_balances", origin(), args.try_to_vec().unwrap());
+    result.check_ok();
+    let balances = AccountBalances::try_from_slice(&result.value()).unwrap();
+
+    assert_eq!(balances.address, address.0);
+    assert_eq!(balances.neth_balance, 0);
+    assert_eq!(Wei::from(balances.eth_balance), INITIAL_BALANCE);
+    // Tokens which are not bridged are skipped
+    assert_eq!(balances.tokens.len(), 1);
+    let token_balance = &balances.tokens[0];
+    assert_eq!(token_balance.erc20, token);
+    assert_eq!(
+        token_balance
+            .balance
+            .map(|balance| U256::from_big_endian(&balance)),
+        Some(U256::from(10))
+    );
+}
+
+#[test]
+fn test_get_balances_json() {
+    use aurora_engine::parameters::GetBalancesCallArgs;
+
+    let mut runner = AuroraRunner::new();
+    let nep141 = "tt.testnet".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let account_id = "alice.near";
+    let address = aurora_engine_sdk::types::near_account_to_evm_address(account_id.as_bytes());
+    runner.create_address(address, INITIAL_BALANCE, INITIAL_NONCE.into());
+    runner.mint(token, address.0, 10, origin());
+
+    let args = GetBalancesCallArgs {
+        account_id: account_id.parse().unwrap(),
+        tokens: vec![nep141.parse().unwrap()],
+    };
+    let result = runner.make_call("get_balances_json", origin(), args.try_to_vec().unwrap());
+    result.check_ok();
+    let balances: serde_json::Value = serde_json::from_slice(&result.value()).unwrap();
+
+    assert_eq!(balances["address"], hex::encode(address));
+    assert_eq!(
+        balances["eth_balance"],
+        json!({
+            "raw": "1000",
+            "decimals": 18,
+            "formatted": "0.000000000000001 ETH"
+        })
+    );
+    assert_eq!(balances["neth_balance"]["formatted"], "0 ETH");
+    // The token deployed by the engine has the metadata of the template until it is set
+    assert_eq!(
+        balances["tokens"],
+        json!([{
+            "nep141": nep141,
+            "erc20": hex::encode(token),
+            "balance": {
+                "raw": "10",
+                "decimals": 0,
+                "formatted": "10 EMPTY"
+            }
+        }])
+    );
+}
+
+#[test]
+fn test_get_erc20_balance() {
+    use aurora_engine::parameters::GetErc20BalanceArgs;
+
+    let mut runner = AuroraRunner::new();
+    let token = runner.deploy_erc20_token(&"tt.testnet".to_string());
+    let address = runner.create_account().address;
+    let get_erc20_balance = |runner: &mut AuroraRunner, erc20: RawAddress| {
+        let args = GetErc20BalanceArgs { erc20, address };
+        runner.make_call("get_erc20_balance", origin(), args.try_to_vec().unwrap())
+    };
+
+    let result = get_erc20_balance(&mut runner, token);
+    result.check_ok();
+    assert_eq!(U256::from_big_endian(&result.value()), U256::zero());
+
+    // The balance read from the storage of the token is the one of `balanceOf`
+    runner.mint(token, address, 10, origin());
+    let result = get_erc20_balance(&mut runner, token);
+    result.check_ok();
+    assert_eq!(U256::from_big_endian(&result.value()), U256::from(10));
+    assert_eq!(runner.balance_of(token, address, origin()), U256::from(10));
+
+    let result = get_erc20_balance(&mut runner, [0x11; 20]);
+    assert!(result.error.is_some());
+}
+
+#[test]
+fn test_check_token_supply() {
+    use aurora_engine::parameters::{CheckTokenSupplyArgs, SetTokenPausedArgs, TokenSupplyCheck};
+
+    let mut runner = AuroraRunner::new();
+    let nep141 = "tt.testnet".to_string();
+    let alice = "alice".to_string();
+    let token = runner.deploy_erc20_token(&nep141);
+    let recipient = runner.create_account().address;
+    let check_token_supply = |runner: &mut AuroraRunner| {
+        let args = CheckTokenSupplyArgs {
+            token: nep141.parse().unwrap(),
+        };
+        let result = runner.make_call("check_token_supply", origin(), args.try_to_vec().unwrap());
+        result.check_ok();
+        TokenSupplyCheck::try_from_slice(&result.value()).unwrap()
+    };
+
+    let res = runner.ft_on_transfer(
+        nep141.clone(),
+        alice.clone(),
+        alice.clone(),
+        10,
+        hex::encode(recipient),
+    );
+    assert_eq!(res, "\"0\"");
+    let check = check_token_supply(&mut runner);
+    assert_eq!(check.erc20, token);
+    assert_eq!(check.locked, Some(10));
+    assert_eq!(
+        U256::from_big_endian(&check.erc20_total_supply),
+        U256::from(10)
+    );
+    assert!(!check.diverged);
+    assert!(!check.paused);
+
+    // Minted without locking NEP-141 tokens
+    runner.mint(token, recipient, 5, origin());
+    let check = check_token_supply(&mut runner);
+    assert!(check.diverged);
+    assert!(!check.paused);
+
+    // The deposit finding the mismatch is refunded and pauses the token
+    let res = runner.ft_on_transfer(
+        nep141.clone(),
+        alice.clone(),
+        alice.clone(),
+        10,
+        hex::encode(recipient),
+    );
+    assert_eq!(res, "\"10\"");
+    assert!(check_token_supply(&mut runner).paused);
+    assert_eq!(
+        runner.balance_of(token, recipient, origin()),
+        U256::from(15)
+    );
+
+    // Only the owner resumes the token, which is then tracked from the supply of its ERC-20
+    let args = SetTokenPausedArgs {
+        token: nep141.parse().unwrap(),
+        paused: false,
+    }
+    .try_to_vec()
+    .unwrap();
+    let result = runner.make_call("set_token_paused", alice.clone(), args.clone());
+    assert!(result.error.is_some());
+    runner
+        .make_call("set_token_paused", origin(), args)
+        .check_ok();
+    let res = runner.ft_on_transfer(
+        nep141.clone(),
+        alice.clone(),
+        alice,
+        10,
+        hex::encode(recipient),
+    );
+    assert_eq!(res, "\"0\"");
+    let check = check_token_supply(&mut runner);
+    assert_eq!(check.locked, Some(25));
+    assert!(!check.diverged);
+    assert!(!check.paused);
+}
@@ -3,13 +3,14 @@ use crate::prelude::WithdrawCallArgs;
 use crate::test_utils::str_to_account_id;
 use aurora_engine::admin_controlled::{PausedMask, ERR_PAUSED};
 use aurora_engine::connector::{
-    ERR_NOT_ENOUGH_BALANCE_FOR_FEE, PAUSE_DEPOSIT, PAUSE_WITHDRAW, UNPAUSE_ALL,
+    ERR_NOT_ENOUGH_BALANCE_FOR_FEE, PAUSE_DEPOSIT, PAUSE_WITHDRAW, PAUSE_WITHDRAW_ETH, UNPAUSE_ALL,
 };
 use aurora_engine::fungible_token::FungibleTokenMetadata;
 use aurora_engine::parameters::{
-    InitCallArgs, NewCallArgs, RegisterRelayerCallArgs, WithdrawResult,
+    DepositReceipt, InitCallArgs, MintCircuitBreakerArgs, NewCallArgs, ProverConfig,
+    ProverStatistics, ProverStatus, RegisterRelayerCallArgs, SetProversArgs, WithdrawResult,
 };
-use aurora_engine_types::types::Fee;
+use aurora_engine_types::types::{Fee, MAX_MEMO_LEN};
 use borsh::{BorshDeserialize, BorshSerialize};
 use byte_slice_cast::AsByteSlice;
 use ethabi::ethereum_types::U256;
@@ -258,6 +259,496 @@ fn test_deposit_eth_to_near_balance_total_supply() {
     assert_eq!(balance, 0);
 }
 
+#[test]
+fn test_deposit_with_proof_above_default_input_limit() {
+    use aurora_engine::input_limits::{DEFAULT_MAX_INPUT_LEN, MAX_PROOF_INPUT_LEN};
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    // The nodes of the proof are only checked by the prover.
+    let mut proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    proof.proof.push(vec![0; DEFAULT_MAX_INPUT_LEN]);
+    let raw_proof = proof.try_to_vec().unwrap();
+    assert!(raw_proof.len() > DEFAULT_MAX_INPUT_LEN);
+    assert!(raw_proof.len() < MAX_PROOF_INPUT_LEN);
+
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "deposit",
+        &raw_proof,
+        DEFAULT_GAS,
+        0,
+    );
+    res.assert_success();
+    for result in res.promise_results().into_iter().flatten() {
+        result.assert_success();
+    }
+
+    assert_proof_was_used(
+        &contract,
+        CONTRACT_ACC,
+        &serde_json::to_string(&proof).unwrap(),
+    );
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+}
+
+#[test]
+fn test_is_used_proof_key() {
+    use aurora_engine::parameters::IsUsedProofKeyCallArgs;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let key = aurora_engine::proof::Proof::try_from_slice(&proof.try_to_vec().unwrap())
+        .unwrap()
+        .get_key();
+    let is_used_proof_key = || {
+        let res = master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "is_used_proof_key",
+            &IsUsedProofKeyCallArgs { key: key.clone() }
+                .try_to_vec()
+                .unwrap(),
+        );
+        bool::try_from_slice(&res.unwrap()).unwrap()
+    };
+    assert!(!is_used_proof_key());
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert!(is_used_proof_key());
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+}
+
+#[test]
+fn test_check_proofs() {
+    use aurora_engine::parameters::CheckProofsCallArgs;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let key = |data: &str| {
+        let proof: Proof = serde_json::from_str(data).unwrap();
+        aurora_engine::proof::Proof::try_from_slice(&proof.try_to_vec().unwrap())
+            .unwrap()
+            .get_key()
+    };
+    let keys = vec![
+        key(PROOF_DATA_NEAR),
+        "unknown".to_string(),
+        key(PROOF_DATA_ETH),
+    ];
+    let check_proofs = || {
+        let res = master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "check_proofs",
+            &CheckProofsCallArgs { keys: keys.clone() }
+                .try_to_vec()
+                .unwrap(),
+        );
+        Vec::<bool>::try_from_slice(&res.unwrap()).unwrap()
+    };
+    assert_eq!(check_proofs(), vec![false, false, false]);
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_eq!(check_proofs(), vec![true, false, false]);
+
+    call_deposit_eth_to_aurora(&contract, CONTRACT_ACC);
+    assert_eq!(check_proofs(), vec![true, false, true]);
+}
+
+#[test]
+fn test_relayer_earnings() {
+    use aurora_engine::parameters::{
+        GetRelayerEarningsArgs, Page, PageArgs, RelayerEarnings, RelayerEarningsEntry,
+    };
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let get_earnings = |relayer_id: &str| {
+        let res = master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_relayer_earnings",
+            &GetRelayerEarningsArgs {
+                relayer_id: relayer_id.parse().unwrap(),
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Option::<RelayerEarnings>::try_from_slice(&res.unwrap()).unwrap()
+    };
+    assert_eq!(get_earnings(CONTRACT_ACC), None);
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let earnings = RelayerEarnings {
+        near_fees: DEPOSITED_FEE,
+        aurora_fees: 0,
+        deposits: 1,
+    };
+    assert_eq!(get_earnings(CONTRACT_ACC), Some(earnings.clone()));
+    assert_eq!(get_earnings(DEPOSITED_RECIPIENT), None);
+
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "get_relayer_earnings_page",
+        &PageArgs {
+            cursor: None,
+            limit: 10,
+        }
+        .try_to_vec()
+        .unwrap(),
+    );
+    let page = Page::<RelayerEarningsEntry>::try_from_slice(&res.unwrap()).unwrap();
+    assert_eq!(
+        page.items,
+        vec![RelayerEarningsEntry {
+            relayer_id: CONTRACT_ACC.parse().unwrap(),
+            earnings,
+        }]
+    );
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_estimate_deposit_gas() {
+    use aurora_engine::parameters::DepositGasEstimate;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let proof = proof.try_to_vec().unwrap();
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "estimate_deposit_gas",
+        &proof,
+    );
+    let estimate = DepositGasEstimate::try_from_slice(&res.unwrap()).unwrap();
+    assert_eq!(estimate.verify_log_entry, 40_000_000_000_000);
+    assert_eq!(estimate.finish_deposit, 50_000_000_000_000);
+    assert!(estimate.deposit > 10_000_000_000_000);
+    assert_eq!(
+        estimate.total,
+        estimate.deposit
+            + estimate.verify_log_entry
+            + estimate.finish_deposit
+            + estimate.safety_margin
+    );
+
+    // The estimated gas is enough to finish the deposit.
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "deposit",
+        &proof,
+        estimate.total,
+        0,
+    );
+    res.assert_success();
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "estimate_deposit_gas",
+        &[1, 2, 3],
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_deposit_eth_to_near_result() {
+    use aurora_engine::parameters::FinishDepositNearResult;
+
+    let (_master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let promises = call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    // The callback finishing the deposit returns what was minted
+    let result = promises
+        .iter()
+        .find_map(|promise| match promise.as_ref().unwrap().outcome().status {
+            ExecutionStatus::SuccessValue(ref value) => {
+                FinishDepositNearResult::try_from_slice(value).ok()
+            }
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(
+        result,
+        FinishDepositNearResult {
+            recipient_id: DEPOSITED_RECIPIENT.parse().unwrap(),
+            amount: DEPOSITED_AMOUNT - DEPOSITED_FEE,
+            relayer_id: CONTRACT_ACC.parse().unwrap(),
+            fee: DEPOSITED_FEE,
+            protocol_fee: 0,
+        }
+    );
+}
+
+#[test]
+fn test_deposit_batch() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let deposit_batch = |proofs: &[&str]| {
+        let proofs: Vec<Proof> = proofs
+            .iter()
+            .map(|proof| serde_json::from_str(proof).unwrap())
+            .collect();
+        contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "deposit_batch",
+            &proofs.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+
+    let res = deposit_batch(&[]);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_EMPTY_DEPOSIT_BATCH",
+        "Expected failure as the batch is empty",
+    );
+    let res = deposit_batch(&[PROOF_DATA_NEAR, PROOF_DATA_NEAR]);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_DUPLICATE_PROOF",
+        "Expected failure as the same proof is deposited twice",
+    );
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+
+    // A batch fits more than a single proof, but every proof must fit the limit of `deposit`
+    let mut proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    proof
+        .proof
+        .push(vec![0; aurora_engine::input_limits::MAX_PROOF_INPUT_LEN]);
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "deposit_batch",
+        &vec![proof].try_to_vec().unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_PROOF_INPUT_TOO_LARGE",
+        "Expected failure as the proof exceeds the input limit of deposit",
+    );
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+
+    // A deposit to a NEAR account and a deposit to an EVM address settle in a single transaction
+    deposit_batch(&[PROOF_DATA_NEAR, PROOF_DATA_ETH]).assert_success();
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_ETH);
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    // The relayer has no registered EVM address, so the recipient gets the fee as well
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, DEPOSITED_EVM_AMOUNT);
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT + DEPOSITED_EVM_AMOUNT);
+
+    // The proofs are used now, which is checked when the deposit is finished
+    let promises = deposit_batch(&[PROOF_DATA_NEAR]).promise_results();
+    let promise = &promises[promises.len() - 2];
+    assert_execution_status_failure(
+        promise.as_ref().unwrap().outcome().clone().status,
+        "ERR_PROOF_EXIST",
+        "Expected failure as the proof was used already",
+    );
+}
+
+#[test]
+fn test_deposit_protocol_fee_and_waiver() {
+    use aurora_engine::parameters::{
+        IsFeeWaivedCallArgs, SetDepositProtocolFeeArgs, SetFeeWaiverArgs,
+    };
+
+    let init_with_protocol_fee = || {
+        let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+        master_account
+            .call(
+                CONTRACT_ACC.parse().unwrap(),
+                "set_deposit_protocol_fee",
+                &SetDepositProtocolFeeArgs { fee_bps: 100 }
+                    .try_to_vec()
+                    .unwrap(),
+                DEFAULT_GAS,
+                0,
+            )
+            .assert_success();
+        (master_account, contract)
+    };
+    let net_amount = DEPOSITED_AMOUNT - DEPOSITED_FEE;
+
+    // The contract account relays the deposit, it receives the relayer and the protocol fees
+    let (master_account, contract) = init_with_protocol_fee();
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, net_amount - net_amount / 100);
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE + net_amount / 100);
+    assert_eq!(
+        total_eth_supply_on_near(&master_account, CONTRACT_ACC),
+        DEPOSITED_AMOUNT
+    );
+
+    // The deposits of a waived relayer are not charged the protocol fee
+    let (master_account, contract) = init_with_protocol_fee();
+    let set_fee_waiver = |account: &UserAccount| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_fee_waiver",
+            &SetFeeWaiverArgs {
+                relayer_id: CONTRACT_ACC.parse().unwrap(),
+                waived: true,
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let user_account = create_user_account(&master_account);
+    let res = set_fee_waiver(&user_account);
+    assert!(matches!(
+        res.outcome().clone().status,
+        ExecutionStatus::Failure(_)
+    ));
+    let res = set_fee_waiver(&master_account);
+    res.assert_success();
+    assert!(res
+        .logs()
+        .iter()
+        .any(|log| log.contains("fee_waiver_added")));
+    let waived = master_account
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "is_fee_waived",
+            &IsFeeWaivedCallArgs {
+                relayer_id: CONTRACT_ACC.parse().unwrap(),
+            }
+            .try_to_vec()
+            .unwrap(),
+        )
+        .unwrap();
+    assert!(bool::try_from_slice(&waived).unwrap());
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, net_amount);
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE);
+}
+
+#[test]
+fn test_legacy_logs() {
+    use aurora_engine::parameters::SetLegacyLogsArgs;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let is_legacy_logs_enabled = || {
+        let res = contract
+            .view(CONTRACT_ACC.parse().unwrap(), "is_legacy_logs_enabled", &[])
+            .unwrap();
+        bool::try_from_slice(&res).unwrap()
+    };
+    assert!(is_legacy_logs_enabled());
+
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "set_legacy_logs_enabled",
+        &SetLegacyLogsArgs { enabled: false }.try_to_vec().unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    res.assert_success();
+    assert!(!is_legacy_logs_enabled());
+
+    // Only the event is emitted for the minted tokens
+    let promises = call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let logs: Vec<String> = promises
+        .iter()
+        .flatten()
+        .flat_map(|p| p.logs().clone())
+        .collect();
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("\"event\": \"ft_mint\"")));
+    assert!(!logs.iter().any(|log| log.contains("nETH tokens for")));
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+}
+
+#[test]
+fn test_deposit_receipt_and_mint_event() {
+    let (_master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let get_deposit_receipt = || {
+        let res = contract
+            .view(
+                CONTRACT_ACC.parse().unwrap(),
+                "get_deposit_receipt",
+                &proof.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        Option::<DepositReceipt>::try_from_slice(&res).unwrap()
+    };
+    assert!(get_deposit_receipt().is_none());
+
+    let promises = call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let mint_event = format!(
+        "EVENT_JSON:{{\"data\": [{{\"amount\": \"{}\", \"owner_id\": \"{}\"}}, {{\"amount\": \"{}\", \"owner_id\": \"{}\"}}], \"event\": \"ft_mint\", \"standard\": \"nep141\", \"version\": \"1.0.0\"}}",
+        DEPOSITED_AMOUNT - DEPOSITED_FEE,
+        DEPOSITED_RECIPIENT,
+        DEPOSITED_FEE,
+        CONTRACT_ACC,
+    );
+    assert!(promises
+        .iter()
+        .flatten()
+        .any(|p| p.logs().contains(&mint_event)));
+
+    assert_eq!(
+        get_deposit_receipt(),
+        Some(DepositReceipt {
+            recipient_id: str_to_account_id(DEPOSITED_RECIPIENT),
+            amount: DEPOSITED_AMOUNT,
+            fee: DEPOSITED_FEE.into(),
+            protocol_fee: 0,
+            memo: None,
+        })
+    );
+}
+
+#[test]
+fn test_deposits_by_sender() {
+    use aurora_engine::parameters::{DepositReceiptEntry, GetDepositsBySenderCallArgs};
+
+    let (_master_account, contract) = init(CUSTODIAN_ADDRESS);
+    // The indexed sender of the Deposited event of the proof
+    let sender = validate_eth_address("79183fdbd80e2d8aea1acaa2f67bfb8a36d40a8d");
+    let get_deposits_by_sender = |sender, limit| {
+        let res = contract
+            .view(
+                CONTRACT_ACC.parse().unwrap(),
+                "get_deposits_by_sender",
+                &GetDepositsBySenderCallArgs { sender, limit }
+                    .try_to_vec()
+                    .unwrap(),
+            )
+            .unwrap();
+        Vec::<DepositReceiptEntry>::try_from_slice(&res).unwrap()
+    };
+    assert!(get_deposits_by_sender(sender, 10).is_empty());
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let proof = aurora_engine::proof::Proof::try_from_slice(&proof.try_to_vec().unwrap()).unwrap();
+    let deposits = get_deposits_by_sender(sender, 10);
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].proof_key, proof.get_key());
+    assert_eq!(deposits[0].receipt.amount, DEPOSITED_AMOUNT);
+    assert!(get_deposits_by_sender(sender, 0).is_empty());
+    assert!(get_deposits_by_sender([1u8; 20], 10).is_empty());
+}
+
 #[test]
 fn test_deposit_eth_to_aurora_balance_total_supply() {
     let (master_account, contract) = init(EVM_CUSTODIAN_ADDRESS);
@@ -321,6 +812,14 @@ fn test_withdraw_eth_from_near() {
         1,
     );
     res.assert_success();
+    let withdraw_event = format!(
+        "EVENT_JSON:{{\"data\": [{{\"amount\": \"{}\", \"eth_custodian_address\": \"{}\", \"recipient_address\": \"{}\", \"sender_id\": \"{}\"}}], \"event\": \"withdraw\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+        withdraw_amount,
+        CUSTODIAN_ADDRESS.to_lowercase(),
+        RECIPIENT_ETH_ADDRESS.to_lowercase(),
+        CONTRACT_ACC,
+    );
+    assert!(res.logs().contains(&withdraw_event));
     let data = res.promise_results();
     assert!(data.len() > 1);
     assert!(data[0].is_some());
@@ -345,6 +844,111 @@ fn test_withdraw_eth_from_near() {
     assert_eq!(balance, DEPOSITED_AMOUNT - withdraw_amount as u128);
 }
 
+#[test]
+fn test_estimate_withdraw() {
+    use aurora_engine::parameters::{EstimateWithdrawArgs, WithdrawEstimate};
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let recipient_addr = validate_eth_address(RECIPIENT_ETH_ADDRESS);
+    let estimate_withdraw = |account_id: &str, amount: u128| {
+        let args = EstimateWithdrawArgs {
+            account_id: account_id.parse().unwrap(),
+            recipient_address: recipient_addr,
+            amount,
+        };
+        master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "estimate_withdraw",
+            &args.try_to_vec().unwrap(),
+        )
+    };
+
+    let res = estimate_withdraw(DEPOSITED_RECIPIENT, 100);
+    let estimate = WithdrawEstimate::try_from_slice(&res.unwrap()).unwrap();
+    assert_eq!(estimate.fee, 0);
+    assert_eq!(estimate.net_amount, 100);
+    assert_eq!(estimate.result.amount, 100);
+    assert_eq!(estimate.result.recipient_id, recipient_addr);
+    assert_eq!(
+        estimate.result.eth_custodian_address,
+        validate_eth_address(CUSTODIAN_ADDRESS)
+    );
+    // Nothing is burned by the estimate.
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+
+    assert!(estimate_withdraw(DEPOSITED_RECIPIENT, DEPOSITED_AMOUNT).is_err());
+
+    // The withdrawals of the users are paused, not the ones of the engine itself.
+    call_set_paused_flags(&contract, CONTRACT_ACC, PAUSE_WITHDRAW).assert_success();
+    assert!(estimate_withdraw(DEPOSITED_RECIPIENT, 100).is_err());
+    assert!(estimate_withdraw(CONTRACT_ACC, DEPOSITED_FEE).is_ok());
+}
+
+#[test]
+fn test_ft_balances_of() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    call_deposit_eth_to_aurora(&contract, CONTRACT_ACC);
+
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_balances_of",
+        json!({ "account_ids": [DEPOSITED_RECIPIENT, CONTRACT_ACC, "unknown.root"] })
+            .to_string()
+            .as_bytes(),
+    );
+    let balances: serde_json::Value = serde_json::from_slice(&res.unwrap()).unwrap();
+    let recipient_balance =
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    let relayer_balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(
+        balances,
+        json!({
+            DEPOSITED_RECIPIENT: recipient_balance.to_string(),
+            CONTRACT_ACC: relayer_balance.to_string(),
+            "unknown.root": "0",
+        })
+    );
+
+    // The addresses are accepted with or without `0x`
+    let recipient = format!("0x{}", RECIPIENT_ETH_ADDRESS);
+    let zero_address = "00".repeat(20);
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_balances_of_eth",
+        json!({ "addresses": [recipient, zero_address] })
+            .to_string()
+            .as_bytes(),
+    );
+    let balances: serde_json::Value = serde_json::from_slice(&res.unwrap()).unwrap();
+    let recipient_balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(
+        balances,
+        json!({
+            RECIPIENT_ETH_ADDRESS: recipient_balance.to_string(),
+            zero_address: "0",
+        })
+    );
+
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_balances_of_eth",
+        json!({ "addresses": ["not an address"] })
+            .to_string()
+            .as_bytes(),
+    );
+    assert!(res.is_err());
+}
+
 #[test]
 fn test_ft_transfer() {
     let (master_account, contract) = init(CUSTODIAN_ADDRESS);
@@ -372,29 +976,188 @@ fn test_ft_transfer() {
         DEPOSITED_AMOUNT - DEPOSITED_FEE + transfer_amount as u128
     );
 
-    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_FEE - transfer_amount as u128);
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE - transfer_amount as u128);
+
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+
+    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+
+    let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT);
+}
+
+#[test]
+fn test_ft_transfer_oversized_memo() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_transfer",
+        json!({
+            "receiver_id": DEPOSITED_RECIPIENT,
+            "amount": "70",
+            "memo": "m".repeat(MAX_MEMO_LEN + 1)
+        })
+        .to_string()
+        .as_bytes(),
+        DEFAULT_GAS,
+        1,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_MEMO_TOO_LONG",
+        "Expected failure as the memo is too long, but transfer succeeded",
+    );
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+}
+
+#[test]
+fn test_ft_transfer_call_eth() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE);
+
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "register_relayer",
+        &RegisterRelayerCallArgs {
+            address: validate_eth_address(CUSTODIAN_ADDRESS),
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    res.assert_success();
+
+    let transfer_amount = 50;
+    let fee: u128 = 30;
+    let mut msg = U256::from(fee).as_byte_slice().to_vec();
+    msg.append(&mut validate_eth_address(RECIPIENT_ETH_ADDRESS).to_vec());
+
+    let message = [CONTRACT_ACC, hex::encode(msg).as_str()].join(":");
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_transfer_call",
+        json!({
+            "receiver_id": CONTRACT_ACC,
+            "amount": transfer_amount.to_string(),
+            "msg": message,
+        })
+        .to_string()
+        .as_bytes(),
+        DEFAULT_GAS,
+        1,
+    );
+    res.assert_success();
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE);
+
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, transfer_amount - fee);
+
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(CUSTODIAN_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, fee);
 
     let balance = total_supply(&master_account, CONTRACT_ACC);
     assert_eq!(balance, DEPOSITED_AMOUNT);
 
-    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, 0);
-
     let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
     assert_eq!(balance, DEPOSITED_AMOUNT);
+
+    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, transfer_amount);
 }
 
 #[test]
-fn test_ft_transfer_call_eth() {
+fn test_deposit_fee_currency() {
+    use aurora_engine::parameters::{DepositFeeCurrency, FeeConversionRoute};
+
     let (master_account, contract) = init(CUSTODIAN_ADDRESS);
     call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let set_deposit_fee_currency = |account: &UserAccount, currency: &DepositFeeCurrency| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_deposit_fee_currency",
+            &currency.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
 
-    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    let res = master_account
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_deposit_fee_currency",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        DepositFeeCurrency::try_from_slice(&res[..]).unwrap(),
+        DepositFeeCurrency::Eth
+    );
 
-    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_FEE);
+    // The router has no code, so the swap of the fee fails
+    let route = FeeConversionRoute {
+        router: [0x42; 20],
+        path: vec![[0x43; 20], [0x44; 20]],
+        min_amount_out_per_eth: crate::prelude::u256_to_arr(&crate::prelude::U256::from(1_000)),
+        gas_limit: 1_000_000,
+    };
+    let currency = DepositFeeCurrency::BaseToken(route.clone());
+    let res = set_deposit_fee_currency(&contract, &currency);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NOT_ALLOWED",
+        "Expected failure as only the owner can set the deposit fee currency",
+    );
+    let res = set_deposit_fee_currency(
+        &master_account,
+        &DepositFeeCurrency::BaseToken(FeeConversionRoute {
+            path: vec![[0x44; 20]],
+            ..route
+        }),
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_INVALID_FEE_CONVERSION_ROUTE",
+        "Expected failure as the path has no base token",
+    );
+    set_deposit_fee_currency(&master_account, &currency).assert_success();
+    let res = master_account
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_deposit_fee_currency",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        DepositFeeCurrency::try_from_slice(&res[..]).unwrap(),
+        currency
+    );
 
     let res = contract.call(
         CONTRACT_ACC.parse().unwrap(),
@@ -413,7 +1176,6 @@ fn test_ft_transfer_call_eth() {
     let fee: u128 = 30;
     let mut msg = U256::from(fee).as_byte_slice().to_vec();
     msg.append(&mut validate_eth_address(RECIPIENT_ETH_ADDRESS).to_vec());
-
     let message = [CONTRACT_ACC, hex::encode(msg).as_str()].join(":");
     let res = contract.call(
         CONTRACT_ACC.parse().unwrap(),
@@ -430,34 +1192,131 @@ fn test_ft_transfer_call_eth() {
     );
     res.assert_success();
 
-    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
-
-    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_FEE);
-
+    // The fee is paid in ETH instead, none is left to the engine
     let balance = get_eth_balance(
         &master_account,
         validate_eth_address(RECIPIENT_ETH_ADDRESS),
         CONTRACT_ACC,
     );
     assert_eq!(balance, transfer_amount - fee);
-
     let balance = get_eth_balance(
         &master_account,
         validate_eth_address(CUSTODIAN_ADDRESS),
         CONTRACT_ACC,
     );
     assert_eq!(balance, fee);
+    let engine_address =
+        aurora_engine_sdk::types::near_account_to_evm_address(CONTRACT_ACC.as_bytes());
+    let balance = get_eth_balance(&master_account, engine_address.0, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, transfer_amount);
+}
 
-    let balance = total_supply(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_AMOUNT);
+#[test]
+fn test_ft_transfer_call_gas_for_on_transfer() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
 
-    let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, DEPOSITED_AMOUNT);
+    let transfer_amount = 50;
+    let message = format!("0x{}", RECIPIENT_ETH_ADDRESS);
+    let transfer_call = |gas_for_on_transfer: u64, prepaid_gas: u64| {
+        contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "ft_transfer_call",
+            json!({
+                "receiver_id": CONTRACT_ACC,
+                "amount": transfer_amount.to_string(),
+                "msg": message,
+                "gas_for_on_transfer": gas_for_on_transfer,
+            })
+            .to_string()
+            .as_bytes(),
+            prepaid_gas,
+            1,
+        )
+    };
+
+    let res = transfer_call(250_000_000_000_000, DEFAULT_GAS);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_GAS_FOR_ON_TRANSFER_TOO_HIGH",
+        "Expected failure as the gas is above the maximum",
+    );
+    let res = transfer_call(80_000_000_000_000, 100_000_000_000_000);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NOT_ENOUGH_GAS",
+        "Expected failure as the prepaid gas doesn't cover the gas of the receiver",
+    );
+    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+
+    let res = transfer_call(50_000_000_000_000, DEFAULT_GAS);
+    res.assert_success();
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, transfer_amount);
+}
+
+#[test]
+fn test_ft_transfer_call_to_eth_address() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let transfer_amount = 50;
+    // Only the recipient address, the whole amount is credited
+    let message = format!("0x{}", RECIPIENT_ETH_ADDRESS);
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_transfer_call",
+        json!({
+            "receiver_id": CONTRACT_ACC,
+            "amount": transfer_amount.to_string(),
+            "msg": message,
+        })
+        .to_string()
+        .as_bytes(),
+        DEFAULT_GAS,
+        1,
+    );
+    res.assert_success();
+
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_FEE);
+
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, transfer_amount);
 
     let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
     assert_eq!(balance, transfer_amount);
+
+    // An address of the wrong length is rejected
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_transfer_call",
+        json!({
+            "receiver_id": CONTRACT_ACC,
+            "amount": transfer_amount.to_string(),
+            "msg": &RECIPIENT_ETH_ADDRESS[2..],
+        })
+        .to_string()
+        .as_bytes(),
+        DEFAULT_GAS,
+        1,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_INVALID_ON_TRANSFER_MESSAGE_DATA",
+        "Expected failure as the address is too short",
+    );
 }
 
 #[test]
@@ -620,6 +1479,8 @@ fn test_deposit_with_0x_prefix() {
         token_message_data,
         amount: deposit_amount,
         fee,
+        memo: None,
+        metadata: None,
     };
 
     let event_schema = ethabi::Event {
@@ -704,6 +1565,132 @@ fn test_deposit_wrong_custodian_address() {
     assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
 }
 
+#[test]
+fn test_deposit_extra_custodian() {
+    use aurora_engine::parameters::CustodianArgs;
+
+    let previous_custodian_address = "0000000000000000000000000000000000000001";
+    let (master_account, contract) = init(previous_custodian_address);
+    let call_custodian = |method: &str, address: &str| {
+        master_account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            method,
+            &CustodianArgs {
+                address: address.to_string(),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let get_custodians = || {
+        let res = master_account.view(CONTRACT_ACC.parse().unwrap(), "get_custodians", &[]);
+        Vec::<EthAddress>::try_from_slice(&res.unwrap()).unwrap()
+    };
+
+    call_custodian("add_custodian", CUSTODIAN_ADDRESS).assert_success();
+    assert_eq!(
+        get_custodians(),
+        vec![
+            validate_eth_address(previous_custodian_address),
+            validate_eth_address(CUSTODIAN_ADDRESS)
+        ]
+    );
+    assert_execution_status_failure(
+        call_custodian("add_custodian", CUSTODIAN_ADDRESS)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_ALREADY_CUSTODIAN",
+        "Expected failure as the custodian was already added",
+    );
+    assert_execution_status_failure(
+        call_custodian("remove_custodian", previous_custodian_address)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_PRIMARY_CUSTODIAN",
+        "Expected failure as the custodian of the contract data can't be removed",
+    );
+
+    // The deposits of the extra custodian are accepted
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+
+    call_custodian("remove_custodian", CUSTODIAN_ADDRESS).assert_success();
+    assert_eq!(
+        get_custodians(),
+        vec![validate_eth_address(previous_custodian_address)]
+    );
+    assert_execution_status_failure(
+        call_custodian("remove_custodian", CUSTODIAN_ADDRESS)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_CUSTODIAN_NOT_FOUND",
+        "Expected failure as the custodian was removed",
+    );
+}
+
+#[test]
+fn test_deposit_proven_against_light_client_block_hash() {
+    use aurora_engine::parameters::{AddLightClientBlockHashArgs, SetLightClientArgs};
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let add_block_hash = |account: &UserAccount| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "add_light_client_block_hash",
+            &AddLightClientBlockHashArgs {
+                block_hash: aurora_engine_sdk::keccak(&proof.header_data).0,
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+
+    master_account
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_light_client",
+            &SetLightClientArgs {
+                account_id: Some(master_account.account_id.as_str().parse().unwrap()),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    assert_execution_status_failure(
+        add_block_hash(&contract).outcome().clone().status,
+        "ERR_NOT_LIGHT_CLIENT",
+        "Expected failure as the block hashes are only provided by the light client",
+    );
+    add_block_hash(&master_account).assert_success();
+
+    // The deposit is finished without a call to the prover
+    let promises = call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    for p in promises.iter() {
+        let p = p.as_ref().unwrap();
+        p.assert_success();
+        assert_ne!(p.outcome().executor_id.as_str(), accounts(0).as_str());
+    }
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+}
+
 #[test]
 fn test_ft_transfer_call_without_relayer() {
     let (master_account, contract) = init(CUSTODIAN_ADDRESS);
@@ -971,34 +1958,185 @@ fn test_deposit_pausability() {
         let p = p.as_ref().unwrap();
         p.assert_success()
     }
-
-    // Pause deposit
-    let res = call_set_paused_flags(&contract, CONTRACT_ACC, PAUSE_DEPOSIT);
-    res.assert_success();
+
+    // Pause deposit
+    let res = call_set_paused_flags(&contract, CONTRACT_ACC, PAUSE_DEPOSIT);
+    res.assert_success();
+
+    // 2nd deposit call - should fail
+    // NB: We can use `PROOF_DATA_ETH` this will be just a different proof but the same deposit
+    // method which should be paused
+    let promises = call_deposit_with_proof(&user_account, CONTRACT_ACC, PROOF_DATA_ETH);
+    let num_promises = promises.len();
+    let p = promises[num_promises - 2].clone();
+    assert_execution_status_failure(
+        p.unwrap().outcome().clone().status,
+        ERR_PAUSED,
+        "Expected failure due to pause, but deposit succeeded",
+    );
+
+    // Unpause all
+    let res = call_set_paused_flags(&contract, CONTRACT_ACC, UNPAUSE_ALL);
+    res.assert_success();
+
+    // 3rd deposit call - should succeed
+    let promises = call_deposit_with_proof(&user_account, CONTRACT_ACC, PROOF_DATA_ETH);
+    for p in promises.iter() {
+        assert!(p.is_some());
+        let p = p.as_ref().unwrap();
+        p.assert_success()
+    }
+}
+
+#[test]
+fn test_withdraw_pausability() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+    call_deposit_eth_to_near(&user_account, CONTRACT_ACC);
+
+    let withdraw = |account: &UserAccount| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "withdraw",
+            &WithdrawCallArgs {
+                recipient_address: validate_eth_address(RECIPIENT_ETH_ADDRESS),
+                amount: 100,
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            1,
+        )
+    };
+
+    // The owner of the engine can pause as well
+    let res = call_set_paused_flags(&master_account, CONTRACT_ACC, PAUSE_WITHDRAW_ETH);
+    res.assert_success();
+
+    // Withdrawals of the ETH exited from the EVM are done by the engine itself
+    let res = withdraw(&contract);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        ERR_PAUSED,
+        "Expected failure due to pause, but the withdrawal of ETH succeeded",
+    );
+    // The withdrawals of nETH are not paused
+    withdraw(&user_account).assert_success();
+
+    let res = call_set_paused_flags(&master_account, CONTRACT_ACC, PAUSE_WITHDRAW);
+    res.assert_success();
+    let res = withdraw(&user_account);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        ERR_PAUSED,
+        "Expected failure due to pause, but the withdrawal of nETH succeeded",
+    );
+}
+
+#[test]
+fn test_mint_circuit_breaker_pauses_deposit() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "set_mint_circuit_breaker",
+        &MintCircuitBreakerArgs {
+            window_blocks: 1_000,
+            max_net_mint: DEPOSITED_AMOUNT - 1,
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    res.assert_success();
+
+    // 1st deposit call - should succeed and trip the circuit breaker
+    let promises = call_deposit_with_proof(&user_account, CONTRACT_ACC, PROOF_DATA_NEAR);
+    for p in promises.iter() {
+        assert!(p.is_some());
+        let p = p.as_ref().unwrap();
+        p.assert_success()
+    }
+    let paused_mask = contract
+        .view(CONTRACT_ACC.parse().unwrap(), "get_paused_flags", &[])
+        .unwrap();
+    assert_eq!(
+        PausedMask::try_from_slice(&paused_mask).unwrap(),
+        PAUSE_DEPOSIT
+    );
 
     // 2nd deposit call - should fail
-    // NB: We can use `PROOF_DATA_ETH` this will be just a different proof but the same deposit
-    // method which should be paused
     let promises = call_deposit_with_proof(&user_account, CONTRACT_ACC, PROOF_DATA_ETH);
     let num_promises = promises.len();
     let p = promises[num_promises - 2].clone();
     assert_execution_status_failure(
         p.unwrap().outcome().clone().status,
         ERR_PAUSED,
-        "Expected failure due to pause, but deposit succeeded",
+        "Expected failure due to the circuit breaker, but deposit succeeded",
     );
+}
 
-    // Unpause all
-    let res = call_set_paused_flags(&contract, CONTRACT_ACC, UNPAUSE_ALL);
-    res.assert_success();
+#[test]
+fn test_deposit_falls_back_to_next_prover() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let set_provers = |provers: Vec<ProverConfig>| {
+        master_account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_provers",
+            &SetProversArgs { provers }.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let prover = |account_id: &str, enabled| ProverConfig {
+        account_id: str_to_account_id(account_id),
+        enabled,
+    };
 
-    // 3rd deposit call - should succeed
-    let promises = call_deposit_with_proof(&user_account, CONTRACT_ACC, PROOF_DATA_ETH);
-    for p in promises.iter() {
-        assert!(p.is_some());
-        let p = p.as_ref().unwrap();
-        p.assert_success()
-    }
+    let res = set_provers(vec![prover("prover.root", false)]);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NO_ENABLED_PROVER",
+        "Expected failure as no prover is enabled",
+    );
+
+    // The first prover doesn't exist, so its verification always fails
+    set_provers(vec![
+        prover("prover.root", true),
+        prover("disabled.root", false),
+        prover(PROVER_ACCOUNT, true),
+    ])
+    .assert_success();
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+
+    let provers = contract
+        .view(CONTRACT_ACC.parse().unwrap(), "get_provers", &[])
+        .unwrap();
+    let statistics: Vec<ProverStatistics> = Vec::<ProverStatus>::try_from_slice(&provers)
+        .unwrap()
+        .into_iter()
+        .map(|status| status.statistics)
+        .collect();
+    assert_eq!(
+        statistics,
+        vec![
+            ProverStatistics {
+                verified: 0,
+                rejected: 1
+            },
+            ProverStatistics::default(),
+            ProverStatistics {
+                verified: 1,
+                rejected: 0
+            },
+        ]
+    );
 }
 
 #[test]
@@ -1094,6 +2232,186 @@ fn test_get_accounts_counter() {
     assert_eq!(u64::try_from_slice(&counter[..]).unwrap(), 2);
 }
 
+#[test]
+fn test_get_balance_storage_layout() {
+    use aurora_engine::fungible_token::account_balance_key;
+    use aurora_engine::parameters::{BalanceOfCallArgs, BalanceStorageLayout};
+
+    let (master_account, _contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&master_account, CONTRACT_ACC);
+
+    let get_layout = |account_id: &str| {
+        let args = BalanceOfCallArgs {
+            account_id: account_id.parse().unwrap(),
+        };
+        let layout = master_account
+            .view(
+                CONTRACT_ACC.parse().unwrap(),
+                "get_balance_storage_layout",
+                &args.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        BalanceStorageLayout::try_from_slice(&layout).unwrap()
+    };
+
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(
+        get_layout(DEPOSITED_RECIPIENT),
+        BalanceStorageLayout {
+            storage_key: account_balance_key(&DEPOSITED_RECIPIENT.parse().unwrap()),
+            value: Some(balance.try_to_vec().unwrap()),
+        }
+    );
+    assert_eq!(get_layout("unknown.root").value, None);
+}
+
+#[test]
+fn test_enumeration_views() {
+    use aurora_engine::pagination::Cursor;
+    use aurora_engine::parameters::{AccountBalance, DepositReceiptEntry, Page, PageArgs};
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let user_account = create_user_account(&master_account);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let page_args =
+        |cursor: Option<Cursor>, limit: u32| PageArgs { cursor, limit }.try_to_vec().unwrap();
+    let get_page = |method: &str, cursor: Option<Cursor>, limit: u32| {
+        master_account
+            .view(
+                CONTRACT_ACC.parse().unwrap(),
+                method,
+                &page_args(cursor, limit),
+            )
+            .unwrap()
+    };
+
+    // The recipient of the deposit is registered before the relayer
+    let page =
+        Page::<AccountBalance>::try_from_slice(&get_page("get_accounts_page", None, 1)).unwrap();
+    assert_eq!(
+        page.items,
+        vec![AccountBalance {
+            account_id: DEPOSITED_RECIPIENT.parse().unwrap(),
+            balance: DEPOSITED_AMOUNT - DEPOSITED_FEE,
+        }]
+    );
+    let accounts_cursor = page.next_cursor.unwrap();
+    let page = Page::<AccountBalance>::try_from_slice(&get_page(
+        "get_accounts_page",
+        Some(accounts_cursor.clone()),
+        10,
+    ))
+    .unwrap();
+    assert_eq!(
+        page.items,
+        vec![AccountBalance {
+            account_id: CONTRACT_ACC.parse().unwrap(),
+            balance: DEPOSITED_FEE,
+        }]
+    );
+    assert_eq!(page.next_cursor, None);
+
+    let page = Page::<DepositReceiptEntry>::try_from_slice(&get_page(
+        "get_deposit_receipts_page",
+        None,
+        10,
+    ))
+    .unwrap();
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let proof = aurora_engine::proof::Proof::try_from_slice(&proof.try_to_vec().unwrap()).unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].proof_key, proof.get_key());
+    assert_eq!(
+        page.items[0].receipt.recipient_id.to_string(),
+        DEPOSITED_RECIPIENT
+    );
+
+    let res = user_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "withdraw",
+        &WithdrawCallArgs {
+            recipient_address: validate_eth_address(RECIPIENT_ETH_ADDRESS),
+            amount: 100,
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        1,
+    );
+    res.assert_success();
+    let page = Page::<WithdrawResult>::try_from_slice(&get_page("get_withdrawals_page", None, 10))
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].amount, 100);
+    assert_eq!(
+        page.items[0].recipient_id,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS)
+    );
+
+    // A cursor is only valid for the collection it was returned for
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "get_withdrawals_page",
+        &page_args(Some(accounts_cursor), 10),
+        DEFAULT_GAS,
+        0,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_INVALID_CURSOR",
+        "Expected failure as the cursor was returned for the accounts",
+    );
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "get_accounts_page",
+        &page_args(None, 0),
+        DEFAULT_GAS,
+        0,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_INVALID_PAGE_LIMIT",
+        "Expected failure as the page is empty",
+    );
+}
+
+#[test]
+fn test_migrate() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+
+    let migrate = |account: &UserAccount| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "migrate",
+            &[],
+            DEFAULT_GAS,
+            0,
+        )
+    };
+
+    let res = migrate(&master_account);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_PRIVATE_CALL",
+        "Expected failure as only the contract itself can migrate",
+    );
+
+    // The state written by `new_eth_connector` is already current
+    let res = migrate(&contract);
+    res.assert_success();
+    assert_eq!(res.unwrap_borsh::<u32>(), 1);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+    assert_eq!(
+        total_eth_supply_on_near(&master_account, CONTRACT_ACC),
+        DEPOSITED_AMOUNT
+    );
+}
+
 #[test]
 fn test_get_accounts_counter_and_transfer() {
     let (master_account, contract) = init(CUSTODIAN_ADDRESS);
@@ -1144,6 +2462,141 @@ fn test_get_accounts_counter_and_transfer() {
     assert_eq!(u64::try_from_slice(&counter[..]).unwrap(), 2);
 }
 
+#[test]
+fn test_storage_unregister() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let storage_unregister = |force: bool| {
+        contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "storage_unregister",
+            json!({ "force": force }).to_string().as_bytes(),
+            DEFAULT_GAS,
+            1,
+        )
+    };
+
+    // The account still holds the deposit fee
+    let res = storage_unregister(false);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_FAILED_UNREGISTER_ACCOUNT_POSITIVE_BALANCE",
+        "Expected failure as the account holds nETH, but unregister succeeded",
+    );
+
+    let res = storage_unregister(true);
+    res.assert_success();
+    assert_eq!(res.unwrap_json_value().to_string(), "true");
+
+    // The nETH of the account is burned
+    let balance = get_eth_on_near_balance(&master_account, CONTRACT_ACC, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+    let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    let counter = master_account
+        .view(CONTRACT_ACC.parse().unwrap(), "get_accounts_counter", &[])
+        .unwrap();
+    assert_eq!(u64::try_from_slice(&counter[..]).unwrap(), 1);
+
+    // Nothing left to unregister
+    let res = storage_unregister(true);
+    res.assert_success();
+    assert_eq!(res.unwrap_json_value().to_string(), "false");
+}
+
+#[test]
+fn test_storage_balance_of_implicit_registration() {
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    // The pool is empty and not required, the recipient of the deposit is registered for free
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+
+    let res = master_account
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "storage_balance_of",
+            json!({ "account_id": DEPOSITED_RECIPIENT })
+                .to_string()
+                .as_bytes(),
+        )
+        .unwrap();
+    let storage_balance: serde_json::Value = serde_json::from_slice(&res[..]).unwrap();
+    // Nothing was paid for its storage, so nothing is refunded when it is unregistered.
+    assert_eq!(storage_balance["total"], "0");
+}
+
+#[test]
+fn test_storage_pool() {
+    use aurora_engine::parameters::StorageConfig;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let get_storage_pool = || {
+        let res = master_account
+            .view(CONTRACT_ACC.parse().unwrap(), "get_storage_pool", &[])
+            .unwrap();
+        u128::try_from_slice(&res[..]).unwrap()
+    };
+    let set_storage_config = |account: &UserAccount, config: &StorageConfig| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_storage_config",
+            &config.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+
+    let res = master_account
+        .view(CONTRACT_ACC.parse().unwrap(), "get_storage_config", &[])
+        .unwrap();
+    let config = StorageConfig::try_from_slice(&res[..]).unwrap();
+    assert_eq!(config.min_balance, None);
+    assert!(!config.pool_required);
+    assert_eq!(get_storage_pool(), 0);
+
+    let config = StorageConfig {
+        min_balance: None,
+        pool_required: true,
+    };
+    let res = set_storage_config(&contract, &config);
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NOT_ALLOWED",
+        "Expected failure as only the owner can set the storage config",
+    );
+    set_storage_config(&master_account, &config).assert_success();
+
+    // The pool is empty, the recipient of the deposit can't be registered
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, 0);
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+
+    let funded = to_yocto("1");
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "fund_storage_pool",
+        &[],
+        DEFAULT_GAS,
+        funded,
+    );
+    res.assert_success();
+    assert_eq!(get_storage_pool(), funded);
+
+    let res = master_account
+        .view(CONTRACT_ACC.parse().unwrap(), "storage_balance_bounds", &[])
+        .unwrap();
+    let bounds: serde_json::Value = serde_json::from_slice(&res[..]).unwrap();
+    let min_balance: u128 = bounds["min"].as_str().unwrap().parse().unwrap();
+
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let balance = get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC);
+    assert_eq!(balance, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    assert_proof_was_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
+    assert!(get_storage_pool() <= funded - min_balance);
+}
+
 #[test]
 fn test_deposit_to_near_with_zero_fee() {
     let (master_account, contract) = init(CUSTODIAN_ADDRESS);
@@ -1192,45 +2645,307 @@ fn test_deposit_to_aurora_with_zero_fee() {
         DEFAULT_GAS,
         0,
     );
-    res.assert_success();
+    res.assert_success();
+
+    let proof_str = r#"{"log_index":0,"log_entry_data":[249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0],"receipt_index":3,"receipt_data":[249,2,41,1,131,2,246,200,185,1,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,32,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,249,1,30,249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0],"header_data":[249,2,23,160,110,48,40,236,52,198,197,25,255,191,199,4,137,3,185,31,202,84,90,80,104,32,176,13,144,141,165,183,36,30,94,138,160,29,204,77,232,222,199,93,122,171,133,181,103,182,204,212,26,211,18,69,27,148,138,116,19,240,161,66,253,64,212,147,71,148,148,156,193,169,167,156,148,249,191,22,225,202,121,212,79,2,197,75,191,164,160,127,26,168,212,111,22,173,213,25,217,187,227,114,86,173,99,166,195,67,16,104,111,200,109,110,147,241,23,71,122,89,215,160,47,120,179,75,110,158,228,18,242,156,38,111,95,25,236,211,158,53,53,62,89,190,2,40,220,41,151,200,127,219,33,219,160,222,177,165,249,98,109,130,37,226,229,165,113,45,12,145,30,16,28,154,86,22,203,218,233,13,246,165,177,61,57,68,83,185,1,0,0,32,8,0,33,0,0,0,64,0,32,0,128,0,0,0,132,0,0,0,64,32,64,0,0,1,0,32,64,0,0,8,0,0,0,0,0,0,137,32,0,0,0,64,128,0,0,16,0,0,0,0,33,64,0,1,0,0,0,0,0,0,0,0,68,0,0,0,2,1,64,0,0,0,0,9,16,0,0,32,0,0,0,128,2,0,0,0,33,0,0,0,128,0,0,0,12,64,32,8,66,2,0,0,64,0,0,8,0,0,40,8,8,0,0,0,0,16,0,0,0,0,64,49,0,0,8,0,96,0,0,18,0,0,0,0,0,64,10,0,1,0,0,32,0,0,0,33,0,0,128,136,10,64,0,64,0,0,192,128,0,0,64,1,0,0,4,0,8,0,64,0,34,0,0,0,0,0,0,0,0,0,0,0,8,8,0,4,0,0,0,32,0,4,0,2,0,0,0,129,4,0,96,16,4,8,0,0,0,0,0,0,1,0,128,16,0,0,2,0,4,0,32,0,8,0,0,0,0,16,0,1,0,0,0,0,64,0,128,0,0,32,36,128,0,0,4,64,0,8,8,16,0,1,4,16,132,50,32,156,229,131,157,92,137,131,122,18,0,131,35,159,183,132,96,174,111,126,153,216,131,1,10,3,132,103,101,116,104,136,103,111,49,46,49,54,46,51,133,108,105,110,117,120,160,59,74,90,253,211,14,166,114,39,213,120,95,221,43,109,173,72,205,160,203,71,44,83,159,36,59,129,84,32,16,254,251,136,49,16,97,244,161,246,244,85],"proof":[[248,113,160,227,103,29,228,16,56,196,146,115,29,122,202,254,140,214,86,189,108,47,197,2,195,50,211,4,126,58,175,71,11,70,78,160,229,239,23,242,100,150,90,169,21,162,252,207,202,244,187,71,172,126,191,33,166,162,45,134,108,114,6,76,78,177,148,140,128,128,128,128,128,128,160,21,91,249,81,132,162,52,236,128,181,5,72,158,228,177,131,87,144,64,194,111,103,180,16,183,103,245,136,125,213,208,76,128,128,128,128,128,128,128,128],[249,1,241,128,160,52,154,34,8,39,210,121,1,151,92,91,225,198,154,204,207,11,204,187,59,223,154,187,102,115,110,193,141,201,198,95,253,160,218,19,188,241,210,48,51,3,76,125,48,152,171,188,45,136,109,71,236,171,242,162,10,34,245,160,191,5,120,9,80,129,160,147,160,142,184,113,171,112,171,131,124,150,117,65,27,207,149,119,136,120,65,7,99,155,114,169,57,91,125,26,117,49,67,160,173,217,104,114,149,170,18,227,251,73,78,11,220,243,240,66,117,32,199,64,138,173,169,43,8,122,39,47,210,54,41,192,160,139,116,124,73,113,242,225,65,167,48,33,13,149,51,152,196,79,93,126,103,116,48,177,25,80,186,34,55,15,116,2,13,160,67,10,207,13,108,228,254,73,175,10,166,107,144,157,150,135,173,179,140,112,129,205,168,132,194,4,191,175,239,50,66,245,160,26,193,195,232,40,106,60,72,133,32,204,205,104,90,20,60,166,16,214,184,115,44,216,62,82,30,141,124,160,72,173,62,160,67,5,174,33,105,28,248,245,48,15,129,153,96,27,97,125,29,194,233,139,228,8,243,221,79,2,151,52,75,30,47,136,160,103,94,192,58,117,224,88,80,21,183,254,178,135,21,78,20,233,250,7,22,243,14,41,56,12,118,206,224,75,42,96,77,160,225,64,237,254,248,145,134,195,166,49,205,129,233,54,142,136,235,242,10,14,175,76,73,131,26,135,102,237,64,23,102,213,160,167,104,45,101,228,93,89,216,167,142,125,0,216,77,167,4,245,156,140,98,117,19,165,25,185,204,84,161,175,153,193,20,160,53,22,192,197,176,225,102,6,251,115,216,238,53,110,254,106,193,134,232,100,173,93,211,71,195,10,192,107,97,190,165,12,160,104,206,244,51,77,131,79,209,64,233,97,35,142,75,42,205,198,120,222,90,199,168,126,235,12,225,30,240,214,56,253,168,160,230,94,127,56,22,169,3,159,236,49,217,88,2,175,168,22,104,177,154,127,106,165,176,238,236,141,83,64,123,28,177,206,160,140,137,2,195,227,9,182,245,76,62,215,174,168,254,15,125,111,241,30,50,110,189,66,58,230,2,252,104,182,247,223,94,128],[249,2,48,32,185,2,44,249,2,41,1,131,2,246,200,185,1,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,32,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,249,1,30,249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0]]}"#;
+    let proof: Proof = serde_json::from_str(proof_str).unwrap();
+    let res = master_account.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "deposit",
+        &proof.try_to_vec().unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    res.assert_success();
+
+    assert_proof_was_used(&contract, CONTRACT_ACC, proof_str);
+
+    let deposited_amount = 2000;
+
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(RECIPIENT_ETH_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, deposited_amount);
+
+    let balance = get_eth_balance(
+        &master_account,
+        validate_eth_address(CUSTODIAN_ADDRESS),
+        CONTRACT_ACC,
+    );
+    assert_eq!(balance, 0);
+
+    let balance = total_supply(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, deposited_amount);
+
+    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, deposited_amount);
+
+    let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
+    assert_eq!(balance, deposited_amount);
+}
+
+#[test]
+fn test_proof_cache() {
+    use aurora_engine::parameters::ProofCacheConfig;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let set_ttl_blocks = |ttl_blocks| {
+        master_account
+            .call(
+                CONTRACT_ACC.parse().unwrap(),
+                "set_proof_cache_config",
+                &ProofCacheConfig { ttl_blocks }.try_to_vec().unwrap(),
+                DEFAULT_GAS,
+                0,
+            )
+            .assert_success();
+    };
+    let deposit = || {
+        let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+        contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "deposit",
+            &proof.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let cache_log = "Deposit proof receipt found in the verified proof cache".to_string();
+
+    set_ttl_blocks(1_000);
+    let res = deposit();
+    assert!(!res.logs().contains(&cache_log));
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+
+    // The receipt is verified, so the deposit is finished without calling the prover, where
+    // the proof is found used.
+    let res = deposit();
+    assert!(res.logs().contains(&cache_log));
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_PROOF_EXIST",
+        "Expected failure as the proof was used already",
+    );
+
+    // Without the cache, the prover is called again
+    set_ttl_blocks(0);
+    let res = deposit();
+    assert!(!res.logs().contains(&cache_log));
+    let promise = &res.promise_results()[res.promise_results().len() - 2];
+    assert_execution_status_failure(
+        promise.as_ref().unwrap().outcome().clone().status,
+        "ERR_PROOF_EXIST",
+        "Expected failure as the proof was used already",
+    );
+}
+
+#[test]
+fn test_recipient_rules() {
+    use aurora_engine::parameters::RecipientRules;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let set_rules = |account: &UserAccount, allowed: &[&str], denied: &[&str]| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_recipient_rules",
+            &RecipientRules {
+                allowed_suffixes: allowed.iter().map(|s| s.to_string()).collect(),
+                denied_suffixes: denied.iter().map(|s| s.to_string()).collect(),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let deposit = || {
+        let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+        let res = contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "deposit",
+            &proof.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        );
+        res.promise_results()[res.promise_results().len() - 2]
+            .as_ref()
+            .unwrap()
+            .outcome()
+            .clone()
+            .status
+    };
+
+    let user_account = create_user_account(&master_account);
+    assert!(matches!(
+        set_rules(&user_account, &[], &["root"])
+            .outcome()
+            .clone()
+            .status,
+        ExecutionStatus::Failure(_)
+    ));
+
+    // The recipient of the proof is `eth_recipient.root`
+    set_rules(&master_account, &[], &["root"]).assert_success();
+    assert_execution_status_failure(
+        deposit(),
+        "ERR_RECIPIENT_DENIED",
+        "Expected failure as the recipient is denied",
+    );
+    set_rules(&master_account, &["near"], &[]).assert_success();
+    assert_execution_status_failure(
+        deposit(),
+        "ERR_RECIPIENT_NOT_ALLOWED",
+        "Expected failure as the recipient is not allowed",
+    );
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
 
-    let proof_str = r#"{"log_index":0,"log_entry_data":[249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0],"receipt_index":3,"receipt_data":[249,2,41,1,131,2,246,200,185,1,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,32,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,249,1,30,249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0],"header_data":[249,2,23,160,110,48,40,236,52,198,197,25,255,191,199,4,137,3,185,31,202,84,90,80,104,32,176,13,144,141,165,183,36,30,94,138,160,29,204,77,232,222,199,93,122,171,133,181,103,182,204,212,26,211,18,69,27,148,138,116,19,240,161,66,253,64,212,147,71,148,148,156,193,169,167,156,148,249,191,22,225,202,121,212,79,2,197,75,191,164,160,127,26,168,212,111,22,173,213,25,217,187,227,114,86,173,99,166,195,67,16,104,111,200,109,110,147,241,23,71,122,89,215,160,47,120,179,75,110,158,228,18,242,156,38,111,95,25,236,211,158,53,53,62,89,190,2,40,220,41,151,200,127,219,33,219,160,222,177,165,249,98,109,130,37,226,229,165,113,45,12,145,30,16,28,154,86,22,203,218,233,13,246,165,177,61,57,68,83,185,1,0,0,32,8,0,33,0,0,0,64,0,32,0,128,0,0,0,132,0,0,0,64,32,64,0,0,1,0,32,64,0,0,8,0,0,0,0,0,0,137,32,0,0,0,64,128,0,0,16,0,0,0,0,33,64,0,1,0,0,0,0,0,0,0,0,68,0,0,0,2,1,64,0,0,0,0,9,16,0,0,32,0,0,0,128,2,0,0,0,33,0,0,0,128,0,0,0,12,64,32,8,66,2,0,0,64,0,0,8,0,0,40,8,8,0,0,0,0,16,0,0,0,0,64,49,0,0,8,0,96,0,0,18,0,0,0,0,0,64,10,0,1,0,0,32,0,0,0,33,0,0,128,136,10,64,0,64,0,0,192,128,0,0,64,1,0,0,4,0,8,0,64,0,34,0,0,0,0,0,0,0,0,0,0,0,8,8,0,4,0,0,0,32,0,4,0,2,0,0,0,129,4,0,96,16,4,8,0,0,0,0,0,0,1,0,128,16,0,0,2,0,4,0,32,0,8,0,0,0,0,16,0,1,0,0,0,0,64,0,128,0,0,32,36,128,0,0,4,64,0,8,8,16,0,1,4,16,132,50,32,156,229,131,157,92,137,131,122,18,0,131,35,159,183,132,96,174,111,126,153,216,131,1,10,3,132,103,101,116,104,136,103,111,49,46,49,54,46,51,133,108,105,110,117,120,160,59,74,90,253,211,14,166,114,39,213,120,95,221,43,109,173,72,205,160,203,71,44,83,159,36,59,129,84,32,16,254,251,136,49,16,97,244,161,246,244,85],"proof":[[248,113,160,227,103,29,228,16,56,196,146,115,29,122,202,254,140,214,86,189,108,47,197,2,195,50,211,4,126,58,175,71,11,70,78,160,229,239,23,242,100,150,90,169,21,162,252,207,202,244,187,71,172,126,191,33,166,162,45,134,108,114,6,76,78,177,148,140,128,128,128,128,128,128,160,21,91,249,81,132,162,52,236,128,181,5,72,158,228,177,131,87,144,64,194,111,103,180,16,183,103,245,136,125,213,208,76,128,128,128,128,128,128,128,128],[249,1,241,128,160,52,154,34,8,39,210,121,1,151,92,91,225,198,154,204,207,11,204,187,59,223,154,187,102,115,110,193,141,201,198,95,253,160,218,19,188,241,210,48,51,3,76,125,48,152,171,188,45,136,109,71,236,171,242,162,10,34,245,160,191,5,120,9,80,129,160,147,160,142,184,113,171,112,171,131,124,150,117,65,27,207,149,119,136,120,65,7,99,155,114,169,57,91,125,26,117,49,67,160,173,217,104,114,149,170,18,227,251,73,78,11,220,243,240,66,117,32,199,64,138,173,169,43,8,122,39,47,210,54,41,192,160,139,116,124,73,113,242,225,65,167,48,33,13,149,51,152,196,79,93,126,103,116,48,177,25,80,186,34,55,15,116,2,13,160,67,10,207,13,108,228,254,73,175,10,166,107,144,157,150,135,173,179,140,112,129,205,168,132,194,4,191,175,239,50,66,245,160,26,193,195,232,40,106,60,72,133,32,204,205,104,90,20,60,166,16,214,184,115,44,216,62,82,30,141,124,160,72,173,62,160,67,5,174,33,105,28,248,245,48,15,129,153,96,27,97,125,29,194,233,139,228,8,243,221,79,2,151,52,75,30,47,136,160,103,94,192,58,117,224,88,80,21,183,254,178,135,21,78,20,233,250,7,22,243,14,41,56,12,118,206,224,75,42,96,77,160,225,64,237,254,248,145,134,195,166,49,205,129,233,54,142,136,235,242,10,14,175,76,73,131,26,135,102,237,64,23,102,213,160,167,104,45,101,228,93,89,216,167,142,125,0,216,77,167,4,245,156,140,98,117,19,165,25,185,204,84,161,175,153,193,20,160,53,22,192,197,176,225,102,6,251,115,216,238,53,110,254,106,193,134,232,100,173,93,211,71,195,10,192,107,97,190,165,12,160,104,206,244,51,77,131,79,209,64,233,97,35,142,75,42,205,198,120,222,90,199,168,126,235,12,225,30,240,214,56,253,168,160,230,94,127,56,22,169,3,159,236,49,217,88,2,175,168,22,104,177,154,127,106,165,176,238,236,141,83,64,123,28,177,206,160,140,137,2,195,227,9,182,245,76,62,215,174,168,254,15,125,111,241,30,50,110,189,66,58,230,2,252,104,182,247,223,94,128],[249,2,48,32,185,2,44,249,2,41,1,131,2,246,200,185,1,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,32,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,8,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,128,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,249,1,30,249,1,27,148,9,109,233,194,184,165,184,194,44,238,50,137,177,1,246,150,13,104,229,30,248,66,160,209,66,67,156,39,142,37,218,217,165,7,102,241,83,208,227,210,215,191,43,209,111,194,120,28,75,212,148,178,177,90,157,160,0,0,0,0,0,0,0,0,0,0,0,0,121,24,63,219,216,14,45,138,234,26,202,162,246,123,251,138,54,212,10,141,184,192,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,96,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,7,208,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,59,101,116,104,95,99,111,110,110,101,99,116,111,114,46,114,111,111,116,58,56,57,49,66,50,55,52,57,50,51,56,66,50,55,102,70,53,56,101,57,53,49,48,56,56,101,53,53,98,48,52,100,101,55,49,68,99,51,55,52,0,0,0,0,0]]}"#;
-    let proof: Proof = serde_json::from_str(proof_str).unwrap();
-    let res = master_account.call(
-        CONTRACT_ACC.parse().unwrap(),
-        "deposit",
-        &proof.try_to_vec().unwrap(),
-        DEFAULT_GAS,
-        0,
+    // A suffix only matches whole account id parts
+    set_rules(&master_account, &["root"], &["t.root", "recipient.root"]).assert_success();
+    let rules = master_account
+        .view(CONTRACT_ACC.parse().unwrap(), "get_recipient_rules", &[])
+        .unwrap();
+    assert_eq!(
+        RecipientRules::try_from_slice(&rules)
+            .unwrap()
+            .denied_suffixes,
+        vec!["t.root".to_string(), "recipient.root".to_string()]
     );
-    res.assert_success();
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+}
 
-    assert_proof_was_used(&contract, CONTRACT_ACC, proof_str);
+#[test]
+fn test_min_deposit() {
+    use aurora_engine::parameters::MinDepositArgs;
 
-    let deposited_amount = 2000;
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let set_min_deposit = |min_amount, min_net_amount| {
+        master_account
+            .call(
+                CONTRACT_ACC.parse().unwrap(),
+                "set_min_deposit",
+                &MinDepositArgs {
+                    min_amount,
+                    min_net_amount,
+                }
+                .try_to_vec()
+                .unwrap(),
+                DEFAULT_GAS,
+                0,
+            )
+            .assert_success();
+    };
+    let deposit = || {
+        let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+        let res = contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "deposit",
+            &proof.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        );
+        res.promise_results()[res.promise_results().len() - 2]
+            .as_ref()
+            .unwrap()
+            .outcome()
+            .clone()
+            .status
+    };
 
-    let balance = get_eth_balance(
-        &master_account,
-        validate_eth_address(RECIPIENT_ETH_ADDRESS),
-        CONTRACT_ACC,
+    set_min_deposit(DEPOSITED_AMOUNT + 1, 0);
+    assert_execution_status_failure(
+        deposit(),
+        "ERR_BELOW_MIN_DEPOSIT",
+        "Expected failure as the deposited amount is below the minimum deposit",
     );
-    assert_eq!(balance, deposited_amount);
 
-    let balance = get_eth_balance(
-        &master_account,
-        validate_eth_address(CUSTODIAN_ADDRESS),
-        CONTRACT_ACC,
+    // The amount net of the fee has to exceed the minimum net amount
+    set_min_deposit(DEPOSITED_AMOUNT, DEPOSITED_AMOUNT - DEPOSITED_FEE);
+    assert_execution_status_failure(
+        deposit(),
+        ERR_NOT_ENOUGH_BALANCE_FOR_FEE,
+        "Expected failure as the deposited amount net of the fee is below the minimum",
     );
-    assert_eq!(balance, 0);
+    assert_proof_was_not_used(&contract, CONTRACT_ACC, PROOF_DATA_NEAR);
 
-    let balance = total_supply(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, deposited_amount);
+    set_min_deposit(DEPOSITED_AMOUNT, DEPOSITED_AMOUNT - DEPOSITED_FEE - 1);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+}
 
-    let balance = total_eth_supply_on_aurora(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, deposited_amount);
+#[test]
+fn test_set_eth_connector_contract_data() {
+    use aurora_engine::connector::EthConnector;
 
-    let balance = total_eth_supply_on_near(&master_account, CONTRACT_ACC);
-    assert_eq!(balance, deposited_amount);
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let new_custodian_address = "73c8931CA2aD746d97a59A7ABDDa0a9205F7ffF9";
+    let set_contract_data = |account: &UserAccount, eth_custodian_address: &str| {
+        account.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_eth_connector_contract_data",
+            &InitCallArgs {
+                prover_account: str_to_account_id("new-prover.near"),
+                eth_custodian_address: eth_custodian_address.to_string(),
+                metadata: FungibleTokenMetadata::default(),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+
+    let user_account = create_user_account(&master_account);
+    assert_execution_status_failure(
+        set_contract_data(&user_account, new_custodian_address)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_NOT_ALLOWED",
+        "Expected failure as only the owner can set the contract data",
+    );
+    assert_execution_status_failure(
+        set_contract_data(&master_account, &"0".repeat(40))
+            .outcome()
+            .clone()
+            .status,
+        "ERR_ZERO_CUSTODIAN_ADDRESS",
+        "Expected failure as the custodian address is zero",
+    );
+
+    let res = set_contract_data(&master_account, new_custodian_address);
+    res.assert_success();
+    assert!(res
+        .logs()
+        .iter()
+        .any(|log| log.contains("\"event\": \"eth_connector_contract_data_changed\"")));
+
+    let res = contract
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_eth_connector_contract_data",
+            &[],
+        )
+        .unwrap();
+    let contract_data = Option::<EthConnector>::try_from_slice(&res)
+        .unwrap()
+        .unwrap();
+    assert_eq!(contract_data.prover_account.as_ref(), "new-prover.near");
+    assert_eq!(
+        contract_data.eth_custodian_address,
+        validate_eth_address(new_custodian_address)
+    );
 }
 
 #[test]
@@ -1478,6 +3193,144 @@ fn test_ft_transfer_wrong_u128_json_type() {
     );
 }
 
+#[test]
+fn test_deposit_erc20() {
+    use aurora_engine::deposit_event::{LockedEvent, LOCKED_EVENT};
+    use aurora_engine::parameters::{
+        GetBridgedNep141Args, RegisterErc20TokenArgs, SetErc20LockerArgs,
+    };
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let locker_address = [0x11u8; 20];
+    let token = [0x22u8; 20];
+    let nep141 = "token.root";
+
+    let event_schema = ethabi::Event {
+        name: LOCKED_EVENT.into(),
+        inputs: LockedEvent::event_params(),
+        anonymous: false,
+    };
+    let mut token_topic = [0u8; 32];
+    token_topic[12..].copy_from_slice(&token);
+    let log_entry = aurora_engine::log_entry::LogEntry {
+        address: locker_address.into(),
+        topics: vec![
+            event_schema.signature(),
+            crate::prelude::H256(token_topic),
+            // the sender is not important
+            crate::prelude::H256::zero(),
+        ],
+        data: ethabi::encode(&[
+            ethabi::Token::Uint(U256::from(1_000)),
+            ethabi::Token::String(DEPOSITED_RECIPIENT.into()),
+        ]),
+    };
+    let proof = Proof {
+        log_index: 1,
+        log_entry_data: rlp::encode(&log_entry).to_vec(),
+        receipt_index: 1,
+        receipt_data: Vec::new(),
+        header_data: Vec::new(),
+        proof: Vec::new(),
+    };
+    let deposit_erc20 = || {
+        contract.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "deposit_erc20",
+            &proof.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let is_used_proof = || {
+        let res = contract.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "is_used_proof",
+            &proof.try_to_vec().unwrap(),
+        );
+        IsUsedProofResult::try_from_slice(&res.unwrap())
+            .unwrap()
+            .is_used_proof
+    };
+
+    // The deposits are only accepted from the ERC-20 locker
+    let res = deposit_erc20();
+    let promises = res.promise_results();
+    assert_execution_status_failure(
+        promises[promises.len() - 2]
+            .as_ref()
+            .unwrap()
+            .outcome()
+            .clone()
+            .status,
+        "ERR_WRONG_LOCKER_ADDRESS",
+        "Expected failure as the ERC-20 locker is not set",
+    );
+    master_account
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_erc20_locker",
+            &SetErc20LockerArgs { locker_address }.try_to_vec().unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+
+    let res = deposit_erc20();
+    let promises = res.promise_results();
+    assert_execution_status_failure(
+        promises[promises.len() - 2]
+            .as_ref()
+            .unwrap()
+            .outcome()
+            .clone()
+            .status,
+        "ERR_UNKNOWN_ERC20_TOKEN",
+        "Expected failure as the ERC-20 token is not registered",
+    );
+    master_account
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "register_erc20_token",
+            &RegisterErc20TokenArgs {
+                token,
+                nep141: Some(nep141.parse().unwrap()),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    let res = master_account.view(
+        CONTRACT_ACC.parse().unwrap(),
+        "get_bridged_nep141",
+        &GetBridgedNep141Args { token }.try_to_vec().unwrap(),
+    );
+    let bridged: Option<aurora_engine_types::account_id::AccountId> =
+        BorshDeserialize::try_from_slice(&res.unwrap()).unwrap();
+    assert_eq!(bridged.unwrap().to_string(), nep141);
+
+    // The proof is recorded before the NEP-141 token is minted, the mint itself fails as
+    // there is no such token in the simulator.
+    assert!(!is_used_proof());
+    deposit_erc20();
+    assert!(is_used_proof());
+
+    let res = deposit_erc20();
+    let promises = res.promise_results();
+    assert_execution_status_failure(
+        promises[promises.len() - 2]
+            .as_ref()
+            .unwrap()
+            .outcome()
+            .clone()
+            .status,
+        "ERR_PROOF_EXIST",
+        "Expected failure as the proof was already used",
+    );
+}
+
 /// Bytes for a NEAR smart contract implementing `ft_on_transfer`
 fn dummy_ft_receiver_bytes() -> Vec<u8> {
     let base_path = std::path::Path::new("../etc").join("ft-receiver");
@@ -1485,3 +3338,244 @@ fn dummy_ft_receiver_bytes() -> Vec<u8> {
     crate::test_utils::rust::compile(base_path);
     std::fs::read(output_path).unwrap()
 }
+
+#[test]
+fn test_reassign_unclaimed_deposit() {
+    use aurora_engine::parameters::{
+        GetUnclaimedDepositArgs, ReassignUnclaimedArgs, SetUnclaimedDepositArgs, UnclaimedDeposit,
+    };
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let deposit_id = proof.get_key();
+    let new_recipient = "new_recipient.root".parse().unwrap();
+
+    let set_unclaimed = |caller: &UserAccount, deposit_id: &str, flagged| {
+        caller.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_unclaimed_deposit",
+            &SetUnclaimedDepositArgs {
+                deposit_id: deposit_id.to_string(),
+                flagged,
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let reassign = |caller: &UserAccount| {
+        caller.call(
+            CONTRACT_ACC.parse().unwrap(),
+            "reassign_unclaimed",
+            &ReassignUnclaimedArgs {
+                deposit_id: deposit_id.clone(),
+                new_recipient: new_recipient.clone(),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+    };
+    let get_unclaimed = || {
+        let res = master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_unclaimed_deposit",
+            &GetUnclaimedDepositArgs {
+                deposit_id: deposit_id.clone(),
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Option::<UnclaimedDeposit>::try_from_slice(&res.unwrap()).unwrap()
+    };
+
+    let user_acc = create_user_account(&master_account);
+    assert_execution_status_failure(
+        set_unclaimed(&user_acc, &deposit_id, true)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_NOT_ALLOWED",
+        "Expected failure as only the owner can flag a deposit",
+    );
+    assert_execution_status_failure(
+        set_unclaimed(&master_account, "unknown", true)
+            .outcome()
+            .clone()
+            .status,
+        "ERR_DEPOSIT_NOT_FOUND",
+        "Expected failure as the deposit doesn't exist",
+    );
+    assert_execution_status_failure(
+        reassign(&master_account).outcome().clone().status,
+        "ERR_DEPOSIT_NOT_FLAGGED",
+        "Expected failure as the deposit isn't flagged",
+    );
+
+    let res = set_unclaimed(&master_account, &deposit_id, true);
+    res.assert_success();
+    assert!(res
+        .logs()
+        .iter()
+        .any(|log| log.contains("\"event\": \"unclaimed_deposit_flagged\"")));
+    let unclaimed = get_unclaimed().unwrap();
+    assert_eq!(unclaimed.reassigned_to, None);
+
+    // The recipient has `UNCLAIMED_REASSIGN_DELAY_BLOCKS` blocks to claim the deposit.
+    assert_execution_status_failure(
+        reassign(&master_account).outcome().clone().status,
+        "ERR_REASSIGN_DELAY_NOT_ELAPSED",
+        "Expected failure as the delay didn't elapse",
+    );
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+
+    set_unclaimed(&master_account, &deposit_id, false).assert_success();
+    assert!(get_unclaimed().is_none());
+}
+
+#[test]
+fn test_deposit_and_transfer_events() {
+    let (_master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let proof: Proof = serde_json::from_str(PROOF_DATA_NEAR).unwrap();
+    let logs: Vec<String> = call_deposit_eth_to_near(&contract, CONTRACT_ACC)
+        .into_iter()
+        .flatten()
+        .flat_map(|result| result.logs().clone())
+        .collect();
+    let deposit_event = logs
+        .iter()
+        .find(|log| log.contains("\"event\": \"deposit\""))
+        .unwrap();
+    assert!(deposit_event.contains(&format!("\"proof_key\": \"{}\"", proof.get_key())));
+    assert!(deposit_event.contains(&format!("\"recipient_id\": \"{}\"", DEPOSITED_RECIPIENT)));
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("\"event\": \"ft_mint\"")));
+
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_transfer",
+        json!({
+            "receiver_id": DEPOSITED_RECIPIENT,
+            "amount": "70",
+            "memo": "transfer memo"
+        })
+        .to_string()
+        .as_bytes(),
+        DEFAULT_GAS,
+        1,
+    );
+    res.assert_success();
+    assert!(res.logs().contains(&format!(
+        "EVENT_JSON:{{\"data\": [{{\"amount\": \"70\", \"memo\": \"transfer memo\", \"new_owner_id\": \"{}\", \"old_owner_id\": \"{}\"}}], \"event\": \"ft_transfer\", \"standard\": \"nep141\", \"version\": \"1.0.0\"}}",
+        DEPOSITED_RECIPIENT, CONTRACT_ACC
+    )));
+}
+
+#[test]
+fn test_callback_tickets() {
+    use aurora_engine::parameters::{ResolveTransferCallArgs, SetCallbackTicketsRequiredArgs};
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let args = SetCallbackTicketsRequiredArgs { required: true }
+        .try_to_vec()
+        .unwrap();
+    let user_acc = create_user_account(&master_account);
+    let res = user_acc.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "set_callback_tickets_required",
+        &args,
+        DEFAULT_GAS,
+        0,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_NOT_ALLOWED",
+        "Expected failure as only the owner can require the tickets",
+    );
+    master_account
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "set_callback_tickets_required",
+            &args,
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    let required = master_account
+        .view(
+            CONTRACT_ACC.parse().unwrap(),
+            "is_callback_tickets_required",
+            &[],
+        )
+        .unwrap();
+    assert!(bool::try_from_slice(&required).unwrap());
+
+    // The callbacks scheduled by the engine carry their tickets.
+    call_deposit_eth_to_near(&contract, CONTRACT_ACC);
+    assert_eq!(
+        get_eth_on_near_balance(&master_account, DEPOSITED_RECIPIENT, CONTRACT_ACC),
+        DEPOSITED_AMOUNT - DEPOSITED_FEE
+    );
+
+    // A callback called directly has no ticket, even by the engine itself.
+    let res = contract.call(
+        CONTRACT_ACC.parse().unwrap(),
+        "ft_resolve_transfer",
+        &ResolveTransferCallArgs {
+            sender_id: DEPOSITED_RECIPIENT.parse().unwrap(),
+            amount: 10,
+            receiver_id: CONTRACT_ACC.parse().unwrap(),
+        }
+        .try_to_vec()
+        .unwrap(),
+        DEFAULT_GAS,
+        0,
+    );
+    assert_execution_status_failure(
+        res.outcome().clone().status,
+        "ERR_MISSING_CB_TICKET",
+        "Expected failure as the callback wasn't scheduled by the engine",
+    );
+}
+
+#[test]
+fn test_get_relayer() {
+    use aurora_engine::parameters::GetRelayerArgs;
+
+    let (master_account, contract) = init(CUSTODIAN_ADDRESS);
+    let get_relayer = || {
+        let res = master_account.view(
+            CONTRACT_ACC.parse().unwrap(),
+            "get_relayer",
+            &GetRelayerArgs {
+                account_id: CONTRACT_ACC.parse().unwrap(),
+            }
+            .try_to_vec()
+            .unwrap(),
+        );
+        Option::<EthAddress>::try_from_slice(&res.unwrap()).unwrap()
+    };
+    assert_eq!(get_relayer(), None);
+
+    contract
+        .call(
+            CONTRACT_ACC.parse().unwrap(),
+            "register_relayer",
+            &RegisterRelayerCallArgs {
+                address: validate_eth_address(CUSTODIAN_ADDRESS),
+            }
+            .try_to_vec()
+            .unwrap(),
+            DEFAULT_GAS,
+            0,
+        )
+        .assert_success();
+    assert_eq!(get_relayer(), Some(validate_eth_address(CUSTODIAN_ADDRESS)));
+}
@@ -0,0 +1,287 @@
+//! Runner for the multi-step scenarios of `src/tests/res/fixtures`.
+//!
+//! A fixture is a JSON file describing a scenario crossing the connector and the engine, as a
+//! list of steps executed in order. Every fixture is executed on the wasm contract, and each
+//! transaction is replayed in the standalone engine which must end up in the same state, so
+//! that the same files cover both runtimes. The format only refers to the public methods of
+//! the contract, any harness able to sign Ethereum transactions can execute them.
+//!
+//! ```json
+//! {
+//!   "accounts": { "alice": "0x<secret key>" },
+//!   "steps": [
+//!     { "deposit": { "address": "alice", "amount": "1000" } },
+//!     { "submit": { "from": "alice", "nonce": "0", "to": "0x<address>", "value": "10", "data": "0x" } },
+//!     { "exit_to_near": { "from": "alice", "nonce": "1", "recipient": "alice.near", "amount": "10" } },
+//!     { "exit_to_ethereum": { "from": "alice", "nonce": "2", "recipient": "alice", "amount": "10" } },
+//!     { "expect_account": { "address": "alice", "balance": "970", "nonce": "3" } }
+//!   ]
+//! }
+//! ```
+//!
+//! Addresses are either hex strings or names of `accounts`, whose address is derived from the
+//! secret key. Amounts and nonces are decimal strings. A transaction step expected to be
+//! rejected by the engine sets `expect_error` to the error message, and one executed with a
+//! status other than success sets `expect_status` to its name, e.g. `OutOfFund`; otherwise the
+//! transaction must succeed. The gas price of the transactions is zero, the balances are exact.
+//!
+//! The test runtime can't execute the promises of the contract, so a deposit step credits the
+//! address as a finished deposit would, without a proof, and the NEP-141 transfers scheduled
+//! by the exit steps are not executed.
+use crate::prelude::{Address, Wei, U256};
+use crate::test_utils::{self, AuroraRunner};
+use aurora_engine::parameters::{SubmitResult, TransactionStatus};
+use aurora_engine::transaction::legacy::TransactionLegacy;
+use aurora_engine_precompiles::native::{ExitToEthereum, ExitToNear};
+use borsh::BorshDeserialize;
+use secp256k1::SecretKey;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const FIXTURES_DIR: &str = "src/tests/res/fixtures";
+
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    accounts: BTreeMap<String, String>,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Step {
+    Deposit(DepositStep),
+    Submit(SubmitStep),
+    ExitToNear(ExitToNearStep),
+    ExitToEthereum(ExitToEthereumStep),
+    ExpectAccount(ExpectAccountStep),
+}
+
+#[derive(Debug, Deserialize)]
+struct DepositStep {
+    address: String,
+    amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitStep {
+    from: String,
+    nonce: String,
+    to: Option<String>,
+    value: Option<String>,
+    data: Option<String>,
+    #[serde(flatten)]
+    expect: Expect,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExitToNearStep {
+    from: String,
+    nonce: String,
+    recipient: String,
+    amount: String,
+    #[serde(flatten)]
+    expect: Expect,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExitToEthereumStep {
+    from: String,
+    nonce: String,
+    recipient: String,
+    amount: String,
+    #[serde(flatten)]
+    expect: Expect,
+}
+
+/// Outcome of a transaction step, a successful execution when neither is set.
+#[derive(Debug, Deserialize)]
+struct Expect {
+    /// Error message the engine rejects the transaction with.
+    expect_error: Option<String>,
+    /// Name of the status of an executed transaction, e.g. `Revert` or `OutOfFund`.
+    expect_status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExpectAccountStep {
+    address: String,
+    balance: Option<String>,
+    nonce: Option<String>,
+}
+
+#[test]
+fn test_fixtures() {
+    let mut files = Vec::new();
+    collect_fixture_files(Path::new(FIXTURES_DIR), &mut files);
+    files.sort();
+    assert!(!files.is_empty(), "No fixtures found in {}", FIXTURES_DIR);
+
+    for file in files {
+        let contents = std::fs::read_to_string(&file).unwrap();
+        let fixture: Fixture = serde_json::from_str(&contents)
+            .unwrap_or_else(|e| panic!("Failed to parse {}: {}", file.display(), e));
+        run_fixture(&fixture, &file);
+    }
+}
+
+fn collect_fixture_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_fixture_files(&path, files);
+        } else if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+}
+
+fn run_fixture(fixture: &Fixture, file: &Path) {
+    // The standalone runner is kept, every transaction is replayed and its state validated.
+    let mut runner = test_utils::deploy_evm();
+    let accounts: BTreeMap<&str, SecretKey> = fixture
+        .accounts
+        .iter()
+        .map(|(name, secret_key)| {
+            let secret_key = SecretKey::parse_slice(&parse_bytes(secret_key)).unwrap();
+            (name.as_str(), secret_key)
+        })
+        .collect();
+
+    for (index, step) in fixture.steps.iter().enumerate() {
+        let id = format!("{} step {}", file.display(), index);
+        match step {
+            Step::Deposit(step) => {
+                let address = resolve_address(&accounts, &step.address);
+                let balance = runner.get_balance(address).raw() + parse_u256(&step.amount);
+                let nonce = runner.get_nonce(address);
+                runner.create_address(address, Wei::new(balance), nonce);
+            }
+            Step::Submit(step) => {
+                let tx = TransactionLegacy {
+                    nonce: parse_u256(&step.nonce),
+                    gas_price: U256::zero(),
+                    gas_limit: u64::MAX.into(),
+                    to: step.to.as_ref().map(|to| resolve_address(&accounts, to)),
+                    value: Wei::new(step.value.as_deref().map(parse_u256).unwrap_or_default()),
+                    data: step.data.as_deref().map(parse_bytes).unwrap_or_default(),
+                };
+                submit(&mut runner, &accounts, &step.from, tx, &step.expect, &id);
+            }
+            Step::ExitToNear(step) => {
+                let sender = resolve_address(&accounts, &step.from);
+                let mut input = vec![0x0];
+                // The address the value is refunded to if the transfer on NEAR fails.
+                if cfg!(feature = "error_refund") {
+                    input.extend_from_slice(sender.as_bytes());
+                }
+                input.extend_from_slice(step.recipient.as_bytes());
+                let tx = TransactionLegacy {
+                    nonce: parse_u256(&step.nonce),
+                    gas_price: U256::zero(),
+                    gas_limit: u64::MAX.into(),
+                    to: Some(ExitToNear::ADDRESS),
+                    value: Wei::new(parse_u256(&step.amount)),
+                    data: input,
+                };
+                submit(&mut runner, &accounts, &step.from, tx, &step.expect, &id);
+            }
+            Step::ExitToEthereum(step) => {
+                let mut input = vec![0x0];
+                input.extend_from_slice(resolve_address(&accounts, &step.recipient).as_bytes());
+                let tx = TransactionLegacy {
+                    nonce: parse_u256(&step.nonce),
+                    gas_price: U256::zero(),
+                    gas_limit: u64::MAX.into(),
+                    to: Some(ExitToEthereum::ADDRESS),
+                    value: Wei::new(parse_u256(&step.amount)),
+                    data: input,
+                };
+                submit(&mut runner, &accounts, &step.from, tx, &step.expect, &id);
+            }
+            Step::ExpectAccount(step) => {
+                let address = resolve_address(&accounts, &step.address);
+                if let Some(balance) = step.balance.as_ref() {
+                    assert_eq!(
+                        runner.get_balance(address),
+                        Wei::new(parse_u256(balance)),
+                        "{}: balance mismatch",
+                        id
+                    );
+                }
+                if let Some(nonce) = step.nonce.as_ref() {
+                    assert_eq!(
+                        runner.get_nonce(address),
+                        parse_u256(nonce),
+                        "{}: nonce mismatch",
+                        id
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn submit(
+    runner: &mut AuroraRunner,
+    accounts: &BTreeMap<&str, SecretKey>,
+    from: &str,
+    tx: TransactionLegacy,
+    expect: &Expect,
+    id: &str,
+) {
+    let secret_key = accounts
+        .get(from)
+        .unwrap_or_else(|| panic!("{}: unknown account {}", id, from));
+    let signed_tx = test_utils::sign_transaction(tx, Some(runner.chain_id), secret_key);
+    let (outcome, maybe_error) = runner.call(
+        test_utils::SUBMIT,
+        "relay.aurora",
+        rlp::encode(&signed_tx).to_vec(),
+    );
+
+    match (maybe_error, expect.expect_error.as_deref()) {
+        (Some(error), Some(expected)) => {
+            assert!(
+                format!("{:?}", error).contains(expected),
+                "{}: expected {}, got {:?}",
+                id,
+                expected,
+                error
+            );
+        }
+        (Some(error), None) => panic!("{}: unexpected error {:?}", id, error),
+        (None, Some(expected)) => panic!("{}: expected {}", id, expected),
+        (None, None) => {
+            let result =
+                SubmitResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap())
+                    .unwrap();
+            match (result.status, expect.expect_status.as_deref()) {
+                (TransactionStatus::Succeed(_), None) => (),
+                (status, Some(expected)) => assert!(
+                    format!("{:?}", status).starts_with(expected),
+                    "{}: expected status {}, got {:?}",
+                    id,
+                    expected,
+                    status
+                ),
+                (status, None) => panic!("{}: transaction failed with {:?}", id, status),
+            }
+        }
+    }
+}
+
+fn resolve_address(accounts: &BTreeMap<&str, SecretKey>, address: &str) -> Address {
+    match accounts.get(address) {
+        Some(secret_key) => test_utils::address_from_secret_key(secret_key),
+        None => test_utils::address_from_hex(address),
+    }
+}
+
+fn parse_bytes(value: &str) -> Vec<u8> {
+    hex::decode(value.trim_start_matches("0x")).unwrap()
+}
+
+fn parse_u256(value: &str) -> U256 {
+    U256::from_dec_str(value).unwrap()
+}
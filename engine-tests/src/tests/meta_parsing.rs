@@ -1,14 +1,22 @@
 use {
-    crate::prelude::meta_parsing::{near_erc712_domain, parse_meta_call, prepare_meta_call_args},
-    crate::prelude::parameters::MetaCallArgs,
-    crate::prelude::sdk::keccak,
-    crate::prelude::{u256_to_arr, Address, BorshSerialize, InternalMetaCallArgs, Wei, U256},
+    crate::prelude::meta_parsing::{
+        erc712_domain, near_erc712_domain, parse_meta_call, prepare_meta_call_args,
+    },
+    crate::prelude::parameters::{Eip712Domain, FeePayerMetaCallArgs, FreezeTarget, MetaCallArgs},
+    crate::prelude::{
+        address_from_public_key, u256_to_arr, Address, BorshDeserialize, BorshSerialize,
+        InternalMetaCallArgs, Wei, U256,
+    },
+    crate::test_utils::standalone::StandaloneRunner,
+    aurora_engine::engine::{EngineErrorKind, GasPaymentError},
+    aurora_engine_sdk::types::near_account_to_evm_address,
     near_crypto::{InMemorySigner, KeyType, PublicKey, Signature, Signer},
 };
 
 fn encode_meta_call_function_args(
     signer: &dyn Signer,
     chain_id: u64,
+    account_id: &str,
     nonce: U256,
     fee_amount: Wei,
     fee_address: Address,
@@ -20,7 +28,7 @@ fn encode_meta_call_function_args(
     let domain_separator = near_erc712_domain(U256::from(chain_id));
     let (msg, _) = match prepare_meta_call_args(
         &domain_separator,
-        "evm".as_bytes(),
+        account_id.as_bytes(),
         method_def.to_string(),
         &InternalMetaCallArgs {
             sender: Address::zero(),
@@ -64,10 +72,7 @@ fn public_key_to_address(public_key: PublicKey) -> Address {
         PublicKey::ED25519(_) => panic!("Wrong PublicKey"),
         PublicKey::SECP256K1(pubkey) => {
             let pk: [u8; 64] = pubkey.into();
-            let bytes = keccak(&pk.to_vec());
-            let mut result = Address::zero();
-            result.as_bytes_mut().copy_from_slice(&bytes[12..]);
-            result
+            address_from_public_key(&pk)
         }
     }
 }
@@ -82,6 +87,7 @@ fn test_meta_parsing() {
     let meta_tx = encode_meta_call_function_args(
         &signer,
         chain_id,
+        "evm",
         U256::from(14),
         Wei::new_u64(6),
         Address::from_slice(&[0u8; 20]),
@@ -102,6 +108,7 @@ fn test_meta_parsing() {
     let meta_tx3 = encode_meta_call_function_args(
         &signer,
         chain_id,
+        "evm",
         U256::from(14),
         Wei::new_u64(6),
         Address::from_slice(&[0u8; 20]),
@@ -117,3 +124,116 @@ fn test_meta_parsing() {
 
     assert_eq!(result.sender, signer_addr);
 }
+
+#[test]
+fn test_erc712_domain() {
+    let chain_id = U256::from(1313161555);
+    assert_eq!(
+        erc712_domain(&Eip712Domain::default(), chain_id),
+        near_erc712_domain(chain_id)
+    );
+
+    // Domain of the example of EIP-712
+    let mut verifying_contract = [0u8; 20];
+    verifying_contract
+        .copy_from_slice(&hex::decode("CcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC").unwrap());
+    let domain = Eip712Domain {
+        name: "Ether Mail".to_string(),
+        version: "1".to_string(),
+        chain_id: Some(u256_to_arr(&U256::one())),
+        verifying_contract: Some(verifying_contract),
+    };
+    assert_eq!(
+        hex::encode(erc712_domain(&domain, chain_id)),
+        "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+    );
+}
+
+#[test]
+fn test_submit_with_fee_payer() {
+    let mut runner = StandaloneRunner::default();
+    runner.init_evm();
+    let chain_id = runner.chain_id;
+    let aurora_id = runner.env.current_account_id.to_string();
+
+    let signer = InMemorySigner::from_seed("doesnt".parse().unwrap(), KeyType::SECP256K1, "a");
+    let sender = public_key_to_address(signer.public_key.clone());
+    let fee_payer_id = "relayer.near";
+    let fee_payer = near_account_to_evm_address(fee_payer_id.as_bytes());
+    let recipient = Address::from_slice(&[0x11; 20]);
+    runner.mint_account(sender, Wei::new_u64(1_000), U256::zero(), None);
+    runner.mint_account(fee_payer, Wei::new_u64(10_000_000), U256::zero(), None);
+
+    let submit_args = |nonce: u64, fee_address: Address, gas_price: u64| {
+        let meta_call = encode_meta_call_function_args(
+            &signer,
+            chain_id,
+            &aurora_id,
+            U256::from(nonce),
+            Wei::zero(),
+            fee_address,
+            recipient,
+            Wei::new_u64(100),
+            "adopt(uint256 petId)",
+            hex::decode("c109").unwrap(),
+        );
+        FeePayerMetaCallArgs {
+            meta_call: MetaCallArgs::try_from_slice(&meta_call).unwrap(),
+            gas_limit: 100_000,
+            gas_price: u256_to_arr(&U256::from(gas_price)),
+        }
+        .try_to_vec()
+        .unwrap()
+    };
+
+    // Any fee payer can submit a meta-transaction designating none.
+    let result = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(0, Address::zero(), 1))
+        .unwrap();
+    assert!(result.status.is_ok());
+    assert!(result.gas_used > 0);
+    assert_eq!(runner.get_nonce(&sender), U256::one());
+    assert_eq!(runner.get_balance(&sender), Wei::new_u64(900));
+    assert_eq!(runner.get_balance(&recipient), Wei::new_u64(100));
+    // The fee payer paid the gas and, as the relayer of the transaction, got its priority fees.
+    assert_eq!(runner.get_balance(&fee_payer), Wei::new_u64(10_000_000));
+
+    let err = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(0, Address::zero(), 1))
+        .unwrap_err();
+    assert_eq!(err.kind, EngineErrorKind::IncorrectNonce);
+
+    let other_fee_payer = Address::from_slice(&[0x22; 20]);
+    let err = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(1, other_fee_payer, 1))
+        .unwrap_err();
+    assert_eq!(err.kind, EngineErrorKind::FeePayerMismatch);
+
+    let err = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(1, fee_payer, 1_000))
+        .unwrap_err();
+    assert_eq!(
+        err.kind,
+        EngineErrorKind::GasPayment(GasPaymentError::OutOfFund)
+    );
+    assert_eq!(runner.get_nonce(&sender), U256::one());
+
+    let result = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(1, fee_payer, 1))
+        .unwrap();
+    assert!(result.status.is_ok());
+    assert_eq!(runner.get_nonce(&sender), U256::from(2));
+    assert_eq!(runner.get_balance(&recipient), Wei::new_u64(200));
+
+    // A frozen fee payer can't pay for the gas of the meta-transactions.
+    runner.freeze(&FreezeTarget::Evm(fee_payer.0));
+    let fee_payer_balance = runner.get_balance(&fee_payer);
+    let err = runner
+        .submit_with_fee_payer(fee_payer_id, &submit_args(2, fee_payer, 1))
+        .unwrap_err();
+    assert_eq!(err.kind, EngineErrorKind::AccountFrozen);
+    assert_eq!(runner.get_balance(&fee_payer), fee_payer_balance);
+    assert_eq!(runner.get_nonce(&sender), U256::from(2));
+
+    runner.close();
+}
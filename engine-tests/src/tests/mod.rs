@@ -4,6 +4,7 @@ mod eip1559;
 mod erc20;
 mod erc20_connector;
 mod eth_connector;
+mod fixtures;
 #[cfg(feature = "meta-call")]
 mod meta_parsing;
 mod one_inch;
@@ -13,4 +14,6 @@ mod self_destruct_state;
 mod standalone;
 mod standard_precompiles;
 mod state_migration;
+#[cfg(feature = "ethereum-state-tests")]
+mod state_tests;
 pub(crate) mod uniswap;
@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: CC0-1.0
+pragma solidity ^0.8.6;
+
+interface IERC20 {
+    function transfer(address recipient, uint256 amount) external returns (bool);
+}
+
+// Router with the interface of the Uniswap V2 routers, swapping ETH at a fixed rate for the
+// last token of the path, out of its own balance of the token.
+contract FixedRateRouter {
+    event Swap(address indexed to, uint256 amountIn, uint256 amountOut);
+
+    uint256 public rate;
+
+    constructor(uint256 _rate) {
+        rate = _rate;
+    }
+
+    function swapExactETHForTokens(
+        uint256 amountOutMin,
+        address[] calldata path,
+        address to,
+        uint256 deadline
+    ) external payable returns (uint256[] memory amounts) {
+        require(block.timestamp <= deadline, "EXPIRED");
+        uint256 amountOut = msg.value * rate;
+        require(amountOut >= amountOutMin, "INSUFFICIENT_OUTPUT_AMOUNT");
+        amounts = new uint256[](path.length);
+        amounts[0] = msg.value;
+        amounts[path.length - 1] = amountOut;
+        require(IERC20(path[path.length - 1]).transfer(to, amountOut), "TRANSFER_FAILED");
+        emit Swap(to, msg.value, amountOut);
+    }
+}
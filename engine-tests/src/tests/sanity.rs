@@ -64,6 +64,47 @@ fn test_deploy_contract() {
     assert_eq!(code, stored_code);
 }
 
+#[test]
+fn test_deploy_contract_compressed_code() {
+    use aurora_engine::parameters::SetCodeCompressionArgs;
+
+    let (mut runner, mut signer, _) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call(
+        "set_code_compression",
+        &account_id,
+        SetCodeCompressionArgs { enabled: true }
+            .try_to_vec()
+            .unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    let code = [0x60, 0x80, 0x60, 0x40, 0x52, 0x34, 0x80, 0x15].repeat(100);
+    let result = runner
+        .submit_with_signer(&mut signer, |nonce| {
+            test_utils::create_deploy_transaction(code.clone(), nonce)
+        })
+        .unwrap();
+    let address = Address::from_slice(test_utils::unwrap_success_slice(&result));
+
+    // The code is stored compressed, and read back decompressed.
+    let code_key =
+        crate::prelude::storage::address_to_key(crate::prelude::storage::KeyPrefix::Code, &address);
+    let stored = runner.ext.fake_trie.get(&code_key[..]).unwrap();
+    assert_eq!(stored[0], aurora_engine::compression::COMPRESSED_BLOB_MAGIC);
+    assert!(stored.len() < code.len());
+    assert_eq!(runner.get_code(address), code);
+    // Its length is stored aside, EXTCODESIZE doesn't read the code.
+    let len_key = crate::prelude::storage::config_key(
+        crate::prelude::storage::ConfigKey::CodeLength,
+        address.as_bytes(),
+    );
+    assert_eq!(
+        runner.ext.fake_trie.get(&len_key).unwrap(),
+        &(code.len() as u64).to_le_bytes().to_vec()
+    );
+}
+
 #[test]
 fn test_deploy_largest_contract() {
     // Check to see we can deploy the largest allowed contract size within the
@@ -371,241 +412,2535 @@ fn test_eth_transfer_incorrect_nonce() {
 }
 
 #[test]
-fn test_eth_transfer_not_enough_gas() {
-    let (mut runner, mut source_account, dest_address) = initialize_transfer();
-    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
-    let transaction = |nonce| {
-        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
-        tx.gas_limit = 10_000.into(); // this is not enough gas
-        tx
+fn test_chain_id_replay_protection() {
+    use aurora_engine::parameters::{SetChainIdArgs, SetUnprotectedTxsArgs};
+
+    let (mut runner, source_account, dest_address) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let new_chain_id = runner.chain_id + 1;
+    let mut chain_id = [0u8; 32];
+    U256::from(new_chain_id).to_big_endian(&mut chain_id);
+    let set_chain_id_args = SetChainIdArgs { chain_id }.try_to_vec().unwrap();
+    let (_, maybe_error) = runner.call("set_chain_id", &account_id, set_chain_id_args.clone());
+    assert!(maybe_error.is_none());
+    let (outcome, _) = runner.one_shot().call("get_chain_id", "getter", Vec::new());
+    assert_eq!(outcome.unwrap().return_data.as_value().unwrap(), chain_id);
+
+    // Transactions signed for the other chain are rejected
+    let submit = |runner: &mut test_utils::AuroraRunner, chain_id, nonce: u64| {
+        let transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce.into());
+        let signed_tx =
+            test_utils::sign_transaction(transaction, chain_id, &source_account.secret_key);
+        runner
+            .call(
+                test_utils::SUBMIT,
+                "some-account.near",
+                rlp::encode(&signed_tx).to_vec(),
+            )
+            .1
     };
+    let old_chain_id = runner.chain_id;
+    let maybe_error = submit(&mut runner, Some(old_chain_id), INITIAL_NONCE);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INVALID_CHAIN_ID"));
+    assert!(submit(&mut runner, Some(new_chain_id), INITIAL_NONCE).is_none());
+
+    // The chain ID can't change once a transaction was executed
+    let (_, maybe_error) = runner.call("set_chain_id", &account_id, set_chain_id_args);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_CHAIN_ID_LOCKED"));
+
+    // The transactions signed for no chain are accepted until the owner rejects them
+    let args = SetUnprotectedTxsArgs { allowed: false }
+        .try_to_vec()
+        .unwrap();
+    let (_, maybe_error) = runner.call("set_unprotected_txs_allowed", &account_id, args);
+    assert!(maybe_error.is_none());
+    let maybe_error = submit(&mut runner, None, INITIAL_NONCE + 1);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_UNPROTECTED_TX"));
+}
 
-    // validate pre-state
-    test_utils::validate_address_balance_and_nonce(
-        &runner,
-        source_address,
-        INITIAL_BALANCE,
-        INITIAL_NONCE.into(),
-    );
-    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+#[test]
+fn test_handle_ops() {
+    use aurora_engine::entry_point::{self, ENTRY_POINT_ADDRESS};
+    use aurora_engine::parameters::{DeployEntryPointArgs, HandleOpsArgs, SetGasPriceArgs};
+
+    let (mut runner, _, _) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let bundler = "bundler.near";
+    let beneficiary = sdk::types::near_account_to_evm_address(bundler.as_bytes());
+    let word = |value: u64| {
+        let mut word = [0u8; 32];
+        U256::from(value).to_big_endian(&mut word);
+        word
+    };
+    // A bundle of one user operation with empty `bytes` fields
+    let ops: Vec<u8> = [
+        word(1),
+        word(32),
+        // sender, nonce, initCode, callData
+        word(0x33),
+        word(0),
+        word(11 * 32),
+        word(12 * 32),
+        // callGasLimit, verificationGasLimit, preVerificationGas
+        word(100_000),
+        word(50_000),
+        word(21_000),
+        // maxFeePerGas, maxPriorityFeePerGas, paymasterAndData, signature
+        word(1),
+        word(1),
+        word(13 * 32),
+        word(14 * 32),
+        word(0),
+        word(0),
+        word(0),
+        word(0),
+    ]
+    .concat();
+    let handle_ops_args = HandleOpsArgs { ops: ops.clone() }.try_to_vec().unwrap();
 
-    // attempt transfer
-    let err = runner
-        .submit_with_signer(&mut source_account, transaction)
-        .unwrap_err();
-    let error_message = format!("{:?}", err);
-    assert!(error_message.contains("ERR_INTRINSIC_GAS"));
+    let (_, maybe_error) = runner.call("handle_ops", bundler, handle_ops_args.clone());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_ENTRY_POINT_NOT_DEPLOYED"));
 
-    // validate post-state (which is the same as pre-state in this case)
-    test_utils::validate_address_balance_and_nonce(
-        &runner,
-        source_address,
-        INITIAL_BALANCE,
-        INITIAL_NONCE.into(),
+    // Only the init code of the canonical EntryPoint can be deployed at its address
+    let deploy_args = DeployEntryPointArgs {
+        init_code: vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+        salt: [0u8; 32],
+    }
+    .try_to_vec()
+    .unwrap();
+    let (_, maybe_error) = runner.call("deploy_entry_point", bundler, deploy_args.clone());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (_, maybe_error) = runner.call("deploy_entry_point", &account_id, deploy_args.clone());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_ENTRY_POINT_UNEXPECTED_INIT_CODE"));
+
+    // Stand-in for the EntryPoint returning its input:
+    // CALLDATASIZE PUSH1 0 PUSH1 0 CALLDATACOPY CALLDATASIZE PUSH1 0 RETURN
+    let echo = vec![0x36, 0x60, 0x00, 0x60, 0x00, 0x37, 0x36, 0x60, 0x00, 0xf3];
+    runner.create_address_with_code(ENTRY_POINT_ADDRESS, Wei::zero(), U256::from(2), echo);
+    let (_, maybe_error) = runner.call("deploy_entry_point", &account_id, deploy_args);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_ENTRY_POINT_ALREADY_DEPLOYED"));
+
+    let invalid_args = HandleOpsArgs {
+        ops: word(1).to_vec(),
+    }
+    .try_to_vec()
+    .unwrap();
+    let (_, maybe_error) = runner.call("handle_ops", bundler, invalid_args);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INVALID_USER_OPS"));
+
+    // The bundler prepays the gas limits of the operations
+    let set_gas_price_args = SetGasPriceArgs {
+        price: U256::one().into(),
+    };
+    let (_, maybe_error) = runner.call(
+        "set_gas_price",
+        &account_id,
+        set_gas_price_args.try_to_vec().unwrap(),
     );
-    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call("handle_ops", bundler, handle_ops_args.clone());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_OUT_OF_FUND"));
+
+    // The bundler is the beneficiary of the fees of the operations
+    runner.create_address(beneficiary, Wei::new_u64(1_000_000), U256::zero());
+    let (outcome, maybe_error) = runner.call("handle_ops", bundler, handle_ops_args);
+    assert!(maybe_error.is_none());
+    let result =
+        SubmitResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert_eq!(
+        result.status,
+        TransactionStatus::Succeed(entry_point::handle_ops_input(&ops, &beneficiary))
+    );
+    assert!(result.gas_used > 0);
+    // As the relayer of the bundle, the bundler got back the fees of the gas it used
+    assert_eq!(runner.get_balance(beneficiary), Wei::new_u64(1_000_000));
 }
 
 #[test]
-fn test_transfer_charging_gas_success() {
-    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+fn test_eth_transfer_duplicate_submit() {
+    let (mut runner, source_account, dest_address) = initialize_transfer();
     let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
-    let transaction = |nonce| {
-        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
-        tx.gas_limit = 30_000.into();
-        tx.gas_price = GAS_PRICE.into();
-        tx
-    };
 
-    // validate pre-state
-    test_utils::validate_address_balance_and_nonce(
-        &runner,
-        source_address,
-        INITIAL_BALANCE,
-        INITIAL_NONCE.into(),
+    let args = aurora_engine::parameters::SetDuplicateSubmitWindowArgs { window_blocks: 10 };
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call(
+        "set_duplicate_submit_window",
+        &account_id,
+        args.try_to_vec().unwrap(),
     );
-    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+    assert!(maybe_error.is_none());
 
-    // do transfer
-    let result = runner
-        .submit_with_signer(&mut source_account, transaction)
-        .unwrap();
-    let spent_amount = Wei::new_u64(GAS_PRICE * result.gas_used);
-    let expected_source_balance = INITIAL_BALANCE - TRANSFER_AMOUNT - spent_amount;
-    let expected_dest_balance = TRANSFER_AMOUNT;
-    let expected_relayer_balance = spent_amount;
-    let relayer_address = sdk::types::near_account_to_evm_address(
-        runner.context.predecessor_account_id.as_ref().as_bytes(),
+    let transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, INITIAL_NONCE.into());
+    let signed_tx = test_utils::sign_transaction(
+        transaction,
+        Some(runner.chain_id),
+        &source_account.secret_key,
     );
+    let transaction_bytes = rlp::encode(&signed_tx).to_vec();
+
+    let (_, maybe_error) = runner.call(
+        test_utils::SUBMIT,
+        "some-account.near",
+        transaction_bytes.clone(),
+    );
+    assert!(maybe_error.is_none());
+
+    // submitting the same transaction again is rejected before the nonce is checked
+    let (_, maybe_error) = runner.call(test_utils::SUBMIT, "some-account.near", transaction_bytes);
+    let error_message = format!("{:?}", maybe_error.unwrap());
+    assert!(error_message.contains("ERR_ALREADY_SUBMITTED"));
 
-    // validate post-state
     test_utils::validate_address_balance_and_nonce(
         &runner,
         source_address,
-        expected_source_balance,
+        INITIAL_BALANCE - TRANSFER_AMOUNT,
         (INITIAL_NONCE + 1).into(),
     );
     test_utils::validate_address_balance_and_nonce(
         &runner,
         dest_address,
-        expected_dest_balance,
+        TRANSFER_AMOUNT,
         0.into(),
     );
+}
+
+#[test]
+fn test_eth_transfer_min_gas_price() {
+    use aurora_engine::parameters::{
+        GasPriceResult, SetGasPriceArgs, SetGasPriceMaxAgeArgs, SetGasPriceUpdaterArgs,
+    };
+
+    let (mut runner, source_account, dest_address) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let set_gas_price_args = SetGasPriceArgs {
+        price: U256::one().into(),
+    };
+
+    let (_, maybe_error) = runner.call(
+        "set_gas_price",
+        "oracle.near",
+        set_gas_price_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED_TO_SET_PRICE"));
+    let args = SetGasPriceUpdaterArgs {
+        account_id: "oracle.near".parse().unwrap(),
+        allowed: true,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_gas_price_updater",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call(
+        "set_gas_price",
+        "oracle.near",
+        set_gas_price_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    // The transaction pays a zero gas price
+    let transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, INITIAL_NONCE.into());
+    let signed_tx = test_utils::sign_transaction(
+        transaction,
+        Some(runner.chain_id),
+        &source_account.secret_key,
+    );
+    let transaction_bytes = rlp::encode(&signed_tx).to_vec();
+    let (_, maybe_error) = runner.call(
+        test_utils::SUBMIT,
+        "some-account.near",
+        transaction_bytes.clone(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_GAS_PRICE_TOO_LOW"));
+
+    // Every call of the runner advances the block timestamp by one second
+    let args = SetGasPriceMaxAgeArgs { max_age_secs: 2 };
+    let (_, maybe_error) = runner.call(
+        "set_gas_price_max_age",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    runner.context.block_timestamp += 2_000_000_000;
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("get_gas_price", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    let gas_price =
+        GasPriceResult::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap();
+    assert_eq!(gas_price.price, set_gas_price_args.price);
+    assert!(gas_price.is_stale);
+
+    // The stale gas price is not enforced
+    let (_, maybe_error) = runner.call(test_utils::SUBMIT, "some-account.near", transaction_bytes);
+    assert!(maybe_error.is_none());
     test_utils::validate_address_balance_and_nonce(
         &runner,
-        relayer_address,
-        expected_relayer_balance,
+        dest_address,
+        TRANSFER_AMOUNT,
         0.into(),
     );
 }
 
 #[test]
-fn test_eth_transfer_charging_gas_not_enough_balance() {
+fn test_eth_transfer_witness_size() {
+    use aurora_engine::parameters::{SetWitnessSizeLimitArgs, SubmitBatchArgs};
+
     let (mut runner, mut source_account, dest_address) = initialize_transfer();
     let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
-    let transaction = |nonce| {
-        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
-        // With this gas limit and price the account does not
-        // have enough balance to cover the gas cost
-        tx.gas_limit = 3_000_000.into();
-        tx.gas_price = GAS_PRICE.into();
-        tx
-    };
 
-    // validate pre-state
+    let transaction = test_utils::transfer(
+        dest_address,
+        TRANSFER_AMOUNT,
+        source_account.use_nonce().into(),
+    );
+    let signed_tx = test_utils::sign_transaction(
+        transaction,
+        Some(runner.chain_id),
+        &source_account.secret_key,
+    );
+    let (maybe_outcome, maybe_error) = runner.call(
+        test_utils::SUBMIT,
+        "some-account.near",
+        rlp::encode(&signed_tx).to_vec(),
+    );
+    assert!(maybe_error.is_none());
+    let witness_size_log = maybe_outcome
+        .unwrap()
+        .logs
+        .into_iter()
+        .find(|log| log.contains("\"event\": \"witness_size\""))
+        .unwrap();
+    assert!(witness_size_log.starts_with("EVENT_JSON:{\"data\": [{\"reads\": "));
+
+    let account_id = runner.aurora_account_id.clone();
+    let args = SetWitnessSizeLimitArgs { limit: Some(100) };
+    let (_, maybe_error) = runner.call(
+        "set_witness_size_limit",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    // A transfer touches at least the nonces and balances of both addresses
+    let transaction =
+        test_utils::transfer(dest_address, TRANSFER_AMOUNT, source_account.nonce.into());
+    let signed_tx = test_utils::sign_transaction(
+        transaction,
+        Some(runner.chain_id),
+        &source_account.secret_key,
+    );
+    let (_, maybe_error) = runner.call(
+        test_utils::SUBMIT,
+        "some-account.near",
+        rlp::encode(&signed_tx).to_vec(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_WITNESS_SIZE_EXCEEDED"));
     test_utils::validate_address_balance_and_nonce(
         &runner,
         source_address,
-        INITIAL_BALANCE,
-        INITIAL_NONCE.into(),
+        INITIAL_BALANCE - TRANSFER_AMOUNT,
+        (INITIAL_NONCE + 1).into(),
     );
-    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
-
-    // attempt transfer
-    let result = runner
-        .submit_with_signer(&mut source_account, transaction)
-        .unwrap();
-    assert_eq!(result.status, TransactionStatus::OutOfFund);
 
-    // validate post-state
-    let relayer = sdk::types::near_account_to_evm_address(
-        runner.context.predecessor_account_id.as_ref().as_bytes(),
+    // The limit applies to the transactions submitted in a batch as well
+    let args = SubmitBatchArgs {
+        transactions: vec![rlp::encode(&signed_tx).to_vec()],
+        stop_on_failure: false,
+        gas_cap: None,
+    };
+    let (_, maybe_error) = runner.call(
+        "submit_batch",
+        "some-account.near",
+        args.try_to_vec().unwrap(),
     );
-
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_WITNESS_SIZE_EXCEEDED"));
     test_utils::validate_address_balance_and_nonce(
         &runner,
         source_address,
-        INITIAL_BALANCE,
-        // nonce is still incremented since the transaction was otherwise valid
+        INITIAL_BALANCE - TRANSFER_AMOUNT,
         (INITIAL_NONCE + 1).into(),
     );
-    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
-    test_utils::validate_address_balance_and_nonce(&runner, relayer, Wei::zero(), 0.into());
 }
 
-fn initialize_transfer() -> (test_utils::AuroraRunner, test_utils::Signer, Address) {
-    // set up Aurora runner and accounts
+#[test]
+fn test_upgrade_delay() {
+    use aurora_engine::parameters::SetUpgradeDelayBlocksArgs;
+
     let mut runner = test_utils::deploy_evm();
-    let mut rng = rand::thread_rng();
-    let source_account = SecretKey::random(&mut rng);
-    let source_address = test_utils::address_from_secret_key(&source_account);
-    runner.create_address(source_address, INITIAL_BALANCE, INITIAL_NONCE.into());
-    let dest_address = test_utils::address_from_secret_key(&SecretKey::random(&mut rng));
-    let mut signer = test_utils::Signer::new(source_account);
-    signer.nonce = INITIAL_NONCE;
+    let account_id = runner.aurora_account_id.clone();
 
-    (runner, signer, dest_address)
-}
+    let args = SetUpgradeDelayBlocksArgs {
+        upgrade_delay_blocks: 5,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_upgrade_delay_blocks",
+        "not-owner.near",
+        args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (_, maybe_error) = runner.call(
+        "set_upgrade_delay_blocks",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
 
-use sha3::Digest;
+    let (_, maybe_error) = runner.call("stage_upgrade", &account_id, b"new code".to_vec());
+    assert!(maybe_error.is_none());
+    let stage_height = runner.context.block_index;
+    let get_upgrade_index = |runner: &test_utils::AuroraRunner| {
+        let (outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_upgrade_index", "getter", Vec::new());
+        assert!(maybe_error.is_none());
+        u64::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+    assert_eq!(get_upgrade_index(&runner), stage_height + 5);
 
-#[test]
-fn check_selector() {
-    // Selector to call mint function in ERC 20 contract
-    //
-    // keccak("mint(address,uint256)".as_bytes())[..4];
-    let mut hasher = sha3::Keccak256::default();
-    hasher.update(b"mint(address,uint256)");
-    assert_eq!(hasher.finalize()[..4].to_vec(), ERC20_MINT_SELECTOR);
+    // Lowering the delay doesn't apply to the upgrade already staged
+    let args = SetUpgradeDelayBlocksArgs {
+        upgrade_delay_blocks: 0,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_upgrade_delay_blocks",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert_eq!(get_upgrade_index(&runner), stage_height + 5);
+
+    let (_, maybe_error) = runner.call("deploy_upgrade", &account_id, Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED:TOO_EARLY"));
 }
 
 #[test]
-fn test_block_hash() {
-    let runner = test_utils::AuroraRunner::default();
-    let chain_id = {
-        let number = crate::prelude::U256::from(runner.chain_id);
-        crate::prelude::u256_to_arr(&number)
+fn test_engine_sequence() {
+    use aurora_engine::parameters::SetUpgradeDelayBlocksArgs;
+
+    let (mut runner, mut signer, _) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let get_sequence = |runner: &test_utils::AuroraRunner| {
+        let (outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_engine_sequence", "getter", Vec::new());
+        assert!(maybe_error.is_none());
+        u64::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap()
     };
-    let account_id = runner.aurora_account_id;
-    let block_hash = aurora_engine::engine::compute_block_hash(chain_id, 10, account_id.as_bytes());
+    let sequence_event = |sequence: u64| {
+        format!(
+            "EVENT_JSON:{{\"data\": [{{\"sequence\": {}}}], \"event\": \"engine_sequence\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+            sequence
+        )
+    };
+    let initial_sequence = get_sequence(&runner);
+    assert!(initial_sequence > 0);
 
-    assert_eq!(
-        hex::encode(block_hash.0).as_str(),
-        "c4a46f076b64877cbd8c5dbfd7bfbbea21a5653b79e3b6d06b6dfb5c88f1c384",
-    );
+    // Every call changing the state increments the sequence once
+    let args = SetUpgradeDelayBlocksArgs {
+        upgrade_delay_blocks: 5,
+    }
+    .try_to_vec()
+    .unwrap();
+    let (outcome, maybe_error) = runner.call("set_upgrade_delay_blocks", &account_id, args.clone());
+    assert!(maybe_error.is_none());
+    assert!(outcome
+        .unwrap()
+        .logs
+        .contains(&sequence_event(initial_sequence + 1)));
+    runner
+        .submit_with_signer(&mut signer, |nonce| {
+            test_utils::transfer(Address([1; 20]), TRANSFER_AMOUNT, nonce)
+        })
+        .unwrap();
+    assert!(runner
+        .previous_logs
+        .contains(&sequence_event(initial_sequence + 2)));
+    assert_eq!(get_sequence(&runner), initial_sequence + 2);
+
+    // The failed calls and the views don't
+    let (_, maybe_error) = runner.call("set_upgrade_delay_blocks", "not-owner.near", args);
+    assert!(maybe_error.is_some());
+    let (_, maybe_error) = runner.call("get_chain_id", "getter", Vec::new());
+    assert!(maybe_error.is_none());
+    assert_eq!(get_sequence(&runner), initial_sequence + 2);
 }
 
 #[test]
-fn test_block_hash_api() {
+fn test_admin_actions_log() {
+    use aurora_engine::admin_log::AdminAction;
+    use aurora_engine::parameters::{Page, PageArgs, SetUpgradeDelayBlocksArgs};
+
     let mut runner = test_utils::deploy_evm();
+    let account_id = runner.aurora_account_id.clone();
+    let get_admin_actions = |runner: &test_utils::AuroraRunner| {
+        let args = PageArgs {
+            cursor: None,
+            limit: 100,
+        };
+        let (outcome, maybe_error) = runner.one_shot().call(
+            "get_admin_actions_page",
+            "getter",
+            args.try_to_vec().unwrap(),
+        );
+        assert!(maybe_error.is_none());
+        Page::<AdminAction>::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+            .items
+    };
+    let initial_actions = get_admin_actions(&runner);
 
-    let block_height: u64 = 10;
-    let (maybe_outcome, maybe_error) = runner.call(
-        "get_block_hash",
-        "any.near",
-        block_height.try_to_vec().unwrap(),
-    );
-    if let Some(error) = maybe_error {
-        panic!("Call failed: {:?}", error);
+    // Rejected calls are not recorded
+    let args = SetUpgradeDelayBlocksArgs {
+        upgrade_delay_blocks: 5,
     }
-    let outcome = maybe_outcome.unwrap();
-    let block_hash = outcome.return_data.as_value().unwrap();
+    .try_to_vec()
+    .unwrap();
+    let (_, maybe_error) = runner.call("set_upgrade_delay_blocks", "not-owner.near", args.clone());
+    assert!(maybe_error.is_some());
+    assert_eq!(get_admin_actions(&runner), initial_actions);
+
+    let (_, maybe_error) = runner.call("set_upgrade_delay_blocks", &account_id, args.clone());
+    assert!(maybe_error.is_none());
+    let block_height = runner.context.block_index;
+    let (_, maybe_error) = runner.call("stage_upgrade", &account_id, b"new code".to_vec());
+    assert!(maybe_error.is_none());
 
+    let actions = get_admin_actions(&runner);
+    assert_eq!(actions.len(), initial_actions.len() + 2);
     assert_eq!(
-        hex::encode(&block_hash).as_str(),
-        "c4a46f076b64877cbd8c5dbfd7bfbbea21a5653b79e3b6d06b6dfb5c88f1c384",
+        &actions[..initial_actions.len()],
+        initial_actions.as_slice()
     );
-}
+    let sequence = initial_actions.len() as u64;
+    assert_eq!(
+        actions[initial_actions.len()],
+        AdminAction {
+            sequence,
+            actor: account_id.parse().unwrap(),
+            method: "set_upgrade_delay_blocks".to_string(),
+            args_hash: sdk::keccak(&args).0,
+            block_height,
+        }
+    );
+    let stage_action = &actions[initial_actions.len() + 1];
+    assert_eq!(stage_action.sequence, sequence + 1);
+    assert_eq!(stage_action.method, "stage_upgrade");
+    assert_eq!(stage_action.args_hash, sdk::keccak(b"new code").0);
+}
+
+#[test]
+fn test_view_chunk() {
+    use aurora_engine::parameters::{ViewChunk, ViewChunkArgs, ViewRequest};
+    use aurora_engine::view_chunk::VIEW_CHUNK_SIZE;
+
+    let mut runner = test_utils::deploy_evm();
+    let address = test_utils::address_from_hex("0x1234123412341234123412341234123412341234");
+    let code: Vec<u8> = (0..2 * VIEW_CHUNK_SIZE + 100).map(|i| i as u8).collect();
+    runner.create_address_with_code(address, Wei::zero(), U256::zero(), code.clone());
+    let view_chunk = |index: u32| {
+        let args = ViewChunkArgs {
+            request: ViewRequest::GetCode(address.0),
+            index,
+        };
+        let (outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("view_chunk", "getter", args.try_to_vec().unwrap());
+        match maybe_error {
+            Some(error) => Err(error),
+            None => Ok(ViewChunk::try_from_slice(
+                &outcome.unwrap().return_data.as_value().unwrap(),
+            )
+            .unwrap()),
+        }
+    };
+
+    let first_chunk = view_chunk(0).unwrap();
+    assert_eq!(first_chunk.total_chunks, 3);
+    let mut joined = first_chunk.data;
+    for index in 1..first_chunk.total_chunks {
+        joined.extend(view_chunk(index).unwrap().data);
+    }
+    assert_eq!(joined, code);
+
+    let error = view_chunk(3).unwrap_err();
+    assert!(format!("{:?}", error).contains("ERR_INVALID_CHUNK_INDEX"));
+}
+
+#[test]
+fn test_json_rpc_views() {
+    let mut runner = test_utils::deploy_evm();
+    let address = test_utils::address_from_hex("0x1234123412341234123412341234123412341234");
+    // PUSH1 0x2a PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = hex::decode("602a60005260206000f3").unwrap();
+    runner.create_address_with_code(address, Wei::new_u64(1000), U256::from(5), code.clone());
+    let json_view = |method: &str, args: serde_json::Value| {
+        let (outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call(method, "getter", args.to_string().into_bytes());
+        assert!(maybe_error.is_none(), "{}: {:?}", method, maybe_error);
+        let output = outcome.unwrap().return_data.as_value().unwrap();
+        serde_json::from_slice::<serde_json::Value>(&output).unwrap()
+    };
+    let address_args = serde_json::json!({ "address": format!("0x{}", hex::encode(address)) });
+
+    assert_eq!(
+        json_view("eth_get_balance", address_args.clone()),
+        serde_json::json!("0x3e8")
+    );
+    assert_eq!(
+        json_view("eth_get_transaction_count", address_args.clone()),
+        serde_json::json!("0x5")
+    );
+    assert_eq!(
+        json_view("eth_get_code", address_args),
+        serde_json::json!(format!("0x{}", hex::encode(&code)))
+    );
+    assert_eq!(
+        json_view(
+            "eth_get_storage_at",
+            serde_json::json!({
+                "address": format!("0x{}", hex::encode(address)),
+                "key": "0x0",
+            })
+        ),
+        serde_json::json!(format!("0x{}", "00".repeat(32)))
+    );
+    assert_eq!(
+        json_view(
+            "eth_call",
+            serde_json::json!({ "to": format!("0x{}", hex::encode(address)) })
+        ),
+        serde_json::json!({
+            "output": format!("0x{:064x}", 0x2a),
+            "status": "succeed",
+        })
+    );
+
+    let (_, maybe_error) = runner.one_shot().call(
+        "eth_get_balance",
+        "getter",
+        br#"{"address": "0x1234"}"#.to_vec(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INVALID_ADDRESS"));
+}
+
+#[test]
+fn test_dry_run_submit() {
+    let (runner, source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let dry_run = |nonce: u64| {
+        let transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce.into());
+        let signed_tx = test_utils::sign_transaction(
+            transaction,
+            Some(runner.chain_id),
+            &source_account.secret_key,
+        );
+        let (maybe_outcome, maybe_error) = runner.one_shot().call(
+            "dry_run_submit",
+            "some-account.near",
+            rlp::encode(&signed_tx).to_vec(),
+        );
+        assert!(maybe_error.is_none());
+        aurora_engine::parameters::DryRunSubmitResult::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+
+    let report = dry_run(INITIAL_NONCE);
+    assert_eq!(report.sender, Some(source_address.0));
+    assert!(report.error.is_none());
+    assert_eq!(report.status, Some(TransactionStatus::Succeed(Vec::new())));
+    assert_eq!(report.gas_used, 21_000);
+    assert!(report.logs.is_empty());
+
+    let report = dry_run(INITIAL_NONCE + 1);
+    assert_eq!(report.sender, Some(source_address.0));
+    assert_eq!(report.error, Some(b"ERR_INCORRECT_NONCE".to_vec()));
+    assert!(report.status.is_none());
+
+    // nothing is committed by the dry run
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+}
+
+#[test]
+fn test_submit_batch() {
+    use aurora_engine::parameters::{BatchTransactionResult, SubmitBatchArgs};
+
+    let (mut runner, source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let chain_id = runner.chain_id;
+
+    let transfer = |nonce: u64| {
+        let mut transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce.into());
+        transaction.gas_limit = 21_000.into();
+        let signed_tx =
+            test_utils::sign_transaction(transaction, Some(chain_id), &source_account.secret_key);
+        rlp::encode(&signed_tx).to_vec()
+    };
+    let submit_batch = |runner: &mut test_utils::AuroraRunner, args: SubmitBatchArgs| {
+        let (maybe_outcome, maybe_error) =
+            runner.call("submit_batch", "relay.aurora", args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+        Vec::<BatchTransactionResult>::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+    let is_success = |result: &BatchTransactionResult| matches!(result, BatchTransactionResult::Submitted(result) if result.status.is_ok());
+
+    // A rejected transaction doesn't prevent the following ones from being submitted
+    let args = SubmitBatchArgs {
+        transactions: vec![transfer(0), transfer(5), transfer(1)],
+        stop_on_failure: false,
+        gas_cap: None,
+    };
+    let results = submit_batch(&mut runner, args);
+    assert_eq!(results.len(), 3);
+    assert!(is_success(&results[0]));
+    assert!(
+        matches!(&results[1], BatchTransactionResult::Rejected(e) if e == b"ERR_INCORRECT_NONCE")
+    );
+    assert!(is_success(&results[2]));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        dest_address,
+        Wei::new_u64(2 * TRANSFER_AMOUNT.raw().as_u64()),
+        0.into(),
+    );
+
+    // Short-circuit on the first failure
+    let args = SubmitBatchArgs {
+        transactions: vec![transfer(5), transfer(2)],
+        stop_on_failure: true,
+        gas_cap: None,
+    };
+    let results = submit_batch(&mut runner, args);
+    assert!(matches!(&results[0], BatchTransactionResult::Rejected(_)));
+    assert!(matches!(&results[1], BatchTransactionResult::Skipped));
+
+    // Only the transactions fitting in the gas cap are submitted
+    let args = SubmitBatchArgs {
+        transactions: vec![transfer(2), transfer(3), transfer(4)],
+        stop_on_failure: false,
+        gas_cap: Some(50_000),
+    };
+    let results = submit_batch(&mut runner, args);
+    assert!(is_success(&results[0]));
+    assert!(is_success(&results[1]));
+    assert!(matches!(&results[2], BatchTransactionResult::Skipped));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        Wei::new_u64(INITIAL_BALANCE.raw().as_u64() - 4 * TRANSFER_AMOUNT.raw().as_u64()),
+        (INITIAL_NONCE + 4).into(),
+    );
+}
+
+#[test]
+fn test_submit_batch_block_gas_cap() {
+    use aurora_engine::parameters::{BatchTransactionResult, BlockAttributes, SubmitBatchArgs};
+
+    let (mut runner, source_account, dest_address) = initialize_transfer();
+    let chain_id = runner.chain_id;
+
+    let transfer = |nonce: u64| {
+        let mut transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce.into());
+        transaction.gas_limit = 21_000.into();
+        let signed_tx =
+            test_utils::sign_transaction(transaction, Some(chain_id), &source_account.secret_key);
+        rlp::encode(&signed_tx).to_vec()
+    };
+    let submit_batch = |runner: &mut test_utils::AuroraRunner, nonces: &[u64]| {
+        let args = SubmitBatchArgs {
+            transactions: nonces.iter().map(|nonce| transfer(*nonce)).collect(),
+            stop_on_failure: false,
+            gas_cap: None,
+        };
+        let (maybe_outcome, maybe_error) =
+            runner.call("submit_batch", "relay.aurora", args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+        Vec::<BatchTransactionResult>::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+    let is_success = |result: &BatchTransactionResult| matches!(result, BatchTransactionResult::Submitted(result) if result.status.is_ok());
+
+    let attributes = BlockAttributes {
+        coinbase: Some([0x11; 20]),
+        gas_limit: Some(30_000_000),
+        block_gas_cap: Some(50_000),
+    };
+    let (_, maybe_error) = runner.call(
+        "set_block_attributes",
+        "some-account.near",
+        attributes.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_some());
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call(
+        "set_block_attributes",
+        &account_id,
+        attributes.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let (maybe_outcome, maybe_error) = runner.call("get_block_attributes", &account_id, Vec::new());
+    assert!(maybe_error.is_none());
+    assert_eq!(
+        BlockAttributes::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap(),
+        attributes
+    );
+
+    let results = submit_batch(&mut runner, &[0]);
+    assert!(is_success(&results[0]));
+    // The cap is shared with the previous batch of the same block
+    runner.context.block_index -= 1;
+    let results = submit_batch(&mut runner, &[1, 2]);
+    assert!(is_success(&results[0]));
+    assert!(matches!(&results[1], BatchTransactionResult::Skipped));
+    // The cap is reset by a new block
+    let results = submit_batch(&mut runner, &[2, 3]);
+    assert!(is_success(&results[0]));
+    assert!(is_success(&results[1]));
+}
+
+#[test]
+fn test_begin_end_block() {
+    use aurora_engine::parameters::{
+        BatchTransactionResult, BeginBlockArgs, BlockAttributes, SubmitBatchArgs, TestBlockSummary,
+    };
+    use aurora_engine::test_chain;
+
+    let (mut runner, source_account, dest_address) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let chain_id = runner.chain_id;
+
+    let transfer = |nonce: u64| {
+        let mut transaction = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce.into());
+        transaction.gas_limit = 21_000.into();
+        let signed_tx =
+            test_utils::sign_transaction(transaction, Some(chain_id), &source_account.secret_key);
+        rlp::encode(&signed_tx).to_vec()
+    };
+    let begin_args = |number: u64| BeginBlockArgs {
+        hash: [0u8; 32],
+        coinbase: [0u8; 20],
+        timestamp: crate::prelude::u256_to_arr(&U256::from(1_650_000_000)),
+        number: crate::prelude::u256_to_arr(&U256::from(number)),
+        difficulty: [0u8; 32],
+        gaslimit: [0u8; 32],
+    };
+    // The controls are mirrored in the standalone engine, which must give the same hashes.
+    let begin_block = |runner: &mut test_utils::AuroraRunner, number: u64| {
+        let args = begin_args(number);
+        if let Some(standalone_runner) = &mut runner.standalone_runner {
+            standalone_runner.env.block_height = runner.context.block_index;
+            standalone_runner.begin_block(&args).unwrap();
+        }
+        let (_, maybe_error) = runner.call("begin_block", &account_id, args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+    };
+    let end_block = |runner: &mut test_utils::AuroraRunner| {
+        let block_index = runner.context.block_index;
+        let standalone_summary = runner.standalone_runner.as_mut().map(|standalone_runner| {
+            standalone_runner.env.block_height = block_index;
+            standalone_runner.end_block().unwrap()
+        });
+        let (maybe_outcome, maybe_error) = runner.call("end_block", &account_id, Vec::new());
+        assert!(maybe_error.is_none());
+        let summary = TestBlockSummary::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap();
+        if let Some(standalone_summary) = standalone_summary {
+            assert_eq!(summary, standalone_summary);
+        }
+        summary
+    };
+    let submit = |runner: &mut test_utils::AuroraRunner, transaction_bytes: Vec<u8>| {
+        let (maybe_outcome, maybe_error) =
+            runner.call(test_utils::SUBMIT, "relay.aurora", transaction_bytes);
+        assert!(maybe_error.is_none());
+        SubmitResult::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+    };
+
+    let (_, maybe_error) = runner.call("end_block", &account_id, Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NO_OPEN_BLOCK"));
+    let (_, maybe_error) = runner.call(
+        "begin_block",
+        "some-account.near",
+        begin_args(1_000).try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_some());
+
+    // The transactions of the block are in different NEAR blocks
+    begin_block(&mut runner, 1_000);
+    let (_, maybe_error) = runner.call(
+        "begin_block",
+        &account_id,
+        begin_args(1_001).try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_BLOCK_ALREADY_OPEN"));
+    let transactions = vec![transfer(0), transfer(1)];
+    let results: Vec<SubmitResult> = transactions
+        .iter()
+        .map(|transaction_bytes| submit(&mut runner, transaction_bytes.clone()))
+        .collect();
+    let summary = end_block(&mut runner);
+
+    let mut hashchain = [0u8; 32];
+    for (transaction_bytes, result) in transactions.iter().zip(results.iter()) {
+        let receipt = test_chain::receipt_hash(result);
+        hashchain = test_chain::next_hashchain(&hashchain, transaction_bytes, &receipt).0;
+    }
+    let receipts: Vec<u8> = results
+        .iter()
+        .flat_map(|result| test_chain::receipt_hash(result).0.to_vec())
+        .collect();
+    assert_eq!(summary.number, 1_000);
+    assert_eq!(summary.timestamp, 1_650_000_000 * 1_000_000_000);
+    assert_eq!(summary.parent_hashchain, [0u8; 32]);
+    assert_eq!(summary.hashchain, hashchain);
+    assert_eq!(summary.receipts_root, sdk::keccak(&receipts).0);
+    assert_eq!(summary.transactions, 2);
+    assert_eq!(summary.gas_used, 2 * 21_000);
+
+    // The blocks are chained and numbered in order
+    let (_, maybe_error) = runner.call(
+        "begin_block",
+        &account_id,
+        begin_args(1_000).try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_BLOCK_NUMBER_NOT_INCREASING"));
+    begin_block(&mut runner, 1_001);
+    let empty = end_block(&mut runner);
+    assert_eq!(empty.parent_hashchain, summary.hashchain);
+    assert_eq!(empty.hashchain, summary.hashchain);
+    assert_eq!(empty.transactions, 0);
+
+    // The gas cap of a block is shared by its transactions, whatever their NEAR blocks.
+    let attributes = BlockAttributes {
+        coinbase: None,
+        gas_limit: None,
+        block_gas_cap: Some(50_000),
+    };
+    let (_, maybe_error) = runner.call(
+        "set_block_attributes",
+        &account_id,
+        attributes.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let submit_batch = |runner: &mut test_utils::AuroraRunner, nonces: &[u64]| {
+        let args = SubmitBatchArgs {
+            transactions: nonces.iter().map(|nonce| transfer(*nonce)).collect(),
+            stop_on_failure: false,
+            gas_cap: None,
+        };
+        let (maybe_outcome, maybe_error) =
+            runner.call("submit_batch", "relay.aurora", args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+        Vec::<BatchTransactionResult>::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+    begin_block(&mut runner, 1_002);
+    let results = submit_batch(&mut runner, &[2]);
+    assert!(matches!(&results[0], BatchTransactionResult::Submitted(_)));
+    let results = submit_batch(&mut runner, &[3, 4]);
+    assert!(matches!(&results[0], BatchTransactionResult::Submitted(_)));
+    assert!(matches!(&results[1], BatchTransactionResult::Skipped));
+    let summary = end_block(&mut runner);
+    assert_eq!(summary.transactions, 2);
+    begin_block(&mut runner, 1_003);
+    let results = submit_batch(&mut runner, &[4]);
+    assert!(matches!(&results[0], BatchTransactionResult::Submitted(_)));
+}
+
+#[test]
+fn test_eth_transfer_frozen_account() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+
+    let target = aurora_engine::parameters::FreezeTarget::Evm(source_address.0);
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call("freeze_account", &account_id, target.try_to_vec().unwrap());
+    assert!(maybe_error.is_none());
+
+    let transfer = |nonce| test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
+    let error = runner
+        .submit_with_signer(&mut source_account, transfer)
+        .unwrap_err();
+    let error_message = format!("{:?}", error);
+    assert!(error_message.contains("ERR_ACCOUNT_FROZEN"));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+
+    let (_, maybe_error) = runner.call(
+        "unfreeze_account",
+        &account_id,
+        target.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    // the nonce of the rejected transaction was not used
+    source_account.nonce = INITIAL_NONCE;
+    let result = runner
+        .submit_with_signer(&mut source_account, transfer)
+        .unwrap();
+    assert!(result.status.is_ok());
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        dest_address,
+        TRANSFER_AMOUNT,
+        0.into(),
+    );
+}
+
+#[test]
+fn test_paused_precompile() {
+    let (mut runner, mut source_account, _) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let account_id = runner.aurora_account_id.clone();
+    let set_paused_precompiles = |runner: &mut test_utils::AuroraRunner, paused_mask| {
+        let args = aurora_engine::parameters::PausePrecompilesCallArgs { paused_mask };
+        let (_, maybe_error) = runner.call(
+            "set_paused_precompiles",
+            &account_id,
+            args.try_to_vec().unwrap(),
+        );
+        assert!(maybe_error.is_none());
+    };
+    let exit_to_ethereum = |nonce| {
+        let mut tx = test_utils::transfer(
+            aurora_engine_precompiles::native::ExitToEthereum::ADDRESS,
+            TRANSFER_AMOUNT,
+            nonce,
+        );
+        tx.data = [&[0x00], Address([0x11; 20]).as_bytes()].concat();
+        tx
+    };
+
+    set_paused_precompiles(
+        &mut runner,
+        aurora_engine::admin_controlled::PAUSE_EXIT_TO_ETHEREUM,
+    );
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("get_paused_precompiles", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    assert_eq!(
+        u32::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap(),
+        aurora_engine::admin_controlled::PAUSE_EXIT_TO_ETHEREUM
+    );
+
+    let error = runner
+        .submit_with_signer(&mut source_account, exit_to_ethereum)
+        .unwrap_err();
+    let error_message = format!("{:?}", error);
+    assert!(error_message.contains("ERR_PAUSED"));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+
+    // Flags of precompiles which can't be paused are rejected
+    let args = aurora_engine::parameters::PausePrecompilesCallArgs {
+        paused_mask: 1 << 31,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_paused_precompiles",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_UNKNOWN_PRECOMPILE_FLAGS"));
+
+    set_paused_precompiles(&mut runner, 0);
+    source_account.nonce = INITIAL_NONCE;
+    let result = runner
+        .submit_with_signer(&mut source_account, exit_to_ethereum)
+        .unwrap();
+    assert!(result.status.is_ok());
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE - TRANSFER_AMOUNT,
+        (INITIAL_NONCE + 1).into(),
+    );
+}
+
+#[test]
+fn test_eth_transfer_not_enough_gas() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let transaction = |nonce| {
+        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
+        tx.gas_limit = 10_000.into(); // this is not enough gas
+        tx
+    };
+
+    // validate pre-state
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+
+    // attempt transfer
+    let err = runner
+        .submit_with_signer(&mut source_account, transaction)
+        .unwrap_err();
+    let error_message = format!("{:?}", err);
+    assert!(error_message.contains("ERR_INTRINSIC_GAS"));
+
+    // validate post-state (which is the same as pre-state in this case)
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+}
+
+#[test]
+fn test_transfer_charging_gas_success() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let transaction = |nonce| {
+        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
+        tx.gas_limit = 30_000.into();
+        tx.gas_price = GAS_PRICE.into();
+        tx
+    };
+
+    // validate pre-state
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+
+    // do transfer
+    let result = runner
+        .submit_with_signer(&mut source_account, transaction)
+        .unwrap();
+    let spent_amount = Wei::new_u64(GAS_PRICE * result.gas_used);
+    let expected_source_balance = INITIAL_BALANCE - TRANSFER_AMOUNT - spent_amount;
+    let expected_dest_balance = TRANSFER_AMOUNT;
+    let expected_relayer_balance = spent_amount;
+    let relayer_address = sdk::types::near_account_to_evm_address(
+        runner.context.predecessor_account_id.as_ref().as_bytes(),
+    );
+
+    // validate post-state
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        expected_source_balance,
+        (INITIAL_NONCE + 1).into(),
+    );
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        dest_address,
+        expected_dest_balance,
+        0.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        relayer_address,
+        expected_relayer_balance,
+        0.into(),
+    );
+}
+
+#[test]
+fn test_priority_fee_split() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+    let treasury = Address([0x77; 20]);
+    let account_id = runner.aurora_account_id.clone();
+    let set_split = |runner: &mut test_utils::AuroraRunner, treasury_share| {
+        let args = aurora_engine::parameters::SetPriorityFeeSplitArgs {
+            split: Some(aurora_engine::parameters::PriorityFeeSplit {
+                treasury: treasury.0,
+                treasury_share,
+            }),
+        };
+        let (_, maybe_error) = runner.call(
+            "set_priority_fee_split",
+            &account_id,
+            args.try_to_vec().unwrap(),
+        );
+        maybe_error
+    };
+
+    // The share is in basis points
+    assert!(set_split(&mut runner, 10_001).is_some());
+    assert!(set_split(&mut runner, 2_500).is_none());
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("get_priority_fee_split", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    let split = Option::<aurora_engine::parameters::PriorityFeeSplit>::try_from_slice(
+        &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(split.treasury_share, 2_500);
+
+    let result = runner
+        .submit_with_signer(&mut source_account, |nonce| {
+            let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
+            tx.gas_limit = 30_000.into();
+            tx.gas_price = GAS_PRICE.into();
+            tx
+        })
+        .unwrap();
+    let reward = GAS_PRICE * result.gas_used;
+    let treasury_reward = Wei::new_u64(reward * 2_500 / 10_000);
+    let relayer_address = sdk::types::near_account_to_evm_address(
+        runner.context.predecessor_account_id.as_ref().as_bytes(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, treasury, treasury_reward, 0.into());
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        relayer_address,
+        Wei::new_u64(reward) - treasury_reward,
+        0.into(),
+    );
+}
+
+#[test]
+fn test_run_due_tasks() {
+    use aurora_engine::parameters::{
+        RunDueTasksArgs, RunDueTasksResult, ScheduledTask, ScheduledTaskKind, SetScheduledTasksArgs,
+    };
+
+    let mut runner = test_utils::deploy_evm();
+    let account_id = runner.aurora_account_id.clone();
+    let treasury = Address([0x77; 20]);
+    runner.create_address(treasury, INITIAL_BALANCE, INITIAL_NONCE.into());
+    let args = aurora_engine::parameters::SetPriorityFeeSplitArgs {
+        split: Some(aurora_engine::parameters::PriorityFeeSplit {
+            treasury: treasury.0,
+            treasury_share: 1_000,
+        }),
+    };
+    let (_, maybe_error) = runner.call(
+        "set_priority_fee_split",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    runner.context.block_index = 100;
+    let bloom_key = |block_height: u64| {
+        crate::prelude::storage::bytes_to_key(
+            crate::prelude::storage::KeyPrefix::LogBloom,
+            &block_height.to_le_bytes(),
+        )
+    };
+    for block_height in [10, 100].iter() {
+        runner.set_storage(bloom_key(*block_height), Some(vec![0; 256]));
+    }
+    runner.set_storage(
+        crate::prelude::storage::config_key(
+            crate::prelude::storage::ConfigKey::FirstLogsBloomHeight,
+            &[],
+        ),
+        Some(10u64.to_le_bytes().to_vec()),
+    );
+
+    let task = |interval_blocks| ScheduledTask {
+        kind: ScheduledTaskKind::PruneLogsBlooms {
+            retention_blocks: 5,
+            max_blocks_per_run: 100,
+        },
+        interval_blocks,
+        reward: Wei::new_u64(7).to_bytes(),
+    };
+    let set_tasks = |runner: &mut test_utils::AuroraRunner, tasks: Vec<ScheduledTask>| {
+        let args = SetScheduledTasksArgs { tasks };
+        let (_, maybe_error) = runner.call(
+            "set_scheduled_tasks",
+            &account_id,
+            args.try_to_vec().unwrap(),
+        );
+        maybe_error
+    };
+    assert!(set_tasks(&mut runner, vec![task(0)]).is_some());
+    assert!(set_tasks(&mut runner, vec![task(10)]).is_none());
+
+    let relayer_id = "relayer.near";
+    let relayer_address = sdk::types::near_account_to_evm_address(relayer_id.as_bytes());
+    let run_due_tasks = |runner: &mut test_utils::AuroraRunner| {
+        let args = RunDueTasksArgs { limit: 4 };
+        let (outcome, maybe_error) =
+            runner.call("run_due_tasks", relayer_id, args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+        RunDueTasksResult::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+    };
+
+    let result = run_due_tasks(&mut runner);
+    assert_eq!(result.executed, 1);
+    assert_eq!(Wei::from(result.reward), Wei::new_u64(7));
+    // Only the blooms older than the retention are pruned
+    assert!(!runner.ext.fake_trie.contains_key(&bloom_key(10)));
+    assert!(runner.ext.fake_trie.contains_key(&bloom_key(100)));
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        relayer_address,
+        Wei::new_u64(7),
+        0.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        treasury,
+        INITIAL_BALANCE - Wei::new_u64(7),
+        INITIAL_NONCE.into(),
+    );
+
+    // The task is not due again before its interval
+    let result = run_due_tasks(&mut runner);
+    assert_eq!(result.executed, 0);
+    assert_eq!(Wei::from(result.reward), Wei::zero());
+
+    // A run with nothing to prune is not rewarded
+    let mut no_op_task = task(10);
+    no_op_task.kind = ScheduledTaskKind::PruneLogsBlooms {
+        retention_blocks: 1_000,
+        max_blocks_per_run: 100,
+    };
+    assert!(set_tasks(&mut runner, vec![no_op_task]).is_none());
+    let result = run_due_tasks(&mut runner);
+    assert_eq!(result.executed, 1);
+    assert_eq!(Wei::from(result.reward), Wei::zero());
+    assert!(runner.ext.fake_trie.contains_key(&bloom_key(100)));
+}
+
+#[test]
+fn test_evm_memory_limit() {
+    use aurora_engine::parameters::EvmLimits;
+
+    let (mut runner, mut signer, _) = initialize_transfer();
+    let account_id = runner.aurora_account_id.clone();
+    let set_limits = |runner: &mut test_utils::AuroraRunner, limits: EvmLimits| {
+        let (_, maybe_error) =
+            runner.call("set_evm_limits", &account_id, limits.try_to_vec().unwrap());
+        maybe_error
+    };
+    // PUSH1 0x01 PUSH3 0x010000 MSTORE STOP, i.e. grows the memory to 64 KiB.
+    let init_code = hex::decode("600162010000520000").unwrap();
+    let mut deploy = |runner: &mut test_utils::AuroraRunner| {
+        runner
+            .submit_with_signer(&mut signer, |nonce| {
+                crate::prelude::transaction::legacy::TransactionLegacy {
+                    nonce,
+                    gas_price: Default::default(),
+                    gas_limit: u64::MAX.into(),
+                    to: None,
+                    value: Default::default(),
+                    data: init_code.clone(),
+                }
+            })
+            .unwrap()
+            .status
+    };
+
+    // The call depth is bounded by the one of Ethereum
+    let too_deep = EvmLimits {
+        max_call_depth: EvmLimits::MAX_CALL_DEPTH + 1,
+        max_memory_bytes: None,
+    };
+    assert!(set_limits(&mut runner, too_deep).is_some());
+    assert!(matches!(deploy(&mut runner), TransactionStatus::Succeed(_)));
+
+    let limits = EvmLimits {
+        max_call_depth: 64,
+        max_memory_bytes: Some(32 * 1024),
+    };
+    assert!(set_limits(&mut runner, limits).is_none());
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("get_evm_limits", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    assert_eq!(
+        EvmLimits::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap(),
+        limits
+    );
+
+    // The transaction fails with a status instead of trapping
+    assert_eq!(deploy(&mut runner), TransactionStatus::MemoryLimit);
+}
+
+#[test]
+fn test_eth_transfer_charging_gas_not_enough_balance() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+    let source_address = test_utils::address_from_secret_key(&source_account.secret_key);
+    let transaction = |nonce| {
+        let mut tx = test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce);
+        // With this gas limit and price the account does not
+        // have enough balance to cover the gas cost
+        tx.gas_limit = 3_000_000.into();
+        tx.gas_price = GAS_PRICE.into();
+        tx
+    };
+
+    // validate pre-state
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        INITIAL_NONCE.into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+
+    // attempt transfer
+    let result = runner
+        .submit_with_signer(&mut source_account, transaction)
+        .unwrap();
+    assert_eq!(result.status, TransactionStatus::OutOfFund);
+
+    // validate post-state
+    let relayer = sdk::types::near_account_to_evm_address(
+        runner.context.predecessor_account_id.as_ref().as_bytes(),
+    );
+
+    test_utils::validate_address_balance_and_nonce(
+        &runner,
+        source_address,
+        INITIAL_BALANCE,
+        // nonce is still incremented since the transaction was otherwise valid
+        (INITIAL_NONCE + 1).into(),
+    );
+    test_utils::validate_address_balance_and_nonce(&runner, dest_address, Wei::zero(), 0.into());
+    test_utils::validate_address_balance_and_nonce(&runner, relayer, Wei::zero(), 0.into());
+}
+
+fn initialize_transfer() -> (test_utils::AuroraRunner, test_utils::Signer, Address) {
+    // set up Aurora runner and accounts
+    let mut runner = test_utils::deploy_evm();
+    let mut rng = rand::thread_rng();
+    let source_account = SecretKey::random(&mut rng);
+    let source_address = test_utils::address_from_secret_key(&source_account);
+    runner.create_address(source_address, INITIAL_BALANCE, INITIAL_NONCE.into());
+    let dest_address = test_utils::address_from_secret_key(&SecretKey::random(&mut rng));
+    let mut signer = test_utils::Signer::new(source_account);
+    signer.nonce = INITIAL_NONCE;
+
+    (runner, signer, dest_address)
+}
+
+use sha3::Digest;
+
+#[test]
+fn check_selector() {
+    // Selector to call mint function in ERC 20 contract
+    //
+    // keccak("mint(address,uint256)".as_bytes())[..4];
+    let mut hasher = sha3::Keccak256::default();
+    hasher.update(b"mint(address,uint256)");
+    assert_eq!(hasher.finalize()[..4].to_vec(), ERC20_MINT_SELECTOR);
+}
+
+#[test]
+fn test_block_hash() {
+    let runner = test_utils::AuroraRunner::default();
+    let chain_id = {
+        let number = crate::prelude::U256::from(runner.chain_id);
+        crate::prelude::u256_to_arr(&number)
+    };
+    let account_id = runner.aurora_account_id;
+    let block_hash = aurora_engine::engine::compute_block_hash(chain_id, 10, account_id.as_bytes());
+
+    assert_eq!(
+        hex::encode(block_hash.0).as_str(),
+        "c4a46f076b64877cbd8c5dbfd7bfbbea21a5653b79e3b6d06b6dfb5c88f1c384",
+    );
+}
+
+#[test]
+fn test_block_hash_api() {
+    let mut runner = test_utils::deploy_evm();
+
+    let block_height: u64 = 10;
+    let (maybe_outcome, maybe_error) = runner.call(
+        "get_block_hash",
+        "any.near",
+        block_height.try_to_vec().unwrap(),
+    );
+    if let Some(error) = maybe_error {
+        panic!("Call failed: {:?}", error);
+    }
+    let outcome = maybe_outcome.unwrap();
+    let block_hash = outcome.return_data.as_value().unwrap();
+
+    assert_eq!(
+        hex::encode(&block_hash).as_str(),
+        "c4a46f076b64877cbd8c5dbfd7bfbbea21a5653b79e3b6d06b6dfb5c88f1c384",
+    );
+}
+
+#[test]
+fn test_block_hash_contract() {
+    let (mut runner, mut source_account, _) = initialize_transfer();
+    let test_constructor = test_utils::solidity::ContractConstructor::compile_from_source(
+        ["src", "tests", "res"].iter().collect::<PathBuf>(),
+        Path::new("target").join("solidity_build"),
+        "blockhash.sol",
+        "BlockHash",
+    );
+    let nonce = source_account.use_nonce();
+    let test_contract = runner.deploy_contract(
+        &source_account.secret_key,
+        |c| c.deploy_without_args(nonce.into()),
+        test_constructor,
+    );
+
+    let result = runner
+        .submit_with_signer(&mut source_account, |nonce| {
+            test_contract.call_method_without_args("test", nonce)
+        })
+        .unwrap();
+
+    test_utils::panic_on_fail(result.status);
+}
+
+#[test]
+fn test_ft_metadata() {
+    let mut runner = test_utils::deploy_evm();
+
+    let account_id: String = runner.context.signer_account_id.clone().into();
+    let (maybe_outcome, maybe_error) = runner.call("ft_metadata", &account_id, Vec::new());
+    assert!(maybe_error.is_none());
+    let outcome = maybe_outcome.unwrap();
+    let json_value =
+        aurora_engine::json::parse_json(&outcome.return_data.as_value().unwrap()).unwrap();
+
+    assert_eq!(
+        json_value,
+        aurora_engine::json::JsonValue::from(FungibleTokenMetadata::default())
+    );
+}
+
+#[test]
+fn test_set_metadata() {
+    let mut runner = test_utils::deploy_evm();
+
+    let owner_id = runner.aurora_account_id.clone();
+    let metadata = FungibleTokenMetadata {
+        name: "Bridged Ether".to_string(),
+        icon: None,
+        decimals: 18,
+        ..Default::default()
+    };
+    let (_, maybe_error) = runner.call(
+        "set_metadata",
+        "someone.near",
+        metadata.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_some());
+    let (_, maybe_error) = runner.call("set_metadata", &owner_id, metadata.try_to_vec().unwrap());
+    assert!(maybe_error.is_none());
+
+    let (maybe_outcome, maybe_error) = runner.call("ft_metadata", &owner_id, Vec::new());
+    assert!(maybe_error.is_none());
+    let json_value =
+        aurora_engine::json::parse_json(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap();
+    assert_eq!(json_value, aurora_engine::json::JsonValue::from(metadata));
+
+    let invalid_metadata = FungibleTokenMetadata {
+        symbol: String::new(),
+        ..Default::default()
+    };
+    let (_, maybe_error) = runner.call(
+        "set_metadata",
+        &owner_id,
+        invalid_metadata.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_METADATA_EMPTY_SYMBOL"));
+}
+
+#[test]
+fn test_two_step_ownership_transfer() {
+    use aurora_engine::parameters::ProposeOwnerArgs;
+
+    let mut runner = test_utils::deploy_evm();
+
+    let owner_id = runner.aurora_account_id.clone();
+    let new_owner_id = "new-owner.near";
+    let get_owner = |runner: &mut test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) = runner.call("get_owner", "someone.near", Vec::new());
+        assert!(maybe_error.is_none());
+        String::from_utf8(maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+    let propose = |new_owner_id: Option<&str>| {
+        ProposeOwnerArgs {
+            new_owner_id: new_owner_id.map(|id| id.parse().unwrap()),
+        }
+        .try_to_vec()
+        .unwrap()
+    };
+
+    let (_, maybe_error) = runner.call("accept_owner", new_owner_id, Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NO_PENDING_OWNER"));
+    let (_, maybe_error) = runner.call("propose_owner", new_owner_id, propose(Some(new_owner_id)));
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+
+    let (_, maybe_error) = runner.call("propose_owner", &owner_id, propose(Some(new_owner_id)));
+    assert!(maybe_error.is_none());
+    let (maybe_outcome, maybe_error) = runner.call("get_pending_owner", "someone.near", Vec::new());
+    assert!(maybe_error.is_none());
+    let pending_owner = Option::<aurora_engine_types::account_id::AccountId>::try_from_slice(
+        &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(pending_owner, Some(new_owner_id.parse().unwrap()));
+    // The proposal doesn't change the owner until it is accepted, by the proposed account only.
+    assert_eq!(get_owner(&mut runner), owner_id);
+    let (_, maybe_error) = runner.call("accept_owner", "someone.near", Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_PENDING_OWNER"));
+
+    let (_, maybe_error) = runner.call("accept_owner", new_owner_id, Vec::new());
+    assert!(maybe_error.is_none());
+    assert_eq!(get_owner(&mut runner), new_owner_id);
+    let (_, maybe_error) = runner.call("accept_owner", new_owner_id, Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NO_PENDING_OWNER"));
+
+    // The previous owner lost its privileges, and a cancelled proposal can't be accepted.
+    let (_, maybe_error) =
+        runner.call("propose_owner", &owner_id, propose(Some(owner_id.as_str())));
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (_, maybe_error) = runner.call(
+        "propose_owner",
+        new_owner_id,
+        propose(Some(owner_id.as_str())),
+    );
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call("propose_owner", new_owner_id, propose(None));
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call("accept_owner", &owner_id, Vec::new());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NO_PENDING_OWNER"));
+    assert_eq!(get_owner(&mut runner), new_owner_id);
+}
+
+#[test]
+fn test_roles() {
+    use aurora_engine::parameters::{HasRoleArgs, Role, SetRoleArgs};
+
+    let mut runner = test_utils::deploy_evm();
+
+    let owner_id = runner.aurora_account_id.clone();
+    let manager_id = "metadata-manager.near";
+    let set_role = |runner: &mut test_utils::AuroraRunner, caller: &str, granted| {
+        let args = SetRoleArgs {
+            role: Role::MetadataManager,
+            account_id: manager_id.parse().unwrap(),
+            granted,
+        };
+        runner.call("set_role", caller, args.try_to_vec().unwrap())
+    };
+    let has_role = |runner: &mut test_utils::AuroraRunner, role, account_id: &str| {
+        let args = HasRoleArgs {
+            role,
+            account_id: account_id.parse().unwrap(),
+        };
+        let (maybe_outcome, maybe_error) =
+            runner.call("has_role", "someone.near", args.try_to_vec().unwrap());
+        assert!(maybe_error.is_none());
+        bool::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+    let metadata = FungibleTokenMetadata {
+        name: "Bridged Ether".to_string(),
+        ..Default::default()
+    }
+    .try_to_vec()
+    .unwrap();
+
+    assert!(has_role(&mut runner, Role::UpgradeManager, &owner_id));
+    assert!(!has_role(&mut runner, Role::MetadataManager, manager_id));
+    let (_, maybe_error) = runner.call("set_metadata", manager_id, metadata.clone());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (_, maybe_error) = set_role(&mut runner, manager_id, true);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+
+    let (_, maybe_error) = set_role(&mut runner, &owner_id, true);
+    assert!(maybe_error.is_none());
+    assert!(has_role(&mut runner, Role::MetadataManager, manager_id));
+    assert!(!has_role(&mut runner, Role::PauseManager, manager_id));
+    let (_, maybe_error) = runner.call("set_metadata", manager_id, metadata.clone());
+    assert!(maybe_error.is_none());
+    // The role is limited to its operations.
+    let args = aurora_engine::parameters::PauseEthConnectorCallArgs { paused_mask: 0 };
+    let (_, maybe_error) = runner.call("set_paused_flags", manager_id, args.try_to_vec().unwrap());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+
+    let (_, maybe_error) = set_role(&mut runner, &owner_id, false);
+    assert!(maybe_error.is_none());
+    assert!(!has_role(&mut runner, Role::MetadataManager, manager_id));
+    let (_, maybe_error) = runner.call("set_metadata", manager_id, metadata);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+}
 
 #[test]
-fn test_block_hash_contract() {
-    let (mut runner, mut source_account, _) = initialize_transfer();
-    let test_constructor = test_utils::solidity::ContractConstructor::compile_from_source(
-        ["src", "tests", "res"].iter().collect::<PathBuf>(),
-        Path::new("target").join("solidity_build"),
-        "blockhash.sol",
-        "BlockHash",
+fn test_input_size_limits() {
+    use aurora_engine::input_limits::{DEFAULT_MAX_INPUT_LEN, MAX_TRANSACTION_INPUT_LEN};
+
+    let mut runner = test_utils::deploy_evm();
+
+    let (_, maybe_error) = runner.call(
+        "register_relayer",
+        "relay.near",
+        vec![0; DEFAULT_MAX_INPUT_LEN + 1],
     );
-    let nonce = source_account.use_nonce();
-    let test_contract = runner.deploy_contract(
-        &source_account.secret_key,
-        |c| c.deploy_without_args(nonce.into()),
-        test_constructor,
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INPUT_TOO_LARGE"));
+
+    // Below its limit, the input is parsed and rejected for being invalid
+    let (_, maybe_error) = runner.call("submit", "relay.near", vec![0; DEFAULT_MAX_INPUT_LEN + 1]);
+    let error = format!("{:?}", maybe_error.unwrap());
+    assert!(!error.contains("INPUT_TOO_LARGE"));
+    let (_, maybe_error) = runner.call(
+        "submit",
+        "relay.near",
+        vec![0; MAX_TRANSACTION_INPUT_LEN + 1],
     );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_TRANSACTION_INPUT_TOO_LARGE"));
+
+    // The owner can override the limit of a method.
+    let owner_id = runner.aurora_account_id.clone();
+    let set_input_limit = |runner: &mut test_utils::AuroraRunner,
+                           account_id: &str,
+                           method: &str,
+                           max_len: Option<u64>| {
+        let args = aurora_engine::parameters::SetInputLimitArgs {
+            method: method.to_string(),
+            max_len,
+        };
+        runner.call("set_input_limit", account_id, args.try_to_vec().unwrap())
+    };
+    let (_, maybe_error) = set_input_limit(&mut runner, "relay.near", "submit", Some(100));
+    assert!(maybe_error.is_some());
+    let (_, maybe_error) = set_input_limit(&mut runner, &owner_id, "submit", Some(100));
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call("submit", "relay.near", vec![0; 101]);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_TRANSACTION_INPUT_TOO_LARGE"));
 
-    let result = runner
+    let (_, maybe_error) = set_input_limit(&mut runner, &owner_id, "submit", None);
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call("submit", "relay.near", vec![0; 101]);
+    assert!(!format!("{:?}", maybe_error.unwrap()).contains("INPUT_TOO_LARGE"));
+
+    let (_, maybe_error) = set_input_limit(&mut runner, &owner_id, "set_input_limit", Some(0));
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INPUT_LIMIT_NOT_CONFIGURABLE"));
+}
+
+#[test]
+fn test_ft_metadata_chunked_icon() {
+    let mut runner = test_utils::deploy_evm();
+
+    let owner_id = runner.aurora_account_id.clone();
+    let set_icon_chunk = |runner: &mut test_utils::AuroraRunner, index: u32, chunk: &str| {
+        let args = aurora_engine::parameters::SetIconChunkArgs {
+            index,
+            chunk: chunk.as_bytes().to_vec(),
+        };
+        runner.call("set_icon_chunk", &owner_id, args.try_to_vec().unwrap())
+    };
+    let get_icon = |runner: &mut test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) = runner.call("ft_metadata", &owner_id, Vec::new());
+        assert!(maybe_error.is_none());
+        let outcome = maybe_outcome.unwrap();
+        let json_value =
+            aurora_engine::json::parse_json(&outcome.return_data.as_value().unwrap()).unwrap();
+        json_value.string("icon").unwrap()
+    };
+
+    let (_, maybe_error) = set_icon_chunk(&mut runner, 0, "data:image/svg+xml;");
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = set_icon_chunk(&mut runner, 1, "base64,PHN2Zz4=");
+    assert!(maybe_error.is_none());
+    assert_eq!(get_icon(&mut runner), "data:image/svg+xml;base64,PHN2Zz4=");
+
+    // Chunks can't be skipped
+    let (_, maybe_error) = set_icon_chunk(&mut runner, 3, "AAAA");
+    assert!(maybe_error.is_some());
+
+    // Rewriting the first chunk starts a new icon
+    let (_, maybe_error) = set_icon_chunk(&mut runner, 0, "data:,");
+    assert!(maybe_error.is_none());
+    assert_eq!(get_icon(&mut runner), "data:,");
+
+    // Only the owner can set the icon
+    let (_, maybe_error) = runner.call(
+        "set_icon_chunk",
+        "someone.near",
+        aurora_engine::parameters::SetIconChunkArgs {
+            index: 0,
+            chunk: b"data:,".to_vec(),
+        }
+        .try_to_vec()
+        .unwrap(),
+    );
+    assert!(maybe_error.is_some());
+}
+
+#[test]
+fn test_get_storage_breakdown() {
+    let (mut runner, mut source_account, dest_address) = initialize_transfer();
+
+    let get_storage_breakdown = |runner: &test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_storage_breakdown", "some-account.near", Vec::new());
+        assert!(maybe_error.is_none());
+        Option::<aurora_engine::parameters::StorageBreakdown>::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(get_storage_breakdown(&runner).is_none());
+
+    let account_id = runner.aurora_account_id.clone();
+    let args = aurora_engine::parameters::SetStorageBreakdownEnabledArgs { enabled: true };
+    let (_, maybe_error) = runner.call(
+        "set_storage_breakdown_enabled",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert_eq!(
+        get_storage_breakdown(&runner),
+        Some(aurora_engine::parameters::StorageBreakdown::default())
+    );
+
+    // The transfer creates the nonce and the balance of the destination account
+    runner
         .submit_with_signer(&mut source_account, |nonce| {
-            test_contract.call_method_without_args("test", nonce)
+            test_utils::transfer(dest_address, TRANSFER_AMOUNT, nonce)
         })
         .unwrap();
+    let breakdown = get_storage_breakdown(&runner).unwrap();
+    // each record is made of the version byte, prefix byte and address, a 32 bytes value
+    // and the record overhead
+    assert_eq!(breakdown.evm_accounts, 2 * (22 + 32 + 40));
+    assert_eq!(breakdown.eth_connector, 0);
+
+    let args = aurora_engine::parameters::SetStorageBreakdownEnabledArgs { enabled: false };
+    let (_, maybe_error) = runner.call(
+        "set_storage_breakdown_enabled",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert!(get_storage_breakdown(&runner).is_none());
+}
 
-    test_utils::panic_on_fail(result.status);
+#[test]
+fn test_capabilities() {
+    let mut runner = test_utils::deploy_evm();
+
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("list_capabilities", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    let capabilities =
+        Vec::<String>::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap();
+    assert!(capabilities.contains(&"evm".to_string()));
+    assert!(capabilities.contains(&"eth-connector".to_string()));
+    assert!(!capabilities.contains(&"log-bloom-index".to_string()));
+
+    let supports_capability = |runner: &test_utils::AuroraRunner, name: &str| {
+        let args = aurora_engine::parameters::SupportsCapabilityCallArgs {
+            name: name.to_string(),
+        };
+        let (maybe_outcome, maybe_error) = runner.one_shot().call(
+            "supports_capability",
+            "some-account.near",
+            args.try_to_vec().unwrap(),
+        );
+        assert!(maybe_error.is_none());
+        bool::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+    assert!(supports_capability(&runner, "eip-1559"));
+    assert!(!supports_capability(&runner, "unknown"));
+    assert!(!supports_capability(&runner, "log-bloom-index"));
+
+    // Capabilities enabled in the configuration are discovered at runtime.
+    let account_id = runner.aurora_account_id.clone();
+    let args = aurora_engine::parameters::SetLogBloomIndexArgs { enabled: true };
+    let (_, maybe_error) = runner.call(
+        "set_log_bloom_index_enabled",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert!(supports_capability(&runner, "log-bloom-index"));
 }
 
 #[test]
-fn test_ft_metadata() {
+fn test_host_functions() {
+    use aurora_engine_precompiles::HostFunctions;
+
+    let mut runner = test_utils::deploy_evm();
+    let account_id = runner.aurora_account_id.clone();
+    let get_host_functions = |runner: &test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_host_functions", "some-account.near", Vec::new());
+        assert!(maybe_error.is_none());
+        HostFunctions::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+    };
+    let ecrecover = |runner: &test_utils::AuroraRunner| {
+        let input = hex::decode("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad000000000000000000000000000000000000000000000000000000000000001b650acf9d3f5f0a2c799776a1254355d5f4061762a237396a99a0e0e3fc2bcd6729514a0dacb2e623ac4abd157cb18163ff942280db4d5caad66ddf941ba12e03").unwrap();
+        let mut address = [0u8; 20];
+        address[19] = 1;
+        let args = aurora_engine::parameters::ViewCallArgs {
+            sender: [0u8; 20],
+            address,
+            amount: [0u8; 32],
+            input,
+        };
+        match runner.view_call(args).unwrap() {
+            TransactionStatus::Succeed(bytes) => bytes,
+            status => panic!("Unexpected view call status {:?}", status),
+        }
+    };
+    assert_eq!(get_host_functions(&runner), HostFunctions::default());
+    let expected = ecrecover(&runner);
+
+    let host_functions = HostFunctions {
+        ecrecover: false,
+        alt_bn128: false,
+        ed25519: true,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_host_functions",
+        "some-account.near",
+        host_functions.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_some());
+    let (_, maybe_error) = runner.call(
+        "set_host_functions",
+        &account_id,
+        host_functions.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert_eq!(get_host_functions(&runner), host_functions);
+
+    let (maybe_outcome, maybe_error) =
+        runner
+            .one_shot()
+            .call("list_capabilities", "some-account.near", Vec::new());
+    assert!(maybe_error.is_none());
+    let capabilities =
+        Vec::<String>::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap();
+    assert!(!capabilities.contains(&"host-ecrecover".to_string()));
+    assert!(capabilities.contains(&"host-ed25519".to_string()));
+
+    // The precompile falls back to the pure-Rust implementation, with the same result
+    assert_eq!(ecrecover(&runner), expected);
+}
+
+#[test]
+fn test_multisig_call() {
+    use aurora_engine::multisig::MultisigWallet;
+    use aurora_engine::parameters::{
+        CreateMultisigCallArgs, FreezeTarget, MultisigIdCallArgs, MultisigProposalCallArgs,
+        ProposeMultisigCallArgs,
+    };
+
+    let mut runner = test_utils::deploy_evm();
+    runner.context.attached_deposit = 1;
+    let owners = ["alice.near", "bob.near"];
+    let create_args = CreateMultisigCallArgs {
+        owners: owners.iter().map(|owner| owner.parse().unwrap()).collect(),
+        threshold: 2,
+    };
+    let (maybe_outcome, maybe_error) = runner.call(
+        "create_multisig",
+        "carol.near",
+        create_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let multisig_id =
+        u64::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    let (maybe_outcome, maybe_error) = runner.one_shot().call(
+        "get_multisig",
+        "carol.near",
+        MultisigIdCallArgs { multisig_id }.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let wallet = Option::<MultisigWallet>::try_from_slice(
+        &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(wallet.threshold, 2);
+
+    let propose_args = ProposeMultisigCallArgs {
+        multisig_id,
+        contract: [0x11; 20],
+        value: [0u8; 32],
+        input: Vec::new(),
+    };
+    let (_, maybe_error) = runner.call(
+        "propose_multisig_call",
+        "carol.near",
+        propose_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (maybe_outcome, maybe_error) = runner.call(
+        "propose_multisig_call",
+        owners[0],
+        propose_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let proposal_id =
+        u64::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    let proposal_args = MultisigProposalCallArgs {
+        multisig_id,
+        proposal_id,
+    };
+
+    // A single approval is below the threshold
+    let (_, maybe_error) = runner.call(
+        "execute_multisig_call",
+        owners[0],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ENOUGH_APPROVALS"));
+    let (_, maybe_error) = runner.call(
+        "approve_multisig_call",
+        owners[0],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_ALREADY_APPROVED"));
+
+    // Function call access keys can't approve
+    runner.context.attached_deposit = 0;
+    let (_, maybe_error) = runner.call(
+        "approve_multisig_call",
+        owners[1],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_some());
+    runner.context.attached_deposit = 1;
+    let (_, maybe_error) = runner.call(
+        "approve_multisig_call",
+        owners[1],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    // The engine rejects a call from a frozen wallet, the receipt fails and the call stays
+    // approved to be made again
+    let target = FreezeTarget::Evm(aurora_engine::multisig::multisig_address(multisig_id).0);
+    let owner_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call("freeze_account", &owner_id, target.try_to_vec().unwrap());
+    assert!(maybe_error.is_none());
+    let (_, maybe_error) = runner.call(
+        "execute_multisig_call",
+        owners[1],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_ACCOUNT_FROZEN"));
+    let (_, maybe_error) = runner.call("unfreeze_account", &owner_id, target.try_to_vec().unwrap());
+    assert!(maybe_error.is_none());
+
+    let (maybe_outcome, maybe_error) = runner.call(
+        "execute_multisig_call",
+        owners[1],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    let result =
+        SubmitResult::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap();
+    assert!(matches!(result.status, TransactionStatus::Succeed(_)));
+
+    // The call is made only once
+    let (_, maybe_error) = runner.call(
+        "execute_multisig_call",
+        owners[1],
+        proposal_args.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_PROPOSAL_NOT_FOUND"));
+}
+
+#[test]
+fn test_corrupted_connector_state() {
+    let mut runner = test_utils::deploy_evm();
+    let paused_mask_key = crate::prelude::storage::bytes_to_key(
+        crate::prelude::storage::KeyPrefix::EthConnector,
+        &[crate::prelude::storage::EthConnectorStorageId::PausedMask as u8],
+    );
+    let ft_total_supply = |runner: &test_utils::AuroraRunner| {
+        let (_, maybe_error) =
+            runner
+                .one_shot()
+                .call("ft_total_supply", "some-account.near", Vec::new());
+        maybe_error.map(|e| format!("{:?}", e))
+    };
+    assert!(ft_total_supply(&runner).is_none());
+
+    // The paused mask is a `u8`
+    runner.set_storage(paused_mask_key.clone(), Some(vec![0, 0]));
+    assert!(ft_total_supply(&runner)
+        .unwrap()
+        .contains("ERR_STATE_WRONG_LENGTH"));
+
+    runner.set_storage(paused_mask_key.clone(), Some(Vec::new()));
+    assert!(ft_total_supply(&runner)
+        .unwrap()
+        .contains("ERR_STATE_WRONG_LENGTH"));
+
+    runner.set_storage(paused_mask_key, None);
+    assert!(ft_total_supply(&runner)
+        .unwrap()
+        .contains("ERR_STATE_MISSING_KEY"));
+
+    assert_eq!(
+        aurora_engine_sdk::io::deserialize_state::<bool>(&[2]),
+        Err(aurora_engine_sdk::error::StateCorruption::Borsh)
+    );
+    assert_eq!(
+        aurora_engine_sdk::io::deserialize_state::<bool>(&[1]),
+        Ok(true)
+    );
+}
+
+#[test]
+fn test_migrate_config_keys() {
+    use crate::prelude::storage::{config_key, legacy_config_key, ConfigKey};
+
+    let mut runner = test_utils::deploy_evm();
+    let get_window = |runner: &test_utils::AuroraRunner| {
+        let (outcome, maybe_error) = runner.one_shot().call(
+            "get_duplicate_submit_window",
+            "some-account.near",
+            Vec::new(),
+        );
+        assert!(maybe_error.is_none());
+        u64::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+
+    let legacy_key = legacy_config_key(ConfigKey::DuplicateSubmitWindow, &[]).unwrap();
+    runner.set_storage(legacy_key.clone(), Some(25u64.to_le_bytes().to_vec()));
+    // Values written before the keys were namespaced are still read
+    assert_eq!(get_window(&runner), 25);
+
+    let account_id = runner.aurora_account_id.clone();
+    let (_, maybe_error) = runner.call("migrate_config_keys", "some-account.near", Vec::new());
+    assert!(maybe_error.is_some());
+    let (outcome, maybe_error) = runner.call("migrate_config_keys", &account_id, Vec::new());
+    assert!(maybe_error.is_none());
+    let migrated = u32::try_from_slice(&outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert_eq!(migrated, 1);
+
+    assert!(!runner.ext.fake_trie.contains_key(&legacy_key));
+    assert!(runner
+        .ext
+        .fake_trie
+        .contains_key(&config_key(ConfigKey::DuplicateSubmitWindow, &[])));
+    assert_eq!(get_window(&runner), 25);
+}
+
+#[test]
+fn test_apply_state_diff() {
+    use aurora_engine::parameters::{ApplyStateDiffArgs, StateDiffResult};
+
+    let mut runner = test_utils::deploy_evm();
+    let removed_key = b"\x07\x00MIGRATION_OLD".to_vec();
+    let written_key = b"\x07\x00MIGRATION_NEW".to_vec();
+    runner.set_storage(removed_key.clone(), Some(b"old".to_vec()));
+    let diff = vec![
+        (written_key.clone(), Some(b"new".to_vec())),
+        (removed_key.clone(), None),
+    ];
+    let apply = |runner: &mut test_utils::AuroraRunner, account_id: &str, dry_run: bool| {
+        let args = ApplyStateDiffArgs {
+            diff: diff.clone(),
+            dry_run,
+        };
+        let (outcome, maybe_error) =
+            runner.call("apply_state_diff", account_id, args.try_to_vec().unwrap());
+        match maybe_error {
+            Some(error) => Err(error),
+            None => Ok(StateDiffResult::try_from_slice(
+                &outcome.unwrap().return_data.as_value().unwrap(),
+            )
+            .unwrap()),
+        }
+    };
+
+    assert!(apply(&mut runner, "some-account.near", false).is_err());
+
+    let account_id = runner.aurora_account_id.clone();
+    let expected = StateDiffResult {
+        written: 1,
+        removed: 1,
+        digest: sdk::sha256(&diff.try_to_vec().unwrap()).0,
+    };
+    // A dry run reports the changes without applying them
+    assert_eq!(apply(&mut runner, &account_id, true).unwrap(), expected);
+    assert!(!runner.ext.fake_trie.contains_key(&written_key));
+    assert!(runner.ext.fake_trie.contains_key(&removed_key));
+
+    assert_eq!(apply(&mut runner, &account_id, false).unwrap(), expected);
+    assert_eq!(
+        runner.ext.fake_trie.get(&written_key),
+        Some(&b"new".to_vec())
+    );
+    assert!(!runner.ext.fake_trie.contains_key(&removed_key));
+
+    let args = ApplyStateDiffArgs {
+        diff: vec![(Vec::new(), None)],
+        dry_run: true,
+    };
+    let (_, maybe_error) = runner.call("apply_state_diff", &account_id, args.try_to_vec().unwrap());
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_STATE_DIFF_EMPTY_KEY"));
+
+    // The ownership and the code of the engine can't be changed by a diff
+    let state_key =
+        crate::prelude::storage::bytes_to_key(crate::prelude::storage::KeyPrefix::Config, b"STATE");
+    let state = runner.ext.fake_trie.get(&state_key).cloned().unwrap();
+    let code_key =
+        crate::prelude::storage::bytes_to_key(crate::prelude::storage::KeyPrefix::Config, b"CODE");
+    for key in [state_key.clone(), code_key].iter() {
+        let args = ApplyStateDiffArgs {
+            diff: vec![(key.clone(), Some(b"forged".to_vec()))],
+            dry_run: false,
+        };
+        let (_, maybe_error) =
+            runner.call("apply_state_diff", &account_id, args.try_to_vec().unwrap());
+        assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_STATE_DIFF_PROTECTED_KEY"));
+    }
+    assert_eq!(runner.ext.fake_trie.get(&state_key), Some(&state));
+}
+
+#[test]
+fn test_get_full_config() {
+    let mut runner = test_utils::deploy_evm();
+    let get_full_config = |runner: &test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_full_config", "some-account.near", Vec::new());
+        assert!(maybe_error.is_none());
+        aurora_engine::parameters::FullConfig::try_from_slice(
+            &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+        )
+        .unwrap()
+    };
+
+    let config = get_full_config(&runner);
+
+    assert_eq!(
+        config.version,
+        aurora_engine::parameters::FullConfig::VERSION
+    );
+    assert_eq!(
+        config.engine_state.owner_id,
+        test_utils::str_to_account_id(&runner.aurora_account_id)
+    );
+    assert_eq!(config.evm_hard_fork, "london");
+    assert!(config.eth_connector.is_some());
+    assert_eq!(config.paused_mask, Some(0));
+    let metadata_hash = sdk::sha256(&FungibleTokenMetadata::default().try_to_vec().unwrap());
+    assert_eq!(config.metadata_hash, Some(metadata_hash.0));
+    assert_eq!(config.duplicate_submit_window, 0);
+    assert_eq!(config.witness_size_limit, None);
+    assert!(!config.log_bloom_index_enabled);
+    assert_eq!(config.deposit_protocol_fee, 0);
+    assert_eq!(config.provers.len(), 1);
+
+    // The settings made by the owner are part of the document
+    let account_id = runner.aurora_account_id.clone();
+    let args = aurora_engine::parameters::SetWitnessSizeLimitArgs { limit: Some(1_024) };
+    let (_, maybe_error) = runner.call(
+        "set_witness_size_limit",
+        &account_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert_eq!(get_full_config(&runner).witness_size_limit, Some(1_024));
+}
+
+#[test]
+fn test_reassign_unclaimed() {
+    use aurora_engine::connector::UNCLAIMED_REASSIGN_DELAY_BLOCKS;
+    use aurora_engine::parameters::{
+        DepositReceipt, ReassignUnclaimedArgs, SetUnclaimedDepositArgs,
+    };
+    use aurora_engine_types::storage::{bytes_to_key, EthConnectorStorageId, KeyPrefix};
+
+    let mut runner = test_utils::deploy_evm();
+    let owner_id = runner.aurora_account_id.clone();
+    let recipient_id = "recipient.near";
+    let new_recipient_id = "new_recipient.near";
+    let deposit_id = "deposit_proof_key";
+
+    // The recipient is credited nETH, as if the deposit was minted for it.
+    runner.create_address(Address([1u8; 20]), Wei::new_u64(1_000), U256::zero());
+    runner.context.attached_deposit = 1;
+    let transfer_args = serde_json::json!({ "receiver_id": recipient_id, "amount": "700" });
+    let (_, maybe_error) = runner.call(
+        "ft_transfer",
+        &owner_id,
+        transfer_args.to_string().into_bytes(),
+    );
+    assert!(maybe_error.is_none());
+    runner.context.attached_deposit = 0;
+    let receipt = DepositReceipt {
+        recipient_id: recipient_id.parse().unwrap(),
+        amount: 700,
+        fee: 100.into(),
+        protocol_fee: 0,
+        memo: None,
+    };
+    runner.set_storage(
+        [
+            bytes_to_key(
+                KeyPrefix::EthConnector,
+                &[EthConnectorStorageId::DepositReceipt as u8],
+            ),
+            deposit_id.as_bytes().to_vec(),
+        ]
+        .concat(),
+        Some(receipt.try_to_vec().unwrap()),
+    );
+
+    runner.context.block_index = 10;
+    let args = SetUnclaimedDepositArgs {
+        deposit_id: deposit_id.to_string(),
+        flagged: true,
+    };
+    let (_, maybe_error) = runner.call(
+        "set_unclaimed_deposit",
+        &owner_id,
+        args.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+
+    let reassign = |runner: &mut test_utils::AuroraRunner, account_id: &str| {
+        let args = ReassignUnclaimedArgs {
+            deposit_id: deposit_id.to_string(),
+            new_recipient: new_recipient_id.parse().unwrap(),
+        };
+        runner.call("reassign_unclaimed", account_id, args.try_to_vec().unwrap())
+    };
+    let (_, maybe_error) = reassign(&mut runner, &owner_id);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_REASSIGN_DELAY_NOT_ELAPSED"));
+
+    runner.context.block_index = 10 + UNCLAIMED_REASSIGN_DELAY_BLOCKS;
+    let (_, maybe_error) = reassign(&mut runner, recipient_id);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (maybe_outcome, maybe_error) = reassign(&mut runner, &owner_id);
+    assert!(maybe_error.is_none());
+    // The amount net of the relayer fee is moved.
+    let amount =
+        u128::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap();
+    assert_eq!(amount, 600);
+
+    let ft_balance_of = |runner: &test_utils::AuroraRunner, account_id: &str| {
+        let args = serde_json::json!({ "account_id": account_id });
+        let (maybe_outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("ft_balance_of", account_id, args.to_string().into_bytes());
+        assert!(maybe_error.is_none());
+        String::from_utf8(maybe_outcome.unwrap().return_data.as_value().unwrap()).unwrap()
+    };
+    assert_eq!(ft_balance_of(&runner, recipient_id), "\"100\"");
+    assert_eq!(ft_balance_of(&runner, new_recipient_id), "\"600\"");
+
+    let (_, maybe_error) = reassign(&mut runner, &owner_id);
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_DEPOSIT_ALREADY_REASSIGNED"));
+}
+
+#[test]
+fn test_set_deposit_fee_currency() {
+    use aurora_engine::parameters::{DepositFeeCurrency, FeeConversionRoute};
+
+    let mut runner = test_utils::deploy_evm();
+    let owner_id = runner.aurora_account_id.clone();
+    let get_deposit_fee_currency = |runner: &test_utils::AuroraRunner| {
+        let (maybe_outcome, maybe_error) =
+            runner
+                .one_shot()
+                .call("get_deposit_fee_currency", "some-account.near", Vec::new());
+        assert!(maybe_error.is_none());
+        DepositFeeCurrency::try_from_slice(&maybe_outcome.unwrap().return_data.as_value().unwrap())
+            .unwrap()
+    };
+    assert_eq!(get_deposit_fee_currency(&runner), DepositFeeCurrency::Eth);
+
+    let mut route = FeeConversionRoute {
+        router: [0x11; 20],
+        path: vec![[0x22; 20], [0x33; 20]],
+        min_amount_out_per_eth: aurora_engine_types::types::u256_to_arr(&U256::from(1)),
+        gas_limit: 100_000,
+    };
+    let currency = DepositFeeCurrency::BaseToken(route.clone());
+    let (_, maybe_error) = runner.call(
+        "set_deposit_fee_currency",
+        "some-account.near",
+        currency.try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_NOT_ALLOWED"));
+    let (_, maybe_error) = runner.call(
+        "set_deposit_fee_currency",
+        &owner_id,
+        currency.try_to_vec().unwrap(),
+    );
+    assert!(maybe_error.is_none());
+    assert_eq!(get_deposit_fee_currency(&runner), currency);
+
+    // An unbounded swap is rejected.
+    route.gas_limit = 0;
+    let (_, maybe_error) = runner.call(
+        "set_deposit_fee_currency",
+        &owner_id,
+        DepositFeeCurrency::BaseToken(route).try_to_vec().unwrap(),
+    );
+    assert!(format!("{:?}", maybe_error.unwrap()).contains("ERR_INVALID_FEE_CONVERSION_ROUTE"));
+    assert_eq!(get_deposit_fee_currency(&runner), currency);
+}
+
+#[test]
+fn test_get_burned_total() {
     let mut runner = test_utils::deploy_evm();
 
     let account_id: String = runner.context.signer_account_id.clone().into();
-    let (maybe_outcome, maybe_error) = runner.call("ft_metadata", &account_id, Vec::new());
+    let (maybe_outcome, maybe_error) = runner.call("get_burned_total", &account_id, Vec::new());
     assert!(maybe_error.is_none());
     let outcome = maybe_outcome.unwrap();
-    let json_value =
-        aurora_engine::json::parse_json(&outcome.return_data.as_value().unwrap()).unwrap();
+    let burned_total = aurora_engine::parameters::BurnedTotal::try_from_slice(
+        &outcome.return_data.as_value().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(burned_total.burned_on_aurora, [0u8; 32]);
+    assert_eq!(burned_total.burned_on_near, 0);
+}
+
+#[test]
+fn test_get_engine_log_topics() {
+    let mut runner = test_utils::deploy_evm();
+
+    let account_id: String = runner.context.signer_account_id.clone().into();
+    let (maybe_outcome, maybe_error) =
+        runner.call("get_engine_log_topics", &account_id, Vec::new());
+    assert!(maybe_error.is_none());
+    let topics = Vec::<aurora_engine::parameters::EngineLogTopic>::try_from_slice(
+        &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+    )
+    .unwrap();
 
+    let signatures: Vec<&str> = topics.iter().map(|t| t.signature.as_str()).collect();
     assert_eq!(
-        json_value,
-        aurora_engine::json::JsonValue::from(FungibleTokenMetadata::default())
+        signatures,
+        vec![
+            "DepositCredited(address,uint256,uint256)",
+            "ExitCompleted(address,address,uint256,bool)",
+            "XccResult(address,bool,bytes)"
+        ]
+    );
+    for topic in topics.iter() {
+        assert_eq!(
+            topic.topic,
+            aurora_engine_sdk::keccak(topic.signature.as_bytes()).0
+        );
+    }
+}
+
+#[test]
+fn test_list_system_addresses() {
+    use aurora_engine::parameters::{SystemAddress, SystemAddressKind};
+
+    let mut runner = test_utils::deploy_evm();
+
+    let account_id: String = runner.context.signer_account_id.clone().into();
+    let (maybe_outcome, maybe_error) =
+        runner.call("list_system_addresses", &account_id, Vec::new());
+    assert!(maybe_error.is_none());
+    let addresses = Vec::<SystemAddress>::try_from_slice(
+        &maybe_outcome.unwrap().return_data.as_value().unwrap(),
+    )
+    .unwrap();
+
+    let kind_of = |address: Address| {
+        addresses
+            .iter()
+            .find(|system_address| system_address.address == address.0)
+            .map(|system_address| system_address.kind)
+    };
+    assert_eq!(
+        kind_of(aurora_engine_precompiles::native::ExitToNear::ADDRESS),
+        Some(SystemAddressKind::ExitRouter)
+    );
+    assert_eq!(
+        kind_of(aurora_engine_precompiles::native::ExitToEthereum::ADDRESS),
+        Some(SystemAddressKind::ExitRouter)
+    );
+    assert_eq!(
+        kind_of(aurora_engine_precompiles::random::RandomSeed::ADDRESS),
+        Some(SystemAddressKind::Precompile)
+    );
+    let engine_address =
+        aurora_engine_sdk::types::near_account_to_evm_address(runner.aurora_account_id.as_bytes());
+    assert_eq!(
+        kind_of(engine_address),
+        Some(SystemAddressKind::EngineAdmin)
+    );
+    assert_eq!(
+        kind_of(aurora_engine::engine_logs::ENGINE_LOGS_ADDRESS),
+        Some(SystemAddressKind::EngineLogs)
+    );
+    assert_eq!(
+        addresses
+            .iter()
+            .filter(|system_address| system_address.kind == SystemAddressKind::Precompile)
+            .count(),
+        13
     );
 }
 
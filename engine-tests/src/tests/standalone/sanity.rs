@@ -1,9 +1,9 @@
-use crate::test_utils::standalone::mocks::{promise, storage};
 use aurora_engine::engine;
 use aurora_engine_sdk::env::DEFAULT_PREPAID_GAS;
+use aurora_engine_sdk::memory;
 use aurora_engine_types::types::Wei;
 use aurora_engine_types::{account_id::AccountId, Address, H256, U256};
-use std::sync::RwLock;
+use std::cell::RefCell;
 
 #[test]
 fn test_deploy_code() {
@@ -21,8 +21,8 @@ fn test_deploy_code() {
         upgrade_delay_blocks: 0,
     };
     let origin = Address([0u8; 20]);
-    let storage = RwLock::new(storage::Storage::default());
-    let io = storage::StoragePointer(&storage);
+    let storage = RefCell::new(memory::Storage::default());
+    let io = memory::StoragePointer(&storage);
     let env = aurora_engine_sdk::env::Fixed {
         signer_account_id: owner_id.clone(),
         current_account_id: owner_id.clone(),
@@ -33,7 +33,7 @@ fn test_deploy_code() {
         random_seed: H256::zero(),
         prepaid_gas: DEFAULT_PREPAID_GAS,
     };
-    let mut handler = promise::PromiseTracker::default();
+    let mut handler = memory::PromiseTracker::default();
     let mut engine = engine::Engine::new_with_state(state, origin, owner_id, io, &env);
     let code_to_deploy = vec![1, 2, 3, 4, 5, 6];
     let result = engine.deploy_code(
@@ -63,6 +63,579 @@ fn test_deploy_code() {
     assert_eq!(engine::get_code(&io, &contract_address), code_to_deploy);
 }
 
+#[test]
+fn test_transfer_with_dyn_host() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine_sdk::env::Env;
+    use aurora_engine_sdk::promise::PromiseHandler;
+
+    let storage = RefCell::new(memory::Storage::default());
+    let io = memory::StoragePointer(&storage);
+    let fixed_env = mocks::default_env(0);
+    mocks::init_evm(io, &fixed_env, 1313161554);
+
+    let sender = Address([1u8; 20]);
+    let receiver = Address([2u8; 20]);
+    let balance = Wei::new_u64(1_000);
+    mocks::mint_evm_account(sender, balance, U256::zero(), None, io, &fixed_env);
+
+    // The host of the engine is chosen at runtime.
+    let env: &dyn Env = &fixed_env;
+    let mut tracker = memory::PromiseTracker::default();
+    let mut handler: &mut dyn PromiseHandler = &mut tracker;
+    let mut engine = engine::Engine::new(sender, env.current_account_id(), io, &env).unwrap();
+    let result = engine
+        .transfer(sender, receiver, Wei::new_u64(300), u64::MAX, &mut handler)
+        .unwrap();
+    assert!(result.status.is_ok());
+
+    assert!(tracker.scheduled_promises.is_empty());
+    assert_eq!(engine::get_balance(&io, &sender), Wei::new_u64(700));
+    assert_eq!(engine::get_balance(&io, &receiver), Wei::new_u64(300));
+    assert_eq!(engine::get_nonce(&io, &sender), U256::one());
+}
+
+#[test]
+fn test_late_resolve_transfer_after_force_resolve() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::callback;
+    use aurora_engine::connector::{self, EthConnectorContract};
+    use aurora_engine::parameters::{
+        BalanceOfCallArgs, ForceResolveTransferCallArgs, ResolveTransferCallArgs, TransferCallArgs,
+        TransferCallCallArgs, TransferCallOutcome,
+    };
+    use aurora_engine_types::types::PromiseResult;
+
+    let storage = RefCell::new(memory::Storage::default());
+    let io = memory::StoragePointer(&storage);
+    let env = mocks::default_env(0);
+    mocks::init_evm(io, &env, 1313161554);
+    // The nETH of the minted account is held by the engine account on NEAR.
+    mocks::mint_evm_account(
+        Address([1u8; 20]),
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        io,
+        &env,
+    );
+    let sender_id: AccountId = env.current_account_id.clone();
+    let receiver_id: AccountId = "receiver.near".parse().unwrap();
+    let balance_of = |account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage)).ft_balance_of(
+            BalanceOfCallArgs {
+                account_id: account_id.clone(),
+            },
+        );
+        String::from_utf8(storage.borrow().output.clone()).unwrap()
+    };
+
+    let promise = EthConnectorContract::init_instance(io)
+        .ft_transfer_call(
+            sender_id.clone(),
+            env.current_account_id.clone(),
+            TransferCallCallArgs {
+                receiver_id: receiver_id.clone(),
+                amount: 400,
+                memo: None,
+                msg: String::new(),
+                gas_for_on_transfer: None,
+            },
+            DEFAULT_PREPAID_GAS,
+            0,
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    let mut ticket_io = io;
+    callback::issue_ticket(
+        &mut ticket_io,
+        &env.current_account_id,
+        &env.current_account_id,
+        &promise.callback,
+    );
+
+    // The callback never ran, the owner refunds the transfer call.
+    let delay = connector::get_force_resolve_delay(&io);
+    let used_amount = EthConnectorContract::init_instance(io)
+        .force_resolve_transfer(
+            ForceResolveTransferCallArgs {
+                sender_id: sender_id.clone(),
+                amount: 400,
+                receiver_id: receiver_id.clone(),
+                outcome: TransferCallOutcome::Failed,
+            },
+            &env.current_account_id,
+            delay,
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    assert_eq!(used_amount, 0);
+    // The ticket of the callback is invalidated.
+    assert!(!callback::has_ticket(
+        &io,
+        &env.current_account_id,
+        "ft_resolve_transfer",
+        &promise.callback.args,
+    ));
+    assert_eq!(balance_of(&sender_id), "\"1000\"");
+    assert_eq!(balance_of(&receiver_id), "\"0\"");
+
+    // The receiver is funded again before the callback finally runs.
+    EthConnectorContract::init_instance(io)
+        .ft_transfer(
+            &sender_id,
+            TransferCallArgs {
+                receiver_id: receiver_id.clone(),
+                amount: 400,
+                memo: None,
+            },
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+
+    // The late callback doesn't refund the transfer call a second time.
+    EthConnectorContract::init_instance(io).ft_resolve_transfer(
+        ResolveTransferCallArgs {
+            sender_id: sender_id.clone(),
+            amount: 400,
+            receiver_id: receiver_id.clone(),
+        },
+        PromiseResult::Failed,
+    );
+    assert_eq!(storage.borrow().output, b"\"0\"".to_vec());
+    assert_eq!(balance_of(&sender_id), "\"600\"");
+    assert_eq!(balance_of(&receiver_id), "\"400\"");
+}
+
+#[test]
+fn test_resolve_transfer_without_pending_record() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::connector::EthConnectorContract;
+    use aurora_engine::parameters::{BalanceOfCallArgs, ResolveTransferCallArgs, TransferCallArgs};
+    use aurora_engine_types::types::PromiseResult;
+
+    let storage = RefCell::new(memory::Storage::default());
+    let io = memory::StoragePointer(&storage);
+    let env = mocks::default_env(0);
+    mocks::init_evm(io, &env, 1313161554);
+    mocks::mint_evm_account(
+        Address([1u8; 20]),
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        io,
+        &env,
+    );
+    let sender_id: AccountId = env.current_account_id.clone();
+    let receiver_id: AccountId = "receiver.near".parse().unwrap();
+    let balance_of = |account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage)).ft_balance_of(
+            BalanceOfCallArgs {
+                account_id: account_id.clone(),
+            },
+        );
+        String::from_utf8(storage.borrow().output.clone()).unwrap()
+    };
+
+    // A transfer call in flight across the upgrade which started to record the pending ones:
+    // the amount is transferred, but there is no record of the transfer call.
+    EthConnectorContract::init_instance(io)
+        .ft_transfer(
+            &sender_id,
+            TransferCallArgs {
+                receiver_id: receiver_id.clone(),
+                amount: 400,
+                memo: None,
+            },
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+
+    // Its callback still refunds the sender.
+    EthConnectorContract::init_instance(io).ft_resolve_transfer(
+        ResolveTransferCallArgs {
+            sender_id: sender_id.clone(),
+            amount: 400,
+            receiver_id: receiver_id.clone(),
+        },
+        PromiseResult::Failed,
+    );
+    assert_eq!(storage.borrow().output, b"\"0\"".to_vec());
+    assert_eq!(balance_of(&sender_id), "\"1000\"");
+    assert_eq!(balance_of(&receiver_id), "\"0\"");
+}
+
+#[test]
+fn test_reassign_unclaimed_deposit() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::connector::{self, EthConnectorContract, UNCLAIMED_REASSIGN_DELAY_BLOCKS};
+    use aurora_engine::parameters::{BalanceOfCallArgs, FinishDepositCallArgs, TransferCallArgs};
+
+    let storage = RefCell::new(memory::Storage::default());
+    let mut io = memory::StoragePointer(&storage);
+    let env = mocks::default_env(0);
+    mocks::init_evm(io, &env, 1313161554);
+    mocks::mint_evm_account(
+        Address([1u8; 20]),
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        io,
+        &env,
+    );
+    let engine_id = env.current_account_id.clone();
+    let recipient_id: AccountId = "recipient.near".parse().unwrap();
+    let new_recipient_id: AccountId = "new_recipient.near".parse().unwrap();
+    let deposit_id = "deposit_proof";
+    let balance_of = |account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage)).ft_balance_of(
+            BalanceOfCallArgs {
+                account_id: account_id.clone(),
+            },
+        );
+        String::from_utf8(storage.borrow().output.clone()).unwrap()
+    };
+
+    // 1% of the deposit net of the relayer fee is taken by the protocol.
+    connector::set_deposit_protocol_fee(&mut io, 100).unwrap();
+    EthConnectorContract::init_instance(io)
+        .finish_deposit(
+            engine_id.clone(),
+            engine_id.clone(),
+            FinishDepositCallArgs {
+                new_owner_id: recipient_id.clone(),
+                amount: 10_000,
+                proof_key: deposit_id.to_string(),
+                relayer_id: "relayer.near".parse().unwrap(),
+                fee: 100.into(),
+                msg: None,
+                memo: None,
+                sender: None,
+            },
+            DEFAULT_PREPAID_GAS,
+            0,
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    // The recipient received other tokens than the deposit.
+    EthConnectorContract::init_instance(io)
+        .ft_transfer(
+            &engine_id,
+            TransferCallArgs {
+                receiver_id: recipient_id.clone(),
+                amount: 500,
+                memo: None,
+            },
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    assert_eq!(balance_of(&recipient_id), "\"10301\"");
+
+    // The recipient removes the flag of its deposit itself.
+    EthConnectorContract::init_instance(io)
+        .set_unclaimed_deposit(deposit_id, true, &engine_id, 10)
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    let claim = |account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage))
+            .claim_unclaimed_deposit(deposit_id, account_id)
+            .map_err(mocks::unsafe_to_string)
+    };
+    assert_eq!(
+        claim(&new_recipient_id),
+        Err("ERR_NOT_DEPOSIT_RECIPIENT".to_string())
+    );
+    claim(&recipient_id).unwrap();
+    assert!(EthConnectorContract::init_instance(io)
+        .get_unclaimed_deposit(deposit_id)
+        .is_none());
+    assert_eq!(
+        claim(&recipient_id),
+        Err("ERR_DEPOSIT_NOT_FLAGGED".to_string())
+    );
+
+    // Flagged again, the recipient doesn't claim it before the delay elapses.
+    EthConnectorContract::init_instance(io)
+        .set_unclaimed_deposit(deposit_id, true, &engine_id, 20)
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    let reassign = |block_height| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage))
+            .reassign_unclaimed(deposit_id, &new_recipient_id, block_height)
+            .map_err(mocks::unsafe_to_string)
+    };
+    assert_eq!(
+        reassign(20 + UNCLAIMED_REASSIGN_DELAY_BLOCKS - 1),
+        Err("ERR_REASSIGN_DELAY_NOT_ELAPSED".to_string())
+    );
+    // Only the amount credited by the deposit, net of the relayer and protocol fees, is moved.
+    assert_eq!(reassign(20 + UNCLAIMED_REASSIGN_DELAY_BLOCKS), Ok(9_801));
+    assert_eq!(balance_of(&recipient_id), "\"500\"");
+    assert_eq!(balance_of(&new_recipient_id), "\"9801\"");
+    let unclaimed = EthConnectorContract::init_instance(io)
+        .get_unclaimed_deposit(deposit_id)
+        .unwrap();
+    assert_eq!(unclaimed.reassigned_to, Some(new_recipient_id.clone()));
+    assert_eq!(
+        reassign(20 + UNCLAIMED_REASSIGN_DELAY_BLOCKS),
+        Err("ERR_DEPOSIT_ALREADY_REASSIGNED".to_string())
+    );
+    assert_eq!(
+        claim(&recipient_id),
+        Err("ERR_DEPOSIT_ALREADY_REASSIGNED".to_string())
+    );
+}
+
+#[test]
+fn test_withdraw_protocol_fees() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::connector::{self, EthConnectorContract};
+    use aurora_engine::parameters::{BalanceOfCallArgs, FinishDepositCallArgs};
+
+    let storage = RefCell::new(memory::Storage::default());
+    let mut io = memory::StoragePointer(&storage);
+    let env = mocks::default_env(0);
+    mocks::init_evm(io, &env, 1313161554);
+    mocks::mint_evm_account(
+        Address([1u8; 20]),
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        io,
+        &env,
+    );
+    let engine_id = env.current_account_id.clone();
+    let treasury_id: AccountId = "treasury.near".parse().unwrap();
+    let balance_of = |account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage)).ft_balance_of(
+            BalanceOfCallArgs {
+                account_id: account_id.clone(),
+            },
+        );
+        String::from_utf8(storage.borrow().output.clone()).unwrap()
+    };
+
+    connector::set_deposit_protocol_fee(&mut io, 100).unwrap();
+    EthConnectorContract::init_instance(io)
+        .finish_deposit(
+            engine_id.clone(),
+            engine_id.clone(),
+            FinishDepositCallArgs {
+                new_owner_id: "recipient.near".parse().unwrap(),
+                amount: 10_000,
+                proof_key: "deposit_proof".to_string(),
+                relayer_id: "relayer.near".parse().unwrap(),
+                fee: 100.into(),
+                msg: None,
+                memo: None,
+                sender: None,
+            },
+            DEFAULT_PREPAID_GAS,
+            0,
+        )
+        .map_err(mocks::unsafe_to_string)
+        .unwrap();
+    // The protocol fee is held by the engine account, with the nETH of the EVM accounts.
+    assert_eq!(connector::get_protocol_fees(&io), 99);
+    assert_eq!(balance_of(&engine_id), "\"1099\"");
+
+    let withdraw = |amount| {
+        EthConnectorContract::init_instance(memory::StoragePointer(&storage))
+            .withdraw_protocol_fees(&engine_id, &treasury_id, amount)
+            .map_err(mocks::unsafe_to_string)
+    };
+    // The nETH of the EVM accounts can't be withdrawn.
+    assert_eq!(
+        withdraw(100),
+        Err("ERR_INSUFFICIENT_PROTOCOL_FEES".to_string())
+    );
+    withdraw(99).unwrap();
+    assert_eq!(connector::get_protocol_fees(&io), 0);
+    assert_eq!(balance_of(&engine_id), "\"1000\"");
+    assert_eq!(balance_of(&treasury_id), "\"99\"");
+}
+
+#[test]
+fn test_silo_bridge_transfer() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::connector::EthConnectorContract;
+    use aurora_engine::parameters::{
+        BalanceOfCallArgs, SiloBridgeReceiveArgs, SiloBridgeResolveTransferArgs,
+        SiloBridgeTransferArgs,
+    };
+    use aurora_engine::silo_bridge::{self, RemoteSilo};
+    use aurora_engine_types::types::PromiseResult;
+    use borsh::BorshDeserialize;
+
+    // Two silos, each with its own storage.
+    let local_storage = RefCell::new(memory::Storage::default());
+    let mut local_io = memory::StoragePointer(&local_storage);
+    let local_env = mocks::default_env(0);
+    mocks::init_evm(local_io, &local_env, 1313161554);
+    mocks::mint_evm_account(
+        Address([1u8; 20]),
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        local_io,
+        &local_env,
+    );
+    let remote_storage = RefCell::new(memory::Storage::default());
+    let mut remote_io = memory::StoragePointer(&remote_storage);
+    let mut remote_env = mocks::default_env(0);
+    remote_env.current_account_id = "silo.near".parse().unwrap();
+    mocks::init_evm(remote_io, &remote_env, 1313161555);
+
+    let local_id = local_env.current_account_id.clone();
+    let remote_id = remote_env.current_account_id.clone();
+    let sender_id = local_id.clone();
+    let recipient_id: AccountId = "recipient.near".parse().unwrap();
+    let balance_of = |storage: &RefCell<memory::Storage>, account_id: &AccountId| {
+        EthConnectorContract::init_instance(memory::StoragePointer(storage)).ft_balance_of(
+            BalanceOfCallArgs {
+                account_id: account_id.clone(),
+            },
+        );
+        String::from_utf8(storage.borrow().output.clone()).unwrap()
+    };
+    let transfer = |amount| {
+        silo_bridge::transfer(
+            memory::StoragePointer(&local_storage),
+            sender_id.clone(),
+            local_id.clone(),
+            SiloBridgeTransferArgs {
+                remote_silo: remote_id.clone(),
+                recipient_id: recipient_id.clone(),
+                amount,
+            },
+        )
+        .map_err(mocks::unsafe_to_string)
+    };
+
+    // The remote silo must be allowed.
+    assert_eq!(
+        transfer(300).unwrap_err(),
+        "ERR_REMOTE_SILO_NOT_ALLOWED".to_string()
+    );
+    silo_bridge::set_remote_silo_allowed(&mut local_io, &remote_id, true);
+    assert_eq!(transfer(0).unwrap_err(), "ERR_ZERO_AMOUNT".to_string());
+
+    // The nETH is burned on the local silo, and minted by the remote one.
+    let promise = transfer(300).unwrap();
+    assert_eq!(balance_of(&local_storage, &sender_id), "\"700\"");
+    assert_eq!(promise.base.target_account_id, remote_id);
+    assert_eq!(promise.base.method, "silo_bridge_receive");
+    assert_eq!(promise.callback.target_account_id, local_id);
+    assert_eq!(promise.callback.method, "silo_bridge_resolve_transfer");
+    assert_eq!(
+        silo_bridge::get_remote_silo(&local_io, &remote_id),
+        Some(RemoteSilo {
+            allowed: true,
+            total_sent: 300,
+            total_received: 0,
+        })
+    );
+
+    let receive_args = SiloBridgeReceiveArgs::try_from_slice(&promise.base.args).unwrap();
+    let receive = |args: SiloBridgeReceiveArgs| {
+        silo_bridge::receive(
+            memory::StoragePointer(&remote_storage),
+            local_id.clone(),
+            args,
+        )
+        .map_err(mocks::unsafe_to_string)
+    };
+    assert_eq!(
+        receive(SiloBridgeReceiveArgs::try_from_slice(&promise.base.args).unwrap()).unwrap_err(),
+        "ERR_REMOTE_SILO_NOT_ALLOWED".to_string()
+    );
+    silo_bridge::set_remote_silo_allowed(&mut remote_io, &local_id, true);
+    receive(receive_args).unwrap();
+    assert_eq!(balance_of(&remote_storage, &recipient_id), "\"300\"");
+    assert_eq!(
+        silo_bridge::get_remote_silo(&remote_io, &local_id),
+        Some(RemoteSilo {
+            allowed: true,
+            total_sent: 0,
+            total_received: 300,
+        })
+    );
+
+    // The transfer accepted by the remote silo is kept.
+    let resolve_args =
+        SiloBridgeResolveTransferArgs::try_from_slice(&promise.callback.args).unwrap();
+    silo_bridge::resolve_transfer(
+        local_io,
+        resolve_args,
+        PromiseResult::Successful(Vec::new()),
+    )
+    .unwrap();
+    assert_eq!(balance_of(&local_storage, &sender_id), "\"700\"");
+
+    // The transfer the remote silo fails to mint is refunded to the sender.
+    let promise = transfer(200).unwrap();
+    assert_eq!(balance_of(&local_storage, &sender_id), "\"500\"");
+    let resolve_args =
+        SiloBridgeResolveTransferArgs::try_from_slice(&promise.callback.args).unwrap();
+    silo_bridge::resolve_transfer(local_io, resolve_args, PromiseResult::Failed).unwrap();
+    assert_eq!(balance_of(&local_storage, &sender_id), "\"700\"");
+    assert_eq!(
+        silo_bridge::get_remote_silo(&local_io, &remote_id),
+        Some(RemoteSilo {
+            allowed: true,
+            total_sent: 300,
+            total_received: 0,
+        })
+    );
+}
+
+#[test]
+fn test_swap_with_invalid_router_output() {
+    use crate::test_utils::standalone::mocks;
+    use aurora_engine::parameters::FeeConversionRoute;
+
+    let storage = RefCell::new(memory::Storage::default());
+    let mut io = memory::StoragePointer(&storage);
+    let env = mocks::default_env(0);
+    mocks::init_evm(io, &env, 1313161554);
+    let admin_address = engine::current_address(&env.current_account_id);
+    mocks::mint_evm_account(
+        admin_address,
+        Wei::new_u64(1_000),
+        U256::zero(),
+        None,
+        io,
+        &env,
+    );
+    // The router stops right away: the call succeeds, but returns no amounts.
+    let router = Address([0x11; 20]);
+    engine::set_code(&mut io, &router, &[0x00]);
+    let route = FeeConversionRoute {
+        router: router.0,
+        path: vec![[0x22; 20], [0x33; 20]],
+        min_amount_out_per_eth: [0u8; 32],
+        gas_limit: 100_000,
+    };
+
+    let mut handler = memory::PromiseTracker::default();
+    let mut engine =
+        engine::Engine::new(admin_address, env.current_account_id.clone(), io, &env).unwrap();
+    let amount_out = engine.swap_eth_for_base_token(
+        &route,
+        Address([0x44; 20]),
+        Wei::new_u64(400),
+        &mut handler,
+    );
+
+    // The swap is reverted, the ETH is still held by the admin address.
+    assert_eq!(amount_out, None);
+    assert_eq!(
+        engine::get_balance(&io, &admin_address),
+        Wei::new_u64(1_000)
+    );
+    assert_eq!(engine::get_balance(&io, &router), Wei::zero());
+}
+
 fn evm_deploy(code: &[u8]) -> Vec<u8> {
     let len = code.len();
     if len > u16::MAX as usize {
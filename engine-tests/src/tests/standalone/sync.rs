@@ -331,6 +331,76 @@ fn test_consume_submit_message() {
     assert_eq!(runner.get_nonce(&signer_address), U256::one());
 }
 
+#[test]
+fn test_consume_test_block_messages() {
+    let (mut runner, _) = initialize();
+
+    let mut signer = test_utils::Signer::random();
+    let signer_address = test_utils::address_from_secret_key(&signer.secret_key);
+    runner.mint_account(
+        signer_address,
+        Wei::new_u64(800_000),
+        signer.nonce.into(),
+        None,
+    );
+
+    runner.env.block_height += 1;
+    test_utils::standalone::mocks::insert_block(&mut runner.storage, runner.env.block_height);
+    let block_hash = test_utils::standalone::mocks::compute_block_hash(runner.env.block_height);
+    let transaction = test_utils::transfer(
+        Address([1u8; 20]),
+        Wei::new_u64(115_321),
+        signer.use_nonce().into(),
+    );
+    let signed_transaction =
+        test_utils::sign_transaction(transaction, Some(runner.chain_id), &signer.secret_key);
+    let transaction_bytes = rlp::encode(&signed_transaction).to_vec();
+    let begin_args = aurora_engine::parameters::BeginBlockArgs {
+        hash: [0u8; 32],
+        coinbase: [0u8; 20],
+        timestamp: aurora_engine_types::types::u256_to_arr(&U256::from(1_650_000_000)),
+        number: aurora_engine_types::types::u256_to_arr(&U256::from(77)),
+        difficulty: [0u8; 32],
+        gaslimit: [0u8; 32],
+    };
+    let kinds = vec![
+        sync::types::TransactionKind::BeginBlock(begin_args),
+        sync::types::TransactionKind::Submit(
+            aurora_engine::transaction::EthTransactionKind::Legacy(signed_transaction),
+        ),
+        sync::types::TransactionKind::EndBlock,
+    ];
+
+    for (position, transaction) in kinds.into_iter().enumerate() {
+        let transaction_message = sync::types::TransactionMessage {
+            block_hash,
+            near_tx_hash: H256([position as u8; 32]),
+            position: position as u16,
+            succeeded: true,
+            signer: runner.env.signer_account_id(),
+            caller: runner.env.predecessor_account_id(),
+            attached_near: 0,
+            transaction,
+        };
+        sync::consume_message(
+            &mut runner.storage,
+            sync::types::Message::Transaction(Box::new(transaction_message)),
+        )
+        .unwrap();
+    }
+
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    assert!(aurora_engine::test_chain::get_open_block(&io).is_none());
+    let summary = aurora_engine::test_chain::get_last_block(&io).unwrap();
+    assert_eq!(summary.number, 77);
+    assert_eq!(summary.transactions, 1);
+    assert_eq!(summary.gas_used, 21_000);
+    assert_ne!(summary.hashchain, [0u8; 32]);
+    assert_eq!(runner.get_nonce(&signer_address), U256::one());
+}
+
 fn mock_proof(recipient_address: Address, deposit_amount: Wei) -> aurora_engine::proof::Proof {
     let eth_custodian_address = test_utils::standalone::mocks::ETH_CUSTODIAN_ADDRESS;
 
@@ -346,6 +416,8 @@ fn mock_proof(recipient_address: Address, deposit_amount: Wei) -> aurora_engine:
         token_message_data,
         amount: deposit_amount.raw().as_u128(),
         fee,
+        memo: None,
+        metadata: None,
     };
 
     let event_schema = ethabi::Event {
@@ -0,0 +1,639 @@
+//! Runner for the official Ethereum `GeneralStateTests` fixtures (https://github.com/ethereum/tests).
+//!
+//! The tests are only compiled with the `ethereum-state-tests` feature and need a checkout of
+//! the fixtures pointed to by the `ETHEREUM_TESTS_DIR` environment variable:
+//!
+//! `ETHEREUM_TESTS_DIR=/path/to/ethereum/tests make test-ethereum-state`
+//!
+//! `ETHEREUM_TESTS_FILTER` can be used to only run the fixture files whose path contains it.
+//!
+//! Every post state entry is executed in a fresh standalone engine, with the transaction signed
+//! for the chain ID of the fixture. An entry passes if the transaction is rejected with the
+//! exception the fixture expects, or executes with the expected hash of the logs, and the root
+//! of the resulting state matches. The engine does not keep a Merkle Patricia Trie of the EVM
+//! state, the root is computed from the accounts it stores. Since the engine pays the gas to
+//! the relayer and burns no base fee, the fees of the relayer are credited to the coinbase
+//! minus the base fee, as an Ethereum client does. The engine only implements the London fork,
+//! the entries for every other fork are reported as skipped, the fixtures which can't be parsed
+//! fail.
+use crate::test_utils::{self, standalone::StandaloneRunner};
+use aurora_engine::engine;
+use aurora_engine::transaction::legacy::TransactionLegacy;
+use aurora_engine_types::storage::KeyPrefix;
+use aurora_engine_types::types::Wei;
+use aurora_engine_types::{Address, H256, U256};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const TESTS_DIR_VAR: &str = "ETHEREUM_TESTS_DIR";
+const TESTS_FILTER_VAR: &str = "ETHEREUM_TESTS_FILTER";
+const SUPPORTED_FORK: &str = "London";
+/// Chain ID of the fixtures which don't configure one.
+const DEFAULT_CHAIN_ID: u64 = 1;
+
+#[derive(Debug, Deserialize)]
+struct StateTest {
+    config: Option<TestConfig>,
+    env: TestEnv,
+    pre: BTreeMap<String, AccountState>,
+    transaction: TestTransaction,
+    post: BTreeMap<String, Vec<PostState>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TestConfig {
+    chainid: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestEnv {
+    current_base_fee: Option<String>,
+    current_coinbase: String,
+    current_number: String,
+    current_timestamp: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountState {
+    balance: String,
+    code: String,
+    nonce: String,
+    storage: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestTransaction {
+    data: Vec<String>,
+    gas_limit: Vec<String>,
+    /// Only legacy transactions are supported, typed transactions have no `gasPrice`.
+    gas_price: Option<String>,
+    nonce: String,
+    secret_key: String,
+    to: String,
+    value: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostState {
+    hash: String,
+    indexes: Indexes,
+    logs: String,
+    expect_exception: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Indexes {
+    data: usize,
+    gas: usize,
+    value: usize,
+}
+
+/// Account of the post state, as hashed in the state root.
+#[derive(Debug, Default)]
+struct PostAccount {
+    nonce: U256,
+    balance: U256,
+    code: Vec<u8>,
+    storage: Vec<(H256, U256)>,
+}
+
+impl PostAccount {
+    /// Empty accounts don't exist in the state, see EIP-161.
+    fn is_empty(&self) -> bool {
+        self.nonce.is_zero() && self.balance.is_zero() && self.code.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+struct ForkReport {
+    passed: usize,
+    failed: Vec<String>,
+    skipped: usize,
+}
+
+#[test]
+fn test_general_state_tests() {
+    let tests_dir = match std::env::var(TESTS_DIR_VAR) {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => {
+            println!(
+                "{} is not set, skipping the Ethereum GeneralStateTests",
+                TESTS_DIR_VAR
+            );
+            return;
+        }
+    };
+    let tests_dir = if tests_dir.join("GeneralStateTests").is_dir() {
+        tests_dir.join("GeneralStateTests")
+    } else {
+        tests_dir
+    };
+    let filter = std::env::var(TESTS_FILTER_VAR).ok();
+
+    let mut files = Vec::new();
+    collect_fixture_files(&tests_dir, &mut files);
+    files.sort();
+
+    let mut reports: BTreeMap<String, ForkReport> = BTreeMap::new();
+    let mut unparsed = Vec::new();
+    for file in files {
+        if let Some(filter) = filter.as_ref() {
+            if !file.to_string_lossy().contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let contents = std::fs::read_to_string(&file).unwrap();
+        let tests: BTreeMap<String, StateTest> = match serde_json::from_str(&contents) {
+            Ok(tests) => tests,
+            Err(e) => {
+                unparsed.push(format!("{}: {}", file.display(), e));
+                continue;
+            }
+        };
+        for (name, test) in tests {
+            run_state_test(&name, &test, &mut reports);
+        }
+    }
+
+    let mut failures = 0;
+    for (fork, report) in reports.iter() {
+        println!(
+            "{}: {} passed, {} failed, {} skipped",
+            fork,
+            report.passed,
+            report.failed.len(),
+            report.skipped
+        );
+        for failure in report.failed.iter() {
+            println!("    FAILED {}", failure);
+        }
+        failures += report.failed.len();
+    }
+    for file in unparsed.iter() {
+        println!("FAILED to parse {}", file);
+    }
+    assert_eq!(failures, 0, "Some Ethereum GeneralStateTests failed");
+    assert!(
+        unparsed.is_empty(),
+        "Some Ethereum GeneralStateTests can't be parsed"
+    );
+}
+
+fn collect_fixture_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_fixture_files(&path, files);
+        } else if path.extension().map(|ext| ext == "json").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+}
+
+fn run_state_test(name: &str, test: &StateTest, reports: &mut BTreeMap<String, ForkReport>) {
+    for (fork, posts) in test.post.iter() {
+        let report = reports.entry(fork.clone()).or_default();
+        for post in posts {
+            if fork != SUPPORTED_FORK || test.transaction.gas_price.is_none() {
+                report.skipped += 1;
+                continue;
+            }
+            let id = format!(
+                "{} (d{}g{}v{})",
+                name, post.indexes.data, post.indexes.gas, post.indexes.value
+            );
+            match run_post_state(test, post) {
+                Ok(()) => report.passed += 1,
+                Err(reason) => report.failed.push(format!("{}: {}", id, reason)),
+            }
+        }
+    }
+}
+
+fn run_post_state(test: &StateTest, post: &PostState) -> Result<(), String> {
+    let chain_id = test
+        .config
+        .as_ref()
+        .map(|config| parse_u256(&config.chainid).low_u64())
+        .unwrap_or(DEFAULT_CHAIN_ID);
+    let mut runner = StandaloneRunner::default();
+    // Best effort to execute the transaction at the height of the fixture, every
+    // state modification below happens in its own block.
+    let setup_blocks = 1 + test.pre.len() as u64;
+    runner.env.block_height = parse_u256(&test.env.current_number)
+        .low_u64()
+        .saturating_sub(setup_blocks + 1);
+    runner.env.block_timestamp = aurora_engine_sdk::env::Timestamp::new(
+        parse_u256(&test.env.current_timestamp)
+            .low_u64()
+            .saturating_mul(1_000_000_000),
+    );
+    runner.init_evm_with_chain_id(chain_id);
+    // The accounts of the engine itself are not part of the state of the fixture.
+    let engine_addresses: BTreeSet<Address> = post_state(&mut runner).keys().copied().collect();
+
+    let mut pre_addresses = BTreeSet::new();
+    for (address, account) in test.pre.iter() {
+        let storage = account
+            .storage
+            .iter()
+            .map(|(key, value)| (parse_h256(key), parse_h256(value)))
+            .collect();
+        pre_addresses.insert(parse_address(address));
+        runner.set_account_state(
+            parse_address(address),
+            Wei::new(parse_u256(&account.balance)),
+            parse_u256(&account.nonce),
+            parse_bytes(&account.code),
+            storage,
+        );
+    }
+
+    let transaction = &test.transaction;
+    let to = if transaction.to.is_empty() {
+        None
+    } else {
+        Some(parse_address(&transaction.to))
+    };
+    let tx = TransactionLegacy {
+        nonce: parse_u256(&transaction.nonce),
+        gas_price: parse_u256(transaction.gas_price.as_ref().unwrap()),
+        gas_limit: parse_u256(&transaction.gas_limit[post.indexes.gas]),
+        to,
+        value: Wei::new(parse_u256(&transaction.value[post.indexes.value])),
+        data: parse_bytes(&transaction.data[post.indexes.data]),
+    };
+    let secret_key =
+        secp256k1::SecretKey::parse_slice(&parse_bytes(&transaction.secret_key)).unwrap();
+    let signed_tx = test_utils::sign_transaction(tx, Some(chain_id), &secret_key);
+    let result = runner.submit_raw_transaction_bytes(&rlp::encode(&signed_tx));
+
+    let outcome = match (result, post.expect_exception.as_ref()) {
+        (Err(e), Some(exception)) => {
+            let error = e.kind.as_bytes();
+            if expected_errors(exception)
+                .iter()
+                .any(|expected| *expected == error)
+            {
+                Ok(0)
+            } else {
+                Err(format!(
+                    "expected exception {}, got {}",
+                    exception,
+                    String::from_utf8_lossy(e.kind.as_bytes())
+                ))
+            }
+        }
+        (Ok(_), Some(exception)) => Err(format!("expected exception {}", exception)),
+        (Err(e), None) => Err(format!("unexpected error {:?}", e.kind)),
+        (Ok(result), None) => {
+            let logs_hash = hash_logs(&result.logs);
+            if logs_hash == parse_h256(&post.logs) {
+                Ok(result.gas_used)
+            } else {
+                Err(format!("logs hash mismatch {:?}", logs_hash))
+            }
+        }
+    };
+    let result = outcome.and_then(|gas_used| {
+        let relayer = aurora_engine_sdk::types::near_account_to_evm_address(
+            runner.env.predecessor_account_id.as_bytes(),
+        );
+        let mut accounts = post_state(&mut runner);
+        accounts.retain(|address, _| {
+            !engine_addresses.contains(address) || pre_addresses.contains(address)
+        });
+        let fees = accounts
+            .remove(&relayer)
+            .map(|account| account.balance)
+            .unwrap_or_default();
+        if !fees.is_zero() {
+            let base_fee = test
+                .env
+                .current_base_fee
+                .as_ref()
+                .map(|base_fee| parse_u256(base_fee))
+                .unwrap_or_default();
+            let coinbase = accounts
+                .entry(parse_address(&test.env.current_coinbase))
+                .or_default();
+            coinbase.balance = coinbase
+                .balance
+                .saturating_add(fees.saturating_sub(base_fee.saturating_mul(U256::from(gas_used))));
+        }
+        accounts.retain(|_, account| !account.is_empty());
+
+        let root = state_root(&accounts);
+        if root == parse_h256(&post.hash) {
+            Ok(())
+        } else {
+            Err(format!("state root mismatch {:?}", root))
+        }
+    });
+    runner.close();
+    result
+}
+
+/// Errors of the engine for the exceptions of the fixtures, which may list several
+/// alternatives separated by `|`.
+fn expected_errors(exception: &str) -> Vec<&'static [u8]> {
+    exception
+        .split('|')
+        .filter_map(
+            |name| match name.trim().trim_start_matches("TransactionException.") {
+                "TR_IntrinsicGas" | "INTRINSIC_GAS_TOO_LOW" => Some(&b"ERR_INTRINSIC_GAS"[..]),
+                "TR_NoFunds"
+                | "TR_NoFundsValue"
+                | "TR_NoFundsOrGas"
+                | "INSUFFICIENT_ACCOUNT_FUNDS" => Some(&b"ERR_OUT_OF_FUND"[..]),
+                "TR_TipGtFeeCap" | "PRIORITY_GREATER_THAN_MAX_FEE_PER_GAS" => {
+                    Some(&b"ERR_MAX_PRIORITY_FEE_GREATER"[..])
+                }
+                "TR_FeeCapLessThanBlocks" | "INSUFFICIENT_MAX_FEE_PER_GAS" => {
+                    Some(&b"ERR_MAX_FEE_BELOW_BASE_FEE"[..])
+                }
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// Accounts stored by the engine, with the storage of their current generation.
+fn post_state(runner: &mut StandaloneRunner) -> BTreeMap<Address, PostAccount> {
+    let account_prefixes = [
+        KeyPrefix::Nonce as u8,
+        KeyPrefix::Balance as u8,
+        KeyPrefix::Code as u8,
+        KeyPrefix::Storage as u8,
+    ];
+    let mut addresses = BTreeSet::new();
+    let mut slots = Vec::new();
+    for (key, value) in runner.cumulative_diff.iter() {
+        let value = match value.value() {
+            Some(value) if key.len() >= 22 && account_prefixes.contains(&key[1]) => value,
+            _ => continue,
+        };
+        let address = Address::from_slice(&key[2..22]);
+        addresses.insert(address);
+        if key[1] != KeyPrefix::Storage as u8 || value.len() != 32 {
+            continue;
+        }
+        let (generation, slot) = match key.len() {
+            54 => (0, &key[22..54]),
+            58 => (
+                u32::from_le_bytes([key[22], key[23], key[24], key[25]]),
+                &key[26..58],
+            ),
+            _ => continue,
+        };
+        slots.push((
+            address,
+            generation,
+            H256::from_slice(slot),
+            U256::from_big_endian(value),
+        ));
+    }
+
+    let io = runner
+        .storage
+        .access_engine_storage_at_position(runner.env.block_height + 1, 0, &[]);
+    addresses
+        .into_iter()
+        .map(|address| {
+            let generation = engine::get_generation(&io, &address);
+            let storage = slots
+                .iter()
+                .filter(|(owner, slot_generation, _, value)| {
+                    *owner == address && *slot_generation == generation && !value.is_zero()
+                })
+                .map(|(_, _, slot, value)| (*slot, *value))
+                .collect();
+            let account = PostAccount {
+                nonce: engine::get_nonce(&io, &address),
+                balance: engine::get_balance(&io, &address).raw(),
+                code: engine::get_code(&io, &address),
+                storage,
+            };
+            (address, account)
+        })
+        .collect()
+}
+
+/// Root of the secure trie of the accounts, keyed by the hash of their address.
+fn state_root(accounts: &BTreeMap<Address, PostAccount>) -> H256 {
+    let entries = accounts
+        .iter()
+        .map(|(address, account)| {
+            let storage_root = trie_root(
+                account
+                    .storage
+                    .iter()
+                    .map(|(slot, value)| {
+                        (
+                            aurora_engine_sdk::keccak(slot.as_bytes()).0.to_vec(),
+                            rlp::encode(value).to_vec(),
+                        )
+                    })
+                    .collect(),
+            );
+            let mut stream = rlp::RlpStream::new_list(4);
+            stream.append(&account.nonce);
+            stream.append(&account.balance);
+            stream.append(&storage_root);
+            stream.append(&aurora_engine_sdk::keccak(&account.code));
+            (
+                aurora_engine_sdk::keccak(address.as_bytes()).0.to_vec(),
+                stream.out().to_vec(),
+            )
+        })
+        .collect();
+    trie_root(entries)
+}
+
+/// Root of the Merkle Patricia Trie of the entries.
+fn trie_root(entries: Vec<(Vec<u8>, Vec<u8>)>) -> H256 {
+    let mut entries: Vec<(Vec<u8>, Vec<u8>)> = entries
+        .into_iter()
+        .map(|(key, value)| {
+            let nibbles = key
+                .iter()
+                .flat_map(|byte| vec![byte >> 4, byte & 0x0f])
+                .collect();
+            (nibbles, value)
+        })
+        .collect();
+    entries.sort();
+    aurora_engine_sdk::keccak(&encode_trie_node(&entries, 0))
+}
+
+/// RLP encoding of the node holding the entries, sorted by their key in nibbles, below the
+/// first `depth` nibbles they share.
+fn encode_trie_node(entries: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    let mut stream = rlp::RlpStream::new();
+    match entries {
+        [] => {
+            stream.append_empty_data();
+        }
+        [(key, value)] => {
+            stream.begin_list(2);
+            stream.append(&hex_prefix(&key[depth..], true));
+            stream.append(value);
+        }
+        _ => {
+            let first = &entries[0].0;
+            let last = &entries[entries.len() - 1].0;
+            let prefix_len = first[depth..]
+                .iter()
+                .zip(last[depth..].iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            if prefix_len > 0 {
+                stream.begin_list(2);
+                stream.append(&hex_prefix(&first[depth..depth + prefix_len], false));
+                append_trie_node_ref(&mut stream, &encode_trie_node(entries, depth + prefix_len));
+            } else {
+                stream.begin_list(17);
+                for nibble in 0..16u8 {
+                    let children: Vec<_> = entries
+                        .iter()
+                        .filter(|(key, _)| key.len() > depth && key[depth] == nibble)
+                        .cloned()
+                        .collect();
+                    if children.is_empty() {
+                        stream.append_empty_data();
+                    } else {
+                        append_trie_node_ref(&mut stream, &encode_trie_node(&children, depth + 1));
+                    }
+                }
+                match entries.iter().find(|(key, _)| key.len() == depth) {
+                    Some((_, value)) => stream.append(value),
+                    None => stream.append_empty_data(),
+                };
+            }
+        }
+    }
+    stream.out().to_vec()
+}
+
+/// The nodes shorter than a hash are embedded in their parent.
+fn append_trie_node_ref(stream: &mut rlp::RlpStream, node: &[u8]) {
+    if node.len() < 32 {
+        stream.append_raw(node, 1);
+    } else {
+        stream.append(&aurora_engine_sdk::keccak(node));
+    }
+}
+
+/// Hex-prefix encoding of the nibbles of a path, flagged as the path of a leaf or extension.
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag: u8 = if is_leaf { 2 } else { 0 };
+    let (mut bytes, rest) = if nibbles.len() % 2 == 1 {
+        (vec![((flag + 1) << 4) | nibbles[0]], &nibbles[1..])
+    } else {
+        (vec![flag << 4], nibbles)
+    };
+    bytes.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    bytes
+}
+
+/// The logs hash of the fixtures is `keccak(rlp([[address, [topics...], data]...]))`.
+fn hash_logs(logs: &[aurora_engine::parameters::ResultLog]) -> H256 {
+    let mut stream = rlp::RlpStream::new_list(logs.len());
+    for log in logs {
+        stream.begin_list(3);
+        stream.append(&Address(log.address));
+        stream.begin_list(log.topics.len());
+        for topic in log.topics.iter() {
+            stream.append(&H256(*topic));
+        }
+        stream.append(&log.data);
+    }
+    aurora_engine_sdk::keccak(&stream.out())
+}
+
+/// Values in the fixtures are hex strings, big integers are written as `0x:bigint 0x...`.
+fn parse_bytes(value: &str) -> Vec<u8> {
+    let value = value.trim_start_matches("0x:bigint ");
+    let value = value.trim_start_matches("0x");
+    if value.len() % 2 == 1 {
+        hex::decode(format!("0{}", value)).unwrap()
+    } else {
+        hex::decode(value).unwrap()
+    }
+}
+
+fn parse_u256(value: &str) -> U256 {
+    U256::from_big_endian(&parse_bytes(value))
+}
+
+fn parse_h256(value: &str) -> H256 {
+    let mut bytes = [0u8; 32];
+    parse_u256(value).to_big_endian(&mut bytes);
+    H256(bytes)
+}
+
+fn parse_address(value: &str) -> Address {
+    let bytes = parse_bytes(value);
+    let mut address = [0u8; 20];
+    address[20 - bytes.len()..].copy_from_slice(&bytes);
+    Address(address)
+}
+
+#[test]
+fn test_hash_logs() {
+    // Hash of the empty list of logs, used by most of the fixtures.
+    assert_eq!(
+        hash_logs(&[]),
+        parse_h256("0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347")
+    );
+}
+
+#[test]
+fn test_parse_values() {
+    assert_eq!(parse_u256("0x0a"), U256::from(10));
+    assert_eq!(parse_u256("0x"), U256::zero());
+    assert_eq!(parse_u256("0x:bigint 0x0100"), U256::from(256));
+    assert_eq!(parse_bytes("0xabc"), vec![0x0a, 0xbc]);
+    assert_eq!(
+        parse_address("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b").as_ref(),
+        hex::decode("a94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap()
+    );
+}
+
+#[test]
+fn test_trie_root() {
+    assert_eq!(
+        trie_root(Vec::new()),
+        parse_h256("0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+    );
+    let entries = [
+        ("do", "verb"),
+        ("dog", "puppy"),
+        ("doge", "coin"),
+        ("horse", "stallion"),
+    ]
+    .iter()
+    .map(|(key, value)| (key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+    .collect();
+    assert_eq!(
+        trie_root(entries),
+        parse_h256("0x5991bb8c6514148a29db676a14ac506cd2cd5775ace63c30a4fe457715e9ac84")
+    );
+}
+
+#[test]
+fn test_expected_errors() {
+    assert_eq!(
+        expected_errors("TR_IntrinsicGas"),
+        vec![&b"ERR_INTRINSIC_GAS"[..]]
+    );
+    assert_eq!(
+        expected_errors("TransactionException.INSUFFICIENT_ACCOUNT_FUNDS|TR_Unknown"),
+        vec![&b"ERR_OUT_OF_FUND"[..]]
+    );
+    assert!(expected_errors("TR_Unknown").is_empty());
+}
@@ -64,3 +64,40 @@ pub struct RefundCallArgs {
     pub erc20_address: Option<EthAddress>,
     pub amount: RawU256,
 }
+
+/// Arguments of a callback of the engine which mutates the EVM state, together with the
+/// execution id committed when the callback was scheduled.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct CallbackArgs {
+    pub execution_id: u64,
+    pub args: Vec<u8>,
+}
+
+/// Field types of a schema registered for the ABI utilities precompile.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq)]
+pub enum AbiSchemaType {
+    /// Unsigned integer with the given number of bits (multiple of 8, up to 256).
+    Uint(u16),
+    /// Signed integer with the given number of bits (multiple of 8, up to 256).
+    Int(u16),
+    Address,
+    Bool,
+    /// Fixed size byte array with the given length (from 1 to 32).
+    FixedBytes(u8),
+    Bytes,
+    String,
+}
+
+impl AbiSchemaType {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Uint(bits) | Self::Int(bits) => *bits > 0 && *bits <= 256 && bits % 8 == 0,
+            Self::FixedBytes(len) => *len > 0 && *len <= 32,
+            Self::Address | Self::Bool | Self::Bytes | Self::String => true,
+        }
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Self::Bytes | Self::String)
+    }
+}
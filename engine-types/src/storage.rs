@@ -20,6 +20,13 @@ pub enum KeyPrefix {
     Generation = 0x7,
     Nep141Erc20Map = 0x8,
     Erc20Nep141Map = 0x9,
+    SubmittedTxHash = 0xa,
+    SiloBridge = 0xb,
+    EthStream = 0xc,
+    AbiSchema = 0xd,
+    LogBloom = 0xe,
+    FrozenAccount = 0xf,
+    Multisig = 0x10,
 }
 
 /// Enum used to differentiate different storage keys used by eth-connector
@@ -31,6 +38,197 @@ pub enum EthConnectorStorageId {
     PausedMask = 0x3,
     StatisticsAuroraAccountsCounter = 0x4,
     FungibleTokenMetadata = 0x5,
+    PendingTransferCall = 0x6,
+    BurnedEthOnNear = 0x7,
+    MintCircuitBreaker = 0x8,
+    MintVolume = 0x9,
+    FungibleTokenIconChunk = 0xa,
+    FungibleTokenIconChunksCount = 0xb,
+    Provers = 0xc,
+    ProverStatistics = 0xd,
+    DepositReceipt = 0xe,
+    StateVersion = 0xf,
+    Enumeration = 0x10,
+    MinDeposit = 0x11,
+    DepositsBySender = 0x12,
+    RecipientRules = 0x13,
+    ProofCacheConfig = 0x14,
+    VerifiedReceipt = 0x15,
+    UnclaimedDeposit = 0x16,
+    ExtraCustodians = 0x17,
+    LightClient = 0x18,
+    LightClientBlockHash = 0x19,
+    StoragePool = 0x1a,
+    AccountStorageDeposit = 0x1b,
+    StorageConfig = 0x1c,
+    ProtocolFees = 0x1d,
+    ForceResolvedTransferCall = 0x1e,
+}
+
+/// Enum used to differentiate the configuration values of the engine subsystems stored under
+/// `KeyPrefix::Config`. Some of them used to be stored under human-readable names, which are
+/// kept as `legacy_name` to read the values written before. The ids are below any ASCII
+/// letter, so the new keys can't collide with the legacy ones.
+///
+/// The state and the code of the engine keep their names (`STATE`, `CODE`, `CODE_STAGE`)
+/// since they are read by tools outside of the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum ConfigKey {
+    DuplicateSubmitWindow = 0x1,
+    LogBloomIndex = 0x2,
+    PriorityFeeSplit = 0x3,
+    ForceResolveDelay = 0x4,
+    NextExecutionId = 0x5,
+    PendingExecution = 0x6,
+    StorageBreakdown = 0x7,
+    NextStreamId = 0x8,
+    EvmLimits = 0x9,
+    ScheduledTasks = 0xa,
+    PausedPrecompiles = 0xb,
+    BlockAttributes = 0xc,
+    BlockGasUsage = 0xd,
+    DepositProtocolFee = 0xe,
+    FeeWaivedRelayer = 0xf,
+    HostFunctions = 0x10,
+    Erc20Locker = 0x11,
+    BridgedErc20Token = 0x12,
+    NextMultisigId = 0x13,
+    GasPrice = 0x14,
+    GasPriceUpdater = 0x15,
+    WitnessSizeLimit = 0x16,
+    LegacyLogs = 0x17,
+    EngineSequence = 0x18,
+    CallbackTicket = 0x19,
+    CallbackTicketsRequired = 0x1a,
+    CodeCompression = 0x1b,
+    ChainIdLocked = 0x1c,
+    UnprotectedTxs = 0x1d,
+    TokenSupply = 0x1e,
+    PausedToken = 0x1f,
+    TestBlock = 0x20,
+    LastTestBlock = 0x21,
+    RelayerEarnings = 0x22,
+    PendingOwner = 0x23,
+    Role = 0x24,
+    Eip712Domain = 0x25,
+    DepositFeeCurrency = 0x26,
+    FirstLogsBloomHeight = 0x27,
+    CodeLength = 0x28,
+    SubmittedTxQueue = 0x29,
+    SubmittedTxQueueRange = 0x2a,
+    CodeStageDelay = 0x2b,
+    InputLimit = 0x2c,
+}
+
+impl ConfigKey {
+    pub const ALL: [ConfigKey; 44] = [
+        Self::DuplicateSubmitWindow,
+        Self::LogBloomIndex,
+        Self::PriorityFeeSplit,
+        Self::ForceResolveDelay,
+        Self::NextExecutionId,
+        Self::PendingExecution,
+        Self::StorageBreakdown,
+        Self::NextStreamId,
+        Self::EvmLimits,
+        Self::ScheduledTasks,
+        Self::PausedPrecompiles,
+        Self::BlockAttributes,
+        Self::BlockGasUsage,
+        Self::DepositProtocolFee,
+        Self::FeeWaivedRelayer,
+        Self::HostFunctions,
+        Self::Erc20Locker,
+        Self::BridgedErc20Token,
+        Self::NextMultisigId,
+        Self::GasPrice,
+        Self::GasPriceUpdater,
+        Self::WitnessSizeLimit,
+        Self::LegacyLogs,
+        Self::EngineSequence,
+        Self::CallbackTicket,
+        Self::CallbackTicketsRequired,
+        Self::CodeCompression,
+        Self::ChainIdLocked,
+        Self::UnprotectedTxs,
+        Self::TokenSupply,
+        Self::PausedToken,
+        Self::TestBlock,
+        Self::LastTestBlock,
+        Self::RelayerEarnings,
+        Self::PendingOwner,
+        Self::Role,
+        Self::Eip712Domain,
+        Self::DepositFeeCurrency,
+        Self::FirstLogsBloomHeight,
+        Self::CodeLength,
+        Self::SubmittedTxQueue,
+        Self::SubmittedTxQueueRange,
+        Self::CodeStageDelay,
+        Self::InputLimit,
+    ];
+
+    /// Name the value was stored under before the configuration keys were namespaced,
+    /// `None` for the values which were introduced afterwards.
+    pub fn legacy_name(&self) -> Option<&'static [u8]> {
+        match self {
+            Self::DuplicateSubmitWindow => Some(b"DUPLICATE_SUBMIT_WINDOW"),
+            Self::LogBloomIndex => Some(b"LOG_BLOOM_INDEX"),
+            Self::PriorityFeeSplit => Some(b"PRIORITY_FEE_SPLIT"),
+            Self::ForceResolveDelay => Some(b"FORCE_RESOLVE_DELAY"),
+            Self::NextExecutionId => Some(b"NEXT_EXECUTION_ID"),
+            Self::PendingExecution => Some(b"PENDING_EXECUTION"),
+            Self::StorageBreakdown => Some(b"STORAGE_BREAKDOWN"),
+            Self::NextStreamId => Some(b"NEXT_STREAM_ID"),
+            Self::CodeStageDelay => Some(b"CODE_STAGE_DELAY"),
+            Self::EvmLimits
+            | Self::ScheduledTasks
+            | Self::PausedPrecompiles
+            | Self::BlockAttributes
+            | Self::BlockGasUsage
+            | Self::DepositProtocolFee
+            | Self::FeeWaivedRelayer
+            | Self::HostFunctions
+            | Self::Erc20Locker
+            | Self::BridgedErc20Token
+            | Self::NextMultisigId
+            | Self::GasPrice
+            | Self::GasPriceUpdater
+            | Self::WitnessSizeLimit
+            | Self::LegacyLogs
+            | Self::EngineSequence
+            | Self::CallbackTicket
+            | Self::CallbackTicketsRequired
+            | Self::CodeCompression
+            | Self::ChainIdLocked
+            | Self::UnprotectedTxs
+            | Self::TokenSupply
+            | Self::PausedToken
+            | Self::TestBlock
+            | Self::LastTestBlock
+            | Self::RelayerEarnings
+            | Self::PendingOwner
+            | Self::Role
+            | Self::Eip712Domain
+            | Self::DepositFeeCurrency
+            | Self::FirstLogsBloomHeight
+            | Self::CodeLength
+            | Self::SubmittedTxQueue
+            | Self::SubmittedTxQueueRange
+            | Self::InputLimit => None,
+        }
+    }
+}
+
+/// Key of a configuration value, `suffix` differentiates the values of the same kind.
+pub fn config_key(key: ConfigKey, suffix: &[u8]) -> Vec<u8> {
+    bytes_to_key(KeyPrefix::Config, &[&[key as u8], suffix].concat())
+}
+
+/// Key the configuration value was stored under before the configuration keys were namespaced.
+pub fn legacy_config_key(key: ConfigKey, suffix: &[u8]) -> Option<Vec<u8>> {
+    key.legacy_name()
+        .map(|name| bytes_to_key(KeyPrefix::Config, &[name, suffix].concat()))
 }
 
 /// We can't use const generic over Enum, but we can do it over integral type
@@ -50,6 +248,13 @@ impl From<KeyPrefixU8> for KeyPrefix {
             0x7 => Self::Generation,
             0x8 => Self::Nep141Erc20Map,
             0x9 => Self::Erc20Nep141Map,
+            0xa => Self::SubmittedTxHash,
+            0xb => Self::SiloBridge,
+            0xc => Self::EthStream,
+            0xd => Self::AbiSchema,
+            0xe => Self::LogBloom,
+            0xf => Self::FrozenAccount,
+            0x10 => Self::Multisig,
             _ => unreachable!(),
         }
     }
@@ -112,3 +317,15 @@ fn generation_storage_key(address: &Address, key: &H256, generation: u32) -> [u8
     result[26..58].copy_from_slice(&key.0);
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_prefix_from_u8() {
+        for value in 0x0..=(KeyPrefix::Multisig as u8) {
+            assert_eq!(KeyPrefix::from(value) as u8, value);
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use crate::{str, vec, Add, Address, Display, Div, Mul, String, Sub, Vec, U256};
+use crate::{
+    format, str, vec, Add, Address, Display, Div, Mul, String, Sub, ToString, TryFrom, Vec, U256,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 
 use crate::fmt::Formatter;
+use primitive_types::U512;
 
 // TODO: introduce new Balance type for more strict typing
 pub type Balance = u128;
@@ -138,6 +141,14 @@ impl Fee {
     pub fn into_u128(self) -> u128 {
         self.0
     }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
 }
 
 impl Add<Fee> for Fee {
@@ -154,12 +165,73 @@ impl From<u128> for Fee {
     }
 }
 
+/// Newtype to distinguish the amounts of the NEP-141 token of the eth-connector (nETH), and
+/// of the ETH it backs on Aurora, from the other u128 values. The token has the decimals of
+/// ETH, so the amounts are in Wei.
+#[derive(
+    Default, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, BorshSerialize, BorshDeserialize,
+)]
+pub struct NEP141Wei(u128);
+
+impl Display for NEP141Wei {
+    fn fmt(&self, f: &mut Formatter<'_>) -> crate::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl NEP141Wei {
+    pub const fn new(amount: u128) -> Self {
+        Self(amount)
+    }
+
+    pub const fn zero() -> Self {
+        Self(0)
+    }
+
+    pub fn as_u128(self) -> u128 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Amount net of the fee, `None` if the fee exceeds it.
+    pub fn checked_sub_fee(self, fee: Fee) -> Option<Self> {
+        self.0.checked_sub(fee.0).map(Self)
+    }
+
+    pub fn to_wei(self) -> Wei {
+        Wei::new(U256::from(self.0))
+    }
+}
+
+impl From<Fee> for NEP141Wei {
+    fn from(fee: Fee) -> Self {
+        Self(fee.0)
+    }
+}
+
 /// Selector to call mint function in ERC 20 contract
 ///
 /// keccak("mint(address,uint256)".as_bytes())[..4];
 #[allow(dead_code)]
 pub const ERC20_MINT_SELECTOR: &[u8] = &[64, 193, 15, 25];
 
+/// Selector to call balanceOf function in ERC 20 contract
+///
+/// keccak("balanceOf(address)".as_bytes())[..4];
+#[allow(dead_code)]
+pub const ERC20_BALANCE_OF_SELECTOR: &[u8] = &[112, 160, 130, 49];
+
 #[derive(Debug)]
 pub enum AddressValidationError {
     FailedDecodeHex,
@@ -186,6 +258,70 @@ pub fn validate_eth_address(address: String) -> Result<EthAddress, AddressValida
     Ok(result)
 }
 
+/// Order of the field over which the secp256k1 curve is defined.
+const SECP256K1_FIELD_PRIME: U256 = U256([0xFFFF_FFFE_FFFF_FC2F, u64::MAX, u64::MAX, u64::MAX]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicKeyValidationError {
+    IncorrectLength,
+    NotOnCurve,
+}
+
+impl AsRef<[u8]> for PublicKeyValidationError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::IncorrectLength => b"ERR_WRONG_PUBLIC_KEY_LENGTH",
+            Self::NotOnCurve => b"ERR_PUBLIC_KEY_NOT_ON_CURVE",
+        }
+    }
+}
+
+/// Ethereum address of an uncompressed secp256k1 public key, given as the 64 bytes of
+/// its coordinates (i.e. without the `0x04` prefix).
+pub fn address_from_public_key(public_key: &[u8; 64]) -> Address {
+    use sha3::Digest;
+
+    let hash = sha3::Keccak256::digest(public_key);
+    Address::from_slice(&hash[12..])
+}
+
+/// Checks that the coordinates of the public key are a point of the secp256k1 curve.
+pub fn is_valid_public_key(public_key: &[u8; 64]) -> bool {
+    let x = U256::from_big_endian(&public_key[..32]);
+    let y = U256::from_big_endian(&public_key[32..]);
+    if x >= SECP256K1_FIELD_PRIME || y >= SECP256K1_FIELD_PRIME {
+        return false;
+    }
+    // y^2 = x^3 + 7, the addition can't overflow as x^3 is reduced modulo the prime.
+    let mut rhs = field_mul(field_mul(x, x), x) + U256::from(7);
+    if rhs >= SECP256K1_FIELD_PRIME {
+        rhs -= SECP256K1_FIELD_PRIME;
+    }
+    field_mul(y, y) == rhs
+}
+
+fn field_mul(a: U256, b: U256) -> U256 {
+    let product = a.full_mul(b) % U512::from(SECP256K1_FIELD_PRIME);
+    // The remainder is smaller than the prime, so it always fits.
+    U256::try_from(product).unwrap()
+}
+
+/// Parses an uncompressed secp256k1 public key, either as the 64 bytes of its coordinates
+/// or prefixed with `0x04` (as serialized by `libsecp256k1`), and validates it.
+pub fn parse_public_key(bytes: &[u8]) -> Result<[u8; 64], PublicKeyValidationError> {
+    let coordinates = match bytes {
+        [0x04, coordinates @ ..] if coordinates.len() == 64 => coordinates,
+        coordinates if coordinates.len() == 64 => coordinates,
+        _ => return Err(PublicKeyValidationError::IncorrectLength),
+    };
+    let mut public_key = [0u8; 64];
+    public_key.copy_from_slice(coordinates);
+    if !is_valid_public_key(&public_key) {
+        return Err(PublicKeyValidationError::NotOnCurve);
+    }
+    Ok(public_key)
+}
+
 /// Newtype to distinguish balances (denominated in Wei) from other U256 types.
 #[derive(Default, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Wei(U256);
@@ -271,6 +407,78 @@ impl From<WeiU256> for Wei {
     }
 }
 
+/// Maximum length in bytes of the memo attached to a transfer.
+pub const MAX_MEMO_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoError {
+    TooLong,
+    InvalidUtf8,
+}
+
+impl AsRef<[u8]> for MemoError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::TooLong => b"ERR_MEMO_TOO_LONG",
+            Self::InvalidUtf8 => b"ERR_MEMO_INVALID_UTF8",
+        }
+    }
+}
+
+/// Memo attached to a transfer, valid UTF-8 of at most `MAX_MEMO_LEN` bytes.
+/// The limit is also enforced when the memo is deserialized.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize)]
+pub struct Memo(String);
+
+impl Memo {
+    pub fn new(memo: String) -> Result<Self, MemoError> {
+        if memo.len() > MAX_MEMO_LEN {
+            return Err(MemoError::TooLong);
+        }
+        Ok(Self(memo))
+    }
+
+    pub fn from_utf8(bytes: Vec<u8>) -> Result<Self, MemoError> {
+        String::from_utf8(bytes)
+            .map_err(|_| MemoError::InvalidUtf8)
+            .and_then(Self::new)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Memo {
+    type Error = MemoError;
+
+    fn try_from(memo: String) -> Result<Self, Self::Error> {
+        Self::new(memo)
+    }
+}
+
+impl BorshDeserialize for Memo {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        use borsh::maybestd::io::{Error, ErrorKind};
+
+        // Encoded like a `String`.
+        let bytes = Vec::<u8>::deserialize(buf)?;
+        Self::from_utf8(bytes).map_err(|e| {
+            let message = match e {
+                MemoError::TooLong => "ERR_MEMO_TOO_LONG",
+                MemoError::InvalidUtf8 => "ERR_MEMO_INVALID_UTF8",
+            };
+            Error::new(ErrorKind::InvalidData, message)
+        })
+    }
+}
+
+impl Display for Memo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> crate::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 pub const STORAGE_PRICE_PER_BYTE: u128 = 10_000_000_000_000_000_000;
 // 1e19yN, 0.00001N
 pub const ERR_FAILED_PARSE: &str = "ERR_FAILED_PARSE";
@@ -334,6 +542,38 @@ pub fn bytes_to_hex(v: &[u8]) -> String {
     result
 }
 
+/// Decimals of ETH, and of nETH.
+pub const ETH_DECIMALS: u8 = 18;
+/// Decimals of NEAR.
+pub const NEAR_DECIMALS: u8 = 24;
+
+/// Human-readable amount of a token with the given decimals, e.g. `1.5` for `1500` with 3
+/// decimals. The fraction has no trailing zeros, and no point if the amount is whole.
+pub fn format_amount(amount: U256, decimals: u8) -> String {
+    let digits = amount.to_string();
+    let decimals = usize::from(decimals);
+    let (integer, fraction) = if digits.len() > decimals {
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        (integer.to_string(), fraction.to_string())
+    } else {
+        (
+            "0".to_string(),
+            "0".repeat(decimals - digits.len()) + &digits,
+        )
+    };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// Amount formatted by `format_amount` followed by the symbol of the token, e.g. `1.5 ETH`.
+pub fn format_amount_with_symbol(amount: U256, decimals: u8, symbol: &str) -> String {
+    format!("{} {}", format_amount(amount, decimals), symbol)
+}
+
 #[derive(Default)]
 pub struct Stack<T> {
     stack: Vec<T>,
@@ -370,6 +610,53 @@ impl<T> Stack<T> {
     }
 }
 
+/// Size of the logs bloom filter in bytes.
+pub const LOG_BLOOM_LEN: usize = 256;
+
+/// Bloom filter of logs, using the same construction as the `logsBloom` of Ethereum blocks.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LogBloom(pub [u8; LOG_BLOOM_LEN]);
+
+impl Default for LogBloom {
+    fn default() -> Self {
+        Self([0u8; LOG_BLOOM_LEN])
+    }
+}
+
+impl LogBloom {
+    /// Bit positions in the filter (byte index and mask) for the given input.
+    fn bits(input: &[u8]) -> [(usize, u8); 3] {
+        use sha3::Digest;
+
+        let hash = sha3::Keccak256::digest(input);
+        let mut bits = [(0, 0); 3];
+        for (i, bit) in bits.iter_mut().enumerate() {
+            let index = (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) & 2047;
+            *bit = (LOG_BLOOM_LEN - 1 - index / 8, 1u8 << (index % 8));
+        }
+        bits
+    }
+
+    pub fn accrue(&mut self, input: &[u8]) {
+        for (byte, mask) in Self::bits(input).iter() {
+            self.0[*byte] |= mask;
+        }
+    }
+
+    /// Returns `false` if the input was definitely not added to the filter.
+    pub fn contains_input(&self, input: &[u8]) -> bool {
+        Self::bits(input)
+            .iter()
+            .all(|(byte, mask)| self.0[*byte] & mask == *mask)
+    }
+
+    pub fn accrue_bloom(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0.iter()) {
+            *a |= b;
+        }
+    }
+}
+
 pub fn str_from_slice(inp: &[u8]) -> &str {
     str::from_utf8(inp).unwrap()
 }
@@ -398,6 +685,27 @@ pub mod error {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(U256::from(1500), 3), "1.5");
+        assert_eq!(format_amount(U256::from(2000), 3), "2");
+        assert_eq!(format_amount(U256::from(5), 3), "0.005");
+        assert_eq!(format_amount(U256::zero(), 18), "0");
+        assert_eq!(format_amount(U256::from(42), 0), "42");
+        assert_eq!(
+            format_amount_with_symbol(U256::from(15) * U256::exp10(17), ETH_DECIMALS, "ETH"),
+            "1.5 ETH"
+        );
+        assert_eq!(
+            format_amount(U256::from(1) + U256::exp10(24), NEAR_DECIMALS),
+            "1.000000000000000000000001"
+        );
+        assert_eq!(
+            format_amount(U256::MAX, ETH_DECIMALS),
+            "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+        );
+    }
+
     #[test]
     fn test_hex() {
         assert_eq!(
@@ -406,6 +714,113 @@ mod tests {
         );
     }
 
+    /// Public key of the secret key `1`, i.e. the generator of the curve.
+    fn generator_public_key() -> [u8; 64] {
+        let mut public_key = [0u8; 64];
+        public_key.copy_from_slice(
+            &hex::decode(
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                 483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            )
+            .unwrap(),
+        );
+        public_key
+    }
+
+    #[test]
+    fn test_address_from_public_key() {
+        assert_eq!(
+            address_from_public_key(&generator_public_key()),
+            Address::from_slice(&hex::decode("7e5f4552091a69125d5dfcb7b8c2659029395bdf").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_public_key_validation() {
+        let public_key = generator_public_key();
+        assert!(is_valid_public_key(&public_key));
+        assert_eq!(parse_public_key(&public_key), Ok(public_key));
+        assert_eq!(
+            parse_public_key(&[[0x04].as_ref(), &public_key].concat()),
+            Ok(public_key)
+        );
+
+        let mut off_curve = public_key;
+        off_curve[63] ^= 1;
+        assert!(!is_valid_public_key(&off_curve));
+        assert_eq!(
+            parse_public_key(&off_curve),
+            Err(PublicKeyValidationError::NotOnCurve)
+        );
+        assert!(!is_valid_public_key(&[0u8; 64]));
+        assert!(!is_valid_public_key(&[0xffu8; 64]));
+        assert_eq!(
+            parse_public_key(&public_key[1..]),
+            Err(PublicKeyValidationError::IncorrectLength)
+        );
+        assert_eq!(
+            parse_public_key(&[[0x02].as_ref(), &public_key].concat()),
+            Err(PublicKeyValidationError::IncorrectLength)
+        );
+    }
+
+    #[test]
+    fn test_memo() {
+        let memo = Memo::new("a".repeat(MAX_MEMO_LEN)).unwrap();
+        assert_eq!(memo.as_str().len(), MAX_MEMO_LEN);
+        assert_eq!(
+            Memo::new("a".repeat(MAX_MEMO_LEN + 1)),
+            Err(MemoError::TooLong)
+        );
+        assert_eq!(
+            Memo::from_utf8(vec![0xff, 0xfe]),
+            Err(MemoError::InvalidUtf8)
+        );
+
+        let bytes = memo.try_to_vec().unwrap();
+        assert_eq!(bytes, memo.as_str().to_string().try_to_vec().unwrap());
+        assert_eq!(Memo::try_from_slice(&bytes).unwrap(), memo);
+
+        let oversized = "a".repeat(MAX_MEMO_LEN + 1).try_to_vec().unwrap();
+        assert!(Memo::try_from_slice(&oversized).is_err());
+        let invalid_utf8 = vec![0xffu8, 0xfe].try_to_vec().unwrap();
+        assert!(Memo::try_from_slice(&invalid_utf8).is_err());
+    }
+
+    #[test]
+    fn test_log_bloom() {
+        let address = [1u8; 20];
+        let topic = [2u8; 32];
+        let mut bloom = LogBloom::default();
+        assert!(!bloom.contains_input(&address));
+
+        bloom.accrue(&address);
+        bloom.accrue(&topic);
+        assert!(bloom.contains_input(&address));
+        assert!(bloom.contains_input(&topic));
+        assert!(!bloom.contains_input(&[3u8; 20]));
+
+        let mut other = LogBloom::default();
+        other.accrue_bloom(&bloom);
+        assert!(other == bloom);
+    }
+
+    #[test]
+    fn test_log_bloom_matches_ethereum() {
+        // Bloom of the empty input: keccak("") = c5d2460186f7233c927e7db2dcc703c0...
+        let mut bloom = LogBloom::default();
+        bloom.accrue(&[]);
+        let set_bits: usize = bloom.0.iter().map(|b| b.count_ones() as usize).sum();
+        assert_eq!(set_bits, 3);
+        // 0xc5d2 & 2047 = 1490, 0x4601 & 2047 = 1537, 0x86f7 & 2047 = 1783
+        for index in [1490usize, 1537, 1783].iter() {
+            assert_ne!(
+                bloom.0[LOG_BLOOM_LEN - 1 - index / 8] & (1 << (index % 8)),
+                0
+            );
+        }
+    }
+
     /// Build view of the stack. Intervals between None values are scopes.
     fn view_stack(stack: &Stack<i32>) -> Vec<Option<i32>> {
         let mut res = vec![];
@@ -512,4 +927,24 @@ mod tests {
         let res: u128 = fee.into_u128();
         assert_eq!(res, 100);
     }
+
+    #[test]
+    fn test_nep141_wei_checked_sub_fee() {
+        let amount = NEP141Wei::new(100);
+        assert_eq!(
+            amount.checked_sub_fee(Fee::new(30)),
+            Some(NEP141Wei::new(70))
+        );
+        assert_eq!(
+            amount.checked_sub_fee(Fee::new(100)),
+            Some(NEP141Wei::zero())
+        );
+        assert_eq!(amount.checked_sub_fee(Fee::new(101)), None);
+        assert_eq!(Fee::new(1).checked_sub(Fee::new(2)), None);
+        assert_eq!(
+            NEP141Wei::new(u128::MAX).checked_add(NEP141Wei::new(1)),
+            None
+        );
+        assert_eq!(amount.to_wei(), Wei::new_u64(100));
+    }
 }
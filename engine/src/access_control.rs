@@ -0,0 +1,96 @@
+//! Owner of the engine and the roles it delegates.
+//!
+//! The owner is the `owner_id` of the engine state. It is transferred in two steps: the owner
+//! proposes the new owner, which accepts the ownership with a call of its own, so that the
+//! engine can't be handed over to a mistyped account nobody controls. Until it is accepted, the
+//! proposal can be replaced or cancelled by the owner.
+//!
+//! The owner grants the roles of the privileged operations, e.g. pausing the eth-connector, to
+//! other accounts, which can then call them without holding the ownership. The owner has every
+//! role, the roles granted are kept when the ownership is transferred.
+use crate::engine::{self, EngineState};
+use crate::events;
+use crate::parameters::Role;
+use crate::prelude::{sdk, AccountId, ConfigKey, Vec};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Account the ownership was proposed to, until it accepts it.
+pub fn get_pending_owner<I: IO>(io: &I) -> Option<AccountId> {
+    storage_key::read_config(io, ConfigKey::PendingOwner, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+/// Proposes the ownership to the account, or cancels the pending proposal with `None`.
+pub fn propose_owner<I: IO>(io: &mut I, state: &EngineState, new_owner_id: Option<&AccountId>) {
+    match new_owner_id {
+        Some(new_owner_id) => {
+            storage_key::write_config_borsh(io, ConfigKey::PendingOwner, &[], new_owner_id)
+        }
+        None => storage_key::remove_config(io, ConfigKey::PendingOwner, &[]),
+    }
+    sdk::log!(&events::owner_proposed_log(&state.owner_id, new_owner_id));
+}
+
+/// Transfers the ownership to the pending owner, which must be the caller.
+pub fn accept_owner<I: IO>(
+    io: &mut I,
+    mut state: EngineState,
+    predecessor_account_id: &AccountId,
+) -> Result<(), error::AccessControlError> {
+    let pending_owner = get_pending_owner(io).ok_or(error::AccessControlError::NoPendingOwner)?;
+    if &pending_owner != predecessor_account_id {
+        return Err(error::AccessControlError::NotPendingOwner);
+    }
+    sdk::log!(&events::owner_changed_log(&state.owner_id, &pending_owner));
+    state.owner_id = pending_owner;
+    engine::set_state(io, state);
+    storage_key::remove_config(io, ConfigKey::PendingOwner, &[]);
+    Ok(())
+}
+
+fn role_suffix(role: Role, account_id: &AccountId) -> Vec<u8> {
+    [&[role as u8], account_id.as_bytes()].concat()
+}
+
+/// Whether the account can perform the operations of the role: it is the owner or it was
+/// granted the role.
+pub fn has_role<I: IO>(io: &I, state: &EngineState, role: Role, account_id: &AccountId) -> bool {
+    &state.owner_id == account_id
+        || storage_key::has_config(io, ConfigKey::Role, &role_suffix(role, account_id))
+}
+
+/// Grants or revokes the role, logging the change if there is one.
+pub fn set_role<I: IO>(io: &mut I, role: Role, account_id: &AccountId, granted: bool) {
+    let suffix = role_suffix(role, account_id);
+    if storage_key::has_config(io, ConfigKey::Role, &suffix) == granted {
+        return;
+    }
+    if granted {
+        storage_key::write_config(io, ConfigKey::Role, &suffix, &[1]);
+    } else {
+        storage_key::remove_config(io, ConfigKey::Role, &suffix);
+    }
+    sdk::log!(&events::role_log(role, account_id, granted));
+}
+
+pub mod error {
+    const NO_PENDING_OWNER: &[u8; 20] = b"ERR_NO_PENDING_OWNER";
+    const NOT_PENDING_OWNER: &[u8; 21] = b"ERR_NOT_PENDING_OWNER";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AccessControlError {
+        NoPendingOwner,
+        /// The caller isn't the account the ownership was proposed to.
+        NotPendingOwner,
+    }
+
+    impl AsRef<[u8]> for AccessControlError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NoPendingOwner => NO_PENDING_OWNER,
+                Self::NotPendingOwner => NOT_PENDING_OWNER,
+            }
+        }
+    }
+}
@@ -1,5 +1,13 @@
 pub type PausedMask = u8;
 
+/// Mask of the precompiles paused individually, the EVM and the other precompiles keep
+/// running while they are paused.
+pub type PausedPrecompilesMask = u32;
+
+pub const PAUSE_EXIT_TO_NEAR: PausedPrecompilesMask = 1 << 0;
+
+pub const PAUSE_EXIT_TO_ETHEREUM: PausedPrecompilesMask = 1 << 1;
+
 pub const ERR_PAUSED: &str = "ERR_PAUSED";
 
 pub trait AdminControlled {
@@ -0,0 +1,58 @@
+//! Append-only log of the privileged calls to the engine.
+//!
+//! Every call to an administrative method (pauses, configuration changes, upgrades, state
+//! patches) adds an `AdminAction` to the log, with its sequence number, the account which made
+//! it and the keccak hash of its input, so that the operators' actions can be audited from the
+//! state of the engine instead of the history of the blocks. An action is recorded in the same
+//! receipt as its effects, a call which fails leaves no entry. The methods of the contract never
+//! change nor remove an entry, only a raw `apply_state_diff` could, which is itself recorded.
+use crate::pagination;
+use crate::parameters::{Page, PageArgs};
+use crate::prelude::{sdk, AccountId, BorshDeserialize, BorshSerialize, RawH256, String};
+use aurora_engine_sdk::io::IO;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AdminAction {
+    /// Position of the action in the log, starting at zero.
+    pub sequence: u64,
+    /// Predecessor of the call, the engine itself for its own callbacks.
+    pub actor: AccountId,
+    /// Name of the called method.
+    pub method: String,
+    /// Keccak hash of the input of the call.
+    pub args_hash: RawH256,
+    pub block_height: u64,
+}
+
+/// Adds the call to the log, returns its sequence number.
+pub fn record<I: IO>(
+    io: &mut I,
+    actor: AccountId,
+    method: &str,
+    input: &[u8],
+    block_height: u64,
+) -> u64 {
+    let sequence = pagination::length(io, pagination::Collection::AdminActions);
+    let action = AdminAction {
+        sequence,
+        actor,
+        method: method.into(),
+        args_hash: sdk::keccak(input).0,
+        block_height,
+    };
+    pagination::append(
+        io,
+        pagination::Collection::AdminActions,
+        &action.try_to_vec().unwrap(),
+    )
+}
+
+/// Page of the admin actions, oldest first.
+pub fn get_admin_actions_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<AdminAction>, pagination::error::PaginationError> {
+    pagination::page(io, pagination::Collection::AdminActions, args, |item| {
+        AdminAction::try_from_slice(&item).ok()
+    })
+}
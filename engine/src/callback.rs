@@ -0,0 +1,389 @@
+//! Replay protection of the callbacks which mutate the EVM state.
+//!
+//! When the engine schedules such a callback on itself (e.g. `refund_on_error` after an exit
+//! to NEAR), a unique execution id is committed to the state before the promise is created and
+//! the arguments of the callback are wrapped together with it. The callback only proceeds if
+//! the execution id is still pending and consumes it, so forged or duplicated callback inputs
+//! can't mutate the EVM state twice.
+//!
+//! The callbacks of the connector keep their arguments, which the relayers and the pending
+//! promises of the previous versions rely on. When the engine schedules one of them, it commits
+//! an engine ticket instead, the hash of the account expected to call it back, the method and
+//! its arguments. The callback redeems the ticket committed for its predecessor and its input,
+//! so it is authorized by a promise the engine created rather than by its predecessor alone,
+//! which lets a callback be executed from an account other than the engine in the deployments
+//! split over several accounts. The callbacks without a ticket are still accepted from the
+//! engine itself until the owner requires the tickets, once the promises scheduled before the
+//! tickets were introduced are resolved.
+use crate::prelude::{
+    sdk, AccountId, BorshDeserialize, BorshSerialize, CallbackArgs, ConfigKey, PromiseCreateArgs,
+    Vec, H256,
+};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Methods of the engine which mutate the EVM state when invoked as a callback. The result of
+/// an exit is logged only once as well.
+const GUARDED_CALLBACKS: &[&str] = &["refund_on_error", "exit_result"];
+
+/// Methods of the engine which are only called as the callback of a promise it created, and
+/// require an engine ticket.
+const TICKETED_CALLBACKS: &[&str] = &[
+    "finish_deposit",
+    "finish_deposit_erc20",
+    "resolve_deposit_verification",
+    "ft_resolve_transfer",
+    "silo_bridge_resolve_transfer",
+];
+
+fn next_execution_id<I: IO>(io: &mut I) -> u64 {
+    let id = storage_key::read_config_u64(io, ConfigKey::NextExecutionId, &[]).unwrap_or(0);
+    storage_key::write_config(io, ConfigKey::NextExecutionId, &[], &(id + 1).to_le_bytes());
+    id
+}
+
+pub fn is_guarded_callback(current_account_id: &AccountId, promise: &PromiseCreateArgs) -> bool {
+    &promise.target_account_id == current_account_id
+        && GUARDED_CALLBACKS.contains(&promise.method.as_str())
+}
+
+/// Commits a new execution id and returns the callback with its arguments wrapped together
+/// with it. The callbacks on the engine which use tickets get their ticket committed instead,
+/// and the callbacks on the other accounts are returned unchanged. Any other callback on the
+/// engine is rejected, since nothing would prevent it from being replayed.
+pub fn register_callback<I: IO>(
+    io: &mut I,
+    current_account_id: &AccountId,
+    promise: &PromiseCreateArgs,
+) -> Result<PromiseCreateArgs, error::CallbackError> {
+    let mut promise = promise.clone();
+    if is_ticketed_callback(current_account_id, &promise) {
+        issue_ticket(io, current_account_id, current_account_id, &promise);
+        return Ok(promise);
+    }
+    if &promise.target_account_id != current_account_id {
+        return Ok(promise);
+    }
+    if !is_guarded_callback(current_account_id, &promise) {
+        return Err(error::CallbackError::Unguarded);
+    }
+    let execution_id = next_execution_id(io);
+    storage_key::write_config(
+        io,
+        ConfigKey::PendingExecution,
+        &execution_id.to_le_bytes(),
+        &[1],
+    );
+    promise.args = CallbackArgs {
+        execution_id,
+        args: promise.args,
+    }
+    .try_to_vec()
+    .map_err(|_| error::CallbackError::InvalidArgs)?;
+    Ok(promise)
+}
+
+/// Verifies the execution id of the callback input and consumes it.
+/// Returns the arguments of the callback. The inputs of the callbacks scheduled before the
+/// execution ids were introduced are the arguments themselves, which are accepted until the
+/// owner requires the callback tickets, as for the callbacks scheduled without a ticket.
+pub fn consume_callback<I: IO>(io: &mut I, input: &[u8]) -> Result<Vec<u8>, error::CallbackError> {
+    let callback_args = match CallbackArgs::try_from_slice(input) {
+        Ok(callback_args) => callback_args,
+        Err(_) if !is_tickets_required(io) => return Ok(input.to_vec()),
+        Err(_) => return Err(error::CallbackError::InvalidArgs),
+    };
+    let execution_id = callback_args.execution_id.to_le_bytes();
+    if !storage_key::has_config(io, ConfigKey::PendingExecution, &execution_id) {
+        return Err(error::CallbackError::UnknownExecution);
+    }
+    storage_key::remove_config(io, ConfigKey::PendingExecution, &execution_id);
+    Ok(callback_args.args)
+}
+
+fn ticket_hash(predecessor_account_id: &AccountId, method: &str, args: &[u8]) -> H256 {
+    let account_id = predecessor_account_id.as_bytes();
+    let mut data = Vec::with_capacity(8 + account_id.len() + method.len() + args.len());
+    data.extend_from_slice(&(account_id.len() as u32).to_be_bytes());
+    data.extend_from_slice(account_id);
+    data.extend_from_slice(&(method.len() as u32).to_be_bytes());
+    data.extend_from_slice(method.as_bytes());
+    data.extend_from_slice(args);
+    sdk::keccak(&data)
+}
+
+/// Number of the tickets committed for the hash and not redeemed yet, the same callback can be
+/// scheduled several times before it is called (e.g. two identical transfer calls).
+fn ticket_count<I: IO>(io: &I, hash: &H256) -> u64 {
+    storage_key::read_config_u64(io, ConfigKey::CallbackTicket, hash.as_bytes()).unwrap_or(0)
+}
+
+fn set_ticket_count<I: IO>(io: &mut I, hash: &H256, count: u64) {
+    if count == 0 {
+        storage_key::remove_config(io, ConfigKey::CallbackTicket, hash.as_bytes());
+    } else {
+        storage_key::write_config(
+            io,
+            ConfigKey::CallbackTicket,
+            hash.as_bytes(),
+            &count.to_le_bytes(),
+        );
+    }
+}
+
+pub fn is_ticketed_callback(current_account_id: &AccountId, promise: &PromiseCreateArgs) -> bool {
+    &promise.target_account_id == current_account_id
+        && TICKETED_CALLBACKS.contains(&promise.method.as_str())
+}
+
+/// Commits the ticket of the callback, to be called back by `predecessor_account_id`, the
+/// account scheduling the callback. Does nothing for the callbacks which don't use tickets.
+pub fn issue_ticket<I: IO>(
+    io: &mut I,
+    current_account_id: &AccountId,
+    predecessor_account_id: &AccountId,
+    promise: &PromiseCreateArgs,
+) {
+    if !is_ticketed_callback(current_account_id, promise) {
+        return;
+    }
+    let hash = ticket_hash(predecessor_account_id, &promise.method, &promise.args);
+    let count = ticket_count(io, &hash);
+    set_ticket_count(io, &hash, count + 1);
+}
+
+/// Whether the engine has a ticket committed for the callback, not redeemed yet.
+pub fn has_ticket<I: IO>(
+    io: &I,
+    predecessor_account_id: &AccountId,
+    method: &str,
+    input: &[u8],
+) -> bool {
+    ticket_count(io, &ticket_hash(predecessor_account_id, method, input)) > 0
+}
+
+/// Invalidates a ticket committed for the callback, when the engine settles what the callback
+/// would have done without it (e.g. `force_resolve_transfer`). Returns false if there was no
+/// ticket to invalidate.
+pub fn revoke_ticket<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    method: &str,
+    input: &[u8],
+) -> bool {
+    let hash = ticket_hash(predecessor_account_id, method, input);
+    let count = ticket_count(io, &hash);
+    if count == 0 {
+        return false;
+    }
+    set_ticket_count(io, &hash, count - 1);
+    true
+}
+
+pub fn is_tickets_required<I: IO>(io: &I) -> bool {
+    storage_key::read_config(io, ConfigKey::CallbackTicketsRequired, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(false)
+}
+
+pub fn set_tickets_required<I: IO>(io: &mut I, required: bool) {
+    storage_key::write_config_borsh(io, ConfigKey::CallbackTicketsRequired, &[], &required);
+}
+
+/// Authorizes the call of a callback method with the input, called by `predecessor_account_id`.
+/// Redeems the ticket committed for it if there is one, otherwise the callback is only allowed
+/// from the engine itself while the tickets aren't required.
+pub fn authorize_callback<I: IO>(
+    io: &mut I,
+    current_account_id: &AccountId,
+    predecessor_account_id: &AccountId,
+    method: &str,
+    input: &[u8],
+) -> Result<(), error::CallbackError> {
+    let hash = ticket_hash(predecessor_account_id, method, input);
+    let count = ticket_count(io, &hash);
+    if count > 0 {
+        set_ticket_count(io, &hash, count - 1);
+        Ok(())
+    } else if predecessor_account_id == current_account_id && !is_tickets_required(io) {
+        Ok(())
+    } else {
+        Err(error::CallbackError::MissingTicket)
+    }
+}
+
+pub mod error {
+    const INVALID_ARGS: &[u8; 25] = b"ERR_INVALID_CALLBACK_ARGS";
+    const UNKNOWN_EXECUTION: &[u8; 24] = b"ERR_UNKNOWN_EXECUTION_ID";
+    const MISSING_TICKET: &[u8; 21] = b"ERR_MISSING_CB_TICKET";
+    const UNGUARDED: &[u8; 26] = b"ERR_UNGUARDED_CB_ON_ENGINE";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum CallbackError {
+        InvalidArgs,
+        UnknownExecution,
+        /// The callback wasn't scheduled by the engine for its predecessor and input.
+        MissingTicket,
+        /// The callback on the engine is neither guarded by an execution id nor by a ticket.
+        Unguarded,
+    }
+
+    impl AsRef<[u8]> for CallbackError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidArgs => INVALID_ARGS,
+                Self::UnknownExecution => UNKNOWN_EXECUTION,
+                Self::MissingTicket => MISSING_TICKET,
+                Self::Unguarded => UNGUARDED,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{vec, String};
+    use aurora_engine_sdk::memory;
+    use core::cell::RefCell;
+
+    fn promise(target_account_id: &str, method: &str) -> PromiseCreateArgs {
+        PromiseCreateArgs {
+            target_account_id: target_account_id.parse().unwrap(),
+            method: String::from(method),
+            args: Vec::new(),
+            attached_balance: 0,
+            attached_gas: 0,
+        }
+    }
+
+    #[test]
+    fn test_is_guarded_callback() {
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        assert!(is_guarded_callback(
+            &current_account_id,
+            &promise("aurora", "refund_on_error")
+        ));
+        assert!(is_guarded_callback(
+            &current_account_id,
+            &promise("aurora", "exit_result")
+        ));
+        assert!(!is_guarded_callback(
+            &current_account_id,
+            &promise("token.near", "refund_on_error")
+        ));
+        assert!(!is_guarded_callback(
+            &current_account_id,
+            &promise("aurora", "ft_transfer")
+        ));
+    }
+
+    #[test]
+    fn test_ticketed_callbacks() {
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        assert!(is_ticketed_callback(
+            &current_account_id,
+            &promise("aurora", "ft_resolve_transfer")
+        ));
+        assert!(!is_ticketed_callback(
+            &current_account_id,
+            &promise("token.near", "ft_resolve_transfer")
+        ));
+        assert!(!is_ticketed_callback(
+            &current_account_id,
+            &promise("aurora", "refund_on_error")
+        ));
+
+        // The ticket binds the predecessor, the method and the arguments.
+        let connector_account_id: AccountId = "connector.aurora".parse().unwrap();
+        let hash = ticket_hash(&current_account_id, "finish_deposit", &[1]);
+        assert_ne!(
+            hash,
+            ticket_hash(&connector_account_id, "finish_deposit", &[1])
+        );
+        assert_ne!(
+            hash,
+            ticket_hash(&current_account_id, "finish_deposit", &[2])
+        );
+        assert_ne!(
+            hash,
+            ticket_hash(&current_account_id, "finish_deposit_erc20", &[1])
+        );
+    }
+
+    #[test]
+    fn test_callback_replay() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        let mut refund = promise("aurora", "refund_on_error");
+        refund.args = vec![1, 2, 3];
+
+        let callback = register_callback(&mut io, &current_account_id, &refund).unwrap();
+        assert_ne!(callback.args, refund.args);
+        assert_eq!(
+            consume_callback(&mut io, &callback.args).unwrap(),
+            refund.args
+        );
+        // The execution id is consumed by the first call.
+        assert_eq!(
+            consume_callback(&mut io, &callback.args).unwrap_err(),
+            error::CallbackError::UnknownExecution
+        );
+        let forged = CallbackArgs {
+            execution_id: 1,
+            args: refund.args.clone(),
+        }
+        .try_to_vec()
+        .unwrap();
+        assert_eq!(
+            consume_callback(&mut io, &forged).unwrap_err(),
+            error::CallbackError::UnknownExecution
+        );
+
+        // The arguments scheduled before the execution ids are accepted as they are, until
+        // the tickets are required.
+        assert_eq!(
+            consume_callback(&mut io, &refund.args).unwrap(),
+            refund.args
+        );
+        set_tickets_required(&mut io, true);
+        assert_eq!(
+            consume_callback(&mut io, &refund.args).unwrap_err(),
+            error::CallbackError::InvalidArgs
+        );
+    }
+
+    #[test]
+    fn test_register_callback() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+
+        let other = promise("token.near", "ft_resolve_transfer");
+        let callback = register_callback(&mut io, &current_account_id, &other).unwrap();
+        assert_eq!(callback.args, other.args);
+        assert!(!has_ticket(
+            &io,
+            &current_account_id,
+            "ft_resolve_transfer",
+            &[]
+        ));
+
+        let ticketed = promise("aurora", "ft_resolve_transfer");
+        let callback = register_callback(&mut io, &current_account_id, &ticketed).unwrap();
+        assert_eq!(callback.args, ticketed.args);
+        assert!(has_ticket(
+            &io,
+            &current_account_id,
+            "ft_resolve_transfer",
+            &[]
+        ));
+
+        let unguarded = promise("aurora", "deploy_code");
+        assert_eq!(
+            register_callback(&mut io, &current_account_id, &unguarded).unwrap_err(),
+            error::CallbackError::Unguarded
+        );
+    }
+}
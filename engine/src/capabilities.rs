@@ -0,0 +1,75 @@
+//! Runtime discovery of the subsystems supported by a deployment of the engine.
+//!
+//! Deployments differ by the features the contract was compiled with and by the subsystems
+//! enabled in their configuration, so clients can't assume any of them is available.
+use crate::connector;
+use crate::engine;
+use crate::prelude::{String, ToString, Vec};
+use crate::storage_breakdown;
+use aurora_engine_sdk::io::IO;
+
+pub const EVM: &str = "evm";
+pub const EIP_2930: &str = "eip-2930";
+pub const EIP_1559: &str = "eip-1559";
+pub const ETH_CONNECTOR: &str = "eth-connector";
+pub const MINT_CIRCUIT_BREAKER: &str = "mint-circuit-breaker";
+pub const EXIT_TO_NEAR: &str = "exit-to-near";
+pub const EXIT_TO_ETHEREUM: &str = "exit-to-ethereum";
+pub const ERROR_REFUND: &str = "error-refund";
+pub const META_CALL: &str = "meta-call";
+pub const EVM_BULLY: &str = "evm-bully";
+pub const SILO_BRIDGE: &str = "silo-bridge";
+pub const ETH_STREAMS: &str = "eth-streams";
+pub const MULTISIG: &str = "multisig";
+pub const DUPLICATE_SUBMIT_PROTECTION: &str = "duplicate-submit-protection";
+pub const LOG_BLOOM_INDEX: &str = "log-bloom-index";
+pub const STORAGE_BREAKDOWN: &str = "storage-breakdown";
+pub const HOST_ECRECOVER: &str = "host-ecrecover";
+pub const HOST_ALT_BN128: &str = "host-alt-bn128";
+pub const HOST_ED25519: &str = "host-ed25519";
+
+/// Returns the names of the capabilities of the engine, in a stable order.
+pub fn list_capabilities<I: IO>(io: &I) -> Vec<String> {
+    let host_functions = engine::get_host_functions(io);
+    let capabilities = [
+        (EVM, true),
+        (EIP_2930, true),
+        (EIP_1559, true),
+        (ETH_CONNECTOR, connector::is_initialized(io)),
+        (
+            MINT_CIRCUIT_BREAKER,
+            connector::get_mint_circuit_breaker(io).is_some(),
+        ),
+        (EXIT_TO_NEAR, true),
+        (EXIT_TO_ETHEREUM, true),
+        (ERROR_REFUND, cfg!(feature = "error_refund")),
+        (META_CALL, cfg!(feature = "meta-call")),
+        (EVM_BULLY, cfg!(feature = "evm_bully")),
+        (SILO_BRIDGE, true),
+        (ETH_STREAMS, true),
+        (MULTISIG, true),
+        (
+            DUPLICATE_SUBMIT_PROTECTION,
+            engine::get_duplicate_submit_window(io) > 0,
+        ),
+        (LOG_BLOOM_INDEX, engine::is_log_bloom_index_enabled(io)),
+        (
+            STORAGE_BREAKDOWN,
+            storage_breakdown::is_storage_breakdown_enabled(io),
+        ),
+        (HOST_ECRECOVER, host_functions.ecrecover),
+        (HOST_ALT_BN128, host_functions.alt_bn128),
+        (HOST_ED25519, host_functions.ed25519),
+    ];
+    capabilities
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+pub fn supports_capability<I: IO>(io: &I, name: &str) -> bool {
+    list_capabilities(io)
+        .iter()
+        .any(|capability| capability == name)
+}
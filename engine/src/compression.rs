@@ -0,0 +1,290 @@
+//! Transparent compression of the large blobs stored by the engine, the EVM bytecode.
+//!
+//! The owner enables the compression in the configuration of the engine, the code of the
+//! contracts deployed afterwards is then stored compressed whenever it makes it smaller, which
+//! lowers the NEAR staked for the storage of the silos deploying a lot of code. A compressed
+//! blob starts with a header, the `0xEF` byte followed by the compression method and the length
+//! of the data. Since EIP-3541 no contract can be deployed with code starting with `0xEF`, and a
+//! blob which doesn't decode is read as it is, so the blobs stored before the compression was
+//! introduced, or while it is disabled, are read unchanged.
+//!
+//! The method is a small LZ77 variant in the layout of the LZ4 blocks: a sequence is a token
+//! holding the lengths of its literals and of its match, the literals, then the offset of the
+//! match in the already decoded data, the last sequence has literals only.
+use crate::prelude::{vec, ConfigKey, Vec};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// First byte of a compressed blob, reserved by EIP-3541.
+pub const COMPRESSED_BLOB_MAGIC: u8 = 0xEF;
+/// Blobs smaller than this are always stored as they are.
+pub const MIN_COMPRESSED_BLOB_SIZE: usize = 256;
+
+const LZ_METHOD: u8 = 0x01;
+const HEADER_LEN: usize = 6;
+const MIN_MATCH: usize = 4;
+const MAX_OFFSET: usize = u16::MAX as usize;
+const HASH_LOG: u32 = 12;
+
+/// Whether the code is compressed when stored, `None` if the compression was never configured
+/// so that no stored blob can be compressed.
+pub fn get_code_compression<I: IO>(io: &I) -> Option<bool> {
+    storage_key::read_config(io, ConfigKey::CodeCompression, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+pub fn set_code_compression<I: IO>(io: &mut I, enabled: bool) {
+    storage_key::write_config_borsh(io, ConfigKey::CodeCompression, &[], &enabled);
+}
+
+/// Compressed blob of the data, with its header. `None` if the compression doesn't make it
+/// smaller.
+pub fn compress_blob(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < MIN_COMPRESSED_BLOB_SIZE || data.len() > u32::MAX as usize {
+        return None;
+    }
+    let mut blob = Vec::with_capacity(data.len());
+    blob.push(COMPRESSED_BLOB_MAGIC);
+    blob.push(LZ_METHOD);
+    blob.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    compress(data, &mut blob);
+    if blob.len() < data.len() {
+        Some(blob)
+    } else {
+        None
+    }
+}
+
+/// Data of the stored blob, decompressed if it is compressed.
+pub fn decode_blob(blob: Vec<u8>) -> Vec<u8> {
+    match decompress_blob(&blob) {
+        Some(data) => data,
+        None => blob,
+    }
+}
+
+fn decompress_blob(blob: &[u8]) -> Option<Vec<u8>> {
+    if blob.len() < HEADER_LEN || blob[0] != COMPRESSED_BLOB_MAGIC || blob[1] != LZ_METHOD {
+        return None;
+    }
+    let mut len = [0u8; 4];
+    len.copy_from_slice(&blob[2..HEADER_LEN]);
+    decompress(&blob[HEADER_LEN..], u32::from_le_bytes(len) as usize).ok()
+}
+
+fn read_u32(data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]])
+}
+
+fn hash(value: u32) -> usize {
+    (value.wrapping_mul(2_654_435_761) >> (32 - HASH_LOG)) as usize
+}
+
+/// Appends the compressed data to the output.
+pub fn compress(input: &[u8], output: &mut Vec<u8>) {
+    // Position + 1 of the last occurrence of each hash of 4 bytes, 0 if none.
+    let mut table = vec![0usize; 1 << HASH_LOG];
+    let mut anchor = 0;
+    let mut pos = 0;
+    while pos + MIN_MATCH <= input.len() {
+        let value = read_u32(input, pos);
+        let h = hash(value);
+        let candidate = table[h];
+        table[h] = pos + 1;
+        if candidate > 0 {
+            let candidate = candidate - 1;
+            if pos - candidate <= MAX_OFFSET && read_u32(input, candidate) == value {
+                let mut len = MIN_MATCH;
+                while pos + len < input.len() && input[candidate + len] == input[pos + len] {
+                    len += 1;
+                }
+                write_sequence(output, &input[anchor..pos], Some((pos - candidate, len)));
+                pos += len;
+                anchor = pos;
+                continue;
+            }
+        }
+        pos += 1;
+    }
+    write_sequence(output, &input[anchor..], None);
+}
+
+fn write_sequence(output: &mut Vec<u8>, literals: &[u8], m: Option<(usize, usize)>) {
+    let match_len = m.map(|(_, len)| len - MIN_MATCH).unwrap_or(0);
+    output.push(((literals.len().min(15) as u8) << 4) | match_len.min(15) as u8);
+    if literals.len() >= 15 {
+        write_length(output, literals.len() - 15);
+    }
+    output.extend_from_slice(literals);
+    if let Some((offset, _)) = m {
+        output.extend_from_slice(&(offset as u16).to_le_bytes());
+        if match_len >= 15 {
+            write_length(output, match_len - 15);
+        }
+    }
+}
+
+fn write_length(output: &mut Vec<u8>, mut len: usize) {
+    while len >= 255 {
+        output.push(255);
+        len -= 255;
+    }
+    output.push(len as u8);
+}
+
+fn read_length(input: &[u8], pos: &mut usize) -> Result<usize, error::DecompressError> {
+    let mut len = 0usize;
+    loop {
+        let byte = *input
+            .get(*pos)
+            .ok_or(error::DecompressError::UnexpectedEnd)?;
+        *pos += 1;
+        len = len
+            .checked_add(usize::from(byte))
+            .ok_or(error::DecompressError::InvalidLength)?;
+        if byte != 255 {
+            return Ok(len);
+        }
+    }
+}
+
+/// Decompresses data of the given length.
+pub fn decompress(input: &[u8], len: usize) -> Result<Vec<u8>, error::DecompressError> {
+    // The length comes from the header of a blob which may not be compressed, it is only
+    // trusted as far as the input can expand to.
+    let mut output = Vec::with_capacity(len.min(input.len().saturating_mul(255)));
+    let mut pos = 0;
+    while pos < input.len() {
+        let token = input[pos];
+        pos += 1;
+        let mut literals_len = usize::from(token >> 4);
+        if literals_len == 15 {
+            literals_len += read_length(input, &mut pos)?;
+        }
+        let literals_end = pos
+            .checked_add(literals_len)
+            .ok_or(error::DecompressError::InvalidLength)?;
+        let literals = input
+            .get(pos..literals_end)
+            .ok_or(error::DecompressError::UnexpectedEnd)?;
+        if output.len() + literals.len() > len {
+            return Err(error::DecompressError::InvalidLength);
+        }
+        output.extend_from_slice(literals);
+        pos = literals_end;
+        if pos == input.len() {
+            break;
+        }
+
+        let offset = input
+            .get(pos..pos + 2)
+            .map(|bytes| usize::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+            .ok_or(error::DecompressError::UnexpectedEnd)?;
+        pos += 2;
+        let mut match_len = usize::from(token & 0xf) + MIN_MATCH;
+        if token & 0xf == 15 {
+            match_len += read_length(input, &mut pos)?;
+        }
+        if offset == 0 || offset > output.len() {
+            return Err(error::DecompressError::InvalidOffset);
+        }
+        if output.len() + match_len > len {
+            return Err(error::DecompressError::InvalidLength);
+        }
+        // The match can overlap the bytes it produces, so it is copied byte by byte.
+        let start = output.len() - offset;
+        for i in 0..match_len {
+            let byte = output[start + i];
+            output.push(byte);
+        }
+    }
+    if output.len() != len {
+        return Err(error::DecompressError::InvalidLength);
+    }
+    Ok(output)
+}
+
+pub mod error {
+    const UNEXPECTED_END: &[u8; 29] = b"ERR_DECOMPRESS_UNEXPECTED_END";
+    const INVALID_LENGTH: &[u8; 29] = b"ERR_DECOMPRESS_INVALID_LENGTH";
+    const INVALID_OFFSET: &[u8; 29] = b"ERR_DECOMPRESS_INVALID_OFFSET";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DecompressError {
+        UnexpectedEnd,
+        /// The data doesn't have the length of the header.
+        InvalidLength,
+        /// A match refers to bytes before the start of the data.
+        InvalidOffset,
+    }
+
+    impl AsRef<[u8]> for DecompressError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::UnexpectedEnd => UNEXPECTED_END,
+                Self::InvalidLength => INVALID_LENGTH,
+                Self::InvalidOffset => INVALID_OFFSET,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(data: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        compress(data, &mut compressed);
+        assert_eq!(decompress(&compressed, data.len()).unwrap(), data);
+        compressed
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        assert_eq!(round_trip(&[]), vec![0]);
+        round_trip(b"abc");
+        round_trip(&[7; 1000]);
+        let text = b"PUSH1 0x80 PUSH1 0x40 MSTORE ".repeat(40);
+        assert!(round_trip(&text).len() < text.len() / 4);
+        // Bytes which don't repeat
+        let data: Vec<u8> = (0..5000u32)
+            .map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8)
+            .collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn test_compressed_blob() {
+        let code = [0x60, 0x80, 0x60, 0x40, 0x52].repeat(100);
+        let blob = compress_blob(&code).unwrap();
+        assert_eq!(blob[0], COMPRESSED_BLOB_MAGIC);
+        assert_eq!(decode_blob(blob), code);
+
+        // Small data is stored as it is.
+        assert_eq!(compress_blob(&code[..10]), None);
+        // Blobs which don't decode are read as they are.
+        let raw = vec![COMPRESSED_BLOB_MAGIC, LZ_METHOD, 1, 0, 0, 0, 0xff];
+        assert_eq!(decode_blob(raw.clone()), raw);
+    }
+
+    #[test]
+    fn test_decompress_invalid() {
+        assert_eq!(
+            decompress(&[0x10], 1),
+            Err(error::DecompressError::UnexpectedEnd)
+        );
+        assert_eq!(
+            decompress(&[0x10, 1, 5, 0], 5),
+            Err(error::DecompressError::InvalidOffset)
+        );
+        assert_eq!(
+            decompress(&[0x10, 1], 2),
+            Err(error::DecompressError::InvalidLength)
+        );
+        assert_eq!(
+            decompress(&[0x1f, 1, 1, 0], 2),
+            Err(error::DecompressError::UnexpectedEnd)
+        );
+    }
+}
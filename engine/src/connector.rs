@@ -1,24 +1,40 @@
 use crate::admin_controlled::{AdminControlled, PausedMask};
-use crate::deposit_event::{DepositedEvent, FtTransferMessageData, TokenMessageData};
-use crate::engine::Engine;
+use crate::callback;
+use crate::connector_migration;
+use crate::deposit_event::{DepositedEvent, FtTransferMessageData, LockedEvent, TokenMessageData};
+use crate::engine::{self, Engine};
+use crate::engine_logs;
+use crate::events;
 use crate::fungible_token::{self, FungibleToken, FungibleTokenMetadata, FungibleTokenOps};
+use crate::json::JsonValue;
+use crate::pagination;
 use crate::parameters::{
-    BalanceOfCallArgs, BalanceOfEthCallArgs, FinishDepositCallArgs, InitCallArgs,
-    NEP141FtOnTransferArgs, PauseEthConnectorCallArgs, ResolveTransferCallArgs,
-    SetContractDataCallArgs, StorageBalanceOfCallArgs, StorageDepositCallArgs,
-    StorageWithdrawCallArgs, TransferCallArgs, TransferCallCallArgs, WithdrawResult,
+    AccountBalance, BalanceOfCallArgs, BalanceOfEthCallArgs, BalancesOfCallArgs,
+    BalancesOfEthCallArgs, DepositFeeCurrency, DepositGasEstimate, DepositReceipt,
+    DepositReceiptEntry, EstimateWithdrawArgs, FinishDepositCallArgs, FinishDepositErc20CallArgs,
+    FinishDepositNearResult, ForceResolveTransferCallArgs, InitCallArgs, MinDepositArgs,
+    MintCircuitBreakerArgs, NEP141FtOnTransferArgs, Page, PageArgs, PauseEthConnectorCallArgs,
+    PendingTransferCall, ProofCacheConfig, ProverConfig, ProverStatistics, ProverStatus,
+    RecipientRules, RelayerEarnings, RelayerEarningsEntry, ResolveDepositVerificationArgs,
+    ResolveTransferCallArgs, SetContractDataCallArgs, SetIconChunkArgs, StorageBalanceOfCallArgs,
+    StorageConfig, StorageDepositCallArgs, StorageWithdrawCallArgs, TransferCallArgs,
+    TransferCallCallArgs, TransferCallOutcome, WithdrawEstimate, WithdrawResult,
 };
+use crate::prelude::types::error::BalanceOverflowError;
 use crate::prelude::{
-    format, sdk, str, validate_eth_address, AccountId, Address, Balance, BorshDeserialize,
-    BorshSerialize, EthAddress, EthConnectorStorageId, KeyPrefix, NearGas, PromiseResult, ToString,
-    Vec, WithdrawCallArgs, ERR_FAILED_PARSE, H160,
+    format, sdk, str, validate_eth_address, vec, AccountId, Address, BTreeMap, Balance,
+    BorshDeserialize, BorshSerialize, ConfigKey, EthAddress, EthConnectorStorageId, KeyPrefix,
+    NEP141Wei, NearGas, PromiseResult, String, ToString, TryFrom, Vec, Wei, WithdrawCallArgs,
+    ERR_FAILED_PARSE, H160, H256, U256,
 };
 use crate::prelude::{
     AddressValidationError, PromiseBatchAction, PromiseCreateArgs, PromiseWithCallbackArgs,
 };
-use crate::proof::Proof;
+use crate::proof::{Proof, ProofView};
+use crate::storage_key;
 use aurora_engine_sdk::env::Env;
 use aurora_engine_sdk::io::{StorageIntermediate, IO};
+use aurora_engine_sdk::promise::PromiseHandler;
 
 pub const ERR_NOT_ENOUGH_BALANCE_FOR_FEE: &str = "ERR_NOT_ENOUGH_BALANCE_FOR_FEE";
 /// Indicate zero attached balance for promise call
@@ -28,13 +44,44 @@ pub const GAS_FOR_FINISH_DEPOSIT: NearGas = NearGas::new(50_000_000_000_000);
 /// NEAR Gas for calling `verify_log_entry` promise. Used in the `deposit` logic.
 // Note: Is 40Tgas always enough?
 const GAS_FOR_VERIFY_LOG_ENTRY: NearGas = NearGas::new(40_000_000_000_000);
+/// NEAR Gas for calling `mint` on the NEP-141 token of a deposited ERC-20.
+const GAS_FOR_MINT_BRIDGED_TOKEN: NearGas = NearGas::new(10_000_000_000_000);
+/// NEAR Gas used by the `deposit` call itself, besides the gas it attaches to its promises and
+/// the gas depending on the size of the proof.
+const GAS_FOR_DEPOSIT: NearGas = NearGas::new(10_000_000_000_000);
+/// NEAR Gas per byte of the proof, which the deposit reads, hashes and passes to the prover and
+/// to its callback.
+const GAS_FOR_DEPOSIT_PER_PROOF_BYTE: u64 = 10_000_000;
+/// NEAR Gas added to the estimate of `estimate_deposit_gas` for the costs it doesn't track.
+pub const DEPOSIT_GAS_SAFETY_MARGIN: NearGas = NearGas::new(20_000_000_000_000);
+/// Denominator of the deposit protocol fee, which is in basis points.
+pub const PROTOCOL_FEE_DENOMINATOR: u16 = 10_000;
+/// Maximum number of proofs of a single `deposit_batch` call. Every deposit attaches the gas of
+/// its verification and its callback, so more of them don't fit in the gas of a transaction.
+pub const MAX_DEPOSIT_BATCH_SIZE: usize = 3;
+/// Maximum length in bytes of the memo stored in the receipt of a deposit.
+pub const MAX_RECEIPT_MEMO_LEN: usize = 64;
+/// Number of the last deposits of every Ethereum sender kept in the index by sender.
+pub const MAX_DEPOSITS_PER_SENDER: u64 = 32;
+/// Number of the fee paying deposits of a relayer between two `relayer_earnings` events.
+pub const RELAYER_EARNINGS_SUMMARY_INTERVAL: u64 = 100;
+/// Fee the connector deducts from the withdrawals of nETH, none: the custodian unlocks the
+/// whole amount burned.
+pub const WITHDRAW_FEE: Balance = 0;
+/// Default number of blocks after which a pending transfer call can be force resolved.
+pub const DEFAULT_FORCE_RESOLVE_DELAY_BLOCKS: u64 = 86_400;
+/// Number of blocks after a deposit was flagged as unclaimed before it can be reassigned.
+pub const UNCLAIMED_REASSIGN_DELAY_BLOCKS: u64 = 86_400;
 
 /// Admin control flow flag indicates that all control flow unpause (unblocked).
 pub const UNPAUSE_ALL: PausedMask = 0;
 /// Admin control flow flag indicates that the deposit is paused.
 pub const PAUSE_DEPOSIT: PausedMask = 1 << 0;
-/// Admin control flow flag indicates that withdrawal is paused.
+/// Admin control flow flag indicates that withdrawal of nETH by the NEAR accounts is paused.
 pub const PAUSE_WITHDRAW: PausedMask = 1 << 1;
+/// Admin control flow flag indicates that withdrawal of the ETH exited from the EVM is paused.
+/// The exits are withdrawn by the engine itself, so the owner doesn't skip this flag.
+pub const PAUSE_WITHDRAW_ETH: PausedMask = 1 << 2;
 
 /// Eth-connector contract data. It's stored in the storage.
 /// Contains:
@@ -49,6 +96,65 @@ pub struct EthConnectorContract<I: IO> {
     io: I,
 }
 
+/// Number of buckets the window of the mint circuit breaker is split into. The window slides
+/// by a bucket at a time.
+const MINT_VOLUME_BUCKETS: u64 = 16;
+
+/// Amounts of ETH minted and burned by the eth-connector during a bucket of the window of the
+/// mint circuit breaker.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct MintBucket {
+    /// Block height at which the bucket started.
+    start: u64,
+    minted: Balance,
+    burned: Balance,
+}
+
+/// Buckets of the window of the mint circuit breaker, oldest first.
+#[derive(BorshSerialize, BorshDeserialize, Default)]
+struct MintVolume {
+    buckets: Vec<MintBucket>,
+}
+
+impl MintVolume {
+    /// Adds the amounts to the bucket of the block, after dropping the buckets which started
+    /// before the window ending at the block. Returns the net minted amount of the window.
+    fn record(
+        &mut self,
+        minted: Balance,
+        burned: Balance,
+        block_height: u64,
+        window_blocks: u64,
+    ) -> Balance {
+        let bucket_blocks = (window_blocks / MINT_VOLUME_BUCKETS).max(1);
+        let bucket_start = block_height - block_height % bucket_blocks;
+        self.buckets
+            .retain(|bucket| bucket.start.saturating_add(window_blocks) > block_height);
+        match self.buckets.last_mut() {
+            Some(bucket) if bucket.start == bucket_start => {
+                bucket.minted = bucket.minted.saturating_add(minted);
+                bucket.burned = bucket.burned.saturating_add(burned);
+            }
+            _ => self.buckets.push(MintBucket {
+                start: bucket_start,
+                minted,
+                burned,
+            }),
+        }
+
+        let (minted, burned) =
+            self.buckets
+                .iter()
+                .fold((0, 0), |(minted, burned): (Balance, Balance), bucket| {
+                    (
+                        minted.saturating_add(bucket.minted),
+                        burned.saturating_add(bucket.burned),
+                    )
+                });
+        minted.saturating_sub(burned)
+    }
+}
+
 /// Connector specific data. It always should contain `prover account` -
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct EthConnector {
@@ -58,6 +164,22 @@ pub struct EthConnector {
     pub eth_custodian_address: EthAddress,
 }
 
+/// Outcome of a finished deposit.
+pub enum FinishDepositResult {
+    /// Deposit to a NEAR account, which is minted right away.
+    Near(FinishDepositNearResult),
+    /// Deposit to an EVM address, the minted nETH is transferred to the engine with this promise.
+    Eth(PromiseWithCallbackArgs),
+}
+
+/// Outcome of the verification of a deposit proof by a prover.
+pub enum DepositVerification {
+    /// The proof is valid, the deposit can be finished.
+    Verified(FinishDepositCallArgs),
+    /// The proof was rejected and is verified by the next enabled prover.
+    Retry(PromiseWithCallbackArgs),
+}
+
 impl<I: IO + Copy> EthConnectorContract<I> {
     /// Init Eth-connector contract instance.
     /// Load contract data from storage and init I/O handler.
@@ -108,6 +230,7 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             &construct_contract_key(&EthConnectorStorageId::PausedMask),
             &paused_mask,
         );
+        connector_migration::set_state_version(&mut io, connector_migration::CURRENT_STATE_VERSION);
 
         Self {
             contract: contract_data,
@@ -135,11 +258,11 @@ impl<I: IO + Copy> EthConnectorContract<I> {
 
         sdk::log!("[Deposit tokens]");
 
-        // Get incoming deposit arguments
-        let proof: Proof =
-            Proof::try_from_slice(&raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
+        // Get incoming deposit arguments, the raw proof is only forwarded to the prover
+        let proof =
+            ProofView::decode(&raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
         // Fetch event data from Proof
-        let event = DepositedEvent::from_log_entry_data(&proof.log_entry_data)
+        let event = DepositedEvent::from_log_entry_data(proof.log_entry_data)
             .map_err(error::DepositError::EventParseFailed)?;
 
         sdk::log!(&format!(
@@ -156,35 +279,42 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             hex::encode(&self.contract.eth_custodian_address),
         ));
 
-        if event.eth_custodian_address != self.contract.eth_custodian_address {
+        if !is_custodian(&self.io, &self.contract, &event.eth_custodian_address) {
             return Err(error::DepositError::CustodianAddressMismatch);
         }
-
-        if event.fee.into_u128() >= event.amount {
-            return Err(error::DepositError::InsufficientAmountForFee);
+        if let TokenMessageData::Near(recipient) = &event.token_message_data {
+            check_recipient(&get_recipient_rules(&self.io), recipient)?;
         }
 
-        // Verify proof data with cross-contract call to prover account
-        sdk::log!(&format!(
-            "Deposit verify_log_entry for prover: {}",
-            self.contract.prover_account,
-        ));
+        // Proofs used before the proof key derivation changed are recorded with the legacy key.
+        if self.is_used_event(&proof.get_legacy_key()) {
+            return Err(error::DepositError::ProofUsed);
+        }
 
-        // Do not skip bridge call. This is only used for development and diagnostics.
-        let skip_bridge_call = false.try_to_vec().unwrap();
-        let mut proof_to_verify = raw_proof;
-        proof_to_verify.extend(skip_bridge_call);
+        // Dust deposits are rejected here, before the proof is verified, rather than in the
+        // callbacks minting them.
+        let min_deposit = get_min_deposit(&self.io);
+        if event.amount < min_deposit.min_amount {
+            return Err(error::DepositError::BelowMinDeposit);
+        }
+        if event.amount
+            <= event
+                .fee
+                .into_u128()
+                .saturating_add(min_deposit.min_net_amount)
+        {
+            return Err(error::DepositError::InsufficientAmountForFee);
+        }
 
-        let verify_call = PromiseCreateArgs {
-            target_account_id: self.contract.prover_account.clone(),
-            method: "verify_log_entry".to_string(),
-            args: proof_to_verify,
-            attached_balance: ZERO_ATTACHED_BALANCE,
-            attached_gas: GAS_FOR_VERIFY_LOG_ENTRY.into_u64(),
-        };
+        // Verify proof data with cross-contract call to the first enabled prover
+        let provers = get_enabled_provers(&self.io);
+        let prover_account_id = provers
+            .first()
+            .cloned()
+            .ok_or(error::DepositError::NoEnabledProver)?;
 
         // Finalize deposit
-        let data = match event.token_message_data {
+        let finish_args = match event.token_message_data {
             // Deposit to NEAR accounts
             TokenMessageData::Near(account_id) => FinishDepositCallArgs {
                 new_owner_id: account_id,
@@ -193,9 +323,9 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                 relayer_id: predecessor_account_id,
                 fee: event.fee,
                 msg: None,
-            }
-            .try_to_vec()
-            .unwrap(),
+                memo: event.memo,
+                sender: Some(event.sender),
+            },
             // Deposit to Eth accounts
             // fee is being minted in the `ft_on_transfer` callback method
             TokenMessageData::Eth {
@@ -209,6 +339,7 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                     amount: event.amount,
                     memo: None,
                     msg: message.encode(),
+                    gas_for_on_transfer: None,
                 }
                 .try_to_vec()
                 .unwrap();
@@ -221,23 +352,305 @@ impl<I: IO + Copy> EthConnectorContract<I> {
                     relayer_id: predecessor_account_id,
                     fee: event.fee,
                     msg: Some(transfer_data),
+                    memo: event.memo,
+                    sender: Some(event.sender),
                 }
-                .try_to_vec()
-                .unwrap()
             }
         };
 
-        let finish_call = PromiseCreateArgs {
+        Ok(Self::verify_deposit_proof(
+            ResolveDepositVerificationArgs {
+                prover_account_id,
+                proof: raw_proof,
+                finish_args,
+            },
+            current_account_id,
+            provers.len() as u64 - 1,
+        ))
+    }
+
+    /// Deposit as `deposit`, except that the prover call is skipped if the receipt of the
+    /// proof was verified recently, when the verified proof cache is enabled, or if it is
+    /// proven against the header of a block whose hash was provided by the light client. The
+    /// deposit is then finished right away.
+    pub fn deposit_cached(
+        &self,
+        raw_proof: Vec<u8>,
+        current_account_id: AccountId,
+        predecessor_account_id: AccountId,
+        block_height: u64,
+    ) -> Result<DepositVerification, error::DepositError> {
+        let is_cached = get_proof_cache_config(&self.io).ttl_blocks > 0
+            && ProofView::decode(&raw_proof)
+                .map(|proof| {
+                    proof.is_log_entry_in_receipt()
+                        && self.is_receipt_verified(&proof.receipt_hash(), block_height)
+                })
+                .unwrap_or(false);
+        let is_proven = !is_cached && self.is_receipt_proven(&raw_proof);
+        let promise_args = self.deposit(raw_proof, current_account_id, predecessor_account_id)?;
+        if is_cached {
+            sdk::log!("Deposit proof receipt found in the verified proof cache");
+        } else if is_proven {
+            sdk::log!("Deposit proof receipt proven against a light client block hash");
+        } else {
+            return Ok(DepositVerification::Retry(promise_args));
+        }
+        let args = ResolveDepositVerificationArgs::try_from_slice(&promise_args.callback.args)
+            .map_err(|_| error::DepositError::ProofParseFailed)?;
+        Ok(DepositVerification::Verified(args.finish_args))
+    }
+
+    /// Whether the proof is verified by the engine itself: its block header has a hash provided
+    /// by the light client, the receipt is in the receipts trie of the header and the log entry
+    /// in the receipt.
+    fn is_receipt_proven(&self, raw_proof: &[u8]) -> bool {
+        if get_light_client(&self.io).is_none() {
+            return false;
+        }
+        let proof = match Proof::try_from_slice(raw_proof) {
+            Ok(proof) => proof,
+            Err(_) => return false,
+        };
+        is_light_client_block_hash(&self.io, &sdk::keccak(&proof.header_data))
+            && proof.is_receipt_in_header()
+            && ProofView::decode(raw_proof)
+                .map(|view| view.is_log_entry_in_receipt())
+                .unwrap_or(false)
+    }
+
+    fn is_receipt_verified(&self, receipt_hash: &H256, block_height: u64) -> bool {
+        let ttl_blocks = get_proof_cache_config(&self.io).ttl_blocks;
+        self.io
+            .read_u64(&verified_receipt_key(receipt_hash))
+            .ok()
+            .map(|verified_at| block_height <= verified_at.saturating_add(ttl_blocks))
+            .unwrap_or(false)
+    }
+
+    /// Deposit from several proofs at once, e.g. to catch up after an outage of a relayer.
+    /// Every proof is validated as by `deposit`, the whole batch fails if any of them is
+    /// invalid. Returns the promises of the deposits in the order of the proofs.
+    pub fn deposit_batch(
+        &self,
+        proofs: Vec<Proof>,
+        current_account_id: AccountId,
+        predecessor_account_id: AccountId,
+    ) -> Result<Vec<PromiseWithCallbackArgs>, error::DepositError> {
+        if proofs.is_empty() {
+            return Err(error::DepositError::EmptyBatch);
+        }
+        if proofs.len() > MAX_DEPOSIT_BATCH_SIZE {
+            return Err(error::DepositError::BatchTooLarge);
+        }
+        let raw_proofs: Vec<Vec<u8>> = proofs.iter().map(|p| p.try_to_vec().unwrap()).collect();
+        // The callbacks of a deposit only accept a single proof within the limit of `deposit`.
+        if raw_proofs
+            .iter()
+            .any(|raw_proof| raw_proof.len() > crate::input_limits::MAX_PROOF_INPUT_LEN)
+        {
+            return Err(error::DepositError::ProofTooLarge);
+        }
+        // A proof is only recorded as used once its deposit is finished.
+        let mut keys: Vec<String> = proofs.iter().map(Proof::get_key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        if keys.len() != proofs.len() {
+            return Err(error::DepositError::DuplicateProof);
+        }
+
+        raw_proofs
+            .into_iter()
+            .map(|raw_proof| {
+                self.deposit(
+                    raw_proof,
+                    current_account_id.clone(),
+                    predecessor_account_id.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Deposit of an ERC-20 token locked in the ERC-20 locker on Ethereum. The proof of the
+    /// Locked event is verified by the first enabled prover, then `finish_deposit_erc20` mints
+    /// the NEP-141 token the ERC-20 is registered with. There is no fallback to the other
+    /// provers, a rejected proof can be submitted again.
+    pub fn deposit_erc20(
+        &self,
+        raw_proof: Vec<u8>,
+        current_account_id: AccountId,
+        predecessor_account_id: AccountId,
+    ) -> Result<PromiseWithCallbackArgs, error::DepositError> {
+        let is_owner = current_account_id == predecessor_account_id;
+        self.assert_not_paused(PAUSE_DEPOSIT, is_owner)
+            .map_err(|_| error::DepositError::Paused)?;
+
+        let proof =
+            ProofView::decode(&raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
+        let event = LockedEvent::from_log_entry_data(proof.log_entry_data)
+            .map_err(error::DepositError::EventParseFailed)?;
+
+        sdk::log!(&format!(
+            "Deposit of ERC-20 {} started: from {} to recipient {} with amount: {}",
+            hex::encode(event.token),
+            hex::encode(event.sender),
+            event.recipient,
+            event.amount,
+        ));
+
+        if get_erc20_locker(&self.io) != Some(event.locker_address) {
+            return Err(error::DepositError::LockerAddressMismatch);
+        }
+        check_recipient(&get_recipient_rules(&self.io), &event.recipient)?;
+        let nep141 = get_bridged_nep141(&self.io, &event.token)
+            .ok_or(error::DepositError::UnknownErc20Token)?;
+        let proof_key = proof.get_key();
+        if self.is_used_event(&proof_key) || self.is_used_event(&proof.get_legacy_key()) {
+            return Err(error::DepositError::ProofUsed);
+        }
+
+        let prover_account_id = get_enabled_provers(&self.io)
+            .first()
+            .cloned()
+            .ok_or(error::DepositError::NoEnabledProver)?;
+
+        // Do not skip bridge call. This is only used for development and diagnostics.
+        let mut proof_to_verify = raw_proof;
+        proof_to_verify.extend(false.try_to_vec().unwrap());
+        let finish_args = FinishDepositErc20CallArgs {
+            nep141,
+            new_owner_id: event.recipient,
+            amount: event.amount,
+            proof_key,
+        };
+        Ok(PromiseWithCallbackArgs {
+            base: PromiseCreateArgs {
+                target_account_id: prover_account_id,
+                method: "verify_log_entry".to_string(),
+                args: proof_to_verify,
+                attached_balance: ZERO_ATTACHED_BALANCE,
+                attached_gas: GAS_FOR_VERIFY_LOG_ENTRY.into_u64(),
+            },
+            callback: PromiseCreateArgs {
+                target_account_id: current_account_id,
+                method: "finish_deposit_erc20".to_string(),
+                args: finish_args.try_to_vec().unwrap(),
+                attached_balance: ZERO_ATTACHED_BALANCE,
+                attached_gas: GAS_FOR_FINISH_DEPOSIT.into_u64(),
+            },
+        })
+    }
+
+    /// Finish deposit of an ERC-20 token (private method). The proof is recorded before the
+    /// NEP-141 token is minted, so the same lock can't be minted twice while the mint is in
+    /// flight.
+    pub fn finish_deposit_erc20(
+        &mut self,
+        data: FinishDepositErc20CallArgs,
+    ) -> Result<PromiseCreateArgs, error::FinishDepositError> {
+        self.record_proof(&data.proof_key)?;
+        sdk::log!(&format!(
+            "Mint {} of {} to {}",
+            data.amount, data.nep141, data.new_owner_id
+        ));
+        Ok(PromiseCreateArgs {
+            target_account_id: data.nep141,
+            method: "mint".to_string(),
+            args: format!(
+                r#"{{"account_id": "{}", "amount": "{}"}}"#,
+                data.new_owner_id, data.amount
+            )
+            .into_bytes(),
+            attached_balance: ZERO_ATTACHED_BALANCE,
+            attached_gas: GAS_FOR_MINT_BRIDGED_TOKEN.into_u64(),
+        })
+    }
+
+    /// Creates the promise verifying the proof with the prover of the arguments. Its callback
+    /// `resolve_deposit_verification` falls back to the next enabled prover if the proof is
+    /// rejected, `fallbacks` is the number of the enabled provers left after this one.
+    fn verify_deposit_proof(
+        args: ResolveDepositVerificationArgs,
+        current_account_id: AccountId,
+        fallbacks: u64,
+    ) -> PromiseWithCallbackArgs {
+        sdk::log!(&format!(
+            "Deposit verify_log_entry for prover: {}",
+            args.prover_account_id,
+        ));
+
+        // Do not skip bridge call. This is only used for development and diagnostics.
+        let skip_bridge_call = false.try_to_vec().unwrap();
+        let mut proof_to_verify = args.proof.clone();
+        proof_to_verify.extend(skip_bridge_call);
+
+        let verify_call = PromiseCreateArgs {
+            target_account_id: args.prover_account_id.clone(),
+            method: "verify_log_entry".to_string(),
+            args: proof_to_verify,
+            attached_balance: ZERO_ATTACHED_BALANCE,
+            attached_gas: GAS_FOR_VERIFY_LOG_ENTRY.into_u64(),
+        };
+
+        let resolve_call = PromiseCreateArgs {
             target_account_id: current_account_id,
-            method: "finish_deposit".to_string(),
-            args: data,
+            method: "resolve_deposit_verification".to_string(),
+            args: args.try_to_vec().unwrap(),
             attached_balance: ZERO_ATTACHED_BALANCE,
-            attached_gas: GAS_FOR_FINISH_DEPOSIT.into_u64(),
+            attached_gas: gas_for_resolve_deposit_verification(fallbacks).into_u64(),
         };
-        Ok(PromiseWithCallbackArgs {
+        PromiseWithCallbackArgs {
             base: verify_call,
-            callback: finish_call,
-        })
+            callback: resolve_call,
+        }
+    }
+
+    /// Accounts the result of the verification of a deposit proof by a prover (private method).
+    /// Returns the arguments to finish the deposit with if the proof was validated, otherwise
+    /// the promise verifying the proof with the next enabled prover.
+    pub fn resolve_deposit_verification(
+        &mut self,
+        args: ResolveDepositVerificationArgs,
+        verified: bool,
+        current_account_id: AccountId,
+        block_height: u64,
+    ) -> Result<DepositVerification, error::DepositError> {
+        let mut statistics = get_prover_statistics(&self.io, &args.prover_account_id);
+        if verified {
+            statistics.verified += 1;
+            self.io
+                .write_borsh(&prover_statistics_key(&args.prover_account_id), &statistics);
+            if get_proof_cache_config(&self.io).ttl_blocks > 0 {
+                if let Ok(proof) = ProofView::decode(&args.proof) {
+                    self.io.write_storage(
+                        &verified_receipt_key(&proof.receipt_hash()),
+                        &block_height.to_le_bytes(),
+                    );
+                }
+            }
+            return Ok(DepositVerification::Verified(args.finish_args));
+        }
+
+        // The provers might have been reconfigured since the deposit started.
+        let provers = get_enabled_provers(&self.io);
+        let next_index = provers
+            .iter()
+            .position(|account_id| account_id == &args.prover_account_id)
+            .map(|index| index + 1)
+            .filter(|index| *index < provers.len())
+            .ok_or(error::DepositError::ProofVerificationFailed)?;
+
+        statistics.rejected += 1;
+        self.io
+            .write_borsh(&prover_statistics_key(&args.prover_account_id), &statistics);
+        Ok(DepositVerification::Retry(Self::verify_deposit_proof(
+            ResolveDepositVerificationArgs {
+                prover_account_id: provers[next_index].clone(),
+                ..args
+            },
+            current_account_id,
+            (provers.len() - next_index - 1) as u64,
+        )))
     }
 
     /// Finish deposit (private method)
@@ -251,37 +664,369 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         current_account_id: AccountId,
         data: FinishDepositCallArgs,
         prepaid_gas: NearGas,
-    ) -> Result<Option<PromiseWithCallbackArgs>, error::FinishDepositError> {
+        block_height: u64,
+    ) -> Result<FinishDepositResult, error::FinishDepositError> {
         sdk::log!(&format!("Finish deposit with the amount: {}", data.amount));
 
-        // Mint tokens to recipient minus fee
-        if let Some(msg) = data.msg {
-            // Mint - calculate new balances
-            self.mint_eth_on_near(data.new_owner_id, data.amount)?;
-            // Store proof only after `mint` calculations
-            self.record_proof(&data.proof_key)?;
-            // Save new contract data
-            self.save_ft_contract();
-            let transfer_call_args = TransferCallCallArgs::try_from_slice(&msg).unwrap();
-            let promise = self.ft_transfer_call(
-                predecessor_account_id,
-                current_account_id,
-                transfer_call_args,
-                prepaid_gas,
-            )?;
-            Ok(Some(promise))
+        match data.msg.clone() {
+            Some(msg) => self
+                .finish_deposit_eth(
+                    predecessor_account_id,
+                    current_account_id,
+                    data,
+                    &msg,
+                    prepaid_gas,
+                    block_height,
+                )
+                .map(FinishDepositResult::Eth),
+            None => self
+                .finish_deposit_near(current_account_id, data, block_height)
+                .map(FinishDepositResult::Near),
+        }
+    }
+
+    /// Mints the deposit to the engine itself, then transfers it to the EVM address of the
+    /// message with a transfer call.
+    fn finish_deposit_eth(
+        &mut self,
+        predecessor_account_id: AccountId,
+        current_account_id: AccountId,
+        data: FinishDepositCallArgs,
+        msg: &[u8],
+        prepaid_gas: NearGas,
+        block_height: u64,
+    ) -> Result<PromiseWithCallbackArgs, error::FinishDepositError> {
+        let protocol_fee = self.deposit_protocol_fee(&data)?;
+        let mut transfer_call_args = TransferCallCallArgs::try_from_slice(msg)
+            .map_err(|_| error::FinishDepositError::InvalidMessage)?;
+        // Mint - calculate new balances
+        self.mint_eth_on_near(data.new_owner_id.clone(), NEP141Wei::new(data.amount))?;
+        // Store proof only after `mint` calculations
+        self.record_proof(&data.proof_key)?;
+        self.record_mint_volume(data.amount, 0, block_height);
+        // Save new contract data
+        self.save_ft_contract();
+        self.record_deposit_receipt(&data, protocol_fee);
+        sdk::log!(&events::deposit_log(&data));
+        // The deposit is minted to the engine itself, the protocol fee is not transferred.
+        accrue_protocol_fees(&mut self.io, protocol_fee);
+        transfer_call_args.amount = NEP141Wei::new(transfer_call_args.amount)
+            .checked_sub(protocol_fee)
+            .ok_or(error::FtTransferCallError::InsufficientAmountForFee)?
+            .as_u128();
+        sdk::log!(&events::ft_mint_log(&[events::FtMint {
+            owner_id: &data.new_owner_id,
+            amount: data.amount,
+            memo: data.memo.as_deref(),
+        }]));
+        let promise = self.ft_transfer_call(
+            predecessor_account_id,
+            current_account_id,
+            transfer_call_args,
+            prepaid_gas,
+            block_height,
+        )?;
+        Ok(promise)
+    }
+
+    /// Mints the deposit net of the fees to the NEAR account, and the fees to the relayer and
+    /// to the engine.
+    fn finish_deposit_near(
+        &mut self,
+        current_account_id: AccountId,
+        data: FinishDepositCallArgs,
+        block_height: u64,
+    ) -> Result<FinishDepositNearResult, error::FinishDepositError> {
+        let protocol_fee = self.deposit_protocol_fee(&data)?;
+        let fee = NEP141Wei::from(data.fee);
+        let amount = NEP141Wei::new(data.amount)
+            .checked_sub_fee(data.fee)
+            .and_then(|amount| amount.checked_sub(protocol_fee))
+            .ok_or(error::FtTransferCallError::InsufficientAmountForFee)?;
+        // Mint - calculate new balances
+        self.mint_eth_on_near(data.new_owner_id.clone(), amount)?;
+        self.mint_eth_on_near(data.relayer_id.clone(), fee)?;
+        record_relayer_fee(&mut self.io, &data.relayer_id, fee, false);
+        if !protocol_fee.is_zero() {
+            self.mint_eth_on_near(current_account_id.clone(), protocol_fee)?;
+            accrue_protocol_fees(&mut self.io, protocol_fee);
+        }
+        // Store proof only after `mint` calculations
+        self.record_proof(&data.proof_key)?;
+        self.record_mint_volume(data.amount, 0, block_height);
+        // Save new contract data
+        self.save_ft_contract();
+        self.record_deposit_receipt(&data, protocol_fee);
+        sdk::log!(&events::deposit_log(&data));
+        sdk::log!(&events::ft_mint_log(&[
+            events::FtMint {
+                owner_id: &data.new_owner_id,
+                amount: amount.as_u128(),
+                memo: data.memo.as_deref(),
+            },
+            events::FtMint {
+                owner_id: &data.relayer_id,
+                amount: fee.as_u128(),
+                memo: None,
+            },
+        ]));
+        if !protocol_fee.is_zero() {
+            sdk::log!(&events::ft_mint_log(&[events::FtMint {
+                owner_id: &current_account_id,
+                amount: protocol_fee.as_u128(),
+                memo: Some("protocol fee"),
+            }]));
+        }
+        Ok(FinishDepositNearResult {
+            recipient_id: data.new_owner_id,
+            amount: amount.as_u128(),
+            relayer_id: data.relayer_id,
+            fee: fee.as_u128(),
+            protocol_fee: protocol_fee.as_u128(),
+        })
+    }
+
+    /// Part of the deposited amount net of the relayer fee taken by the protocol, unless the
+    /// relayer is in the fee waiver list.
+    fn deposit_protocol_fee(
+        &self,
+        data: &FinishDepositCallArgs,
+    ) -> Result<NEP141Wei, error::FtTransferCallError> {
+        let net_amount = NEP141Wei::new(data.amount)
+            .checked_sub_fee(data.fee)
+            .ok_or(error::FtTransferCallError::InsufficientAmountForFee)?
+            .as_u128();
+        if is_fee_waived(&self.io, &data.relayer_id) {
+            return Ok(NEP141Wei::zero());
+        }
+        let fee_bps = Balance::from(get_deposit_protocol_fee(&self.io));
+        let denominator = Balance::from(PROTOCOL_FEE_DENOMINATOR);
+        // Can't overflow, unlike `net_amount * fee_bps`.
+        Ok(NEP141Wei::new(
+            net_amount / denominator * fee_bps + net_amount % denominator * fee_bps / denominator,
+        ))
+    }
+
+    fn record_deposit_receipt(&mut self, data: &FinishDepositCallArgs, protocol_fee: NEP141Wei) {
+        let receipt = DepositReceipt {
+            recipient_id: data.new_owner_id.clone(),
+            amount: data.amount,
+            fee: data.fee,
+            protocol_fee: protocol_fee.as_u128(),
+            memo: data
+                .memo
+                .as_ref()
+                .map(|memo| truncate_memo(memo, MAX_RECEIPT_MEMO_LEN).to_string()),
+        };
+        self.io
+            .write_borsh(&deposit_receipt_key(&data.proof_key), &receipt);
+        pagination::append(
+            &mut self.io,
+            pagination::Collection::DepositReceipts,
+            data.proof_key.as_bytes(),
+        );
+        if let Some(sender) = data.sender.as_ref() {
+            self.record_deposit_by_sender(sender, &data.proof_key);
+        }
+    }
+
+    /// Keeps the proof key of the deposit in the ring of the last `MAX_DEPOSITS_PER_SENDER`
+    /// deposits of the sender, overwriting its oldest deposit once the ring is full.
+    fn record_deposit_by_sender(&mut self, sender: &EthAddress, proof_key: &str) {
+        let count_key = deposits_by_sender_key(sender, &[]);
+        let count = self.io.read_u64(&count_key).unwrap_or(0);
+        let slot = count % MAX_DEPOSITS_PER_SENDER;
+        self.io.write_storage(
+            &deposits_by_sender_key(sender, &slot.to_be_bytes()),
+            proof_key.as_bytes(),
+        );
+        self.io
+            .write_storage(&count_key, &(count + 1).to_le_bytes());
+    }
+
+    /// Returns the receipt of the deposit of the proof, if it was finished.
+    pub fn get_deposit_receipt(&self, proof: Proof) -> Option<DepositReceipt> {
+        self.io
+            .read_storage(&deposit_receipt_key(&proof.get_key()))
+            .and_then(|data| data.to_value().ok())
+    }
+
+    pub fn get_unclaimed_deposit(&self, deposit_id: &str) -> Option<UnclaimedDeposit> {
+        self.io
+            .read_storage(&unclaimed_deposit_key(deposit_id))
+            .and_then(|data| data.to_value().ok())
+    }
+
+    /// Flags the deposit to a NEAR account as minted to an invalid or unreachable recipient,
+    /// or removes the flag. The deposit can be reassigned `UNCLAIMED_REASSIGN_DELAY_BLOCKS`
+    /// blocks after it was flagged, which leaves the recipient time to prove the flag wrong.
+    pub fn set_unclaimed_deposit(
+        &mut self,
+        deposit_id: &str,
+        flagged: bool,
+        current_account_id: &AccountId,
+        block_height: u64,
+    ) -> Result<(), error::UnclaimedDepositError> {
+        let receipt = self.unclaimed_deposit_receipt(deposit_id)?;
+        if &receipt.recipient_id == current_account_id {
+            return Err(error::UnclaimedDepositError::DepositToEngine);
+        }
+        match self.get_unclaimed_deposit(deposit_id) {
+            Some(UnclaimedDeposit {
+                reassigned_to: Some(_),
+                ..
+            }) => return Err(error::UnclaimedDepositError::AlreadyReassigned),
+            Some(_) if flagged => return Ok(()),
+            None if !flagged => return Ok(()),
+            _ => {}
+        }
+        if flagged {
+            self.io.write_borsh(
+                &unclaimed_deposit_key(deposit_id),
+                &UnclaimedDeposit {
+                    flagged_at: block_height,
+                    reassigned_to: None,
+                },
+            );
         } else {
-            // Mint - calculate new balances
-            self.mint_eth_on_near(
-                data.new_owner_id.clone(),
-                data.amount - data.fee.into_u128(),
-            )?;
-            self.mint_eth_on_near(data.relayer_id, data.fee.into_u128())?;
-            // Store proof only after `mint` calculations
-            self.record_proof(&data.proof_key)?;
-            // Save new contract data
-            self.save_ft_contract();
-            Ok(None)
+            self.io.remove_storage(&unclaimed_deposit_key(deposit_id));
+        }
+        sdk::log!(&events::unclaimed_deposit_flag_log(
+            deposit_id,
+            &receipt.recipient_id,
+            flagged
+        ));
+        Ok(())
+    }
+
+    /// Removes the flag of the deposit on behalf of its recipient, which proves the recipient
+    /// is reachable. Only possible until the deposit is reassigned.
+    pub fn claim_unclaimed_deposit(
+        &mut self,
+        deposit_id: &str,
+        predecessor_account_id: &AccountId,
+    ) -> Result<(), error::UnclaimedDepositError> {
+        let receipt = self.unclaimed_deposit_receipt(deposit_id)?;
+        if &receipt.recipient_id != predecessor_account_id {
+            return Err(error::UnclaimedDepositError::NotRecipient);
+        }
+        match self.get_unclaimed_deposit(deposit_id) {
+            None => return Err(error::UnclaimedDepositError::NotFlagged),
+            Some(UnclaimedDeposit {
+                reassigned_to: Some(_),
+                ..
+            }) => return Err(error::UnclaimedDepositError::AlreadyReassigned),
+            Some(_) => {}
+        }
+        self.io.remove_storage(&unclaimed_deposit_key(deposit_id));
+        sdk::log!(&events::unclaimed_deposit_flag_log(
+            deposit_id,
+            &receipt.recipient_id,
+            false
+        ));
+        Ok(())
+    }
+
+    /// Moves the tokens of a flagged deposit from its recipient to the new recipient, once the
+    /// delay after the flag elapsed. The amount moved is the amount the recipient was credited,
+    /// net of the relayer and protocol fees, or its balance if lower. Returns the amount moved.
+    pub fn reassign_unclaimed(
+        &mut self,
+        deposit_id: &str,
+        new_recipient: &AccountId,
+        block_height: u64,
+    ) -> Result<Balance, error::UnclaimedDepositError> {
+        let mut unclaimed = self
+            .get_unclaimed_deposit(deposit_id)
+            .ok_or(error::UnclaimedDepositError::NotFlagged)?;
+        if unclaimed.reassigned_to.is_some() {
+            return Err(error::UnclaimedDepositError::AlreadyReassigned);
+        }
+        if block_height < unclaimed.flagged_at + UNCLAIMED_REASSIGN_DELAY_BLOCKS {
+            return Err(error::UnclaimedDepositError::DelayNotElapsed);
+        }
+        let receipt = self.unclaimed_deposit_receipt(deposit_id)?;
+        let amount = NEP141Wei::new(receipt.amount)
+            .checked_sub_fee(receipt.fee)
+            .and_then(|amount| amount.checked_sub(NEP141Wei::new(receipt.protocol_fee)))
+            .unwrap_or_default()
+            .min(NEP141Wei::new(self.ft.ft_balance_of(&receipt.recipient_id)));
+        self.ft
+            .internal_transfer_eth_on_near(&receipt.recipient_id, new_recipient, amount, &None)
+            .map_err(error::UnclaimedDepositError::Transfer)?;
+        self.save_ft_contract();
+        unclaimed.reassigned_to = Some(new_recipient.clone());
+        self.io
+            .write_borsh(&unclaimed_deposit_key(deposit_id), &unclaimed);
+        sdk::log!(&events::unclaimed_deposit_reassigned_log(
+            deposit_id,
+            &receipt.recipient_id,
+            new_recipient,
+            amount.as_u128()
+        ));
+        Ok(amount.as_u128())
+    }
+
+    /// Transfers `amount` of the protocol fees accrued on the engine account to `receiver_id`.
+    pub fn withdraw_protocol_fees(
+        &mut self,
+        current_account_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance,
+    ) -> Result<(), error::WithdrawProtocolFeesError> {
+        let remaining = get_protocol_fees(&self.io)
+            .checked_sub(amount)
+            .ok_or(error::WithdrawProtocolFeesError::InsufficientProtocolFees)?;
+        self.ft
+            .internal_transfer_eth_on_near(
+                current_account_id,
+                receiver_id,
+                NEP141Wei::new(amount),
+                &None,
+            )
+            .map_err(error::WithdrawProtocolFeesError::Transfer)?;
+        self.save_ft_contract();
+        self.io.write_borsh(
+            &construct_contract_key(&EthConnectorStorageId::ProtocolFees),
+            &remaining,
+        );
+        Ok(())
+    }
+
+    fn unclaimed_deposit_receipt(
+        &self,
+        deposit_id: &str,
+    ) -> Result<DepositReceipt, error::UnclaimedDepositError> {
+        self.io
+            .read_storage(&deposit_receipt_key(deposit_id))
+            .and_then(|data| data.to_value().ok())
+            .ok_or(error::UnclaimedDepositError::DepositNotFound)
+    }
+
+    /// Accounts minted and burned amounts in the sliding window of the circuit breaker, the
+    /// last `window_blocks` blocks. Deposits are paused when the net minted amount exceeds the
+    /// threshold of the window.
+    fn record_mint_volume(&mut self, minted: Balance, burned: Balance, block_height: u64) {
+        let circuit_breaker = match get_mint_circuit_breaker(&self.io) {
+            Some(circuit_breaker) if circuit_breaker.window_blocks > 0 => circuit_breaker,
+            _ => return,
+        };
+        let window_blocks = circuit_breaker.window_blocks;
+        let volume_key = construct_contract_key(&EthConnectorStorageId::MintVolume);
+        let mut volume: MintVolume = self
+            .io
+            .read_storage(&volume_key)
+            .and_then(|data| data.to_value().ok())
+            .unwrap_or_default();
+        let net_mint = volume.record(minted, burned, block_height, window_blocks);
+        self.io.write_borsh(&volume_key, &volume);
+
+        if net_mint > circuit_breaker.max_net_mint && self.paused_mask & PAUSE_DEPOSIT == 0 {
+            self.set_paused(self.paused_mask | PAUSE_DEPOSIT);
+            sdk::log!(&format!(
+                "ALERT: mint circuit breaker tripped, net minted {} in the last {} blocks exceeds {}, deposits paused",
+                net_mint, window_blocks, circuit_breaker.max_net_mint
+            ));
         }
     }
 
@@ -289,13 +1034,46 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     pub(crate) fn internal_remove_eth(
         &mut self,
         address: &Address,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), fungible_token::error::WithdrawError> {
         self.burn_eth_on_aurora(address.0, amount)?;
         self.save_ft_contract();
         Ok(())
     }
 
+    /// Internal nETH mint logic, used for transfers between silos
+    pub(crate) fn internal_mint_eth_on_near(
+        &mut self,
+        owner_id: AccountId,
+        amount: NEP141Wei,
+    ) -> Result<(), fungible_token::error::DepositError> {
+        self.mint_eth_on_near(owner_id.clone(), amount)?;
+        self.save_ft_contract();
+        sdk::log!(&events::ft_mint_log(&[events::FtMint {
+            owner_id: &owner_id,
+            amount: amount.as_u128(),
+            memo: None,
+        }]));
+        Ok(())
+    }
+
+    /// Internal nETH burn logic, used for transfers between silos
+    pub(crate) fn internal_burn_eth_on_near(
+        &mut self,
+        account_id: &AccountId,
+        amount: NEP141Wei,
+    ) -> Result<(), fungible_token::error::WithdrawError> {
+        self.ft
+            .internal_withdraw_eth_from_near(account_id, amount)?;
+        self.save_ft_contract();
+        sdk::log!(&events::ft_burn_log(&[events::FtBurn {
+            owner_id: account_id,
+            amount: amount.as_u128(),
+            memo: None,
+        }]));
+        Ok(())
+    }
+
     /// Record used proof as hash key
     fn record_proof(&mut self, key: &str) -> Result<(), error::ProofUsed> {
         sdk::log!(&format!("Record proof: {}", key));
@@ -312,13 +1090,16 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     fn mint_eth_on_near(
         &mut self,
         owner_id: AccountId,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), fungible_token::error::DepositError> {
-        sdk::log!(&format!("Mint {} nETH tokens for: {}", amount, owner_id));
-
-        if self.ft.get_account_eth_balance(&owner_id).is_none() {
-            self.ft.accounts_insert(&owner_id, 0);
+        // Replaced by the `ft_mint` event.
+        if events::is_legacy_logs_enabled(&self.io) {
+            sdk::log!(&format!("Mint {} nETH tokens for: {}", amount, owner_id));
         }
+
+        self.ft
+            .register_implicit_account(&owner_id)
+            .map_err(|_| fungible_token::error::DepositError::StoragePoolExhausted)?;
         self.ft.internal_deposit_eth_to_near(&owner_id, amount)
     }
 
@@ -326,28 +1107,58 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     fn mint_eth_on_aurora(
         &mut self,
         owner_id: EthAddress,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), fungible_token::error::DepositError> {
-        sdk::log!(&format!(
-            "Mint {} ETH tokens for: {}",
-            amount,
-            hex::encode(owner_id)
-        ));
-        self.ft.internal_deposit_eth_to_aurora(owner_id, amount)
+        // Replaced by the `eth_mint` event.
+        if events::is_legacy_logs_enabled(&self.io) {
+            sdk::log!(&format!(
+                "Mint {} ETH tokens for: {}",
+                amount,
+                hex::encode(owner_id)
+            ));
+        }
+        self.ft.internal_deposit_eth_to_aurora(owner_id, amount)?;
+        sdk::log!(&events::eth_mint_log(&owner_id, amount.as_u128()));
+        Ok(())
     }
 
     /// Burn ETH tokens
     fn burn_eth_on_aurora(
         &mut self,
         address: EthAddress,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), fungible_token::error::WithdrawError> {
-        sdk::log!(&format!(
-            "Burn {} ETH tokens for: {}",
-            amount,
-            hex::encode(address)
-        ));
-        self.ft.internal_withdraw_eth_from_aurora(address, amount)
+        // Replaced by the `eth_burn` event.
+        if events::is_legacy_logs_enabled(&self.io) {
+            sdk::log!(&format!(
+                "Burn {} ETH tokens for: {}",
+                amount,
+                hex::encode(address)
+            ));
+        }
+        self.ft.internal_withdraw_eth_from_aurora(address, amount)?;
+        sdk::log!(&events::eth_burn_log(&address, amount.as_u128()));
+        Ok(())
+    }
+
+    fn assert_withdraw_not_paused(
+        &self,
+        current_account_id: &AccountId,
+        predecessor_account_id: &AccountId,
+    ) -> Result<(), error::WithdrawError> {
+        // Check is current account id is owner
+        let is_owner = current_account_id == predecessor_account_id;
+        // Check is current flow paused. If it's owner just skip asserrion. The ETH exited from
+        // the EVM is withdrawn by the engine, it has a flag of its own.
+        self.assert_not_paused(PAUSE_WITHDRAW, is_owner)
+            .and_then(|_| {
+                if is_owner {
+                    self.assert_not_paused(PAUSE_WITHDRAW_ETH, false)
+                } else {
+                    Ok(())
+                }
+            })
+            .map_err(|_| error::WithdrawError::Paused)
     }
 
     /// Withdraw nETH from NEAR accounts
@@ -357,23 +1168,56 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         current_account_id: &AccountId,
         predecessor_account_id: &AccountId,
         args: WithdrawCallArgs,
+        block_height: u64,
     ) -> Result<WithdrawResult, error::WithdrawError> {
-        // Check is current account id is owner
-        let is_owner = current_account_id == predecessor_account_id;
-        // Check is current flow paused. If it's owner just skip asserrion.
-        self.assert_not_paused(PAUSE_WITHDRAW, is_owner)
-            .map_err(|_| error::WithdrawError::Paused)?;
+        self.assert_withdraw_not_paused(current_account_id, predecessor_account_id)?;
 
         // Burn tokens to recipient
         self.ft
-            .internal_withdraw_eth_from_near(predecessor_account_id, args.amount)?;
+            .internal_withdraw_eth_from_near(predecessor_account_id, NEP141Wei::new(args.amount))?;
+        self.record_mint_volume(0, args.amount, block_height);
         // Save new contract data
         self.save_ft_contract();
 
-        Ok(WithdrawResult {
+        let result = WithdrawResult {
             recipient_id: args.recipient_address,
             amount: args.amount,
             eth_custodian_address: self.contract.eth_custodian_address,
+        };
+        pagination::append(
+            &mut self.io,
+            pagination::Collection::Withdrawals,
+            &result.try_to_vec().unwrap(),
+        );
+        sdk::log!(&events::ft_burn_log(&[events::FtBurn {
+            owner_id: predecessor_account_id,
+            amount: args.amount,
+            memo: None,
+        }]));
+        sdk::log!(&events::withdraw_log(predecessor_account_id, &result));
+        Ok(result)
+    }
+
+    /// Dry run of `withdraw_eth_from_near` by the account: the checks of the withdrawal and its
+    /// result, without burning anything. The connector charges no fee on the withdrawals, the
+    /// custodian unlocks the whole amount to the recipient on Ethereum.
+    pub fn estimate_withdraw(
+        &self,
+        current_account_id: &AccountId,
+        args: EstimateWithdrawArgs,
+    ) -> Result<WithdrawEstimate, error::WithdrawError> {
+        self.assert_withdraw_not_paused(current_account_id, &args.account_id)?;
+        self.ft
+            .check_withdraw_eth_from_near(&args.account_id, NEP141Wei::new(args.amount))?;
+        let fee = WITHDRAW_FEE;
+        Ok(WithdrawEstimate {
+            fee,
+            net_amount: args.amount.saturating_sub(fee),
+            result: WithdrawResult {
+                recipient_id: args.recipient_address,
+                amount: args.amount,
+                eth_custodian_address: self.contract.eth_custodian_address,
+            },
         })
     }
 
@@ -423,6 +1267,39 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         Ok(())
     }
 
+    /// Return the balances of nETH of the accounts, a JSON object from the account to its balance
+    pub fn ft_balances_of(&mut self, args: BalancesOfCallArgs) {
+        let balances = args
+            .account_ids
+            .into_iter()
+            .map(|account_id| {
+                let balance = self.ft.ft_balance_of(&account_id);
+                (
+                    account_id.to_string(),
+                    JsonValue::String(balance.to_string()),
+                )
+            })
+            .collect();
+        self.io
+            .return_output(JsonValue::Object(balances).to_string().as_bytes());
+    }
+
+    /// Return the balances of ETH in Aurora EVM of the addresses, a JSON object from the hex
+    /// address to its balance
+    pub fn ft_balances_of_eth_on_aurora(
+        &mut self,
+        args: BalancesOfEthCallArgs,
+    ) -> Result<(), crate::prelude::types::error::BalanceOverflowError> {
+        let mut balances = BTreeMap::new();
+        for address in args.addresses {
+            let balance = self.ft.internal_unwrap_balance_of_eth_on_aurora(address)?;
+            balances.insert(hex::encode(address), JsonValue::String(balance.to_string()));
+        }
+        self.io
+            .return_output(JsonValue::Object(balances).to_string().as_bytes());
+        Ok(())
+    }
+
     /// Transfer between NEAR accounts
     pub fn ft_transfer(
         &mut self,
@@ -432,23 +1309,38 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         self.ft.internal_transfer_eth_on_near(
             predecessor_account_id,
             &args.receiver_id,
-            args.amount,
+            NEP141Wei::new(args.amount),
             &args.memo,
         )?;
         self.save_ft_contract();
         sdk::log!(&format!(
             "Transfer amount {} to {} success with memo: {:?}",
-            args.amount, args.receiver_id, args.memo
+            args.amount,
+            args.receiver_id,
+            args.memo.as_ref().map(|memo| memo.as_str())
         ));
         Ok(())
     }
 
-    /// FT resolve transfer logic
+    /// FT resolve transfer logic.
+    /// The callback of a transfer call already resolved by `force_resolve_transfer` does nothing
+    /// and returns a zero used amount, the transfer was settled when it was forced. The transfer
+    /// calls made before the pending ones were recorded are resolved as usual.
     pub fn ft_resolve_transfer(
         &mut self,
         args: ResolveTransferCallArgs,
         promise_result: PromiseResult,
     ) {
+        if !self.remove_pending_transfer_call(&args)
+            && self.remove_force_resolved_transfer_call(&args)
+        {
+            sdk::log!(&format!(
+                "Transfer call from {} to {} is already resolved",
+                args.sender_id, args.receiver_id
+            ));
+            self.io.return_output(b"\"0\"");
+            return;
+        }
         let amount = self.ft.ft_resolve_transfer(
             promise_result,
             &args.sender_id,
@@ -475,6 +1367,7 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         current_account_id: AccountId,
         args: TransferCallCallArgs,
         prepaid_gas: NearGas,
+        block_height: u64,
     ) -> Result<PromiseWithCallbackArgs, error::FtTransferCallError> {
         sdk::log!(&format!(
             "Transfer call to {} amount {}",
@@ -484,10 +1377,14 @@ impl<I: IO + Copy> EthConnectorContract<I> {
         // Verify message data before `ft_on_transfer` call to avoid verification panics
         // It's allowed empty message if `receiver_id =! current_account_id`
         if args.receiver_id == current_account_id {
-            let message_data = FtTransferMessageData::parse_on_transfer_message(&args.msg)
-                .map_err(error::FtTransferCallError::MessageParseFailed)?;
+            let message_data = FtTransferMessageData::parse_transfer_call_message(
+                &args.msg,
+                &predecessor_account_id,
+            )
+            .map_err(error::FtTransferCallError::MessageParseFailed)?;
             // Check is transfer amount > fee
-            if message_data.fee.into_u128() >= args.amount {
+            let net_amount = NEP141Wei::new(args.amount).checked_sub_fee(message_data.fee);
+            if net_amount.map_or(true, |amount| amount.is_zero()) {
                 return Err(error::FtTransferCallError::InsufficientAmountForFee);
             }
 
@@ -515,17 +1412,139 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             }
         }
 
-        self.ft
-            .ft_transfer_call(
-                predecessor_account_id,
-                args.receiver_id,
-                args.amount,
-                &args.memo,
-                args.msg,
-                current_account_id,
-                prepaid_gas,
-            )
-            .map_err(Into::into)
+        let pending_transfer_call = ResolveTransferCallArgs {
+            sender_id: predecessor_account_id.clone(),
+            amount: args.amount,
+            receiver_id: args.receiver_id.clone(),
+        };
+        let promise = self.ft.ft_transfer_call(
+            predecessor_account_id,
+            args.receiver_id,
+            args.amount,
+            &args.memo,
+            args.msg,
+            current_account_id,
+            prepaid_gas,
+            args.gas_for_on_transfer,
+        )?;
+        self.record_pending_transfer_call(&pending_transfer_call, block_height);
+        Ok(promise)
+    }
+
+    /// Resolve a transfer call whose `ft_resolve_transfer` callback never ran.
+    /// The transfer call must be pending for at least the force resolve delay. The engine ticket
+    /// of the callback is invalidated, so the callback can't be redeemed if it runs later.
+    pub fn force_resolve_transfer(
+        &mut self,
+        args: ForceResolveTransferCallArgs,
+        current_account_id: &AccountId,
+        block_height: u64,
+    ) -> Result<Balance, error::ForceResolveTransferError> {
+        let resolve_args = ResolveTransferCallArgs {
+            sender_id: args.sender_id,
+            amount: args.amount,
+            receiver_id: args.receiver_id,
+        };
+        let created_at = get_pending_transfer_calls(&self.io, &resolve_args)
+            .first()
+            .copied()
+            .ok_or(error::ForceResolveTransferError::TransferCallNotPending)?;
+        let delay = get_force_resolve_delay(&self.io);
+        if created_at.saturating_add(delay) > block_height {
+            return Err(error::ForceResolveTransferError::TooEarly);
+        }
+
+        let promise_result = match args.outcome {
+            TransferCallOutcome::Failed => PromiseResult::Failed,
+            TransferCallOutcome::Succeeded { unused_amount } => {
+                PromiseResult::Successful(format!("\"{}\"", unused_amount).into_bytes())
+            }
+        };
+        self.remove_pending_transfer_call(&resolve_args);
+        // The callback can still run without its ticket while the tickets aren't required.
+        self.record_force_resolved_transfer_call(&resolve_args);
+        callback::revoke_ticket(
+            &mut self.io,
+            current_account_id,
+            "ft_resolve_transfer",
+            &resolve_args.try_to_vec().unwrap(),
+        );
+        let used_amount = self.ft.ft_resolve_transfer(
+            promise_result,
+            &resolve_args.sender_id,
+            &resolve_args.receiver_id,
+            resolve_args.amount,
+        );
+        self.save_ft_contract();
+        sdk::log!(&format!(
+            "Force resolve transfer call of {} from {} to {} created at block {}: used amount {}",
+            resolve_args.amount,
+            resolve_args.sender_id,
+            resolve_args.receiver_id,
+            created_at,
+            used_amount
+        ));
+        Ok(used_amount)
+    }
+
+    /// Save the transfer call as pending until its `ft_resolve_transfer` callback runs
+    fn record_pending_transfer_call(&mut self, args: &ResolveTransferCallArgs, block_height: u64) {
+        let mut pending = get_pending_transfer_calls(&self.io, args);
+        pending.push(block_height);
+        self.io
+            .write_borsh(&pending_transfer_call_key(args), &pending);
+        pagination::insert(
+            &mut self.io,
+            pagination::Collection::PendingTransferCalls,
+            &args.try_to_vec().unwrap(),
+        );
+    }
+
+    /// Remove the oldest pending transfer call with the given arguments.
+    /// Returns false if there is no such pending transfer call.
+    fn remove_pending_transfer_call(&mut self, args: &ResolveTransferCallArgs) -> bool {
+        let mut pending = get_pending_transfer_calls(&self.io, args);
+        if pending.is_empty() {
+            return false;
+        }
+        pending.remove(0);
+        let key = pending_transfer_call_key(args);
+        if pending.is_empty() {
+            self.io.remove_storage(&key);
+            pagination::remove(
+                &mut self.io,
+                pagination::Collection::PendingTransferCalls,
+                &args.try_to_vec().unwrap(),
+            );
+        } else {
+            self.io.write_borsh(&key, &pending);
+        }
+        true
+    }
+
+    /// Save the transfer call as resolved by `force_resolve_transfer`, so its callback does
+    /// nothing if it runs later
+    fn record_force_resolved_transfer_call(&mut self, args: &ResolveTransferCallArgs) {
+        let key = force_resolved_transfer_call_key(args);
+        let count = self.io.read_u64(&key).unwrap_or(0);
+        self.io.write_storage(&key, &(count + 1).to_le_bytes());
+    }
+
+    /// Remove one of the force resolved transfer calls with the given arguments.
+    /// Returns false if there is no such transfer call.
+    fn remove_force_resolved_transfer_call(&mut self, args: &ResolveTransferCallArgs) -> bool {
+        let key = force_resolved_transfer_call_key(args);
+        match self.io.read_u64(&key) {
+            Ok(count) if count > 1 => {
+                self.io.write_storage(&key, &(count - 1).to_le_bytes());
+                true
+            }
+            Ok(_) => {
+                self.io.remove_storage(&key);
+                true
+            }
+            Err(_) => false,
+        }
     }
 
     /// FT storage deposit logic
@@ -557,6 +1576,8 @@ impl<I: IO + Copy> EthConnectorContract<I> {
     ) -> Result<Option<PromiseBatchAction>, fungible_token::error::StorageFundingError> {
         let promise = match self.ft.internal_storage_unregister(account_id, force) {
             Ok((_, p)) => {
+                // The total supply changes if nETH was burned.
+                self.save_ft_contract();
                 self.io.return_output(b"true");
                 Some(p)
             }
@@ -587,33 +1608,144 @@ impl<I: IO + Copy> EthConnectorContract<I> {
             .return_output(&self.ft.storage_balance_of(&args.account_id).to_json_bytes());
     }
 
+    /// Storage deposit of the accounts registered from now on, NEP-145 `storage_balance_bounds`.
+    pub fn storage_balance_bounds(&mut self) {
+        let bounds = self.ft.storage_balance_bounds();
+        let max = bounds.max.unwrap_or(bounds.min);
+        self.io.return_output(
+            format!("{{\"min\": \"{}\", \"max\": \"{}\"}}", bounds.min, max).as_bytes(),
+        );
+    }
+
+    pub fn get_storage_config(&self) -> StorageConfig {
+        self.ft.storage_config()
+    }
+
+    pub fn set_storage_config(
+        &mut self,
+        config: &StorageConfig,
+    ) -> Result<(), fungible_token::error::StorageFundingError> {
+        self.ft.set_storage_config(config)
+    }
+
+    pub fn get_storage_pool(&self) -> Balance {
+        self.ft.get_storage_pool()
+    }
+
+    /// Adds the attached NEAR to the pool paying for the storage deposits of the accounts
+    /// registered by the deposits and the transfers. Returns the new balance of the pool.
+    pub fn fund_storage_pool(&mut self, amount: Balance) -> Balance {
+        self.ft.fund_storage_pool(amount)
+    }
+
     /// ft_on_transfer callback function
-    pub fn ft_on_transfer<'env, E: Env>(
+    pub fn ft_on_transfer<'env, E: Env, P: PromiseHandler>(
         &mut self,
-        engine: &Engine<'env, I, E>,
+        engine: &mut Engine<'env, I, E>,
         args: &NEP141FtOnTransferArgs,
+        handler: &mut P,
     ) -> Result<(), error::FtTransferCallError> {
         sdk::log!("Call ft_on_transfer");
-        // Parse message with specific rules
-        let message_data = FtTransferMessageData::parse_on_transfer_message(&args.msg)
-            .map_err(error::FtTransferCallError::MessageParseFailed)?;
+        // Parse message with specific rules. The tokens which can't be credited are returned
+        // as unused, so that `ft_resolve_transfer` refunds them to the sender.
+        let message_data =
+            FtTransferMessageData::parse_transfer_call_message(&args.msg, &args.sender_id)
+                .map_err(error::FtTransferCallError::MessageParseFailed)
+                .and_then(|message_data| {
+                    match NEP141Wei::new(args.amount).checked_sub_fee(message_data.fee) {
+                        Some(net_amount) if !net_amount.is_zero() => Ok((message_data, net_amount)),
+                        _ => Err(error::FtTransferCallError::InsufficientAmountForFee),
+                    }
+                });
+        let (message_data, net_amount) = match message_data {
+            Ok(message_data) => message_data,
+            Err(e) => {
+                sdk::log!(&format!(
+                    "Refund {} to {}: {}",
+                    args.amount,
+                    args.sender_id,
+                    String::from_utf8_lossy(e.as_ref())
+                ));
+                self.io
+                    .return_output(format!("\"{}\"", args.amount).as_bytes());
+                return Ok(());
+            }
+        };
 
         // Special case when predecessor_account_id is current_account_id
-        let fee = message_data.fee.into_u128();
+        let fee = NEP141Wei::from(message_data.fee);
         // Mint fee to relayer
         let relayer = engine.get_relayer(message_data.relayer.as_bytes());
-        match (fee, relayer) {
-            (fee, Some(H160(evm_relayer_address))) if fee > 0 => {
-                self.mint_eth_on_aurora(message_data.recipient, args.amount - fee)?;
-                self.mint_eth_on_aurora(evm_relayer_address, fee)?;
+        let deposit_log = match relayer {
+            Some(H160(evm_relayer_address)) if !fee.is_zero() => {
+                self.mint_eth_on_aurora(message_data.recipient, net_amount)?;
+                self.pay_deposit_fee(
+                    engine,
+                    &message_data.relayer,
+                    Address(evm_relayer_address),
+                    fee,
+                    handler,
+                )?;
+                record_relayer_fee(&mut self.io, &message_data.relayer, fee, true);
+                engine_logs::deposit_credited(
+                    &Address(message_data.recipient),
+                    net_amount.as_u128(),
+                    fee.as_u128(),
+                )
             }
-            _ => self.mint_eth_on_aurora(message_data.recipient, args.amount)?,
-        }
+            _ => {
+                self.mint_eth_on_aurora(message_data.recipient, NEP141Wei::new(args.amount))?;
+                engine_logs::deposit_credited(&Address(message_data.recipient), args.amount, 0)
+            }
+        };
         self.save_ft_contract();
+        engine_logs::emit(&mut self.io, engine.block_height(), deposit_log);
         self.io.return_output("\"0\"".as_bytes());
         Ok(())
     }
 
+    /// Pays the relayer fee of a deposit to the EVM in the currency set by the owner. A fee
+    /// converted into the base token is minted to the engine, which swaps it for the relayer;
+    /// it is paid in ETH when the swap fails.
+    fn pay_deposit_fee<'env, E: Env, P: PromiseHandler>(
+        &mut self,
+        engine: &mut Engine<'env, I, E>,
+        relayer_id: &AccountId,
+        relayer_address: Address,
+        fee: NEP141Wei,
+        handler: &mut P,
+    ) -> Result<(), error::FtTransferCallError> {
+        let route = match get_deposit_fee_currency(&self.io) {
+            DepositFeeCurrency::Eth => {
+                return self
+                    .mint_eth_on_aurora(relayer_address.0, fee)
+                    .map_err(Into::into)
+            }
+            DepositFeeCurrency::BaseToken(route) => route,
+        };
+        let admin_address = engine.admin_address();
+        self.mint_eth_on_aurora(admin_address.0, fee)?;
+        let amount = Wei::new(U256::from(fee.as_u128()));
+        let amount_out = engine.swap_eth_for_base_token(&route, relayer_address, amount, handler);
+        if amount_out.is_none() {
+            // The swap failed, the ETH minted is still held by the engine.
+            let admin_balance = engine::get_balance(&self.io, &admin_address)
+                .checked_sub(amount)
+                .ok_or(error::FtTransferCallError::BalanceOverflow(
+                    BalanceOverflowError,
+                ))?;
+            engine::set_balance(&mut self.io, &admin_address, &admin_balance);
+            engine::add_balance(&mut self.io, &relayer_address, amount)
+                .map_err(|_| error::FtTransferCallError::BalanceOverflow(BalanceOverflowError))?;
+        }
+        sdk::log!(&events::deposit_fee_conversion_log(
+            relayer_id,
+            fee.as_u128(),
+            amount_out
+        ));
+        Ok(())
+    }
+
     /// Get accounts counter for statistics.
     /// It represents total unique accounts (all-time, including accounts which now have zero balance).
     pub fn get_accounts_counter(&mut self) {
@@ -648,7 +1780,19 @@ impl<I: IO + Copy> EthConnectorContract<I> {
 
     /// Checks whether the provided proof was already used
     pub fn is_used_proof(&self, proof: Proof) -> bool {
-        self.is_used_event(&proof.get_key())
+        self.is_used_event(&proof.get_key()) || self.is_used_event(&proof.get_legacy_key())
+    }
+
+    /// Checks whether the proof with the provided key was already used. Only the proofs recorded
+    /// with the current key derivation are found, see `Proof::get_legacy_key`.
+    pub fn is_used_proof_key(&self, key: &str) -> bool {
+        self.is_used_event(key)
+    }
+
+    /// Whether each of the proofs with the provided keys was already used, in the order of the
+    /// keys, as `is_used_proof_key` would return it.
+    pub fn are_used_proof_keys(&self, keys: &[String]) -> Vec<bool> {
+        keys.iter().map(|key| self.is_used_event(key)).collect()
     }
 
     /// Get Eth connector paused flags
@@ -678,15 +1822,585 @@ impl<I: IO + Copy> AdminControlled for EthConnectorContract<I> {
     }
 }
 
-fn construct_contract_key(suffix: &EthConnectorStorageId) -> Vec<u8> {
+/// Returns the number of blocks after which a pending transfer call can be force resolved.
+pub fn get_force_resolve_delay<I: IO>(io: &I) -> u64 {
+    storage_key::read_config_u64(io, ConfigKey::ForceResolveDelay, &[])
+        .unwrap_or(DEFAULT_FORCE_RESOLVE_DELAY_BLOCKS)
+}
+
+pub fn set_force_resolve_delay<I: IO>(io: &mut I, delay_blocks: u64) {
+    storage_key::write_config(
+        io,
+        ConfigKey::ForceResolveDelay,
+        &[],
+        &delay_blocks.to_le_bytes(),
+    );
+}
+
+/// Returns the fee taken by the protocol on the deposits, in basis points of the deposited
+/// amount net of the relayer fee.
+pub fn get_deposit_protocol_fee<I: IO>(io: &I) -> u16 {
+    storage_key::read_config(io, ConfigKey::DepositProtocolFee, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(0)
+}
+
+pub fn set_deposit_protocol_fee<I: IO>(
+    io: &mut I,
+    fee_bps: u16,
+) -> Result<(), error::InvalidProtocolFee> {
+    if fee_bps > PROTOCOL_FEE_DENOMINATOR {
+        return Err(error::InvalidProtocolFee);
+    }
+    storage_key::write_config_borsh(io, ConfigKey::DepositProtocolFee, &[], &fee_bps);
+    Ok(())
+}
+
+/// Returns the protocol fees held by the engine account and not withdrawn by the owner yet.
+pub fn get_protocol_fees<I: IO>(io: &I) -> Balance {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::ProtocolFees,
+    ))
+    .and_then(|data| data.to_value().ok())
+    .unwrap_or_default()
+}
+
+fn accrue_protocol_fees<I: IO>(io: &mut I, protocol_fee: NEP141Wei) {
+    let protocol_fees = get_protocol_fees(io).saturating_add(protocol_fee.as_u128());
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::ProtocolFees),
+        &protocol_fees,
+    );
+}
+
+/// Returns the currency the relayer fees of the deposits to the EVM are paid in, bridged ETH
+/// unless set by the owner.
+pub fn get_deposit_fee_currency<I: IO>(io: &I) -> DepositFeeCurrency {
+    storage_key::read_config(io, ConfigKey::DepositFeeCurrency, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+pub fn set_deposit_fee_currency<I: IO>(
+    io: &mut I,
+    currency: &DepositFeeCurrency,
+) -> Result<(), error::InvalidFeeConversionRoute> {
+    if let DepositFeeCurrency::BaseToken(route) = currency {
+        // The path goes at least from the wrapped ETH to the base token, and the swap must be
+        // bounded.
+        if route.router == [0; 20]
+            || route.path.len() < 2
+            || route.min_amount_out_per_eth == [0; 32]
+            || route.gas_limit == 0
+        {
+            return Err(error::InvalidFeeConversionRoute);
+        }
+    }
+    storage_key::write_config_borsh(io, ConfigKey::DepositFeeCurrency, &[], currency);
+    Ok(())
+}
+
+/// Returns whether the relayer is in the list of the relayers whose deposits are not charged
+/// the protocol fee.
+pub fn is_fee_waived<I: IO>(io: &I, relayer_id: &AccountId) -> bool {
+    storage_key::has_config(io, ConfigKey::FeeWaivedRelayer, relayer_id.as_bytes())
+}
+
+/// Adds the relayer to the fee waiver list or removes it. An event is emitted when the
+/// membership changes.
+pub fn set_fee_waiver<I: IO>(io: &mut I, relayer_id: &AccountId, waived: bool) {
+    if is_fee_waived(io, relayer_id) == waived {
+        return;
+    }
+    if waived {
+        storage_key::write_config(io, ConfigKey::FeeWaivedRelayer, relayer_id.as_bytes(), &[1]);
+    } else {
+        storage_key::remove_config(io, ConfigKey::FeeWaivedRelayer, relayer_id.as_bytes());
+    }
+    sdk::log!(&events::fee_waiver_log(relayer_id, waived));
+}
+
+/// Returns the fees credited to the relayer by the deposits, `None` if it wasn't credited any.
+pub fn get_relayer_earnings<I: IO>(io: &I, relayer_id: &AccountId) -> Option<RelayerEarnings> {
+    storage_key::read_config(io, ConfigKey::RelayerEarnings, relayer_id.as_bytes())
+        .and_then(|value| value.to_value().ok())
+}
+
+/// Adds the fee a deposit credited to the relayer to its earnings, as nETH on NEAR or as ETH
+/// on Aurora. The withdrawals don't pay the relayers, so only the deposits are recorded. The
+/// totals of the relayer are published every `RELAYER_EARNINGS_SUMMARY_INTERVAL` deposits.
+pub fn record_relayer_fee<I: IO>(
+    io: &mut I,
+    relayer_id: &AccountId,
+    fee: NEP141Wei,
+    on_aurora: bool,
+) {
+    if fee.is_zero() {
+        return;
+    }
+    let mut earnings = get_relayer_earnings(io, relayer_id).unwrap_or_default();
+    if on_aurora {
+        earnings.aurora_fees = earnings.aurora_fees.saturating_add(fee.as_u128());
+    } else {
+        earnings.near_fees = earnings.near_fees.saturating_add(fee.as_u128());
+    }
+    earnings.deposits = earnings.deposits.saturating_add(1);
+    storage_key::write_config_borsh(
+        io,
+        ConfigKey::RelayerEarnings,
+        relayer_id.as_bytes(),
+        &earnings,
+    );
+    pagination::insert(
+        io,
+        pagination::Collection::RelayerEarnings,
+        relayer_id.as_bytes(),
+    );
+    if earnings.deposits % RELAYER_EARNINGS_SUMMARY_INTERVAL == 0 {
+        sdk::log!(&events::relayer_earnings_log(relayer_id, &earnings));
+    }
+}
+
+/// Page of the relayers credited a fee by a deposit, with their earnings, in the order of
+/// their first fee.
+pub fn get_relayer_earnings_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<RelayerEarningsEntry>, pagination::error::PaginationError> {
+    pagination::page(io, pagination::Collection::RelayerEarnings, args, |item| {
+        let relayer_id = AccountId::try_from(item).ok()?;
+        let earnings = get_relayer_earnings(io, &relayer_id)?;
+        Some(RelayerEarningsEntry {
+            relayer_id,
+            earnings,
+        })
+    })
+}
+
+/// Returns the address of the ERC-20 locker on Ethereum, if the ERC-20 deposits are enabled.
+pub fn get_erc20_locker<I: IO>(io: &I) -> Option<EthAddress> {
+    storage_key::read_config(io, ConfigKey::Erc20Locker, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+pub fn set_erc20_locker<I: IO>(io: &mut I, locker_address: EthAddress) {
+    storage_key::write_config_borsh(io, ConfigKey::Erc20Locker, &[], &locker_address);
+}
+
+/// Returns the NEP-141 token minted for the deposits of the ERC-20 token.
+pub fn get_bridged_nep141<I: IO>(io: &I, token: &EthAddress) -> Option<AccountId> {
+    storage_key::read_config(io, ConfigKey::BridgedErc20Token, token)
+        .and_then(|value| AccountId::try_from(value.to_vec()).ok())
+}
+
+/// Registers the NEP-141 token minted for the deposits of the ERC-20 token, or unregisters the
+/// ERC-20 if it is `None`. The engine must be allowed to mint the NEP-141 token.
+pub fn register_erc20_token<I: IO>(io: &mut I, token: &EthAddress, nep141: Option<&AccountId>) {
+    match nep141 {
+        Some(nep141) => {
+            storage_key::write_config(io, ConfigKey::BridgedErc20Token, token, nep141.as_bytes())
+        }
+        None => storage_key::remove_config(io, ConfigKey::BridgedErc20Token, token),
+    }
+}
+
+/// Returns whether the eth-connector was initialized with `new_eth_connector`.
+pub fn is_initialized<I: IO>(io: &I) -> bool {
+    io.storage_has_key(&construct_contract_key(&EthConnectorStorageId::Contract))
+}
+
+pub fn get_mint_circuit_breaker<I: IO>(io: &I) -> Option<MintCircuitBreakerArgs> {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::MintCircuitBreaker,
+    ))
+    .and_then(|data| data.to_value().ok())
+}
+
+pub fn set_mint_circuit_breaker<I: IO>(io: &mut I, args: &MintCircuitBreakerArgs) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::MintCircuitBreaker),
+        args,
+    );
+}
+
+/// Returns the thresholds the amount of a deposit is checked against, zero unless
+/// `set_min_deposit` was called.
+pub fn get_min_deposit<I: IO>(io: &I) -> MinDepositArgs {
+    io.read_storage(&construct_contract_key(&EthConnectorStorageId::MinDeposit))
+        .and_then(|data| data.to_value().ok())
+        .unwrap_or_default()
+}
+
+pub fn set_min_deposit<I: IO>(io: &mut I, args: &MinDepositArgs) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::MinDeposit),
+        args,
+    );
+}
+
+pub fn get_recipient_rules<I: IO>(io: &I) -> RecipientRules {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::RecipientRules,
+    ))
+    .and_then(|data| data.to_value().ok())
+    .unwrap_or_default()
+}
+
+pub fn set_recipient_rules<I: IO>(io: &mut I, rules: &RecipientRules) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::RecipientRules),
+        rules,
+    );
+}
+
+/// Checks the NEAR recipient of a deposit against the rules set by the owner, so that a
+/// mistyped recipient is rejected before the proof is verified rather than minted to an
+/// account nobody controls.
+pub fn check_recipient(
+    rules: &RecipientRules,
+    recipient: &AccountId,
+) -> Result<(), error::DepositError> {
+    let matches = |suffix: &String| {
+        let recipient = recipient.as_ref();
+        recipient == suffix
+            || (recipient.ends_with(suffix.as_str())
+                && recipient[..recipient.len() - suffix.len()].ends_with('.'))
+    };
+    if rules.denied_suffixes.iter().any(matches) {
+        return Err(error::DepositError::RecipientDenied);
+    }
+    if !rules.allowed_suffixes.is_empty() && !rules.allowed_suffixes.iter().any(matches) {
+        return Err(error::DepositError::RecipientNotAllowed);
+    }
+    Ok(())
+}
+
+/// Returns the provers the deposit proofs are verified with, in order. Unless `set_provers`
+/// was called, it is only the prover the eth-connector was initialized with.
+pub fn get_provers<I: IO>(io: &I) -> Vec<ProverConfig> {
+    io.read_storage(&construct_contract_key(&EthConnectorStorageId::Provers))
+        .and_then(|data| data.to_value().ok())
+        .or_else(|| {
+            get_eth_connector(io).map(|contract| {
+                vec![ProverConfig {
+                    account_id: contract.prover_account,
+                    enabled: true,
+                }]
+            })
+        })
+        .unwrap_or_default()
+}
+
+fn get_enabled_provers<I: IO>(io: &I) -> Vec<AccountId> {
+    get_provers(io)
+        .into_iter()
+        .filter(|prover| prover.enabled)
+        .map(|prover| prover.account_id)
+        .collect()
+}
+
+/// NEAR Gas attached to `resolve_deposit_verification`, which finishes the deposit. Every
+/// fallback verifies the proof once more and calls back again.
+fn gas_for_resolve_deposit_verification(fallbacks: u64) -> NearGas {
+    let fallback_gas = GAS_FOR_VERIFY_LOG_ENTRY.into_u64() + GAS_FOR_FINISH_DEPOSIT.into_u64();
+    NearGas::new(GAS_FOR_FINISH_DEPOSIT.into_u64() + fallbacks * fallback_gas)
+}
+
+/// Estimates the NEAR gas a `deposit` of the proof needs, with the provers currently enabled,
+/// for the relayers to attach. The proof is only decoded, not validated as by `deposit`.
+pub fn estimate_deposit_gas<I: IO>(
+    io: &I,
+    raw_proof: &[u8],
+) -> Result<DepositGasEstimate, error::DepositError> {
+    ProofView::decode(raw_proof).map_err(|_| error::DepositError::ProofParseFailed)?;
+    let provers = get_enabled_provers(io).len() as u64;
+    if provers == 0 {
+        return Err(error::DepositError::NoEnabledProver);
+    }
+    let deposit = GAS_FOR_DEPOSIT
+        .into_u64()
+        .saturating_add((raw_proof.len() as u64).saturating_mul(GAS_FOR_DEPOSIT_PER_PROOF_BYTE));
+    let verify_log_entry = GAS_FOR_VERIFY_LOG_ENTRY.into_u64();
+    let finish_deposit = gas_for_resolve_deposit_verification(provers - 1).into_u64();
+    let safety_margin = DEPOSIT_GAS_SAFETY_MARGIN.into_u64();
+    Ok(DepositGasEstimate {
+        deposit,
+        verify_log_entry,
+        finish_deposit,
+        safety_margin,
+        total: deposit
+            .saturating_add(verify_log_entry)
+            .saturating_add(finish_deposit)
+            .saturating_add(safety_margin),
+    })
+}
+
+/// Sets the provers the deposit proofs are verified with. A proof is accepted as soon as
+/// one of the enabled provers validates it, they are tried in the given order.
+pub fn set_provers<I: IO>(
+    io: &mut I,
+    provers: Vec<ProverConfig>,
+) -> Result<(), error::ProversError> {
+    if !provers.iter().any(|prover| prover.enabled) {
+        return Err(error::ProversError::NoEnabledProver);
+    }
+    for (index, prover) in provers.iter().enumerate() {
+        if provers[..index]
+            .iter()
+            .any(|other| other.account_id == prover.account_id)
+        {
+            return Err(error::ProversError::DuplicateProver);
+        }
+    }
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::Provers),
+        &provers,
+    );
+    Ok(())
+}
+
+pub fn get_proof_cache_config<I: IO>(io: &I) -> ProofCacheConfig {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::ProofCacheConfig,
+    ))
+    .and_then(|data| data.to_value().ok())
+    .unwrap_or_default()
+}
+
+pub fn set_proof_cache_config<I: IO>(io: &mut I, config: &ProofCacheConfig) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::ProofCacheConfig),
+        config,
+    );
+}
+
+/// Key of the block height the receipt was last verified by a prover at.
+fn verified_receipt_key(receipt_hash: &H256) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::VerifiedReceipt);
+    key.extend_from_slice(receipt_hash.as_bytes());
+    key
+}
+
+/// Account providing the hashes of the Ethereum blocks the deposit proofs are verified
+/// against by the engine, without a call to a prover. `None` if the deposits are only
+/// verified by the provers.
+pub fn get_light_client<I: IO>(io: &I) -> Option<AccountId> {
+    io.read_storage(&construct_contract_key(&EthConnectorStorageId::LightClient))
+        .and_then(|data| data.to_value().ok())
+}
+
+pub fn set_light_client<I: IO>(io: &mut I, account_id: Option<AccountId>) {
+    let key = construct_contract_key(&EthConnectorStorageId::LightClient);
+    match account_id {
+        Some(account_id) => io.write_borsh(&key, &account_id),
+        None => io.remove_storage(&key),
+    };
+}
+
+fn light_client_block_hash_key(block_hash: &H256) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::LightClientBlockHash);
+    key.extend_from_slice(block_hash.as_bytes());
+    key
+}
+
+pub fn is_light_client_block_hash<I: IO>(io: &I, block_hash: &H256) -> bool {
+    io.storage_has_key(&light_client_block_hash_key(block_hash))
+}
+
+/// Records the hash of a final Ethereum block, at the block height it is provided at.
+pub fn add_light_client_block_hash<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    block_hash: &H256,
+    block_height: u64,
+) -> Result<(), error::LightClientError> {
+    if get_light_client(io).as_ref() != Some(predecessor_account_id) {
+        return Err(error::LightClientError::NotLightClient);
+    }
+    io.write_storage(
+        &light_client_block_hash_key(block_hash),
+        &block_height.to_le_bytes(),
+    );
+    Ok(())
+}
+
+fn prover_statistics_key(account_id: &AccountId) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::ProverStatistics);
+    key.extend_from_slice(account_id.as_bytes());
+    key
+}
+
+pub fn get_prover_statistics<I: IO>(io: &I, account_id: &AccountId) -> ProverStatistics {
+    io.read_storage(&prover_statistics_key(account_id))
+        .and_then(|data| data.to_value().ok())
+        .unwrap_or_default()
+}
+
+/// Generate key for pending transfer calls with the given arguments
+fn pending_transfer_call_key(args: &ResolveTransferCallArgs) -> Vec<u8> {
+    let mut v = construct_contract_key(&EthConnectorStorageId::PendingTransferCall).to_vec();
+    v.extend_from_slice(sdk::sha256(&args.try_to_vec().unwrap()).as_bytes());
+    v
+}
+
+/// Generate key for force resolved transfer calls with the given arguments
+fn force_resolved_transfer_call_key(args: &ResolveTransferCallArgs) -> Vec<u8> {
+    let mut v = construct_contract_key(&EthConnectorStorageId::ForceResolvedTransferCall).to_vec();
+    v.extend_from_slice(sdk::sha256(&args.try_to_vec().unwrap()).as_bytes());
+    v
+}
+
+/// Block heights of pending transfer calls with the given arguments, oldest first
+fn get_pending_transfer_calls<I: IO>(io: &I, args: &ResolveTransferCallArgs) -> Vec<u64> {
+    io.read_storage(&pending_transfer_call_key(args))
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+/// Page of the accounts registered with the eth-connector, with their balances of nETH.
+pub fn get_accounts_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<AccountBalance>, pagination::error::PaginationError> {
+    pagination::page(io, pagination::Collection::Accounts, args, |item| {
+        let account_id = AccountId::try_from(item).ok()?;
+        let balance = io
+            .read_storage(&fungible_token::account_balance_key(&account_id))?
+            .to_value()
+            .ok()?;
+        Some(AccountBalance {
+            account_id,
+            balance,
+        })
+    })
+}
+
+/// Page of the receipts of the finished deposits, oldest first.
+pub fn get_deposit_receipts_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<DepositReceiptEntry>, pagination::error::PaginationError> {
+    pagination::page(io, pagination::Collection::DepositReceipts, args, |item| {
+        let proof_key = String::from_utf8(item).ok()?;
+        let receipt = io
+            .read_storage(&deposit_receipt_key(&proof_key))?
+            .to_value()
+            .ok()?;
+        Some(DepositReceiptEntry { proof_key, receipt })
+    })
+}
+
+/// Returns the receipts of the last finished deposits sent from the Ethereum address, newest
+/// first.
+pub fn get_deposits_by_sender<I: IO>(
+    io: &I,
+    sender: &EthAddress,
+    limit: u32,
+) -> Vec<DepositReceiptEntry> {
+    let count = io
+        .read_u64(&deposits_by_sender_key(sender, &[]))
+        .unwrap_or(0);
+    let limit = u64::from(limit).min(MAX_DEPOSITS_PER_SENDER).min(count);
+    (1..=limit)
+        .filter_map(|i| {
+            let slot = (count - i) % MAX_DEPOSITS_PER_SENDER;
+            let proof_key = io
+                .read_storage(&deposits_by_sender_key(sender, &slot.to_be_bytes()))
+                .and_then(|value| String::from_utf8(value.to_vec()).ok())?;
+            let receipt = io
+                .read_storage(&deposit_receipt_key(&proof_key))?
+                .to_value()
+                .ok()?;
+            Some(DepositReceiptEntry { proof_key, receipt })
+        })
+        .collect()
+}
+
+/// Page of the withdrawals, oldest first.
+pub fn get_withdrawals_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<WithdrawResult>, pagination::error::PaginationError> {
+    pagination::page(io, pagination::Collection::Withdrawals, args, |item| {
+        WithdrawResult::try_from_slice(&item).ok()
+    })
+}
+
+/// Page of the pending transfer calls, in the order the first of the transfer calls with the
+/// same arguments started.
+pub fn get_pending_transfer_calls_page<I: IO>(
+    io: &I,
+    args: &PageArgs,
+) -> Result<Page<PendingTransferCall>, pagination::error::PaginationError> {
+    pagination::page(
+        io,
+        pagination::Collection::PendingTransferCalls,
+        args,
+        |item| {
+            let args = ResolveTransferCallArgs::try_from_slice(&item).ok()?;
+            let block_heights = get_pending_transfer_calls(io, &args);
+            Some(PendingTransferCall {
+                args,
+                block_heights,
+            })
+        },
+    )
+}
+
+/// Returns the provers together with their statistics.
+pub fn get_prover_statuses<I: IO>(io: &I) -> Vec<ProverStatus> {
+    get_provers(io)
+        .into_iter()
+        .map(|config| ProverStatus {
+            statistics: get_prover_statistics(io, &config.account_id),
+            config,
+        })
+        .collect()
+}
+
+/// Key of the number of deposits of the sender with an empty suffix, of a slot of its ring
+/// otherwise.
+fn deposits_by_sender_key(sender: &EthAddress, suffix: &[u8]) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::DepositsBySender);
+    key.extend_from_slice(sender);
+    key.extend_from_slice(suffix);
+    key
+}
+
+fn unclaimed_deposit_key(proof_key: &str) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::UnclaimedDeposit);
+    key.extend_from_slice(proof_key.as_bytes());
+    key
+}
+
+fn deposit_receipt_key(proof_key: &str) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::DepositReceipt);
+    key.extend_from_slice(proof_key.as_bytes());
+    key
+}
+
+/// Truncates the memo to at most `max_len` bytes, on a character boundary.
+fn truncate_memo(memo: &str, max_len: usize) -> &str {
+    if memo.len() <= max_len {
+        return memo;
+    }
+    let mut end = max_len;
+    while !memo.is_char_boundary(end) {
+        end -= 1;
+    }
+    &memo[..end]
+}
+
+pub(crate) fn construct_contract_key(suffix: &EthConnectorStorageId) -> Vec<u8> {
     crate::prelude::bytes_to_key(KeyPrefix::EthConnector, &[*suffix as u8])
 }
 
+/// Panics with the reason why the data can't be loaded if it is missing or corrupted.
 fn get_contract_data<T: BorshDeserialize, I: IO>(io: &I, suffix: &EthConnectorStorageId) -> T {
-    io.read_storage(&construct_contract_key(suffix))
-        .expect("Failed read storage")
-        .to_value()
-        .unwrap()
+    match io.load_state(&construct_contract_key(suffix)) {
+        Ok(data) => data,
+        Err(e) => sdk::panic_utf8(e.as_ref()),
+    }
 }
 
 /// Sets the contract data and returns it back
@@ -713,12 +2427,206 @@ pub fn set_contract_data<I: IO>(
     Ok(contract_data)
 }
 
-/// Return metdata
+/// Changes the prover and the custodian address of an initialized eth-connector, e.g. after a
+/// migration of the custodian contract on Ethereum, and its metadata.
+pub fn update_contract_data<I: IO>(
+    io: &mut I,
+    args: SetContractDataCallArgs,
+) -> Result<(), error::SetContractDataError> {
+    let previous = get_eth_connector(io).ok_or(error::SetContractDataError::NotInitialized)?;
+    let eth_custodian_address = validate_eth_address(args.eth_custodian_address.clone())
+        .map_err(error::SetContractDataError::InvalidCustodianAddress)?;
+    // The deposits of a zero custodian address could never be proven, nor the withdrawals
+    // unlocked.
+    if eth_custodian_address == [0u8; 20] {
+        return Err(error::SetContractDataError::ZeroCustodianAddress);
+    }
+    let contract_data = set_contract_data(io, args)
+        .map_err(error::SetContractDataError::InvalidCustodianAddress)?;
+    sdk::log!(&events::contract_data_log(&previous, &contract_data));
+    Ok(())
+}
+
+/// Custodian contracts the deposits are accepted from in addition to `eth_custodian_address`,
+/// e.g. the previous one during a migration of the custodian. The withdrawals are always
+/// unlocked by `eth_custodian_address`.
+pub fn get_extra_custodians<I: IO>(io: &I) -> Vec<EthAddress> {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::ExtraCustodians,
+    ))
+    .and_then(|data| data.to_value().ok())
+    .unwrap_or_default()
+}
+
+fn set_extra_custodians<I: IO>(io: &mut I, custodians: &[EthAddress]) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::ExtraCustodians),
+        &custodians.to_vec(),
+    );
+}
+
+/// All the custodian addresses the deposits are accepted from, `eth_custodian_address` first.
+pub fn get_custodians<I: IO>(io: &I) -> Result<Vec<EthAddress>, error::CustodianError> {
+    let contract = get_eth_connector(io).ok_or(error::CustodianError::NotInitialized)?;
+    let mut custodians = vec![contract.eth_custodian_address];
+    custodians.extend(
+        get_extra_custodians(io)
+            .into_iter()
+            .filter(|address| *address != contract.eth_custodian_address),
+    );
+    Ok(custodians)
+}
+
+pub fn is_custodian<I: IO>(io: &I, contract: &EthConnector, address: &EthAddress) -> bool {
+    *address == contract.eth_custodian_address || get_extra_custodians(io).contains(address)
+}
+
+pub fn add_custodian<I: IO>(io: &mut I, address: String) -> Result<(), error::CustodianError> {
+    let contract = get_eth_connector(io).ok_or(error::CustodianError::NotInitialized)?;
+    let address =
+        validate_eth_address(address).map_err(error::CustodianError::InvalidCustodianAddress)?;
+    if address == [0u8; 20] {
+        return Err(error::CustodianError::ZeroCustodianAddress);
+    }
+    if is_custodian(io, &contract, &address) {
+        return Err(error::CustodianError::AlreadyCustodian);
+    }
+    let mut custodians = get_extra_custodians(io);
+    custodians.push(address);
+    set_extra_custodians(io, &custodians);
+    Ok(())
+}
+
+/// Removes an extra custodian, `eth_custodian_address` is only changed with the contract data.
+pub fn remove_custodian<I: IO>(io: &mut I, address: String) -> Result<(), error::CustodianError> {
+    let contract = get_eth_connector(io).ok_or(error::CustodianError::NotInitialized)?;
+    let address =
+        validate_eth_address(address).map_err(error::CustodianError::InvalidCustodianAddress)?;
+    if address == contract.eth_custodian_address {
+        return Err(error::CustodianError::PrimaryCustodian);
+    }
+    let mut custodians = get_extra_custodians(io);
+    let len = custodians.len();
+    custodians.retain(|custodian| *custodian != address);
+    if custodians.len() == len {
+        return Err(error::CustodianError::CustodianNotFound);
+    }
+    set_extra_custodians(io, &custodians);
+    Ok(())
+}
+
+/// Returns the eth-connector contract data, if the eth-connector is initialized
+pub fn get_eth_connector<I: IO>(io: &I) -> Option<EthConnector> {
+    io.read_storage(&construct_contract_key(&EthConnectorStorageId::Contract))
+        .and_then(|data| data.to_value().ok())
+}
+
+/// Returns the eth-connector paused flags, if the eth-connector is initialized
+pub fn get_paused_mask<I: IO>(io: &I) -> Option<PausedMask> {
+    io.read_storage(&construct_contract_key(&EthConnectorStorageId::PausedMask))
+        .and_then(|data| data.to_value().ok())
+}
+
+/// Returns the fungible token metadata. If an icon was stored in chunks with `set_icon_chunk`,
+/// it replaces the icon of the stored metadata.
 pub fn get_metadata<I: IO>(io: &I) -> Option<FungibleTokenMetadata> {
     io.read_storage(&construct_contract_key(
         &EthConnectorStorageId::FungibleTokenMetadata,
     ))
     .and_then(|data| data.to_value().ok())
+    .map(|mut metadata: FungibleTokenMetadata| {
+        if let Some(icon) = get_chunked_icon(io) {
+            metadata.icon = Some(icon);
+        }
+        metadata
+    })
+}
+
+/// Checks that the metadata has an NEP-148 spec, a name and a symbol.
+pub fn validate_metadata(metadata: &FungibleTokenMetadata) -> Result<(), error::MetadataError> {
+    if !metadata.spec.starts_with("ft-") {
+        return Err(error::MetadataError::InvalidSpec);
+    }
+    if metadata.name.is_empty() {
+        return Err(error::MetadataError::EmptyName);
+    }
+    if metadata.symbol.is_empty() {
+        return Err(error::MetadataError::EmptySymbol);
+    }
+    Ok(())
+}
+
+/// Replaces the fungible token metadata set at the initialization of the eth-connector. The
+/// icon stored in chunks is removed, so the icon of the new metadata is the one returned.
+pub fn set_metadata<I: IO>(
+    io: &mut I,
+    metadata: FungibleTokenMetadata,
+) -> Result<(), error::MetadataError> {
+    validate_metadata(&metadata)?;
+    for index in 0..get_icon_chunks_count(io) {
+        io.remove_storage(&icon_chunk_key(index));
+    }
+    io.remove_storage(&construct_contract_key(
+        &EthConnectorStorageId::FungibleTokenIconChunksCount,
+    ));
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::FungibleTokenMetadata),
+        &metadata,
+    );
+    Ok(())
+}
+
+fn icon_chunk_key(index: u32) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::FungibleTokenIconChunk);
+    key.extend_from_slice(&index.to_le_bytes());
+    key
+}
+
+fn get_icon_chunks_count<I: IO>(io: &I) -> u32 {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::FungibleTokenIconChunksCount,
+    ))
+    .and_then(|data| data.to_value().ok())
+    .unwrap_or(0)
+}
+
+/// Reassembles the icon stored in chunks, if any.
+fn get_chunked_icon<I: IO>(io: &I) -> Option<String> {
+    let count = get_icon_chunks_count(io);
+    if count == 0 {
+        return None;
+    }
+    let mut icon = Vec::new();
+    for index in 0..count {
+        if let Some(chunk) = io.read_storage(&icon_chunk_key(index)) {
+            icon.extend_from_slice(&chunk.to_vec());
+        }
+    }
+    // Chunks are checked to be ASCII when they are set.
+    String::from_utf8(icon).ok()
+}
+
+/// Stores a chunk of the fungible token icon. Writing the chunk at `index` makes it the last
+/// chunk of the icon, so an icon is set by writing its chunks in order starting from zero.
+/// A chunk can only be written at an existing index or right after the last chunk.
+pub fn set_icon_chunk<I: IO>(io: &mut I, args: SetIconChunkArgs) -> Result<(), error::IconError> {
+    if !args.chunk.is_ascii() {
+        return Err(error::IconError::InvalidChunk);
+    }
+    let count = get_icon_chunks_count(io);
+    if args.index > count {
+        return Err(error::IconError::ChunkOutOfOrder);
+    }
+    io.write_storage(&icon_chunk_key(args.index), &args.chunk);
+    // Remove the chunks of a previous icon which are not part of the new one anymore.
+    for index in args.index + 1..count {
+        io.remove_storage(&icon_chunk_key(index));
+    }
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::FungibleTokenIconChunksCount),
+        &(args.index + 1),
+    );
+    Ok(())
 }
 
 pub mod error {
@@ -728,6 +2636,27 @@ pub mod error {
     use crate::{deposit_event, fungible_token};
 
     const PROOF_EXIST: &[u8; 15] = b"ERR_PROOF_EXIST";
+    const EMPTY_DEPOSIT_BATCH: &[u8; 23] = b"ERR_EMPTY_DEPOSIT_BATCH";
+    const DEPOSIT_BATCH_TOO_LARGE: &[u8; 27] = b"ERR_DEPOSIT_BATCH_TOO_LARGE";
+    const DUPLICATE_PROOF: &[u8; 19] = b"ERR_DUPLICATE_PROOF";
+    const PROOF_TOO_LARGE: &[u8; 25] = b"ERR_PROOF_INPUT_TOO_LARGE";
+    const WRONG_LOCKER_ADDRESS: &[u8; 24] = b"ERR_WRONG_LOCKER_ADDRESS";
+    const UNKNOWN_ERC20_TOKEN: &[u8; 23] = b"ERR_UNKNOWN_ERC20_TOKEN";
+    const BELOW_MIN_DEPOSIT: &[u8; 21] = b"ERR_BELOW_MIN_DEPOSIT";
+    const RECIPIENT_DENIED: &[u8; 20] = b"ERR_RECIPIENT_DENIED";
+    const RECIPIENT_NOT_ALLOWED: &[u8; 25] = b"ERR_RECIPIENT_NOT_ALLOWED";
+    const CONNECTOR_NOT_INITIALIZED: &[u8; 29] = b"ERR_CONNECTOR_NOT_INITIALIZED";
+    const ZERO_CUSTODIAN_ADDRESS: &[u8; 26] = b"ERR_ZERO_CUSTODIAN_ADDRESS";
+    const ALREADY_CUSTODIAN: &[u8; 21] = b"ERR_ALREADY_CUSTODIAN";
+    const CUSTODIAN_NOT_FOUND: &[u8; 23] = b"ERR_CUSTODIAN_NOT_FOUND";
+    const PRIMARY_CUSTODIAN: &[u8; 21] = b"ERR_PRIMARY_CUSTODIAN";
+    const NOT_LIGHT_CLIENT: &[u8; 20] = b"ERR_NOT_LIGHT_CLIENT";
+    const DEPOSIT_NOT_FOUND: &[u8; 21] = b"ERR_DEPOSIT_NOT_FOUND";
+    const DEPOSIT_TO_ENGINE: &[u8; 21] = b"ERR_DEPOSIT_TO_ENGINE";
+    const DEPOSIT_NOT_FLAGGED: &[u8; 23] = b"ERR_DEPOSIT_NOT_FLAGGED";
+    const REASSIGN_DELAY_NOT_ELAPSED: &[u8; 30] = b"ERR_REASSIGN_DELAY_NOT_ELAPSED";
+    const DEPOSIT_ALREADY_REASSIGNED: &[u8; 30] = b"ERR_DEPOSIT_ALREADY_REASSIGNED";
+    const NOT_DEPOSIT_RECIPIENT: &[u8; 25] = b"ERR_NOT_DEPOSIT_RECIPIENT";
 
     #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
     pub enum DepositError {
@@ -735,8 +2664,26 @@ pub mod error {
         ProofParseFailed,
         EventParseFailed(deposit_event::error::ParseError),
         CustodianAddressMismatch,
+        /// The amount is below the minimum deposit set by the owner.
+        BelowMinDeposit,
+        /// The amount doesn't exceed the fee by the minimum net amount set by the owner.
         InsufficientAmountForFee,
         InvalidAddress(AddressValidationError),
+        ProofUsed,
+        NoEnabledProver,
+        ProofVerificationFailed,
+        EmptyBatch,
+        BatchTooLarge,
+        DuplicateProof,
+        /// A proof of the batch exceeds the input limit of `deposit`.
+        ProofTooLarge,
+        /// The Locked event wasn't emitted by the ERC-20 locker, or there is none.
+        LockerAddressMismatch,
+        UnknownErc20Token,
+        /// The recipient matches a suffix denied by the owner.
+        RecipientDenied,
+        /// The recipient matches none of the suffixes allowed by the owner.
+        RecipientNotAllowed,
     }
 
     impl AsRef<[u8]> for DepositError {
@@ -746,8 +2693,20 @@ pub mod error {
                 Self::ProofParseFailed => super::ERR_FAILED_PARSE.as_bytes(),
                 Self::EventParseFailed(e) => e.as_ref(),
                 Self::CustodianAddressMismatch => b"ERR_WRONG_EVENT_ADDRESS",
+                Self::BelowMinDeposit => BELOW_MIN_DEPOSIT,
                 Self::InsufficientAmountForFee => super::ERR_NOT_ENOUGH_BALANCE_FOR_FEE.as_bytes(),
                 Self::InvalidAddress(e) => e.as_ref(),
+                Self::ProofUsed => PROOF_EXIST,
+                Self::NoEnabledProver => NO_ENABLED_PROVER,
+                Self::ProofVerificationFailed => b"ERR_VERIFY_PROOF",
+                Self::EmptyBatch => EMPTY_DEPOSIT_BATCH,
+                Self::BatchTooLarge => DEPOSIT_BATCH_TOO_LARGE,
+                Self::DuplicateProof => DUPLICATE_PROOF,
+                Self::ProofTooLarge => PROOF_TOO_LARGE,
+                Self::LockerAddressMismatch => WRONG_LOCKER_ADDRESS,
+                Self::UnknownErc20Token => UNKNOWN_ERC20_TOKEN,
+                Self::RecipientDenied => RECIPIENT_DENIED,
+                Self::RecipientNotAllowed => RECIPIENT_NOT_ALLOWED,
             }
         }
     }
@@ -756,6 +2715,8 @@ pub mod error {
     pub enum FinishDepositError {
         TransferCall(FtTransferCallError),
         ProofUsed,
+        /// The message of a deposit to an EVM address isn't a transfer call.
+        InvalidMessage,
     }
 
     impl From<ProofUsed> for FinishDepositError {
@@ -781,6 +2742,147 @@ pub mod error {
             match self {
                 Self::ProofUsed => PROOF_EXIST,
                 Self::TransferCall(e) => e.as_ref(),
+                Self::InvalidMessage => super::ERR_FAILED_PARSE.as_bytes(),
+            }
+        }
+    }
+
+    const PROMISE_COUNT: &[u8; 17] = b"ERR_PROMISE_COUNT";
+    const PROMISE_FAILED: &[u8; 18] = b"ERR_PROMISE_FAILED";
+    const PROMISE_ENCODING: &[u8; 20] = b"ERR_PROMISE_ENCODING";
+
+    /// Errors of the entry points of the eth-connector, including the ones of the callbacks
+    /// which don't come from the connector itself. The code of every error is the message the
+    /// call fails with.
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum ConnectorError {
+        /// The callback isn't attached to exactly one promise.
+        PromiseCount,
+        /// The promise the callback is attached to failed.
+        PromiseFailed,
+        /// The result of the promise the callback is attached to can't be decoded.
+        PromiseEncoding,
+        Deposit(DepositError),
+        FinishDeposit(FinishDepositError),
+    }
+
+    impl From<DepositError> for ConnectorError {
+        fn from(e: DepositError) -> Self {
+            Self::Deposit(e)
+        }
+    }
+
+    impl From<FinishDepositError> for ConnectorError {
+        fn from(e: FinishDepositError) -> Self {
+            Self::FinishDeposit(e)
+        }
+    }
+
+    impl AsRef<[u8]> for ConnectorError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::PromiseCount => PROMISE_COUNT,
+                Self::PromiseFailed => PROMISE_FAILED,
+                Self::PromiseEncoding => PROMISE_ENCODING,
+                Self::Deposit(e) => e.as_ref(),
+                Self::FinishDeposit(e) => e.as_ref(),
+            }
+        }
+    }
+
+    const INVALID_ICON_CHUNK: &[u8; 22] = b"ERR_INVALID_ICON_CHUNK";
+    const ICON_CHUNK_OUT_OF_ORDER: &[u8; 27] = b"ERR_ICON_CHUNK_OUT_OF_ORDER";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum IconError {
+        InvalidChunk,
+        ChunkOutOfOrder,
+    }
+
+    impl AsRef<[u8]> for IconError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidChunk => INVALID_ICON_CHUNK,
+                Self::ChunkOutOfOrder => ICON_CHUNK_OUT_OF_ORDER,
+            }
+        }
+    }
+
+    const INVALID_PROTOCOL_FEE: &[u8; 24] = b"ERR_INVALID_PROTOCOL_FEE";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub struct InvalidProtocolFee;
+
+    impl AsRef<[u8]> for InvalidProtocolFee {
+        fn as_ref(&self) -> &[u8] {
+            INVALID_PROTOCOL_FEE
+        }
+    }
+
+    const INSUFFICIENT_PROTOCOL_FEES: &[u8; 30] = b"ERR_INSUFFICIENT_PROTOCOL_FEES";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum WithdrawProtocolFeesError {
+        /// The amount is above the protocol fees accrued on the engine account.
+        InsufficientProtocolFees,
+        Transfer(fungible_token::error::TransferError),
+    }
+
+    impl AsRef<[u8]> for WithdrawProtocolFeesError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InsufficientProtocolFees => INSUFFICIENT_PROTOCOL_FEES,
+                Self::Transfer(e) => e.as_ref(),
+            }
+        }
+    }
+
+    const INVALID_FEE_CONVERSION_ROUTE: &[u8; 32] = b"ERR_INVALID_FEE_CONVERSION_ROUTE";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub struct InvalidFeeConversionRoute;
+
+    impl AsRef<[u8]> for InvalidFeeConversionRoute {
+        fn as_ref(&self) -> &[u8] {
+            INVALID_FEE_CONVERSION_ROUTE
+        }
+    }
+
+    const INVALID_METADATA_SPEC: &[u8; 25] = b"ERR_INVALID_METADATA_SPEC";
+    const METADATA_EMPTY_NAME: &[u8; 23] = b"ERR_METADATA_EMPTY_NAME";
+    const METADATA_EMPTY_SYMBOL: &[u8; 25] = b"ERR_METADATA_EMPTY_SYMBOL";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum MetadataError {
+        InvalidSpec,
+        EmptyName,
+        EmptySymbol,
+    }
+
+    impl AsRef<[u8]> for MetadataError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidSpec => INVALID_METADATA_SPEC,
+                Self::EmptyName => METADATA_EMPTY_NAME,
+                Self::EmptySymbol => METADATA_EMPTY_SYMBOL,
+            }
+        }
+    }
+
+    const NO_ENABLED_PROVER: &[u8; 21] = b"ERR_NO_ENABLED_PROVER";
+    const DUPLICATE_PROVER: &[u8; 20] = b"ERR_DUPLICATE_PROVER";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum ProversError {
+        NoEnabledProver,
+        DuplicateProver,
+    }
+
+    impl AsRef<[u8]> for ProversError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NoEnabledProver => NO_ENABLED_PROVER,
+                Self::DuplicateProver => DUPLICATE_PROVER,
             }
         }
     }
@@ -842,6 +2944,21 @@ pub mod error {
         }
     }
 
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum ForceResolveTransferError {
+        TransferCallNotPending,
+        TooEarly,
+    }
+
+    impl AsRef<[u8]> for ForceResolveTransferError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::TransferCallNotPending => b"ERR_TRANSFER_CALL_NOT_PENDING",
+                Self::TooEarly => b"ERR_NOT_ALLOWED:TOO_EARLY",
+            }
+        }
+    }
+
     pub enum InitContractError {
         AlreadyInitialized,
         InvalidCustodianAddress(AddressValidationError),
@@ -856,6 +2973,90 @@ pub mod error {
         }
     }
 
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum SetContractDataError {
+        NotInitialized,
+        InvalidCustodianAddress(AddressValidationError),
+        ZeroCustodianAddress,
+    }
+
+    impl AsRef<[u8]> for SetContractDataError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotInitialized => CONNECTOR_NOT_INITIALIZED,
+                Self::InvalidCustodianAddress(e) => e.as_ref(),
+                Self::ZeroCustodianAddress => ZERO_CUSTODIAN_ADDRESS,
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum LightClientError {
+        /// The block hashes are only provided by the light client account.
+        NotLightClient,
+    }
+
+    impl AsRef<[u8]> for LightClientError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotLightClient => NOT_LIGHT_CLIENT,
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum CustodianError {
+        NotInitialized,
+        InvalidCustodianAddress(AddressValidationError),
+        ZeroCustodianAddress,
+        AlreadyCustodian,
+        CustodianNotFound,
+        /// `eth_custodian_address` can't be removed from the custodians.
+        PrimaryCustodian,
+    }
+
+    impl AsRef<[u8]> for CustodianError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotInitialized => CONNECTOR_NOT_INITIALIZED,
+                Self::InvalidCustodianAddress(e) => e.as_ref(),
+                Self::ZeroCustodianAddress => ZERO_CUSTODIAN_ADDRESS,
+                Self::AlreadyCustodian => ALREADY_CUSTODIAN,
+                Self::CustodianNotFound => CUSTODIAN_NOT_FOUND,
+                Self::PrimaryCustodian => PRIMARY_CUSTODIAN,
+            }
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum UnclaimedDepositError {
+        /// There is no finished deposit with the id.
+        DepositNotFound,
+        /// The deposit was made to Aurora, the engine is not an invalid recipient.
+        DepositToEngine,
+        NotFlagged,
+        /// The reassignment delay after the flag didn't elapse yet.
+        DelayNotElapsed,
+        AlreadyReassigned,
+        /// Only the recipient of the deposit can claim it.
+        NotRecipient,
+        Transfer(fungible_token::error::TransferError),
+    }
+
+    impl AsRef<[u8]> for UnclaimedDepositError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::DepositNotFound => DEPOSIT_NOT_FOUND,
+                Self::DepositToEngine => DEPOSIT_TO_ENGINE,
+                Self::NotFlagged => DEPOSIT_NOT_FLAGGED,
+                Self::DelayNotElapsed => REASSIGN_DELAY_NOT_ELAPSED,
+                Self::AlreadyReassigned => DEPOSIT_ALREADY_REASSIGNED,
+                Self::NotRecipient => NOT_DEPOSIT_RECIPIENT,
+                Self::Transfer(e) => e.as_ref(),
+            }
+        }
+    }
+
     pub struct ProofUsed;
 
     impl AsRef<[u8]> for ProofUsed {
@@ -864,3 +3065,28 @@ pub mod error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mint_volume_sliding_window() {
+        let mut volume = MintVolume::default();
+        // Buckets of 10 blocks in a window of 160 blocks.
+        assert_eq!(volume.record(100, 0, 1_000, 160), 100);
+        assert_eq!(volume.record(50, 0, 1_005, 160), 150);
+        assert_eq!(volume.record(0, 30, 1_080, 160), 120);
+        assert_eq!(volume.buckets.len(), 2);
+        // The first bucket slides out of the window, the later amounts stay in it.
+        assert_eq!(volume.record(10, 0, 1_160, 160), 0);
+        assert_eq!(volume.buckets.len(), 2);
+        assert_eq!(volume.record(40, 0, 1_170, 160), 20);
+        assert_eq!(volume.buckets.len(), 3);
+
+        // The end of the longest window doesn't overflow.
+        let mut volume = MintVolume::default();
+        assert_eq!(volume.record(7, 0, 1_000, u64::MAX), 7);
+        assert_eq!(volume.record(3, 0, 2_000, u64::MAX), 10);
+    }
+}
@@ -0,0 +1,115 @@
+//! Versioning of the layout of the eth-connector state.
+//!
+//! The contract data, the fungible token totals and the paused flags of the eth-connector are
+//! Borsh-encoded structs, which can't be read anymore once their layout changes. The version of
+//! the layout they are stored with is kept next to them, and `migrate` converts them step by
+//! step to the layout of the running engine. A new layout goes with a converter from the
+//! previous one, and `CURRENT_STATE_VERSION` is incremented.
+use crate::admin_controlled::PausedMask;
+use crate::connector::{construct_contract_key, EthConnector};
+use crate::fungible_token::FungibleToken;
+use crate::prelude::{BorshDeserialize, BorshSerialize, EthConnectorStorageId};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Version of the layout of the eth-connector state written by this engine.
+pub const CURRENT_STATE_VERSION: u32 = 1;
+
+/// Version of the deployments initialized before the state was versioned, their layout is the
+/// one of version 1.
+const UNVERSIONED_STATE: u32 = 0;
+
+pub fn get_state_version<I: IO>(io: &I) -> u32 {
+    io.read_storage(&construct_contract_key(
+        &EthConnectorStorageId::StateVersion,
+    ))
+    .and_then(|value| value.to_value().ok())
+    .unwrap_or(UNVERSIONED_STATE)
+}
+
+pub fn set_state_version<I: IO>(io: &mut I, version: u32) {
+    io.write_borsh(
+        &construct_contract_key(&EthConnectorStorageId::StateVersion),
+        &version,
+    );
+}
+
+/// Converts the state of the eth-connector to the current layout. Returns the version of the
+/// state, which is unchanged if it is already current.
+pub fn migrate<I: IO>(io: &mut I) -> Result<u32, error::MigrationError> {
+    if !io.storage_has_key(&construct_contract_key(&EthConnectorStorageId::Contract)) {
+        return Err(error::MigrationError::NotInitialized);
+    }
+    let mut version = get_state_version(io);
+    if version > CURRENT_STATE_VERSION {
+        return Err(error::MigrationError::UnknownVersion);
+    }
+    while version < CURRENT_STATE_VERSION {
+        match version {
+            // Only checks that the state can be read with the layout of version 1.
+            UNVERSIONED_STATE => {
+                convert_state::<EthConnector, EthConnector, I>(
+                    io,
+                    EthConnectorStorageId::Contract,
+                )?;
+                convert_state::<FungibleToken, FungibleToken, I>(
+                    io,
+                    EthConnectorStorageId::FungibleToken,
+                )?;
+                convert_state::<PausedMask, PausedMask, I>(io, EthConnectorStorageId::PausedMask)?;
+            }
+            _ => return Err(error::MigrationError::MissingConverter),
+        }
+        version += 1;
+    }
+    set_state_version(io, version);
+    Ok(version)
+}
+
+/// Reads the value stored under the id with its old layout and writes it back with the new one.
+fn convert_state<Old, New, I>(
+    io: &mut I,
+    id: EthConnectorStorageId,
+) -> Result<(), error::MigrationError>
+where
+    Old: BorshDeserialize,
+    New: BorshSerialize + From<Old>,
+    I: IO,
+{
+    let key = construct_contract_key(&id);
+    let old: Old = io
+        .read_storage(&key)
+        .ok_or(error::MigrationError::MissingValue)?
+        .to_value()
+        .map_err(|_| error::MigrationError::DeserializationFailed)?;
+    io.write_borsh(&key, &New::from(old));
+    Ok(())
+}
+
+pub mod error {
+    const NOT_INITIALIZED: &[u8; 29] = b"ERR_CONNECTOR_NOT_INITIALIZED";
+    const UNKNOWN_VERSION: &[u8; 29] = b"ERR_UNKNOWN_CONNECTOR_VERSION";
+    const MISSING_CONVERTER: &[u8; 27] = b"ERR_MISSING_STATE_CONVERTER";
+    const MISSING_VALUE: &[u8; 27] = b"ERR_MISSING_CONNECTOR_STATE";
+    const DESERIALIZATION_FAILED: &[u8; 29] = b"ERR_CONNECTOR_STATE_CORRUPTED";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum MigrationError {
+        NotInitialized,
+        UnknownVersion,
+        MissingConverter,
+        MissingValue,
+        DeserializationFailed,
+    }
+
+    impl AsRef<[u8]> for MigrationError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotInitialized => NOT_INITIALIZED,
+                Self::UnknownVersion => UNKNOWN_VERSION,
+                Self::MissingConverter => MISSING_CONVERTER,
+                Self::MissingValue => MISSING_VALUE,
+                Self::DeserializationFailed => DESERIALIZATION_FAILED,
+            }
+        }
+    }
+}
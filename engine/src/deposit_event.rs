@@ -1,5 +1,5 @@
 use crate::deposit_event::error::ParseEventMessageError;
-use crate::log_entry::LogEntry;
+use crate::eth_rlp::LogEntry;
 use crate::prelude::account_id::AccountId;
 use crate::prelude::{
     validate_eth_address, vec, AddressValidationError, Balance, BorshDeserialize, BorshSerialize,
@@ -9,6 +9,11 @@ use byte_slice_cast::AsByteSlice;
 use ethabi::{Event, EventParam, Hash, Log, ParamType, RawLog};
 
 pub const DEPOSITED_EVENT: &str = "Deposited";
+/// Event of the ERC-20 locker on Ethereum, emitted when tokens are locked to be bridged.
+pub const LOCKED_EVENT: &str = "Locked";
+/// Separates the optional memo from the recipient in the `recipient` field of the event.
+/// It can't be part of a NEAR account id nor of the hex encoded message of the recipient.
+pub const MEMO_SEPARATOR: char = '#';
 
 pub type EventParams = Vec<EventParam>;
 
@@ -68,6 +73,33 @@ impl FtTransferMessageData {
         })
     }
 
+    /// Get on-transfer data from the message of a transfer call to the engine, sent by
+    /// `sender_id`. The message is either the one of `parse_on_transfer_message`, or only the
+    /// hex encoded recipient address (with or without `0x`), credited with the whole amount:
+    /// there is no fee and the sender is the relayer.
+    pub fn parse_transfer_call_message(
+        message: &str,
+        sender_id: &AccountId,
+    ) -> Result<Self, error::ParseOnTransferMessageError> {
+        if message.contains(':') {
+            return Self::parse_on_transfer_message(message);
+        }
+        let address = message.strip_prefix("0x").unwrap_or(message);
+        let recipient = validate_eth_address(address.to_string()).map_err(|e| match e {
+            AddressValidationError::FailedDecodeHex => {
+                error::ParseOnTransferMessageError::InvalidHexData
+            }
+            AddressValidationError::IncorrectLength => {
+                error::ParseOnTransferMessageError::WrongMessageFormat
+            }
+        })?;
+        Ok(FtTransferMessageData {
+            relayer: sender_id.clone(),
+            recipient,
+            fee: 0.into(),
+        })
+    }
+
     /// Encode to String with specific rules
     pub fn encode(&self) -> String {
         // The first data section should contain fee data.
@@ -193,13 +225,22 @@ impl EthEvent {
         name: &str,
         params: EventParams,
         data: &[u8],
+    ) -> Result<Self, error::DecodeError> {
+        let log_entry: LogEntry = rlp::decode(data).map_err(|_| error::DecodeError::RlpFailed)?;
+        Self::from_log_entry(name, params, log_entry)
+    }
+
+    /// Get Ethereum event from a decoded log entry
+    pub fn from_log_entry(
+        name: &str,
+        params: EventParams,
+        log_entry: LogEntry,
     ) -> Result<Self, error::DecodeError> {
         let event = Event {
             name: name.to_string(),
             inputs: params,
             anonymous: false,
         };
-        let log_entry: LogEntry = rlp::decode(data).map_err(|_| error::DecodeError::RlpFailed)?;
         let eth_custodian_address = log_entry.address.0;
         let topics = log_entry.topics.iter().map(|h| Hash::from(h.0)).collect();
 
@@ -218,19 +259,22 @@ impl EthEvent {
     }
 }
 
-/// Data that was emitted by Deposited event.
-pub struct DepositedEvent {
-    pub eth_custodian_address: EthAddress,
-    pub sender: EthAddress,
-    pub token_message_data: TokenMessageData,
-    pub amount: Balance,
-    pub fee: Fee,
+/// Versions of the ABI of the Deposited event of the custodian, told apart by the topic of
+/// their signature. The custodian can be upgraded on Ethereum while deposits emitted by the
+/// former version are still in flight, so every version is decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepositedEventVersion {
+    /// `Deposited(address indexed sender, string recipient, uint256 amount, uint256 fee)`
+    V1,
+    /// `Deposited(address indexed sender, string recipient, uint256 amount, uint256 fee, bytes metadata)`
+    V2,
 }
 
-impl DepositedEvent {
-    #[allow(dead_code)]
-    pub fn event_params() -> EventParams {
-        vec![
+impl DepositedEventVersion {
+    pub const ALL: [DepositedEventVersion; 2] = [Self::V1, Self::V2];
+
+    pub fn event_params(self) -> EventParams {
+        let mut params = vec![
             EventParam {
                 name: "sender".to_string(),
                 kind: ParamType::Address,
@@ -251,12 +295,83 @@ impl DepositedEvent {
                 kind: ParamType::Uint(256),
                 indexed: false,
             },
-        ]
+        ];
+        if self == Self::V2 {
+            params.push(EventParam {
+                name: "metadata".to_string(),
+                kind: ParamType::Bytes,
+                indexed: false,
+            });
+        }
+        params
     }
 
-    /// Parses raw Ethereum logs proof's entry data
+    /// Topic of the signature of the event, the first topic of its logs.
+    pub fn topic(self) -> Hash {
+        Event {
+            name: DEPOSITED_EVENT.to_string(),
+            inputs: self.event_params(),
+            anonymous: false,
+        }
+        .signature()
+    }
+
+    pub fn from_topic(topic: &Hash) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| version.topic() == *topic)
+    }
+}
+
+/// Data that was emitted by Deposited event.
+pub struct DepositedEvent {
+    pub eth_custodian_address: EthAddress,
+    pub sender: EthAddress,
+    pub token_message_data: TokenMessageData,
+    pub amount: Balance,
+    pub fee: Fee,
+    /// Memo of the deposit, e.g. to identify the depositor on an exchange.
+    pub memo: Option<String>,
+    /// Metadata of the deposit, only emitted from the version 2 of the event.
+    pub metadata: Option<Vec<u8>>,
+}
+
+/// Splits the `recipient` field of the event into the recipient and the memo, if any.
+fn split_memo(recipient: &str) -> (&str, Option<String>) {
+    match recipient.find(MEMO_SEPARATOR) {
+        Some(index) => {
+            let memo = &recipient[index + MEMO_SEPARATOR.len_utf8()..];
+            let memo = if memo.is_empty() {
+                None
+            } else {
+                Some(memo.to_string())
+            };
+            (&recipient[..index], memo)
+        }
+        None => (recipient, None),
+    }
+}
+
+impl DepositedEvent {
+    /// Parameters of the version 1 of the event.
+    #[allow(dead_code)]
+    pub fn event_params() -> EventParams {
+        DepositedEventVersion::V1.event_params()
+    }
+
+    /// Parses raw Ethereum logs proof's entry data, of any version of the event
     pub fn from_log_entry_data(data: &[u8]) -> Result<Self, error::ParseError> {
-        let event = EthEvent::fetch_log_entry_data(DEPOSITED_EVENT, Self::event_params(), data)
+        let log_entry: LogEntry = rlp::decode(data)
+            .map_err(|_| error::ParseError::LogParseFailed(error::DecodeError::RlpFailed))?;
+        let version = log_entry
+            .topics
+            .first()
+            .and_then(|topic| DepositedEventVersion::from_topic(&Hash::from(topic.0)))
+            .ok_or(error::ParseError::LogParseFailed(
+                error::DecodeError::SchemaMismatch,
+            ))?;
+        let event = EthEvent::from_log_entry(DEPOSITED_EVENT, version.event_params(), log_entry)
             .map_err(error::ParseError::LogParseFailed)?;
         let sender = event.log.params[0]
             .value
@@ -283,12 +398,20 @@ impl DepositedEvent {
             .try_into()
             .map_err(|_| error::ParseError::OverflowNumber)?;
         let fee: Fee = raw_fee.into();
+        let metadata = match version {
+            DepositedEventVersion::V1 => None,
+            DepositedEventVersion::V2 => Some(
+                event.log.params[4]
+                    .value
+                    .clone()
+                    .into_bytes()
+                    .ok_or(error::ParseError::InvalidMetadata)?,
+            ),
+        };
 
+        let (recipient, memo) = split_memo(&event_message_data);
         let token_message_data =
-            TokenMessageData::parse_event_message_and_prepare_token_message_data(
-                &event_message_data,
-                fee,
-            )?;
+            TokenMessageData::parse_event_message_and_prepare_token_message_data(recipient, fee)?;
 
         Ok(Self {
             eth_custodian_address: event.eth_custodian_address,
@@ -296,6 +419,80 @@ impl DepositedEvent {
             token_message_data,
             amount,
             fee,
+            memo,
+            metadata,
+        })
+    }
+}
+
+/// Data that was emitted by the Locked event of the ERC-20 locker.
+pub struct LockedEvent {
+    pub locker_address: EthAddress,
+    /// Address of the locked ERC-20 token on Ethereum.
+    pub token: EthAddress,
+    pub sender: EthAddress,
+    pub amount: Balance,
+    pub recipient: AccountId,
+}
+
+impl LockedEvent {
+    pub fn event_params() -> EventParams {
+        vec![
+            EventParam {
+                name: "token".to_string(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "sender".to_string(),
+                kind: ParamType::Address,
+                indexed: true,
+            },
+            EventParam {
+                name: "amount".to_string(),
+                kind: ParamType::Uint(256),
+                indexed: false,
+            },
+            EventParam {
+                name: "accountId".to_string(),
+                kind: ParamType::String,
+                indexed: false,
+            },
+        ]
+    }
+
+    /// Parses raw Ethereum logs proof's entry data
+    pub fn from_log_entry_data(data: &[u8]) -> Result<Self, error::ParseError> {
+        let event = EthEvent::fetch_log_entry_data(LOCKED_EVENT, Self::event_params(), data)
+            .map_err(error::ParseError::LogParseFailed)?;
+        let token = event.log.params[0]
+            .value
+            .clone()
+            .into_address()
+            .ok_or(error::ParseError::InvalidToken)?
+            .0;
+        let sender = event.log.params[1]
+            .value
+            .clone()
+            .into_address()
+            .ok_or(error::ParseError::InvalidSender)?
+            .0;
+        let amount: u128 = event.log.params[2]
+            .value
+            .clone()
+            .into_uint()
+            .ok_or(error::ParseError::InvalidAmount)?
+            .try_into()
+            .map_err(|_| error::ParseError::OverflowNumber)?;
+        let recipient = AccountId::try_from(event.log.params[3].value.to_string().as_bytes())
+            .map_err(|_| ParseEventMessageError::InvalidAccount)?;
+
+        Ok(Self {
+            locker_address: event.eth_custodian_address,
+            token,
+            sender,
+            amount,
+            recipient,
         })
     }
 }
@@ -348,6 +545,8 @@ pub mod error {
         InvalidSender,
         InvalidAmount,
         InvalidFee,
+        InvalidToken,
+        InvalidMetadata,
         MessageParseFailed(ParseEventMessageError),
         OverflowNumber,
     }
@@ -358,6 +557,8 @@ pub mod error {
                 Self::InvalidSender => b"ERR_INVALID_SENDER",
                 Self::InvalidAmount => b"ERR_INVALID_AMOUNT",
                 Self::InvalidFee => b"ERR_INVALID_FEE",
+                Self::InvalidToken => b"ERR_INVALID_TOKEN",
+                Self::InvalidMetadata => b"ERR_INVALID_METADATA",
                 Self::MessageParseFailed(e) => e.as_ref(),
                 Self::OverflowNumber => b"ERR_OVERFLOW_NUMBER",
             }
@@ -385,3 +586,123 @@ pub mod error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_memo() {
+        assert_eq!(split_memo("alice.near"), ("alice.near", None));
+        assert_eq!(
+            split_memo("alice.near#deposit 42"),
+            ("alice.near", Some("deposit 42".to_string()))
+        );
+        assert_eq!(
+            split_memo("aurora:0123#a#b"),
+            ("aurora:0123", Some("a#b".to_string()))
+        );
+        assert_eq!(split_memo("alice.near#"), ("alice.near", None));
+    }
+
+    fn deposited_log_entry(version: DepositedEventVersion, tokens: &[ethabi::Token]) -> Vec<u8> {
+        let mut sender = [0u8; 32];
+        sender[12..].copy_from_slice(&[0x11; 20]);
+        let log_entry = LogEntry {
+            address: [0x22; 20].into(),
+            topics: vec![version.topic().0.into(), sender.into()],
+            data: ethabi::encode(tokens),
+        };
+        rlp::encode(&log_entry).to_vec()
+    }
+
+    #[test]
+    fn test_deposited_event_topics() {
+        // The topics are the ones of the custodian contracts on Ethereum, they must not change.
+        assert_eq!(
+            hex::encode(DepositedEventVersion::V1.topic()),
+            "d142439c278e25dad9a50766f153d0e3d2d7bf2bd16fc2781c4bd494b2b15a9d"
+        );
+        assert_eq!(
+            hex::encode(DepositedEventVersion::V2.topic()),
+            "502b8d87ddf08abf4b25ea02c20156c1c46d1f5a5b900a477e308e7d239e1f0e"
+        );
+    }
+
+    #[test]
+    fn test_deposited_event_versions() {
+        let v1_tokens = vec![
+            ethabi::Token::String("alice.near#memo".to_string()),
+            ethabi::Token::Uint(100.into()),
+            ethabi::Token::Uint(10.into()),
+        ];
+        let event = DepositedEvent::from_log_entry_data(&deposited_log_entry(
+            DepositedEventVersion::V1,
+            &v1_tokens,
+        ))
+        .unwrap();
+        assert_eq!(event.eth_custodian_address, [0x22; 20]);
+        assert_eq!(event.sender, [0x11; 20]);
+        assert_eq!(
+            event.token_message_data.get_recipient().as_ref(),
+            "alice.near"
+        );
+        assert_eq!(event.amount, 100);
+        assert_eq!(event.fee.into_u128(), 10);
+        assert_eq!(event.memo, Some("memo".to_string()));
+        assert_eq!(event.metadata, None);
+
+        let mut v2_tokens = v1_tokens.clone();
+        v2_tokens.push(ethabi::Token::Bytes(vec![1, 2, 3]));
+        let event = DepositedEvent::from_log_entry_data(&deposited_log_entry(
+            DepositedEventVersion::V2,
+            &v2_tokens,
+        ))
+        .unwrap();
+        assert_eq!(event.amount, 100);
+        assert_eq!(event.memo, Some("memo".to_string()));
+        assert_eq!(event.metadata, Some(vec![1, 2, 3]));
+
+        // The logs of other events are rejected.
+        let log_entry = LogEntry {
+            address: [0x22; 20].into(),
+            topics: vec![[0x33; 32].into()],
+            data: ethabi::encode(&v1_tokens),
+        };
+        assert!(DepositedEvent::from_log_entry_data(&rlp::encode(&log_entry)).is_err());
+    }
+
+    #[test]
+    fn test_parse_transfer_call_message() {
+        let sender_id: AccountId = "sender.near".parse().unwrap();
+        let recipient = [0x12; 20];
+        for message in vec![
+            hex::encode(recipient),
+            format!("0x{}", hex::encode(recipient)),
+        ] {
+            let data =
+                FtTransferMessageData::parse_transfer_call_message(&message, &sender_id).unwrap();
+            assert_eq!(data.relayer, sender_id);
+            assert_eq!(data.recipient, recipient);
+            assert_eq!(data.fee.into_u128(), 0);
+        }
+
+        let message = FtTransferMessageData {
+            relayer: "relayer.near".parse().unwrap(),
+            recipient,
+            fee: 7.into(),
+        }
+        .encode();
+        let data =
+            FtTransferMessageData::parse_transfer_call_message(&message, &sender_id).unwrap();
+        assert_eq!(data.relayer.as_ref(), "relayer.near");
+        assert_eq!(data.recipient, recipient);
+        assert_eq!(data.fee.into_u128(), 7);
+
+        assert!(FtTransferMessageData::parse_transfer_call_message("", &sender_id).is_err());
+        assert!(FtTransferMessageData::parse_transfer_call_message("0x1234", &sender_id).is_err());
+        assert!(
+            FtTransferMessageData::parse_transfer_call_message("alice.near", &sender_id).is_err()
+        );
+    }
+}
@@ -4,22 +4,44 @@ use evm::backend::{Apply, ApplyBackend, Backend, Basic, Log};
 use evm::executor;
 use evm::{Config, CreateScheme, ExitError, ExitFatal, ExitReason};
 
-use crate::connector::EthConnectorContract;
+use crate::admin_controlled::{PausedPrecompilesMask, PAUSE_EXIT_TO_ETHEREUM, PAUSE_EXIT_TO_NEAR};
+use crate::callback;
+use crate::compression;
+use crate::connector::{self, EthConnectorContract};
+use crate::engine_logs;
+use crate::entry_point;
+use crate::events;
+use crate::freeze;
+use crate::fungible_token;
+use crate::gas_price;
+use crate::json::JsonValue;
 use crate::map::BijectionMap;
+use crate::storage_key;
+use crate::system_addresses;
+use crate::token_supply;
 use aurora_engine_sdk::env::Env;
+use aurora_engine_sdk::error::StateCorruption;
 use aurora_engine_sdk::io::{StorageIntermediate, IO};
 use aurora_engine_sdk::promise::{PromiseHandler, PromiseId};
 
-use crate::parameters::{DeployErc20TokenArgs, NewCallArgs, TransactionStatus};
+use crate::parameters::{
+    AccountBalances, BatchTransactionResult, BlockAttributes, BlockGasUsage, BurnedTotal,
+    DeployErc20TokenArgs, DryRunSubmitResult, EvmLimits, FeeConversionRoute, FullConfig,
+    GetBalancesCallArgs, HandleOpsArgs, NewCallArgs, PriorityFeeSplit, SubmitBatchArgs,
+    TokenBalance, TransactionStatus,
+};
 use crate::prelude::precompiles::native::{ExitToEthereum, ExitToNear};
 use crate::prelude::precompiles::Precompiles;
 use crate::prelude::{
-    address_to_key, bytes_to_key, sdk, storage_to_key, u256_to_arr, vec, AccountId, Address,
-    BorshDeserialize, BorshSerialize, KeyPrefix, PromiseArgs, PromiseCreateArgs, ToString, TryFrom,
-    TryInto, Vec, Wei, ERC20_MINT_SELECTOR, H256, U256,
+    address_to_key, bytes_to_key, format_amount_with_symbol, sdk, storage_to_key, u256_to_arr, vec,
+    AccountId, Address, BTreeMap, BorshDeserialize, BorshSerialize, Box, ConfigKey,
+    InternalMetaCallArgs, KeyPrefix, LogBloom, NEP141Wei, PromiseArgs, PromiseCreateArgs, RawU256,
+    String, ToString, TryFrom, TryInto, Vec, Wei, ERC20_BALANCE_OF_SELECTOR, ERC20_MINT_SELECTOR,
+    ETH_DECIMALS, H256, U256,
 };
 use crate::transaction::{EthTransactionKind, NormalizedEthTransaction};
-use aurora_engine_precompiles::PrecompileConstructorContext;
+use aurora_engine_precompiles::{HostFunctions, PrecompileConstructorContext};
+use aurora_engine_types::parameters::AbiSchemaType;
 
 /// Used as the first byte in the concatenation of data used to compute the blockhash.
 /// Could be useful in the future as a version byte, or to distinguish different types of blocks.
@@ -87,8 +109,31 @@ pub enum EngineErrorKind {
     InvalidSignature,
     IntrinsicGasNotMet,
     MaxPriorityGasFeeTooLarge,
+    /// The maximum fee per gas of the transaction is below the base fee of the block.
+    MaxGasFeeTooLow,
     GasPayment(GasPaymentError),
     GasOverflow,
+    /// The same transaction was already submitted within the duplicate submit window.
+    AlreadySubmitted,
+    InvalidAbiSchema,
+    /// The origin of the transaction is frozen.
+    AccountFrozen,
+    /// The transaction transfers value out of a frozen address.
+    FrozenTransfer,
+    /// The transaction deploys code at a reserved address.
+    SystemAddressCollision,
+    /// The gas price of the transaction is below the one set with `set_gas_price`.
+    GasPriceTooLow,
+    /// The transaction isn't signed for a chain (pre EIP-155) and these are rejected.
+    UnprotectedTransaction,
+    /// The meta-transaction designates another fee payer than the one submitting it.
+    FeePayerMismatch,
+    /// The bundle of `handle_ops` isn't an ABI encoded array of user operations.
+    InvalidUserOps,
+    /// A callback on the engine scheduled by the transaction can't be guarded.
+    Callback(callback::error::CallbackError),
+    /// The output of the DEX router doesn't decode as the amounts of the swap.
+    InvalidSwapOutput,
 }
 
 impl EngineErrorKind {
@@ -122,11 +167,23 @@ impl EngineErrorKind {
             IncorrectNonce => b"ERR_INCORRECT_NONCE",
             FailedTransactionParse(e) => e.as_ref(),
             InvalidChainId => b"ERR_INVALID_CHAIN_ID",
+            UnprotectedTransaction => b"ERR_UNPROTECTED_TX",
             InvalidSignature => b"ERR_INVALID_ECDSA_SIGNATURE",
             IntrinsicGasNotMet => b"ERR_INTRINSIC_GAS",
             MaxPriorityGasFeeTooLarge => b"ERR_MAX_PRIORITY_FEE_GREATER",
+            MaxGasFeeTooLow => b"ERR_MAX_FEE_BELOW_BASE_FEE",
             GasPayment(e) => e.as_ref(),
             GasOverflow => b"ERR_GAS_OVERFLOW",
+            AlreadySubmitted => b"ERR_ALREADY_SUBMITTED",
+            InvalidAbiSchema => b"ERR_INVALID_ABI_SCHEMA",
+            AccountFrozen => b"ERR_ACCOUNT_FROZEN",
+            FrozenTransfer => b"ERR_FROZEN_TRANSFER",
+            SystemAddressCollision => b"ERR_SYSTEM_ADDRESS_COLLISION",
+            GasPriceTooLow => b"ERR_GAS_PRICE_TOO_LOW",
+            FeePayerMismatch => b"ERR_FEE_PAYER_MISMATCH",
+            InvalidUserOps => b"ERR_INVALID_USER_OPS",
+            Callback(e) => e.as_ref(),
+            InvalidSwapOutput => b"ERR_INVALID_SWAP_OUTPUT",
         }
     }
 }
@@ -166,7 +223,11 @@ impl ExitIntoResult for ExitReason {
             Error(ExitError::OutOfOffset) => Ok(TransactionStatus::OutOfOffset),
             Error(ExitError::OutOfFund) => Ok(TransactionStatus::OutOfFund),
             Error(ExitError::OutOfGas) => Ok(TransactionStatus::OutOfGas),
+            Error(ExitError::CallTooDeep) => Ok(TransactionStatus::CallTooDeep),
             Error(e) => Err(e.into()),
+            // The interpreter reports a memory growing beyond the configured limit as
+            // not supported.
+            Fatal(ExitFatal::NotSupported) => Ok(TransactionStatus::MemoryLimit),
             Fatal(e) => Err(e.into()),
         }
     }
@@ -208,6 +269,42 @@ impl From<BalanceOverflow> for GasPaymentError {
     }
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InvalidPriorityFeeShare;
+
+impl AsRef<[u8]> for InvalidPriorityFeeShare {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_INVALID_PRIORITY_FEE_SHARE"
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InvalidEvmLimits;
+
+impl AsRef<[u8]> for InvalidEvmLimits {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_INVALID_EVM_LIMITS"
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InvalidBlockAttributes;
+
+impl AsRef<[u8]> for InvalidBlockAttributes {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_INVALID_BLOCK_ATTRIBUTES"
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnknownPrecompileFlags;
+
+impl AsRef<[u8]> for UnknownPrecompileFlags {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_UNKNOWN_PRECOMPILE_FLAGS"
+    }
+}
+
 #[derive(Debug)]
 pub enum DeployErc20Error {
     State(EngineStateError),
@@ -226,6 +323,26 @@ impl AsRef<[u8]> for DeployErc20Error {
     }
 }
 
+#[derive(Debug)]
+pub enum Erc20ReadError {
+    State(EngineStateError),
+    /// The address isn't the ERC-20 of a bridged NEP-141 token.
+    NotBridged,
+    Failed(TransactionStatus),
+    Engine(EngineErrorKind),
+}
+
+impl AsRef<[u8]> for Erc20ReadError {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::State(e) => e.as_ref(),
+            Self::NotBridged => b"ERR_NOT_BRIDGED_TOKEN",
+            Self::Failed(e) => e.as_ref(),
+            Self::Engine(e) => e.as_ref(),
+        }
+    }
+}
+
 pub struct ERC20Address(Address);
 
 impl AsRef<[u8]> for ERC20Address {
@@ -329,19 +446,45 @@ impl AsRef<[u8]> for EngineStateError {
     }
 }
 
-struct StackExecutorParams {
-    precompiles: Precompiles,
+/// The chain ID can't change once a transaction was executed.
+#[derive(Debug)]
+pub struct ChainIdLockedError;
+
+impl AsRef<[u8]> for ChainIdLockedError {
+    fn as_ref(&self) -> &[u8] {
+        b"ERR_CHAIN_ID_LOCKED"
+    }
+}
+
+struct StackExecutorParams<'p> {
+    precompiles: Precompiles<'p>,
     gas_limit: u64,
+    config: Config,
 }
 
-impl StackExecutorParams {
-    fn new(gas_limit: u64, current_account_id: AccountId, random_seed: H256) -> Self {
+impl<'p> StackExecutorParams<'p> {
+    #[allow(clippy::too_many_arguments)]
+    fn new<I: IO + Copy + 'p, E: Env>(
+        gas_limit: u64,
+        current_account_id: AccountId,
+        env: &'p E,
+        limits: EvmLimits,
+        paused_precompiles: PausedPrecompilesMask,
+        host_functions: HostFunctions,
+        io: I,
+    ) -> Self {
+        let mut precompiles = Precompiles::new_london(PrecompileConstructorContext {
+            current_account_id,
+            random_seed: env.random_seed(),
+            host_functions,
+            io,
+            env,
+        });
+        precompiles.pause(&paused_precompile_addresses(paused_precompiles));
         Self {
-            precompiles: Precompiles::new_london(PrecompileConstructorContext {
-                current_account_id,
-                random_seed,
-            }),
+            precompiles,
             gas_limit,
+            config: evm_config(limits),
         }
     }
 
@@ -349,14 +492,14 @@ impl StackExecutorParams {
         &'a self,
         engine: &'a Engine<'env, I, E>,
     ) -> executor::StackExecutor<
-        'static,
+        'a,
         'a,
         executor::MemoryStackState<Engine<'env, I, E>>,
-        Precompiles,
+        Precompiles<'p>,
     > {
-        let metadata = executor::StackSubstateMetadata::new(self.gas_limit, CONFIG);
+        let metadata = executor::StackSubstateMetadata::new(self.gas_limit, &self.config);
         let state = executor::MemoryStackState::new(metadata, engine);
-        executor::StackExecutor::new_with_precompiles(state, CONFIG, &self.precompiles)
+        executor::StackExecutor::new_with_precompiles(state, &self.config, &self.precompiles)
     }
 }
 
@@ -367,6 +510,23 @@ pub struct GasPaymentResult {
     pub priority_fee_per_gas: U256,
 }
 
+/// Settlement of the gas of a transaction, once its EVM gas used is known. The gas used is paid
+/// at the gas price of the transaction, the rest of the prepaid amount is refunded to the sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasPayment {
+    pub gas_used: u64,
+    pub gas_price: U256,
+    pub relayer: Address,
+    /// Part of the priority fees credited to the relayer.
+    pub relayer_reward: Wei,
+    /// Part of the priority fees credited to the treasury, if a split is configured.
+    pub treasury_reward: Wei,
+    /// Unused prepaid amount refunded to the sender.
+    pub refund: Wei,
+    /// Base fees, credited to the burn address.
+    pub burned: Wei,
+}
+
 /// Engine internal state, mostly configuration.
 /// Should not contain anything large or enumerable.
 #[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
@@ -399,14 +559,48 @@ pub struct Engine<'env, I: IO, E: Env> {
     origin: Address,
     gas_price: U256,
     current_account_id: AccountId,
+    block_attributes: BlockAttributes,
     io: I,
     env: &'env E,
 }
 
 pub(crate) const CONFIG: &Config = &Config::london();
 
+/// Coinbase of the blocks unless set by the owner.
+const DEFAULT_COINBASE: Address = Address([
+    0x44, 0x44, 0x58, 0x84, 0x43, 0xC3, 0xa9, 0x12, 0x88, 0xc5, 0x00, 0x24, 0x83, 0x44, 0x9A, 0xba,
+    0x10, 0x54, 0x19, 0x2b,
+]);
+
+/// Configuration of the EVM with the limits set by the owner.
+fn evm_config(limits: EvmLimits) -> Config {
+    let mut config = CONFIG.clone();
+    config.call_stack_limit = limits.max_call_depth as usize;
+    if let Some(max_memory_bytes) = limits.max_memory_bytes {
+        config.memory_limit = usize::try_from(max_memory_bytes).unwrap_or(usize::MAX);
+    }
+    config
+}
+
 /// Key for storing the state of the engine.
-const STATE_KEY: &[u8; 5] = b"STATE";
+pub(crate) const STATE_KEY: &[u8; 5] = b"STATE";
+/// Keys of the code of the engine and of the upgrade staged by the owner.
+pub(crate) const CODE_KEY: &[u8; 4] = b"CODE";
+pub(crate) const CODE_STAGE_KEY: &[u8; 10] = b"CODE_STAGE";
+
+/// Address which receives the ETH burned by the engine, e.g. the base fee of transactions.
+/// Nobody knows a private key of this address, so its balance can never be spent and
+/// it is equal to the total amount of ETH burned on Aurora.
+///
+/// Address: `0xdf3fa4c29816e410764afb0dd0558c46f460ed15`
+/// This address is computed as: `&keccak("burned")[12..]`
+pub const BURN_ADDRESS: Address = Address([
+    0xdf, 0x3f, 0xa4, 0xc2, 0x98, 0x16, 0xe4, 0x10, 0x76, 0x4a, 0xfb, 0x0d, 0xd0, 0x55, 0x8c, 0x46,
+    0xf4, 0x60, 0xed, 0x15,
+]);
+
+/// Denominator of the share of the priority fees routed to the treasury.
+pub const PRIORITY_FEE_SHARE_DENOMINATOR: u16 = 10_000;
 
 impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
     pub fn new(
@@ -430,6 +624,7 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
             origin,
             gas_price: U256::zero(),
             current_account_id,
+            block_attributes: get_block_attributes(&io),
             io,
             env,
         }
@@ -444,10 +639,8 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
             return Ok(GasPaymentResult::default());
         }
 
-        let priority_fee_per_gas = transaction
-            .max_priority_fee_per_gas
-            .min(transaction.max_fee_per_gas - self.block_base_fee_per_gas());
-        let effective_gas_price = priority_fee_per_gas + self.block_base_fee_per_gas();
+        let priority_fee_per_gas = transaction.priority_fee_per_gas(block_base_fee_per_gas());
+        let effective_gas_price = priority_fee_per_gas + block_base_fee_per_gas();
         let gas_limit = transaction.gas_limit;
         let prepaid_amount = gas_limit
             .checked_mul(effective_gas_price)
@@ -488,17 +681,76 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         access_list: Vec<(Address, Vec<H256>)>, // See EIP-2930
         handler: &mut P,
     ) -> EngineResult<SubmitResult> {
+        self.create(origin, value, input, None, gas_limit, access_list, handler)
+    }
+
+    /// Deploys the code at an address which depends only on the origin, the salt and the
+    /// code, as the `CREATE2` opcode does.
+    pub fn deploy_code_create2<P: PromiseHandler>(
+        &mut self,
+        origin: Address,
+        input: Vec<u8>,
+        salt: H256,
+        handler: &mut P,
+    ) -> EngineResult<SubmitResult> {
+        self.create(
+            origin,
+            Wei::zero(),
+            input,
+            Some(salt),
+            u64::MAX,
+            Vec::new(),
+            handler,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create<P: PromiseHandler>(
+        &mut self,
+        origin: Address,
+        value: Wei,
+        input: Vec<u8>,
+        salt: Option<H256>,
+        gas_limit: u64,
+        access_list: Vec<(Address, Vec<H256>)>,
+        handler: &mut P,
+    ) -> EngineResult<SubmitResult> {
+        if freeze::is_address_frozen(&self.io, &origin) {
+            return Err(EngineErrorKind::AccountFrozen.into());
+        }
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            self.env,
+            get_evm_limits(&self.io),
+            get_paused_precompiles(&self.io),
+            get_host_functions(&self.io),
+            self.io,
         );
         let mut executor = executor_params.make_executor(self);
-        let address = executor.create_address(CreateScheme::Legacy { caller: origin });
-        let (exit_reason, result) = (
-            executor.transact_create(origin, value.raw(), input, gas_limit, access_list),
-            address,
-        );
+        let (exit_reason, result) = match salt {
+            None => {
+                let address = executor.create_address(CreateScheme::Legacy { caller: origin });
+                (
+                    executor.transact_create(origin, value.raw(), input, gas_limit, access_list),
+                    address,
+                )
+            }
+            Some(salt) => {
+                let address = create2_address(&origin, &salt, &input);
+                (
+                    executor.transact_create2(
+                        origin,
+                        value.raw(),
+                        input,
+                        salt,
+                        gas_limit,
+                        access_list,
+                    ),
+                    address,
+                )
+            }
+        };
 
         let used_gas = executor.used_gas();
         let status = match exit_reason.into_result(result.0.to_vec()) {
@@ -510,7 +762,25 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         };
 
         let (values, logs) = executor.into_state().deconstruct();
-        let logs = filter_promises_from_logs(handler, logs);
+        let values: Vec<_> = values.into_iter().collect();
+        if let Err(e) = check_system_address_collision(&self.current_account_id, &values)
+            .and_then(|_| check_frozen_transfers(&self.io, &values))
+        {
+            increment_nonce(&mut self.io, &origin);
+            return Err(e.with_gas_used(used_gas));
+        }
+        let logs = match filter_promises_from_logs(
+            &mut self.io,
+            &self.current_account_id,
+            handler,
+            logs,
+        ) {
+            Ok(logs) => logs,
+            Err(e) => {
+                increment_nonce(&mut self.io, &origin);
+                return Err(e.with_gas_used(used_gas));
+            }
+        };
 
         self.apply(values, Vec::<Log>::new(), true);
 
@@ -556,6 +826,73 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         }
     }
 
+    /// Executes a bundle of ERC-4337 user operations with the EntryPoint. The origin, the
+    /// relayer submitting the bundle, is the beneficiary of the fees of the operations. It
+    /// prepays the gas limits of the operations at the minimum gas price, and the gas used is
+    /// settled as the one of a transaction it relayed.
+    pub fn handle_ops<P: PromiseHandler>(
+        &mut self,
+        args: HandleOpsArgs,
+        handler: &mut P,
+    ) -> EngineResult<SubmitResult> {
+        let origin = self.origin();
+        let ops_gas_limit =
+            entry_point::bundle_gas_limit(&args.ops).ok_or(EngineErrorKind::InvalidUserOps)?;
+        let input = entry_point::handle_ops_input(&args.ops, &origin);
+        let intrinsic_gas = input.iter().fold(CONFIG.gas_transaction_call, |gas, byte| {
+            gas + if *byte == 0 {
+                CONFIG.gas_transaction_zero_data
+            } else {
+                CONFIG.gas_transaction_non_zero_data
+            }
+        });
+        let gas_limit: u64 = ops_gas_limit
+            .checked_add(intrinsic_gas.into())
+            .and_then(|gas_limit| gas_limit.try_into().ok())
+            .ok_or(EngineErrorKind::GasOverflow)?;
+
+        let gas_price = gas_price::min_gas_price(&self.io, self.env.block_timestamp().secs());
+        let prepaid_amount = U256::from(gas_limit)
+            .checked_mul(gas_price)
+            .map(Wei::new)
+            .ok_or(EngineErrorKind::GasPayment(
+                GasPaymentError::EthAmountOverflow,
+            ))?;
+        let new_balance = get_balance(&self.io, &origin)
+            .checked_sub(prepaid_amount)
+            .ok_or(EngineErrorKind::GasPayment(GasPaymentError::OutOfFund))?;
+        set_balance(&mut self.io, &origin, &new_balance);
+        let gas_result = GasPaymentResult {
+            prepaid_amount,
+            effective_gas_price: gas_price,
+            priority_fee_per_gas: gas_price.saturating_sub(block_base_fee_per_gas()),
+        };
+
+        self.gas_price = gas_price;
+        let result = self.call(
+            origin,
+            entry_point::ENTRY_POINT_ADDRESS,
+            Wei::zero(),
+            input,
+            gas_limit,
+            Vec::new(),
+            handler,
+        );
+
+        let gas_used = match &result {
+            Ok(submit_result) => submit_result.gas_used,
+            Err(engine_err) => engine_err.gas_used,
+        };
+        refund_unused_gas(&mut self.io, &origin, gas_used, gas_result, &origin).map_err(|e| {
+            EngineError {
+                gas_used,
+                kind: EngineErrorKind::GasPayment(e),
+            }
+        })?;
+
+        result
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn call<P: PromiseHandler>(
         &mut self,
@@ -567,10 +904,46 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         access_list: Vec<(Address, Vec<H256>)>, // See EIP-2930
         handler: &mut P,
     ) -> EngineResult<SubmitResult> {
+        self.call_checked(
+            origin,
+            contract,
+            value,
+            input,
+            gas_limit,
+            access_list,
+            handler,
+            |_| Ok(()),
+        )
+    }
+
+    /// Same as `call`, with a check of the status of the call before its state is applied.
+    /// The call fails without any effect but the increment of the nonce if the check fails.
+    #[allow(clippy::too_many_arguments)]
+    fn call_checked<P: PromiseHandler, F>(
+        &mut self,
+        origin: Address,
+        contract: Address,
+        value: Wei,
+        input: Vec<u8>,
+        gas_limit: u64,
+        access_list: Vec<(Address, Vec<H256>)>,
+        handler: &mut P,
+        check: F,
+    ) -> EngineResult<SubmitResult>
+    where
+        F: FnOnce(&TransactionStatus) -> Result<(), EngineErrorKind>,
+    {
+        if freeze::is_address_frozen(&self.io, &origin) {
+            return Err(EngineErrorKind::AccountFrozen.into());
+        }
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            self.env,
+            get_evm_limits(&self.io),
+            get_paused_precompiles(&self.io),
+            get_host_functions(&self.io),
+            self.io,
         );
         let mut executor = executor_params.make_executor(self);
         let (exit_reason, result) =
@@ -586,7 +959,26 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         };
 
         let (values, logs) = executor.into_state().deconstruct();
-        let logs = filter_promises_from_logs(handler, logs);
+        let values: Vec<_> = values.into_iter().collect();
+        if let Err(e) = check(&status)
+            .and_then(|_| check_system_address_collision(&self.current_account_id, &values))
+            .and_then(|_| check_frozen_transfers(&self.io, &values))
+        {
+            increment_nonce(&mut self.io, &origin);
+            return Err(e.with_gas_used(used_gas));
+        }
+        let logs = match filter_promises_from_logs(
+            &mut self.io,
+            &self.current_account_id,
+            handler,
+            logs,
+        ) {
+            Ok(logs) => logs,
+            Err(e) => {
+                increment_nonce(&mut self.io, &origin);
+                return Err(e.with_gas_used(used_gas));
+            }
+        };
 
         // There is no way to return the logs to the NEAR log method as it only
         // allows a return of UTF-8 strings.
@@ -613,7 +1005,11 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         let executor_params = StackExecutorParams::new(
             gas_limit,
             self.current_account_id.clone(),
-            self.env.random_seed(),
+            self.env,
+            get_evm_limits(&self.io),
+            get_paused_precompiles(&self.io),
+            get_host_functions(&self.io),
+            self.io,
         );
         let mut executor = executor_params.make_executor(self);
         let (status, result) =
@@ -621,20 +1017,23 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         status.into_result(result)
     }
 
-    fn relayer_key(account_id: &[u8]) -> Vec<u8> {
-        bytes_to_key(KeyPrefix::RelayerEvmAddressMap, account_id)
-    }
-
     pub fn register_relayer(&mut self, account_id: &[u8], evm_address: Address) {
-        let key = Self::relayer_key(account_id);
+        let key = relayer_key(account_id);
         self.io.write_storage(&key, evm_address.as_bytes());
     }
 
     pub fn get_relayer(&self, account_id: &[u8]) -> Option<Address> {
-        let key = Self::relayer_key(account_id);
-        self.io
-            .read_storage(&key)
-            .map(|v| Address::from_slice(&v.to_vec()))
+        get_relayer(&self.io, account_id)
+    }
+
+    /// Height of the NEAR block the engine executes in.
+    pub fn block_height(&self) -> u64 {
+        self.env.block_height()
+    }
+
+    /// Address of the engine in the EVM, the admin of the bridged tokens.
+    pub fn admin_address(&self) -> Address {
+        current_address(&self.current_account_id)
     }
 
     pub fn register_token(
@@ -680,6 +1079,68 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
         )
     }
 
+    /// Swaps ETH held by the admin address for the base token of the silo along the DEX route,
+    /// the base token being sent to the recipient. Returns the amount of the base token
+    /// received, `None` if the swap failed, in which case the ETH is still held by the admin
+    /// address. A swap whose amount received can't be decoded is reverted.
+    pub fn swap_eth_for_base_token<P: PromiseHandler>(
+        &mut self,
+        route: &FeeConversionRoute,
+        recipient: Address,
+        amount: Wei,
+        handler: &mut P,
+    ) -> Option<U256> {
+        // A call to an address without code would succeed and keep the ETH.
+        let router = Address(route.router);
+        if get_code_size(&self.io, &router) == 0 {
+            return None;
+        }
+        let min_amount_out = amount
+            .raw()
+            .checked_mul(U256::from_big_endian(&route.min_amount_out_per_eth))?
+            / U256::exp10(ETH_DECIMALS as usize);
+        let path = route
+            .path
+            .iter()
+            .map(|token| ethabi::Token::Address(Address(*token)))
+            .collect();
+        let tail = ethabi::encode(&[
+            ethabi::Token::Uint(min_amount_out),
+            ethabi::Token::Array(path),
+            ethabi::Token::Address(recipient),
+            ethabi::Token::Uint(U256::MAX),
+        ]);
+        let result = self
+            .call_checked(
+                self.admin_address(),
+                router,
+                amount,
+                [SWAP_EXACT_ETH_FOR_TOKENS_SELECTOR, tail.as_slice()].concat(),
+                route.gas_limit,
+                Vec::new(),
+                handler,
+                |status| match status {
+                    TransactionStatus::Succeed(output)
+                        if decode_swap_amount_out(output).is_none() =>
+                    {
+                        Err(EngineErrorKind::InvalidSwapOutput)
+                    }
+                    _ => Ok(()),
+                },
+            )
+            .ok()?;
+        // The swap isn't part of an EVM transaction, its logs (e.g. the transfers of the
+        // tokens) are published like the logs of the engine.
+        let block_height = self.block_height();
+        for log in result.logs {
+            engine_logs::emit(&mut self.io, block_height, log);
+        }
+        match result.status {
+            TransactionStatus::Succeed(output) => decode_swap_amount_out(&output),
+            _ => None,
+        }
+    }
+
     /// Mint tokens for recipient on a particular ERC20 token
     /// This function should return the amount of tokens unused,
     /// which will be always all (<amount>) if there is any problem
@@ -740,6 +1201,14 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
             self.io
         ));
 
+        // The deposits of a token whose supplies diverged are refunded, it is paused by the
+        // check so that its exits fail too.
+        assert_or_finish!(
+            token_supply::reconcile(self.io, self.env, token),
+            output_on_fail,
+            self.io
+        );
+
         if fee != U256::from(0) {
             let relayer_address = unwrap_res_or_finish!(
                 self.get_relayer(relayer_account_id.as_bytes()).ok_or(()),
@@ -808,11 +1277,18 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
                         kind: EngineErrorKind::EvmError(ExitError::CallTooDeep),
                         gas_used: submit_result.gas_used,
                     }),
+                    TransactionStatus::MemoryLimit => Err(EngineError {
+                        kind: EngineErrorKind::EvmError(ExitError::Other(
+                            crate::prelude::Cow::Borrowed("ERR_MEMORY_LIMIT"),
+                        )),
+                        gas_used: submit_result.gas_used,
+                    }),
                 }
             }),
             output_on_fail,
             self.io
         );
+        token_supply::record_mint(&mut self.io, token, args.amount);
 
         // TODO(marX)
         // Everything succeed so return "0"
@@ -820,25 +1296,68 @@ impl<'env, I: IO + Copy, E: Env> Engine<'env, I, E> {
     }
 }
 
-pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
-    mut io: I,
+/// A transaction which passed all the checks of `submit` done before modifying the state.
+struct ValidatedTransaction {
+    transaction: NormalizedEthTransaction,
+    sender: Address,
+    transaction_hash: H256,
+}
+
+/// Rejects the transactions signed for another chain (EIP-155), and the transactions signed
+/// for no chain unless they are allowed.
+fn validate_chain_id<I: IO>(
+    io: &I,
+    transaction: &NormalizedEthTransaction,
+    state: &EngineState,
+) -> Result<(), EngineErrorKind> {
+    match transaction.chain_id {
+        Some(chain_id) if U256::from(chain_id) != U256::from(state.chain_id) => {
+            Err(EngineErrorKind::InvalidChainId)
+        }
+        None if !is_unprotected_txs_allowed(io) => Err(EngineErrorKind::UnprotectedTransaction),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the transaction without modifying the state: duplicate, chain ID, signature,
+/// nonce, intrinsic gas and priority fee.
+fn validate_transaction<I: IO, E: Env>(
+    io: &I,
     env: &E,
     transaction_bytes: &[u8],
-    state: EngineState,
-    current_account_id: AccountId,
-    relayer_address: Address,
-    handler: &mut P,
-) -> EngineResult<SubmitResult> {
+    state: &EngineState,
+    duplicate_submit_window: u64,
+) -> EngineResult<ValidatedTransaction> {
+    let validated =
+        recover_transaction(io, env, transaction_bytes, state, duplicate_submit_window)?;
+    check_transaction(io, env, &validated)?;
+    Ok(validated)
+}
+
+/// Checks of `validate_transaction` up to the recovery of the signer: duplicate, chain ID
+/// and signature.
+fn recover_transaction<I: IO, E: Env>(
+    io: &I,
+    env: &E,
+    transaction_bytes: &[u8],
+    state: &EngineState,
+    duplicate_submit_window: u64,
+) -> EngineResult<ValidatedTransaction> {
+    let transaction_hash = sdk::keccak(transaction_bytes);
+    if duplicate_submit_window > 0 {
+        check_duplicate_submit(
+            io,
+            &transaction_hash,
+            env.block_height(),
+            duplicate_submit_window,
+        )?;
+    }
+
     let transaction: NormalizedEthTransaction = EthTransactionKind::try_from(transaction_bytes)
         .map_err(EngineErrorKind::FailedTransactionParse)?
         .into();
 
-    // Validate the chain ID, if provided inside the signature:
-    if let Some(chain_id) = transaction.chain_id {
-        if U256::from(chain_id) != U256::from(state.chain_id) {
-            return Err(EngineErrorKind::InvalidChainId.into());
-        }
-    }
+    validate_chain_id(io, &transaction, state)?;
 
     // Retrieve the signer of the transaction:
     let sender = transaction
@@ -847,7 +1366,22 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
 
     sdk::log!(crate::prelude::format!("signer_address {:?}", sender).as_str());
 
-    check_nonce(&io, &sender, &transaction.nonce)?;
+    Ok(ValidatedTransaction {
+        transaction,
+        sender,
+        transaction_hash,
+    })
+}
+
+/// Checks of `validate_transaction` once the signer is known: nonce, intrinsic gas and
+/// priority fee.
+fn check_transaction<I: IO, E: Env>(
+    io: &I,
+    env: &E,
+    validated: &ValidatedTransaction,
+) -> EngineResult<()> {
+    let transaction = &validated.transaction;
+    check_nonce(io, &validated.sender, &transaction.nonce)?;
 
     // Check intrinsic gas is covered by transaction gas limit
     match transaction.intrinsic_gas(crate::engine::CONFIG) {
@@ -864,7 +1398,36 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
     if transaction.max_priority_fee_per_gas > transaction.max_fee_per_gas {
         return Err(EngineErrorKind::MaxPriorityGasFeeTooLarge.into());
     }
+    // The transactions with a zero gas price are paid for by the relayers.
+    if !transaction.max_fee_per_gas.is_zero()
+        && transaction.max_fee_per_gas < block_base_fee_per_gas()
+    {
+        return Err(EngineErrorKind::MaxGasFeeTooLow.into());
+    }
+    check_gas_price(io, env, transaction)?;
+    Ok(())
+}
 
+#[allow(clippy::too_many_arguments)]
+fn execute_transaction<I: IO + Copy, E: Env, P: PromiseHandler>(
+    mut io: I,
+    env: &E,
+    validated: ValidatedTransaction,
+    state: EngineState,
+    current_account_id: AccountId,
+    relayer_address: Address,
+    duplicate_submit_window: u64,
+    handler: &mut P,
+) -> EngineResult<SubmitResult> {
+    let ValidatedTransaction {
+        transaction,
+        sender,
+        transaction_hash,
+    } = validated;
+
+    if !is_chain_id_locked(&io) {
+        storage_key::write_config(&mut io, ConfigKey::ChainIdLocked, &[], &[1]);
+    }
     let mut engine = Engine::new_with_state(state, sender, current_account_id, io, env);
     let prepaid_amount = match engine.charge_gas(&sender, &transaction) {
         Ok(gas_result) => gas_result,
@@ -922,53 +1485,704 @@ pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
         },
     )?;
 
-    // return result to user
-    result
-}
-
-/// There is one Aurora block per NEAR block height (note: when heights in NEAR are skipped
-/// they are interpreted as empty blocks on Aurora). The blockhash is derived from the height
-/// according to
-/// ```text
-/// block_hash = sha256(concat(
-///     BLOCK_HASH_PREFIX,
-///     block_height as u64,
-///     chain_id,
-///     engine_account_id,
-/// ))
-/// ```
-pub fn compute_block_hash(chain_id: [u8; 32], block_height: u64, account_id: &[u8]) -> H256 {
-    debug_assert_eq!(BLOCK_HASH_PREFIX_SIZE, mem::size_of_val(&BLOCK_HASH_PREFIX));
-    debug_assert_eq!(BLOCK_HEIGHT_SIZE, mem::size_of_val(&block_height));
-    debug_assert_eq!(CHAIN_ID_SIZE, mem::size_of_val(&chain_id));
-    let mut data = Vec::with_capacity(
-        BLOCK_HASH_PREFIX_SIZE + BLOCK_HEIGHT_SIZE + CHAIN_ID_SIZE + account_id.len(),
-    );
-    data.push(BLOCK_HASH_PREFIX);
-    data.extend_from_slice(&chain_id);
-    data.extend_from_slice(account_id);
-    data.extend_from_slice(&block_height.to_be_bytes());
-
-    sdk::sha256(&data)
-}
+    if result.is_ok() {
+        record_submitted_transaction(
+            &mut io,
+            &transaction_hash,
+            env.block_height(),
+            duplicate_submit_window,
+        );
+    }
 
-pub fn get_state<I: IO>(io: &I) -> Result<EngineState, EngineStateError> {
-    match io.read_storage(&bytes_to_key(KeyPrefix::Config, STATE_KEY)) {
-        None => Err(EngineStateError::NotFound),
-        Some(bytes) => EngineState::try_from_slice(&bytes.to_vec())
-            .map_err(|_| EngineStateError::DeserializationFailed),
+    if let Ok(submit_result) = &result {
+        if is_log_bloom_index_enabled(&io) {
+            record_logs_bloom(&mut io, env.block_height(), &submit_result.logs);
+        }
     }
-}
 
-/// Saves state into the storage.
-pub fn set_state<I: IO>(io: &mut I, state: EngineState) {
-    io.write_storage(
-        &bytes_to_key(KeyPrefix::Config, STATE_KEY),
-        &state.try_to_vec().expect("ERR_SER"),
-    );
+    // return result to user
+    result
 }
 
-pub fn refund_unused_gas<I: IO>(
+pub fn submit<I: IO + Copy, E: Env, P: PromiseHandler>(
+    io: I,
+    env: &E,
+    transaction_bytes: &[u8],
+    state: EngineState,
+    current_account_id: AccountId,
+    relayer_address: Address,
+    handler: &mut P,
+) -> EngineResult<SubmitResult> {
+    let duplicate_submit_window = get_duplicate_submit_window(&io);
+    let validated =
+        validate_transaction(&io, env, transaction_bytes, &state, duplicate_submit_window)?;
+    execute_transaction(
+        io,
+        env,
+        validated,
+        state,
+        current_account_id,
+        relayer_address,
+        duplicate_submit_window,
+        handler,
+    )
+}
+
+/// Submits the transactions of the batch one after the other, within the same receipt.
+///
+/// A transaction rejected by the checks done before modifying the state is reported as
+/// `Rejected` and the batch goes on, unless `stop_on_failure` is set: then the transactions
+/// following a rejected or unsuccessful one are `Skipped`. The transactions whose gas limit
+/// would exceed the remaining `gas_cap` of the batch are skipped as well, together with all the
+/// following ones. The `block_gas_cap` of the block attributes is shared by all the batches of
+/// a NEAR block and is enforced the same way. An error occurring once a transaction started to
+/// modify the state can't be reported without committing a partial transaction, so it fails
+/// the whole batch.
+pub fn submit_batch<I: IO + Copy, E: Env, P: PromiseHandler>(
+    io: I,
+    env: &E,
+    args: &SubmitBatchArgs,
+    state: EngineState,
+    current_account_id: AccountId,
+    relayer_address: Address,
+    handler: &mut P,
+) -> EngineResult<Vec<BatchTransactionResult>> {
+    let duplicate_submit_window = get_duplicate_submit_window(&io);
+    let mut results = Vec::with_capacity(args.transactions.len());
+    let block_gas_cap = get_block_attributes(&io).block_gas_cap;
+    let block_height = env.block_height();
+    let mut block_gas_used = match block_gas_cap {
+        Some(_) => get_block_gas_usage(&io)
+            .filter(|usage| usage.block_height == block_height)
+            .map(|usage| usage.gas_used)
+            .unwrap_or(0),
+        None => 0,
+    };
+    let mut total_gas_used: u64 = 0;
+    let mut stopped = false;
+
+    for transaction_bytes in args.transactions.iter() {
+        if stopped {
+            results.push(BatchTransactionResult::Skipped);
+            continue;
+        }
+        let validated = match validate_transaction(
+            &io,
+            env,
+            transaction_bytes,
+            &state,
+            duplicate_submit_window,
+        ) {
+            Ok(validated) => validated,
+            Err(e) => {
+                results.push(BatchTransactionResult::Rejected(e.kind.as_bytes().to_vec()));
+                stopped = args.stop_on_failure;
+                continue;
+            }
+        };
+        let exceeds_cap = |used: u64, cap: Option<u64>| match cap {
+            Some(cap) => U256::from(used)
+                .checked_add(validated.transaction.gas_limit)
+                .map(|total| total > U256::from(cap))
+                .unwrap_or(true),
+            None => false,
+        };
+        if exceeds_cap(total_gas_used, args.gas_cap) || exceeds_cap(block_gas_used, block_gas_cap) {
+            results.push(BatchTransactionResult::Skipped);
+            stopped = true;
+            continue;
+        }
+
+        let result = execute_transaction(
+            io,
+            env,
+            validated,
+            state.clone(),
+            current_account_id.clone(),
+            relayer_address,
+            duplicate_submit_window,
+            handler,
+        )?;
+        total_gas_used = total_gas_used.saturating_add(result.gas_used);
+        block_gas_used = block_gas_used.saturating_add(result.gas_used);
+        stopped = args.stop_on_failure && !result.status.is_ok();
+        results.push(BatchTransactionResult::Submitted(result));
+    }
+
+    if block_gas_cap.is_some() {
+        let mut io = io;
+        storage_key::write_config_borsh(
+            &mut io,
+            ConfigKey::BlockGasUsage,
+            &[],
+            &BlockGasUsage {
+                block_height,
+                gas_used: block_gas_used,
+            },
+        );
+    }
+    Ok(results)
+}
+
+/// Executes the meta-transaction signed by its sender, with the gas paid by the fee payer
+/// submitting it instead of the sender.
+///
+/// The signature of the meta-transaction was verified by its parsing. Its nonce is the one of the
+/// sender, which the execution increments as for `submit`. The fee address it signed, unless it
+/// is zero, is the only fee payer allowed to submit it. The fee payer prepays the gas limit at
+/// its gas price and the gas used is settled as the one of a transaction the fee payer relayed:
+/// the unused gas is refunded to it, the priority fees are split with the treasury.
+#[allow(clippy::too_many_arguments)]
+pub fn submit_with_fee_payer<I: IO + Copy, E: Env, P: PromiseHandler>(
+    mut io: I,
+    env: &E,
+    meta_call: InternalMetaCallArgs,
+    gas_limit: u64,
+    gas_price: U256,
+    state: EngineState,
+    current_account_id: AccountId,
+    fee_payer: Address,
+    handler: &mut P,
+) -> EngineResult<SubmitResult> {
+    check_nonce(&io, &meta_call.sender, &meta_call.nonce)?;
+    if !meta_call.fee_address.is_zero() && meta_call.fee_address != fee_payer {
+        return Err(EngineErrorKind::FeePayerMismatch.into());
+    }
+    // The prepaid gas is a transfer out of the fee payer.
+    if freeze::is_address_frozen(&io, &fee_payer) {
+        return Err(EngineErrorKind::AccountFrozen.into());
+    }
+    if gas_price < gas_price::min_gas_price(&io, env.block_timestamp().secs()) {
+        return Err(EngineErrorKind::GasPriceTooLow.into());
+    }
+
+    let prepaid_amount = U256::from(gas_limit)
+        .checked_mul(gas_price)
+        .map(Wei::new)
+        .ok_or(EngineErrorKind::GasPayment(
+            GasPaymentError::EthAmountOverflow,
+        ))?;
+    let new_balance = get_balance(&io, &fee_payer)
+        .checked_sub(prepaid_amount)
+        .ok_or(EngineErrorKind::GasPayment(GasPaymentError::OutOfFund))?;
+    set_balance(&mut io, &fee_payer, &new_balance);
+    let gas_result = GasPaymentResult {
+        prepaid_amount,
+        effective_gas_price: gas_price,
+        priority_fee_per_gas: gas_price.saturating_sub(block_base_fee_per_gas()),
+    };
+
+    let mut engine = Engine::new_with_state(state, meta_call.sender, current_account_id, io, env);
+    engine.gas_price = gas_price;
+    let result = engine.call(
+        meta_call.sender,
+        meta_call.contract_address,
+        meta_call.value,
+        meta_call.input,
+        gas_limit,
+        Vec::new(),
+        handler,
+    );
+
+    let gas_used = match &result {
+        Ok(submit_result) => submit_result.gas_used,
+        Err(engine_err) => engine_err.gas_used,
+    };
+    refund_unused_gas(&mut io, &fee_payer, gas_used, gas_result, &fee_payer).map_err(|e| {
+        EngineError {
+            gas_used,
+            kind: EngineErrorKind::GasPayment(e),
+        }
+    })?;
+
+    result
+}
+
+/// Simulates the `submit` of the transaction without committing any state change.
+/// The same checks as in `submit` are done on the transaction (chain ID, signature, nonce,
+/// intrinsic gas, balance) and a failed check is reported as the `error` of the result,
+/// otherwise the transaction is executed and its would-be status, logs and fee are reported.
+/// Promises created by the exit precompiles are not scheduled.
+pub fn dry_run_submit<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    transaction_bytes: &[u8],
+    state: EngineState,
+    current_account_id: AccountId,
+) -> DryRunSubmitResult {
+    let mut report = DryRunSubmitResult::default();
+    if let Err(e) = dry_run_submit_inner(
+        io,
+        env,
+        transaction_bytes,
+        state,
+        current_account_id,
+        &mut report,
+    ) {
+        report.gas_used = e.gas_used;
+        report.error = Some(e.kind.as_bytes().to_vec());
+    }
+    report
+}
+
+fn dry_run_submit_inner<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    transaction_bytes: &[u8],
+    state: EngineState,
+    current_account_id: AccountId,
+    report: &mut DryRunSubmitResult,
+) -> EngineResult<()> {
+    let validated = recover_transaction(
+        &io,
+        env,
+        transaction_bytes,
+        &state,
+        get_duplicate_submit_window(&io),
+    )?;
+    report.sender = Some(validated.sender.0);
+    check_transaction(&io, env, &validated)?;
+    let ValidatedTransaction {
+        transaction,
+        sender,
+        ..
+    } = validated;
+
+    if freeze::is_address_frozen(&io, &sender) {
+        return Err(EngineErrorKind::AccountFrozen.into());
+    }
+
+    let mut engine = Engine::new_with_state(state, sender, current_account_id, io, env);
+
+    // Same computation as in `Engine::charge_gas`, but the balance is left untouched.
+    let effective_gas_price = effective_gas_price(&transaction);
+    let prepaid_amount = transaction
+        .gas_limit
+        .checked_mul(effective_gas_price)
+        .ok_or(EngineErrorKind::GasPayment(
+            GasPaymentError::EthAmountOverflow,
+        ))?;
+    report.effective_gas_price = u256_to_arr(&effective_gas_price);
+
+    // The prepaid gas is not deducted from the balance of the sender during the simulation,
+    // so the value transfer must be checked against what would be left of it.
+    let balance_after_prepaid = match get_balance(&io, &sender).raw().checked_sub(prepaid_amount) {
+        Some(balance) => balance,
+        None => {
+            report.status = Some(TransactionStatus::OutOfFund);
+            return Ok(());
+        }
+    };
+    if balance_after_prepaid < transaction.value.raw() {
+        report.status = Some(TransactionStatus::OutOfFund);
+        return Ok(());
+    }
+    engine.gas_price = effective_gas_price;
+
+    let gas_limit: u64 = transaction
+        .gas_limit
+        .try_into()
+        .map_err(|_| EngineErrorKind::GasOverflow)?;
+    let access_list = transaction
+        .access_list
+        .into_iter()
+        .map(|a| (a.address, a.storage_keys))
+        .collect();
+    let executor_params = StackExecutorParams::new(
+        gas_limit,
+        engine.current_account_id.clone(),
+        env,
+        get_evm_limits(&engine.io),
+        get_paused_precompiles(&engine.io),
+        get_host_functions(&engine.io),
+        engine.io,
+    );
+    let mut executor = executor_params.make_executor(&engine);
+    let (exit_reason, output) = match transaction.to {
+        Some(receiver) => executor.transact_call(
+            sender,
+            receiver,
+            transaction.value.raw(),
+            transaction.data,
+            gas_limit,
+            access_list,
+        ),
+        None => {
+            let address = executor.create_address(CreateScheme::Legacy { caller: sender });
+            let exit_reason = executor.transact_create(
+                sender,
+                transaction.value.raw(),
+                transaction.data,
+                gas_limit,
+                access_list,
+            );
+            (exit_reason, address.0.to_vec())
+        }
+    };
+
+    let gas_used = executor.used_gas();
+    report.gas_used = gas_used;
+    report.fee = u256_to_arr(&(U256::from(gas_used) * effective_gas_price));
+    let status = exit_reason
+        .into_result(output)
+        .map_err(|e| e.with_gas_used(gas_used))?;
+
+    let (_, logs) = executor.into_state().deconstruct();
+    report.logs = logs
+        .into_iter()
+        .filter(|log| !is_promise_log(log))
+        .map(Into::into)
+        .collect();
+    report.status = Some(status);
+
+    Ok(())
+}
+
+/// There is one Aurora block per NEAR block height (note: when heights in NEAR are skipped
+/// they are interpreted as empty blocks on Aurora). The blockhash is derived from the height
+/// according to
+/// ```text
+/// block_hash = sha256(concat(
+///     BLOCK_HASH_PREFIX,
+///     block_height as u64,
+///     chain_id,
+///     engine_account_id,
+/// ))
+/// ```
+pub fn compute_block_hash(chain_id: [u8; 32], block_height: u64, account_id: &[u8]) -> H256 {
+    debug_assert_eq!(BLOCK_HASH_PREFIX_SIZE, mem::size_of_val(&BLOCK_HASH_PREFIX));
+    debug_assert_eq!(BLOCK_HEIGHT_SIZE, mem::size_of_val(&block_height));
+    debug_assert_eq!(CHAIN_ID_SIZE, mem::size_of_val(&chain_id));
+    let mut data = Vec::with_capacity(
+        BLOCK_HASH_PREFIX_SIZE + BLOCK_HEIGHT_SIZE + CHAIN_ID_SIZE + account_id.len(),
+    );
+    data.push(BLOCK_HASH_PREFIX);
+    data.extend_from_slice(&chain_id);
+    data.extend_from_slice(account_id);
+    data.extend_from_slice(&block_height.to_be_bytes());
+
+    sdk::sha256(&data)
+}
+
+pub fn get_state<I: IO>(io: &I) -> Result<EngineState, EngineStateError> {
+    io.load_state(&bytes_to_key(KeyPrefix::Config, STATE_KEY))
+        .map_err(|e| match e {
+            StateCorruption::MissingKey => EngineStateError::NotFound,
+            StateCorruption::WrongLength | StateCorruption::Borsh => {
+                EngineStateError::DeserializationFailed
+            }
+        })
+}
+
+/// Assembles the whole configuration of the engine into a single document.
+pub fn get_full_config<I: IO>(io: &I) -> Result<FullConfig, EngineStateError> {
+    let engine_state = get_state(io)?;
+    let metadata_hash =
+        connector::get_metadata(io).map(|metadata| sdk::sha256(&metadata.try_to_vec().unwrap()).0);
+
+    Ok(FullConfig {
+        version: FullConfig::VERSION,
+        engine_state,
+        evm_hard_fork: "london".to_string(),
+        eth_connector: connector::get_eth_connector(io),
+        paused_mask: connector::get_paused_mask(io),
+        metadata_hash,
+        duplicate_submit_window: get_duplicate_submit_window(io),
+        force_resolve_delay: connector::get_force_resolve_delay(io),
+        chain_id_locked: is_chain_id_locked(io),
+        unprotected_txs_allowed: is_unprotected_txs_allowed(io),
+        evm_limits: get_evm_limits(io),
+        block_attributes: get_block_attributes(io),
+        paused_precompiles: get_paused_precompiles(io),
+        host_functions: get_host_functions(io),
+        code_compression: compression::get_code_compression(io),
+        witness_size_limit: crate::witness::get_witness_size_limit(io),
+        log_bloom_index_enabled: is_log_bloom_index_enabled(io),
+        legacy_logs_enabled: events::is_legacy_logs_enabled(io),
+        storage_breakdown_enabled: crate::storage_breakdown::is_storage_breakdown_enabled(io),
+        callback_tickets_required: callback::is_tickets_required(io),
+        priority_fee_split: get_priority_fee_split(io),
+        scheduled_tasks: crate::scheduled_tasks::get_scheduled_tasks(io)
+            .into_iter()
+            .map(|status| status.task)
+            .collect(),
+        pending_owner: crate::access_control::get_pending_owner(io),
+        deposit_protocol_fee: connector::get_deposit_protocol_fee(io),
+        deposit_fee_currency: connector::get_deposit_fee_currency(io),
+        mint_circuit_breaker: connector::get_mint_circuit_breaker(io),
+        min_deposit: connector::get_min_deposit(io),
+        recipient_rules_hash: sdk::sha256(
+            &connector::get_recipient_rules(io).try_to_vec().unwrap(),
+        )
+        .0,
+        provers: connector::get_provers(io),
+        light_client: connector::get_light_client(io),
+        proof_cache_config: connector::get_proof_cache_config(io),
+        extra_custodians: connector::get_extra_custodians(io),
+    })
+}
+
+/// Slots of the state of the OpenZeppelin `ERC20`, which the token template inherits first.
+const ERC20_BALANCES_SLOT: u64 = 0;
+const ERC20_TOTAL_SUPPLY_SLOT: u64 = 2;
+/// Selector of `totalSupply()`.
+const ERC20_TOTAL_SUPPLY_SELECTOR: &[u8] = &[0x18, 0x16, 0x0d, 0xdd];
+/// Selector of the `swapExactETHForTokens(uint256,address[],address,uint256)` of the routers.
+const SWAP_EXACT_ETH_FOR_TOKENS_SELECTOR: &[u8] = &[0x7f, 0xf3, 0x6a, 0xb5];
+/// Selectors of `decimals()` and `symbol()`.
+const ERC20_DECIMALS_SELECTOR: &[u8] = &[0x31, 0x3c, 0xe5, 0x67];
+const ERC20_SYMBOL_SELECTOR: &[u8] = &[0x95, 0xd8, 0x9b, 0x41];
+
+/// Storage key of the balance of the address in a token deployed from the template.
+fn erc20_balance_key(address: &Address) -> H256 {
+    sdk::keccak(&ethabi::encode(&[
+        ethabi::Token::Address(*address),
+        ethabi::Token::Uint(ERC20_BALANCES_SLOT.into()),
+    ]))
+}
+
+/// Reads a value of the ERC-20 of a bridged NEP-141 token. The tokens deployed by
+/// `deploy_erc20_token` have the layout of the template of the engine, the value is read from
+/// their storage at the key without running the EVM. The others, registered with a contract
+/// deployed otherwise, are called with the input.
+fn read_erc20_value<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    erc20: Address,
+    key: H256,
+    input: Vec<u8>,
+) -> Result<U256, Erc20ReadError> {
+    let nep141 = nep141_erc20_map(io)
+        .lookup_right(&ERC20Address(erc20))
+        .ok_or(Erc20ReadError::NotBridged)?;
+    let current_account_id = env.current_account_id();
+    if erc20_deploy_address(&current_account_id, &nep141.0) == erc20 {
+        let generation = get_generation(&io, &erc20);
+        let value = get_storage(&io, &erc20, &key, generation);
+        return Ok(U256::from_big_endian(value.as_bytes()));
+    }
+
+    let origin = current_address(&current_account_id);
+    let engine = Engine::new(origin, current_account_id, io, env).map_err(Erc20ReadError::State)?;
+    match engine.view(origin, erc20, Wei::zero(), input, u64::MAX) {
+        Ok(TransactionStatus::Succeed(output)) if output.len() == 32 => {
+            Ok(U256::from_big_endian(&output))
+        }
+        Ok(status) => Err(Erc20ReadError::Failed(status)),
+        Err(e) => Err(Erc20ReadError::Engine(e)),
+    }
+}
+
+/// Balance of the address in the ERC-20 of a bridged NEP-141 token, read from the storage of
+/// the tokens deployed by the engine, asked with `balanceOf` to the others.
+pub fn get_erc20_balance<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    erc20: Address,
+    address: Address,
+) -> Result<U256, Erc20ReadError> {
+    let balance_of_input = [
+        ERC20_BALANCE_OF_SELECTOR,
+        &ethabi::encode(&[ethabi::Token::Address(address)]),
+    ]
+    .concat();
+    read_erc20_value(
+        io,
+        env,
+        erc20,
+        erc20_balance_key(&address),
+        balance_of_input,
+    )
+}
+
+/// Total supply of the ERC-20 of a bridged NEP-141 token, read as `get_erc20_balance`.
+pub fn get_erc20_total_supply<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    erc20: Address,
+) -> Result<U256, Erc20ReadError> {
+    let mut key = [0u8; 32];
+    U256::from(ERC20_TOTAL_SUPPLY_SLOT).to_big_endian(&mut key);
+    read_erc20_value(
+        io,
+        env,
+        erc20,
+        H256(key),
+        ERC20_TOTAL_SUPPLY_SELECTOR.to_vec(),
+    )
+}
+
+/// Returns the balances of the NEAR account in a single view: nETH on NEAR, ETH of the EVM
+/// address it is mapped to and the given NEP-141 tokens bridged as ERC-20s.
+pub fn get_balances<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    args: GetBalancesCallArgs,
+) -> Result<AccountBalances, EngineStateError> {
+    let address = sdk::types::near_account_to_evm_address(args.account_id.as_bytes());
+    let tokens = args
+        .tokens
+        .into_iter()
+        .filter_map(|nep141| {
+            let erc20 = get_erc20_from_nep141(&io, &nep141).ok()?;
+            let erc20: [u8; 20] = erc20.as_slice().try_into().ok()?;
+            let balance = match get_erc20_balance(io, env, Address(erc20), address) {
+                Ok(balance) => Some(u256_to_arr(&balance)),
+                Err(Erc20ReadError::State(e)) => return Some(Err(e)),
+                Err(_) => None,
+            };
+            Some(Ok(TokenBalance {
+                nep141,
+                erc20,
+                balance,
+            }))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AccountBalances {
+        address: address.0,
+        neth_balance: fungible_token::FungibleTokenOps::new(io).ft_balance_of(&args.account_id),
+        eth_balance: u256_to_arr(&get_balance(&io, &address).raw()),
+        tokens,
+    })
+}
+
+/// Output of a view of the ERC-20 of a bridged token, `None` if it fails.
+fn view_erc20<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    erc20: Address,
+    input: &[u8],
+) -> Option<Vec<u8>> {
+    let current_account_id = env.current_account_id();
+    let origin = current_address(&current_account_id);
+    let engine = Engine::new(origin, current_account_id, io, env).ok()?;
+    match engine.view(origin, erc20, Wei::zero(), input.to_vec(), u64::MAX) {
+        Ok(TransactionStatus::Succeed(output)) => Some(output),
+        _ => None,
+    }
+}
+
+/// Decimals and symbol of the ERC-20 of a bridged token, `None` if it doesn't implement the
+/// optional metadata functions of the standard.
+pub fn get_erc20_metadata<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    erc20: Address,
+) -> Option<(u8, String)> {
+    let decimals = view_erc20(io, env, erc20, ERC20_DECIMALS_SELECTOR)?;
+    if decimals.len() != 32 {
+        return None;
+    }
+    let decimals = U256::from_big_endian(&decimals);
+    if decimals > U256::from(u8::MAX) {
+        return None;
+    }
+    let symbol = view_erc20(io, env, erc20, ERC20_SYMBOL_SELECTOR)?;
+    match ethabi::decode(&[ethabi::ParamType::String], &symbol)
+        .ok()?
+        .pop()?
+    {
+        ethabi::Token::String(symbol) => Some((decimals.low_u32() as u8, symbol)),
+        _ => None,
+    }
+}
+
+/// Returns the balances of `get_balances` as JSON for the wallets displaying them. Each amount
+/// has its raw value, in the smallest unit as a decimal string, and its human-readable one
+/// with the decimals and the symbol of its token, `null` if the token has no metadata.
+pub fn get_balances_json<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    args: GetBalancesCallArgs,
+) -> Result<JsonValue, EngineStateError> {
+    let balances = get_balances(io, env, args)?;
+    let neth_metadata = connector::get_metadata(&io).unwrap_or_default();
+    let tokens = balances
+        .tokens
+        .into_iter()
+        .map(|token| {
+            let metadata = get_erc20_metadata(io, env, Address(token.erc20));
+            let mut kvs = BTreeMap::new();
+            kvs.insert(
+                "nep141".to_string(),
+                JsonValue::String(token.nep141.to_string()),
+            );
+            kvs.insert(
+                "erc20".to_string(),
+                JsonValue::String(hex::encode(token.erc20)),
+            );
+            kvs.insert(
+                "balance".to_string(),
+                token
+                    .balance
+                    .map(|balance| {
+                        amount_json(
+                            U256::from_big_endian(&balance),
+                            metadata
+                                .as_ref()
+                                .map(|(decimals, symbol)| (*decimals, symbol.as_str())),
+                        )
+                    })
+                    .unwrap_or(JsonValue::Null),
+            );
+            JsonValue::Object(kvs)
+        })
+        .collect();
+
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "address".to_string(),
+        JsonValue::String(hex::encode(balances.address)),
+    );
+    kvs.insert(
+        "neth_balance".to_string(),
+        amount_json(
+            U256::from(balances.neth_balance),
+            Some((neth_metadata.decimals, neth_metadata.symbol.as_str())),
+        ),
+    );
+    kvs.insert(
+        "eth_balance".to_string(),
+        amount_json(
+            U256::from_big_endian(&balances.eth_balance),
+            Some((ETH_DECIMALS, "ETH")),
+        ),
+    );
+    kvs.insert("tokens".to_string(), JsonValue::Array(tokens));
+    Ok(JsonValue::Object(kvs))
+}
+
+/// Raw and human-readable values of an amount of the token with the decimals and the symbol.
+fn amount_json(amount: U256, metadata: Option<(u8, &str)>) -> JsonValue {
+    let mut kvs = BTreeMap::new();
+    kvs.insert("raw".to_string(), JsonValue::String(amount.to_string()));
+    kvs.insert(
+        "decimals".to_string(),
+        metadata
+            .map(|(decimals, _)| JsonValue::U64(decimals.into()))
+            .unwrap_or(JsonValue::Null),
+    );
+    kvs.insert(
+        "formatted".to_string(),
+        metadata
+            .map(|(decimals, symbol)| {
+                JsonValue::String(format_amount_with_symbol(amount, decimals, symbol))
+            })
+            .unwrap_or(JsonValue::Null),
+    );
+    JsonValue::Object(kvs)
+}
+
+/// Saves state into the storage.
+pub fn set_state<I: IO>(io: &mut I, state: EngineState) {
+    io.write_storage(
+        &bytes_to_key(KeyPrefix::Config, STATE_KEY),
+        &state.try_to_vec().expect("ERR_SER"),
+    );
+}
+
+pub fn refund_unused_gas<I: IO>(
     io: &mut I,
     sender: &Address,
     gas_used: u64,
@@ -988,37 +2202,228 @@ pub fn refund_unused_gas<I: IO>(
 
     let spent_amount = gas_to_wei(gas_result.effective_gas_price)?;
     let reward_amount = gas_to_wei(gas_result.priority_fee_per_gas)?;
+    let (relayer_reward, treasury_reward) = split_priority_fee(io, reward_amount)?;
 
     let refund = gas_result
         .prepaid_amount
         .checked_sub(spent_amount)
         .ok_or(GasPaymentError::EthAmountOverflow)?;
+    // The part of the spent amount which is not rewarded to the relayer is burned.
+    let burned_amount = spent_amount
+        .checked_sub(reward_amount)
+        .ok_or(GasPaymentError::EthAmountOverflow)?;
 
     add_balance(io, sender, refund)?;
-    add_balance(io, relayer, reward_amount)?;
+    add_balance(io, relayer, relayer_reward)?;
+    if let Some((treasury, amount)) = treasury_reward {
+        add_balance(io, &treasury, amount)?;
+        if events::is_legacy_logs_enabled(io) {
+            sdk::log!(crate::prelude::format!(
+                "priority_fee relayer {} treasury {}",
+                relayer_reward,
+                amount
+            )
+            .as_str());
+        }
+    }
+    if !burned_amount.is_zero() {
+        add_balance(io, &BURN_ADDRESS, burned_amount)?;
+    }
+
+    sdk::log!(&events::gas_payment_log(&GasPayment {
+        gas_used,
+        gas_price: gas_result.effective_gas_price,
+        relayer: *relayer,
+        relayer_reward,
+        treasury_reward: treasury_reward
+            .map(|(_, amount)| amount)
+            .unwrap_or_else(Wei::zero),
+        refund,
+        burned: burned_amount,
+    }));
 
     Ok(())
 }
 
-/// Used to bridge NEP-141 tokens from NEAR to Aurora. On Aurora the NEP-141 becomes an ERC-20.
-pub fn deploy_erc20_token<I: IO + Copy, E: Env, P: PromiseHandler>(
-    args: DeployErc20TokenArgs,
-    io: I,
-    env: &E,
-    handler: &mut P,
-) -> Result<Address, DeployErc20Error> {
-    let current_account_id = env.current_account_id();
-    let erc20_admin_address = current_address(&current_account_id);
-    let mut engine = Engine::new(
-        aurora_engine_sdk::types::near_account_to_evm_address(
-            env.predecessor_account_id().as_bytes(),
-        ),
-        current_account_id,
-        io,
-        env,
-    )
-    .map_err(DeployErc20Error::State)?;
+/// Splits the priority fees of a transaction between the relayer and the treasury,
+/// if a split is configured. Everything goes to the relayer otherwise.
+fn split_priority_fee<I: IO>(
+    io: &I,
+    reward_amount: Wei,
+) -> Result<(Wei, Option<(Address, Wei)>), GasPaymentError> {
+    if reward_amount.is_zero() {
+        return Ok((reward_amount, None));
+    }
+    let split = match get_priority_fee_split(io) {
+        Some(split) => split,
+        None => return Ok((reward_amount, None)),
+    };
+    let treasury_amount = reward_amount
+        .raw()
+        .checked_mul(split.treasury_share.into())
+        .map(|amount| Wei::new(amount / U256::from(PRIORITY_FEE_SHARE_DENOMINATOR)))
+        .ok_or(GasPaymentError::EthAmountOverflow)?;
+    let relayer_amount = reward_amount
+        .checked_sub(treasury_amount)
+        .ok_or(GasPaymentError::EthAmountOverflow)?;
+    Ok((
+        relayer_amount,
+        Some((Address(split.treasury), treasury_amount)),
+    ))
+}
+
+/// Returns how the priority fees are split between the relayers and the treasury,
+/// `None` if the relayers receive all of them.
+pub fn get_priority_fee_split<I: IO>(io: &I) -> Option<PriorityFeeSplit> {
+    storage_key::read_config(io, ConfigKey::PriorityFeeSplit, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+pub fn set_priority_fee_split<I: IO>(
+    io: &mut I,
+    split: Option<PriorityFeeSplit>,
+) -> Result<(), InvalidPriorityFeeShare> {
+    match split {
+        Some(split) if split.treasury_share > PRIORITY_FEE_SHARE_DENOMINATOR => {
+            return Err(InvalidPriorityFeeShare);
+        }
+        Some(split) => {
+            storage_key::write_config_borsh(io, ConfigKey::PriorityFeeSplit, &[], &split);
+        }
+        None => {
+            storage_key::remove_config(io, ConfigKey::PriorityFeeSplit, &[]);
+        }
+    }
+    Ok(())
+}
+
+/// Returns the limits of the EVM, the ones of Ethereum unless set by the owner.
+pub fn get_evm_limits<I: IO>(io: &I) -> EvmLimits {
+    storage_key::read_config(io, ConfigKey::EvmLimits, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+/// The call depth must be between 1 and the maximum of Ethereum, the memory limit must
+/// allow at least one word.
+pub fn set_evm_limits<I: IO>(io: &mut I, limits: EvmLimits) -> Result<(), InvalidEvmLimits> {
+    if limits.max_call_depth == 0
+        || limits.max_call_depth > EvmLimits::MAX_CALL_DEPTH
+        || limits
+            .max_memory_bytes
+            .map(|bytes| bytes < 32)
+            .unwrap_or(false)
+    {
+        return Err(InvalidEvmLimits);
+    }
+    storage_key::write_config_borsh(io, ConfigKey::EvmLimits, &[], &limits);
+    Ok(())
+}
+
+/// Returns the block attributes set by the owner.
+pub fn get_block_attributes<I: IO>(io: &I) -> BlockAttributes {
+    storage_key::read_config(io, ConfigKey::BlockAttributes, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+/// A gas limit or a gas cap of zero would reject every transaction.
+pub fn set_block_attributes<I: IO>(
+    io: &mut I,
+    attributes: BlockAttributes,
+) -> Result<(), InvalidBlockAttributes> {
+    if attributes.gas_limit == Some(0) || attributes.block_gas_cap == Some(0) {
+        return Err(InvalidBlockAttributes);
+    }
+    if attributes == BlockAttributes::default() {
+        storage_key::remove_config(io, ConfigKey::BlockAttributes, &[]);
+    } else {
+        storage_key::write_config_borsh(io, ConfigKey::BlockAttributes, &[], &attributes);
+    }
+    sdk::log!(&crate::events::block_attributes_log(&attributes));
+    Ok(())
+}
+
+fn get_block_gas_usage<I: IO>(io: &I) -> Option<BlockGasUsage> {
+    storage_key::read_config(io, ConfigKey::BlockGasUsage, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+/// Precompiles which can be paused individually, with their flag.
+const PAUSABLE_PRECOMPILES: [(PausedPrecompilesMask, Address); 2] = [
+    (PAUSE_EXIT_TO_NEAR, ExitToNear::ADDRESS),
+    (PAUSE_EXIT_TO_ETHEREUM, ExitToEthereum::ADDRESS),
+];
+
+fn paused_precompile_addresses(paused_mask: PausedPrecompilesMask) -> Vec<Address> {
+    PAUSABLE_PRECOMPILES
+        .iter()
+        .filter(|(flag, _)| paused_mask & flag != 0)
+        .map(|(_, address)| *address)
+        .collect()
+}
+
+pub fn get_paused_precompiles<I: IO>(io: &I) -> PausedPrecompilesMask {
+    storage_key::read_config(io, ConfigKey::PausedPrecompiles, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(0)
+}
+
+/// Replaces the mask of the paused precompiles. Flags of precompiles which can't be paused
+/// are rejected, rather than giving the impression that they are.
+pub fn set_paused_precompiles<I: IO>(
+    io: &mut I,
+    paused_mask: PausedPrecompilesMask,
+) -> Result<(), UnknownPrecompileFlags> {
+    let known_flags = PAUSABLE_PRECOMPILES
+        .iter()
+        .fold(0, |mask, (flag, _)| mask | flag);
+    if paused_mask & !known_flags != 0 {
+        return Err(UnknownPrecompileFlags);
+    }
+    storage_key::write_config_borsh(io, ConfigKey::PausedPrecompiles, &[], &paused_mask);
+    Ok(())
+}
+
+/// Returns the host functions declared as available on the runtime of the deployment.
+pub fn get_host_functions<I: IO>(io: &I) -> HostFunctions {
+    storage_key::read_config(io, ConfigKey::HostFunctions, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+/// Declares the host functions available on the runtime. The precompiles only call the declared
+/// ones, so a missing host function can be declared before the runtime drops it.
+pub fn set_host_functions<I: IO>(io: &mut I, host_functions: HostFunctions) {
+    if host_functions == HostFunctions::default() {
+        storage_key::remove_config(io, ConfigKey::HostFunctions, &[]);
+    } else {
+        storage_key::write_config_borsh(io, ConfigKey::HostFunctions, &[], &host_functions);
+    }
+}
 
+/// Returns the total amounts of burned ETH.
+pub fn get_burned_total<I: IO>(io: &I) -> BurnedTotal {
+    BurnedTotal {
+        burned_on_aurora: get_balance(io, &BURN_ADDRESS).to_bytes(),
+        burned_on_near: fungible_token::get_burned_eth_on_near(io),
+    }
+}
+
+/// Used to bridge NEP-141 tokens from NEAR to Aurora. On Aurora the NEP-141 becomes an ERC-20.
+/// Address of the contract deployed with `CREATE2` by the origin, with the salt and the code.
+pub fn create2_address(origin: &Address, salt: &H256, code: &[u8]) -> Address {
+    let code_hash = sdk::keccak(code);
+    let mut data = Vec::with_capacity(85);
+    data.push(0xff);
+    data.extend_from_slice(origin.as_bytes());
+    data.extend_from_slice(salt.as_bytes());
+    data.extend_from_slice(code_hash.as_bytes());
+    Address::from_slice(&sdk::keccak(&data).as_bytes()[12..])
+}
+
+/// Creation code of the ERC-20 representation of the NEP-141 tokens, with the engine as admin.
+fn erc20_init_code(current_account_id: &AccountId) -> Vec<u8> {
     #[cfg(feature = "error_refund")]
     let erc20_contract = include_bytes!("../../etc/eth-contracts/res/EvmErc20V2.bin");
     #[cfg(not(feature = "error_refund"))]
@@ -1028,12 +2433,39 @@ pub fn deploy_erc20_token<I: IO + Copy, E: Env, P: PromiseHandler>(
         ethabi::Token::String("Empty".to_string()),
         ethabi::Token::String("EMPTY".to_string()),
         ethabi::Token::Uint(ethabi::Uint::from(0)),
-        ethabi::Token::Address(erc20_admin_address),
+        ethabi::Token::Address(current_address(current_account_id)),
     ]);
+    [erc20_contract, deploy_args.as_slice()].concat()
+}
+
+/// Address `deploy_erc20_token` deploys the ERC-20 of the NEP-141 token at. The token is
+/// deployed by the engine with `CREATE2`, salted with the hash of the NEP-141 account id, so
+/// the address only depends on the engine account and the NEP-141 account, and can be known
+/// before the token is deployed.
+pub fn erc20_deploy_address(current_account_id: &AccountId, nep141: &AccountId) -> Address {
+    create2_address(
+        &current_address(current_account_id),
+        &sdk::keccak(nep141.as_bytes()),
+        &erc20_init_code(current_account_id),
+    )
+}
 
-    let address = match Engine::deploy_code_with_input(
-        &mut engine,
-        (&[erc20_contract, deploy_args.as_slice()].concat()).to_vec(),
+pub fn deploy_erc20_token<I: IO + Copy, E: Env, P: PromiseHandler>(
+    args: DeployErc20TokenArgs,
+    mut io: I,
+    env: &E,
+    handler: &mut P,
+) -> Result<Address, DeployErc20Error> {
+    let current_account_id = env.current_account_id();
+    let erc20_admin_address = current_address(&current_account_id);
+    let init_code = erc20_init_code(&current_account_id);
+    let mut engine = Engine::new(erc20_admin_address, current_account_id, io, env)
+        .map_err(DeployErc20Error::State)?;
+
+    let address = match engine.deploy_code_create2(
+        erc20_admin_address,
+        init_code,
+        sdk::keccak(args.nep141.as_bytes()),
         handler,
     ) {
         Ok(result) => match result.status {
@@ -1045,29 +2477,61 @@ pub fn deploy_erc20_token<I: IO + Copy, E: Env, P: PromiseHandler>(
 
     sdk::log!(crate::prelude::format!("Deployed ERC-20 in Aurora at: {:#?}", address).as_str());
     engine
-        .register_token(address, args.nep141)
+        .register_token(address, args.nep141.clone())
         .map_err(DeployErc20Error::Register)?;
+    token_supply::init_locked(&mut io, &args.nep141);
 
     Ok(address)
 }
 
 pub fn set_code<I: IO>(io: &mut I, address: &Address, code: &[u8]) {
-    io.write_storage(&address_to_key(KeyPrefix::Code, address), code);
+    let key = address_to_key(KeyPrefix::Code, address);
+    let compression = compression::get_code_compression(io);
+    match compression
+        .unwrap_or(false)
+        .then(|| compression::compress_blob(code))
+        .flatten()
+    {
+        Some(blob) => {
+            io.write_storage(&key, &blob);
+            // The length of the code is kept aside, for EXTCODESIZE not to read the code.
+            storage_key::write_config(
+                io,
+                ConfigKey::CodeLength,
+                address.as_bytes(),
+                &(code.len() as u64).to_le_bytes(),
+            );
+        }
+        None => {
+            io.write_storage(&key, code);
+            // No length is stored before the compression is configured.
+            if compression.is_some() {
+                storage_key::remove_config(io, ConfigKey::CodeLength, address.as_bytes());
+            }
+        }
+    }
 }
 
 pub fn remove_code<I: IO>(io: &mut I, address: &Address) {
     io.remove_storage(&address_to_key(KeyPrefix::Code, address));
+    storage_key::remove_config(io, ConfigKey::CodeLength, address.as_bytes());
 }
 
 pub fn get_code<I: IO>(io: &I, address: &Address) -> Vec<u8> {
     io.read_storage(&address_to_key(KeyPrefix::Code, address))
-        .map(|s| s.to_vec())
+        .map(|s| compression::decode_blob(s.to_vec()))
         .unwrap_or_else(Vec::new)
 }
 
+/// Length of the code, read without the code itself. Only the code stored compressed has its
+/// length stored aside.
 pub fn get_code_size<I: IO>(io: &I, address: &Address) -> usize {
-    io.read_storage_len(&address_to_key(KeyPrefix::Code, address))
-        .unwrap_or(0)
+    storage_key::read_config_u64(io, ConfigKey::CodeLength, address.as_bytes())
+        .map(|len| len as usize)
+        .unwrap_or_else(|| {
+            io.read_storage_len(&address_to_key(KeyPrefix::Code, address))
+                .unwrap_or(0)
+        })
 }
 
 pub fn set_nonce<I: IO>(io: &mut I, address: &Address, nonce: &U256) {
@@ -1081,6 +2545,248 @@ pub fn remove_nonce<I: IO>(io: &mut I, address: &Address) {
     io.remove_storage(&address_to_key(KeyPrefix::Nonce, address));
 }
 
+/// Whether the transactions signed without a chain ID are accepted, the default.
+pub fn is_unprotected_txs_allowed<I: IO>(io: &I) -> bool {
+    storage_key::read_config(io, ConfigKey::UnprotectedTxs, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(true)
+}
+
+pub fn set_unprotected_txs_allowed<I: IO>(io: &mut I, allowed: bool) {
+    storage_key::write_config_borsh(io, ConfigKey::UnprotectedTxs, &[], &allowed);
+}
+
+/// Whether a transaction was executed, after which the chain ID can't change anymore.
+pub fn is_chain_id_locked<I: IO>(io: &I) -> bool {
+    storage_key::has_config(io, ConfigKey::ChainIdLocked, &[])
+}
+
+/// Sets the chain ID the transactions are signed for, until the first one is executed.
+pub fn set_chain_id<I: IO>(
+    io: &mut I,
+    mut state: EngineState,
+    chain_id: RawU256,
+) -> Result<(), ChainIdLockedError> {
+    if is_chain_id_locked(io) {
+        return Err(ChainIdLockedError);
+    }
+    state.chain_id = chain_id;
+    set_state(io, state);
+    Ok(())
+}
+
+/// Returns the number of blocks during which a transaction with the same hash is rejected
+/// after it was submitted. A value of zero disables the check.
+pub fn get_duplicate_submit_window<I: IO>(io: &I) -> u64 {
+    storage_key::read_config_u64(io, ConfigKey::DuplicateSubmitWindow, &[]).unwrap_or(0)
+}
+
+pub fn set_duplicate_submit_window<I: IO>(io: &mut I, window_blocks: u64) {
+    storage_key::write_config(
+        io,
+        ConfigKey::DuplicateSubmitWindow,
+        &[],
+        &window_blocks.to_le_bytes(),
+    );
+}
+
+/// Base fee per gas of the blocks, see EIP-1559.
+/// Currently, this returns 0 as there is no concept of a base fee at this
+/// time but this may change in the future.
+pub fn block_base_fee_per_gas() -> U256 {
+    U256::zero()
+}
+
+/// Gas price paid by the transaction, as charged by `Engine::charge_gas`.
+fn effective_gas_price(transaction: &NormalizedEthTransaction) -> U256 {
+    if transaction.max_fee_per_gas.is_zero() {
+        U256::zero()
+    } else {
+        transaction.effective_gas_price(block_base_fee_per_gas())
+    }
+}
+
+/// Checks that the gas price the transaction pays is at least the minimum gas price.
+fn check_gas_price<I: IO, E: Env>(
+    io: &I,
+    env: &E,
+    transaction: &NormalizedEthTransaction,
+) -> Result<(), EngineErrorKind> {
+    let min_gas_price = gas_price::min_gas_price(io, env.block_timestamp().secs());
+    if effective_gas_price(transaction) < min_gas_price {
+        return Err(EngineErrorKind::GasPriceTooLow);
+    }
+    Ok(())
+}
+
+/// Checks that a transaction with the given hash was not submitted during the last
+/// `window_blocks` blocks.
+pub fn check_duplicate_submit<I: IO>(
+    io: &I,
+    transaction_hash: &H256,
+    block_height: u64,
+    window_blocks: u64,
+) -> Result<(), EngineErrorKind> {
+    let key = bytes_to_key(KeyPrefix::SubmittedTxHash, transaction_hash.as_bytes());
+    match io.read_u64(&key) {
+        Ok(submitted_at) if submitted_at.saturating_add(window_blocks) >= block_height => {
+            Err(EngineErrorKind::AlreadySubmitted)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Records the hash of a transaction submitted at the given height, and prunes the hashes
+/// which fell out of the duplicate submit window.
+fn record_submitted_transaction<I: IO>(
+    io: &mut I,
+    transaction_hash: &H256,
+    block_height: u64,
+    window_blocks: u64,
+) {
+    let range = get_submitted_tx_queue_range(io);
+    let (head, mut tail) = range;
+    if window_blocks > 0 {
+        io.write_storage(
+            &bytes_to_key(KeyPrefix::SubmittedTxHash, transaction_hash.as_bytes()),
+            &block_height.to_le_bytes(),
+        );
+        storage_key::write_config_borsh(
+            io,
+            ConfigKey::SubmittedTxQueue,
+            &tail.to_le_bytes(),
+            &(transaction_hash.0, block_height),
+        );
+        tail += 1;
+    }
+    let head = prune_submitted_transactions(io, head, tail, block_height, window_blocks);
+    if (head, tail) != range {
+        storage_key::write_config_borsh(io, ConfigKey::SubmittedTxQueueRange, &[], &(head, tail));
+    }
+}
+
+/// Maximum number of hashes pruned by a submit. Since a submit records at most one hash, the
+/// hashes left over when the window shrinks are pruned by the following submits.
+const MAX_PRUNED_SUBMITTED_TXS: u64 = 2;
+
+/// Removes the oldest hashes of the queue of submitted transactions which are out of the
+/// window, and returns the new head of the queue.
+fn prune_submitted_transactions<I: IO>(
+    io: &mut I,
+    mut head: u64,
+    tail: u64,
+    block_height: u64,
+    window_blocks: u64,
+) -> u64 {
+    let end = tail.min(head.saturating_add(MAX_PRUNED_SUBMITTED_TXS));
+    while head < end {
+        let entry: Option<([u8; 32], u64)> =
+            storage_key::read_config(io, ConfigKey::SubmittedTxQueue, &head.to_le_bytes())
+                .and_then(|value| value.to_value().ok());
+        if let Some((hash, submitted_at)) = entry {
+            if submitted_at.saturating_add(window_blocks) >= block_height {
+                break;
+            }
+            let key = bytes_to_key(KeyPrefix::SubmittedTxHash, &hash);
+            // The hash is kept if it was submitted again since.
+            if io.read_u64(&key).ok() == Some(submitted_at) {
+                io.remove_storage(&key);
+            }
+            storage_key::remove_config(io, ConfigKey::SubmittedTxQueue, &head.to_le_bytes());
+        }
+        head += 1;
+    }
+    head
+}
+
+/// Returns the indices of the oldest and the next hash of the queue of submitted transactions.
+fn get_submitted_tx_queue_range<I: IO>(io: &I) -> (u64, u64) {
+    storage_key::read_config(io, ConfigKey::SubmittedTxQueueRange, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or((0, 0))
+}
+
+pub fn is_log_bloom_index_enabled<I: IO>(io: &I) -> bool {
+    storage_key::read_config(io, ConfigKey::LogBloomIndex, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(false)
+}
+
+pub fn set_log_bloom_index_enabled<I: IO>(io: &mut I, enabled: bool) {
+    storage_key::write_config_borsh(io, ConfigKey::LogBloomIndex, &[], &enabled);
+}
+
+/// Returns the bloom filter of all logs emitted by transactions submitted at the given height.
+pub fn get_logs_bloom<I: IO>(io: &I, block_height: u64) -> Option<LogBloom> {
+    io.read_storage(&bytes_to_key(
+        KeyPrefix::LogBloom,
+        &block_height.to_le_bytes(),
+    ))
+    .and_then(|value| value.to_value().ok())
+}
+
+pub fn remove_logs_bloom<I: IO>(io: &mut I, block_height: u64) {
+    io.remove_storage(&bytes_to_key(
+        KeyPrefix::LogBloom,
+        &block_height.to_le_bytes(),
+    ));
+}
+
+/// Height of the first bloom filter recorded, the blooms below it were never stored.
+pub fn get_first_logs_bloom_height<I: IO>(io: &I) -> Option<u64> {
+    storage_key::read_config_u64(io, ConfigKey::FirstLogsBloomHeight, &[])
+}
+
+/// Adds the addresses and topics of the logs to the bloom filter of the given height.
+pub fn record_logs_bloom<I: IO>(io: &mut I, block_height: u64, logs: &[ResultLog]) {
+    if logs.is_empty() {
+        return;
+    }
+    if get_first_logs_bloom_height(io).is_none() {
+        storage_key::write_config(
+            io,
+            ConfigKey::FirstLogsBloomHeight,
+            &[],
+            &block_height.to_le_bytes(),
+        );
+    }
+    let mut bloom = get_logs_bloom(io, block_height).unwrap_or_default();
+    for log in logs {
+        bloom.accrue(&log.address);
+        for topic in log.topics.iter() {
+            bloom.accrue(topic);
+        }
+    }
+    io.write_borsh(
+        &bytes_to_key(KeyPrefix::LogBloom, &block_height.to_le_bytes()),
+        &bloom,
+    );
+}
+
+pub fn get_abi_schema<I: IO>(io: &I, schema_id: u32) -> Option<Vec<AbiSchemaType>> {
+    io.read_storage(&bytes_to_key(
+        KeyPrefix::AbiSchema,
+        &schema_id.to_le_bytes(),
+    ))
+    .and_then(|value| value.to_value().ok())
+}
+
+/// Registers the schema used by the ABI utilities precompile.
+pub fn set_abi_schema<I: IO>(
+    io: &mut I,
+    schema_id: u32,
+    fields: &[AbiSchemaType],
+) -> Result<(), EngineErrorKind> {
+    if fields.is_empty() || !fields.iter().all(AbiSchemaType::is_valid) {
+        return Err(EngineErrorKind::InvalidAbiSchema);
+    }
+    io.write_borsh(
+        &bytes_to_key(KeyPrefix::AbiSchema, &schema_id.to_le_bytes()),
+        &fields.to_vec(),
+    );
+    Ok(())
+}
+
 /// Checks the nonce to ensure that the address matches the transaction
 /// nonce.
 #[inline]
@@ -1098,6 +2804,16 @@ pub fn check_nonce<I: IO>(
     Ok(())
 }
 
+fn relayer_key(account_id: &[u8]) -> Vec<u8> {
+    bytes_to_key(KeyPrefix::RelayerEvmAddressMap, account_id)
+}
+
+/// EVM address registered by the relayer account to receive its fees.
+pub fn get_relayer<I: IO>(io: &I, account_id: &[u8]) -> Option<Address> {
+    io.read_storage(&relayer_key(account_id))
+        .map(|v| Address::from_slice(&v.to_vec()))
+}
+
 pub fn get_nonce<I: IO>(io: &I, address: &Address) -> U256 {
     io.read_u256(&address_to_key(KeyPrefix::Nonce, address))
         .unwrap_or_else(|_| U256::zero())
@@ -1123,6 +2839,12 @@ pub fn get_erc20_from_nep141<I: IO>(
         .ok_or(GetErc20FromNep141Error::Nep141NotFound)
 }
 
+pub fn get_nep141_from_erc20<I: IO>(io: &I, erc20: &Address) -> Option<AccountId> {
+    let key = bytes_to_key(KeyPrefix::Erc20Nep141Map, erc20.as_bytes());
+    io.read_storage(&key)
+        .and_then(|v| AccountId::try_from(v.to_vec()).ok())
+}
+
 pub fn add_balance<I: IO>(
     io: &mut I,
     address: &Address,
@@ -1149,7 +2871,7 @@ pub fn remove_balance<I: IO + Copy>(io: &mut I, address: &Address) {
     // is implemented correctly then the total supply wll never underflow and (b) we are passing
     // in the balance directly so there will always be enough balance.
     EthConnectorContract::init_instance(*io)
-        .internal_remove_eth(address, balance)
+        .internal_remove_eth(address, NEP141Wei::new(balance))
         .unwrap();
     io.remove_storage(&address_to_key(KeyPrefix::Balance, address));
 }
@@ -1235,37 +2957,112 @@ fn remove_account<I: IO + Copy>(io: &mut I, address: &Address, generation: u32)
     remove_all_storage(io, address, generation);
 }
 
-fn filter_promises_from_logs<T, P>(handler: &mut P, logs: T) -> Vec<ResultLog>
+fn filter_promises_from_logs<I, T, P>(
+    io: &mut I,
+    current_account_id: &AccountId,
+    handler: &mut P,
+    logs: T,
+) -> Result<Vec<ResultLog>, EngineErrorKind>
 where
+    I: IO,
     T: IntoIterator<Item = Log>,
     P: PromiseHandler,
 {
-    logs.into_iter()
-        .filter_map(|log| {
-            if log.address == ExitToNear::ADDRESS || log.address == ExitToEthereum::ADDRESS {
-                if log.topics.is_empty() {
-                    if let Ok(promise) = PromiseArgs::try_from_slice(&log.data) {
-                        match promise {
-                            PromiseArgs::Create(promise) => schedule_promise(handler, &promise),
-                            PromiseArgs::Callback(promise) => {
-                                let base_id = schedule_promise(handler, &promise.base);
-                                schedule_promise_callback(handler, base_id, &promise.callback)
-                            }
-                        };
-                    }
-                    // do not pass on these "internal logs" to caller
-                    None
-                } else {
-                    // The exit precompiles do produce externally consumable logs in
-                    // addition to the promises. The external logs have a non-empty
-                    // `topics` field.
-                    Some(log.into())
+    let mut result_logs = Vec::new();
+    // The callbacks are all registered before any promise is scheduled, so a rejected callback
+    // leaves none of the promises of the transaction scheduled.
+    let mut promises = Vec::new();
+    for log in logs {
+        if !is_promise_log(&log) {
+            result_logs.push(log.into());
+            continue;
+        }
+        // do not pass on these "internal logs" to caller
+        if let Ok(promise) = PromiseArgs::try_from_slice(&log.data) {
+            match promise {
+                PromiseArgs::Create(promise) => promises.push((promise, None)),
+                PromiseArgs::Callback(promise) => {
+                    // The execution id is committed before any promise is created.
+                    let callback =
+                        callback::register_callback(io, current_account_id, &promise.callback)
+                            .map_err(EngineErrorKind::Callback)?;
+                    promises.push((promise.base, Some(callback)));
                 }
-            } else {
-                Some(log.into())
             }
-        })
-        .collect()
+        }
+    }
+    for (base, callback) in promises {
+        token_supply::record_exit_promise(io, current_account_id, &base);
+        let base_id = schedule_promise(handler, &base);
+        if let Some(callback) = callback {
+            schedule_promise_callback(handler, base_id, &callback);
+        }
+    }
+    Ok(result_logs)
+}
+
+/// Amount received by a swap, from the output of the router which returns the amounts of all
+/// the tokens of the path, the last one being the amount received.
+fn decode_swap_amount_out(output: &[u8]) -> Option<U256> {
+    ethabi::decode(
+        &[ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(
+            256,
+        )))],
+        output,
+    )
+    .ok()
+    .and_then(|tokens| match tokens.into_iter().next() {
+        Some(ethabi::Token::Array(amounts)) => amounts.into_iter().last(),
+        _ => None,
+    })
+    .and_then(|amount_out| amount_out.into_uint())
+}
+
+/// Fails if the outcome of a transaction deploys code at one of the system addresses.
+/// Checked before any promise of the transaction is scheduled.
+fn check_system_address_collision<I>(
+    current_account_id: &AccountId,
+    values: &[Apply<I>],
+) -> Result<(), EngineErrorKind> {
+    let collides = values.iter().any(|apply| match apply {
+        Apply::Modify {
+            address,
+            code: Some(_),
+            ..
+        } => system_addresses::is_system_address(current_account_id, address),
+        _ => false,
+    });
+    if collides {
+        Err(EngineErrorKind::SystemAddressCollision)
+    } else {
+        Ok(())
+    }
+}
+
+/// Fails if the outcome of a transaction lowers the balance of a frozen address, which the
+/// contracts called by the transaction may transfer value out of, or self-destruct.
+/// Checked before any promise of the transaction is scheduled.
+fn check_frozen_transfers<I: IO, S>(io: &I, values: &[Apply<S>]) -> Result<(), EngineErrorKind> {
+    let transfers_out = values.iter().any(|apply| match apply {
+        Apply::Modify { address, basic, .. } => {
+            freeze::is_address_frozen(io, address) && basic.balance < get_balance(io, address).raw()
+        }
+        Apply::Delete { address } => {
+            freeze::is_address_frozen(io, address) && !get_balance(io, address).is_zero()
+        }
+    });
+    if transfers_out {
+        Err(EngineErrorKind::FrozenTransfer)
+    } else {
+        Ok(())
+    }
+}
+
+/// The exit precompiles do produce externally consumable logs in addition to the
+/// promises. The external logs have a non-empty `topics` field.
+fn is_promise_log(log: &Log) -> bool {
+    (log.address == ExitToNear::ADDRESS || log.address == ExitToEthereum::ADDRESS)
+        && log.topics.is_empty()
 }
 
 fn schedule_promise<P: PromiseHandler>(handler: &mut P, promise: &PromiseCreateArgs) -> PromiseId {
@@ -1337,15 +3134,16 @@ impl<'env, I: IO + Copy, E: Env> evm::backend::Backend for Engine<'env, I, E> {
         U256::from(self.env.block_height())
     }
 
-    /// Returns a mocked coinbase which is the EVM address for the Aurora
-    /// account, being 0x4444588443C3a91288c5002483449Aba1054192b.
+    /// Returns the coinbase set by the owner, otherwise a mocked coinbase which is
+    /// the EVM address for the Aurora account, being
+    /// 0x4444588443C3a91288c5002483449Aba1054192b.
     ///
     /// See: https://doc.aurora.dev/develop/compat/evm#coinbase
     fn block_coinbase(&self) -> Address {
-        Address([
-            0x44, 0x44, 0x58, 0x84, 0x43, 0xC3, 0xa9, 0x12, 0x88, 0xc5, 0x00, 0x24, 0x83, 0x44,
-            0x9A, 0xba, 0x10, 0x54, 0x19, 0x2b,
-        ])
+        self.block_attributes
+            .coinbase
+            .map(Address)
+            .unwrap_or(DEFAULT_COINBASE)
     }
 
     /// Returns the current block timestamp.
@@ -1362,23 +3160,26 @@ impl<'env, I: IO + Copy, E: Env> evm::backend::Backend for Engine<'env, I, E> {
 
     /// Returns the current block gas limit.
     ///
-    /// Currently, this returns 0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
+    /// Unless set by the owner, this returns
+    /// 0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff
     /// as there isn't a gas limit alternative right now but this may change in
     /// the future.
     ///
     /// See: https://doc.aurora.dev/develop/compat/evm#gaslimit
     fn block_gas_limit(&self) -> U256 {
-        U256::max_value()
+        self.block_attributes
+            .gas_limit
+            .map(U256::from)
+            .unwrap_or_else(U256::max_value)
     }
 
     /// Returns the current base fee for the current block.
     ///
-    /// Currently, this returns 0 as there is no concept of a base fee at this
-    /// time but this may change in the future.
+    /// See `block_base_fee_per_gas`.
     ///
     /// TODO: doc.aurora.dev link
     fn block_base_fee_per_gas(&self) -> U256 {
-        U256::zero()
+        block_base_fee_per_gas()
     }
 
     /// Returns the states chain ID.
@@ -1504,4 +3305,228 @@ impl<'env, J: IO + Copy, E: Env> ApplyBackend for Engine<'env, J, E> {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_system_address_collision() {
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        let deploy_at = |address: Address| Apply::Modify {
+            address,
+            basic: Basic {
+                balance: U256::zero(),
+                nonce: U256::one(),
+            },
+            code: Some(vec![0x00]),
+            storage: Vec::<(H256, H256)>::new(),
+            reset_storage: false,
+        };
+
+        assert!(check_system_address_collision(
+            &current_account_id,
+            &[deploy_at(Address([0x42; 20]))]
+        )
+        .is_ok());
+        assert!(matches!(
+            check_system_address_collision(&current_account_id, &[deploy_at(ExitToNear::ADDRESS)]),
+            Err(EngineErrorKind::SystemAddressCollision)
+        ));
+        // Transferring to a system address doesn't deploy anything.
+        let transfer_to = Apply::Modify {
+            address: ExitToNear::ADDRESS,
+            basic: Basic {
+                balance: U256::one(),
+                nonce: U256::zero(),
+            },
+            code: None,
+            storage: Vec::<(H256, H256)>::new(),
+            reset_storage: false,
+        };
+        assert!(check_system_address_collision(&current_account_id, &[transfer_to]).is_ok());
+    }
+
+    #[test]
+    fn test_check_frozen_transfers() {
+        use crate::parameters::FreezeTarget;
+        use aurora_engine_sdk::memory;
+        use core::cell::RefCell;
+
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let frozen = Address([0x42; 20]);
+        set_balance(&mut io, &frozen, &Wei::new_u64(10));
+        freeze::freeze(&mut io, &FreezeTarget::Evm(frozen.0));
+        let modify = |address: Address, balance: u64| Apply::Modify {
+            address,
+            basic: Basic {
+                balance: U256::from(balance),
+                nonce: U256::zero(),
+            },
+            code: None,
+            storage: Vec::<(H256, H256)>::new(),
+            reset_storage: false,
+        };
+
+        // A frozen address can still receive value.
+        assert!(check_frozen_transfers(&io, &[modify(frozen, 15)]).is_ok());
+        assert!(check_frozen_transfers(&io, &[modify(Address([0x43; 20]), 0)]).is_ok());
+        assert_eq!(
+            check_frozen_transfers(&io, &[modify(frozen, 5)]),
+            Err(EngineErrorKind::FrozenTransfer)
+        );
+        let self_destruct = Apply::<Vec<(H256, H256)>>::Delete { address: frozen };
+        assert_eq!(
+            check_frozen_transfers(&io, &[self_destruct]),
+            Err(EngineErrorKind::FrozenTransfer)
+        );
+    }
+
+    #[test]
+    fn test_prune_submitted_transactions() {
+        use aurora_engine_sdk::memory;
+        use core::cell::RefCell;
+
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let hash = |i: u8| H256([i; 32]);
+        let is_recorded = |i: u8| {
+            storage
+                .borrow()
+                .kv_store
+                .contains_key(&bytes_to_key(KeyPrefix::SubmittedTxHash, &[i; 32]))
+        };
+
+        record_submitted_transaction(&mut io, &hash(1), 10, 5);
+        record_submitted_transaction(&mut io, &hash(2), 12, 5);
+        assert_eq!(
+            check_duplicate_submit(&io, &hash(1), 15, 5),
+            Err(EngineErrorKind::AlreadySubmitted)
+        );
+
+        // The hash submitted at height 10 is out of the window at height 16.
+        record_submitted_transaction(&mut io, &hash(3), 16, 5);
+        assert!(!is_recorded(1));
+        assert!(is_recorded(2));
+        assert!(is_recorded(3));
+        assert_eq!(get_submitted_tx_queue_range(&io), (1, 3));
+        assert!(check_duplicate_submit(&io, &hash(1), 16, 5).is_ok());
+
+        // Once the check is disabled, the remaining hashes are pruned by the next submits.
+        record_submitted_transaction(&mut io, &hash(4), 30, 0);
+        assert!(!is_recorded(2));
+        assert!(!is_recorded(3));
+        assert!(!is_recorded(4));
+        assert_eq!(get_submitted_tx_queue_range(&io), (3, 3));
+        let queue_key = crate::prelude::config_key(ConfigKey::SubmittedTxQueue, &[]);
+        assert!(!storage
+            .borrow()
+            .kv_store
+            .keys()
+            .any(|key| key.starts_with(&queue_key)));
+    }
+
+    #[test]
+    fn test_get_burned_total() {
+        use aurora_engine_sdk::memory;
+        use core::cell::RefCell;
+
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let sender = Address([0x42; 20]);
+        let relayer = Address([0x43; 20]);
+
+        // The base fee of the gas used is burned, the priority fee goes to the relayer.
+        let gas_result = || GasPaymentResult {
+            prepaid_amount: Wei::new_u64(1_000),
+            effective_gas_price: U256::from(3),
+            priority_fee_per_gas: U256::one(),
+        };
+        refund_unused_gas(&mut io, &sender, 100, gas_result(), &relayer).unwrap();
+        assert_eq!(get_balance(&io, &sender), Wei::new_u64(700));
+        assert_eq!(get_balance(&io, &relayer), Wei::new_u64(100));
+        assert_eq!(get_balance(&io, &BURN_ADDRESS), Wei::new_u64(200));
+
+        // The nETH of an account unregistered with `force` is burned.
+        let account_id: AccountId = "alice.near".parse().unwrap();
+        let mut ft = fungible_token::FungibleTokenOps::new(io);
+        ft.register_implicit_account(&account_id).unwrap();
+        ft.internal_deposit_eth_to_near(&account_id, NEP141Wei::new(50))
+            .unwrap();
+        ft.internal_storage_unregister(account_id, Some(true))
+            .unwrap();
+
+        let burned_total = get_burned_total(&io);
+        assert_eq!(burned_total.burned_on_aurora, Wei::new_u64(200).to_bytes());
+        assert_eq!(burned_total.burned_on_near, 50);
+
+        // The totals accumulate.
+        refund_unused_gas(&mut io, &sender, 100, gas_result(), &relayer).unwrap();
+        assert_eq!(
+            get_burned_total(&io).burned_on_aurora,
+            Wei::new_u64(400).to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_filter_promises_from_logs_rejected_callback() {
+        use crate::prelude::PromiseWithCallbackArgs;
+        use aurora_engine_sdk::memory;
+        use core::cell::RefCell;
+
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let mut handler = memory::PromiseTracker::default();
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        let promise_to = |target_account_id: &AccountId, method: &str| PromiseCreateArgs {
+            target_account_id: target_account_id.clone(),
+            method: method.into(),
+            args: Vec::new(),
+            attached_balance: 0,
+            attached_gas: 0,
+        };
+        let promise_log = |promise: PromiseArgs| Log {
+            address: ExitToNear::ADDRESS,
+            topics: Vec::new(),
+            data: promise.try_to_vec().unwrap(),
+        };
+        let token_id: AccountId = "token.near".parse().unwrap();
+        let logs = vec![
+            promise_log(PromiseArgs::Create(promise_to(&token_id, "ft_transfer"))),
+            // Nothing guards a callback of `submit` from being replayed.
+            promise_log(PromiseArgs::Callback(PromiseWithCallbackArgs {
+                base: promise_to(&token_id, "ft_transfer"),
+                callback: promise_to(&current_account_id, "submit"),
+            })),
+        ];
+
+        assert_eq!(
+            filter_promises_from_logs(&mut io, &current_account_id, &mut handler, logs).err(),
+            Some(EngineErrorKind::Callback(
+                callback::error::CallbackError::Unguarded
+            ))
+        );
+        // The promise logged before the rejected callback isn't scheduled either.
+        assert!(handler.scheduled_promises.is_empty());
+    }
+
+    #[test]
+    fn test_paused_precompile_addresses() {
+        assert!(paused_precompile_addresses(0).is_empty());
+        assert_eq!(
+            paused_precompile_addresses(PAUSE_EXIT_TO_ETHEREUM),
+            vec![ExitToEthereum::ADDRESS]
+        );
+        assert_eq!(
+            paused_precompile_addresses(PAUSE_EXIT_TO_NEAR | PAUSE_EXIT_TO_ETHEREUM),
+            vec![ExitToNear::ADDRESS, ExitToEthereum::ADDRESS]
+        );
+    }
+
+    #[test]
+    fn test_burn_address() {
+        assert_eq!(
+            BURN_ADDRESS,
+            aurora_engine_sdk::types::near_account_to_evm_address(b"burned")
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Registry of the EVM logs the engine emits for the bridge activity.
+//!
+//! The deposits of ETH to the EVM, the completion of the exits and the results of the NEAR
+//! calls they make are NEAR receipts, not EVM transactions, so the EVM indexers don't see them in the logs of any contract. The engine
+//! emits a log of its own for each of them at `ENGINE_LOGS_ADDRESS`, reserved for the purpose,
+//! encoded like a Solidity event: the first topic is the keccak of the signature of the event,
+//! followed by its indexed parameters, the other ones are ABI encoded in the data.
+//!
+//! A log is published as the `evm_log` event, for the relayer to append it to the EVM receipt
+//! of the NEAR receipt, and is added to the logs bloom of the block while the bloom index is
+//! enabled. The signatures below are part of the interface of the engine and never change, a
+//! new version of an event is a new signature. The logs of the EVM calls the engine makes
+//! outside of a transaction, e.g. the swap of the relayer fee of a deposit, are published the
+//! same way.
+use crate::engine;
+use crate::events;
+use crate::parameters::{EngineLogTopic, ResultLog};
+use crate::prelude::{sdk, vec, Address, ToString, Vec, H256, U256};
+use aurora_engine_sdk::io::IO;
+
+/// Address of the logs emitted by the engine.
+///
+/// Address: `0xf6504160a975dce179c4cd3ed6beef40c6278702`
+/// This address is computed as: `&keccak("engineLogs")[12..]`
+pub const ENGINE_LOGS_ADDRESS: Address =
+    aurora_engine_precompiles::make_address(0xf6504160, 0xa975dce179c4cd3ed6beef40c6278702);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineLog {
+    /// ETH credited to an EVM address by a deposit, its fee to the relayer.
+    /// `DepositCredited(address indexed recipient, uint256 amount, uint256 fee)`
+    DepositCredited,
+    /// Exit whose transfer to NEAR or Ethereum completed, or was refunded to the sender on
+    /// error. The ERC-20 is the zero address for an exit of ETH.
+    /// `ExitCompleted(address indexed sender, address indexed erc20, uint256 amount, bool refunded)`
+    ExitCompleted,
+    /// Result of the NEAR call made by an exit, reported by its callback whether the exit
+    /// completed or not. The output is the value returned by the call, empty on failure.
+    /// `XccResult(address indexed sender, bool success, bytes output)`
+    XccResult,
+}
+
+impl EngineLog {
+    pub const ALL: [EngineLog; 3] = [Self::DepositCredited, Self::ExitCompleted, Self::XccResult];
+
+    pub fn signature(self) -> &'static str {
+        match self {
+            Self::DepositCredited => "DepositCredited(address,uint256,uint256)",
+            Self::ExitCompleted => "ExitCompleted(address,address,uint256,bool)",
+            Self::XccResult => "XccResult(address,bool,bytes)",
+        }
+    }
+
+    pub fn topic(self) -> H256 {
+        sdk::keccak(self.signature().as_bytes())
+    }
+}
+
+/// Signatures and topics of all the logs, the registry indexers configure themselves from.
+pub fn engine_log_topics() -> Vec<EngineLogTopic> {
+    EngineLog::ALL
+        .iter()
+        .map(|log| EngineLogTopic {
+            signature: log.signature().to_string(),
+            topic: log.topic().0,
+        })
+        .collect()
+}
+
+fn address_topic(address: &Address) -> [u8; 32] {
+    H256::from(*address).0
+}
+
+pub fn deposit_credited(recipient: &Address, amount: u128, fee: u128) -> ResultLog {
+    ResultLog {
+        address: ENGINE_LOGS_ADDRESS.0,
+        topics: vec![
+            EngineLog::DepositCredited.topic().0,
+            address_topic(recipient),
+        ],
+        data: ethabi::encode(&[
+            ethabi::Token::Uint(amount.into()),
+            ethabi::Token::Uint(fee.into()),
+        ]),
+    }
+}
+
+pub fn exit_completed(
+    sender: &Address,
+    erc20_address: Option<&Address>,
+    amount: U256,
+    refunded: bool,
+) -> ResultLog {
+    let erc20_address = erc20_address.copied().unwrap_or_default();
+    ResultLog {
+        address: ENGINE_LOGS_ADDRESS.0,
+        topics: vec![
+            EngineLog::ExitCompleted.topic().0,
+            address_topic(sender),
+            address_topic(&erc20_address),
+        ],
+        data: ethabi::encode(&[ethabi::Token::Uint(amount), ethabi::Token::Bool(refunded)]),
+    }
+}
+
+pub fn xcc_result(sender: &Address, success: bool, output: &[u8]) -> ResultLog {
+    ResultLog {
+        address: ENGINE_LOGS_ADDRESS.0,
+        topics: vec![EngineLog::XccResult.topic().0, address_topic(sender)],
+        data: ethabi::encode(&[
+            ethabi::Token::Bool(success),
+            ethabi::Token::Bytes(output.to_vec()),
+        ]),
+    }
+}
+
+/// Publishes the log emitted in the block.
+pub fn emit<I: IO>(io: &mut I, block_height: u64, log: ResultLog) {
+    sdk::log!(&events::evm_log_log(&log));
+    if engine::is_log_bloom_index_enabled(io) {
+        engine::record_logs_bloom(io, block_height, &[log]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_logs_address() {
+        assert_eq!(
+            ENGINE_LOGS_ADDRESS.as_bytes(),
+            &sdk::keccak(b"engineLogs")[12..]
+        );
+    }
+
+    #[test]
+    fn test_engine_log_topics() {
+        // The topics must never change, the indexers filter the logs by them.
+        let topics: Vec<_> = engine_log_topics()
+            .into_iter()
+            .map(|topic| (topic.signature, hex::encode(topic.topic)))
+            .collect();
+        assert_eq!(
+            topics,
+            vec![
+                (
+                    "DepositCredited(address,uint256,uint256)".to_string(),
+                    "b302f2273c037550403e945536a8ef2368cd149e67bf7d7d03455517d8c3df6c".to_string()
+                ),
+                (
+                    "ExitCompleted(address,address,uint256,bool)".to_string(),
+                    "a5d7dc9abe4a0174e3832ce1af78e59289252449d0beb7000cb9f4c21f304768".to_string()
+                ),
+                (
+                    "XccResult(address,bool,bytes)".to_string(),
+                    "f7194dc3847e9cd1e071fcaee8e570db934b1223ee7c166c1d96667d1e017d44".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exit_completed() {
+        let sender = Address::from([1; 20]);
+        let log = exit_completed(&sender, None, U256::from(5), true);
+        assert_eq!(log.address, ENGINE_LOGS_ADDRESS.0);
+        assert_eq!(log.topics[1][12..], [1; 20]);
+        assert_eq!(log.topics[2], [0; 32]);
+        let data = ethabi::decode(
+            &[ethabi::ParamType::Uint(256), ethabi::ParamType::Bool],
+            &log.data,
+        )
+        .unwrap();
+        assert_eq!(
+            data,
+            vec![ethabi::Token::Uint(5.into()), ethabi::Token::Bool(true)]
+        );
+    }
+
+    #[test]
+    fn test_xcc_result() {
+        let sender = Address::from([1; 20]);
+        let log = xcc_result(&sender, true, b"\"5\"");
+        assert_eq!(log.address, ENGINE_LOGS_ADDRESS.0);
+        assert_eq!(log.topics.len(), 2);
+        assert_eq!(log.topics[1][12..], [1; 20]);
+        let data = ethabi::decode(
+            &[ethabi::ParamType::Bool, ethabi::ParamType::Bytes],
+            &log.data,
+        )
+        .unwrap();
+        assert_eq!(
+            data,
+            vec![
+                ethabi::Token::Bool(true),
+                ethabi::Token::Bytes(b"\"5\"".to_vec())
+            ]
+        );
+    }
+}
@@ -0,0 +1,38 @@
+//! Global sequence number of the calls changing the state of the engine.
+//!
+//! The sequence is incremented once by every call to the contract which writes to its state,
+//! whatever the entry point, and the new value is published in the `engine_sequence` event of
+//! the call. External automation (keepers, reconcilers) processing the calls in order can
+//! compare the sequence of a call with the last one it processed to detect a missed or
+//! reordered call. The increment is done by the runtime of the contract on the first write of
+//! a call, see `TrackedRuntime`, so the views and the failed calls leave the sequence unchanged.
+//! The standalone engine replaying the transactions doesn't maintain it.
+use crate::prelude::{config_key, ConfigKey};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Sequence number of the last call which changed the state, zero before the first one.
+pub fn get_engine_sequence<I: IO>(io: &I) -> u64 {
+    storage_key::read_config_u64(io, ConfigKey::EngineSequence, &[]).unwrap_or(0)
+}
+
+/// Increments the sequence, returns the new value. The previous value is taken from the
+/// register of the write instead of being read, so that a value the caller read and didn't
+/// load yet is kept.
+pub fn increment<I: IO>(io: &mut I) -> u64 {
+    let key = config_key(ConfigKey::EngineSequence, &[]);
+    let previous = io
+        .write_storage(&key, &[])
+        .and_then(|value| {
+            let mut bytes = [0u8; 8];
+            if value.len() != bytes.len() {
+                return None;
+            }
+            value.copy_to_slice(&mut bytes);
+            Some(u64::from_le_bytes(bytes))
+        })
+        .unwrap_or(0);
+    let sequence = previous.saturating_add(1);
+    io.write_storage(&key, &sequence.to_le_bytes());
+    sequence
+}
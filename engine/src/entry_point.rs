@@ -0,0 +1,238 @@
+//! Support of the ERC-4337 account abstraction, the smart wallets operated through the bundles
+//! of user operations submitted by the bundlers.
+//!
+//! The EntryPoint contract is predeployed by the owner at its canonical address, the one it has
+//! on every Ethereum network, so the wallets, paymasters and bundlers work with the engine as
+//! they are. That address is derived from the deterministic deployment proxy, the salt and the
+//! hash of the init code, so the engine runs the init code as the proxy does, which only deploys
+//! at the canonical address the init code of the canonical EntryPoint. Its constructor creates
+//! the `SenderCreator` helper.
+//!
+//! A bundler submits its bundle with `handle_ops`, the engine calls `handleOps` of the EntryPoint
+//! with the address of the relayer account as beneficiary, the one its `submit` transactions
+//! are reimbursed to, so the fees of the user operations are paid to the bundler. The bundler
+//! prepays the gas limits of the user operations and is refunded the unused gas, as for a
+//! transaction it submits.
+use crate::engine::{create2_address, get_code_size, Engine, EngineState};
+use crate::parameters::DeployEntryPointArgs;
+use crate::prelude::precompiles::make_address;
+use crate::prelude::{AccountId, Address, Vec, H256, U256};
+use aurora_engine_sdk::env::Env;
+use aurora_engine_sdk::io::IO;
+use aurora_engine_sdk::promise::PromiseHandler;
+
+/// Address of the EntryPoint v0.6 on the Ethereum networks.
+pub const ENTRY_POINT_ADDRESS: Address =
+    make_address(0x5ff137d4, 0xb0fdcd49dca30c7cf57e578a026d2789);
+/// Address of the `SenderCreator` created by the EntryPoint, with its nonce 1.
+pub const SENDER_CREATOR_ADDRESS: Address =
+    make_address(0x7fc98430, 0xeaedbb6070b35b39d798725049088348);
+/// Address of the deterministic deployment proxy the EntryPoint is deployed with.
+pub const DEPLOYMENT_PROXY_ADDRESS: Address =
+    make_address(0x4e59b448, 0x47b379578588920ca78fbf26c0b4956c);
+
+/// Selector of `handleOps(UserOperation[],address)`.
+const HANDLE_OPS_SELECTOR: [u8; 4] = [0x1f, 0xad, 0x94, 0x8c];
+
+pub fn is_deployed<I: IO>(io: &I) -> bool {
+    get_code_size(io, &ENTRY_POINT_ADDRESS) > 0
+}
+
+pub fn require_deployed<I: IO>(io: &I) -> Result<(), error::EntryPointError> {
+    if is_deployed(io) {
+        Ok(())
+    } else {
+        Err(error::EntryPointError::NotDeployed)
+    }
+}
+
+/// Deploys the EntryPoint from its init code with the salt, as the deterministic deployment
+/// proxy does. Fails unless the init code and the salt give the canonical address.
+pub fn deploy<I: IO + Copy, E: Env, P: PromiseHandler>(
+    io: I,
+    env: &E,
+    state: EngineState,
+    current_account_id: AccountId,
+    args: DeployEntryPointArgs,
+    handler: &mut P,
+) -> Result<(), error::EntryPointError> {
+    if is_deployed(&io) {
+        return Err(error::EntryPointError::AlreadyDeployed);
+    }
+    let salt = H256(args.salt);
+    if create2_address(&DEPLOYMENT_PROXY_ADDRESS, &salt, &args.init_code) != ENTRY_POINT_ADDRESS {
+        return Err(error::EntryPointError::UnexpectedInitCode);
+    }
+    let mut engine =
+        Engine::new_with_state(state, DEPLOYMENT_PROXY_ADDRESS, current_account_id, io, env);
+    match engine.deploy_code_create2(DEPLOYMENT_PROXY_ADDRESS, args.init_code, salt, handler) {
+        Ok(result) if result.status.is_ok() => Ok(()),
+        _ => Err(error::EntryPointError::DeployFailed),
+    }
+}
+
+/// Sum of the gas the user operations of the bundle can be charged by the EntryPoint: their
+/// call gas limit, pre-verification gas and verification gas limit, the latter three times for
+/// the operations with a paymaster as it also covers its validation and `postOp`. `None` if the
+/// bundle isn't a valid ABI encoding of a `UserOperation[]` array.
+pub fn bundle_gas_limit(ops: &[u8]) -> Option<U256> {
+    let count = read_offset(ops, 0)?;
+    let elements = ops.get(32..)?;
+    let mut total = U256::zero();
+    for i in 0..count {
+        let op = elements.get(read_offset(elements, i.checked_mul(32)?)?..)?;
+        let call_gas_limit = read_word(op, 4 * 32)?;
+        let verification_gas_limit = read_word(op, 5 * 32)?;
+        let pre_verification_gas = read_word(op, 6 * 32)?;
+        let paymaster_and_data_len = read_word(op, read_offset(op, 9 * 32)?)?;
+        let verification_gas_multiplier = if paymaster_and_data_len.is_zero() {
+            1
+        } else {
+            3
+        };
+        total = total
+            .checked_add(call_gas_limit)?
+            .checked_add(verification_gas_limit.checked_mul(verification_gas_multiplier.into())?)?
+            .checked_add(pre_verification_gas)?;
+    }
+    Some(total)
+}
+
+fn read_word(data: &[u8], offset: usize) -> Option<U256> {
+    data.get(offset..offset.checked_add(32)?)
+        .map(U256::from_big_endian)
+}
+
+fn read_offset(data: &[u8], offset: usize) -> Option<usize> {
+    let word = read_word(data, offset)?;
+    if word > U256::from(u32::MAX) {
+        return None;
+    }
+    Some(word.as_usize())
+}
+
+/// Input of the call to `handleOps`. The operations are the ABI encoding of the
+/// `UserOperation[]` array, its length followed by its elements, which is placed after the head
+/// of the arguments as it is, its offsets being relative to its own start.
+pub fn handle_ops_input(ops: &[u8], beneficiary: &Address) -> Vec<u8> {
+    let mut input = Vec::with_capacity(4 + 64 + ops.len());
+    input.extend_from_slice(&HANDLE_OPS_SELECTOR);
+    let mut word = [0u8; 32];
+    U256::from(64).to_big_endian(&mut word);
+    input.extend_from_slice(&word);
+    input.extend_from_slice(&[0u8; 12]);
+    input.extend_from_slice(beneficiary.as_bytes());
+    input.extend_from_slice(ops);
+    input
+}
+
+pub mod error {
+    const NOT_DEPLOYED: &[u8; 28] = b"ERR_ENTRY_POINT_NOT_DEPLOYED";
+    const ALREADY_DEPLOYED: &[u8; 32] = b"ERR_ENTRY_POINT_ALREADY_DEPLOYED";
+    const UNEXPECTED_INIT_CODE: &[u8; 36] = b"ERR_ENTRY_POINT_UNEXPECTED_INIT_CODE";
+    const DEPLOY_FAILED: &[u8; 29] = b"ERR_ENTRY_POINT_DEPLOY_FAILED";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntryPointError {
+        /// `handle_ops` is called before the EntryPoint is deployed.
+        NotDeployed,
+        AlreadyDeployed,
+        /// The init code and the salt don't give the address of the canonical EntryPoint.
+        UnexpectedInitCode,
+        /// The init code reverted or failed.
+        DeployFailed,
+    }
+
+    impl AsRef<[u8]> for EntryPointError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotDeployed => NOT_DEPLOYED,
+                Self::AlreadyDeployed => ALREADY_DEPLOYED,
+                Self::UnexpectedInitCode => UNEXPECTED_INIT_CODE,
+                Self::DeployFailed => DEPLOY_FAILED,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_ops_input() {
+        let beneficiary = Address([0x11; 20]);
+        // An empty array, its length only.
+        let ops = [0u8; 32];
+        let input = handle_ops_input(&ops, &beneficiary);
+        assert_eq!(input.len(), 4 + 3 * 32);
+        assert_eq!(&input[..4], &HANDLE_OPS_SELECTOR);
+        assert_eq!(U256::from_big_endian(&input[4..36]), U256::from(64));
+        assert_eq!(&input[36..48], &[0u8; 12]);
+        assert_eq!(&input[48..68], beneficiary.as_bytes());
+        assert_eq!(&input[68..], &ops);
+    }
+
+    fn word(value: u64) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        U256::from(value).to_big_endian(&mut word);
+        word
+    }
+
+    /// ABI encoding of a user operation with the gas limits, an empty signature and a
+    /// `paymasterAndData` of at most 32 bytes.
+    fn user_op(gas_limits: [u64; 3], paymaster_and_data: &[u8]) -> Vec<u8> {
+        let mut paymaster_word = [0u8; 32];
+        paymaster_word[..paymaster_and_data.len()].copy_from_slice(paymaster_and_data);
+        let signature_offset = if paymaster_and_data.is_empty() {
+            14
+        } else {
+            15
+        };
+        let mut op = [
+            word(0x33),
+            word(0),
+            word(11 * 32),
+            word(12 * 32),
+            word(gas_limits[0]),
+            word(gas_limits[1]),
+            word(gas_limits[2]),
+            word(1),
+            word(1),
+            word(13 * 32),
+            word(signature_offset * 32),
+            word(0),
+            word(0),
+            word(paymaster_and_data.len() as u64),
+        ]
+        .concat();
+        if !paymaster_and_data.is_empty() {
+            op.extend_from_slice(&paymaster_word);
+        }
+        op.extend_from_slice(&word(0));
+        op
+    }
+
+    #[test]
+    fn test_bundle_gas_limit() {
+        assert_eq!(bundle_gas_limit(&word(0)), Some(U256::zero()));
+
+        let op = user_op([100, 10, 1], &[]);
+        let op_with_paymaster = user_op([100, 10, 1], &[0x22; 20]);
+        let ops = [
+            word(2).to_vec(),
+            word(64).to_vec(),
+            word(64 + op.len() as u64).to_vec(),
+            op,
+            op_with_paymaster,
+        ]
+        .concat();
+        // The verification gas is counted three times for the operation with a paymaster.
+        assert_eq!(bundle_gas_limit(&ops), Some(U256::from(111 + 131)));
+
+        // The bundle is truncated.
+        assert_eq!(bundle_gas_limit(&ops[..ops.len() - 3 * 32]), None);
+        assert_eq!(bundle_gas_limit(&word(1)), None);
+        assert_eq!(bundle_gas_limit(&[]), None);
+    }
+}
@@ -0,0 +1,269 @@
+//! Decoding of the RLP encoded Ethereum structures the connectors prove: the log entries, the
+//! receipts holding them and the block headers committing to the receipts.
+//!
+//! The structures are decoded with the `rlp` crate. A connector parsing a new event decodes
+//! the log entry of its proof into a `LogEntry`, then matches its topics and data against the
+//! ABI of the event. The accessors returning a single field borrow it from the encoded data
+//! and ignore the other fields, for the checks done on every deposit.
+use crate::prelude::{Vec, H160, H256, U256};
+use rlp::{DecoderError, Rlp};
+
+pub use crate::log_entry::LogEntry;
+
+/// Index of the receipts root in a block header.
+const HEADER_RECEIPTS_ROOT: usize = 5;
+/// Number of the fields a block header has since the genesis.
+const HEADER_BASE_FIELDS: usize = 15;
+/// Index of the logs in a receipt.
+const RECEIPT_LOGS: usize = 3;
+const BLOOM_LEN: usize = 256;
+
+/// Type of a typed transaction (EIP-2718), `None` for a legacy one, and the RLP payload of
+/// its receipt.
+pub fn split_typed_receipt(data: &[u8]) -> Option<(Option<u8>, &[u8])> {
+    match data.first() {
+        // A legacy receipt is an RLP list, its first byte is at least 0xc0.
+        Some(tx_type) if *tx_type < 0x80 => Some((Some(*tx_type), &data[1..])),
+        Some(_) => Some((None, data)),
+        None => None,
+    }
+}
+
+/// Encoded log entry at the index in the logs of the receipt.
+pub fn receipt_log_at(receipt_data: &[u8], log_index: usize) -> Option<&[u8]> {
+    let (_, payload) = split_typed_receipt(receipt_data)?;
+    Rlp::new(payload)
+        .at(RECEIPT_LOGS)
+        .and_then(|logs| logs.at(log_index))
+        .map(|log| log.as_raw())
+        .ok()
+}
+
+/// Receipts root of an encoded block header.
+pub fn header_receipts_root(header_data: &[u8]) -> Option<H256> {
+    let root = Rlp::new(header_data).at(HEADER_RECEIPTS_ROOT).ok()?;
+    let root = root.data().ok()?;
+    if root.len() == 32 {
+        Some(H256::from_slice(root))
+    } else {
+        None
+    }
+}
+
+/// Outcome of the transaction of a receipt: the state root after it before Byzantium
+/// (EIP-658), whether it succeeded since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptOutcome {
+    StateRoot(H256),
+    Status(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    /// Type of the transaction, `None` for a legacy one.
+    pub tx_type: Option<u8>,
+    pub outcome: ReceiptOutcome,
+    pub cumulative_gas_used: U256,
+    pub logs_bloom: Vec<u8>,
+    pub logs: Vec<LogEntry>,
+}
+
+impl Receipt {
+    /// Decodes a receipt as found in the receipts trie, prefixed by its type if it is typed.
+    pub fn decode(data: &[u8]) -> Result<Self, DecoderError> {
+        let (tx_type, payload) = split_typed_receipt(data).ok_or(DecoderError::RlpIsTooShort)?;
+        let rlp = Rlp::new(payload);
+        if rlp.item_count()? != 4 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        let outcome = rlp.at(0)?;
+        let outcome = match outcome.data()? {
+            root if root.len() == 32 => ReceiptOutcome::StateRoot(H256::from_slice(root)),
+            [] => ReceiptOutcome::Status(false),
+            [1] => ReceiptOutcome::Status(true),
+            _ => return Err(DecoderError::Custom("invalid receipt status")),
+        };
+        Ok(Self {
+            tx_type,
+            outcome,
+            cumulative_gas_used: rlp.val_at(1)?,
+            logs_bloom: decode_bloom(&rlp.at(2)?)?,
+            logs: rlp.list_at(RECEIPT_LOGS)?,
+        })
+    }
+}
+
+/// Block header, with the fields added by the forks up to Shanghai. The fields added later
+/// are ignored, they are still committed to by the hash of the encoded header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub parent_hash: H256,
+    pub ommers_hash: H256,
+    pub beneficiary: H160,
+    pub state_root: H256,
+    pub transactions_root: H256,
+    pub receipts_root: H256,
+    pub logs_bloom: Vec<u8>,
+    pub difficulty: U256,
+    pub number: u64,
+    pub gas_limit: U256,
+    pub gas_used: U256,
+    pub timestamp: u64,
+    pub extra_data: Vec<u8>,
+    pub mix_hash: H256,
+    pub nonce: [u8; 8],
+    /// Since London (EIP-1559).
+    pub base_fee_per_gas: Option<U256>,
+    /// Since Shanghai (EIP-4895).
+    pub withdrawals_root: Option<H256>,
+}
+
+impl rlp::Decodable for BlockHeader {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let count = rlp.item_count()?;
+        if count < HEADER_BASE_FIELDS {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        let optional = |index: usize| (index < count).then(|| index);
+        let nonce = rlp.at(14)?;
+        let nonce = nonce.data()?;
+        if nonce.len() != 8 {
+            return Err(DecoderError::Custom("invalid header nonce"));
+        }
+        let mut header_nonce = [0u8; 8];
+        header_nonce.copy_from_slice(nonce);
+        Ok(Self {
+            parent_hash: rlp.val_at(0)?,
+            ommers_hash: rlp.val_at(1)?,
+            beneficiary: rlp.val_at(2)?,
+            state_root: rlp.val_at(3)?,
+            transactions_root: rlp.val_at(4)?,
+            receipts_root: rlp.val_at(HEADER_RECEIPTS_ROOT)?,
+            logs_bloom: decode_bloom(&rlp.at(6)?)?,
+            difficulty: rlp.val_at(7)?,
+            number: rlp.val_at(8)?,
+            gas_limit: rlp.val_at(9)?,
+            gas_used: rlp.val_at(10)?,
+            timestamp: rlp.val_at(11)?,
+            extra_data: rlp.val_at(12)?,
+            mix_hash: rlp.val_at(13)?,
+            nonce: header_nonce,
+            base_fee_per_gas: optional(15).map(|i| rlp.val_at(i)).transpose()?,
+            withdrawals_root: optional(16).map(|i| rlp.val_at(i)).transpose()?,
+        })
+    }
+}
+
+fn decode_bloom(item: &Rlp) -> Result<Vec<u8>, DecoderError> {
+    let bloom = item.data()?;
+    if bloom.len() == BLOOM_LEN {
+        Ok(bloom.to_vec())
+    } else {
+        Err(DecoderError::Custom("invalid bloom length"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::vec;
+    use rlp::RlpStream;
+
+    fn log(data: u8) -> LogEntry {
+        LogEntry {
+            address: H160([data; 20]),
+            topics: vec![H256([data; 32])],
+            data: vec![data],
+        }
+    }
+
+    fn receipt_payload(status: &[u8]) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&status.to_vec());
+        stream.append(&21_000u64);
+        stream.append(&vec![0u8; BLOOM_LEN]);
+        stream.append_list::<LogEntry, _>(&[log(1), log(2)]);
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_decode_receipt() {
+        let receipt = Receipt::decode(&receipt_payload(&[1])).unwrap();
+        assert_eq!(receipt.tx_type, None);
+        assert_eq!(receipt.outcome, ReceiptOutcome::Status(true));
+        assert_eq!(receipt.cumulative_gas_used, U256::from(21_000));
+        assert_eq!(receipt.logs, vec![log(1), log(2)]);
+
+        let typed = [&[2u8], receipt_payload(&[]).as_slice()].concat();
+        let receipt = Receipt::decode(&typed).unwrap();
+        assert_eq!(receipt.tx_type, Some(2));
+        assert_eq!(receipt.outcome, ReceiptOutcome::Status(false));
+        assert_eq!(receipt_log_at(&typed, 1), Some(&rlp::encode(&log(2))[..]));
+        assert_eq!(receipt_log_at(&typed, 2), None);
+
+        let pre_byzantium = Receipt::decode(&receipt_payload(&[7; 32])).unwrap();
+        assert_eq!(
+            pre_byzantium.outcome,
+            ReceiptOutcome::StateRoot(H256([7; 32]))
+        );
+        assert!(Receipt::decode(&receipt_payload(&[2])).is_err());
+        assert!(Receipt::decode(&[]).is_err());
+    }
+
+    fn header(fields: usize) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(fields);
+        for i in 0..6u8 {
+            if i == 2 {
+                stream.append(&H160([i; 20]));
+            } else {
+                stream.append(&H256([i; 32]));
+            }
+        }
+        stream.append(&vec![0u8; BLOOM_LEN]);
+        stream.append(&0u64);
+        stream.append(&15_000_000u64);
+        stream.append(&30_000_000u64);
+        stream.append(&21_000u64);
+        stream.append(&1_700_000_000u64);
+        stream.append(&b"extra".to_vec());
+        stream.append(&H256([13; 32]));
+        stream.append(&vec![0u8; 8]);
+        for i in 15..fields {
+            if i == 16 {
+                stream.append(&H256([16; 32]));
+            } else {
+                stream.append(&7u64);
+            }
+        }
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_decode_block_header() {
+        let legacy: BlockHeader = rlp::decode(&header(15)).unwrap();
+        assert_eq!(legacy.beneficiary, H160([2; 20]));
+        assert_eq!(legacy.receipts_root, H256([5; 32]));
+        assert_eq!(legacy.number, 15_000_000);
+        assert_eq!(legacy.extra_data, b"extra");
+        assert_eq!(legacy.base_fee_per_gas, None);
+        assert_eq!(
+            header_receipts_root(&header(15)),
+            Some(legacy.receipts_root)
+        );
+
+        let shanghai: BlockHeader = rlp::decode(&header(17)).unwrap();
+        assert_eq!(shanghai.base_fee_per_gas, Some(U256::from(7)));
+        assert_eq!(shanghai.withdrawals_root, Some(H256([16; 32])));
+        // The fields of the later forks are ignored.
+        let cancun: BlockHeader = rlp::decode(&header(20)).unwrap();
+        assert_eq!(cancun.withdrawals_root, shanghai.withdrawals_root);
+
+        let full = header(15);
+        let full = Rlp::new(&full);
+        let mut stream = RlpStream::new_list(14);
+        for i in 0..14 {
+            stream.append_raw(full.at(i).unwrap().as_raw(), 1);
+        }
+        assert!(rlp::decode::<BlockHeader>(&stream.out()).is_err());
+    }
+}
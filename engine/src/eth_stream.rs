@@ -0,0 +1,261 @@
+//! Streams of nETH which vest linearly (per second) from a sender to a recipient.
+//!
+//! The streamed amount is escrowed on the engine account when the stream is created.
+//! The recipient can claim the vested part at any time; the sender can cancel the stream,
+//! in which case the vested unclaimed part goes to the recipient and the rest is returned.
+use crate::connector::EthConnectorContract;
+use crate::parameters::{CreateStreamCallArgs, TransferCallArgs};
+use crate::prelude::{
+    bytes_to_key, format, sdk, AccountId, Balance, BorshDeserialize, BorshSerialize, ConfigKey,
+    KeyPrefix, Vec, U256,
+};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+pub type StreamId = u64;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EthStream {
+    pub sender_id: AccountId,
+    pub recipient_id: AccountId,
+    /// Total amount of nETH streamed.
+    pub total: Balance,
+    /// Amount of nETH already claimed by the recipient.
+    pub claimed: Balance,
+    /// Unix timestamp (in seconds) when vesting starts.
+    pub start: u64,
+    /// Unix timestamp (in seconds) when the total amount is vested.
+    pub end: u64,
+}
+
+impl EthStream {
+    /// Amount of nETH vested at the given timestamp (in seconds).
+    pub fn vested_amount(&self, now: u64) -> Balance {
+        if now <= self.start {
+            return 0;
+        }
+        if now >= self.end {
+            return self.total;
+        }
+        let elapsed = U256::from(now - self.start);
+        let duration = U256::from(self.end - self.start);
+        // Can't overflow, because `elapsed < duration` so the result is less than `total`.
+        (U256::from(self.total) * elapsed / duration).as_u128()
+    }
+
+    /// Vested amount which is not yet claimed.
+    pub fn claimable_amount(&self, now: u64) -> Balance {
+        self.vested_amount(now).saturating_sub(self.claimed)
+    }
+}
+
+fn stream_key(id: StreamId) -> Vec<u8> {
+    bytes_to_key(KeyPrefix::EthStream, &id.to_le_bytes())
+}
+
+pub fn get_stream<I: IO>(io: &I, id: StreamId) -> Option<EthStream> {
+    io.read_storage(&stream_key(id))
+        .and_then(|bytes| bytes.to_value().ok())
+}
+
+fn set_stream<I: IO>(io: &mut I, id: StreamId, stream: &EthStream) {
+    io.write_borsh(&stream_key(id), stream);
+}
+
+fn remove_stream<I: IO>(io: &mut I, id: StreamId) {
+    io.remove_storage(&stream_key(id));
+}
+
+fn next_stream_id<I: IO>(io: &mut I) -> StreamId {
+    let id = storage_key::read_config_u64(io, ConfigKey::NextStreamId, &[]).unwrap_or(0);
+    storage_key::write_config(io, ConfigKey::NextStreamId, &[], &(id + 1).to_le_bytes());
+    id
+}
+
+fn transfer<I: IO + Copy>(
+    io: I,
+    sender_id: &AccountId,
+    receiver_id: AccountId,
+    amount: Balance,
+) -> Result<(), error::StreamError> {
+    if amount == 0 {
+        return Ok(());
+    }
+    EthConnectorContract::init_instance(io)
+        .ft_transfer(
+            sender_id,
+            TransferCallArgs {
+                receiver_id,
+                amount,
+                memo: None,
+            },
+        )
+        .map_err(error::StreamError::Transfer)
+}
+
+/// Create a new stream funded by the sender. Returns the identifier of the stream.
+pub fn create_stream<I: IO + Copy>(
+    mut io: I,
+    sender_id: AccountId,
+    current_account_id: &AccountId,
+    args: CreateStreamCallArgs,
+) -> Result<StreamId, error::StreamError> {
+    if args.total == 0 {
+        return Err(error::StreamError::ZeroAmount);
+    }
+    if args.end <= args.start {
+        return Err(error::StreamError::InvalidSchedule);
+    }
+
+    transfer(io, &sender_id, current_account_id.clone(), args.total)?;
+
+    let id = next_stream_id(&mut io);
+    let stream = EthStream {
+        sender_id,
+        recipient_id: args.recipient_id,
+        total: args.total,
+        claimed: 0,
+        start: args.start,
+        end: args.end,
+    };
+    sdk::log!(&format!(
+        "Create stream {} of {} from {} to {}",
+        id, stream.total, stream.sender_id, stream.recipient_id
+    ));
+    set_stream(&mut io, id, &stream);
+
+    Ok(id)
+}
+
+/// Transfer the claimable amount of the stream to its recipient.
+/// Returns the claimed amount.
+pub fn claim_stream<I: IO + Copy>(
+    mut io: I,
+    predecessor_account_id: &AccountId,
+    current_account_id: &AccountId,
+    id: StreamId,
+    now: u64,
+) -> Result<Balance, error::StreamError> {
+    let mut stream = get_stream(&io, id).ok_or(error::StreamError::StreamNotFound)?;
+    if &stream.recipient_id != predecessor_account_id {
+        return Err(error::StreamError::NotAllowed);
+    }
+
+    let amount = stream.claimable_amount(now);
+    transfer(io, current_account_id, stream.recipient_id.clone(), amount)?;
+    stream.claimed += amount;
+
+    if stream.claimed == stream.total {
+        remove_stream(&mut io, id);
+    } else {
+        set_stream(&mut io, id, &stream);
+    }
+
+    Ok(amount)
+}
+
+/// Cancel the stream. Only the sender is allowed to cancel a stream.
+/// The vested unclaimed amount is sent to the recipient, the remaining part is
+/// returned to the sender.
+pub fn cancel_stream<I: IO + Copy>(
+    mut io: I,
+    predecessor_account_id: &AccountId,
+    current_account_id: &AccountId,
+    id: StreamId,
+    now: u64,
+) -> Result<(), error::StreamError> {
+    let stream = get_stream(&io, id).ok_or(error::StreamError::StreamNotFound)?;
+    if &stream.sender_id != predecessor_account_id {
+        return Err(error::StreamError::NotAllowed);
+    }
+
+    let vested = stream.vested_amount(now);
+    let claimable = vested.saturating_sub(stream.claimed);
+    let refund = stream.total - vested;
+    transfer(
+        io,
+        current_account_id,
+        stream.recipient_id.clone(),
+        claimable,
+    )?;
+    transfer(io, current_account_id, stream.sender_id.clone(), refund)?;
+    remove_stream(&mut io, id);
+
+    sdk::log!(&format!(
+        "Cancel stream {}: {} to {}, {} refunded to {}",
+        id, claimable, stream.recipient_id, refund, stream.sender_id
+    ));
+    Ok(())
+}
+
+pub mod error {
+    use crate::fungible_token;
+
+    const ZERO_AMOUNT: &[u8; 15] = b"ERR_ZERO_AMOUNT";
+    const INVALID_SCHEDULE: &[u8; 27] = b"ERR_INVALID_STREAM_SCHEDULE";
+    const STREAM_NOT_FOUND: &[u8; 20] = b"ERR_STREAM_NOT_FOUND";
+    const NOT_ALLOWED: &[u8; 15] = b"ERR_NOT_ALLOWED";
+
+    #[derive(Debug)]
+    pub enum StreamError {
+        ZeroAmount,
+        InvalidSchedule,
+        StreamNotFound,
+        NotAllowed,
+        Transfer(fungible_token::error::TransferError),
+    }
+
+    impl AsRef<[u8]> for StreamError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::ZeroAmount => ZERO_AMOUNT,
+                Self::InvalidSchedule => INVALID_SCHEDULE,
+                Self::StreamNotFound => STREAM_NOT_FOUND,
+                Self::NotAllowed => NOT_ALLOWED,
+                Self::Transfer(e) => e.as_ref(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(total: Balance, start: u64, end: u64) -> EthStream {
+        EthStream {
+            sender_id: "sender.near".parse().unwrap(),
+            recipient_id: "recipient.near".parse().unwrap(),
+            total,
+            claimed: 0,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_vested_amount() {
+        let s = stream(1_000, 100, 200);
+        assert_eq!(s.vested_amount(0), 0);
+        assert_eq!(s.vested_amount(100), 0);
+        assert_eq!(s.vested_amount(150), 500);
+        assert_eq!(s.vested_amount(199), 990);
+        assert_eq!(s.vested_amount(200), 1_000);
+        assert_eq!(s.vested_amount(u64::MAX), 1_000);
+    }
+
+    #[test]
+    fn test_vested_amount_large_total() {
+        let s = stream(u128::MAX, 0, 2);
+        assert_eq!(s.vested_amount(1), u128::MAX / 2);
+    }
+
+    #[test]
+    fn test_claimable_amount() {
+        let mut s = stream(1_000, 100, 200);
+        s.claimed = 300;
+        assert_eq!(s.claimable_amount(120), 0);
+        assert_eq!(s.claimable_amount(150), 200);
+        assert_eq!(s.claimable_amount(300), 700);
+    }
+}
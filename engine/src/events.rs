@@ -0,0 +1,997 @@
+//! Events of the engine following the NEP-297 standard.
+//!
+//! An event is a log made of the `EVENT_JSON:` prefix and a JSON object describing it, the
+//! events of the fungible token are the ones of the NEP-141 events standard. The events
+//! specific to the engine are published under the `aurora` standard.
+//!
+//! The human-readable log lines an event replaces are still emitted next to it while the legacy
+//! logs are enabled, the default, so that the relayers parsing them keep working until they
+//! read the events. The owner disables them at the end of the deprecation window.
+use crate::connector::EthConnector;
+use crate::engine::GasPayment;
+use crate::json::JsonValue;
+use crate::parameters::{
+    BlockAttributes, FinishDepositCallArgs, RelayerEarnings, ResultLog, Role, StateDiffResult,
+    TokenSupplyCheck, WithdrawResult,
+};
+use crate::prelude::{
+    format, vec, AccountId, Address, BTreeMap, Balance, ConfigKey, EthAddress, String, ToString,
+    Vec, Wei, U256,
+};
+use crate::storage_key;
+use crate::witness::WitnessSize;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+const NEP141_STANDARD: &str = "nep141";
+const NEP141_VERSION: &str = "1.0.0";
+const AURORA_STANDARD: &str = "aurora";
+const AURORA_VERSION: &str = "1.0.0";
+
+pub fn is_legacy_logs_enabled<I: IO>(io: &I) -> bool {
+    storage_key::read_config(io, ConfigKey::LegacyLogs, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(true)
+}
+
+pub fn set_legacy_logs_enabled<I: IO>(io: &mut I, enabled: bool) {
+    storage_key::write_config_borsh(io, ConfigKey::LegacyLogs, &[], &enabled);
+}
+
+/// Tokens minted for an account, an item of the `ft_mint` event.
+pub struct FtMint<'a> {
+    pub owner_id: &'a AccountId,
+    pub amount: Balance,
+    pub memo: Option<&'a str>,
+}
+
+impl<'a> FtMint<'a> {
+    fn to_json(&self) -> JsonValue {
+        owner_amount_json(self.owner_id, self.amount, self.memo)
+    }
+}
+
+/// Tokens burned from an account, an item of the `ft_burn` event.
+pub struct FtBurn<'a> {
+    pub owner_id: &'a AccountId,
+    pub amount: Balance,
+    pub memo: Option<&'a str>,
+}
+
+impl<'a> FtBurn<'a> {
+    fn to_json(&self) -> JsonValue {
+        owner_amount_json(self.owner_id, self.amount, self.memo)
+    }
+}
+
+/// Tokens moved between two accounts, an item of the `ft_transfer` event.
+pub struct FtTransfer<'a> {
+    pub old_owner_id: &'a AccountId,
+    pub new_owner_id: &'a AccountId,
+    pub amount: Balance,
+    pub memo: Option<&'a str>,
+}
+
+impl<'a> FtTransfer<'a> {
+    fn to_json(&self) -> JsonValue {
+        let mut kvs = BTreeMap::new();
+        kvs.insert(
+            "old_owner_id".to_string(),
+            JsonValue::String(self.old_owner_id.to_string()),
+        );
+        kvs.insert(
+            "new_owner_id".to_string(),
+            JsonValue::String(self.new_owner_id.to_string()),
+        );
+        kvs.insert(
+            "amount".to_string(),
+            JsonValue::String(self.amount.to_string()),
+        );
+        if let Some(memo) = self.memo {
+            kvs.insert("memo".to_string(), JsonValue::String(memo.to_string()));
+        }
+        JsonValue::Object(kvs)
+    }
+}
+
+fn owner_amount_json(owner_id: &AccountId, amount: Balance, memo: Option<&str>) -> JsonValue {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "owner_id".to_string(),
+        JsonValue::String(owner_id.to_string()),
+    );
+    // Amounts are strings, as JSON numbers can't hold all the values of `u128`.
+    kvs.insert("amount".to_string(), JsonValue::String(amount.to_string()));
+    if let Some(memo) = memo {
+        kvs.insert("memo".to_string(), JsonValue::String(memo.to_string()));
+    }
+    JsonValue::Object(kvs)
+}
+
+/// Exited tokens given back to the EVM account they were sent from, because their transfer on
+/// the NEAR side failed. `erc20_address` is `None` for ETH.
+pub struct ExitRefund {
+    pub recipient_address: Address,
+    pub erc20_address: Option<Address>,
+    pub amount: U256,
+}
+
+impl ExitRefund {
+    fn to_json(&self) -> JsonValue {
+        let mut kvs = BTreeMap::new();
+        kvs.insert(
+            "recipient_address".to_string(),
+            JsonValue::String(hex::encode(self.recipient_address)),
+        );
+        if let Some(erc20_address) = self.erc20_address {
+            kvs.insert(
+                "erc20_address".to_string(),
+                JsonValue::String(hex::encode(erc20_address)),
+            );
+        }
+        kvs.insert(
+            "amount".to_string(),
+            JsonValue::String(self.amount.to_string()),
+        );
+        JsonValue::Object(kvs)
+    }
+}
+
+fn event(standard: &str, version: &str, event: &str, data: Vec<JsonValue>) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "standard".to_string(),
+        JsonValue::String(standard.to_string()),
+    );
+    kvs.insert(
+        "version".to_string(),
+        JsonValue::String(version.to_string()),
+    );
+    kvs.insert("event".to_string(), JsonValue::String(event.to_string()));
+    kvs.insert("data".to_string(), JsonValue::Array(data));
+    format!("{}{}", EVENT_JSON_PREFIX, JsonValue::Object(kvs))
+}
+
+fn nep141_event(name: &str, data: Vec<JsonValue>) -> String {
+    event(NEP141_STANDARD, NEP141_VERSION, name, data)
+}
+
+/// Returns the log of the `ft_mint` event of the given mints.
+pub fn ft_mint_log(mints: &[FtMint]) -> String {
+    nep141_event("ft_mint", mints.iter().map(FtMint::to_json).collect())
+}
+
+/// Returns the log of the `ft_burn` event of the given burns.
+pub fn ft_burn_log(burns: &[FtBurn]) -> String {
+    nep141_event("ft_burn", burns.iter().map(FtBurn::to_json).collect())
+}
+
+/// Returns the log of the `ft_transfer` event of the given transfers.
+pub fn ft_transfer_log(transfers: &[FtTransfer]) -> String {
+    nep141_event(
+        "ft_transfer",
+        transfers.iter().map(FtTransfer::to_json).collect(),
+    )
+}
+
+/// Returns the log of the `deposit` event of a deposit finished with its proof, the tokens it
+/// minted are in the `ft_mint` event next to it, or the `eth_mint` events of its transfer call.
+///
+/// A receipt proof doesn't hold the Ethereum transaction, so the deposit is identified by its
+/// proof key, derived from the hash of the block, the index of the receipt in the block and the
+/// index of the log in the receipt.
+pub fn deposit_log(data: &FinishDepositCallArgs) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "proof_key".to_string(),
+        JsonValue::String(data.proof_key.clone()),
+    );
+    if let Some(sender) = data.sender {
+        kvs.insert(
+            "sender_address".to_string(),
+            JsonValue::String(hex::encode(sender)),
+        );
+    }
+    kvs.insert(
+        "recipient_id".to_string(),
+        JsonValue::String(data.new_owner_id.to_string()),
+    );
+    kvs.insert(
+        "amount".to_string(),
+        JsonValue::String(data.amount.to_string()),
+    );
+    kvs.insert(
+        "relayer_id".to_string(),
+        JsonValue::String(data.relayer_id.to_string()),
+    );
+    kvs.insert(
+        "fee".to_string(),
+        JsonValue::String(data.fee.into_u128().to_string()),
+    );
+    if let Some(memo) = data.memo.as_ref() {
+        kvs.insert("memo".to_string(), JsonValue::String(memo.clone()));
+    }
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "deposit",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `eth_mint` event of ETH minted to an EVM account.
+pub fn eth_mint_log(address: &EthAddress, amount: Balance) -> String {
+    eth_balance_event("eth_mint", address, amount)
+}
+
+/// Returns the log of the `eth_burn` event of ETH burned from an EVM account.
+pub fn eth_burn_log(address: &EthAddress, amount: Balance) -> String {
+    eth_balance_event("eth_burn", address, amount)
+}
+
+fn eth_balance_event(name: &str, address: &EthAddress, amount: Balance) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "address".to_string(),
+        JsonValue::String(hex::encode(address)),
+    );
+    kvs.insert("amount".to_string(), JsonValue::String(amount.to_string()));
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        name,
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `deposit_fee_conversion` event of the relayer fee of a deposit paid
+/// in the base token of the silo. The amount of the base token is missing if the swap failed
+/// and the fee was paid in ETH.
+pub fn deposit_fee_conversion_log(
+    relayer_id: &AccountId,
+    fee: Balance,
+    amount_out: Option<U256>,
+) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "relayer_id".to_string(),
+        JsonValue::String(relayer_id.to_string()),
+    );
+    kvs.insert("fee".to_string(), JsonValue::String(fee.to_string()));
+    kvs.insert(
+        "converted".to_string(),
+        JsonValue::Bool(amount_out.is_some()),
+    );
+    if let Some(amount_out) = amount_out {
+        kvs.insert(
+            "amount_out".to_string(),
+            JsonValue::String(amount_out.to_string()),
+        );
+    }
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "deposit_fee_conversion",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `exit_refund` event of the given refund.
+pub fn exit_refund_log(refund: &ExitRefund) -> String {
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "exit_refund",
+        vec![refund.to_json()],
+    )
+}
+
+/// Returns the log of the `evm_log` event of an EVM log emitted by the engine outside of a
+/// transaction, see `engine_logs`.
+pub fn evm_log_log(log: &ResultLog) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "address".to_string(),
+        JsonValue::String(hex::encode(log.address)),
+    );
+    kvs.insert(
+        "topics".to_string(),
+        JsonValue::Array(
+            log.topics
+                .iter()
+                .map(|topic| JsonValue::String(hex::encode(topic)))
+                .collect(),
+        ),
+    );
+    kvs.insert(
+        "data".to_string(),
+        JsonValue::String(hex::encode(&log.data)),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "evm_log",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `state_diff` event of the given applied diff.
+pub fn state_diff_log(result: &StateDiffResult) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert("written".to_string(), JsonValue::U64(result.written.into()));
+    kvs.insert("removed".to_string(), JsonValue::U64(result.removed.into()));
+    kvs.insert(
+        "digest".to_string(),
+        JsonValue::String(hex::encode(result.digest)),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "state_diff",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `token_supply_mismatch` event of a bridged token paused because the
+/// amount locked in the engine differs from the total supply of its ERC-20.
+pub fn token_supply_mismatch_log(token: &AccountId, check: &TokenSupplyCheck) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert("token".to_string(), JsonValue::String(token.to_string()));
+    kvs.insert(
+        "erc20".to_string(),
+        JsonValue::String(hex::encode(check.erc20)),
+    );
+    kvs.insert(
+        "locked".to_string(),
+        check
+            .locked
+            .map(|locked| JsonValue::String(locked.to_string()))
+            .unwrap_or(JsonValue::Null),
+    );
+    kvs.insert(
+        "erc20_total_supply".to_string(),
+        JsonValue::String(U256::from_big_endian(&check.erc20_total_supply).to_string()),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "token_supply_mismatch",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `block_attributes_changed` event of the given new attributes,
+/// the attributes kept to their default are `null`.
+pub fn block_attributes_log(attributes: &BlockAttributes) -> String {
+    let optional_u64 = |value: Option<u64>| value.map(JsonValue::U64).unwrap_or(JsonValue::Null);
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "coinbase".to_string(),
+        attributes
+            .coinbase
+            .map(|coinbase| JsonValue::String(hex::encode(coinbase)))
+            .unwrap_or(JsonValue::Null),
+    );
+    kvs.insert("gas_limit".to_string(), optional_u64(attributes.gas_limit));
+    kvs.insert(
+        "block_gas_cap".to_string(),
+        optional_u64(attributes.block_gas_cap),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "block_attributes_changed",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `fee_waiver_added` or `fee_waiver_removed` event of the relayer.
+pub fn fee_waiver_log(relayer_id: &AccountId, waived: bool) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "relayer_id".to_string(),
+        JsonValue::String(relayer_id.to_string()),
+    );
+    let name = if waived {
+        "fee_waiver_added"
+    } else {
+        "fee_waiver_removed"
+    };
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        name,
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `relayer_earnings` event summarizing the fees credited to the relayer
+/// by the deposits so far.
+pub fn relayer_earnings_log(relayer_id: &AccountId, earnings: &RelayerEarnings) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "relayer_id".to_string(),
+        JsonValue::String(relayer_id.to_string()),
+    );
+    kvs.insert(
+        "near_fees".to_string(),
+        JsonValue::String(earnings.near_fees.to_string()),
+    );
+    kvs.insert(
+        "aurora_fees".to_string(),
+        JsonValue::String(earnings.aurora_fees.to_string()),
+    );
+    kvs.insert("deposits".to_string(), JsonValue::U64(earnings.deposits));
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "relayer_earnings",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `owner_proposed` event, `new_owner_id` is `null` when the proposal
+/// is cancelled.
+pub fn owner_proposed_log(owner_id: &AccountId, new_owner_id: Option<&AccountId>) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "owner_id".to_string(),
+        JsonValue::String(owner_id.to_string()),
+    );
+    kvs.insert(
+        "new_owner_id".to_string(),
+        new_owner_id.map_or(JsonValue::Null, |id| JsonValue::String(id.to_string())),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "owner_proposed",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `owner_changed` event, once the proposed owner accepted.
+pub fn owner_changed_log(previous_owner_id: &AccountId, new_owner_id: &AccountId) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "previous_owner_id".to_string(),
+        JsonValue::String(previous_owner_id.to_string()),
+    );
+    kvs.insert(
+        "new_owner_id".to_string(),
+        JsonValue::String(new_owner_id.to_string()),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "owner_changed",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `role_granted` or `role_revoked` event.
+pub fn role_log(role: Role, account_id: &AccountId, granted: bool) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "role".to_string(),
+        JsonValue::String(role.as_str().to_string()),
+    );
+    kvs.insert(
+        "account_id".to_string(),
+        JsonValue::String(account_id.to_string()),
+    );
+    let name = if granted {
+        "role_granted"
+    } else {
+        "role_revoked"
+    };
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        name,
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `gas_payment` event of a transaction paying for its gas, so that the
+/// relayers can account for the fees they were credited.
+pub fn gas_payment_log(payment: &GasPayment) -> String {
+    let wei = |amount: Wei| JsonValue::String(amount.to_string());
+    let mut kvs = BTreeMap::new();
+    kvs.insert("gas_used".to_string(), JsonValue::U64(payment.gas_used));
+    kvs.insert(
+        "gas_price".to_string(),
+        JsonValue::String(payment.gas_price.to_string()),
+    );
+    kvs.insert(
+        "relayer".to_string(),
+        JsonValue::String(hex::encode(payment.relayer)),
+    );
+    kvs.insert("relayer_reward".to_string(), wei(payment.relayer_reward));
+    kvs.insert("treasury_reward".to_string(), wei(payment.treasury_reward));
+    kvs.insert("refund".to_string(), wei(payment.refund));
+    kvs.insert("burned".to_string(), wei(payment.burned));
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "gas_payment",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `unclaimed_deposit_flagged` or `unclaimed_deposit_unflagged` event of
+/// the deposit.
+pub fn unclaimed_deposit_flag_log(
+    deposit_id: &str,
+    recipient_id: &AccountId,
+    flagged: bool,
+) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "deposit_id".to_string(),
+        JsonValue::String(deposit_id.to_string()),
+    );
+    kvs.insert(
+        "recipient_id".to_string(),
+        JsonValue::String(recipient_id.to_string()),
+    );
+    let name = if flagged {
+        "unclaimed_deposit_flagged"
+    } else {
+        "unclaimed_deposit_unflagged"
+    };
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        name,
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `unclaimed_deposit_reassigned` event of the deposit, with the amount
+/// moved from its recipient to the new recipient.
+pub fn unclaimed_deposit_reassigned_log(
+    deposit_id: &str,
+    recipient_id: &AccountId,
+    new_recipient_id: &AccountId,
+    amount: Balance,
+) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "deposit_id".to_string(),
+        JsonValue::String(deposit_id.to_string()),
+    );
+    kvs.insert(
+        "recipient_id".to_string(),
+        JsonValue::String(recipient_id.to_string()),
+    );
+    kvs.insert(
+        "new_recipient_id".to_string(),
+        JsonValue::String(new_recipient_id.to_string()),
+    );
+    kvs.insert("amount".to_string(), JsonValue::String(amount.to_string()));
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "unclaimed_deposit_reassigned",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `engine_sequence` event of a call changing the state, with the
+/// sequence number of the call.
+pub fn engine_sequence_log(sequence: u64) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert("sequence".to_string(), JsonValue::U64(sequence));
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "engine_sequence",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `witness_size` event of the storage touched by a transaction.
+pub fn witness_size_log(size: &WitnessSize) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert("reads".to_string(), JsonValue::U64(size.reads));
+    kvs.insert("writes".to_string(), JsonValue::U64(size.writes));
+    kvs.insert(
+        "touched_bytes".to_string(),
+        JsonValue::U64(size.touched_bytes),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "witness_size",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `withdraw` event of nETH burned by the account, to be unlocked on
+/// Ethereum by the custodian. The account is the engine itself for the ETH exited from the EVM.
+pub fn withdraw_log(sender_id: &AccountId, result: &WithdrawResult) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "sender_id".to_string(),
+        JsonValue::String(sender_id.to_string()),
+    );
+    kvs.insert(
+        "recipient_address".to_string(),
+        JsonValue::String(hex::encode(result.recipient_id)),
+    );
+    kvs.insert(
+        "eth_custodian_address".to_string(),
+        JsonValue::String(hex::encode(result.eth_custodian_address)),
+    );
+    kvs.insert(
+        "amount".to_string(),
+        JsonValue::String(result.amount.to_string()),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "withdraw",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+/// Returns the log of the `eth_connector_contract_data_changed` event, with the prover and the
+/// custodian address before and after the change.
+pub fn contract_data_log(previous: &EthConnector, current: &EthConnector) -> String {
+    let mut kvs = BTreeMap::new();
+    kvs.insert(
+        "previous_prover_account".to_string(),
+        JsonValue::String(previous.prover_account.to_string()),
+    );
+    kvs.insert(
+        "previous_eth_custodian_address".to_string(),
+        JsonValue::String(hex::encode(previous.eth_custodian_address)),
+    );
+    kvs.insert(
+        "prover_account".to_string(),
+        JsonValue::String(current.prover_account.to_string()),
+    );
+    kvs.insert(
+        "eth_custodian_address".to_string(),
+        JsonValue::String(hex::encode(current.eth_custodian_address)),
+    );
+    event(
+        AURORA_STANDARD,
+        AURORA_VERSION,
+        "eth_connector_contract_data_changed",
+        vec![JsonValue::Object(kvs)],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ft_mint_log() {
+        let owner_id: AccountId = "alice.near".parse().unwrap();
+        let relayer_id: AccountId = "relayer.near".parse().unwrap();
+        let log = ft_mint_log(&[
+            FtMint {
+                owner_id: &owner_id,
+                amount: 100,
+                memo: Some("invoice \"42\""),
+            },
+            FtMint {
+                owner_id: &relayer_id,
+                amount: 1,
+                memo: None,
+            },
+        ]);
+        assert_eq!(
+            log,
+            "EVENT_JSON:{\"data\": [{\"amount\": \"100\", \"memo\": \"invoice \\\"42\\\"\", \"owner_id\": \"alice.near\"}, {\"amount\": \"1\", \"owner_id\": \"relayer.near\"}], \"event\": \"ft_mint\", \"standard\": \"nep141\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_ft_burn_and_transfer_logs() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        assert_eq!(
+            ft_burn_log(&[FtBurn {
+                owner_id: &alice,
+                amount: 100,
+                memo: None,
+            }]),
+            "EVENT_JSON:{\"data\": [{\"amount\": \"100\", \"owner_id\": \"alice.near\"}], \"event\": \"ft_burn\", \"standard\": \"nep141\", \"version\": \"1.0.0\"}"
+        );
+        assert_eq!(
+            ft_transfer_log(&[FtTransfer {
+                old_owner_id: &alice,
+                new_owner_id: &bob,
+                amount: 7,
+                memo: Some("refund"),
+            }]),
+            "EVENT_JSON:{\"data\": [{\"amount\": \"7\", \"memo\": \"refund\", \"new_owner_id\": \"bob.near\", \"old_owner_id\": \"alice.near\"}], \"event\": \"ft_transfer\", \"standard\": \"nep141\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_deposit_and_eth_balance_logs() {
+        let data = FinishDepositCallArgs {
+            new_owner_id: "alice.near".parse().unwrap(),
+            amount: 100,
+            proof_key: "abcd".to_string(),
+            relayer_id: "relayer.near".parse().unwrap(),
+            fee: 1.into(),
+            msg: None,
+            memo: None,
+            sender: Some([0x11; 20]),
+        };
+        assert_eq!(
+            deposit_log(&data),
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"amount\": \"100\", \"fee\": \"1\", \"proof_key\": \"abcd\", \"recipient_id\": \"alice.near\", \"relayer_id\": \"relayer.near\", \"sender_address\": \"{}\"}}], \"event\": \"deposit\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "11".repeat(20)
+            )
+        );
+        assert_eq!(
+            eth_burn_log(&[0xab; 20], 5),
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"address\": \"{}\", \"amount\": \"5\"}}], \"event\": \"eth_burn\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "ab".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_deposit_fee_conversion_log() {
+        let relayer_id: AccountId = "relayer.near".parse().unwrap();
+        assert_eq!(
+            deposit_fee_conversion_log(&relayer_id, 10, Some(U256::from(25u64))),
+            "EVENT_JSON:{\"data\": [{\"amount_out\": \"25\", \"converted\": true, \"fee\": \"10\", \"relayer_id\": \"relayer.near\"}], \"event\": \"deposit_fee_conversion\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert_eq!(
+            deposit_fee_conversion_log(&relayer_id, 10, None),
+            "EVENT_JSON:{\"data\": [{\"converted\": false, \"fee\": \"10\", \"relayer_id\": \"relayer.near\"}], \"event\": \"deposit_fee_conversion\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_exit_refund_log() {
+        let log = exit_refund_log(&ExitRefund {
+            recipient_address: Address([0x11; 20]),
+            erc20_address: Some(Address([0xab; 20])),
+            amount: U256::from(1_000u64),
+        });
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"amount\": \"1000\", \"erc20_address\": \"{}\", \"recipient_address\": \"{}\"}}], \"event\": \"exit_refund\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "ab".repeat(20),
+                "11".repeat(20)
+            )
+        );
+
+        let log = exit_refund_log(&ExitRefund {
+            recipient_address: Address([0x11; 20]),
+            erc20_address: None,
+            amount: U256::from(5u64),
+        });
+        assert!(log.starts_with("EVENT_JSON:{\"data\": [{\"amount\": \"5\", \"recipient_address\""));
+    }
+
+    #[test]
+    fn test_evm_log_log() {
+        let log = evm_log_log(&ResultLog {
+            address: [0x11; 20],
+            topics: vec![[0x22; 32]],
+            data: vec![0x33],
+        });
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"address\": \"{}\", \"data\": \"33\", \"topics\": [\"{}\"]}}], \"event\": \"evm_log\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "11".repeat(20),
+                "22".repeat(32)
+            )
+        );
+    }
+
+    #[test]
+    fn test_state_diff_log() {
+        let log = state_diff_log(&StateDiffResult {
+            written: 2,
+            removed: 1,
+            digest: [0xcd; 32],
+        });
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"digest\": \"{}\", \"removed\": 1, \"written\": 2}}], \"event\": \"state_diff\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "cd".repeat(32)
+            )
+        );
+    }
+
+    #[test]
+    fn test_token_supply_mismatch_log() {
+        let token: AccountId = "token.near".parse().unwrap();
+        let log = token_supply_mismatch_log(
+            &token,
+            &TokenSupplyCheck {
+                erc20: [0xab; 20],
+                locked: Some(100),
+                erc20_total_supply: crate::prelude::types::u256_to_arr(&U256::from(150)),
+                diverged: true,
+                paused: true,
+            },
+        );
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"erc20\": \"{}\", \"erc20_total_supply\": \"150\", \"locked\": \"100\", \"token\": \"token.near\"}}], \"event\": \"token_supply_mismatch\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "ab".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_block_attributes_log() {
+        let log = block_attributes_log(&BlockAttributes {
+            coinbase: Some([0x11; 20]),
+            gas_limit: Some(30_000_000),
+            block_gas_cap: None,
+        });
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"block_gas_cap\": null, \"coinbase\": \"{}\", \"gas_limit\": 30000000}}], \"event\": \"block_attributes_changed\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "11".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_fee_waiver_log() {
+        let relayer_id: AccountId = "relayer.near".parse().unwrap();
+        assert_eq!(
+            fee_waiver_log(&relayer_id, true),
+            "EVENT_JSON:{\"data\": [{\"relayer_id\": \"relayer.near\"}], \"event\": \"fee_waiver_added\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert!(fee_waiver_log(&relayer_id, false).contains("\"event\": \"fee_waiver_removed\""));
+    }
+
+    #[test]
+    fn test_relayer_earnings_log() {
+        let relayer_id: AccountId = "relayer.near".parse().unwrap();
+        let earnings = RelayerEarnings {
+            near_fees: 400,
+            aurora_fees: 7,
+            deposits: 100,
+        };
+        assert_eq!(
+            relayer_earnings_log(&relayer_id, &earnings),
+            "EVENT_JSON:{\"data\": [{\"aurora_fees\": \"7\", \"deposits\": 100, \"near_fees\": \"400\", \"relayer_id\": \"relayer.near\"}], \"event\": \"relayer_earnings\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_owner_and_role_logs() {
+        let owner_id: AccountId = "owner.near".parse().unwrap();
+        let new_owner_id: AccountId = "new-owner.near".parse().unwrap();
+        assert_eq!(
+            owner_proposed_log(&owner_id, Some(&new_owner_id)),
+            "EVENT_JSON:{\"data\": [{\"new_owner_id\": \"new-owner.near\", \"owner_id\": \"owner.near\"}], \"event\": \"owner_proposed\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert_eq!(
+            owner_proposed_log(&owner_id, None),
+            "EVENT_JSON:{\"data\": [{\"new_owner_id\": null, \"owner_id\": \"owner.near\"}], \"event\": \"owner_proposed\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert_eq!(
+            owner_changed_log(&owner_id, &new_owner_id),
+            "EVENT_JSON:{\"data\": [{\"new_owner_id\": \"new-owner.near\", \"previous_owner_id\": \"owner.near\"}], \"event\": \"owner_changed\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert_eq!(
+            role_log(Role::MetadataManager, &new_owner_id, false),
+            "EVENT_JSON:{\"data\": [{\"account_id\": \"new-owner.near\", \"role\": \"metadata_manager\"}], \"event\": \"role_revoked\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_withdraw_log() {
+        let sender_id: AccountId = "alice.near".parse().unwrap();
+        let log = withdraw_log(
+            &sender_id,
+            &WithdrawResult {
+                amount: 100,
+                recipient_id: [0x11; 20],
+                eth_custodian_address: [0xcd; 20],
+            },
+        );
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"amount\": \"100\", \"eth_custodian_address\": \"{}\", \"recipient_address\": \"{}\", \"sender_id\": \"alice.near\"}}], \"event\": \"withdraw\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "cd".repeat(20),
+                "11".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_contract_data_log() {
+        let log = contract_data_log(
+            &EthConnector {
+                prover_account: "prover.near".parse().unwrap(),
+                eth_custodian_address: [0x11; 20],
+            },
+            &EthConnector {
+                prover_account: "new-prover.near".parse().unwrap(),
+                eth_custodian_address: [0x22; 20],
+            },
+        );
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"eth_custodian_address\": \"{}\", \"previous_eth_custodian_address\": \"{}\", \"previous_prover_account\": \"prover.near\", \"prover_account\": \"new-prover.near\"}}], \"event\": \"eth_connector_contract_data_changed\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "22".repeat(20),
+                "11".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_gas_payment_log() {
+        let log = gas_payment_log(&GasPayment {
+            gas_used: 21_000,
+            gas_price: U256::from(10),
+            relayer: Address([0x11; 20]),
+            relayer_reward: Wei::new_u64(168_000),
+            treasury_reward: Wei::new_u64(42_000),
+            refund: Wei::new_u64(90_000),
+            burned: Wei::zero(),
+        });
+        assert_eq!(
+            log,
+            format!(
+                "EVENT_JSON:{{\"data\": [{{\"burned\": \"0\", \"gas_price\": \"10\", \"gas_used\": 21000, \"refund\": \"90000\", \"relayer\": \"{}\", \"relayer_reward\": \"168000\", \"treasury_reward\": \"42000\"}}], \"event\": \"gas_payment\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}}",
+                "11".repeat(20)
+            )
+        );
+    }
+
+    #[test]
+    fn test_unclaimed_deposit_logs() {
+        let recipient_id: AccountId = "alice.naer".parse().unwrap();
+        let new_recipient_id: AccountId = "alice.near".parse().unwrap();
+        assert_eq!(
+            unclaimed_deposit_flag_log("abcd", &recipient_id, true),
+            "EVENT_JSON:{\"data\": [{\"deposit_id\": \"abcd\", \"recipient_id\": \"alice.naer\"}], \"event\": \"unclaimed_deposit_flagged\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+        assert!(unclaimed_deposit_flag_log("abcd", &recipient_id, false)
+            .contains("\"event\": \"unclaimed_deposit_unflagged\""));
+        assert_eq!(
+            unclaimed_deposit_reassigned_log("abcd", &recipient_id, &new_recipient_id, 100),
+            "EVENT_JSON:{\"data\": [{\"amount\": \"100\", \"deposit_id\": \"abcd\", \"new_recipient_id\": \"alice.near\", \"recipient_id\": \"alice.naer\"}], \"event\": \"unclaimed_deposit_reassigned\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_engine_sequence_log() {
+        assert_eq!(
+            engine_sequence_log(42),
+            "EVENT_JSON:{\"data\": [{\"sequence\": 42}], \"event\": \"engine_sequence\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+
+    #[test]
+    fn test_witness_size_log() {
+        let log = witness_size_log(&WitnessSize {
+            reads: 12,
+            writes: 3,
+            touched_bytes: 1024,
+        });
+        assert_eq!(
+            log,
+            "EVENT_JSON:{\"data\": [{\"reads\": 12, \"touched_bytes\": 1024, \"writes\": 3}], \"event\": \"witness_size\", \"standard\": \"aurora\", \"version\": \"1.0.0\"}"
+        );
+    }
+}
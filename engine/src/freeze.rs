@@ -0,0 +1,33 @@
+//! Freezing of individual NEAR accounts and EVM addresses.
+//!
+//! Unlike the global pause flags of the eth-connector, freezing only affects the given
+//! account: a frozen NEAR account can't transfer or withdraw its nETH, a frozen EVM address
+//! can't originate transactions, and the transactions which transfer value out of it, e.g.
+//! from a frozen contract called by another one, fail. The rest of the system stays live.
+use crate::parameters::FreezeTarget;
+use crate::prelude::{bytes_to_key, AccountId, Address, BorshSerialize, KeyPrefix, Vec};
+use aurora_engine_sdk::io::IO;
+
+fn frozen_key(target: &FreezeTarget) -> Vec<u8> {
+    bytes_to_key(KeyPrefix::FrozenAccount, &target.try_to_vec().unwrap())
+}
+
+pub fn is_frozen<I: IO>(io: &I, target: &FreezeTarget) -> bool {
+    io.storage_has_key(&frozen_key(target))
+}
+
+pub fn is_account_frozen<I: IO>(io: &I, account_id: &AccountId) -> bool {
+    is_frozen(io, &FreezeTarget::Near(account_id.clone()))
+}
+
+pub fn is_address_frozen<I: IO>(io: &I, address: &Address) -> bool {
+    is_frozen(io, &FreezeTarget::Evm(address.0))
+}
+
+pub fn freeze<I: IO>(io: &mut I, target: &FreezeTarget) {
+    io.write_storage(&frozen_key(target), &[1]);
+}
+
+pub fn unfreeze<I: IO>(io: &mut I, target: &FreezeTarget) {
+    io.remove_storage(&frozen_key(target));
+}
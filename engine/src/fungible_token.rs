@@ -1,13 +1,19 @@
 use crate::connector::ZERO_ATTACHED_BALANCE;
 use crate::engine;
+use crate::events;
+use crate::freeze;
 use crate::json::{parse_json, JsonValue};
-use crate::parameters::{NEP141FtOnTransferArgs, ResolveTransferCallArgs, StorageBalance};
+use crate::pagination;
+use crate::parameters::{
+    AccountStorageDeposit, BalanceStorageLayout, NEP141FtOnTransferArgs, ResolveTransferCallArgs,
+    StorageBalance, StorageConfig,
+};
 use crate::prelude::account_id::AccountId;
 use crate::prelude::{
     sdk, storage, vec, Address, BTreeMap, Balance, BorshDeserialize, BorshSerialize, EthAddress,
-    NearGas, PromiseAction, PromiseBatchAction, PromiseCreateArgs, PromiseResult,
-    PromiseWithCallbackArgs, StorageBalanceBounds, StorageUsage, String, ToString, TryInto, Vec,
-    Wei, U256,
+    EthConnectorStorageId, Memo, NEP141Wei, NearGas, PromiseAction, PromiseBatchAction,
+    PromiseCreateArgs, PromiseResult, PromiseWithCallbackArgs, StorageBalanceBounds, StorageUsage,
+    String, ToString, TryInto, Vec,
 };
 use aurora_engine_sdk::io::{StorageIntermediate, IO};
 
@@ -15,6 +21,8 @@ use aurora_engine_sdk::io::{StorageIntermediate, IO};
 const GAS_FOR_RESOLVE_TRANSFER: NearGas = NearGas::new(5_000_000_000_000);
 /// Gas for `ft_on_transfer`
 const GAS_FOR_FT_TRANSFER_CALL: NearGas = NearGas::new(35_000_000_000_000);
+/// Maximum gas the caller of `ft_transfer_call` can attach to `ft_on_transfer`: 200 TGas
+pub const MAX_GAS_FOR_FT_ON_TRANSFER: NearGas = NearGas::new(200_000_000_000_000);
 
 #[derive(Debug, Default, BorshDeserialize, BorshSerialize)]
 pub struct FungibleToken {
@@ -152,21 +160,25 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         engine::get_balance(&self.io, &Address(address)).try_into_u128()
     }
 
+    fn eth_balance_on_near(&self, account_id: &AccountId) -> NEP141Wei {
+        NEP141Wei::new(self.get_account_eth_balance(account_id).unwrap_or(0))
+    }
+
     /// Internal ETH deposit to NEAR - nETH (NEP-141)
     pub fn internal_deposit_eth_to_near(
         &mut self,
         account_id: &AccountId,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), error::DepositError> {
-        let balance = self.get_account_eth_balance(account_id).unwrap_or(0);
-        let new_balance = balance
+        let new_balance = self
+            .eth_balance_on_near(account_id)
             .checked_add(amount)
             .ok_or(error::DepositError::BalanceOverflow)?;
-        self.accounts_insert(account_id, new_balance);
-        self.total_eth_supply_on_near = self
-            .total_eth_supply_on_near
+        self.accounts_insert(account_id, new_balance.as_u128());
+        self.total_eth_supply_on_near = NEP141Wei::new(self.total_eth_supply_on_near)
             .checked_add(amount)
-            .ok_or(error::DepositError::TotalSupplyOverflow)?;
+            .ok_or(error::DepositError::TotalSupplyOverflow)?
+            .as_u128();
         Ok(())
     }
 
@@ -174,41 +186,52 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
     pub fn internal_deposit_eth_to_aurora(
         &mut self,
         address: EthAddress,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), error::DepositError> {
         let balance = self
             .internal_unwrap_balance_of_eth_on_aurora(address)
             .map_err(|_| error::DepositError::BalanceOverflow)?;
-        let new_balance = balance
+        let new_balance = NEP141Wei::new(balance)
             .checked_add(amount)
             .ok_or(error::DepositError::BalanceOverflow)?;
-        engine::set_balance(
-            &mut self.io,
-            &Address(address),
-            &Wei::new(U256::from(new_balance)),
-        );
-        self.total_eth_supply_on_aurora = self
-            .total_eth_supply_on_aurora
+        engine::set_balance(&mut self.io, &Address(address), &new_balance.to_wei());
+        self.total_eth_supply_on_aurora = NEP141Wei::new(self.total_eth_supply_on_aurora)
             .checked_add(amount)
-            .ok_or(error::DepositError::TotalSupplyOverflow)?;
+            .ok_or(error::DepositError::TotalSupplyOverflow)?
+            .as_u128();
         Ok(())
     }
 
-    /// Withdraw NEAR tokens
-    pub fn internal_withdraw_eth_from_near(
-        &mut self,
+    /// Balance of the account and total supply on NEAR after the withdrawal of the amount,
+    /// without changing them.
+    pub fn check_withdraw_eth_from_near(
+        &self,
         account_id: &AccountId,
-        amount: Balance,
-    ) -> Result<(), error::WithdrawError> {
-        let balance = self.get_account_eth_balance(account_id).unwrap_or(0);
-        let new_balance = balance
+        amount: NEP141Wei,
+    ) -> Result<(NEP141Wei, NEP141Wei), error::WithdrawError> {
+        if freeze::is_account_frozen(&self.io, account_id) {
+            return Err(error::WithdrawError::AccountFrozen);
+        }
+        let new_balance = self
+            .eth_balance_on_near(account_id)
             .checked_sub(amount)
             .ok_or(error::WithdrawError::InsufficientFunds)?;
-        self.accounts_insert(account_id, new_balance);
-        self.total_eth_supply_on_near = self
-            .total_eth_supply_on_near
+        let new_total_supply = NEP141Wei::new(self.total_eth_supply_on_near)
             .checked_sub(amount)
             .ok_or(error::WithdrawError::TotalSupplyUnderflow)?;
+        Ok((new_balance, new_total_supply))
+    }
+
+    /// Withdraw NEAR tokens
+    pub fn internal_withdraw_eth_from_near(
+        &mut self,
+        account_id: &AccountId,
+        amount: NEP141Wei,
+    ) -> Result<(), error::WithdrawError> {
+        let (new_balance, new_total_supply) =
+            self.check_withdraw_eth_from_near(account_id, amount)?;
+        self.accounts_insert(account_id, new_balance.as_u128());
+        self.total_eth_supply_on_near = new_total_supply.as_u128();
         Ok(())
     }
 
@@ -216,23 +239,19 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
     pub fn internal_withdraw_eth_from_aurora(
         &mut self,
         address: EthAddress,
-        amount: Balance,
+        amount: NEP141Wei,
     ) -> Result<(), error::WithdrawError> {
         let balance = self
             .internal_unwrap_balance_of_eth_on_aurora(address)
             .map_err(error::WithdrawError::BalanceOverflow)?;
-        let new_balance = balance
+        let new_balance = NEP141Wei::new(balance)
             .checked_sub(amount)
             .ok_or(error::WithdrawError::InsufficientFunds)?;
-        engine::set_balance(
-            &mut self.io,
-            &Address(address),
-            &Wei::new(U256::from(new_balance)),
-        );
-        self.total_eth_supply_on_aurora = self
-            .total_eth_supply_on_aurora
+        engine::set_balance(&mut self.io, &Address(address), &new_balance.to_wei());
+        self.total_eth_supply_on_aurora = NEP141Wei::new(self.total_eth_supply_on_aurora)
             .checked_sub(amount)
-            .ok_or(error::WithdrawError::TotalSupplyUnderflow)?;
+            .ok_or(error::WithdrawError::TotalSupplyUnderflow)?
+            .as_u128();
         Ok(())
     }
 
@@ -241,31 +260,38 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         &mut self,
         sender_id: &AccountId,
         receiver_id: &AccountId,
-        amount: Balance,
-        #[allow(unused_variables)] memo: &Option<String>,
+        amount: NEP141Wei,
+        memo: &Option<Memo>,
     ) -> Result<(), error::TransferError> {
         if sender_id == receiver_id {
             return Err(error::TransferError::SelfTransfer);
         }
-        if amount == 0 {
+        if amount.is_zero() {
             return Err(error::TransferError::ZeroAmount);
         }
 
-        // Check is account receiver_id exist
-        if !self.accounts_contains_key(receiver_id) {
-            // Register receiver_id account with 0 balance. We need it because
-            // when we retire to get the balance of `receiver_id` it will fail
-            // if it does not exist.
-            self.internal_register_account(receiver_id)
-        }
+        // Register receiver_id account with 0 balance. We need it because
+        // when we retire to get the balance of `receiver_id` it will fail
+        // if it does not exist.
+        self.register_implicit_account(receiver_id)
+            .map_err(|_| error::TransferError::StoragePoolExhausted)?;
         self.internal_withdraw_eth_from_near(sender_id, amount)?;
         self.internal_deposit_eth_to_near(receiver_id, amount)?;
-        sdk::log!(&crate::prelude::format!(
-            "Transfer {} from {} to {}",
-            amount,
-            sender_id,
-            receiver_id
-        ));
+        // Replaced by the `ft_transfer` event.
+        if events::is_legacy_logs_enabled(&self.io) {
+            sdk::log!(&crate::prelude::format!(
+                "Transfer {} from {} to {}",
+                amount,
+                sender_id,
+                receiver_id
+            ));
+        }
+        sdk::log!(&events::ft_transfer_log(&[events::FtTransfer {
+            old_owner_id: sender_id,
+            new_owner_id: receiver_id,
+            amount: amount.as_u128(),
+            memo: memo.as_ref().map(Memo::as_str),
+        }]));
         #[cfg(feature = "log")]
         if let Some(memo) = memo {
             sdk::log!(&crate::prelude::format!("Memo: {}", memo));
@@ -277,6 +303,110 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         self.accounts_insert(account_id, 0)
     }
 
+    /// Registers the account receiving nETH without a storage deposit of its own, e.g. the
+    /// recipient of a deposit or of a transfer. Its storage deposit is paid by the storage
+    /// pool if it can, otherwise the account is registered for free unless the configuration
+    /// requires the pool to pay.
+    pub fn register_implicit_account(
+        &mut self,
+        account_id: &AccountId,
+    ) -> Result<(), error::StorageFundingError> {
+        if self.accounts_contains_key(account_id) {
+            return Ok(());
+        }
+        let min_balance = self.storage_balance_bounds().min;
+        let pool = self.get_storage_pool();
+        if pool >= min_balance {
+            self.set_storage_pool(pool - min_balance);
+            self.set_account_storage_deposit(
+                account_id,
+                &AccountStorageDeposit {
+                    total: min_balance,
+                    from_pool: true,
+                },
+            );
+        } else if self.storage_config().pool_required {
+            return Err(error::StorageFundingError::StoragePoolExhausted);
+        } else {
+            // Nothing was paid, so nothing is refunded when the account is unregistered.
+            self.set_account_storage_deposit(
+                account_id,
+                &AccountStorageDeposit {
+                    total: 0,
+                    from_pool: false,
+                },
+            );
+        }
+        self.internal_register_account(account_id);
+        Ok(())
+    }
+
+    /// NEAR available to pay for the storage deposits of the implicitly registered accounts.
+    pub fn get_storage_pool(&self) -> Balance {
+        self.io
+            .read_storage(&storage_key(EthConnectorStorageId::StoragePool, &[]))
+            .and_then(|value| value.to_value().ok())
+            .unwrap_or(0)
+    }
+
+    fn set_storage_pool(&mut self, pool: Balance) {
+        self.io
+            .write_borsh(&storage_key(EthConnectorStorageId::StoragePool, &[]), &pool);
+    }
+
+    /// Adds the NEAR to the storage pool, returning the new balance of the pool.
+    pub fn fund_storage_pool(&mut self, amount: Balance) -> Balance {
+        let pool = self.get_storage_pool().saturating_add(amount);
+        self.set_storage_pool(pool);
+        pool
+    }
+
+    pub fn storage_config(&self) -> StorageConfig {
+        self.io
+            .read_storage(&storage_key(EthConnectorStorageId::StorageConfig, &[]))
+            .and_then(|value| value.to_value().ok())
+            .unwrap_or_default()
+    }
+
+    /// Sets the storage deposit of the accounts registered from now on, which can't be below
+    /// the cost of the storage of an account.
+    pub fn set_storage_config(
+        &mut self,
+        config: &StorageConfig,
+    ) -> Result<(), error::StorageFundingError> {
+        if config.min_balance.unwrap_or(u128::MAX) < self.account_storage_cost() {
+            return Err(error::StorageFundingError::MinBalanceTooLow);
+        }
+        self.io.write_borsh(
+            &storage_key(EthConnectorStorageId::StorageConfig, &[]),
+            config,
+        );
+        Ok(())
+    }
+
+    fn get_account_storage_deposit(&self, account_id: &AccountId) -> Option<AccountStorageDeposit> {
+        self.io
+            .read_storage(&storage_key(
+                EthConnectorStorageId::AccountStorageDeposit,
+                account_id.as_bytes(),
+            ))
+            .and_then(|value| value.to_value().ok())
+    }
+
+    fn set_account_storage_deposit(
+        &mut self,
+        account_id: &AccountId,
+        deposit: &AccountStorageDeposit,
+    ) {
+        self.io.write_borsh(
+            &storage_key(
+                EthConnectorStorageId::AccountStorageDeposit,
+                account_id.as_bytes(),
+            ),
+            deposit,
+        );
+    }
+
     pub fn ft_total_eth_supply_on_near(&self) -> Balance {
         self.total_eth_supply_on_near
     }
@@ -295,14 +425,35 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         sender_id: AccountId,
         receiver_id: AccountId,
         amount: Balance,
-        memo: &Option<String>,
+        memo: &Option<Memo>,
         msg: String,
         current_account_id: AccountId,
         prepaid_gas: NearGas,
+        gas_for_on_transfer: Option<NearGas>,
     ) -> Result<PromiseWithCallbackArgs, error::TransferError> {
+        // The receiver gets all the gas left unless the caller bounds it.
+        let reserved_gas =
+            GAS_FOR_FT_TRANSFER_CALL.into_u64() + GAS_FOR_RESOLVE_TRANSFER.into_u64();
+        let on_transfer_gas = match gas_for_on_transfer {
+            None => (prepaid_gas - GAS_FOR_FT_TRANSFER_CALL - GAS_FOR_RESOLVE_TRANSFER).into_u64(),
+            Some(gas) if gas > MAX_GAS_FOR_FT_ON_TRANSFER => {
+                return Err(error::TransferError::GasForOnTransferTooHigh);
+            }
+            Some(gas) => {
+                if gas.into_u64() + reserved_gas > prepaid_gas.into_u64() {
+                    return Err(error::TransferError::NotEnoughGas);
+                }
+                gas.into_u64()
+            }
+        };
         // Special case for Aurora transfer itself - we shouldn't transfer
         if sender_id != receiver_id {
-            self.internal_transfer_eth_on_near(&sender_id, &receiver_id, amount, memo)?;
+            self.internal_transfer_eth_on_near(
+                &sender_id,
+                &receiver_id,
+                NEP141Wei::new(amount),
+                memo,
+            )?;
         }
         let data1: String = NEP141FtOnTransferArgs {
             amount,
@@ -325,8 +476,7 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
             method: "ft_on_transfer".to_string(),
             args: data1.into_bytes(),
             attached_balance: ZERO_ATTACHED_BALANCE,
-            attached_gas: (prepaid_gas - GAS_FOR_FT_TRANSFER_CALL - GAS_FOR_RESOLVE_TRANSFER)
-                .into_u64(),
+            attached_gas: on_transfer_gas,
         };
         let ft_resolve_transfer_call = PromiseCreateArgs {
             target_account_id: current_account_id,
@@ -395,11 +545,23 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
                         receiver_id,
                         sender_id
                     ));
+                    sdk::log!(&events::ft_transfer_log(&[events::FtTransfer {
+                        old_owner_id: receiver_id,
+                        new_owner_id: sender_id,
+                        amount: refund_amount,
+                        memo: Some("refund"),
+                    }]));
                     (amount - refund_amount, 0)
                 } else {
                     // Sender's account was deleted, so we need to burn tokens.
                     self.total_eth_supply_on_near -= refund_amount;
+                    self.record_burned_eth_on_near(refund_amount);
                     sdk::log!("The account of the sender was deleted");
+                    sdk::log!(&events::ft_burn_log(&[events::FtBurn {
+                        owner_id: receiver_id,
+                        amount: refund_amount,
+                        memo: Some("refund to deleted account"),
+                    }]));
                     (amount, refund_amount)
                 };
             }
@@ -425,13 +587,44 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
     ) -> Result<(Balance, PromiseBatchAction), error::StorageFundingError> {
         let force = force.unwrap_or(false);
         if let Some(balance) = self.get_account_eth_balance(&account_id) {
+            if freeze::is_account_frozen(&self.io, &account_id) {
+                return Err(error::StorageFundingError::AccountFrozen);
+            }
             if balance == 0 || force {
-                self.accounts_remove(&account_id);
-                self.total_eth_supply_on_near -= balance;
+                // The deposit is read while the account is still registered.
                 let storage_deposit = self.storage_balance_of(&account_id);
+                // The deposit paid by the storage pool goes back to it.
+                let refund = match self.get_account_storage_deposit(&account_id) {
+                    Some(deposit) if deposit.from_pool => {
+                        let pool = self.get_storage_pool();
+                        self.set_storage_pool(pool.saturating_add(deposit.total));
+                        0
+                    }
+                    _ => storage_deposit.total,
+                };
+                self.io.remove_storage(&storage_key(
+                    EthConnectorStorageId::AccountStorageDeposit,
+                    account_id.as_bytes(),
+                ));
+                self.accounts_remove(&account_id);
+                if balance > 0 {
+                    // The nETH of an account unregistered with `force` is burned.
+                    self.total_eth_supply_on_near -= balance;
+                    self.record_burned_eth_on_near(balance);
+                    sdk::log!(&crate::prelude::format!(
+                        "Burned {} of the unregistered account {}",
+                        balance,
+                        account_id
+                    ));
+                    sdk::log!(&events::ft_burn_log(&[events::FtBurn {
+                        owner_id: &account_id,
+                        amount: balance,
+                        memo: Some("force unregister"),
+                    }]));
+                }
                 let action = PromiseAction::Transfer {
                     // The `+ 1` is to cover the 1 yoctoNEAR necessary to call this function in the first place.
-                    amount: storage_deposit.total + 1,
+                    amount: refund + 1,
                 };
                 let promise = PromiseBatchAction {
                     target_account_id: account_id,
@@ -450,19 +643,31 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         }
     }
 
+    /// Cost of the storage used by an account.
+    fn account_storage_cost(&self) -> Balance {
+        Balance::from(self.account_storage_usage) * sdk::storage_byte_cost()
+    }
+
     pub fn storage_balance_bounds(&self) -> StorageBalanceBounds {
-        let required_storage_balance =
-            Balance::from(self.account_storage_usage) * sdk::storage_byte_cost();
+        let required_storage_balance = self
+            .storage_config()
+            .min_balance
+            .unwrap_or_else(|| self.account_storage_cost());
         StorageBalanceBounds {
             min: required_storage_balance,
             max: Some(required_storage_balance),
         }
     }
 
+    /// An account registered before the deposits were recorded paid the cost of the storage of
+    /// an account, the minimum at the time, whatever the minimum is now.
     pub fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
         if self.accounts_contains_key(account_id) {
+            let total = self
+                .get_account_storage_deposit(account_id)
+                .map_or_else(|| self.account_storage_cost(), |deposit| deposit.total);
             Some(StorageBalance {
-                total: self.storage_balance_bounds().min,
+                total,
                 available: 0,
             })
         } else {
@@ -503,6 +708,13 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
             }
 
             self.internal_register_account(account_id);
+            self.set_account_storage_deposit(
+                account_id,
+                &AccountStorageDeposit {
+                    total: min_balance,
+                    from_pool: false,
+                },
+            );
             let refund = amount - min_balance;
             if refund > 0 {
                 let action = PromiseAction::Transfer { amount: refund };
@@ -551,6 +763,11 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
                 .checked_add(1)
                 .expect("ERR_ACCOUNTS_COUNTER_OVERFLOW");
             self.io.write_storage(&key, &accounts_counter.to_le_bytes());
+            pagination::insert(
+                &mut self.io,
+                pagination::Collection::Accounts,
+                account_id.as_bytes(),
+            );
         }
         self.io
             .write_borsh(&Self::account_to_key(account_id), &amount);
@@ -566,7 +783,19 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
         self.io.storage_has_key(&Self::account_to_key(account_id))
     }
 
+    /// Remove account.
+    /// The account no longer counts in the total unique accounts.
     fn accounts_remove(&mut self, account_id: &AccountId) {
+        if self.accounts_contains_key(account_id) {
+            let key = Self::get_statistic_key();
+            let accounts_counter = self.io.read_u64(&key).unwrap_or(0).saturating_sub(1);
+            self.io.write_storage(&key, &accounts_counter.to_le_bytes());
+            pagination::remove(
+                &mut self.io,
+                pagination::Collection::Accounts,
+                account_id.as_bytes(),
+            );
+        }
         self.io.remove_storage(&Self::account_to_key(account_id));
     }
 
@@ -579,12 +808,14 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
 
     /// Fungible token key
     fn account_to_key(account_id: &AccountId) -> Vec<u8> {
-        let mut key = storage::bytes_to_key(
-            storage::KeyPrefix::EthConnector,
-            &[storage::EthConnectorStorageId::FungibleToken as u8],
-        );
-        key.extend_from_slice(account_id.as_bytes());
-        key
+        account_balance_key(account_id)
+    }
+
+    /// Adds the amount to the total of burned nETH.
+    fn record_burned_eth_on_near(&mut self, amount: Balance) {
+        // Can't overflow, because the burned amount is bounded by the total supply.
+        let burned = get_burned_eth_on_near(&self.io) + amount;
+        self.io.write_borsh(&burned_eth_on_near_key(), &burned);
     }
 
     /// Key for store contract statistics data
@@ -596,6 +827,45 @@ impl<I: IO + Copy> FungibleTokenOps<I> {
     }
 }
 
+fn storage_key(id: EthConnectorStorageId, suffix: &[u8]) -> Vec<u8> {
+    let mut key = storage::bytes_to_key(storage::KeyPrefix::EthConnector, &[id as u8]);
+    key.extend_from_slice(suffix);
+    key
+}
+
+/// Storage key of the nETH balance of the account, stored as a Borsh-encoded `u128`.
+pub fn account_balance_key(account_id: &AccountId) -> Vec<u8> {
+    let mut key = storage::bytes_to_key(
+        storage::KeyPrefix::EthConnector,
+        &[storage::EthConnectorStorageId::FungibleToken as u8],
+    );
+    key.extend_from_slice(account_id.as_bytes());
+    key
+}
+
+/// Returns what a NEAR state proof of the nETH balance of the account is made of.
+pub fn get_balance_storage_layout<I: IO>(io: &I, account_id: &AccountId) -> BalanceStorageLayout {
+    let storage_key = account_balance_key(account_id);
+    let value = io.read_storage(&storage_key).map(|value| value.to_vec());
+    BalanceStorageLayout { storage_key, value }
+}
+
+fn burned_eth_on_near_key() -> Vec<u8> {
+    storage::bytes_to_key(
+        storage::KeyPrefix::EthConnector,
+        &[storage::EthConnectorStorageId::BurnedEthOnNear as u8],
+    )
+}
+
+/// Total amount of nETH burned because the account of the sender of a transfer call
+/// was deleted before its resolution, or because an account holding nETH was unregistered
+/// with `force`.
+pub fn get_burned_eth_on_near<I: IO>(io: &I) -> Balance {
+    io.read_storage(&burned_eth_on_near_key())
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or(0)
+}
+
 pub mod error {
     use crate::prelude::types::error::BalanceOverflowError;
 
@@ -605,11 +875,17 @@ pub mod error {
     const TOTAL_SUPPLY_UNDERFLOW: &[u8; 26] = b"ERR_TOTAL_SUPPLY_UNDERFLOW";
     const ZERO_AMOUNT: &[u8; 15] = b"ERR_ZERO_AMOUNT";
     const SELF_TRANSFER: &[u8; 26] = b"ERR_SENDER_EQUALS_RECEIVER";
+    const ACCOUNT_FROZEN: &[u8; 18] = b"ERR_ACCOUNT_FROZEN";
+    const GAS_FOR_ON_TRANSFER_TOO_HIGH: &[u8; 32] = b"ERR_GAS_FOR_ON_TRANSFER_TOO_HIGH";
+    const NOT_ENOUGH_GAS: &[u8; 18] = b"ERR_NOT_ENOUGH_GAS";
+    const STORAGE_POOL_EXHAUSTED: &[u8; 26] = b"ERR_STORAGE_POOL_EXHAUSTED";
 
     #[derive(Debug)]
     pub enum DepositError {
         TotalSupplyOverflow,
         BalanceOverflow,
+        /// The storage pool can't pay for the registration of the recipient.
+        StoragePoolExhausted,
     }
 
     impl AsRef<[u8]> for DepositError {
@@ -617,6 +893,7 @@ pub mod error {
             match self {
                 Self::TotalSupplyOverflow => TOTAL_SUPPLY_OVERFLOW,
                 Self::BalanceOverflow => BALANCE_OVERFLOW,
+                Self::StoragePoolExhausted => STORAGE_POOL_EXHAUSTED,
             }
         }
     }
@@ -626,6 +903,7 @@ pub mod error {
         TotalSupplyUnderflow,
         InsufficientFunds,
         BalanceOverflow(BalanceOverflowError),
+        AccountFrozen,
     }
 
     impl AsRef<[u8]> for WithdrawError {
@@ -634,6 +912,7 @@ pub mod error {
                 Self::TotalSupplyUnderflow => TOTAL_SUPPLY_UNDERFLOW,
                 Self::InsufficientFunds => NOT_ENOUGH_BALANCE,
                 Self::BalanceOverflow(e) => e.as_ref(),
+                Self::AccountFrozen => ACCOUNT_FROZEN,
             }
         }
     }
@@ -646,6 +925,13 @@ pub mod error {
         BalanceOverflow,
         ZeroAmount,
         SelfTransfer,
+        AccountFrozen,
+        /// The gas asked for `ft_on_transfer` is above `MAX_GAS_FOR_FT_ON_TRANSFER`.
+        GasForOnTransferTooHigh,
+        /// The prepaid gas doesn't cover the gas asked for `ft_on_transfer`.
+        NotEnoughGas,
+        /// The storage pool can't pay for the registration of the receiver.
+        StoragePoolExhausted,
     }
 
     impl AsRef<[u8]> for TransferError {
@@ -657,6 +943,10 @@ pub mod error {
                 Self::BalanceOverflow => BALANCE_OVERFLOW,
                 Self::ZeroAmount => ZERO_AMOUNT,
                 Self::SelfTransfer => SELF_TRANSFER,
+                Self::AccountFrozen => ACCOUNT_FROZEN,
+                Self::GasForOnTransferTooHigh => GAS_FOR_ON_TRANSFER_TOO_HIGH,
+                Self::NotEnoughGas => NOT_ENOUGH_GAS,
+                Self::StoragePoolExhausted => STORAGE_POOL_EXHAUSTED,
             }
         }
     }
@@ -667,6 +957,7 @@ pub mod error {
                 WithdrawError::InsufficientFunds => Self::InsufficientFunds,
                 WithdrawError::TotalSupplyUnderflow => Self::TotalSupplyUnderflow,
                 WithdrawError::BalanceOverflow(_) => Self::BalanceOverflow,
+                WithdrawError::AccountFrozen => Self::AccountFrozen,
             }
         }
     }
@@ -676,6 +967,7 @@ pub mod error {
             match err {
                 DepositError::BalanceOverflow => Self::BalanceOverflow,
                 DepositError::TotalSupplyOverflow => Self::TotalSupplyOverflow,
+                DepositError::StoragePoolExhausted => Self::StoragePoolExhausted,
             }
         }
     }
@@ -686,6 +978,10 @@ pub mod error {
         NoAvailableBalance,
         InsufficientDeposit,
         UnRegisterPositiveBalance,
+        AccountFrozen,
+        StoragePoolExhausted,
+        /// The storage deposit is below the cost of the storage of an account.
+        MinBalanceTooLow,
     }
 
     impl AsRef<[u8]> for StorageFundingError {
@@ -697,6 +993,9 @@ pub mod error {
                 Self::UnRegisterPositiveBalance => {
                     b"ERR_FAILED_UNREGISTER_ACCOUNT_POSITIVE_BALANCE"
                 }
+                Self::AccountFrozen => ACCOUNT_FROZEN,
+                Self::StoragePoolExhausted => STORAGE_POOL_EXHAUSTED,
+                Self::MinBalanceTooLow => b"ERR_STORAGE_MIN_BALANCE_TOO_LOW",
             }
         }
     }
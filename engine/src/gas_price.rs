@@ -0,0 +1,140 @@
+//! Minimum gas price of the submitted transactions, kept up to date by the owner or by
+//! permissioned updater accounts.
+//!
+//! The relayers pay for the NEAR gas of the transactions they submit, and the NEAR gas price
+//! changes over time, so a static minimum can't cover their costs. The updaters set the price
+//! following the NEAR gas price, and the price is enforced by `submit` only while it is fresh:
+//! once it wasn't updated for longer than its maximum age, any gas price is accepted again until
+//! a new price is set, so that a stopped updater can't halt the transactions.
+use crate::parameters::GasPriceResult;
+use crate::prelude::{sdk, AccountId, BorshDeserialize, BorshSerialize, ConfigKey, RawU256, U256};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, PartialEq, Eq)]
+struct GasPriceOracle {
+    price: RawU256,
+    /// Block timestamp of the last update, in seconds.
+    updated_at: u64,
+    /// Number of seconds after an update the price is enforced for, zero for no limit.
+    max_age: u64,
+}
+
+fn get_oracle<I: IO>(io: &I) -> GasPriceOracle {
+    storage_key::read_config(io, ConfigKey::GasPrice, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+fn set_oracle<I: IO>(io: &mut I, oracle: &GasPriceOracle) {
+    storage_key::write_config_borsh(io, ConfigKey::GasPrice, &[], oracle);
+}
+
+impl GasPriceOracle {
+    fn is_stale(&self, now: u64) -> bool {
+        self.max_age > 0 && now > self.updated_at.saturating_add(self.max_age)
+    }
+}
+
+pub fn is_gas_price_updater<I: IO>(io: &I, account_id: &AccountId) -> bool {
+    storage_key::has_config(io, ConfigKey::GasPriceUpdater, account_id.as_bytes())
+}
+
+/// Allows or disallows the account to update the gas price.
+pub fn set_gas_price_updater<I: IO>(io: &mut I, account_id: &AccountId, allowed: bool) {
+    if allowed {
+        storage_key::write_config(io, ConfigKey::GasPriceUpdater, account_id.as_bytes(), &[1]);
+    } else {
+        storage_key::remove_config(io, ConfigKey::GasPriceUpdater, account_id.as_bytes());
+    }
+}
+
+/// Sets the minimum gas price, can be called by the owner or by an updater.
+pub fn set_gas_price<I: IO>(
+    io: &mut I,
+    owner_id: &AccountId,
+    predecessor_account_id: &AccountId,
+    price: RawU256,
+    now: u64,
+) -> Result<(), error::GasPriceError> {
+    if predecessor_account_id != owner_id && !is_gas_price_updater(io, predecessor_account_id) {
+        return Err(error::GasPriceError::NotAllowed);
+    }
+    let mut oracle = get_oracle(io);
+    oracle.price = price;
+    oracle.updated_at = now;
+    set_oracle(io, &oracle);
+    sdk::log!(&crate::prelude::format!(
+        "Gas price set to {} by {}",
+        U256::from(price),
+        predecessor_account_id
+    ));
+    Ok(())
+}
+
+/// Sets the number of seconds after an update the price is enforced for.
+pub fn set_gas_price_max_age<I: IO>(io: &mut I, max_age: u64) {
+    let mut oracle = get_oracle(io);
+    oracle.max_age = max_age;
+    set_oracle(io, &oracle);
+}
+
+pub fn get_gas_price<I: IO>(io: &I, now: u64) -> GasPriceResult {
+    let oracle = get_oracle(io);
+    GasPriceResult {
+        price: oracle.price,
+        updated_at: oracle.updated_at,
+        max_age: oracle.max_age,
+        is_stale: oracle.is_stale(now),
+    }
+}
+
+/// Returns the gas price below which the transactions are rejected, zero if the price is stale.
+pub fn min_gas_price<I: IO>(io: &I, now: u64) -> U256 {
+    let oracle = get_oracle(io);
+    if oracle.is_stale(now) {
+        U256::zero()
+    } else {
+        U256::from(oracle.price)
+    }
+}
+
+pub mod error {
+    const NOT_ALLOWED: &[u8; 28] = b"ERR_NOT_ALLOWED_TO_SET_PRICE";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum GasPriceError {
+        /// The predecessor is neither the owner nor an updater.
+        NotAllowed,
+    }
+
+    impl AsRef<[u8]> for GasPriceError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotAllowed => NOT_ALLOWED,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_staleness() {
+        let oracle = GasPriceOracle {
+            price: U256::from(7).into(),
+            updated_at: 100,
+            max_age: 10,
+        };
+        assert!(!oracle.is_stale(110));
+        assert!(oracle.is_stale(111));
+
+        let oracle = GasPriceOracle {
+            max_age: 0,
+            ..oracle
+        };
+        assert!(!oracle.is_stale(u64::MAX));
+    }
+}
@@ -0,0 +1,336 @@
+//! Maximum length of the input of every method of the contract.
+//!
+//! The input is checked before it is deserialized, so an oversized payload is rejected for
+//! the cost of reading its length instead of the one of parsing it with borsh or JSON. The
+//! methods taking transactions, proofs or contract code have the limits of their own below,
+//! every other method the default one. The owner can override the limit of any method under
+//! `ConfigKey::InputLimit`. The error tells which kind of limit was hit.
+use crate::connector::MAX_DEPOSIT_BATCH_SIZE;
+use crate::prelude::ConfigKey;
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Limit of the methods missing from `METHOD_INPUT_LIMITS`.
+pub const DEFAULT_MAX_INPUT_LEN: usize = 64 * 1024;
+
+/// Limit of the methods taking EVM transactions or calls, which carry the init code of the
+/// contracts deployed.
+pub const MAX_TRANSACTION_INPUT_LEN: usize = 512 * 1024;
+
+/// Limit of the methods taking the proofs of Ethereum receipts.
+pub const MAX_PROOF_INPUT_LEN: usize = 128 * 1024;
+
+/// Limit of the callbacks of the deposits, which receive the proof accepted by `deposit`
+/// along with the arguments taken from its event, at most as long as the proof itself.
+pub const MAX_DEPOSIT_CALLBACK_INPUT_LEN: usize = 2 * MAX_PROOF_INPUT_LEN;
+
+/// Limit of the methods taking contract code or state, the 4 MiB NEAR accepts as arguments.
+pub const MAX_STATE_INPUT_LEN: usize = 4 * 1024 * 1024;
+
+pub const MAX_METADATA_INPUT_LEN: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    Transaction,
+    Proof,
+    State,
+    /// Metadata of nETH, whose icon can be an image in a data URL.
+    Metadata,
+}
+
+/// Kind and limit of the input of the methods.
+const METHOD_INPUT_LIMITS: &[(&str, InputKind, usize)] = &[
+    ("submit", InputKind::Transaction, MAX_TRANSACTION_INPUT_LEN),
+    ("submit_batch", InputKind::Transaction, MAX_STATE_INPUT_LEN),
+    (
+        "submit_with_fee_payer",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    (
+        "meta_call",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    ("handle_ops", InputKind::Transaction, MAX_STATE_INPUT_LEN),
+    ("call", InputKind::Transaction, MAX_TRANSACTION_INPUT_LEN),
+    (
+        "deploy_code",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    ("view", InputKind::Transaction, MAX_TRANSACTION_INPUT_LEN),
+    (
+        "eth_call",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    (
+        "dry_run_submit",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    (
+        "propose_multisig_call",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    (
+        "register_abi_schema",
+        InputKind::Transaction,
+        MAX_TRANSACTION_INPUT_LEN,
+    ),
+    ("deposit", InputKind::Proof, MAX_PROOF_INPUT_LEN),
+    (
+        "deposit_batch",
+        InputKind::Proof,
+        MAX_DEPOSIT_BATCH_SIZE * MAX_PROOF_INPUT_LEN,
+    ),
+    ("deposit_erc20", InputKind::Proof, MAX_PROOF_INPUT_LEN),
+    (
+        "verify_log_entry",
+        InputKind::Proof,
+        MAX_DEPOSIT_CALLBACK_INPUT_LEN,
+    ),
+    (
+        "resolve_deposit_verification",
+        InputKind::Proof,
+        MAX_DEPOSIT_CALLBACK_INPUT_LEN,
+    ),
+    (
+        "finish_deposit",
+        InputKind::Proof,
+        MAX_DEPOSIT_CALLBACK_INPUT_LEN,
+    ),
+    (
+        "finish_deposit_erc20",
+        InputKind::Proof,
+        MAX_DEPOSIT_CALLBACK_INPUT_LEN,
+    ),
+    (
+        "estimate_deposit_gas",
+        InputKind::Proof,
+        MAX_PROOF_INPUT_LEN,
+    ),
+    ("check_proofs", InputKind::Proof, MAX_TRANSACTION_INPUT_LEN),
+    ("is_used_proof", InputKind::Proof, MAX_PROOF_INPUT_LEN),
+    ("silo_bridge_receive", InputKind::Proof, MAX_PROOF_INPUT_LEN),
+    ("stage_upgrade", InputKind::State, MAX_STATE_INPUT_LEN),
+    ("apply_state_diff", InputKind::State, MAX_STATE_INPUT_LEN),
+    ("begin_chain", InputKind::State, MAX_STATE_INPUT_LEN),
+    (
+        "new_eth_connector",
+        InputKind::Metadata,
+        MAX_METADATA_INPUT_LEN,
+    ),
+    (
+        "set_eth_connector_contract_data",
+        InputKind::Metadata,
+        MAX_METADATA_INPUT_LEN,
+    ),
+    ("set_metadata", InputKind::Metadata, MAX_METADATA_INPUT_LEN),
+    (
+        "set_icon_chunk",
+        InputKind::Metadata,
+        MAX_METADATA_INPUT_LEN,
+    ),
+];
+
+/// Method whose limit can't be overridden, so that a wrong override can always be undone.
+const SET_INPUT_LIMIT_METHOD: &str = "set_input_limit";
+
+/// Kind and limit of the input of the method, `None` for the default limit.
+fn method_limit(method: &str) -> Option<(InputKind, usize)> {
+    METHOD_INPUT_LIMITS
+        .iter()
+        .find(|(name, _, _)| *name == method)
+        .map(|(_, kind, max_len)| (*kind, *max_len))
+}
+
+/// Limit of the method set by the owner, if any.
+pub fn get_input_limit_override<I: IO>(io: &I, method: &str) -> Option<usize> {
+    storage_key::read_config_u64(io, ConfigKey::InputLimit, method.as_bytes())
+        .map(|max_len| max_len as usize)
+}
+
+/// Overrides the limit of the method, or restores its default one if `max_len` is `None`.
+pub fn set_input_limit_override<I: IO>(
+    io: &mut I,
+    method: &str,
+    max_len: Option<u64>,
+) -> Result<(), error::SetInputLimitError> {
+    if method == SET_INPUT_LIMIT_METHOD {
+        return Err(error::SetInputLimitError::NotConfigurable);
+    }
+    match max_len {
+        Some(max_len) if max_len > MAX_STATE_INPUT_LEN as u64 => {
+            return Err(error::SetInputLimitError::LimitTooHigh);
+        }
+        Some(max_len) => storage_key::write_config(
+            io,
+            ConfigKey::InputLimit,
+            method.as_bytes(),
+            &max_len.to_le_bytes(),
+        ),
+        None => storage_key::remove_config(io, ConfigKey::InputLimit, method.as_bytes()),
+    }
+    Ok(())
+}
+
+pub fn max_input_len<I: IO>(io: &I, method: &str) -> usize {
+    get_input_limit_override(io, method).unwrap_or_else(|| {
+        method_limit(method).map_or(DEFAULT_MAX_INPUT_LEN, |(_, max_len)| max_len)
+    })
+}
+
+pub fn check_input_len<I: IO>(
+    io: &I,
+    method: &str,
+    len: usize,
+) -> Result<(), error::InputLimitError> {
+    let kind = method_limit(method).map(|(kind, _)| kind);
+    let max_len = max_input_len(io, method);
+    if len <= max_len {
+        return Ok(());
+    }
+    Err(match kind {
+        Some(InputKind::Transaction) => error::InputLimitError::TransactionTooLarge,
+        Some(InputKind::Proof) => error::InputLimitError::ProofTooLarge,
+        Some(InputKind::State) => error::InputLimitError::StateTooLarge,
+        Some(InputKind::Metadata) => error::InputLimitError::MetadataTooLarge,
+        None => error::InputLimitError::TooLarge,
+    })
+}
+
+/// Checks the input of the call, without deserializing it, against the limit of the method.
+pub fn check_input<I: IO>(io: &I, method: &str) -> Result<(), error::InputLimitError> {
+    check_input_len(io, method, io.read_input().len())
+}
+
+pub mod error {
+    const INPUT_TOO_LARGE: &[u8; 19] = b"ERR_INPUT_TOO_LARGE";
+    const TRANSACTION_INPUT_TOO_LARGE: &[u8; 31] = b"ERR_TRANSACTION_INPUT_TOO_LARGE";
+    const PROOF_INPUT_TOO_LARGE: &[u8; 25] = b"ERR_PROOF_INPUT_TOO_LARGE";
+    const STATE_INPUT_TOO_LARGE: &[u8; 25] = b"ERR_STATE_INPUT_TOO_LARGE";
+    const METADATA_INPUT_TOO_LARGE: &[u8; 28] = b"ERR_METADATA_INPUT_TOO_LARGE";
+    const INPUT_LIMIT_NOT_CONFIGURABLE: &[u8; 32] = b"ERR_INPUT_LIMIT_NOT_CONFIGURABLE";
+    const INPUT_LIMIT_TOO_HIGH: &[u8; 24] = b"ERR_INPUT_LIMIT_TOO_HIGH";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum InputLimitError {
+        /// The input is above the default limit.
+        TooLarge,
+        TransactionTooLarge,
+        ProofTooLarge,
+        StateTooLarge,
+        MetadataTooLarge,
+    }
+
+    impl AsRef<[u8]> for InputLimitError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::TooLarge => INPUT_TOO_LARGE,
+                Self::TransactionTooLarge => TRANSACTION_INPUT_TOO_LARGE,
+                Self::ProofTooLarge => PROOF_INPUT_TOO_LARGE,
+                Self::StateTooLarge => STATE_INPUT_TOO_LARGE,
+                Self::MetadataTooLarge => METADATA_INPUT_TOO_LARGE,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SetInputLimitError {
+        /// The limit of `set_input_limit` itself can't be overridden.
+        NotConfigurable,
+        /// The limit is above the arguments NEAR accepts.
+        LimitTooHigh,
+    }
+
+    impl AsRef<[u8]> for SetInputLimitError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NotConfigurable => INPUT_LIMIT_NOT_CONFIGURABLE,
+                Self::LimitTooHigh => INPUT_LIMIT_TOO_HIGH,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use aurora_engine_sdk::memory;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_check_input_len() {
+        let storage = RefCell::new(memory::Storage::default());
+        let io = memory::StoragePointer(&storage);
+
+        assert_eq!(max_input_len(&io, "get_version"), DEFAULT_MAX_INPUT_LEN);
+        assert!(check_input_len(&io, "get_version", DEFAULT_MAX_INPUT_LEN).is_ok());
+        assert_eq!(
+            check_input_len(&io, "get_version", DEFAULT_MAX_INPUT_LEN + 1),
+            Err(error::InputLimitError::TooLarge)
+        );
+
+        assert!(check_input_len(&io, "submit", DEFAULT_MAX_INPUT_LEN + 1).is_ok());
+        assert_eq!(
+            check_input_len(&io, "submit", MAX_TRANSACTION_INPUT_LEN + 1),
+            Err(error::InputLimitError::TransactionTooLarge)
+        );
+        assert_eq!(
+            check_input_len(&io, "deposit", MAX_PROOF_INPUT_LEN + 1),
+            Err(error::InputLimitError::ProofTooLarge)
+        );
+        assert!(check_input_len(&io, "stage_upgrade", MAX_STATE_INPUT_LEN).is_ok());
+
+        // The callbacks of a deposit accept the proof `deposit` accepted.
+        for callback in &["resolve_deposit_verification", "finish_deposit"] {
+            assert!(max_input_len(&io, callback) > MAX_PROOF_INPUT_LEN);
+        }
+    }
+
+    #[test]
+    fn test_input_limit_override() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+
+        // The override keeps the kind of the limit of the method.
+        set_input_limit_override(&mut io, "submit", Some(100)).unwrap();
+        assert_eq!(max_input_len(&io, "submit"), 100);
+        assert_eq!(
+            check_input_len(&io, "submit", 101),
+            Err(error::InputLimitError::TransactionTooLarge)
+        );
+        set_input_limit_override(&mut io, "get_version", Some(MAX_STATE_INPUT_LEN as u64)).unwrap();
+        assert!(check_input_len(&io, "get_version", DEFAULT_MAX_INPUT_LEN + 1).is_ok());
+
+        set_input_limit_override(&mut io, "submit", None).unwrap();
+        assert_eq!(max_input_len(&io, "submit"), MAX_TRANSACTION_INPUT_LEN);
+        assert_eq!(get_input_limit_override(&io, "submit"), None);
+
+        assert_eq!(
+            set_input_limit_override(&mut io, "set_input_limit", Some(0)),
+            Err(error::SetInputLimitError::NotConfigurable)
+        );
+        assert_eq!(
+            set_input_limit_override(&mut io, "submit", Some(MAX_STATE_INPUT_LEN as u64 + 1)),
+            Err(error::SetInputLimitError::LimitTooHigh)
+        );
+    }
+
+    #[test]
+    fn test_method_input_limits_are_unique() {
+        for (i, (method, _, _)) in METHOD_INPUT_LIMITS.iter().enumerate() {
+            assert!(
+                METHOD_INPUT_LIMITS[i + 1..]
+                    .iter()
+                    .all(|(other, _, _)| other != method),
+                "{} is listed twice",
+                method
+            );
+        }
+    }
+}
@@ -55,6 +55,16 @@ impl JsonValue {
         }
     }
 
+    pub fn array(&self, key: &str) -> Result<&[JsonValue], JsonError> {
+        match self {
+            JsonValue::Object(o) => match o.get(key).ok_or(JsonError::MissingValue)? {
+                JsonValue::Array(a) => Ok(a),
+                _ => Err(JsonError::InvalidArray),
+            },
+            _ => Err(JsonError::NotJsonType),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn u64(&self, key: &str) -> Result<u64, JsonError> {
         match self {
@@ -237,7 +247,7 @@ impl core::fmt::Debug for JsonValue {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             JsonValue::Null => f.write_str("null"),
-            JsonValue::String(v) => f.write_fmt(format_args!("\"{}\"", v)),
+            JsonValue::String(v) => write_escaped_str(f, v),
             JsonValue::F64(v) => f.write_fmt(format_args!("{}", v)),
             JsonValue::I64(v) => f.write_fmt(format_args!("{}", v)),
             JsonValue::U64(v) => f.write_fmt(format_args!("{}", v)),
@@ -257,10 +267,13 @@ impl core::fmt::Debug for JsonValue {
                 f.write_str("{")?;
                 let mut pairs = kvs.iter();
                 if let Some((key, value)) = pairs.next() {
-                    f.write_fmt(format_args!("\"{}\": {:?}", key, value))?;
+                    write_escaped_str(f, key)?;
+                    f.write_fmt(format_args!(": {:?}", value))?;
                 }
                 for (key, value) in pairs {
-                    f.write_fmt(format_args!(", \"{}\": {:?}", key, value))?;
+                    f.write_str(", ")?;
+                    write_escaped_str(f, key)?;
+                    f.write_fmt(format_args!(": {:?}", value))?;
                 }
                 f.write_str("}")
             }
@@ -268,6 +281,24 @@ impl core::fmt::Debug for JsonValue {
     }
 }
 
+/// Writes the string as a JSON string literal, escaping the characters which can't appear
+/// in it as is.
+fn write_escaped_str(f: &mut core::fmt::Formatter, value: &str) -> core::fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => f.write_fmt(format_args!("\\u{:04x}", c as u32))?,
+            c => f.write_fmt(format_args!("{}", c))?,
+        }
+    }
+    f.write_str("\"")
+}
+
 impl core::fmt::Display for JsonValue {
     fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         f.write_fmt(format_args!("{:?}", *self))
@@ -554,5 +585,18 @@ mod tests {
             &format!("{}", object),
             "{\"words\": [\"Hello\", \"World\"]}"
         );
+        // strings are escaped
+        let object = JsonValue::Object(
+            vec![(
+                "quote\"".to_string(),
+                JsonValue::String("say \"hi\"\\\n\u{1}".to_string()),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(
+            &format!("{}", object),
+            "{\"quote\\\"\": \"say \\\"hi\\\"\\\\\\n\\u0001\"}"
+        );
     }
 }
@@ -0,0 +1,201 @@
+//! Arguments and results of the views in the format of the Ethereum JSON-RPC.
+//!
+//! The `eth_*` views take a JSON object holding the parameters of the JSON-RPC method of the
+//! same name, and return the JSON value of its result, so a relayer can answer the queries of
+//! the Ethereum tooling against the state of the engine without converting them to Borsh. The
+//! addresses, hashes and data are `0x` prefixed hex strings, the quantities hex numbers without
+//! leading zeros. There is no block parameter, the views read the current state.
+use crate::json::{JsonError, JsonValue};
+use crate::parameters::{TransactionStatus, ViewCallArgs};
+use crate::prelude::{format, Address, BTreeMap, String, ToString, Vec, H256, U256};
+
+/// Parameters of `eth_call`: `to`, and optionally `from`, `value` and `data`.
+pub fn parse_call_args(value: &JsonValue) -> Result<ViewCallArgs, error::JsonRpcError> {
+    let sender = match optional_string(value, "from")? {
+        Some(from) => parse_address(&from)?,
+        None => Address::zero(),
+    };
+    let amount = match optional_string(value, "value")? {
+        Some(amount) => parse_quantity(&amount)?,
+        None => U256::zero(),
+    };
+    let input = match optional_string(value, "data")? {
+        Some(data) => parse_data(&data)?,
+        None => Vec::new(),
+    };
+    let mut raw_amount = [0u8; 32];
+    amount.to_big_endian(&mut raw_amount);
+    Ok(ViewCallArgs {
+        sender: sender.0,
+        address: parse_address(&value.string("to")?)?.0,
+        amount: raw_amount,
+        input,
+    })
+}
+
+/// Parameter `address` of `eth_getBalance`, `eth_getCode` and `eth_getTransactionCount`.
+pub fn parse_address_args(value: &JsonValue) -> Result<Address, error::JsonRpcError> {
+    parse_address(&value.string("address")?)
+}
+
+/// Parameters `address` and `key` of `eth_getStorageAt`.
+pub fn parse_storage_at_args(value: &JsonValue) -> Result<(Address, H256), error::JsonRpcError> {
+    let address = parse_address_args(value)?;
+    let mut key = [0u8; 32];
+    parse_quantity(&value.string("key")?)?.to_big_endian(&mut key);
+    Ok((address, H256(key)))
+}
+
+/// Result of `eth_call`, the output and the status of the call. The status is `succeed`,
+/// `revert` (with the revert data as output), or the name of the error of the call.
+pub fn call_result(status: &TransactionStatus) -> JsonValue {
+    let (status, output): (&str, &[u8]) = match status {
+        TransactionStatus::Succeed(output) => ("succeed", output),
+        TransactionStatus::Revert(output) => ("revert", output),
+        TransactionStatus::OutOfGas => ("out_of_gas", &[]),
+        TransactionStatus::OutOfFund => ("out_of_fund", &[]),
+        TransactionStatus::OutOfOffset => ("out_of_offset", &[]),
+        TransactionStatus::CallTooDeep => ("call_too_deep", &[]),
+        TransactionStatus::MemoryLimit => ("memory_limit", &[]),
+    };
+    let mut kvs = BTreeMap::new();
+    kvs.insert("output".to_string(), data(output));
+    kvs.insert("status".to_string(), JsonValue::String(status.to_string()));
+    JsonValue::Object(kvs)
+}
+
+pub fn quantity(value: U256) -> JsonValue {
+    JsonValue::String(format!("0x{:x}", value))
+}
+
+pub fn data(bytes: &[u8]) -> JsonValue {
+    JsonValue::String(format!("0x{}", hex::encode(bytes)))
+}
+
+fn optional_string(value: &JsonValue, key: &str) -> Result<Option<String>, JsonError> {
+    match value {
+        JsonValue::Object(o) => match o.get(key) {
+            None | Some(JsonValue::Null) => Ok(None),
+            Some(JsonValue::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(JsonError::InvalidString),
+        },
+        _ => Err(JsonError::NotJsonType),
+    }
+}
+
+fn strip_hex_prefix(value: &str) -> Result<&str, error::JsonRpcError> {
+    value
+        .strip_prefix("0x")
+        .ok_or(error::JsonRpcError::MissingHexPrefix)
+}
+
+fn parse_address(value: &str) -> Result<Address, error::JsonRpcError> {
+    let bytes =
+        hex::decode(strip_hex_prefix(value)?).map_err(|_| error::JsonRpcError::InvalidAddress)?;
+    if bytes.len() != 20 {
+        return Err(error::JsonRpcError::InvalidAddress);
+    }
+    Ok(Address::from_slice(&bytes))
+}
+
+fn parse_quantity(value: &str) -> Result<U256, error::JsonRpcError> {
+    let digits = strip_hex_prefix(value)?;
+    if digits.is_empty() || digits.len() > 64 {
+        return Err(error::JsonRpcError::InvalidQuantity);
+    }
+    let padded = if digits.len() % 2 == 1 {
+        format!("0{}", digits)
+    } else {
+        digits.to_string()
+    };
+    let bytes = hex::decode(padded).map_err(|_| error::JsonRpcError::InvalidQuantity)?;
+    Ok(U256::from_big_endian(&bytes))
+}
+
+fn parse_data(value: &str) -> Result<Vec<u8>, error::JsonRpcError> {
+    hex::decode(strip_hex_prefix(value)?).map_err(|_| error::JsonRpcError::InvalidData)
+}
+
+pub mod error {
+    use crate::json::JsonError;
+
+    const MISSING_HEX_PREFIX: &[u8; 22] = b"ERR_MISSING_HEX_PREFIX";
+    const INVALID_ADDRESS: &[u8; 19] = b"ERR_INVALID_ADDRESS";
+    const INVALID_QUANTITY: &[u8; 20] = b"ERR_INVALID_QUANTITY";
+    const INVALID_DATA: &[u8; 16] = b"ERR_INVALID_DATA";
+
+    #[cfg_attr(test, derive(Debug))]
+    #[derive(PartialEq, Eq)]
+    pub enum JsonRpcError {
+        Json(JsonError),
+        MissingHexPrefix,
+        /// The value isn't the hex encoding of 20 bytes.
+        InvalidAddress,
+        /// The value isn't a hex number of at most 256 bits.
+        InvalidQuantity,
+        InvalidData,
+    }
+
+    impl From<JsonError> for JsonRpcError {
+        fn from(e: JsonError) -> Self {
+            Self::Json(e)
+        }
+    }
+
+    impl AsRef<[u8]> for JsonRpcError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::Json(e) => e.as_ref(),
+                Self::MissingHexPrefix => MISSING_HEX_PREFIX,
+                Self::InvalidAddress => INVALID_ADDRESS,
+                Self::InvalidQuantity => INVALID_QUANTITY,
+                Self::InvalidData => INVALID_DATA,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::parse_json;
+
+    #[test]
+    fn test_parse_call_args() {
+        let value = parse_json(
+            br#"{"from": "0x1111111111111111111111111111111111111111", "to": "0x2222222222222222222222222222222222222222", "value": "0x3e8", "data": "0xabcd"}"#,
+        )
+        .unwrap();
+        let args = parse_call_args(&value).unwrap();
+        assert_eq!(args.sender, [0x11; 20]);
+        assert_eq!(args.address, [0x22; 20]);
+        assert_eq!(U256::from_big_endian(&args.amount), U256::from(1000));
+        assert_eq!(args.input, vec![0xab, 0xcd]);
+
+        let value = parse_json(br#"{"to": "0x2222222222222222222222222222222222222222"}"#).unwrap();
+        let args = parse_call_args(&value).unwrap();
+        assert_eq!(args.sender, [0; 20]);
+        assert_eq!(args.amount, [0; 32]);
+        assert!(args.input.is_empty());
+
+        for invalid in [
+            r#"{"from": "0x1111111111111111111111111111111111111111"}"#,
+            r#"{"to": "2222222222222222222222222222222222222222"}"#,
+            r#"{"to": "0x22"}"#,
+            r#"{"to": "0x2222222222222222222222222222222222222222", "value": "0x"}"#,
+        ] {
+            assert!(parse_call_args(&parse_json(invalid.as_bytes()).unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_json_rpc_results() {
+        assert_eq!(quantity(U256::zero()).to_string(), r#""0x0""#);
+        assert_eq!(quantity(U256::from(1000)).to_string(), r#""0x3e8""#);
+        assert_eq!(data(&[0, 1]).to_string(), r#""0x0001""#);
+        assert_eq!(
+            call_result(&TransactionStatus::Revert(vec![0x08])).to_string(),
+            r#"{"output": "0x08", "status": "revert"}"#
+        );
+    }
+}
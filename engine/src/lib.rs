@@ -17,15 +17,43 @@ pub mod parameters;
 pub mod proof;
 pub mod transaction;
 
+pub mod access_control;
 pub mod admin_controlled;
+pub mod admin_log;
+pub mod callback;
+pub mod capabilities;
+pub mod compression;
 #[cfg_attr(feature = "contract", allow(dead_code))]
 pub mod connector;
+pub mod connector_migration;
 pub mod deposit_event;
 pub mod engine;
+pub mod engine_logs;
+pub mod engine_sequence;
+pub mod entry_point;
+pub mod eth_rlp;
+pub mod eth_stream;
+pub mod events;
+pub mod freeze;
 pub mod fungible_token;
+pub mod gas_price;
+pub mod input_limits;
 pub mod json;
+pub mod json_rpc;
 pub mod log_entry;
+pub mod multisig;
+pub mod pagination;
 mod prelude;
+pub mod scheduled_tasks;
+pub mod silo_bridge;
+pub mod state_diff;
+pub mod storage_breakdown;
+pub mod storage_key;
+pub mod system_addresses;
+pub mod test_chain;
+pub mod token_supply;
+pub mod view_chunk;
+pub mod witness;
 
 #[cfg(target_arch = "wasm32")]
 #[global_allocator]
@@ -65,41 +93,97 @@ pub unsafe fn on_alloc_error(_: core::alloc::Layout) -> ! {
 #[cfg(feature = "contract")]
 mod contract {
     use borsh::{BorshDeserialize, BorshSerialize};
-
-    use crate::connector::{self, EthConnectorContract};
-    use crate::engine::{self, current_address, Engine, EngineState};
-    use crate::fungible_token::FungibleTokenMetadata;
+    use core::cell::Cell;
+
+    use crate::access_control;
+    use crate::admin_log;
+    use crate::callback;
+    use crate::capabilities;
+    use crate::compression;
+    use crate::connector::error::{ConnectorError, DepositError};
+    use crate::connector::{self, DepositVerification, EthConnectorContract, FinishDepositResult};
+    use crate::connector_migration;
+    use crate::engine::{self, current_address, Engine, EngineState, CODE_KEY, CODE_STAGE_KEY};
+    use crate::engine_logs;
+    use crate::engine_sequence;
+    use crate::entry_point;
+    use crate::eth_stream;
+    use crate::events;
+    use crate::freeze;
+    use crate::fungible_token::{self, FungibleTokenMetadata};
+    use crate::gas_price;
+    use crate::input_limits;
+    use crate::json_rpc;
+    use crate::multisig;
+    #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+    use crate::parameters::BeginBlockArgs;
+    #[cfg(feature = "evm_bully")]
+    use crate::parameters::BeginChainArgs;
     use crate::parameters::{
-        self, CallArgs, DeployErc20TokenArgs, GetErc20FromNep141CallArgs, GetStorageAtArgs,
-        InitCallArgs, IsUsedProofCallArgs, NEP141FtOnTransferArgs, NewCallArgs,
-        PauseEthConnectorCallArgs, ResolveTransferCallArgs, SetContractDataCallArgs,
-        StorageDepositCallArgs, StorageWithdrawCallArgs, TransferCallCallArgs, ViewCallArgs,
+        self, AddLightClientBlockHashArgs, ApplyStateDiffArgs, BlockAttributes, CallArgs,
+        CheckProofsCallArgs, CheckTokenSupplyArgs, ClaimUnclaimedDepositArgs,
+        CreateMultisigCallArgs, CreateStreamCallArgs, CustodianArgs, DeployEntryPointArgs,
+        DeployErc20TokenArgs, DepositFeeCurrency, EstimateWithdrawArgs, EvmLimits,
+        FinishDepositCallArgs, FinishDepositErc20CallArgs, ForceResolveTransferCallArgs,
+        FreezeTarget, GetBalancesCallArgs, GetBridgedNep141Args, GetDepositReceiptCallArgs,
+        GetDepositsBySenderCallArgs, GetErc20BalanceArgs, GetErc20FromNep141CallArgs,
+        GetRelayerArgs, GetRelayerEarningsArgs, GetStorageAtArgs, GetUnclaimedDepositArgs,
+        HandleOpsArgs, HasRoleArgs, InitCallArgs, IsFeeWaivedCallArgs, IsUsedProofCallArgs,
+        IsUsedProofKeyCallArgs, MinDepositArgs, MintCircuitBreakerArgs, MultisigIdCallArgs,
+        MultisigProposalCallArgs, NEP141FtOnTransferArgs, NewCallArgs, PageArgs,
+        PauseEthConnectorCallArgs, PausePrecompilesCallArgs, ProofCacheConfig,
+        ProposeMultisigCallArgs, ProposeOwnerArgs, ReassignUnclaimedArgs, RecipientRules,
+        RegisterAbiSchemaArgs, RegisterErc20TokenArgs, ResolveDepositVerificationArgs,
+        ResolveTransferCallArgs, Role, RunDueTasksArgs, SetCallbackTicketsRequiredArgs,
+        SetChainIdArgs, SetCodeCompressionArgs, SetContractDataCallArgs, SetDepositProtocolFeeArgs,
+        SetDuplicateSubmitWindowArgs, SetErc20LockerArgs, SetFeeWaiverArgs,
+        SetForceResolveDelayCallArgs, SetGasPriceArgs, SetGasPriceMaxAgeArgs,
+        SetGasPriceUpdaterArgs, SetIconChunkArgs, SetInputLimitArgs, SetLegacyLogsArgs,
+        SetLightClientArgs, SetLogBloomIndexArgs, SetPriorityFeeSplitArgs, SetProversArgs,
+        SetRemoteSiloAllowedArgs, SetRoleArgs, SetScheduledTasksArgs,
+        SetStorageBreakdownEnabledArgs, SetTokenPausedArgs, SetUnclaimedDepositArgs,
+        SetUnprotectedTxsArgs, SetUpgradeDelayBlocksArgs, SetWitnessSizeLimitArgs,
+        SiloBridgeReceiveArgs, SiloBridgeResolveTransferArgs, SiloBridgeTransferArgs,
+        StorageConfig, StorageDepositCallArgs, StorageWithdrawCallArgs, StreamIdCallArgs,
+        SubmitBatchArgs, SupportsCapabilityCallArgs, TransferCallCallArgs, ViewCallArgs,
+        ViewChunkArgs, ViewRequest, WithdrawProtocolFeesArgs,
     };
-    #[cfg(feature = "evm_bully")]
-    use crate::parameters::{BeginBlockArgs, BeginChainArgs};
+    use crate::proof::Proof;
+    use crate::scheduled_tasks;
+    use crate::silo_bridge;
+    use crate::state_diff;
+    use crate::storage_breakdown::{self, TrackedRuntime as Runtime};
+    use crate::storage_key;
+    use crate::system_addresses;
+    #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+    use crate::test_chain;
+    use crate::token_supply;
+    use crate::view_chunk;
+    use crate::witness::{self, WitnessSize, WitnessTracker};
     use aurora_engine_sdk::env::Env;
     use aurora_engine_sdk::io::{StorageIntermediate, IO};
-    use aurora_engine_sdk::near_runtime::Runtime;
-    use aurora_engine_sdk::promise::PromiseHandler;
+    use aurora_engine_sdk::near_runtime;
+    use aurora_engine_sdk::promise::{PromiseHandler, PromiseId};
     use aurora_engine_types::account_id::AccountId;
 
     use crate::json::parse_json;
     use crate::prelude::parameters::RefundCallArgs;
+    use crate::prelude::precompiles::HostFunctions;
     use crate::prelude::sdk::types::{
         near_account_to_evm_address, SdkExpect, SdkProcess, SdkUnwrap,
     };
-    use crate::prelude::storage::{bytes_to_key, KeyPrefix};
-    use crate::prelude::types::{u256_to_arr, ERR_FAILED_PARSE};
+    use crate::prelude::storage::{bytes_to_key, ConfigKey, KeyPrefix};
+    use crate::prelude::types::{
+        address_from_public_key, parse_public_key, u256_to_arr, ERR_FAILED_PARSE,
+    };
     use crate::prelude::{
-        sdk, vec, Address, PromiseResult, ToString, TryFrom, TryInto, Vec, Wei,
-        ERC20_MINT_SELECTOR, H256, U256,
+        format, sdk, Address, PromiseResult, PromiseWithCallbackArgs, ToString, TryFrom, TryInto,
+        Vec, Wei, ERC20_MINT_SELECTOR, H256, U256,
     };
 
     #[cfg(feature = "integration-test")]
     use crate::prelude::NearGas;
 
-    const CODE_KEY: &[u8; 4] = b"CODE";
-    const CODE_STAGE_KEY: &[u8; 10] = b"CODE_STAGE";
     const PROMISE_COUNT_ERR: &str = "ERR_PROMISE_COUNT";
 
     ///
@@ -110,10 +194,11 @@ mod contract {
     /// Should be called on deployment.
     #[no_mangle]
     pub extern "C" fn new() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("new");
         if let Ok(state) = engine::get_state(&io) {
             require_owner_only(&state, &io.predecessor_account_id());
         }
+        record_admin_action(io, "new");
 
         let args: NewCallArgs = io.read_input_borsh().sdk_unwrap();
         engine::set_state(&mut io, args.into());
@@ -122,7 +207,7 @@ mod contract {
     /// Get version of the contract.
     #[no_mangle]
     pub extern "C" fn get_version() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_version");
         let version = match option_env!("NEAR_EVM_VERSION") {
             Some(v) => v.as_bytes(),
             None => include_bytes!("../../VERSION"),
@@ -133,15 +218,66 @@ mod contract {
     /// Get owner account id for this contract.
     #[no_mangle]
     pub extern "C" fn get_owner() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_owner");
         let state = engine::get_state(&io).sdk_unwrap();
         io.return_output(state.owner_id.as_bytes());
     }
 
+    /// Propose the ownership of the engine to another account, which has to accept it with
+    /// `accept_owner`. Proposing `None` cancels the pending proposal.
+    #[no_mangle]
+    pub extern "C" fn propose_owner() {
+        let mut io = entry_runtime("propose_owner");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "propose_owner");
+        let args: ProposeOwnerArgs = io.read_input_borsh().sdk_unwrap();
+        access_control::propose_owner(&mut io, &state, args.new_owner_id.as_ref());
+    }
+
+    /// Accept the ownership proposed to the caller.
+    #[no_mangle]
+    pub extern "C" fn accept_owner() {
+        let mut io = entry_runtime("accept_owner");
+        let state = engine::get_state(&io).sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        access_control::accept_owner(&mut io, state, &predecessor_account_id).sdk_unwrap();
+        record_admin_action(io, "accept_owner");
+    }
+
+    /// Account the ownership was proposed to, `None` if there is no pending proposal.
+    #[no_mangle]
+    pub extern "C" fn get_pending_owner() {
+        let mut io = entry_runtime("get_pending_owner");
+        let pending_owner = access_control::get_pending_owner(&io);
+        io.return_output(&pending_owner.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Grant or revoke a role of the privileged operations.
+    #[no_mangle]
+    pub extern "C" fn set_role() {
+        let mut io = entry_runtime("set_role");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_role");
+        let args: SetRoleArgs = io.read_input_borsh().sdk_unwrap();
+        access_control::set_role(&mut io, args.role, &args.account_id, args.granted);
+    }
+
+    /// Whether the account has the role, which the owner always has.
+    #[no_mangle]
+    pub extern "C" fn has_role() {
+        let mut io = entry_runtime("has_role");
+        let args: HasRoleArgs = io.read_input_borsh().sdk_unwrap();
+        let state = engine::get_state(&io).sdk_unwrap();
+        let has_role = access_control::has_role(&io, &state, args.role, &args.account_id);
+        io.return_output(&has_role.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     /// Get bridge prover id for this contract.
     #[no_mangle]
     pub extern "C" fn get_bridge_prover() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_bridge_prover");
         let state = engine::get_state(&io).sdk_unwrap();
         io.return_output(state.bridge_prover_id.as_bytes());
     }
@@ -149,42 +285,498 @@ mod contract {
     /// Get chain id for this contract.
     #[no_mangle]
     pub extern "C" fn get_chain_id() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_chain_id");
         io.return_output(&engine::get_state(&io).sdk_unwrap().chain_id)
     }
 
+    /// Set the chain ID the transactions are signed for. Only possible until the first
+    /// transaction is executed, as the signed transactions would become invalid.
+    #[no_mangle]
+    pub extern "C" fn set_chain_id() {
+        let mut io = entry_runtime("set_chain_id");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_chain_id");
+        let args: SetChainIdArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_chain_id(&mut io, state, args.chain_id).sdk_unwrap();
+    }
+
+    /// Predeploy the ERC-4337 EntryPoint at its canonical address, with its `SenderCreator`.
+    #[no_mangle]
+    pub extern "C" fn deploy_entry_point() {
+        let io = entry_runtime("deploy_entry_point");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "deploy_entry_point");
+        let args: DeployEntryPointArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        entry_point::deploy(io, &io, state, current_account_id, args, &mut Runtime).sdk_unwrap();
+    }
+
+    /// Accept the transactions signed without a chain ID, which can be replayed from other
+    /// networks, or reject them.
+    #[no_mangle]
+    pub extern "C" fn set_unprotected_txs_allowed() {
+        let mut io = entry_runtime("set_unprotected_txs_allowed");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_unprotected_txs_allowed");
+        let args: SetUnprotectedTxsArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_unprotected_txs_allowed(&mut io, args.allowed);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn is_unprotected_txs_allowed() {
+        let mut io = entry_runtime("is_unprotected_txs_allowed");
+        let allowed = engine::is_unprotected_txs_allowed(&io);
+        io.return_output(&allowed.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the total amounts of burned ETH on Aurora and NEAR.
+    #[no_mangle]
+    pub extern "C" fn get_burned_total() {
+        let mut io = entry_runtime("get_burned_total");
+        let burned_total = engine::get_burned_total(&io);
+        io.return_output(&burned_total.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Page of the privileged calls to the engine, oldest first.
+    #[no_mangle]
+    pub extern "C" fn get_admin_actions_page() {
+        let mut io = entry_runtime("get_admin_actions_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = admin_log::get_admin_actions_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Sequence number of the last call which changed the state of the engine.
+    #[no_mangle]
+    pub extern "C" fn get_engine_sequence() {
+        let mut io = entry_runtime("get_engine_sequence");
+        let sequence = engine_sequence::get_engine_sequence(&io);
+        io.return_output(&sequence.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the approximate number of storage bytes used by each subsystem.
+    #[no_mangle]
+    pub extern "C" fn get_storage_breakdown() {
+        let mut io = entry_runtime("get_storage_breakdown");
+        let breakdown = storage_breakdown::get_storage_breakdown(&io);
+        io.return_output(&breakdown.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_storage_breakdown_enabled() {
+        let mut io = entry_runtime("set_storage_breakdown_enabled");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_storage_breakdown_enabled");
+        let args: SetStorageBreakdownEnabledArgs = io.read_input_borsh().sdk_unwrap();
+        storage_breakdown::set_storage_breakdown_enabled(&mut io, args.enabled);
+    }
+
+    /// Move the configuration values stored under their legacy string keys to the namespaced
+    /// keys. Returns the number of moved values (Borsh-encoded `u32`).
+    #[no_mangle]
+    pub extern "C" fn migrate_config_keys() {
+        let mut io = entry_runtime("migrate_config_keys");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "migrate_config_keys");
+        let migrated = storage_key::migrate_config_keys(&mut io);
+        io.return_output(&migrated.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Write and remove raw storage keys in bulk, e.g. to migrate the state between schema
+    /// versions. With `dry_run` the diff is only validated. Returns the Borsh-encoded
+    /// `StateDiffResult` with the digest of the diff.
+    #[no_mangle]
+    pub extern "C" fn apply_state_diff() {
+        let mut io = entry_runtime("apply_state_diff");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "apply_state_diff");
+        let args: ApplyStateDiffArgs = io.read_input_borsh().sdk_unwrap();
+        let result = state_diff::apply_state_diff(&mut io, args).sdk_unwrap();
+        io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the names of the capabilities supported by this deployment of the engine.
+    #[no_mangle]
+    pub extern "C" fn list_capabilities() {
+        let mut io = entry_runtime("list_capabilities");
+        let capabilities = capabilities::list_capabilities(&io);
+        io.return_output(&capabilities.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn supports_capability() {
+        let mut io = entry_runtime("supports_capability");
+        let args: SupportsCapabilityCallArgs = io.read_input_borsh().sdk_unwrap();
+        let supported = capabilities::supports_capability(&io, &args.name);
+        io.return_output(&supported.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the whole configuration of the engine as a single Borsh document.
+    #[no_mangle]
+    pub extern "C" fn get_full_config() {
+        let mut io = entry_runtime("get_full_config");
+        let config = engine::get_full_config(&io).sdk_unwrap();
+        io.return_output(&config.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn get_upgrade_index() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_upgrade_index");
         let state = engine::get_state(&io).sdk_unwrap();
         let index = internal_get_upgrade_index();
-        io.return_output(&(index + state.upgrade_delay_blocks).to_le_bytes())
+        let delay = internal_get_upgrade_delay(&state);
+        io.return_output(&(index + delay).to_le_bytes())
+    }
+
+    /// Get the number of blocks during which a resubmitted transaction is rejected.
+    #[no_mangle]
+    pub extern "C" fn get_duplicate_submit_window() {
+        let mut io = entry_runtime("get_duplicate_submit_window");
+        let window_blocks = engine::get_duplicate_submit_window(&io);
+        io.return_output(&window_blocks.to_le_bytes())
+    }
+
+    /// Set the number of blocks during which a resubmitted transaction is rejected.
+    #[no_mangle]
+    pub extern "C" fn set_duplicate_submit_window() {
+        let mut io = entry_runtime("set_duplicate_submit_window");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_duplicate_submit_window");
+        let args: SetDuplicateSubmitWindowArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_duplicate_submit_window(&mut io, args.window_blocks);
+    }
+
+    /// Override the maximum length of the input of a method.
+    #[no_mangle]
+    pub extern "C" fn set_input_limit() {
+        let mut io = entry_runtime("set_input_limit");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_input_limit");
+        let args: SetInputLimitArgs = io.read_input_borsh().sdk_unwrap();
+        input_limits::set_input_limit_override(&mut io, &args.method, args.max_len).sdk_unwrap();
+    }
+
+    /// Set how the priority fees are split between the relayers and a treasury.
+    #[no_mangle]
+    pub extern "C" fn set_priority_fee_split() {
+        let mut io = entry_runtime("set_priority_fee_split");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_priority_fee_split");
+        let args: SetPriorityFeeSplitArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_priority_fee_split(&mut io, args.split).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_priority_fee_split() {
+        let mut io = entry_runtime("get_priority_fee_split");
+        let split = engine::get_priority_fee_split(&io);
+        io.return_output(&split.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the maximum call depth and memory size of the EVM.
+    #[no_mangle]
+    pub extern "C" fn set_evm_limits() {
+        let mut io = entry_runtime("set_evm_limits");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_evm_limits");
+        let args: EvmLimits = io.read_input_borsh().sdk_unwrap();
+        engine::set_evm_limits(&mut io, args).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_evm_limits() {
+        let mut io = entry_runtime("get_evm_limits");
+        let limits = engine::get_evm_limits(&io);
+        io.return_output(&limits.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the values of COINBASE and GASLIMIT and the gas cap of the batches of a block.
+    #[no_mangle]
+    pub extern "C" fn set_block_attributes() {
+        let mut io = entry_runtime("set_block_attributes");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_block_attributes");
+        let args: BlockAttributes = io.read_input_borsh().sdk_unwrap();
+        engine::set_block_attributes(&mut io, args).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_block_attributes() {
+        let mut io = entry_runtime("get_block_attributes");
+        let attributes = engine::get_block_attributes(&io);
+        io.return_output(&attributes.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Declare the optional host functions available on the NEAR runtime, which the
+    /// precompiles use instead of their pure-Rust implementation.
+    #[no_mangle]
+    pub extern "C" fn set_host_functions() {
+        let mut io = entry_runtime("set_host_functions");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_host_functions");
+        let args: HostFunctions = io.read_input_borsh().sdk_unwrap();
+        engine::set_host_functions(&mut io, args);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_host_functions() {
+        let mut io = entry_runtime("get_host_functions");
+        let host_functions = engine::get_host_functions(&io);
+        io.return_output(&host_functions.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the minimum gas price of the submitted transactions.
+    /// Can be called by the owner or by a gas price updater.
+    #[no_mangle]
+    pub extern "C" fn set_gas_price() {
+        let mut io = entry_runtime("set_gas_price");
+        let state = engine::get_state(&io).sdk_unwrap();
+        let args: SetGasPriceArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let now = io.block_timestamp().secs();
+        gas_price::set_gas_price(
+            &mut io,
+            &state.owner_id,
+            &predecessor_account_id,
+            args.price,
+            now,
+        )
+        .sdk_unwrap();
+    }
+
+    /// Allow or disallow an account to set the gas price.
+    #[no_mangle]
+    pub extern "C" fn set_gas_price_updater() {
+        let mut io = entry_runtime("set_gas_price_updater");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_gas_price_updater");
+        let args: SetGasPriceUpdaterArgs = io.read_input_borsh().sdk_unwrap();
+        gas_price::set_gas_price_updater(&mut io, &args.account_id, args.allowed);
+    }
+
+    /// Set for how long after an update the gas price is enforced.
+    #[no_mangle]
+    pub extern "C" fn set_gas_price_max_age() {
+        let mut io = entry_runtime("set_gas_price_max_age");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_gas_price_max_age");
+        let args: SetGasPriceMaxAgeArgs = io.read_input_borsh().sdk_unwrap();
+        gas_price::set_gas_price_max_age(&mut io, args.max_age_secs);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_gas_price() {
+        let mut io = entry_runtime("get_gas_price");
+        let result = gas_price::get_gas_price(&io, io.block_timestamp().secs());
+        io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the maximum number of storage bytes a submitted transaction can touch,
+    /// or remove the limit.
+    #[no_mangle]
+    pub extern "C" fn set_witness_size_limit() {
+        let mut io = entry_runtime("set_witness_size_limit");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_witness_size_limit");
+        let args: SetWitnessSizeLimitArgs = io.read_input_borsh().sdk_unwrap();
+        witness::set_witness_size_limit(&mut io, args.limit);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_witness_size_limit() {
+        let mut io = entry_runtime("get_witness_size_limit");
+        let limit = witness::get_witness_size_limit(&io);
+        io.return_output(&limit.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Enable or disable the per-block bloom index of logs used by the log index precompile.
+    #[no_mangle]
+    pub extern "C" fn set_log_bloom_index_enabled() {
+        let mut io = entry_runtime("set_log_bloom_index_enabled");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_log_bloom_index_enabled");
+        let args: SetLogBloomIndexArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_log_bloom_index_enabled(&mut io, args.enabled);
+    }
+
+    /// Keep emitting the legacy log lines next to the events replacing them, or stop.
+    #[no_mangle]
+    pub extern "C" fn set_legacy_logs_enabled() {
+        let mut io = entry_runtime("set_legacy_logs_enabled");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_legacy_logs_enabled");
+        let args: SetLegacyLogsArgs = io.read_input_borsh().sdk_unwrap();
+        events::set_legacy_logs_enabled(&mut io, args.enabled);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn is_legacy_logs_enabled() {
+        let mut io = entry_runtime("is_legacy_logs_enabled");
+        let enabled = events::is_legacy_logs_enabled(&io);
+        io.return_output(&enabled.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Reject the callbacks without an engine ticket, once the promises scheduled before the
+    /// tickets were introduced are resolved, or accept them from the engine itself again.
+    #[no_mangle]
+    pub extern "C" fn set_callback_tickets_required() {
+        let mut io = entry_runtime("set_callback_tickets_required");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_callback_tickets_required");
+        let args: SetCallbackTicketsRequiredArgs = io.read_input_borsh().sdk_unwrap();
+        callback::set_tickets_required(&mut io, args.required);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn is_callback_tickets_required() {
+        let mut io = entry_runtime("is_callback_tickets_required");
+        let required = callback::is_tickets_required(&io);
+        io.return_output(&required.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Store the code of the contracts deployed from now on compressed, or stop. The code
+    /// already stored is read the same either way.
+    #[no_mangle]
+    pub extern "C" fn set_code_compression() {
+        let mut io = entry_runtime("set_code_compression");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_code_compression");
+        let args: SetCodeCompressionArgs = io.read_input_borsh().sdk_unwrap();
+        compression::set_code_compression(&mut io, args.enabled);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn is_code_compression_enabled() {
+        let mut io = entry_runtime("is_code_compression_enabled");
+        let enabled = compression::get_code_compression(&io).unwrap_or(false);
+        io.return_output(&enabled.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the bloom filter of logs emitted at the given block height (Borsh-encoded `u64`).
+    #[no_mangle]
+    pub extern "C" fn get_logs_bloom() {
+        let mut io = entry_runtime("get_logs_bloom");
+        let block_height: u64 = io.read_input_borsh().sdk_unwrap();
+        let bloom = engine::get_logs_bloom(&io, block_height);
+        io.return_output(&bloom.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Prevent transfers and withdrawals from the NEAR account or EVM address.
+    #[no_mangle]
+    pub extern "C" fn freeze_account() {
+        let mut io = entry_runtime("freeze_account");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "freeze_account");
+        let target: FreezeTarget = io.read_input_borsh().sdk_unwrap();
+        freeze::freeze(&mut io, &target);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn unfreeze_account() {
+        let mut io = entry_runtime("unfreeze_account");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "unfreeze_account");
+        let target: FreezeTarget = io.read_input_borsh().sdk_unwrap();
+        freeze::unfreeze(&mut io, &target);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn is_account_frozen() {
+        let mut io = entry_runtime("is_account_frozen");
+        let target: FreezeTarget = io.read_input_borsh().sdk_unwrap();
+        let is_frozen = freeze::is_frozen(&io, &target);
+        io.return_output(&is_frozen.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Register a schema for the ABI utilities precompile.
+    #[no_mangle]
+    pub extern "C" fn register_abi_schema() {
+        let mut io = entry_runtime("register_abi_schema");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "register_abi_schema");
+        let args: RegisterAbiSchemaArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_abi_schema(&mut io, args.schema_id, &args.fields).sdk_unwrap();
     }
 
     /// Stage new code for deployment.
     #[no_mangle]
     pub extern "C" fn stage_upgrade() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("stage_upgrade");
         let state = engine::get_state(&io).sdk_unwrap();
         let block_height = io.block_height();
-        require_owner_only(&state, &io.predecessor_account_id());
+        require_role(
+            &io,
+            &state,
+            Role::UpgradeManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "stage_upgrade");
         io.read_input_and_store(&bytes_to_key(KeyPrefix::Config, CODE_KEY));
         io.write_storage(
             &bytes_to_key(KeyPrefix::Config, CODE_STAGE_KEY),
             &block_height.to_le_bytes(),
         );
+        // The delay is fixed when staging, so that lowering it doesn't shorten the time the
+        // users have to exit before this upgrade.
+        storage_key::write_config(
+            &mut io,
+            ConfigKey::CodeStageDelay,
+            &[],
+            &state.upgrade_delay_blocks.to_le_bytes(),
+        );
+        sdk::log!(&crate::prelude::format!(
+            "Upgrade staged at block {}, can be deployed after block {}",
+            block_height,
+            block_height + state.upgrade_delay_blocks
+        ));
+    }
+
+    /// Set the number of blocks after staging before an upgrade can be deployed. A lower
+    /// delay only applies to the upgrades staged afterwards.
+    #[no_mangle]
+    pub extern "C" fn set_upgrade_delay_blocks() {
+        let mut io = entry_runtime("set_upgrade_delay_blocks");
+        let mut state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_upgrade_delay_blocks");
+        let args: SetUpgradeDelayBlocksArgs = io.read_input_borsh().sdk_unwrap();
+        state.upgrade_delay_blocks = args.upgrade_delay_blocks;
+        engine::set_state(&mut io, state);
     }
 
     /// Deploy staged upgrade.
     #[no_mangle]
     pub extern "C" fn deploy_upgrade() {
-        let io = Runtime;
+        let io = entry_runtime("deploy_upgrade");
         let state = engine::get_state(&io).sdk_unwrap();
         let index = internal_get_upgrade_index();
-        if io.block_height() <= index + state.upgrade_delay_blocks {
+        if io.block_height() <= index + internal_get_upgrade_delay(&state) {
             sdk::panic_utf8(b"ERR_NOT_ALLOWED:TOO_EARLY");
         }
-        Runtime::self_deploy(&bytes_to_key(KeyPrefix::Config, CODE_KEY));
+        record_admin_action(io, "deploy_upgrade");
+        near_runtime::Runtime::self_deploy(&bytes_to_key(KeyPrefix::Config, CODE_KEY));
     }
 
     /// Called as part of the upgrade process (see `engine-sdk::self_deploy`). This function is meant
@@ -202,7 +794,7 @@ mod contract {
     /// Deploy code into the EVM.
     #[no_mangle]
     pub extern "C" fn deploy_code() {
-        let io = Runtime;
+        let io = entry_runtime("deploy_code");
         let input = io.read_input().to_vec();
         let current_account_id = io.current_account_id();
         let mut engine = Engine::new(
@@ -221,7 +813,7 @@ mod contract {
     /// Call method on the EVM contract.
     #[no_mangle]
     pub extern "C" fn call() {
-        let io = Runtime;
+        let io = entry_runtime("call");
         let bytes = io.read_input().to_vec();
         let args = CallArgs::deserialize(&bytes).sdk_expect("ERR_BORSH_DESERIALIZE");
         let current_account_id = io.current_account_id();
@@ -238,46 +830,118 @@ mod contract {
         // TODO: charge for storage
     }
 
+    /// Execute a bundle of ERC-4337 user operations with the EntryPoint. The fees of the
+    /// operations are paid to the address of the bundler's account.
+    #[no_mangle]
+    pub extern "C" fn handle_ops() {
+        let io = entry_runtime("handle_ops");
+        let args: HandleOpsArgs = io.read_input_borsh().sdk_unwrap();
+        entry_point::require_deployed(&io).sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let result = with_witness_limit(io, |tracked_io| {
+            let mut engine = Engine::new(
+                predecessor_address(&io.predecessor_account_id()),
+                current_account_id,
+                tracked_io,
+                &io,
+            )
+            .sdk_unwrap();
+            Engine::handle_ops(&mut engine, args, &mut Runtime)
+        });
+        result
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
+    }
+
     /// Process signed Ethereum transaction.
     /// Must match CHAIN_ID to make sure it's signed for given chain vs replayed from another chain.
     #[no_mangle]
     pub extern "C" fn submit() {
-        let io = Runtime;
+        let io = entry_runtime("submit");
         let input = io.read_input().to_vec();
         let current_account_id = io.current_account_id();
         let state = engine::get_state(&io).sdk_unwrap();
         let relayer_address = predecessor_address(&io.predecessor_account_id());
-        let result = engine::submit(
-            io,
-            &io,
-            &input,
-            state,
-            current_account_id,
-            relayer_address,
-            &mut Runtime,
-        );
+        #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+        let env = test_chain::TestBlockEnv::new(&io, test_chain::get_open_block(&io).as_ref());
+        #[cfg(not(any(feature = "evm_bully", feature = "integration-test")))]
+        let env = io;
+        let result = with_witness_limit(io, |tracked_io| {
+            engine::submit(
+                tracked_io,
+                &env,
+                &input,
+                state,
+                current_account_id,
+                relayer_address,
+                &mut Runtime,
+            )
+        });
+        #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+        if let Ok(result) = &result {
+            test_chain::record_transaction(&mut Runtime, &input, result);
+        }
 
         result
             .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
             .sdk_process();
     }
 
-    #[cfg(feature = "meta-call")]
+    /// Process a batch of signed Ethereum transactions, one after the other.
+    /// Returns the result of every transaction of the batch.
     #[no_mangle]
-    pub extern "C" fn meta_call() {
-        let io = Runtime;
-        let input = io.read_input().to_vec();
-        let state = engine::get_state(&io).sdk_unwrap();
-        let domain_separator = crate::meta_parsing::near_erc712_domain(U256::from(state.chain_id));
-        let meta_call_args = crate::meta_parsing::parse_meta_call(
-            &domain_separator,
-            io.current_account_id().as_bytes(),
-            input,
-        )
-        .sdk_expect("ERR_META_TX_PARSE");
-
-        engine::check_nonce(&io, &meta_call_args.sender, &meta_call_args.nonce).sdk_unwrap();
-
+    pub extern "C" fn submit_batch() {
+        let io = entry_runtime("submit_batch");
+        let args: SubmitBatchArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let state = engine::get_state(&io).sdk_unwrap();
+        let relayer_address = predecessor_address(&io.predecessor_account_id());
+        #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+        let env = test_chain::TestBlockEnv::new(&io, test_chain::get_open_block(&io).as_ref());
+        #[cfg(not(any(feature = "evm_bully", feature = "integration-test")))]
+        let env = io;
+        let result = with_witness_limit(io, |tracked_io| {
+            engine::submit_batch(
+                tracked_io,
+                &env,
+                &args,
+                state,
+                current_account_id,
+                relayer_address,
+                &mut Runtime,
+            )
+        });
+        #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+        if let Ok(results) = &result {
+            for (transaction_bytes, result) in args.transactions.iter().zip(results.iter()) {
+                if let parameters::BatchTransactionResult::Submitted(result) = result {
+                    test_chain::record_transaction(&mut Runtime, transaction_bytes, result);
+                }
+            }
+        }
+
+        result
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
+    }
+
+    #[cfg(feature = "meta-call")]
+    #[no_mangle]
+    pub extern "C" fn meta_call() {
+        let io = entry_runtime("meta_call");
+        let input = io.read_input().to_vec();
+        let state = engine::get_state(&io).sdk_unwrap();
+        let domain_separator =
+            crate::meta_parsing::domain_separator(&io, U256::from(state.chain_id));
+        let meta_call_args = crate::meta_parsing::parse_meta_call(
+            &domain_separator,
+            io.current_account_id().as_bytes(),
+            input,
+        )
+        .sdk_expect("ERR_META_TX_PARSE");
+
+        engine::check_nonce(&io, &meta_call_args.sender, &meta_call_args.nonce).sdk_unwrap();
+
         let current_account_id = io.current_account_id();
         let mut engine =
             Engine::new_with_state(state, meta_call_args.sender, current_account_id, io, &io);
@@ -295,10 +959,76 @@ mod contract {
             .sdk_process();
     }
 
+    /// Executes the meta-transaction signed by its sender, with the gas paid in ETH by the EVM
+    /// address of the predecessor instead of the sender.
+    #[cfg(feature = "meta-call")]
+    #[no_mangle]
+    pub extern "C" fn submit_with_fee_payer() {
+        let io = entry_runtime("submit_with_fee_payer");
+        let args: parameters::FeePayerMetaCallArgs = io.read_input_borsh().sdk_unwrap();
+        let state = engine::get_state(&io).sdk_unwrap();
+        let domain_separator =
+            crate::meta_parsing::domain_separator(&io, U256::from(state.chain_id));
+        let meta_call_args = crate::meta_parsing::verify_meta_call(
+            &domain_separator,
+            io.current_account_id().as_bytes(),
+            args.meta_call,
+        )
+        .sdk_expect("ERR_META_TX_PARSE");
+
+        let current_account_id = io.current_account_id();
+        let fee_payer = predecessor_address(&io.predecessor_account_id());
+        let result = with_witness_limit(io, |tracked_io| {
+            engine::submit_with_fee_payer(
+                tracked_io,
+                &io,
+                meta_call_args,
+                args.gas_limit,
+                U256::from_big_endian(&args.gas_price),
+                state,
+                current_account_id,
+                fee_payer,
+                &mut Runtime,
+            )
+        });
+        result
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
+    }
+
+    /// Set the EIP-712 domain the meta-transactions are signed for, so that the signatures made
+    /// for a deployment can't be replayed on another one.
+    #[cfg(feature = "meta-call")]
+    #[no_mangle]
+    pub extern "C" fn set_eip712_domain() {
+        let mut io = entry_runtime("set_eip712_domain");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_eip712_domain");
+        let domain: parameters::Eip712Domain = io.read_input_borsh().sdk_unwrap();
+        crate::meta_parsing::set_eip712_domain(&mut io, &domain);
+    }
+
+    #[cfg(feature = "meta-call")]
+    #[no_mangle]
+    pub extern "C" fn get_eip712_domain() {
+        let mut io = entry_runtime("get_eip712_domain");
+        let domain = crate::meta_parsing::get_eip712_domain(&io);
+        io.return_output(&domain.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Registers the EVM address receiving the fees of the relayer calling this method.
+    /// The input is either the address or the uncompressed secp256k1 public key of the relayer.
     #[no_mangle]
     pub extern "C" fn register_relayer() {
-        let io = Runtime;
-        let relayer_address = io.read_input_arr20().sdk_unwrap();
+        let io = entry_runtime("register_relayer");
+        let input = io.read_input().to_vec();
+        let relayer_address = if input.len() == 20 {
+            Address::from_slice(&input)
+        } else {
+            let public_key = parse_public_key(&input).sdk_unwrap();
+            address_from_public_key(&public_key)
+        };
 
         let current_account_id = io.current_account_id();
         let predecessor_account_id = io.predecessor_account_id();
@@ -309,7 +1039,17 @@ mod contract {
             &io,
         )
         .sdk_unwrap();
-        engine.register_relayer(predecessor_account_id.as_bytes(), Address(relayer_address));
+        engine.register_relayer(predecessor_account_id.as_bytes(), relayer_address);
+    }
+
+    /// EVM address registered by the relayer account, credited with the fees of the deposits
+    /// and the transfer calls it relays.
+    #[no_mangle]
+    pub extern "C" fn get_relayer() {
+        let mut io = entry_runtime("get_relayer");
+        let args: GetRelayerArgs = io.read_input_borsh().sdk_unwrap();
+        let address = engine::get_relayer(&io, args.account_id.as_bytes()).map(|address| address.0);
+        io.return_output(&address.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
     /// Allow receiving NEP141 tokens to the EVM contract.
@@ -319,7 +1059,7 @@ mod contract {
     /// error, or no token is returned if tx was successful.
     #[no_mangle]
     pub extern "C" fn ft_on_transfer() {
-        let io = Runtime;
+        let io = entry_runtime("ft_on_transfer");
         let current_account_id = io.current_account_id();
         let predecessor_account_id = io.predecessor_account_id();
         let mut engine = Engine::new(
@@ -337,7 +1077,7 @@ mod contract {
 
         if predecessor_account_id == current_account_id {
             EthConnectorContract::init_instance(io)
-                .ft_on_transfer(&engine, &args)
+                .ft_on_transfer(&mut engine, &args, &mut Runtime)
                 .sdk_unwrap();
         } else {
             let signer_account_id = io.signer_account_id();
@@ -354,7 +1094,7 @@ mod contract {
     /// Deploy ERC20 token mapped to a NEP141
     #[no_mangle]
     pub extern "C" fn deploy_erc20_token() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("deploy_erc20_token");
         // Id of the NEP141 token in Near
         let args: DeployErc20TokenArgs = io.read_input_borsh().sdk_unwrap();
 
@@ -369,7 +1109,7 @@ mod contract {
     /// errors in the exit call.
     #[no_mangle]
     pub extern "C" fn refund_on_error() {
-        let io = Runtime;
+        let mut io = entry_runtime("refund_on_error");
         io.assert_private_call().sdk_unwrap();
 
         // This function should only be called as the callback of
@@ -378,22 +1118,34 @@ mod contract {
             sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
         }
 
-        if let Some(PromiseResult::Successful(_)) = io.promise_result(0) {
-            // Promise succeeded -- nothing to do
+        // The execution id is consumed whatever the result of the exit call.
+        let input = io.read_input().to_vec();
+        let args = callback::consume_callback(&mut io, &input).sdk_unwrap();
+
+        let args = RefundCallArgs::try_from_slice(&args).sdk_expect(ERR_FAILED_PARSE);
+        let refund_address = Address(args.recipient_address);
+        let erc20_address = args.erc20_address.map(Address);
+        let amount = U256::from_big_endian(&args.amount);
+        let block_height = io.block_height();
+        let result = io.promise_result(0);
+        emit_xcc_result(&mut io, block_height, &refund_address, result.as_ref());
+        if let Some(PromiseResult::Successful(_)) = result {
+            // Promise succeeded -- only the completion of the exit is logged
+            engine_logs::emit(
+                &mut io,
+                block_height,
+                engine_logs::exit_completed(&refund_address, erc20_address.as_ref(), amount, false),
+            );
         } else {
             // Exit call failed; need to refund tokens
 
             let current_account_id = io.current_account_id();
-            let args: RefundCallArgs = io.read_input_borsh().sdk_unwrap();
-            let refund_result = match args.erc20_address {
+            let refunded = match erc20_address {
                 // ERC-20 exit; re-mint burned tokens
                 Some(erc20_address) => {
                     let erc20_admin_address = current_address(&current_account_id);
                     let mut engine =
                         Engine::new(erc20_admin_address, current_account_id, io, &io).sdk_unwrap();
-                    let erc20_address = Address(erc20_address);
-                    let refund_address = Address(args.recipient_address);
-                    let amount = U256::from_big_endian(&args.amount);
 
                     let selector = ERC20_MINT_SELECTOR;
                     let mint_args = ethabi::encode(&[
@@ -401,7 +1153,7 @@ mod contract {
                         ethabi::Token::Uint(amount),
                     ]);
 
-                    engine
+                    let minted = engine
                         .call(
                             erc20_admin_address,
                             erc20_address,
@@ -412,31 +1164,78 @@ mod contract {
                             &mut Runtime,
                         )
                         .sdk_unwrap()
+                        .status
+                        .is_ok();
+                    // The NEP-141 tokens unlocked by the exit are locked again. An amount above
+                    // `u128` never unlocked any, so the supplies diverge and the token is paused.
+                    if minted {
+                        let nep141 = engine::get_nep141_from_erc20(&io, &erc20_address);
+                        if let (Some(nep141), Ok(amount)) = (nep141, u128::try_from(amount)) {
+                            token_supply::record_mint(&mut io, &nep141, amount);
+                        }
+                    }
+                    minted
                 }
-                // ETH exit; transfer ETH back from precompile address
+                // ETH exit; transfer ETH back from precompile address. The balances are
+                // updated directly, so the code of the recipient can't reject the refund.
                 None => {
                     let exit_address = aurora_engine_precompiles::native::ExitToNear::ADDRESS;
-                    let mut engine =
-                        Engine::new(exit_address, current_account_id, io, &io).sdk_unwrap();
-                    let refund_address = Address(args.recipient_address);
-                    let amount = Wei::new(U256::from_big_endian(&args.amount));
-                    engine
-                        .call(
-                            exit_address,
-                            refund_address,
-                            amount,
-                            Vec::new(),
-                            u64::MAX,
-                            vec![(exit_address, Vec::new()), (refund_address, Vec::new())],
-                            &mut Runtime,
-                        )
-                        .sdk_unwrap()
+                    let amount = Wei::new(amount);
+                    match engine::get_balance(&io, &exit_address).checked_sub(amount) {
+                        Some(exit_balance) => {
+                            engine::set_balance(&mut io, &exit_address, &exit_balance);
+                            engine::add_balance(&mut io, &refund_address, amount).is_ok()
+                        }
+                        None => false,
+                    }
                 }
             };
 
-            if !refund_result.status.is_ok() {
+            if !refunded {
                 sdk::panic_utf8(b"ERR_REFUND_FAILURE");
             }
+            sdk::log!(&events::exit_refund_log(&events::ExitRefund {
+                recipient_address: refund_address,
+                erc20_address,
+                amount,
+            }));
+            engine_logs::emit(
+                &mut io,
+                block_height,
+                engine_logs::exit_completed(&refund_address, erc20_address.as_ref(), amount, true),
+            );
+        }
+    }
+
+    /// Callback invoked by the exits which aren't refunded on error, to log the result of the
+    /// exit call.
+    #[no_mangle]
+    pub extern "C" fn exit_result() {
+        let mut io = entry_runtime("exit_result");
+        io.assert_private_call().sdk_unwrap();
+
+        // This function should only be called as the callback of
+        // exactly one promise.
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+
+        let input = io.read_input().to_vec();
+        let args = callback::consume_callback(&mut io, &input).sdk_unwrap();
+
+        let args = RefundCallArgs::try_from_slice(&args).sdk_expect(ERR_FAILED_PARSE);
+        let sender = Address(args.recipient_address);
+        let erc20_address = args.erc20_address.map(Address);
+        let amount = U256::from_big_endian(&args.amount);
+        let block_height = io.block_height();
+        let result = io.promise_result(0);
+        emit_xcc_result(&mut io, block_height, &sender, result.as_ref());
+        if let Some(PromiseResult::Successful(_)) = result {
+            engine_logs::emit(
+                &mut io,
+                block_height,
+                engine_logs::exit_completed(&sender, erc20_address.as_ref(), amount, false),
+            );
         }
     }
 
@@ -445,7 +1244,7 @@ mod contract {
     ///
     #[no_mangle]
     pub extern "C" fn view() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("view");
         let args: ViewCallArgs = io.read_input_borsh().sdk_unwrap();
         let current_account_id = io.current_account_id();
         let engine = Engine::new(
@@ -459,9 +1258,158 @@ mod contract {
         io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    /// Read one chunk of the result of a view, for the results too large to return at once.
+    /// Every chunk holds the number of chunks of the result.
+    #[no_mangle]
+    pub extern "C" fn view_chunk() {
+        let mut io = entry_runtime("view_chunk");
+        let args: ViewChunkArgs = io.read_input_borsh().sdk_unwrap();
+        let result = match args.request {
+            ViewRequest::View(view_args) => {
+                let current_account_id = io.current_account_id();
+                let engine = Engine::new(
+                    Address::from_slice(&view_args.sender),
+                    current_account_id,
+                    io,
+                    &io,
+                )
+                .sdk_unwrap();
+                let result = Engine::view_with_args(&engine, view_args).sdk_unwrap();
+                result.try_to_vec().sdk_expect("ERR_SERIALIZE")
+            }
+            ViewRequest::GetCode(address) => engine::get_code(&io, &Address(address)),
+            ViewRequest::GetFullConfig => {
+                let config = engine::get_full_config(&io).sdk_unwrap();
+                config.try_to_vec().sdk_expect("ERR_SERIALIZE")
+            }
+        };
+        let chunk = view_chunk::get_chunk(&result, args.index).sdk_unwrap();
+        io.return_output(&chunk.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Replace the periodic maintenance tasks anyone can run with `run_due_tasks`.
+    #[no_mangle]
+    pub extern "C" fn set_scheduled_tasks() {
+        let mut io = entry_runtime("set_scheduled_tasks");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_scheduled_tasks");
+        let args: SetScheduledTasksArgs = io.read_input_borsh().sdk_unwrap();
+        let block_height = io.block_height();
+        scheduled_tasks::set_scheduled_tasks(&mut io, args.tasks, block_height).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_scheduled_tasks() {
+        let mut io = entry_runtime("get_scheduled_tasks");
+        let tasks = scheduled_tasks::get_scheduled_tasks(&io);
+        io.return_output(&tasks.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Run the due maintenance tasks, the reward goes to the EVM address of the predecessor.
+    #[no_mangle]
+    pub extern "C" fn run_due_tasks() {
+        let mut io = entry_runtime("run_due_tasks");
+        let args: RunDueTasksArgs = io.read_input_borsh().sdk_unwrap();
+        let caller = near_account_to_evm_address(io.predecessor_account_id().as_bytes());
+        let block_height = io.block_height();
+        let result = scheduled_tasks::run_due_tasks(&mut io, block_height, &caller, args.limit);
+        io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the nETH, ETH and bridged ERC-20 balances of a NEAR account.
+    #[no_mangle]
+    pub extern "C" fn get_balances() {
+        let mut io = entry_runtime("get_balances");
+        let args: GetBalancesCallArgs = io.read_input_borsh().sdk_unwrap();
+        let balances = engine::get_balances(io, &io, args).sdk_unwrap();
+        io.return_output(&balances.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the balances of `get_balances` as JSON, each amount with its raw value and its
+    /// human-readable one, e.g. `1.5 ETH`.
+    #[no_mangle]
+    pub extern "C" fn get_balances_json() {
+        let mut io = entry_runtime("get_balances_json");
+        let args: GetBalancesCallArgs = io.read_input_borsh().sdk_unwrap();
+        let balances = engine::get_balances_json(io, &io, args).sdk_unwrap();
+        io.return_output(balances.to_string().as_bytes());
+    }
+
+    /// Get the balance of an address in the ERC-20 of a bridged NEP-141 token, read from the
+    /// storage of the tokens deployed by the engine. Returns the big-endian balance.
+    #[no_mangle]
+    pub extern "C" fn get_erc20_balance() {
+        let mut io = entry_runtime("get_erc20_balance");
+        let args: GetErc20BalanceArgs = io.read_input_borsh().sdk_unwrap();
+        let balance =
+            engine::get_erc20_balance(io, &io, Address(args.erc20), Address(args.address))
+                .sdk_unwrap();
+        io.return_output(&u256_to_arr(&balance));
+    }
+
+    /// Compares the amount of a bridged NEP-141 token locked in the engine with the total supply
+    /// of its ERC-20.
+    #[no_mangle]
+    pub extern "C" fn check_token_supply() {
+        let mut io = entry_runtime("check_token_supply");
+        let args: CheckTokenSupplyArgs = io.read_input_borsh().sdk_unwrap();
+        let check = token_supply::check(io, &io, &args.token).sdk_unwrap();
+        io.return_output(&check.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Pauses or resumes the deposits and exits of a bridged NEP-141 token. A resumed token is
+    /// tracked again from the total supply of its ERC-20, once the owner has resolved the cause
+    /// of a mismatch.
+    #[no_mangle]
+    pub extern "C" fn set_token_paused() {
+        let mut io = entry_runtime("set_token_paused");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_role(
+            &io,
+            &state,
+            Role::PauseManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "set_token_paused");
+        let args: SetTokenPausedArgs = io.read_input_borsh().sdk_unwrap();
+        if args.paused {
+            token_supply::set_paused(&mut io, &args.token, true);
+        } else {
+            token_supply::resume(&mut io, &args.token);
+        }
+    }
+
+    /// Get the reserved EVM addresses, at which no code can be deployed.
+    #[no_mangle]
+    pub extern "C" fn list_system_addresses() {
+        let mut io = entry_runtime("list_system_addresses");
+        let addresses = system_addresses::system_addresses(&io.current_account_id());
+        io.return_output(&addresses.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Get the signatures and topics of the logs emitted at the engine logs address.
+    #[no_mangle]
+    pub extern "C" fn get_engine_log_topics() {
+        let mut io = entry_runtime("get_engine_log_topics");
+        let topics = engine_logs::engine_log_topics();
+        io.return_output(&topics.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Simulate the submit of a raw Ethereum transaction without committing it.
+    #[no_mangle]
+    pub extern "C" fn dry_run_submit() {
+        let mut io = entry_runtime("dry_run_submit");
+        let input = io.read_input().to_vec();
+        let current_account_id = io.current_account_id();
+        let state = engine::get_state(&io).sdk_unwrap();
+        let result = engine::dry_run_submit(io, &io, &input, state, current_account_id);
+        io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn get_block_hash() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_block_hash");
         let block_height = io.read_input_borsh().sdk_unwrap();
         let account_id = io.current_account_id();
         let chain_id = engine::get_state(&io)
@@ -474,7 +1422,7 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn get_code() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_code");
         let address = io.read_input_arr20().sdk_unwrap();
         let code = engine::get_code(&io, &Address(address));
         io.return_output(&code)
@@ -482,7 +1430,7 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn get_balance() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_balance");
         let address = io.read_input_arr20().sdk_unwrap();
         let balance = engine::get_balance(&io, &Address(address));
         io.return_output(&balance.to_bytes())
@@ -490,15 +1438,23 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn get_nonce() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_nonce");
         let address = io.read_input_arr20().sdk_unwrap();
         let nonce = engine::get_nonce(&io, &Address(address));
         io.return_output(&u256_to_arr(&nonce))
     }
 
+    #[no_mangle]
+    pub extern "C" fn get_abi_schema() {
+        let mut io = entry_runtime("get_abi_schema");
+        let schema_id: u32 = io.read_input_borsh().sdk_unwrap();
+        let schema = engine::get_abi_schema(&io, schema_id);
+        io.return_output(&schema.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn get_storage_at() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_storage_at");
         let args: GetStorageAtArgs = io.read_input_borsh().sdk_unwrap();
         let address = Address(args.address);
         let generation = engine::get_generation(&io, &address);
@@ -506,13 +1462,75 @@ mod contract {
         io.return_output(&value.0)
     }
 
+    /// Simulate a call like `eth_call`, the input is a JSON object with `to`, and optionally
+    /// `from`, `value` and `data`. Returns the JSON object of the status and the output.
+    #[no_mangle]
+    pub extern "C" fn eth_call() {
+        let mut io = entry_runtime("eth_call");
+        let input = parse_json(&io.read_input().to_vec()).sdk_unwrap();
+        let args = json_rpc::parse_call_args(&input).sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let engine = Engine::new(
+            Address::from_slice(&args.sender),
+            current_account_id,
+            io,
+            &io,
+        )
+        .sdk_unwrap();
+        let status = Engine::view_with_args(&engine, args).sdk_unwrap();
+        io.return_output(json_rpc::call_result(&status).to_string().as_bytes())
+    }
+
+    /// Balance of an address like `eth_getBalance`, the input is a JSON object with `address`.
+    #[no_mangle]
+    pub extern "C" fn eth_get_balance() {
+        let mut io = entry_runtime("eth_get_balance");
+        let input = parse_json(&io.read_input().to_vec()).sdk_unwrap();
+        let address = json_rpc::parse_address_args(&input).sdk_unwrap();
+        let balance = engine::get_balance(&io, &address);
+        io.return_output(json_rpc::quantity(balance.raw()).to_string().as_bytes())
+    }
+
+    /// Code of an address like `eth_getCode`, the input is a JSON object with `address`.
+    #[no_mangle]
+    pub extern "C" fn eth_get_code() {
+        let mut io = entry_runtime("eth_get_code");
+        let input = parse_json(&io.read_input().to_vec()).sdk_unwrap();
+        let address = json_rpc::parse_address_args(&input).sdk_unwrap();
+        let code = engine::get_code(&io, &address);
+        io.return_output(json_rpc::data(&code).to_string().as_bytes())
+    }
+
+    /// Nonce of an address like `eth_getTransactionCount`, the input is a JSON object with
+    /// `address`.
+    #[no_mangle]
+    pub extern "C" fn eth_get_transaction_count() {
+        let mut io = entry_runtime("eth_get_transaction_count");
+        let input = parse_json(&io.read_input().to_vec()).sdk_unwrap();
+        let address = json_rpc::parse_address_args(&input).sdk_unwrap();
+        let nonce = engine::get_nonce(&io, &address);
+        io.return_output(json_rpc::quantity(nonce).to_string().as_bytes())
+    }
+
+    /// Storage value like `eth_getStorageAt`, the input is a JSON object with `address` and
+    /// `key`.
+    #[no_mangle]
+    pub extern "C" fn eth_get_storage_at() {
+        let mut io = entry_runtime("eth_get_storage_at");
+        let input = parse_json(&io.read_input().to_vec()).sdk_unwrap();
+        let (address, key) = json_rpc::parse_storage_at_args(&input).sdk_unwrap();
+        let generation = engine::get_generation(&io, &address);
+        let value = engine::get_storage(&io, &address, &key, generation);
+        io.return_output(json_rpc::data(value.as_bytes()).to_string().as_bytes())
+    }
+
     ///
     /// BENCHMARKING METHODS
     ///
     #[cfg(feature = "evm_bully")]
     #[no_mangle]
     pub extern "C" fn begin_chain() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("begin_chain");
         let mut state = engine::get_state(&io).sdk_unwrap();
         require_owner_only(&state, &io.predecessor_account_id());
         let args: BeginChainArgs = io.read_input_borsh().sdk_unwrap();
@@ -530,21 +1548,34 @@ mod contract {
         io.return_output(&engine::get_state(&io).sdk_unwrap().chain_id)
     }
 
-    #[cfg(feature = "evm_bully")]
+    /// Opens an engine block grouping the transactions submitted until `end_block`.
+    #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
     #[no_mangle]
     pub extern "C" fn begin_block() {
-        let io = Runtime;
+        let mut io = entry_runtime("begin_block");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        let args: BeginBlockArgs = io.read_input_borsh().sdk_unwrap();
+        test_chain::begin_block(&mut io, &args).sdk_unwrap();
+    }
+
+    /// Closes the engine block opened by `begin_block` and returns its hashes.
+    #[cfg(any(feature = "evm_bully", feature = "integration-test"))]
+    #[no_mangle]
+    pub extern "C" fn end_block() {
+        let mut io = entry_runtime("end_block");
         let state = engine::get_state(&io).sdk_unwrap();
         require_owner_only(&state, &io.predecessor_account_id());
-        let _args: BeginBlockArgs = io.read_input_borsh().sdk_unwrap();
-        // TODO: https://github.com/aurora-is-near/aurora-engine/issues/2
+        let summary = test_chain::end_block(&mut io).sdk_unwrap();
+        io.return_output(&summary.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
     #[no_mangle]
     pub extern "C" fn new_eth_connector() {
-        let io = Runtime;
+        let io = entry_runtime("new_eth_connector");
         // Only the owner can initialize the EthConnector
         io.assert_private_call().sdk_unwrap();
+        record_admin_action(io, "new_eth_connector");
 
         let args: InitCallArgs = io.read_input_borsh().sdk_unwrap();
         let owner_id = io.current_account_id();
@@ -552,83 +1583,482 @@ mod contract {
         EthConnectorContract::create_contract(io, owner_id, args).sdk_unwrap();
     }
 
+    /// Change the prover, the custodian address and the metadata of the eth-connector. Can be
+    /// called by the engine itself or by the custodian managers.
     #[no_mangle]
     pub extern "C" fn set_eth_connector_contract_data() {
-        let mut io = Runtime;
-        // Only the owner can set the EthConnector contract data
-        io.assert_private_call().sdk_unwrap();
+        let mut io = entry_runtime("set_eth_connector_contract_data");
+        let predecessor_account_id = io.predecessor_account_id();
+        if predecessor_account_id != io.current_account_id() {
+            let state = engine::get_state(&io).sdk_unwrap();
+            require_role(&io, &state, Role::CustodianManager, &predecessor_account_id);
+        }
+        record_admin_action(io, "set_eth_connector_contract_data");
 
         let args: SetContractDataCallArgs = io.read_input_borsh().sdk_unwrap();
-        connector::set_contract_data(&mut io, args).sdk_unwrap();
+        connector::update_contract_data(&mut io, args).sdk_unwrap();
+    }
+
+    /// Prover and custodian address of the eth-connector, `None` if it is not initialized.
+    #[no_mangle]
+    pub extern "C" fn get_eth_connector_contract_data() {
+        let mut io = entry_runtime("get_eth_connector_contract_data");
+        let contract_data = connector::get_eth_connector(&io);
+        io.return_output(&contract_data.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Accept the deposits of another custodian contract, e.g. the previous one during a
+    /// migration of the custodian.
+    #[no_mangle]
+    pub extern "C" fn add_custodian() {
+        let mut io = entry_runtime("add_custodian");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_role(
+            &io,
+            &state,
+            Role::CustodianManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "add_custodian");
+        let args: CustodianArgs = io.read_input_borsh().sdk_unwrap();
+        connector::add_custodian(&mut io, args.address).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn remove_custodian() {
+        let mut io = entry_runtime("remove_custodian");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_role(
+            &io,
+            &state,
+            Role::CustodianManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "remove_custodian");
+        let args: CustodianArgs = io.read_input_borsh().sdk_unwrap();
+        connector::remove_custodian(&mut io, args.address).sdk_unwrap();
+    }
+
+    /// Custodian addresses the deposits are accepted from, the one of the contract data first.
+    #[no_mangle]
+    pub extern "C" fn get_custodians() {
+        let mut io = entry_runtime("get_custodians");
+        let custodians = connector::get_custodians(&io).sdk_unwrap();
+        io.return_output(&custodians.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
     #[no_mangle]
     pub extern "C" fn withdraw() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("withdraw");
         io.assert_one_yocto().sdk_unwrap();
         let args = io.read_input_borsh().sdk_unwrap();
         let current_account_id = io.current_account_id();
         let predecessor_account_id = io.predecessor_account_id();
         let result = EthConnectorContract::init_instance(io)
-            .withdraw_eth_from_near(&current_account_id, &predecessor_account_id, args)
+            .withdraw_eth_from_near(
+                &current_account_id,
+                &predecessor_account_id,
+                args,
+                io.block_height(),
+            )
             .sdk_unwrap();
         let result_bytes = result.try_to_vec().sdk_expect("ERR_SERIALIZE");
         io.return_output(&result_bytes);
     }
 
+    /// Dry run of `withdraw` by the account: the fee, the amount the recipient would receive
+    /// and the result of the withdrawal, failing with the error the withdrawal would fail with.
+    #[no_mangle]
+    pub extern "C" fn estimate_withdraw() {
+        let mut io = entry_runtime("estimate_withdraw");
+        let args: EstimateWithdrawArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let estimate = EthConnectorContract::init_instance(io)
+            .estimate_withdraw(&current_account_id, args)
+            .sdk_unwrap();
+        io.return_output(&estimate.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn deposit() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("deposit");
         let raw_proof = io.read_input().to_vec();
         let current_account_id = io.current_account_id();
         let predecessor_account_id = io.predecessor_account_id();
-        let promise_args = EthConnectorContract::init_instance(io)
-            .deposit(raw_proof, current_account_id, predecessor_account_id)
+        let verification = EthConnectorContract::init_instance(io)
+            .deposit_cached(
+                raw_proof,
+                current_account_id,
+                predecessor_account_id,
+                io.block_height(),
+            )
             .sdk_unwrap();
-        let promise_id = io.promise_crate_with_callback(&promise_args);
-        io.promise_return(promise_id);
+        match verification {
+            DepositVerification::Verified(data) => finish_verified_deposit(io, data),
+            DepositVerification::Retry(promise_args) => {
+                let promise_id = schedule_callback(&mut io, &promise_args);
+                io.promise_return(promise_id);
+            }
+        }
+    }
+
+    /// NEAR gas to attach to a `deposit` of the proof, with its breakdown.
+    #[no_mangle]
+    pub extern "C" fn estimate_deposit_gas() {
+        let mut io = entry_runtime("estimate_deposit_gas");
+        let raw_proof = io.read_input().to_vec();
+        let estimate = connector::estimate_deposit_gas(&io, &raw_proof).sdk_unwrap();
+        io.return_output(&estimate.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    /// Deposit from several proofs in one transaction. The deposits are chained, each one is
+    /// verified once the previous one is finished.
+    #[no_mangle]
+    pub extern "C" fn deposit_batch() {
+        let mut io = entry_runtime("deposit_batch");
+        let proofs: Vec<Proof> = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let predecessor_account_id = io.predecessor_account_id();
+        let promises = EthConnectorContract::init_instance(io)
+            .deposit_batch(proofs, current_account_id.clone(), predecessor_account_id)
+            .sdk_unwrap();
+        let mut promise_id: Option<PromiseId> = None;
+        for promise_args in promises.iter() {
+            let base = match promise_id {
+                None => io.promise_create_call(&promise_args.base),
+                Some(previous) => io.promise_attach_callback(previous, &promise_args.base),
+            };
+            callback::issue_ticket(
+                &mut io,
+                &current_account_id,
+                &current_account_id,
+                &promise_args.callback,
+            );
+            promise_id = Some(io.promise_attach_callback(base, &promise_args.callback));
+        }
+        // The batch is never empty
+        io.promise_return(promise_id.sdk_expect("ERR_EMPTY_DEPOSIT_BATCH"));
+    }
+
+    /// Callback of the verification of a deposit proof by a single prover (private method),
+    /// used by the deposits started before the prover fallback was introduced.
     #[no_mangle]
     pub extern "C" fn finish_deposit() {
-        let mut io = Runtime;
-        io.assert_private_call().sdk_unwrap();
+        let mut io = entry_runtime("finish_deposit");
+        authorize_callback(&mut io, "finish_deposit");
 
         // Check result from proof verification call
         if io.promise_results_count() != 1 {
-            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+            sdk::panic_utf8(ConnectorError::PromiseCount.as_ref());
         }
-        let promise_result = match io.promise_result(0) {
+        let verified = match io.promise_result(0) {
             Some(PromiseResult::Successful(bytes)) => {
-                bool::try_from_slice(&bytes).sdk_expect("ERR_PROMISE_ENCODING")
+                bool::try_from_slice(&bytes).map_err(|_| ConnectorError::PromiseEncoding)
             }
-            _ => sdk::panic_utf8(b"ERR_PROMISE_FAILED"),
-        };
-        if !promise_result {
-            sdk::panic_utf8(b"ERR_VERIFY_PROOF");
+            _ => Err(ConnectorError::PromiseFailed),
+        }
+        .sdk_unwrap();
+        if !verified {
+            sdk::panic_utf8(ConnectorError::from(DepositError::ProofVerificationFailed).as_ref());
         }
 
         let data = io.read_input_borsh().sdk_unwrap();
+        finish_verified_deposit(io, data);
+    }
+
+    /// Deposit of an ERC-20 token from the proof of its Locked event on Ethereum.
+    #[no_mangle]
+    pub extern "C" fn deposit_erc20() {
+        let mut io = entry_runtime("deposit_erc20");
+        let raw_proof = io.read_input().to_vec();
         let current_account_id = io.current_account_id();
         let predecessor_account_id = io.predecessor_account_id();
-        let maybe_promise_args = EthConnectorContract::init_instance(io)
-            .finish_deposit(
-                predecessor_account_id,
-                current_account_id,
-                data,
-                io.prepaid_gas(),
-            )
+        let promise_args = EthConnectorContract::init_instance(io)
+            .deposit_erc20(raw_proof, current_account_id, predecessor_account_id)
+            .sdk_unwrap();
+        let promise_id = schedule_callback(&mut io, &promise_args);
+        io.promise_return(promise_id);
+    }
+
+    /// Callback of the verification of the proof of an ERC-20 deposit (private method).
+    #[no_mangle]
+    pub extern "C" fn finish_deposit_erc20() {
+        let mut io = entry_runtime("finish_deposit_erc20");
+        authorize_callback(&mut io, "finish_deposit_erc20");
+
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(ConnectorError::PromiseCount.as_ref());
+        }
+        let verified = match io.promise_result(0) {
+            Some(PromiseResult::Successful(bytes)) => {
+                bool::try_from_slice(&bytes).map_err(|_| ConnectorError::PromiseEncoding)
+            }
+            _ => Err(ConnectorError::PromiseFailed),
+        }
+        .sdk_unwrap();
+        if !verified {
+            sdk::panic_utf8(ConnectorError::from(DepositError::ProofVerificationFailed).as_ref());
+        }
+
+        let data: FinishDepositErc20CallArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_args = EthConnectorContract::init_instance(io)
+            .finish_deposit_erc20(data)
+            .map_err(ConnectorError::from)
             .sdk_unwrap();
+        let promise_id = io.promise_create_call(&promise_args);
+        io.promise_return(promise_id);
+    }
+
+    /// Set the address of the ERC-20 locker on Ethereum the ERC-20 deposits are accepted from.
+    #[no_mangle]
+    pub extern "C" fn set_erc20_locker() {
+        let mut io = entry_runtime("set_erc20_locker");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_erc20_locker");
+        let args: SetErc20LockerArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_erc20_locker(&mut io, args.locker_address);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_erc20_locker() {
+        let mut io = entry_runtime("get_erc20_locker");
+        let locker_address = connector::get_erc20_locker(&io);
+        io.return_output(&locker_address.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Register the NEP-141 token minted for the deposits of an ERC-20 token, or unregister it.
+    #[no_mangle]
+    pub extern "C" fn register_erc20_token() {
+        let mut io = entry_runtime("register_erc20_token");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "register_erc20_token");
+        let args: RegisterErc20TokenArgs = io.read_input_borsh().sdk_unwrap();
+        connector::register_erc20_token(&mut io, &args.token, args.nep141.as_ref());
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_bridged_nep141() {
+        let mut io = entry_runtime("get_bridged_nep141");
+        let args: GetBridgedNep141Args = io.read_input_borsh().sdk_unwrap();
+        let nep141 = connector::get_bridged_nep141(&io, &args.token);
+        io.return_output(&nep141.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Callback of the verification of a deposit proof by one of the provers (private method).
+    #[no_mangle]
+    pub extern "C" fn resolve_deposit_verification() {
+        let mut io = entry_runtime("resolve_deposit_verification");
+        authorize_callback(&mut io, "resolve_deposit_verification");
+
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(ConnectorError::PromiseCount.as_ref());
+        }
+        // A failed verification call falls back to the next prover as well.
+        let verified = match io.promise_result(0) {
+            Some(PromiseResult::Successful(bytes)) => bool::try_from_slice(&bytes).unwrap_or(false),
+            _ => false,
+        };
 
-        if let Some(promise_args) = maybe_promise_args {
-            let promise_id = io.promise_crate_with_callback(&promise_args);
-            io.promise_return(promise_id);
+        let args: ResolveDepositVerificationArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let verification = EthConnectorContract::init_instance(io)
+            .resolve_deposit_verification(args, verified, current_account_id, io.block_height())
+            .sdk_unwrap();
+        match verification {
+            DepositVerification::Verified(data) => finish_verified_deposit(io, data),
+            DepositVerification::Retry(promise_args) => {
+                let promise_id = schedule_callback(&mut io, &promise_args);
+                io.promise_return(promise_id);
+            }
         }
     }
 
+    /// Set the number of blocks the receipts verified by a prover are trusted for, so that the
+    /// deposits of other logs of the same receipts skip the prover call.
+    #[no_mangle]
+    pub extern "C" fn set_proof_cache_config() {
+        let mut io = entry_runtime("set_proof_cache_config");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_proof_cache_config");
+        let config: ProofCacheConfig = io.read_input_borsh().sdk_unwrap();
+        connector::set_proof_cache_config(&mut io, &config);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_proof_cache_config() {
+        let mut io = entry_runtime("get_proof_cache_config");
+        let config = connector::get_proof_cache_config(&io);
+        io.return_output(&config.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the account providing the hashes of the Ethereum blocks the deposit proofs are
+    /// verified against by the engine, without a call to a prover.
+    #[no_mangle]
+    pub extern "C" fn set_light_client() {
+        let mut io = entry_runtime("set_light_client");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_light_client");
+        let args: SetLightClientArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_light_client(&mut io, args.account_id);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_light_client() {
+        let mut io = entry_runtime("get_light_client");
+        let light_client = connector::get_light_client(&io);
+        io.return_output(&light_client.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Provide the hash of a final Ethereum block. Can only be called by the light client.
+    #[no_mangle]
+    pub extern "C" fn add_light_client_block_hash() {
+        let mut io = entry_runtime("add_light_client_block_hash");
+        let args: AddLightClientBlockHashArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let block_height = io.block_height();
+        connector::add_light_client_block_hash(
+            &mut io,
+            &predecessor_account_id,
+            &H256(args.block_hash),
+            block_height,
+        )
+        .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_provers() {
+        let mut io = entry_runtime("get_provers");
+        let provers = connector::get_prover_statuses(&io);
+        io.return_output(&provers.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_provers() {
+        let mut io = entry_runtime("set_provers");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_provers");
+        let args: SetProversArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_provers(&mut io, args.provers).sdk_unwrap();
+    }
+
+    /// Flag a deposit as minted to an invalid or unreachable recipient, or remove the flag.
+    #[no_mangle]
+    pub extern "C" fn set_unclaimed_deposit() {
+        let mut io = entry_runtime("set_unclaimed_deposit");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_unclaimed_deposit");
+        let args: SetUnclaimedDepositArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        EthConnectorContract::init_instance(io)
+            .set_unclaimed_deposit(
+                &args.deposit_id,
+                args.flagged,
+                &current_account_id,
+                io.block_height(),
+            )
+            .sdk_unwrap();
+    }
+
+    /// Remove the flag of a deposit, called by the recipient of the deposit before it is
+    /// reassigned.
+    #[no_mangle]
+    pub extern "C" fn claim_unclaimed_deposit() {
+        let io = entry_runtime("claim_unclaimed_deposit");
+        let args: ClaimUnclaimedDepositArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        EthConnectorContract::init_instance(io)
+            .claim_unclaimed_deposit(&args.deposit_id, &predecessor_account_id)
+            .sdk_unwrap();
+    }
+
+    /// Move the tokens of a flagged deposit to a new recipient, once the reassignment delay
+    /// elapsed. Returns the amount moved.
+    #[no_mangle]
+    pub extern "C" fn reassign_unclaimed() {
+        let mut io = entry_runtime("reassign_unclaimed");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "reassign_unclaimed");
+        let args: ReassignUnclaimedArgs = io.read_input_borsh().sdk_unwrap();
+        let amount = EthConnectorContract::init_instance(io)
+            .reassign_unclaimed(&args.deposit_id, &args.new_recipient, io.block_height())
+            .sdk_unwrap();
+        io.return_output(&amount.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_unclaimed_deposit() {
+        let mut io = entry_runtime("get_unclaimed_deposit");
+        let args: GetUnclaimedDepositArgs = io.read_input_borsh().sdk_unwrap();
+        let unclaimed =
+            EthConnectorContract::init_instance(io).get_unclaimed_deposit(&args.deposit_id);
+        io.return_output(&unclaimed.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_deposit_receipt() {
+        let mut io = entry_runtime("get_deposit_receipt");
+        let args: GetDepositReceiptCallArgs = io.read_input_borsh().sdk_unwrap();
+
+        let receipt = EthConnectorContract::init_instance(io).get_deposit_receipt(args.proof);
+        io.return_output(&receipt.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Receipts of the last finished deposits sent from an Ethereum address, newest first.
+    #[no_mangle]
+    pub extern "C" fn get_deposits_by_sender() {
+        let mut io = entry_runtime("get_deposits_by_sender");
+        let args: GetDepositsBySenderCallArgs = io.read_input_borsh().sdk_unwrap();
+        let deposits = connector::get_deposits_by_sender(&io, &args.sender, args.limit);
+        io.return_output(&deposits.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Page of the receipts of the finished deposits, oldest first.
+    #[no_mangle]
+    pub extern "C" fn get_deposit_receipts_page() {
+        let mut io = entry_runtime("get_deposit_receipts_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = connector::get_deposit_receipts_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Page of the accounts registered with the eth-connector, with their balances of nETH.
+    #[no_mangle]
+    pub extern "C" fn get_accounts_page() {
+        let mut io = entry_runtime("get_accounts_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = connector::get_accounts_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Page of the withdrawals, oldest first.
+    #[no_mangle]
+    pub extern "C" fn get_withdrawals_page() {
+        let mut io = entry_runtime("get_withdrawals_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = connector::get_withdrawals_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Page of the transfer calls waiting for their `ft_resolve_transfer` callback, which
+    /// include the deposits to EVM addresses.
+    #[no_mangle]
+    pub extern "C" fn get_pending_transfer_calls_page() {
+        let mut io = entry_runtime("get_pending_transfer_calls_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = connector::get_pending_transfer_calls_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn is_used_proof() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("is_used_proof");
         let args: IsUsedProofCallArgs = io.read_input_borsh().sdk_unwrap();
 
         let is_used_proof = EthConnectorContract::init_instance(io).is_used_proof(args.proof);
@@ -636,76 +2066,318 @@ mod contract {
         io.return_output(&res[..]);
     }
 
+    /// Same as `is_used_proof`, for the relayers which already computed the key of the proof.
+    #[no_mangle]
+    pub extern "C" fn is_used_proof_key() {
+        let mut io = entry_runtime("is_used_proof_key");
+        let args: IsUsedProofKeyCallArgs = io.read_input_borsh().sdk_unwrap();
+
+        let is_used_proof = EthConnectorContract::init_instance(io).is_used_proof_key(&args.key);
+        let res = is_used_proof.try_to_vec().unwrap();
+        io.return_output(&res[..]);
+    }
+
+    /// Used status of the proofs with the given keys, in one call for the relayers checking
+    /// many proofs.
+    #[no_mangle]
+    pub extern "C" fn check_proofs() {
+        let mut io = entry_runtime("check_proofs");
+        let args: CheckProofsCallArgs = io.read_input_borsh().sdk_unwrap();
+
+        let used = EthConnectorContract::init_instance(io).are_used_proof_keys(&args.keys);
+        io.return_output(&used.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn ft_total_supply() {
-        let io = Runtime;
+        let io = entry_runtime("ft_total_supply");
+        EthConnectorContract::init_instance(io).ft_total_eth_supply_on_near();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn ft_total_eth_supply_on_near() {
+        let io = entry_runtime("ft_total_eth_supply_on_near");
         EthConnectorContract::init_instance(io).ft_total_eth_supply_on_near();
     }
 
     #[no_mangle]
-    pub extern "C" fn ft_total_eth_supply_on_near() {
-        let io = Runtime;
-        EthConnectorContract::init_instance(io).ft_total_eth_supply_on_near();
+    pub extern "C" fn ft_total_eth_supply_on_aurora() {
+        let io = entry_runtime("ft_total_eth_supply_on_aurora");
+        EthConnectorContract::init_instance(io).ft_total_eth_supply_on_aurora();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn ft_balance_of() {
+        let io = entry_runtime("ft_balance_of");
+        let args = parameters::BalanceOfCallArgs::try_from(
+            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
+        )
+        .sdk_unwrap();
+        EthConnectorContract::init_instance(io).ft_balance_of(args);
+    }
+
+    /// Get the storage key and the stored value of the nETH balance of the account, to verify
+    /// a NEAR state proof of the balance against the state of the engine contract.
+    #[no_mangle]
+    pub extern "C" fn get_balance_storage_layout() {
+        let mut io = entry_runtime("get_balance_storage_layout");
+        let args: parameters::BalanceOfCallArgs = io.read_input_borsh().sdk_unwrap();
+        let layout = fungible_token::get_balance_storage_layout(&io, &args.account_id);
+        io.return_output(&layout.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn ft_balance_of_eth() {
+        let io = entry_runtime("ft_balance_of_eth");
+        let args: parameters::BalanceOfEthCallArgs = io.read_input().to_value().sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .ft_balance_of_eth_on_aurora(args)
+            .sdk_unwrap();
+    }
+
+    /// Get the balances of nETH of many accounts in one call. The input is the JSON
+    /// `{"account_ids": [..]}`, the output a JSON object from the account to its balance.
+    #[no_mangle]
+    pub extern "C" fn ft_balances_of() {
+        let io = entry_runtime("ft_balances_of");
+        let args = parameters::BalancesOfCallArgs::try_from(
+            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
+        )
+        .sdk_unwrap();
+        EthConnectorContract::init_instance(io).ft_balances_of(args);
+    }
+
+    /// Get the balances of ETH in the EVM of many addresses in one call. The input is the JSON
+    /// `{"addresses": [..]}` of hex addresses, the output a JSON object from the address to
+    /// its balance.
+    #[no_mangle]
+    pub extern "C" fn ft_balances_of_eth() {
+        let io = entry_runtime("ft_balances_of_eth");
+        let args = parameters::BalancesOfEthCallArgs::try_from(
+            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
+        )
+        .sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .ft_balances_of_eth_on_aurora(args)
+            .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn ft_transfer() {
+        let io = entry_runtime("ft_transfer");
+        io.assert_one_yocto().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let args = parameters::TransferCallArgs::try_from(
+            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
+        )
+        .sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .ft_transfer(&predecessor_account_id, args)
+            .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn ft_resolve_transfer() {
+        let mut io = entry_runtime("ft_resolve_transfer");
+
+        authorize_callback(&mut io, "ft_resolve_transfer");
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(ConnectorError::PromiseCount.as_ref());
+        }
+
+        let args: ResolveTransferCallArgs = io.read_input().to_value().sdk_unwrap();
+        let promise_result = io.promise_result(0).sdk_unwrap();
+
+        EthConnectorContract::init_instance(io).ft_resolve_transfer(args, promise_result);
+    }
+
+    /// Resolve a transfer call whose `ft_resolve_transfer` callback never ran.
+    /// Returns the amount used by the receiver.
+    #[no_mangle]
+    pub extern "C" fn force_resolve_transfer() {
+        let mut io = entry_runtime("force_resolve_transfer");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "force_resolve_transfer");
+        let args: ForceResolveTransferCallArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        let used_amount = EthConnectorContract::init_instance(io)
+            .force_resolve_transfer(args, &current_account_id, io.block_height())
+            .sdk_unwrap();
+        io.return_output(format!("\"{}\"", used_amount).as_bytes());
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_force_resolve_delay() {
+        let mut io = entry_runtime("get_force_resolve_delay");
+        let delay_blocks = connector::get_force_resolve_delay(&io);
+        io.return_output(&delay_blocks.to_le_bytes());
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_force_resolve_delay() {
+        let mut io = entry_runtime("set_force_resolve_delay");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_force_resolve_delay");
+        let args: SetForceResolveDelayCallArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_force_resolve_delay(&mut io, args.delay_blocks);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_mint_circuit_breaker() {
+        let mut io = entry_runtime("get_mint_circuit_breaker");
+        let circuit_breaker = connector::get_mint_circuit_breaker(&io);
+        io.return_output(&circuit_breaker.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn set_mint_circuit_breaker() {
+        let mut io = entry_runtime("set_mint_circuit_breaker");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_mint_circuit_breaker");
+        let args: MintCircuitBreakerArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_mint_circuit_breaker(&mut io, &args);
+    }
+
+    /// Set the minimum amount of the deposits, and the minimum amount they have to exceed
+    /// the relayer fee by.
+    #[no_mangle]
+    pub extern "C" fn set_min_deposit() {
+        let mut io = entry_runtime("set_min_deposit");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_min_deposit");
+        let args: MinDepositArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_min_deposit(&mut io, &args);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_min_deposit() {
+        let mut io = entry_runtime("get_min_deposit");
+        let min_deposit = connector::get_min_deposit(&io);
+        io.return_output(&min_deposit.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the suffixes the NEAR recipients of the deposits must or must not match.
+    #[no_mangle]
+    pub extern "C" fn set_recipient_rules() {
+        let mut io = entry_runtime("set_recipient_rules");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_recipient_rules");
+        let rules: RecipientRules = io.read_input_borsh().sdk_unwrap();
+        connector::set_recipient_rules(&mut io, &rules);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_recipient_rules() {
+        let mut io = entry_runtime("get_recipient_rules");
+        let rules = connector::get_recipient_rules(&io);
+        io.return_output(&rules.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the fee taken by the protocol on the deposits, in basis points.
+    #[no_mangle]
+    pub extern "C" fn set_deposit_protocol_fee() {
+        let mut io = entry_runtime("set_deposit_protocol_fee");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_deposit_protocol_fee");
+        let args: SetDepositProtocolFeeArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_deposit_protocol_fee(&mut io, args.fee_bps).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_deposit_protocol_fee() {
+        let mut io = entry_runtime("get_deposit_protocol_fee");
+        let fee_bps = connector::get_deposit_protocol_fee(&io);
+        io.return_output(&fee_bps.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Transfer protocol fees accrued on the engine account to the receiver.
+    #[no_mangle]
+    pub extern "C" fn withdraw_protocol_fees() {
+        let io = entry_runtime("withdraw_protocol_fees");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "withdraw_protocol_fees");
+        let args: WithdrawProtocolFeesArgs = io.read_input_borsh().sdk_unwrap();
+        let current_account_id = io.current_account_id();
+        EthConnectorContract::init_instance(io)
+            .withdraw_protocol_fees(&current_account_id, &args.receiver_id, args.amount)
+            .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_protocol_fees() {
+        let mut io = entry_runtime("get_protocol_fees");
+        let protocol_fees = connector::get_protocol_fees(&io);
+        io.return_output(&protocol_fees.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    /// Set the currency the relayer fees of the deposits to the EVM are paid in: bridged ETH,
+    /// or the base token of the silo the fees are converted into by a DEX route.
     #[no_mangle]
-    pub extern "C" fn ft_total_eth_supply_on_aurora() {
-        let io = Runtime;
-        EthConnectorContract::init_instance(io).ft_total_eth_supply_on_aurora();
+    pub extern "C" fn set_deposit_fee_currency() {
+        let mut io = entry_runtime("set_deposit_fee_currency");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_deposit_fee_currency");
+        let currency: DepositFeeCurrency = io.read_input_borsh().sdk_unwrap();
+        connector::set_deposit_fee_currency(&mut io, &currency).sdk_unwrap();
     }
 
     #[no_mangle]
-    pub extern "C" fn ft_balance_of() {
-        let io = Runtime;
-        let args = parameters::BalanceOfCallArgs::try_from(
-            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
-        )
-        .sdk_unwrap();
-        EthConnectorContract::init_instance(io).ft_balance_of(args);
+    pub extern "C" fn get_deposit_fee_currency() {
+        let mut io = entry_runtime("get_deposit_fee_currency");
+        let currency = connector::get_deposit_fee_currency(&io);
+        io.return_output(&currency.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    /// Add a relayer to the list of the relayers whose deposits are not charged the protocol
+    /// fee, or remove it.
     #[no_mangle]
-    pub extern "C" fn ft_balance_of_eth() {
-        let io = Runtime;
-        let args: parameters::BalanceOfEthCallArgs = io.read_input().to_value().sdk_unwrap();
-        EthConnectorContract::init_instance(io)
-            .ft_balance_of_eth_on_aurora(args)
-            .sdk_unwrap();
+    pub extern "C" fn set_fee_waiver() {
+        let mut io = entry_runtime("set_fee_waiver");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_fee_waiver");
+        let args: SetFeeWaiverArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_fee_waiver(&mut io, &args.relayer_id, args.waived);
     }
 
     #[no_mangle]
-    pub extern "C" fn ft_transfer() {
-        let io = Runtime;
-        io.assert_one_yocto().sdk_unwrap();
-        let predecessor_account_id = io.predecessor_account_id();
-        let args = parameters::TransferCallArgs::try_from(
-            parse_json(&io.read_input().to_vec()).sdk_unwrap(),
-        )
-        .sdk_unwrap();
-        EthConnectorContract::init_instance(io)
-            .ft_transfer(&predecessor_account_id, args)
-            .sdk_unwrap();
+    pub extern "C" fn is_fee_waived() {
+        let mut io = entry_runtime("is_fee_waived");
+        let args: IsFeeWaivedCallArgs = io.read_input_borsh().sdk_unwrap();
+        let waived = connector::is_fee_waived(&io, &args.relayer_id);
+        io.return_output(&waived.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
+    /// Fees credited to the relayer by the deposits, `None` if it wasn't credited any.
     #[no_mangle]
-    pub extern "C" fn ft_resolve_transfer() {
-        let io = Runtime;
-
-        io.assert_private_call().sdk_unwrap();
-        if io.promise_results_count() != 1 {
-            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
-        }
-
-        let args: ResolveTransferCallArgs = io.read_input().to_value().sdk_unwrap();
-        let promise_result = io.promise_result(0).sdk_unwrap();
+    pub extern "C" fn get_relayer_earnings() {
+        let mut io = entry_runtime("get_relayer_earnings");
+        let args: GetRelayerEarningsArgs = io.read_input_borsh().sdk_unwrap();
+        let earnings = connector::get_relayer_earnings(&io, &args.relayer_id);
+        io.return_output(&earnings.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
 
-        EthConnectorContract::init_instance(io).ft_resolve_transfer(args, promise_result);
+    /// Page of the relayers credited a fee by a deposit, with their earnings.
+    #[no_mangle]
+    pub extern "C" fn get_relayer_earnings_page() {
+        let mut io = entry_runtime("get_relayer_earnings_page");
+        let args: PageArgs = io.read_input_borsh().sdk_unwrap();
+        let page = connector::get_relayer_earnings_page(&io, &args).sdk_unwrap();
+        io.return_output(&page.try_to_vec().sdk_expect("ERR_SERIALIZE"));
     }
 
     #[no_mangle]
     pub extern "C" fn ft_transfer_call() {
         use sdk::types::ExpectUtf8;
-        let mut io = Runtime;
+        let mut io = entry_runtime("ft_transfer_call");
         // Check is payable
         io.assert_one_yocto().sdk_unwrap();
 
@@ -721,15 +2393,16 @@ mod contract {
                 current_account_id,
                 args,
                 io.prepaid_gas(),
+                io.block_height(),
             )
             .sdk_unwrap();
-        let promise_id = io.promise_crate_with_callback(&promise_args);
+        let promise_id = schedule_callback(&mut io, &promise_args);
         io.promise_return(promise_id);
     }
 
     #[no_mangle]
     pub extern "C" fn storage_deposit() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("storage_deposit");
         let args = StorageDepositCallArgs::from(parse_json(&io.read_input().to_vec()).sdk_unwrap());
         let predecessor_account_id = io.predecessor_account_id();
         let amount = io.attached_deposit();
@@ -743,7 +2416,7 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn storage_unregister() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("storage_unregister");
         io.assert_one_yocto().sdk_unwrap();
         let predecessor_account_id = io.predecessor_account_id();
         let force = parse_json(&io.read_input().to_vec()).and_then(|args| args.bool("force").ok());
@@ -757,7 +2430,7 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn storage_withdraw() {
-        let io = Runtime;
+        let io = entry_runtime("storage_withdraw");
         io.assert_one_yocto().sdk_unwrap();
         let args =
             StorageWithdrawCallArgs::from(parse_json(&io.read_input().to_vec()).sdk_unwrap());
@@ -769,7 +2442,7 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn storage_balance_of() {
-        let io = Runtime;
+        let io = entry_runtime("storage_balance_of");
         let args = parameters::StorageBalanceOfCallArgs::try_from(
             parse_json(&io.read_input().to_vec()).sdk_unwrap(),
         )
@@ -777,32 +2450,114 @@ mod contract {
         EthConnectorContract::init_instance(io).storage_balance_of(args)
     }
 
+    #[no_mangle]
+    pub extern "C" fn storage_balance_bounds() {
+        let io = entry_runtime("storage_balance_bounds");
+        EthConnectorContract::init_instance(io).storage_balance_bounds()
+    }
+
+    /// Add the attached NEAR to the pool paying for the storage deposits of the accounts
+    /// registered by the deposits and the transfers of nETH. Returns the balance of the pool.
+    #[no_mangle]
+    pub extern "C" fn fund_storage_pool() {
+        let mut io = entry_runtime("fund_storage_pool");
+        let amount = io.attached_deposit();
+        let pool = EthConnectorContract::init_instance(io).fund_storage_pool(amount);
+        io.return_output(&pool.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_storage_pool() {
+        let mut io = entry_runtime("get_storage_pool");
+        let pool = EthConnectorContract::init_instance(io).get_storage_pool();
+        io.return_output(&pool.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Set the storage deposit of the new accounts and whether the storage pool must pay for
+    /// the accounts registered by the deposits and the transfers.
+    #[no_mangle]
+    pub extern "C" fn set_storage_config() {
+        let mut io = entry_runtime("set_storage_config");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_storage_config");
+        let config: StorageConfig = io.read_input_borsh().sdk_unwrap();
+        EthConnectorContract::init_instance(io)
+            .set_storage_config(&config)
+            .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_storage_config() {
+        let mut io = entry_runtime("get_storage_config");
+        let config = EthConnectorContract::init_instance(io).get_storage_config();
+        io.return_output(&config.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
     #[no_mangle]
     pub extern "C" fn get_paused_flags() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_paused_flags");
         let paused_flags = EthConnectorContract::init_instance(io).get_paused_flags();
         let data = paused_flags.try_to_vec().expect(ERR_FAILED_PARSE);
         io.return_output(&data[..]);
     }
 
+    /// Pause the deposits, the withdrawals of nETH and the withdrawals of the ETH exited from
+    /// the EVM independently. Callable by the engine itself or the pause managers.
     #[no_mangle]
     pub extern "C" fn set_paused_flags() {
-        let io = Runtime;
-        io.assert_private_call().sdk_unwrap();
+        let io = entry_runtime("set_paused_flags");
+        if io.assert_private_call().is_err() {
+            let state = engine::get_state(&io).sdk_unwrap();
+            require_role(
+                &io,
+                &state,
+                Role::PauseManager,
+                &io.predecessor_account_id(),
+            );
+        }
+        record_admin_action(io, "set_paused_flags");
 
         let args: PauseEthConnectorCallArgs = io.read_input_borsh().sdk_unwrap();
         EthConnectorContract::init_instance(io).set_paused_flags(args);
     }
 
+    #[no_mangle]
+    pub extern "C" fn get_paused_precompiles() {
+        let mut io = entry_runtime("get_paused_precompiles");
+        let paused_mask = engine::get_paused_precompiles(&io);
+        io.return_output(&paused_mask.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Pause the precompiles of the given mask individually (e.g. the exits to NEAR) and
+    /// resume the other ones. Callable by the engine itself or the pause managers.
+    #[no_mangle]
+    pub extern "C" fn set_paused_precompiles() {
+        let mut io = entry_runtime("set_paused_precompiles");
+        if io.assert_private_call().is_err() {
+            let state = engine::get_state(&io).sdk_unwrap();
+            require_role(
+                &io,
+                &state,
+                Role::PauseManager,
+                &io.predecessor_account_id(),
+            );
+        }
+        record_admin_action(io, "set_paused_precompiles");
+
+        let args: PausePrecompilesCallArgs = io.read_input_borsh().sdk_unwrap();
+        engine::set_paused_precompiles(&mut io, args.paused_mask).sdk_unwrap();
+    }
+
     #[no_mangle]
     pub extern "C" fn get_accounts_counter() {
-        let io = Runtime;
+        let io = entry_runtime("get_accounts_counter");
         EthConnectorContract::init_instance(io).get_accounts_counter();
     }
 
     #[no_mangle]
     pub extern "C" fn get_erc20_from_nep141() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_erc20_from_nep141");
         let args: GetErc20FromNep141CallArgs = io.read_input_borsh().sdk_unwrap();
 
         io.return_output(
@@ -812,9 +2567,19 @@ mod contract {
         );
     }
 
+    /// Address the ERC-20 of the NEP-141 token is deployed at by `deploy_erc20_token`, whether
+    /// it is deployed yet or not.
+    #[no_mangle]
+    pub extern "C" fn get_erc20_deploy_address() {
+        let mut io = entry_runtime("get_erc20_deploy_address");
+        let args: GetErc20FromNep141CallArgs = io.read_input_borsh().sdk_unwrap();
+        let address = engine::erc20_deploy_address(&io.current_account_id(), &args.nep141);
+        io.return_output(address.as_bytes());
+    }
+
     #[no_mangle]
     pub extern "C" fn get_nep141_from_erc20() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("get_nep141_from_erc20");
         let erc20_address: crate::engine::ERC20Address =
             io.read_input().to_vec().try_into().sdk_unwrap();
         io.return_output(
@@ -827,17 +2592,291 @@ mod contract {
 
     #[no_mangle]
     pub extern "C" fn ft_metadata() {
-        let mut io = Runtime;
+        let mut io = entry_runtime("ft_metadata");
         let metadata: FungibleTokenMetadata = connector::get_metadata(&io).unwrap_or_default();
         let json_data = crate::json::JsonValue::from(metadata);
         io.return_output(json_data.to_string().as_bytes())
     }
 
+    #[no_mangle]
+    pub extern "C" fn set_icon_chunk() {
+        let mut io = entry_runtime("set_icon_chunk");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_role(
+            &io,
+            &state,
+            Role::MetadataManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "set_icon_chunk");
+        let args: SetIconChunkArgs = io.read_input_borsh().sdk_unwrap();
+        connector::set_icon_chunk(&mut io, args).sdk_unwrap();
+    }
+
+    /// Convert the state of the eth-connector to the layout of this engine, after an upgrade
+    /// of the contract. Returns the version of the state.
+    #[no_mangle]
+    pub extern "C" fn migrate() {
+        let mut io = entry_runtime("migrate");
+        io.assert_private_call().sdk_unwrap();
+        record_admin_action(io, "migrate");
+        let version = connector_migration::migrate(&mut io).sdk_unwrap();
+        io.return_output(&version.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Replace the NEP-148 metadata of nETH returned by `ft_metadata`.
+    #[no_mangle]
+    pub extern "C" fn set_metadata() {
+        let mut io = entry_runtime("set_metadata");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_role(
+            &io,
+            &state,
+            Role::MetadataManager,
+            &io.predecessor_account_id(),
+        );
+        record_admin_action(io, "set_metadata");
+        let metadata: FungibleTokenMetadata = io.read_input_borsh().sdk_unwrap();
+        connector::set_metadata(&mut io, metadata).sdk_unwrap();
+    }
+
+    ///
+    /// ETH STREAM METHODS
+    ///
+
+    /// Create a stream of nETH from the predecessor to the recipient.
+    /// Returns the identifier of the new stream.
+    #[no_mangle]
+    pub extern "C" fn create_stream() {
+        let mut io = entry_runtime("create_stream");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: CreateStreamCallArgs = io.read_input_borsh().sdk_unwrap();
+        let stream_id = eth_stream::create_stream(
+            io,
+            io.predecessor_account_id(),
+            &io.current_account_id(),
+            args,
+        )
+        .sdk_unwrap();
+        io.return_output(&stream_id.to_le_bytes());
+    }
+
+    /// Claim vested nETH of the stream. Returns the claimed amount.
+    #[no_mangle]
+    pub extern "C" fn claim_stream() {
+        let mut io = entry_runtime("claim_stream");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: StreamIdCallArgs = io.read_input_borsh().sdk_unwrap();
+        let amount = eth_stream::claim_stream(
+            io,
+            &io.predecessor_account_id(),
+            &io.current_account_id(),
+            args.stream_id,
+            io.block_timestamp().secs(),
+        )
+        .sdk_unwrap();
+        io.return_output(&amount.to_le_bytes());
+    }
+
+    /// Cancel the stream, can be called only by the stream sender.
+    #[no_mangle]
+    pub extern "C" fn cancel_stream() {
+        let io = entry_runtime("cancel_stream");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: StreamIdCallArgs = io.read_input_borsh().sdk_unwrap();
+        eth_stream::cancel_stream(
+            io,
+            &io.predecessor_account_id(),
+            &io.current_account_id(),
+            args.stream_id,
+            io.block_timestamp().secs(),
+        )
+        .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_stream() {
+        let mut io = entry_runtime("get_stream");
+        let args: StreamIdCallArgs = io.read_input_borsh().sdk_unwrap();
+        let stream = eth_stream::get_stream(&io, args.stream_id);
+        io.return_output(&stream.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    ///
+    /// MULTISIG METHODS
+    ///
+
+    /// Create a multisig wallet owned by NEAR accounts.
+    /// Returns the identifier of the new wallet.
+    #[no_mangle]
+    pub extern "C" fn create_multisig() {
+        let mut io = entry_runtime("create_multisig");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: CreateMultisigCallArgs = io.read_input_borsh().sdk_unwrap();
+        let multisig_id = multisig::create_multisig(&mut io, args).sdk_unwrap();
+        io.return_output(&multisig_id.to_le_bytes());
+    }
+
+    /// Propose a call from the wallet, can be called only by an owner of the wallet.
+    /// Returns the identifier of the proposal.
+    #[no_mangle]
+    pub extern "C" fn propose_multisig_call() {
+        let mut io = entry_runtime("propose_multisig_call");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: ProposeMultisigCallArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let proposal_id =
+            multisig::propose_call(&mut io, &predecessor_account_id, args).sdk_unwrap();
+        io.return_output(&proposal_id.to_le_bytes());
+    }
+
+    /// Approve a proposed call, can be called only by an owner of the wallet.
+    /// Returns the number of approvals of the call.
+    #[no_mangle]
+    pub extern "C" fn approve_multisig_call() {
+        let mut io = entry_runtime("approve_multisig_call");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: MultisigProposalCallArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let approvals = multisig::approve_call(
+            &mut io,
+            &predecessor_account_id,
+            args.multisig_id,
+            args.proposal_id,
+        )
+        .sdk_unwrap();
+        io.return_output(&approvals.to_le_bytes());
+    }
+
+    /// Make an approved call from the wallet, can be called only by an owner of the wallet.
+    /// An error of the engine fails the whole receipt, so the call can be made again.
+    #[no_mangle]
+    pub extern "C" fn execute_multisig_call() {
+        let mut io = entry_runtime("execute_multisig_call");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: MultisigProposalCallArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        let proposal = multisig::take_approved_call(
+            &mut io,
+            &predecessor_account_id,
+            args.multisig_id,
+            args.proposal_id,
+        )
+        .sdk_unwrap();
+        let origin = multisig::multisig_address(args.multisig_id);
+        let current_account_id = io.current_account_id();
+        let mut engine = Engine::new(origin, current_account_id, io, &io).sdk_unwrap();
+        engine
+            .call(
+                origin,
+                Address(proposal.contract),
+                Wei::from(proposal.value),
+                proposal.input,
+                u64::MAX,
+                Vec::new(),
+                &mut Runtime,
+            )
+            .map(|res| res.try_to_vec().sdk_expect("ERR_SERIALIZE"))
+            .sdk_process();
+    }
+
+    /// Remove a proposed call, can be called only by the proposer.
+    #[no_mangle]
+    pub extern "C" fn cancel_multisig_call() {
+        let mut io = entry_runtime("cancel_multisig_call");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: MultisigProposalCallArgs = io.read_input_borsh().sdk_unwrap();
+        let predecessor_account_id = io.predecessor_account_id();
+        multisig::cancel_call(
+            &mut io,
+            &predecessor_account_id,
+            args.multisig_id,
+            args.proposal_id,
+        )
+        .sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_multisig() {
+        let mut io = entry_runtime("get_multisig");
+        let args: MultisigIdCallArgs = io.read_input_borsh().sdk_unwrap();
+        let wallet = multisig::get_multisig(&io, args.multisig_id);
+        io.return_output(&wallet.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_multisig_proposal() {
+        let mut io = entry_runtime("get_multisig_proposal");
+        let args: MultisigProposalCallArgs = io.read_input_borsh().sdk_unwrap();
+        let proposal = multisig::get_proposal(&io, args.multisig_id, args.proposal_id);
+        io.return_output(&proposal.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    ///
+    /// SILO BRIDGE METHODS
+    ///
+
+    /// Allow or disallow transfers of nETH with another engine deployment.
+    #[no_mangle]
+    pub extern "C" fn set_remote_silo_allowed() {
+        let mut io = entry_runtime("set_remote_silo_allowed");
+        let state = engine::get_state(&io).sdk_unwrap();
+        require_owner_only(&state, &io.predecessor_account_id());
+        record_admin_action(io, "set_remote_silo_allowed");
+        let args: SetRemoteSiloAllowedArgs = io.read_input_borsh().sdk_unwrap();
+        silo_bridge::set_remote_silo_allowed(&mut io, &args.remote_silo, args.allowed);
+    }
+
+    #[no_mangle]
+    pub extern "C" fn get_remote_silo() {
+        let mut io = entry_runtime("get_remote_silo");
+        let account_id: AccountId = io.read_input_borsh().sdk_unwrap();
+        let remote_silo = silo_bridge::get_remote_silo(&io, &account_id);
+        io.return_output(&remote_silo.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+    }
+
+    /// Transfer nETH of the predecessor to an account on another engine deployment.
+    #[no_mangle]
+    pub extern "C" fn silo_bridge_transfer() {
+        let mut io = entry_runtime("silo_bridge_transfer");
+        io.assert_one_yocto().sdk_unwrap();
+        let args: SiloBridgeTransferArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_args = silo_bridge::transfer(
+            io,
+            io.predecessor_account_id(),
+            io.current_account_id(),
+            args,
+        )
+        .sdk_unwrap();
+        let promise_id = schedule_callback(&mut io, &promise_args);
+        io.promise_return(promise_id);
+    }
+
+    /// Called by an allowed remote silo to mint transferred nETH.
+    #[no_mangle]
+    pub extern "C" fn silo_bridge_receive() {
+        let io = entry_runtime("silo_bridge_receive");
+        let args: SiloBridgeReceiveArgs = io.read_input_borsh().sdk_unwrap();
+        silo_bridge::receive(io, io.predecessor_account_id(), args).sdk_unwrap();
+    }
+
+    #[no_mangle]
+    pub extern "C" fn silo_bridge_resolve_transfer() {
+        let mut io = entry_runtime("silo_bridge_resolve_transfer");
+        authorize_callback(&mut io, "silo_bridge_resolve_transfer");
+        if io.promise_results_count() != 1 {
+            sdk::panic_utf8(PROMISE_COUNT_ERR.as_bytes());
+        }
+        let args: SiloBridgeResolveTransferArgs = io.read_input_borsh().sdk_unwrap();
+        let promise_result = io.promise_result(0).sdk_unwrap();
+        silo_bridge::resolve_transfer(io, args, promise_result).sdk_unwrap();
+    }
+
     #[cfg(feature = "integration-test")]
     #[no_mangle]
     pub extern "C" fn verify_log_entry() {
         sdk::log!("Call from verify_log_entry");
-        let mut io = Runtime;
+        let mut io = entry_runtime("verify_log_entry");
         let data = true.try_to_vec().unwrap();
         io.return_output(&data[..]);
     }
@@ -850,7 +2889,7 @@ mod contract {
         const GAS_FOR_VERIFY: NearGas = NearGas::new(20_000_000_000_000);
         const GAS_FOR_FINISH: NearGas = NearGas::new(50_000_000_000_000);
 
-        let mut io = Runtime;
+        let mut io = entry_runtime("mint_account");
         let args: ([u8; 20], u64, u64) = io.read_input_borsh().sdk_expect("ERR_ARGS");
         let address = Address(args.0);
         let nonce = U256::from(args.1);
@@ -876,6 +2915,8 @@ mod contract {
             relayer_id: aurora_account_id.clone(),
             fee: 0.into(),
             msg: None,
+            memo: None,
+            sender: None,
         };
         let verify_call = aurora_engine_types::parameters::PromiseCreateArgs {
             target_account_id: aurora_account_id.clone(),
@@ -891,10 +2932,13 @@ mod contract {
             attached_balance: 0,
             attached_gas: GAS_FOR_FINISH.into_u64(),
         };
-        io.promise_crate_with_callback(&aurora_engine_types::parameters::PromiseWithCallbackArgs {
-            base: verify_call,
-            callback: finish_call,
-        });
+        schedule_callback(
+            &mut io,
+            &aurora_engine_types::parameters::PromiseWithCallbackArgs {
+                base: verify_call,
+                callback: finish_call,
+            },
+        );
     }
 
     ///
@@ -910,12 +2954,133 @@ mod contract {
         }
     }
 
+    /// The delay of the staged upgrade, the current delay if it is longer. The upgrades staged
+    /// before the delay was recorded use the current one.
+    fn internal_get_upgrade_delay(state: &EngineState) -> u64 {
+        let io = Runtime;
+        storage_key::read_config_u64(&io, ConfigKey::CodeStageDelay, &[])
+            .map(|delay| delay.max(state.upgrade_delay_blocks))
+            .unwrap_or(state.upgrade_delay_blocks)
+    }
+
+    /// Mints the tokens of a deposit whose proof was verified.
+    /// Finishes the deposit as the engine itself, the predecessor of the verification callbacks,
+    /// also when it wasn't verified by a prover in a callback but found in the proof cache.
+    fn finish_verified_deposit(mut io: Runtime, data: FinishDepositCallArgs) {
+        let current_account_id = io.current_account_id();
+        let result = EthConnectorContract::init_instance(io)
+            .finish_deposit(
+                current_account_id.clone(),
+                current_account_id,
+                data,
+                io.prepaid_gas(),
+                io.block_height(),
+            )
+            .sdk_unwrap();
+
+        match result {
+            FinishDepositResult::Near(result) => {
+                io.return_output(&result.try_to_vec().sdk_expect("ERR_SERIALIZE"));
+            }
+            FinishDepositResult::Eth(promise_args) => {
+                let promise_id = schedule_callback(&mut io, &promise_args);
+                io.promise_return(promise_id);
+            }
+        }
+    }
+
+    /// Logs the result of the NEAR call made by an exit, from its callback.
+    fn emit_xcc_result(
+        io: &mut Runtime,
+        block_height: u64,
+        sender: &Address,
+        result: Option<&PromiseResult>,
+    ) {
+        let log = match result {
+            Some(PromiseResult::Successful(output)) => {
+                engine_logs::xcc_result(sender, true, output)
+            }
+            _ => engine_logs::xcc_result(sender, false, &[]),
+        };
+        engine_logs::emit(io, block_height, log);
+    }
+
+    /// Schedules the promise with its callback, committing the engine ticket of the callback.
+    fn schedule_callback(io: &mut Runtime, promise_args: &PromiseWithCallbackArgs) -> PromiseId {
+        let current_account_id = io.current_account_id();
+        callback::issue_ticket(
+            io,
+            &current_account_id,
+            &current_account_id,
+            &promise_args.callback,
+        );
+        io.promise_crate_with_callback(promise_args)
+    }
+
+    /// Redeems the engine ticket of the callback method called with the current input.
+    fn authorize_callback(io: &mut Runtime, method: &str) {
+        let input = io.read_input().to_vec();
+        let current_account_id = io.current_account_id();
+        let predecessor_account_id = io.predecessor_account_id();
+        callback::authorize_callback(
+            io,
+            &current_account_id,
+            &predecessor_account_id,
+            method,
+            &input,
+        )
+        .sdk_unwrap();
+    }
+
+    /// Runtime of the entry point, which rejects an input above the limit of the method before
+    /// any of it is deserialized.
+    fn entry_runtime(method: &str) -> Runtime {
+        let io = Runtime;
+        input_limits::check_input(&io, method).sdk_unwrap();
+        io
+    }
+
+    /// Runs the transactions of the receipt with an IO counting the storage they touch, which
+    /// is reported in the `witness_size` event. The receipt fails above the witness size limit.
+    fn with_witness_limit<T, F>(io: Runtime, f: F) -> T
+    where
+        F: FnOnce(WitnessTracker<Runtime>) -> T,
+    {
+        let witness_size = Cell::new(WitnessSize::default());
+        let result = f(WitnessTracker::new(io, &witness_size));
+        let witness_size = witness_size.get();
+        sdk::log!(&events::witness_size_log(&witness_size));
+        witness::check_witness_size(&witness_size, witness::get_witness_size_limit(&io))
+            .sdk_unwrap();
+        result
+    }
+
     fn require_owner_only(state: &EngineState, predecessor_account_id: &AccountId) {
         if &state.owner_id != predecessor_account_id {
             sdk::panic_utf8(b"ERR_NOT_ALLOWED");
         }
     }
 
+    /// Requires the caller to be the owner or an account it granted the role to.
+    fn require_role(
+        io: &Runtime,
+        state: &EngineState,
+        role: Role,
+        predecessor_account_id: &AccountId,
+    ) {
+        if !access_control::has_role(io, state, role, predecessor_account_id) {
+            sdk::panic_utf8(b"ERR_NOT_ALLOWED");
+        }
+    }
+
+    /// Adds the current call to the log of the privileged actions, once it is allowed.
+    fn record_admin_action(mut io: Runtime, method: &str) {
+        let input = io.read_input().to_vec();
+        let actor = io.predecessor_account_id();
+        let block_height = io.block_height();
+        admin_log::record(&mut io, actor, method, &input, block_height);
+    }
+
     fn predecessor_address(predecessor_account_id: &AccountId) -> Address {
         near_account_to_evm_address(predecessor_account_id.as_bytes())
     }
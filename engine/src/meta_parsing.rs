@@ -1,9 +1,11 @@
-use crate::parameters::MetaCallArgs;
+use crate::parameters::{Eip712Domain, MetaCallArgs};
 use crate::prelude::precompiles::secp256k1::ecrecover;
 use crate::prelude::{
-    keccak, u256_to_arr, vec, Address, BorshDeserialize, Box, HashMap, InternalMetaCallArgs,
-    RawU256, String, ToOwned, ToString, Vec, Wei, H256, U256,
+    keccak, u256_to_arr, vec, Address, BorshDeserialize, Box, ConfigKey, HashMap,
+    InternalMetaCallArgs, RawU256, String, ToOwned, ToString, Vec, Wei, H256, U256,
 };
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
 use ethabi::{encode, Token as ABIToken};
 use logos::Logos;
 use rlp::{Decodable, DecoderError, Rlp};
@@ -139,18 +141,49 @@ pub fn parse_type(field_type: &str) -> ParsingResult<ArgType> {
 /// and https://eips.ethereum.org/EIPS/eip-712#rationale-for-domainseparator
 /// for definition and rationale for domainSeparator.
 pub fn near_erc712_domain(chain_id: U256) -> RawU256 {
-    let mut bytes = Vec::with_capacity(70);
-    bytes.extend_from_slice(
-        keccak("EIP712Domain(string name,string version,uint256 chainId)".as_bytes()).as_bytes(),
-    );
-    let near: RawU256 = keccak(b"NEAR").into();
-    bytes.extend_from_slice(&near);
-    let version: RawU256 = keccak(b"1").into();
-    bytes.extend_from_slice(&version);
-    bytes.extend_from_slice(&u256_to_arr(&chain_id));
+    erc712_domain(&Eip712Domain::default(), chain_id)
+}
+
+/// Separator of the domain, with `chain_id` as its chain ID unless the domain sets one.
+pub fn erc712_domain(domain: &Eip712Domain, chain_id: U256) -> RawU256 {
+    let mut bytes = Vec::with_capacity(5 * 32);
+    let type_hash = if domain.verifying_contract.is_some() {
+        keccak(
+            "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+                .as_bytes(),
+        )
+    } else {
+        keccak("EIP712Domain(string name,string version,uint256 chainId)".as_bytes())
+    };
+    bytes.extend_from_slice(type_hash.as_bytes());
+    bytes.extend_from_slice(&encode_string(&domain.name));
+    bytes.extend_from_slice(&encode_string(&domain.version));
+    match domain.chain_id {
+        Some(chain_id) => bytes.extend_from_slice(&chain_id),
+        None => bytes.extend_from_slice(&u256_to_arr(&chain_id)),
+    }
+    if let Some(verifying_contract) = domain.verifying_contract {
+        bytes.extend_from_slice(&encode_address(Address(verifying_contract)));
+    }
     keccak(&bytes).into()
 }
 
+/// Domain of the meta-transactions set by the owner, the default one if it was never set.
+pub fn get_eip712_domain<I: IO>(io: &I) -> Eip712Domain {
+    storage_key::read_config(io, ConfigKey::Eip712Domain, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+pub fn set_eip712_domain<I: IO>(io: &mut I, domain: &Eip712Domain) {
+    storage_key::write_config_borsh(io, ConfigKey::Eip712Domain, &[], domain);
+}
+
+/// Separator of the domain the meta-transactions are signed for in the engine.
+pub fn domain_separator<I: IO>(io: &I, chain_id: U256) -> RawU256 {
+    erc712_domain(&get_eip712_domain(io), chain_id)
+}
+
 /// method_sig: format like "adopt(uint256,PetObj)" (no additional PetObj definition)
 pub fn method_sig_to_abi(method_sig: &str) -> [u8; 4] {
     let mut result = [0u8; 4];
@@ -164,7 +197,6 @@ pub fn encode_address(addr: Address) -> Vec<u8> {
     bytes
 }
 
-#[allow(dead_code)]
 pub fn encode_string(s: &str) -> Vec<u8> {
     let mut bytes = vec![];
     bytes.extend_from_slice(keccak(s.as_bytes()).as_bytes());
@@ -576,6 +608,16 @@ pub fn parse_meta_call(
 ) -> ParsingResult<InternalMetaCallArgs> {
     let meta_tx =
         MetaCallArgs::try_from_slice(&args).map_err(|_| ParsingError::ArgumentParseError)?;
+    verify_meta_call(domain_separator, account_id, meta_tx)
+}
+
+/// Validate the decoded `MetaCallArgs` with given domain and account and recover the sender's
+/// address from the signature, as `parse_meta_call` does.
+pub fn verify_meta_call(
+    domain_separator: &RawU256,
+    account_id: &[u8],
+    meta_tx: MetaCallArgs,
+) -> ParsingResult<InternalMetaCallArgs> {
     let nonce = U256::from(meta_tx.nonce);
     let fee_amount = Wei::new(U256::from(meta_tx.fee_amount));
     let fee_address = Address::from(meta_tx.fee_address);
@@ -0,0 +1,251 @@
+//! Multisig wallets on Aurora owned by NEAR accounts.
+//!
+//! A wallet is an EVM address derived from its identifier, for which no secp256k1 key exists.
+//! Its owners are NEAR accounts: a call from the wallet is proposed and approved by the owners
+//! with NEAR transactions, authenticated by the NEAR runtime as the predecessor of the call,
+//! and made by the engine once it has the approvals of the threshold of the wallet. The calls
+//! of the owners require one attached yoctoNEAR, so that function call access keys can't be
+//! used to approve a call.
+use crate::parameters::{CreateMultisigCallArgs, ProposeMultisigCallArgs};
+use crate::prelude::{
+    bytes_to_key, format, sdk, vec, AccountId, Address, BorshDeserialize, BorshSerialize,
+    ConfigKey, KeyPrefix, RawAddress, Vec, WeiU256,
+};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+pub type MultisigId = u64;
+pub type ProposalId = u64;
+
+/// Maximum number of owners of a wallet, every approval checks the list of owners.
+pub const MAX_MULTISIG_OWNERS: usize = 16;
+
+/// Prefix of the preimage of the addresses of the wallets.
+const MULTISIG_ADDRESS_SEED: &[u8] = b"aurora-multisig";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultisigWallet {
+    pub address: RawAddress,
+    pub owners: Vec<AccountId>,
+    /// Number of approvals a call needs to be made.
+    pub threshold: u32,
+    pub next_proposal_id: ProposalId,
+}
+
+/// Call from a wallet waiting for the approvals of its owners.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultisigProposal {
+    pub contract: RawAddress,
+    pub value: WeiU256,
+    pub input: Vec<u8>,
+    /// Owners who approved the call, the proposer first.
+    pub approvals: Vec<AccountId>,
+}
+
+/// Wallet data and its proposals.
+const WALLET: u8 = 0x0;
+const PROPOSAL: u8 = 0x1;
+
+fn wallet_key(id: MultisigId) -> Vec<u8> {
+    bytes_to_key(
+        KeyPrefix::Multisig,
+        &[&[WALLET], &id.to_le_bytes()[..]].concat(),
+    )
+}
+
+fn proposal_key(id: MultisigId, proposal_id: ProposalId) -> Vec<u8> {
+    bytes_to_key(
+        KeyPrefix::Multisig,
+        &[
+            &[PROPOSAL],
+            &id.to_le_bytes()[..],
+            &proposal_id.to_le_bytes(),
+        ]
+        .concat(),
+    )
+}
+
+/// EVM address of the wallet, `keccak("aurora-multisig" ++ id)[12..]`.
+pub fn multisig_address(id: MultisigId) -> Address {
+    let preimage = [MULTISIG_ADDRESS_SEED, &id.to_le_bytes()].concat();
+    Address::from_slice(&sdk::keccak(&preimage)[12..])
+}
+
+pub fn get_multisig<I: IO>(io: &I, id: MultisigId) -> Option<MultisigWallet> {
+    io.read_storage(&wallet_key(id))
+        .and_then(|bytes| bytes.to_value().ok())
+}
+
+pub fn get_proposal<I: IO>(
+    io: &I,
+    id: MultisigId,
+    proposal_id: ProposalId,
+) -> Option<MultisigProposal> {
+    io.read_storage(&proposal_key(id, proposal_id))
+        .and_then(|bytes| bytes.to_value().ok())
+}
+
+fn next_multisig_id<I: IO>(io: &mut I) -> MultisigId {
+    let id = storage_key::read_config_u64(io, ConfigKey::NextMultisigId, &[]).unwrap_or(0);
+    storage_key::write_config(io, ConfigKey::NextMultisigId, &[], &(id + 1).to_le_bytes());
+    id
+}
+
+/// Create a new wallet. Returns the identifier of the wallet.
+pub fn create_multisig<I: IO>(
+    io: &mut I,
+    args: CreateMultisigCallArgs,
+) -> Result<MultisigId, error::MultisigError> {
+    let mut owners = args.owners.clone();
+    owners.sort_unstable();
+    owners.dedup();
+    if owners.is_empty() || owners.len() != args.owners.len() || owners.len() > MAX_MULTISIG_OWNERS
+    {
+        return Err(error::MultisigError::InvalidOwners);
+    }
+    if args.threshold == 0 || args.threshold as usize > owners.len() {
+        return Err(error::MultisigError::InvalidThreshold);
+    }
+
+    let id = next_multisig_id(io);
+    let wallet = MultisigWallet {
+        address: multisig_address(id).0,
+        owners: args.owners,
+        threshold: args.threshold,
+        next_proposal_id: 0,
+    };
+    sdk::log!(&format!(
+        "Create multisig {} at {} with threshold {}",
+        id,
+        hex::encode(wallet.address),
+        wallet.threshold
+    ));
+    io.write_borsh(&wallet_key(id), &wallet);
+
+    Ok(id)
+}
+
+fn get_owned_multisig<I: IO>(
+    io: &I,
+    predecessor_account_id: &AccountId,
+    id: MultisigId,
+) -> Result<MultisigWallet, error::MultisigError> {
+    let wallet = get_multisig(io, id).ok_or(error::MultisigError::MultisigNotFound)?;
+    if !wallet.owners.contains(predecessor_account_id) {
+        return Err(error::MultisigError::NotAllowed);
+    }
+    Ok(wallet)
+}
+
+/// Propose a call from the wallet, approved by the proposer. Returns the identifier of the
+/// proposal.
+pub fn propose_call<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    args: ProposeMultisigCallArgs,
+) -> Result<ProposalId, error::MultisigError> {
+    let mut wallet = get_owned_multisig(io, predecessor_account_id, args.multisig_id)?;
+    let proposal_id = wallet.next_proposal_id;
+    wallet.next_proposal_id += 1;
+    let proposal = MultisigProposal {
+        contract: args.contract,
+        value: args.value,
+        input: args.input,
+        approvals: vec![predecessor_account_id.clone()],
+    };
+    io.write_borsh(&wallet_key(args.multisig_id), &wallet);
+    io.write_borsh(&proposal_key(args.multisig_id, proposal_id), &proposal);
+
+    Ok(proposal_id)
+}
+
+/// Approve a proposed call. Returns the number of approvals of the call.
+pub fn approve_call<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    id: MultisigId,
+    proposal_id: ProposalId,
+) -> Result<u32, error::MultisigError> {
+    get_owned_multisig(io, predecessor_account_id, id)?;
+    let mut proposal =
+        get_proposal(io, id, proposal_id).ok_or(error::MultisigError::ProposalNotFound)?;
+    if proposal.approvals.contains(predecessor_account_id) {
+        return Err(error::MultisigError::AlreadyApproved);
+    }
+    proposal.approvals.push(predecessor_account_id.clone());
+    io.write_borsh(&proposal_key(id, proposal_id), &proposal);
+
+    Ok(proposal.approvals.len() as u32)
+}
+
+/// Remove a proposed call which has the approvals of the threshold of the wallet, for an owner
+/// to make it. The call is made only once, even if it reverts in the EVM. If the engine rejects
+/// the call (e.g. the wallet is frozen), the receipt fails and the call stays approved.
+pub fn take_approved_call<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    id: MultisigId,
+    proposal_id: ProposalId,
+) -> Result<MultisigProposal, error::MultisigError> {
+    let wallet = get_owned_multisig(io, predecessor_account_id, id)?;
+    let proposal =
+        get_proposal(io, id, proposal_id).ok_or(error::MultisigError::ProposalNotFound)?;
+    if (proposal.approvals.len() as u32) < wallet.threshold {
+        return Err(error::MultisigError::NotEnoughApprovals);
+    }
+    io.remove_storage(&proposal_key(id, proposal_id));
+
+    Ok(proposal)
+}
+
+/// Remove a proposed call without making it. Only the proposer is allowed to cancel it.
+pub fn cancel_call<I: IO>(
+    io: &mut I,
+    predecessor_account_id: &AccountId,
+    id: MultisigId,
+    proposal_id: ProposalId,
+) -> Result<(), error::MultisigError> {
+    let proposal =
+        get_proposal(io, id, proposal_id).ok_or(error::MultisigError::ProposalNotFound)?;
+    if proposal.approvals.first() != Some(predecessor_account_id) {
+        return Err(error::MultisigError::NotAllowed);
+    }
+    io.remove_storage(&proposal_key(id, proposal_id));
+    Ok(())
+}
+
+pub mod error {
+    const INVALID_OWNERS: &[u8; 27] = b"ERR_INVALID_MULTISIG_OWNERS";
+    const INVALID_THRESHOLD: &[u8; 30] = b"ERR_INVALID_MULTISIG_THRESHOLD";
+    const MULTISIG_NOT_FOUND: &[u8; 22] = b"ERR_MULTISIG_NOT_FOUND";
+    const PROPOSAL_NOT_FOUND: &[u8; 22] = b"ERR_PROPOSAL_NOT_FOUND";
+    const NOT_ALLOWED: &[u8; 15] = b"ERR_NOT_ALLOWED";
+    const ALREADY_APPROVED: &[u8; 20] = b"ERR_ALREADY_APPROVED";
+    const NOT_ENOUGH_APPROVALS: &[u8; 24] = b"ERR_NOT_ENOUGH_APPROVALS";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum MultisigError {
+        /// The owners are empty, too many or not unique.
+        InvalidOwners,
+        InvalidThreshold,
+        MultisigNotFound,
+        ProposalNotFound,
+        NotAllowed,
+        AlreadyApproved,
+        NotEnoughApprovals,
+    }
+
+    impl AsRef<[u8]> for MultisigError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidOwners => INVALID_OWNERS,
+                Self::InvalidThreshold => INVALID_THRESHOLD,
+                Self::MultisigNotFound => MULTISIG_NOT_FOUND,
+                Self::ProposalNotFound => PROPOSAL_NOT_FOUND,
+                Self::NotAllowed => NOT_ALLOWED,
+                Self::AlreadyApproved => ALREADY_APPROVED,
+                Self::NotEnoughApprovals => NOT_ENOUGH_APPROVALS,
+            }
+        }
+    }
+}
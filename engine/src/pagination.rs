@@ -0,0 +1,191 @@
+//! Enumeration of the collections of the eth-connector and of the engine, page by page.
+//!
+//! NEAR storage can't be iterated, so the items of every enumerable collection are also
+//! written to an index in the order they are added: the n-th added item is at position n.
+//! A `Cursor` is the position the next page starts at. Positions are never reused, so the items
+//! added while a client pages through a collection come after the ones it has seen already,
+//! and the removed items are skipped without shifting the other ones. An item removed and added
+//! again is moved to the end.
+use crate::connector::construct_contract_key;
+use crate::parameters::{Page, PageArgs};
+use crate::prelude::{BorshDeserialize, BorshSerialize, EthConnectorStorageId, Vec};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Maximum number of positions of the index read for a single page.
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Position in a collection at which the next page starts, returned with every page but the
+/// last one. It encodes the collection it was returned for, and is not meant to be built or
+/// inspected by the clients.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Cursor(Vec<u8>);
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct CursorData {
+    collection: u8,
+    position: u64,
+}
+
+impl Cursor {
+    fn new(collection: Collection, position: u64) -> Self {
+        let data = CursorData {
+            collection: collection as u8,
+            position,
+        };
+        Self(data.try_to_vec().unwrap())
+    }
+
+    fn position(&self, collection: Collection) -> Result<u64, error::PaginationError> {
+        match CursorData::try_from_slice(&self.0) {
+            Ok(data) if data.collection == collection as u8 => Ok(data.position),
+            _ => Err(error::PaginationError::InvalidCursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Collection {
+    /// Accounts registered with the eth-connector.
+    Accounts = 0x0,
+    /// Receipts of the finished deposits, by the key of their proof.
+    DepositReceipts = 0x1,
+    /// Withdrawals of nETH and of the ETH exited from the EVM.
+    Withdrawals = 0x2,
+    /// Transfer calls waiting for their `ft_resolve_transfer` callback, including the deposits
+    /// to EVM addresses.
+    PendingTransferCalls = 0x3,
+    /// Privileged calls to the engine, see `admin_log`.
+    AdminActions = 0x4,
+    /// Relayers credited a fee by a deposit.
+    RelayerEarnings = 0x5,
+}
+
+/// Number of positions used so far in the index of the collection.
+const LENGTH: u8 = 0x0;
+/// Item at a position of the index.
+const ENTRY: u8 = 0x1;
+/// Position of an item of a set, if it is in the set.
+const POSITION: u8 = 0x2;
+
+fn index_key(collection: Collection, kind: u8, suffix: &[u8]) -> Vec<u8> {
+    let mut key = construct_contract_key(&EthConnectorStorageId::Enumeration);
+    key.push(collection as u8);
+    key.push(kind);
+    key.extend_from_slice(suffix);
+    key
+}
+
+/// Number of positions used so far in the index of the collection, the position of the next
+/// added item.
+pub fn length<I: IO>(io: &I, collection: Collection) -> u64 {
+    io.read_u64(&index_key(collection, LENGTH, &[]))
+        .unwrap_or(0)
+}
+
+/// Adds the item at the end of the collection, for the collections which are logs.
+pub fn append<I: IO>(io: &mut I, collection: Collection, item: &[u8]) -> u64 {
+    let position = length(io, collection);
+    io.write_storage(&index_key(collection, ENTRY, &position.to_be_bytes()), item);
+    io.write_storage(
+        &index_key(collection, LENGTH, &[]),
+        &(position + 1).to_le_bytes(),
+    );
+    position
+}
+
+/// Adds the item at the end of the collection, unless it is already part of it. For the
+/// collections which are sets.
+pub fn insert<I: IO>(io: &mut I, collection: Collection, item: &[u8]) {
+    let position_key = index_key(collection, POSITION, item);
+    if io.storage_has_key(&position_key) {
+        return;
+    }
+    let position = append(io, collection, item);
+    io.write_storage(&position_key, &position.to_le_bytes());
+}
+
+/// Removes the item from a collection which is a set.
+pub fn remove<I: IO>(io: &mut I, collection: Collection, item: &[u8]) {
+    let position_key = index_key(collection, POSITION, item);
+    if let Ok(position) = io.read_u64(&position_key) {
+        io.remove_storage(&index_key(collection, ENTRY, &position.to_be_bytes()));
+        io.remove_storage(&position_key);
+    }
+}
+
+/// Reads the items of the collection in the order they were added, starting at the cursor of
+/// the arguments or at the beginning. At most `limit` positions are read, so a page can hold
+/// less items than the limit while more items follow. The items `read_item` returns `None`
+/// for are skipped as well.
+pub fn page<I, T, F>(
+    io: &I,
+    collection: Collection,
+    args: &PageArgs,
+    mut read_item: F,
+) -> Result<Page<T>, error::PaginationError>
+where
+    I: IO,
+    F: FnMut(Vec<u8>) -> Option<T>,
+{
+    if args.limit == 0 || args.limit > MAX_PAGE_LIMIT {
+        return Err(error::PaginationError::InvalidLimit);
+    }
+    let start = match args.cursor.as_ref() {
+        Some(cursor) => cursor.position(collection)?,
+        None => 0,
+    };
+    let length = length(io, collection);
+    let end = length.min(start.saturating_add(u64::from(args.limit)));
+    let items = (start..end)
+        .filter_map(|position| {
+            io.read_storage(&index_key(collection, ENTRY, &position.to_be_bytes()))
+                .and_then(|item| read_item(item.to_vec()))
+        })
+        .collect();
+    let next_cursor = if end < length {
+        Some(Cursor::new(collection, end))
+    } else {
+        None
+    };
+    Ok(Page { items, next_cursor })
+}
+
+pub mod error {
+    const INVALID_CURSOR: &[u8; 18] = b"ERR_INVALID_CURSOR";
+    const INVALID_LIMIT: &[u8; 22] = b"ERR_INVALID_PAGE_LIMIT";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum PaginationError {
+        /// The cursor wasn't returned for the enumerated collection.
+        InvalidCursor,
+        InvalidLimit,
+    }
+
+    impl AsRef<[u8]> for PaginationError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidCursor => INVALID_CURSOR,
+                Self::InvalidLimit => INVALID_LIMIT,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_is_bound_to_its_collection() {
+        let cursor = Cursor::new(Collection::Accounts, 7);
+        assert_eq!(cursor.position(Collection::Accounts), Ok(7));
+        assert_eq!(
+            cursor.position(Collection::Withdrawals),
+            Err(error::PaginationError::InvalidCursor)
+        );
+        assert_eq!(
+            Cursor(Vec::new()).position(Collection::Accounts),
+            Err(error::PaginationError::InvalidCursor)
+        );
+    }
+}
@@ -1,15 +1,23 @@
-use crate::admin_controlled::PausedMask;
+use crate::admin_controlled::{PausedMask, PausedPrecompilesMask};
+use crate::connector::EthConnector;
+use crate::engine::EngineState;
 use crate::fungible_token::FungibleTokenMetadata;
 use crate::json::{JsonError, JsonValue};
+use crate::pagination::Cursor;
 use crate::prelude::account_id::AccountId;
 use crate::prelude::{
-    format, Balance, BorshDeserialize, BorshSerialize, EthAddress, RawAddress, RawH256, RawU256,
-    String, ToString, TryFrom, Vec, WeiU256,
+    format, validate_eth_address, Balance, BorshDeserialize, BorshSerialize, EthAddress, NearGas,
+    RawAddress, RawH256, RawU256, String, ToString, TryFrom, Vec, WeiU256,
 };
 use crate::proof::Proof;
-use aurora_engine_types::types::Fee;
+use aurora_engine_precompiles::HostFunctions;
+use aurora_engine_types::parameters::AbiSchemaType;
+use aurora_engine_types::types::{Fee, Memo};
 use evm::backend::Log;
 
+#[cfg(feature = "std")]
+pub mod builders;
+
 /// Borsh-encoded parameters for the `new` function.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct NewCallArgs {
@@ -39,6 +47,39 @@ pub struct MetaCallArgs {
     pub args: Vec<u8>,
 }
 
+/// Borsh-encoded parameters for the `submit_with_fee_payer` function: the meta-transaction
+/// signed by its sender and the gas the fee payer submitting it pays for.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct FeePayerMetaCallArgs {
+    pub meta_call: MetaCallArgs,
+    pub gas_limit: u64,
+    pub gas_price: RawU256,
+}
+
+/// EIP-712 domain of the typed data signed by the senders of the meta-transactions. The
+/// default one is the domain the engine always used: `NEAR`, version `1` and the chain ID of
+/// the engine, without a verifying contract.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Eip712Domain {
+    pub name: String,
+    pub version: String,
+    /// Chain ID of the domain, the one of the engine if `None`.
+    pub chain_id: Option<RawU256>,
+    /// Contract verifying the signatures, not part of the domain if `None`.
+    pub verifying_contract: Option<RawAddress>,
+}
+
+impl Default for Eip712Domain {
+    fn default() -> Self {
+        Self {
+            name: "NEAR".to_string(),
+            version: "1".to_string(),
+            chain_id: None,
+            verifying_contract: None,
+        }
+    }
+}
+
 /// Borsh-encoded log for use in a `SubmitResult`.
 #[derive(Debug, BorshSerialize, BorshDeserialize)]
 pub struct ResultLog {
@@ -62,6 +103,13 @@ impl From<Log> for ResultLog {
     }
 }
 
+/// Signature and topic of an EVM log emitted by the engine, returned by `get_engine_log_topics`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EngineLogTopic {
+    pub signature: String,
+    pub topic: RawH256,
+}
+
 /// The status of a transaction.
 #[derive(Debug, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
 pub enum TransactionStatus {
@@ -71,6 +119,8 @@ pub enum TransactionStatus {
     OutOfFund,
     OutOfOffset,
     CallTooDeep,
+    /// The memory of a call frame grew beyond the limit set with `set_evm_limits`.
+    MemoryLimit,
 }
 
 impl TransactionStatus {
@@ -87,6 +137,7 @@ impl TransactionStatus {
             || *self == TransactionStatus::OutOfFund
             || *self == TransactionStatus::OutOfOffset
             || *self == TransactionStatus::CallTooDeep
+            || *self == TransactionStatus::MemoryLimit
     }
 }
 
@@ -99,10 +150,27 @@ impl AsRef<[u8]> for TransactionStatus {
             Self::OutOfGas => b"ERR_OUT_OF_GAS",
             Self::OutOfOffset => b"ERR_OUT_OF_OFFSET",
             Self::CallTooDeep => b"ERR_CALL_TOO_DEEP",
+            Self::MemoryLimit => b"ERR_MEMORY_LIMIT",
         }
     }
 }
 
+/// Borsh-encoded result of the `dry_run_submit` function.
+#[derive(Debug, Default, BorshSerialize, BorshDeserialize)]
+pub struct DryRunSubmitResult {
+    /// Signer of the transaction, if the signature is valid.
+    pub sender: Option<RawAddress>,
+    /// Error `submit` would fail with, e.g. `ERR_INCORRECT_NONCE`.
+    pub error: Option<Vec<u8>>,
+    /// Status of the transaction, if it would be executed.
+    pub status: Option<TransactionStatus>,
+    pub gas_used: u64,
+    pub logs: Vec<ResultLog>,
+    pub effective_gas_price: RawU256,
+    /// Fee charged to the sender, `gas_used * effective_gas_price`.
+    pub fee: RawU256,
+}
+
 /// Borsh-encoded parameters for the `call`, `call_with_args`, `deploy_code`,
 /// and `deploy_with_input` methods.
 #[derive(Debug, BorshSerialize, BorshDeserialize)]
@@ -131,6 +199,26 @@ impl SubmitResult {
     }
 }
 
+/// Borsh-encoded parameters for the `submit_batch` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub struct SubmitBatchArgs {
+    /// RLP encoded signed transactions, as given to `submit`.
+    pub transactions: Vec<Vec<u8>>,
+    /// Skip the transactions following the first rejected or unsuccessful one.
+    pub stop_on_failure: bool,
+    /// Maximum amount of EVM gas used by the whole batch.
+    pub gas_cap: Option<u64>,
+}
+
+/// Borsh-encoded result of a transaction of the `submit_batch` function.
+#[derive(Debug, BorshSerialize, BorshDeserialize)]
+pub enum BatchTransactionResult {
+    Submitted(SubmitResult),
+    /// The transaction was rejected before modifying any state, with the given error.
+    Rejected(Vec<u8>),
+    Skipped,
+}
+
 /// Borsh-encoded parameters for the engine `call` function.
 #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq, Clone)]
 pub struct FunctionCallArgsV2 {
@@ -214,9 +302,9 @@ pub struct BeginChainArgs {
     pub genesis_alloc: Vec<AccountBalance>,
 }
 
-/// Borsh-encoded parameters for the `begin_block` function.
-#[cfg(feature = "evm_bully")]
-#[derive(BorshSerialize, BorshDeserialize)]
+/// Borsh-encoded parameters for the `begin_block` function. Only the number and the
+/// timestamp are used by the engine, the other fields are kept for the replayer.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub struct BeginBlockArgs {
     /// The current block's hash (for replayer use).
     pub hash: RawU256,
@@ -266,31 +354,54 @@ impl From<NEP141FtOnTransferArgs> for String {
     }
 }
 
-/// Eth-connector deposit arguments
+/// Eth-connector isUsedProof arguments
 #[derive(BorshSerialize, BorshDeserialize)]
-pub struct DepositCallArgs {
+pub struct IsUsedProofCallArgs {
     /// Proof data
     pub proof: Proof,
-    /// Optional relayer address
-    pub relayer_eth_account: Option<EthAddress>,
 }
 
-/// Eth-connector isUsedProof arguments
+/// Borsh-encoded parameters for the `is_used_proof_key` function.
 #[derive(BorshSerialize, BorshDeserialize)]
-pub struct IsUsedProofCallArgs {
-    /// Proof data
-    pub proof: Proof,
+pub struct IsUsedProofKeyCallArgs {
+    /// Key of the proof, as returned by `Proof::get_key`.
+    pub key: String,
+}
+
+/// Borsh-encoded parameters for the `check_proofs` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CheckProofsCallArgs {
+    /// Keys of the proofs, as returned by `Proof::get_key`.
+    pub keys: Vec<String>,
 }
 
 /// withdraw result for eth-connector
-#[derive(BorshSerialize)]
-#[cfg_attr(not(target_arch = "wasm32"), derive(BorshDeserialize))]
+#[derive(BorshSerialize, BorshDeserialize)]
 pub struct WithdrawResult {
     pub amount: Balance,
     pub recipient_id: RawAddress,
     pub eth_custodian_address: RawAddress,
 }
 
+/// Borsh-encoded parameters for the `estimate_withdraw` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EstimateWithdrawArgs {
+    /// Account withdrawing its nETH.
+    pub account_id: AccountId,
+    pub recipient_address: EthAddress,
+    pub amount: Balance,
+}
+
+/// Borsh-encoded result of the `estimate_withdraw` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct WithdrawEstimate {
+    pub fee: Balance,
+    /// Amount the recipient receives on Ethereum.
+    pub net_amount: Balance,
+    /// Result `withdraw` would return.
+    pub result: WithdrawResult,
+}
+
 /// Fungible token storage balance
 #[derive(Default)]
 pub struct StorageBalance {
@@ -310,6 +421,26 @@ impl StorageBalance {
     }
 }
 
+/// Borsh-encoded parameters for the `set_storage_config` function, returned by
+/// `get_storage_config`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageConfig {
+    /// Storage deposit of an account in yoctoNEAR, the cost of the storage it uses if `None`.
+    pub min_balance: Option<Balance>,
+    /// Whether the accounts registered by a deposit or a transfer must be paid for by the
+    /// storage pool, failing once it is exhausted, instead of being registered for free.
+    pub pool_required: bool,
+}
+
+/// Storage deposit held for a registered account.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountStorageDeposit {
+    pub total: Balance,
+    /// Whether it was paid by the storage pool, which is refunded when the account is
+    /// unregistered.
+    pub from_pool: bool,
+}
+
 /// ft_resolve_transfer eth-connector call args
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ResolveTransferCallArgs {
@@ -318,6 +449,24 @@ pub struct ResolveTransferCallArgs {
     pub receiver_id: AccountId,
 }
 
+/// Outcome of the `ft_on_transfer` call, used to force resolve a transfer call
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TransferCallOutcome {
+    /// The receiver call failed, so the whole amount is refunded.
+    Failed,
+    /// The receiver call succeeded and returned the unused amount.
+    Succeeded { unused_amount: Balance },
+}
+
+/// force_resolve_transfer eth-connector call args
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ForceResolveTransferCallArgs {
+    pub sender_id: AccountId,
+    pub amount: Balance,
+    pub receiver_id: AccountId,
+    pub outcome: TransferCallOutcome,
+}
+
 /// Finish deposit NEAR eth-connector call args
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct FinishDepositCallArgs {
@@ -327,22 +476,81 @@ pub struct FinishDepositCallArgs {
     pub relayer_id: AccountId,
     pub fee: Fee,
     pub msg: Option<Vec<u8>>,
+    /// Memo of the deposit event.
+    pub memo: Option<String>,
+    /// Ethereum address the deposit was sent from, `None` for the tokens minted without a proof.
+    pub sender: Option<EthAddress>,
 }
 
-/// Deposit ETH args
-#[derive(Default, BorshDeserialize, BorshSerialize, Clone)]
-pub struct DepositEthCallArgs {
-    pub proof: Proof,
-    pub relayer_eth_account: EthAddress,
+/// Borsh-encoded result of the deposits to NEAR accounts, returned by the callback finishing
+/// the deposit.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FinishDepositNearResult {
+    pub recipient_id: AccountId,
+    /// Amount minted to the recipient, net of the fees.
+    pub amount: Balance,
+    pub relayer_id: AccountId,
+    /// Fee minted to the relayer.
+    pub fee: Balance,
+    /// Fee minted to the engine itself.
+    pub protocol_fee: Balance,
 }
 
-/// Finish deposit NEAR eth-connector call args
-#[derive(BorshSerialize, BorshDeserialize)]
-pub struct FinishDepositEthCallArgs {
-    pub new_owner_id: EthAddress,
+/// Record of a finished deposit, stored under the key of its proof.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DepositReceipt {
+    /// Account the tokens were minted for, the engine itself for the deposits to Aurora.
+    pub recipient_id: AccountId,
     pub amount: Balance,
-    pub fee: Balance,
-    pub relayer_eth_account: AccountId,
+    pub fee: Fee,
+    /// Part of the deposit taken by the protocol.
+    pub protocol_fee: Balance,
+    /// Memo of the deposit event, truncated to `MAX_RECEIPT_MEMO_LEN` bytes.
+    pub memo: Option<String>,
+}
+
+/// Deposit minted to an invalid or unreachable recipient, flagged by the owner to be
+/// reassigned, stored under the key of its proof.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnclaimedDeposit {
+    /// Block height the deposit was flagged at.
+    pub flagged_at: u64,
+    /// Account the deposit was reassigned to, once it is.
+    pub reassigned_to: Option<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `set_unclaimed_deposit` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetUnclaimedDepositArgs {
+    /// Key of the proof of the deposit.
+    pub deposit_id: String,
+    pub flagged: bool,
+}
+
+/// Borsh-encoded parameters for the `reassign_unclaimed` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReassignUnclaimedArgs {
+    /// Key of the proof of the deposit.
+    pub deposit_id: String,
+    pub new_recipient: AccountId,
+}
+
+/// Borsh-encoded parameters for the `claim_unclaimed_deposit` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClaimUnclaimedDepositArgs {
+    /// Key of the proof of the deposit.
+    pub deposit_id: String,
+}
+
+/// Borsh-encoded parameters for the `get_unclaimed_deposit` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetUnclaimedDepositArgs {
+    pub deposit_id: String,
+}
+
+/// Borsh-encoded parameters for the `get_deposit_receipt` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct GetDepositReceiptCallArgs {
     pub proof: Proof,
 }
 
@@ -357,13 +565,25 @@ pub struct InitCallArgs {
 /// Eth-connector Set contract data call args
 pub type SetContractDataCallArgs = InitCallArgs;
 
+/// Borsh-encoded parameters for the `add_custodian` and `remove_custodian` functions.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CustodianArgs {
+    /// Hex encoded address of the custodian contract.
+    pub address: String,
+}
+
 /// transfer eth-connector call args
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct TransferCallCallArgs {
     pub receiver_id: AccountId,
     pub amount: Balance,
-    pub memo: Option<String>,
+    pub memo: Option<Memo>,
     pub msg: String,
+    /// Gas attached to `ft_on_transfer` of the receiver, at most `MAX_GAS_FOR_FT_ON_TRANSFER`,
+    /// all the gas left if `None`. Only taken from the JSON arguments of `ft_transfer_call`,
+    /// the arguments of the deposits embedded in their messages keep their encoding.
+    #[borsh_skip]
+    pub gas_for_on_transfer: Option<NearGas>,
 }
 
 impl TryFrom<JsonValue> for TransferCallCallArgs {
@@ -372,13 +592,19 @@ impl TryFrom<JsonValue> for TransferCallCallArgs {
     fn try_from(v: JsonValue) -> Result<Self, Self::Error> {
         let receiver_id = AccountId::try_from(v.string("receiver_id")?)?;
         let amount = v.u128("amount")?;
-        let memo = v.string("memo").ok();
+        let memo = v.string("memo").ok().map(Memo::new).transpose()?;
         let msg = v.string("msg")?;
+        let gas_for_on_transfer = match v.u64("gas_for_on_transfer") {
+            Ok(gas) => Some(NearGas::new(gas)),
+            Err(JsonError::MissingValue) => None,
+            Err(e) => return Err(e.into()),
+        };
         Ok(Self {
             receiver_id,
             amount,
             memo,
             msg,
+            gas_for_on_transfer,
         })
     }
 }
@@ -435,7 +661,7 @@ impl From<JsonValue> for StorageWithdrawCallArgs {
 pub struct TransferCallArgs {
     pub receiver_id: AccountId,
     pub amount: Balance,
-    pub memo: Option<String>,
+    pub memo: Option<Memo>,
 }
 
 impl TryFrom<JsonValue> for TransferCallArgs {
@@ -445,7 +671,7 @@ impl TryFrom<JsonValue> for TransferCallArgs {
         Ok(Self {
             receiver_id: AccountId::try_from(v.string("receiver_id")?)?,
             amount: v.u128("amount")?,
-            memo: v.string("memo").ok(),
+            memo: v.string("memo").ok().map(Memo::new).transpose()?,
         })
     }
 }
@@ -456,6 +682,15 @@ pub struct BalanceOfCallArgs {
     pub account_id: AccountId,
 }
 
+/// Borsh-encoded result of the `get_balance_storage_layout` function. Key of the nETH balance
+/// of the account in the storage of the engine contract, to request a NEAR state proof of.
+/// The proven value is the Borsh-encoded `u128` balance, `None` if no balance is stored.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BalanceStorageLayout {
+    pub storage_key: Vec<u8>,
+    pub value: Option<Vec<u8>>,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct BalanceOfEthCallArgs {
     pub address: EthAddress,
@@ -471,6 +706,65 @@ impl TryFrom<JsonValue> for BalanceOfCallArgs {
     }
 }
 
+/// Most accounts or addresses of which `ft_balances_of` and `ft_balances_of_eth` return the
+/// balances in a single call.
+pub const MAX_BALANCES_OF_LEN: usize = 500;
+
+/// ft_balances_of args for json invocation
+pub struct BalancesOfCallArgs {
+    pub account_ids: Vec<AccountId>,
+}
+
+impl TryFrom<JsonValue> for BalancesOfCallArgs {
+    type Error = error::ParseTypeFromJsonError;
+
+    fn try_from(v: JsonValue) -> Result<Self, Self::Error> {
+        let values = v.array("account_ids")?;
+        if values.len() > MAX_BALANCES_OF_LEN {
+            return Err(error::ParseTypeFromJsonError::TooManyItems);
+        }
+        let account_ids = values
+            .iter()
+            .map(|value| -> Result<AccountId, Self::Error> {
+                match value {
+                    JsonValue::String(account_id) => Ok(AccountId::try_from(account_id.clone())?),
+                    _ => Err(JsonError::InvalidString.into()),
+                }
+            })
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(Self { account_ids })
+    }
+}
+
+/// ft_balances_of_eth args for json invocation, the hex addresses with or without `0x`
+pub struct BalancesOfEthCallArgs {
+    pub addresses: Vec<EthAddress>,
+}
+
+impl TryFrom<JsonValue> for BalancesOfEthCallArgs {
+    type Error = error::ParseTypeFromJsonError;
+
+    fn try_from(v: JsonValue) -> Result<Self, Self::Error> {
+        let values = v.array("addresses")?;
+        if values.len() > MAX_BALANCES_OF_LEN {
+            return Err(error::ParseTypeFromJsonError::TooManyItems);
+        }
+        let addresses = values
+            .iter()
+            .map(|value| -> Result<EthAddress, Self::Error> {
+                match value {
+                    JsonValue::String(address) => {
+                        let address = address.strip_prefix("0x").unwrap_or(address);
+                        Ok(validate_eth_address(address.to_string())?)
+                    }
+                    _ => Err(JsonError::InvalidString.into()),
+                }
+            })
+            .collect::<Result<_, Self::Error>>()?;
+        Ok(Self { addresses })
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct RegisterRelayerCallArgs {
     pub address: EthAddress,
@@ -481,6 +775,931 @@ pub struct PauseEthConnectorCallArgs {
     pub paused_mask: PausedMask,
 }
 
+/// Borsh-encoded parameters for the `set_paused_precompiles` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PausePrecompilesCallArgs {
+    pub paused_mask: PausedPrecompilesMask,
+}
+
+/// Borsh-encoded parameters for the `set_remote_silo_allowed` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SetRemoteSiloAllowedArgs {
+    pub remote_silo: AccountId,
+    pub allowed: bool,
+}
+
+/// Borsh-encoded parameters for the `silo_bridge_transfer` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SiloBridgeTransferArgs {
+    /// Engine account the nETH is sent to.
+    pub remote_silo: AccountId,
+    /// Account which receives nETH on the remote silo.
+    pub recipient_id: AccountId,
+    pub amount: Balance,
+}
+
+/// Borsh-encoded parameters for the `silo_bridge_receive` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SiloBridgeReceiveArgs {
+    pub sender_id: AccountId,
+    pub recipient_id: AccountId,
+    pub amount: Balance,
+}
+
+/// Borsh-encoded parameters for the `silo_bridge_resolve_transfer` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SiloBridgeResolveTransferArgs {
+    pub remote_silo: AccountId,
+    pub sender_id: AccountId,
+    pub amount: Balance,
+}
+
+/// Borsh-encoded parameters for the `set_force_resolve_delay` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SetForceResolveDelayCallArgs {
+    /// Number of blocks after which a pending transfer call can be force resolved.
+    pub delay_blocks: u64,
+}
+
+/// Borsh-encoded parameters for the `set_mint_circuit_breaker` function,
+/// also returned by the `get_mint_circuit_breaker` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MintCircuitBreakerArgs {
+    /// Length of the sliding window in blocks. Use zero to disable the circuit breaker.
+    pub window_blocks: u64,
+    /// Maximum amount of minted minus burned ETH within a window, after which
+    /// deposits are paused.
+    pub max_net_mint: Balance,
+}
+
+/// Borsh-encoded parameters for the `set_min_deposit` function,
+/// also returned by the `get_min_deposit` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct MinDepositArgs {
+    /// Minimum amount of a deposit, fee included.
+    pub min_amount: Balance,
+    /// Minimum amount a deposit has to exceed the relayer fee by.
+    pub min_net_amount: Balance,
+}
+
+/// Borsh-encoded parameters for the `set_recipient_rules` function,
+/// also returned by the `get_recipient_rules` function.
+///
+/// A suffix matches the account equal to it and its sub-accounts, e.g. `near` matches `near`
+/// and `alice.near` but not `alicenear`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct RecipientRules {
+    /// When not empty, the NEAR recipients of the deposits must match one of the suffixes.
+    pub allowed_suffixes: Vec<String>,
+    /// The NEAR recipients of the deposits must not match any of the suffixes.
+    pub denied_suffixes: Vec<String>,
+}
+
+/// Borsh-encoded parameters for the `set_proof_cache_config` function,
+/// also returned by the `get_proof_cache_config` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProofCacheConfig {
+    /// Number of blocks a receipt verified by a prover is trusted for without being verified
+    /// again, zero to disable the cache.
+    pub ttl_blocks: u64,
+}
+
+/// Borsh-encoded parameters for the `set_light_client` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetLightClientArgs {
+    /// Account providing the hashes of the final Ethereum blocks, `None` to verify the
+    /// deposits with the provers only.
+    pub account_id: Option<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `add_light_client_block_hash` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AddLightClientBlockHashArgs {
+    pub block_hash: RawH256,
+}
+
+/// Light client prover the eth-connector verifies the deposit proofs with.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProverConfig {
+    pub account_id: AccountId,
+    /// Disabled provers are skipped by the deposits.
+    pub enabled: bool,
+}
+
+/// Borsh-encoded parameters for the `set_provers` function.
+/// The provers are tried in order until one of them validates the proof.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetProversArgs {
+    pub provers: Vec<ProverConfig>,
+}
+
+/// Numbers of deposit proofs verified by a prover.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProverStatistics {
+    /// Proofs validated by the prover.
+    pub verified: u64,
+    /// Proofs rejected by the prover and handed over to the next one.
+    pub rejected: u64,
+}
+
+/// Borsh-encoded item of the result of the `get_provers` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProverStatus {
+    pub config: ProverConfig,
+    pub statistics: ProverStatistics,
+}
+
+/// Borsh-encoded parameters for the `resolve_deposit_verification` callback.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ResolveDepositVerificationArgs {
+    /// Prover which verified the proof.
+    pub prover_account_id: AccountId,
+    /// Borsh-encoded proof, to verify it with the next prover if this one rejected it.
+    pub proof: Vec<u8>,
+    pub finish_args: FinishDepositCallArgs,
+}
+
+/// Borsh-encoded parameters for the `set_icon_chunk` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetIconChunkArgs {
+    /// Position of the chunk in the icon, starting from zero.
+    pub index: u32,
+    /// Part of the icon data URL, must be ASCII.
+    pub chunk: Vec<u8>,
+}
+
+/// Borsh-encoded parameters for the `create_stream` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct CreateStreamCallArgs {
+    pub recipient_id: AccountId,
+    /// Total amount of nETH vested over the stream duration.
+    pub total: Balance,
+    /// Unix timestamp (in seconds) when vesting starts.
+    pub start: u64,
+    /// Unix timestamp (in seconds) when the total amount is vested.
+    pub end: u64,
+}
+
+/// Borsh-encoded parameters for the `claim_stream`, `cancel_stream` and `get_stream` functions.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StreamIdCallArgs {
+    pub stream_id: u64,
+}
+
+/// Borsh-encoded result of the `get_full_config` function.
+/// Contains the whole configuration of the engine, used to compare deployments.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct FullConfig {
+    /// Version of the document format.
+    pub version: u8,
+    pub engine_state: EngineState,
+    /// EVM hard fork used by the engine.
+    pub evm_hard_fork: String,
+    /// Eth-connector contract data, `None` if the eth-connector is not initialized.
+    pub eth_connector: Option<EthConnector>,
+    /// Eth-connector paused flags, `None` if the eth-connector is not initialized.
+    pub paused_mask: Option<PausedMask>,
+    /// Sha256 hash of the Borsh-encoded fungible token metadata.
+    pub metadata_hash: Option<RawH256>,
+    pub duplicate_submit_window: u64,
+    pub force_resolve_delay: u64,
+    pub chain_id_locked: bool,
+    pub unprotected_txs_allowed: bool,
+    pub evm_limits: EvmLimits,
+    pub block_attributes: BlockAttributes,
+    pub paused_precompiles: PausedPrecompilesMask,
+    pub host_functions: HostFunctions,
+    /// `None` unless the owner set the code compression.
+    pub code_compression: Option<bool>,
+    pub witness_size_limit: Option<u64>,
+    pub log_bloom_index_enabled: bool,
+    pub legacy_logs_enabled: bool,
+    pub storage_breakdown_enabled: bool,
+    pub callback_tickets_required: bool,
+    pub priority_fee_split: Option<PriorityFeeSplit>,
+    pub scheduled_tasks: Vec<ScheduledTask>,
+    pub pending_owner: Option<AccountId>,
+    /// Fee taken by the protocol on the deposits, in basis points.
+    pub deposit_protocol_fee: u16,
+    pub deposit_fee_currency: DepositFeeCurrency,
+    pub mint_circuit_breaker: Option<MintCircuitBreakerArgs>,
+    pub min_deposit: MinDepositArgs,
+    /// Sha256 hash of the Borsh-encoded rules the recipients of the deposits are checked against.
+    pub recipient_rules_hash: RawH256,
+    pub provers: Vec<ProverConfig>,
+    pub light_client: Option<AccountId>,
+    pub proof_cache_config: ProofCacheConfig,
+    pub extra_custodians: Vec<EthAddress>,
+}
+
+impl FullConfig {
+    pub const VERSION: u8 = 2;
+}
+
+/// Borsh-encoded parameters for the `supports_capability` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SupportsCapabilityCallArgs {
+    pub name: String,
+}
+
+/// Borsh-encoded parameters for the `set_storage_breakdown_enabled` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetStorageBreakdownEnabledArgs {
+    pub enabled: bool,
+}
+
+/// Borsh-encoded result of the `get_storage_breakdown` function (as an `Option`, which is
+/// `None` while the tracking is disabled). Approximate number of storage bytes used by
+/// each subsystem of the engine.
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct StorageBreakdown {
+    pub eth_connector: u64,
+    pub evm_accounts: u64,
+    pub receipts: u64,
+    pub registries: u64,
+    pub other: u64,
+}
+
+/// Split of the priority fees of the transactions between the relayers and a treasury.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PriorityFeeSplit {
+    pub treasury: RawAddress,
+    /// Share of the priority fees routed to the treasury, in basis points.
+    pub treasury_share: u16,
+}
+
+/// Borsh-encoded parameters for the `set_priority_fee_split` function.
+/// `None` routes all the priority fees to the relayers.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetPriorityFeeSplitArgs {
+    pub split: Option<PriorityFeeSplit>,
+}
+
+/// Limits of the EVM, Borsh-encoded parameters of `set_evm_limits` and result of
+/// `get_evm_limits`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvmLimits {
+    /// Maximum depth of nested calls, at most the 1024 of Ethereum.
+    pub max_call_depth: u16,
+    /// Maximum size of the memory of a single call frame in bytes, `None` if it is only
+    /// limited by gas as on Ethereum.
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl EvmLimits {
+    /// Maximum call depth of Ethereum.
+    pub const MAX_CALL_DEPTH: u16 = 1024;
+}
+
+impl Default for EvmLimits {
+    fn default() -> Self {
+        Self {
+            max_call_depth: Self::MAX_CALL_DEPTH,
+            max_memory_bytes: None,
+        }
+    }
+}
+
+/// Values of the block seen by the EVM which the owner of a silo can set, Borsh-encoded
+/// parameters of `set_block_attributes` and result of `get_block_attributes`. `None` keeps
+/// the default of the engine.
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockAttributes {
+    /// Address returned by the COINBASE opcode.
+    pub coinbase: Option<RawAddress>,
+    /// Value returned by the GASLIMIT opcode.
+    pub gas_limit: Option<u64>,
+    /// Maximum gas of the transactions submitted with `submit_batch` within a NEAR block,
+    /// over all the batches of the block.
+    pub block_gas_cap: Option<u64>,
+}
+
+/// Gas of the transactions submitted with `submit_batch` within a NEAR block.
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockGasUsage {
+    pub block_height: u64,
+    pub gas_used: u64,
+}
+
+/// Maintenance work the owner can schedule to be run periodically by anyone.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ScheduledTaskKind {
+    /// Removes the logs bloom filters of the blocks more than `retention_blocks` old,
+    /// at most `max_blocks_per_run` blocks per run.
+    PruneLogsBlooms {
+        retention_blocks: u64,
+        max_blocks_per_run: u64,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledTask {
+    pub kind: ScheduledTaskKind,
+    /// Number of blocks between two runs of the task.
+    pub interval_blocks: u64,
+    /// Amount paid from the treasury to the EVM address of the caller of `run_due_tasks`
+    /// for every run of the task (big-endian), in Wei.
+    pub reward: WeiU256,
+}
+
+/// Borsh-encoded parameters for the `set_scheduled_tasks` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetScheduledTasksArgs {
+    pub tasks: Vec<ScheduledTask>,
+}
+
+/// Borsh-encoded result of the `get_scheduled_tasks` function (as a `Vec`).
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledTaskStatus {
+    pub task: ScheduledTask,
+    /// Block height from which the task is due.
+    pub next_run_height: u64,
+    /// Progress of the task, e.g. the next block whose bloom filter is pruned.
+    pub cursor: u64,
+}
+
+/// Borsh-encoded parameters for the `run_due_tasks` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RunDueTasksArgs {
+    /// Maximum number of tasks to run.
+    pub limit: u32,
+}
+
+/// Borsh-encoded result of the `run_due_tasks` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RunDueTasksResult {
+    pub executed: u32,
+    /// Reward paid to the caller (big-endian), in Wei.
+    pub reward: WeiU256,
+}
+
+/// Borsh-encoded parameters for the `apply_state_diff` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApplyStateDiffArgs {
+    /// Raw storage keys with the value to write, `None` to remove the key.
+    pub diff: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    /// Only validate the diff and compute its digest, nothing is written.
+    pub dry_run: bool,
+}
+
+/// Borsh-encoded result of the `apply_state_diff` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateDiffResult {
+    pub written: u32,
+    pub removed: u32,
+    /// SHA-256 of the Borsh-encoded diff, to match the applied changes with a migration script.
+    pub digest: RawH256,
+}
+
+/// Borsh-encoded parameters for the `set_deposit_protocol_fee` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetDepositProtocolFeeArgs {
+    /// Fee in basis points of the deposited amount net of the relayer fee.
+    pub fee_bps: u16,
+}
+
+/// Borsh-encoded parameters for the `withdraw_protocol_fees` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawProtocolFeesArgs {
+    pub receiver_id: AccountId,
+    pub amount: Balance,
+}
+
+/// Currency the relayer fees of the deposits to the EVM are paid in, set by
+/// `set_deposit_fee_currency`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum DepositFeeCurrency {
+    /// The fee is paid in bridged ETH.
+    Eth,
+    /// The fee is converted into the base token of the silo by a swap on a DEX deployed on the
+    /// engine. The fee is paid in ETH when the swap fails.
+    BaseToken(FeeConversionRoute),
+}
+
+impl Default for DepositFeeCurrency {
+    fn default() -> Self {
+        Self::Eth
+    }
+}
+
+/// DEX route the relayer fees are converted along.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FeeConversionRoute {
+    /// Router with the interface of the Uniswap V2 one, called with `swapExactETHForTokens`.
+    pub router: RawAddress,
+    /// Tokens of the swap, from the wrapped ETH of the router to the base token.
+    pub path: Vec<RawAddress>,
+    /// Slippage bound: the minimum amount of the base token received for 1 ETH (10^18 wei),
+    /// below which the swap reverts.
+    pub min_amount_out_per_eth: RawU256,
+    /// Gas limit of the swap.
+    pub gas_limit: u64,
+}
+
+/// Borsh-encoded parameters for the `set_fee_waiver` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetFeeWaiverArgs {
+    pub relayer_id: AccountId,
+    /// Whether the deposits relayed by the account are not charged the protocol fee.
+    pub waived: bool,
+}
+
+/// Borsh-encoded parameters for the `is_fee_waived` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IsFeeWaivedCallArgs {
+    pub relayer_id: AccountId,
+}
+
+/// Borsh-encoded parameters of the enumeration views, e.g. `get_accounts_page`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PageArgs {
+    /// `next_cursor` of the previous page, `None` for the first page.
+    pub cursor: Option<Cursor>,
+    /// Maximum number of items of the page, at most `MAX_PAGE_LIMIT`.
+    pub limit: u32,
+}
+
+/// Borsh-encoded result of the enumeration views.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor of the next page, `None` if this is the last one.
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Item of the `get_accounts_page` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountBalance {
+    pub account_id: AccountId,
+    pub balance: Balance,
+}
+
+/// Borsh-encoded parameters for the `get_deposits_by_sender` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetDepositsBySenderCallArgs {
+    pub sender: EthAddress,
+    /// Maximum number of deposits returned, at most `MAX_DEPOSITS_PER_SENDER`.
+    pub limit: u32,
+}
+
+/// Item of the `get_deposit_receipts_page` and `get_deposits_by_sender` functions.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DepositReceiptEntry {
+    pub proof_key: String,
+    pub receipt: DepositReceipt,
+}
+
+/// Item of the `get_pending_transfer_calls_page` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PendingTransferCall {
+    pub args: ResolveTransferCallArgs,
+    /// Block heights of the pending transfer calls with these arguments, oldest first.
+    pub block_heights: Vec<u64>,
+}
+
+/// Fees the deposits credited to a relayer, see `connector::record_relayer_fee`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct RelayerEarnings {
+    /// Fees minted as nETH to the relayer by the deposits to NEAR accounts.
+    pub near_fees: Balance,
+    /// Fees minted as ETH to the EVM address of the relayer by the deposits to Aurora.
+    pub aurora_fees: Balance,
+    /// Number of the deposits which credited a fee to the relayer.
+    pub deposits: u64,
+}
+
+/// Borsh-encoded parameters for the `get_relayer_earnings` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetRelayerEarningsArgs {
+    pub relayer_id: AccountId,
+}
+
+/// Item of the `get_relayer_earnings_page` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RelayerEarningsEntry {
+    pub relayer_id: AccountId,
+    pub earnings: RelayerEarnings,
+}
+
+/// Borsh-encoded result of the `estimate_deposit_gas` function, in NEAR gas.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DepositGasEstimate {
+    /// Gas burnt by the `deposit` call itself, which grows with the size of the proof.
+    pub deposit: u64,
+    /// Gas attached to the `verify_log_entry` call of the first prover.
+    pub verify_log_entry: u64,
+    /// Gas attached to the callback finishing the deposit, including the fallbacks to the other
+    /// enabled provers.
+    pub finish_deposit: u64,
+    pub safety_margin: u64,
+    /// Gas to attach to the `deposit` call.
+    pub total: u64,
+}
+
+/// Privileged operations the owner delegates to other accounts. The owner has every role.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Pauses and resumes the eth-connector, the precompiles and the bridged tokens.
+    PauseManager,
+    /// Sets the metadata and the icon of nETH.
+    MetadataManager,
+    /// Sets the contract data of the eth-connector and its custodians.
+    CustodianManager,
+    /// Stages the upgrades of the engine.
+    UpgradeManager,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PauseManager => "pause_manager",
+            Self::MetadataManager => "metadata_manager",
+            Self::CustodianManager => "custodian_manager",
+            Self::UpgradeManager => "upgrade_manager",
+        }
+    }
+}
+
+/// Borsh-encoded parameters for the `propose_owner` function, `None` cancels the pending
+/// proposal.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProposeOwnerArgs {
+    pub new_owner_id: Option<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `set_role` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetRoleArgs {
+    pub role: Role,
+    pub account_id: AccountId,
+    /// Whether the role is granted or revoked.
+    pub granted: bool,
+}
+
+/// Borsh-encoded parameters for the `has_role` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HasRoleArgs {
+    pub role: Role,
+    pub account_id: AccountId,
+}
+
+/// Borsh-encoded parameters for the `set_erc20_locker` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetErc20LockerArgs {
+    /// Address of the ERC-20 locker on Ethereum, the only emitter of the accepted Locked events.
+    pub locker_address: EthAddress,
+}
+
+/// Borsh-encoded parameters for the `register_erc20_token` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RegisterErc20TokenArgs {
+    /// Address of the ERC-20 token on Ethereum.
+    pub token: EthAddress,
+    /// NEP-141 token minted for the deposits of the ERC-20, `None` to stop bridging it.
+    pub nep141: Option<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `get_relayer` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetRelayerArgs {
+    pub account_id: AccountId,
+}
+
+/// Borsh-encoded parameters for the `get_bridged_nep141` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetBridgedNep141Args {
+    pub token: EthAddress,
+}
+
+/// Finish deposit of an ERC-20 token call args
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FinishDepositErc20CallArgs {
+    pub nep141: AccountId,
+    pub new_owner_id: AccountId,
+    pub amount: Balance,
+    pub proof_key: String,
+}
+
+/// Borsh-encoded parameters for the `get_balances` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetBalancesCallArgs {
+    pub account_id: AccountId,
+    /// NEP-141 tokens whose ERC-20 balances are returned, the ones which are not bridged
+    /// to Aurora are skipped.
+    pub tokens: Vec<AccountId>,
+}
+
+/// Borsh-encoded parameters for the `get_erc20_balance` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GetErc20BalanceArgs {
+    /// ERC-20 of a bridged NEP-141 token.
+    pub erc20: RawAddress,
+    pub address: RawAddress,
+}
+
+/// Borsh-encoded parameters for the `check_token_supply` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CheckTokenSupplyArgs {
+    /// NEP-141 token bridged to Aurora.
+    pub token: AccountId,
+}
+
+/// Supplies of a NEP-141 token bridged to Aurora, returned by `check_token_supply`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TokenSupplyCheck {
+    pub erc20: RawAddress,
+    /// Amount of the NEP-141 token locked in the engine, `None` until the token is tracked.
+    pub locked: Option<u128>,
+    pub erc20_total_supply: RawU256,
+    /// Whether the locked amount differs from the total supply of the ERC-20.
+    pub diverged: bool,
+    /// Whether the deposits and exits of the token are paused.
+    pub paused: bool,
+}
+
+/// Borsh-encoded parameters for the `set_token_paused` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetTokenPausedArgs {
+    pub token: AccountId,
+    pub paused: bool,
+}
+
+/// Engine block opened by `begin_block` in the tests, grouping the transactions submitted
+/// until `end_block` whatever the NEAR blocks they are included in.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TestBlock {
+    pub number: u64,
+    /// Timestamp of the block in nanoseconds.
+    pub timestamp: u64,
+    /// Hashchain of the previous block, zero for the first one.
+    pub parent_hashchain: RawH256,
+    /// Hashchain of the transactions submitted so far.
+    pub hashchain: RawH256,
+    /// Hashes of the results of the transactions, in their order.
+    pub receipts: Vec<RawH256>,
+    pub gas_used: u64,
+}
+
+/// Block closed by `end_block`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TestBlockSummary {
+    pub number: u64,
+    pub timestamp: u64,
+    pub parent_hashchain: RawH256,
+    pub hashchain: RawH256,
+    /// Hash of the concatenated hashes of the results of the transactions.
+    pub receipts_root: RawH256,
+    pub transactions: u32,
+    pub gas_used: u64,
+}
+
+/// Balance of a NEP-141 token bridged to Aurora.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalance {
+    pub nep141: AccountId,
+    pub erc20: RawAddress,
+    /// Big-endian balance of the ERC-20, `None` if `balanceOf` failed.
+    pub balance: Option<RawU256>,
+}
+
+/// Borsh-encoded result of the `get_balances` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountBalances {
+    /// EVM address the NEAR account is mapped to.
+    pub address: RawAddress,
+    /// Balance of nETH on NEAR.
+    pub neth_balance: Balance,
+    /// Balance of the mapped address on Aurora (big-endian), in Wei.
+    pub eth_balance: WeiU256,
+    pub tokens: Vec<TokenBalance>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemAddressKind {
+    /// Precompiled contract.
+    Precompile,
+    /// Precompile scheduling the exits to NEAR or Ethereum.
+    ExitRouter,
+    /// Address of the engine itself, the admin of the bridged ERC-20 tokens.
+    EngineAdmin,
+    /// Address of the EVM logs the engine emits for the bridge activity.
+    EngineLogs,
+}
+
+/// Reserved EVM address, no code can be deployed at it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SystemAddress {
+    pub address: RawAddress,
+    pub kind: SystemAddressKind,
+}
+
+/// Borsh-encoded result of the `get_burned_total` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BurnedTotal {
+    /// Balance of the burn address on Aurora (big-endian), in Wei.
+    pub burned_on_aurora: WeiU256,
+    /// Amount of burned nETH.
+    pub burned_on_near: Balance,
+}
+
+/// Borsh-encoded parameters for the `freeze_account`, `unfreeze_account`
+/// and `is_account_frozen` functions.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum FreezeTarget {
+    /// NEAR account holding nETH.
+    Near(AccountId),
+    /// EVM address.
+    Evm(RawAddress),
+}
+
+/// Borsh-encoded parameters for the `register_abi_schema` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct RegisterAbiSchemaArgs {
+    pub schema_id: u32,
+    pub fields: Vec<AbiSchemaType>,
+}
+
+/// Borsh-encoded parameters for the `set_chain_id` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetChainIdArgs {
+    pub chain_id: RawU256,
+}
+
+/// Borsh-encoded parameters for the `deploy_entry_point` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeployEntryPointArgs {
+    /// Init code of the ERC-4337 EntryPoint, as given to the deterministic deployment proxy.
+    pub init_code: Vec<u8>,
+    /// Salt the EntryPoint was deployed with by the deterministic deployment proxy.
+    pub salt: RawH256,
+}
+
+/// Borsh-encoded parameters for the `handle_ops` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HandleOpsArgs {
+    /// ABI encoding of the `UserOperation[]` bundle, its length followed by its elements.
+    pub ops: Vec<u8>,
+}
+
+/// Borsh-encoded parameters for the `set_unprotected_txs_allowed` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetUnprotectedTxsArgs {
+    /// Whether the transactions signed without a chain ID (pre EIP-155) are accepted.
+    pub allowed: bool,
+}
+
+/// Borsh-encoded parameters for the `set_duplicate_submit_window` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SetDuplicateSubmitWindowArgs {
+    /// Number of blocks during which a resubmitted transaction is rejected.
+    /// Use zero to disable the check.
+    pub window_blocks: u64,
+}
+
+/// Borsh-encoded parameters for the `set_input_limit` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SetInputLimitArgs {
+    /// Name of the method of the contract.
+    pub method: String,
+    /// Maximum length of the input of the method, `None` restores its default limit.
+    pub max_len: Option<u64>,
+}
+
+/// Borsh-encoded parameters for the `set_log_bloom_index_enabled` function.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct SetLogBloomIndexArgs {
+    /// Whether the logs of submitted transactions are added to the per-block bloom index.
+    pub enabled: bool,
+}
+
+/// Borsh-encoded parameters for the `create_multisig` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CreateMultisigCallArgs {
+    /// NEAR accounts allowed to propose and approve the calls of the wallet.
+    pub owners: Vec<AccountId>,
+    /// Number of approvals a call needs to be made.
+    pub threshold: u32,
+}
+
+/// Borsh-encoded parameters for the `propose_multisig_call` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProposeMultisigCallArgs {
+    pub multisig_id: u64,
+    pub contract: RawAddress,
+    pub value: WeiU256,
+    pub input: Vec<u8>,
+}
+
+/// Borsh-encoded parameters for the `get_multisig` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultisigIdCallArgs {
+    pub multisig_id: u64,
+}
+
+/// Borsh-encoded parameters for the `approve_multisig_call`, `execute_multisig_call`,
+/// `cancel_multisig_call` and `get_multisig_proposal` functions.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MultisigProposalCallArgs {
+    pub multisig_id: u64,
+    pub proposal_id: u64,
+}
+
+/// Borsh-encoded parameters for the `set_gas_price` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetGasPriceArgs {
+    /// Minimum gas price of the submitted transactions, in wei.
+    pub price: RawU256,
+}
+
+/// Borsh-encoded parameters for the `set_gas_price_updater` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetGasPriceUpdaterArgs {
+    pub account_id: AccountId,
+    pub allowed: bool,
+}
+
+/// Borsh-encoded parameters for the `set_gas_price_max_age` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetGasPriceMaxAgeArgs {
+    /// Number of seconds after an update the gas price is enforced for, zero for no limit.
+    pub max_age_secs: u64,
+}
+
+/// Borsh-encoded result of the `get_gas_price` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GasPriceResult {
+    pub price: RawU256,
+    /// Block timestamp of the last update, in seconds.
+    pub updated_at: u64,
+    pub max_age: u64,
+    /// Whether the price is too old to be enforced.
+    pub is_stale: bool,
+}
+
+/// Borsh-encoded parameters for the `set_witness_size_limit` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetWitnessSizeLimitArgs {
+    /// Maximum number of storage bytes a submitted transaction can touch, `None` for no limit.
+    pub limit: Option<u64>,
+}
+
+/// Borsh-encoded parameters for the `set_upgrade_delay_blocks` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetUpgradeDelayBlocksArgs {
+    /// Number of blocks after staging before an upgrade can be deployed.
+    pub upgrade_delay_blocks: u64,
+}
+
+/// Borsh-encoded parameters for the `set_callback_tickets_required` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetCallbackTicketsRequiredArgs {
+    /// Whether the callbacks of the engine are rejected without an engine ticket, also when
+    /// they are called by the engine itself.
+    pub required: bool,
+}
+
+/// Borsh-encoded parameters for the `set_code_compression` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetCodeCompressionArgs {
+    /// Whether the code of the deployed contracts is stored compressed.
+    pub enabled: bool,
+}
+
+/// Borsh-encoded parameters for the `set_legacy_logs_enabled` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetLegacyLogsArgs {
+    /// Whether the log lines replaced by events are still emitted next to them.
+    pub enabled: bool,
+}
+
+/// View whose result can be read in chunks with `view_chunk`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub enum ViewRequest {
+    /// Result of the `view` function.
+    View(ViewCallArgs),
+    /// Result of the `get_code` function.
+    GetCode(RawAddress),
+    /// Result of the `get_full_config` function.
+    GetFullConfig,
+}
+
+/// Borsh-encoded parameters for the `view_chunk` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq, Eq)]
+pub struct ViewChunkArgs {
+    pub request: ViewRequest,
+    /// Index of the chunk, the first call uses `0` to learn the number of chunks.
+    pub index: u32,
+}
+
+/// Borsh-encoded result of the `view_chunk` function.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ViewChunk {
+    /// Number of chunks of the result, at least one.
+    pub total_chunks: u32,
+    /// Bytes of the result in the chunk, at most `VIEW_CHUNK_SIZE`.
+    pub data: Vec<u8>,
+}
+
 impl TryFrom<JsonValue> for ResolveTransferCallArgs {
     type Error = error::ParseTypeFromJsonError;
 
@@ -496,10 +1715,17 @@ impl TryFrom<JsonValue> for ResolveTransferCallArgs {
 pub mod error {
     use crate::json::JsonError;
     use aurora_engine_types::account_id::ParseAccountError;
+    use aurora_engine_types::types::{AddressValidationError, MemoError};
+
+    const TOO_MANY_ITEMS: &[u8; 18] = b"ERR_TOO_MANY_ITEMS";
 
     pub enum ParseTypeFromJsonError {
         Json(JsonError),
         InvalidAccount(ParseAccountError),
+        InvalidMemo(MemoError),
+        InvalidAddress(AddressValidationError),
+        /// The array is longer than the method accepts.
+        TooManyItems,
     }
 
     impl From<JsonError> for ParseTypeFromJsonError {
@@ -514,11 +1740,26 @@ pub mod error {
         }
     }
 
+    impl From<MemoError> for ParseTypeFromJsonError {
+        fn from(e: MemoError) -> Self {
+            Self::InvalidMemo(e)
+        }
+    }
+
+    impl From<AddressValidationError> for ParseTypeFromJsonError {
+        fn from(e: AddressValidationError) -> Self {
+            Self::InvalidAddress(e)
+        }
+    }
+
     impl AsRef<[u8]> for ParseTypeFromJsonError {
         fn as_ref(&self) -> &[u8] {
             match self {
                 Self::Json(e) => e.as_ref(),
                 Self::InvalidAccount(e) => e.as_ref(),
+                Self::InvalidMemo(e) => e.as_ref(),
+                Self::InvalidAddress(e) => e.as_ref(),
+                Self::TooManyItems => TOO_MANY_ITEMS,
             }
         }
     }
@@ -547,6 +1788,36 @@ mod tests {
         assert_eq!(x, res);
     }
 
+    #[test]
+    fn test_balances_of_args_from_json() {
+        let parse = |json: &str| {
+            BalancesOfEthCallArgs::try_from(crate::json::parse_json(json.as_bytes()).unwrap())
+        };
+        let args = parse(
+            r#"{"addresses": ["0x0101010101010101010101010101010101010101", "0202020202020202020202020202020202020202"]}"#,
+        );
+        assert_eq!(args.ok().unwrap().addresses, vec![[1; 20], [2; 20]]);
+        assert!(parse(r#"{"addresses": ["0x01"]}"#).is_err());
+        assert!(parse(r#"{"addresses": [1]}"#).is_err());
+
+        let args = BalancesOfCallArgs::try_from(
+            crate::json::parse_json(br#"{"account_ids": ["alice.near", "bob.near"]}"#).unwrap(),
+        );
+        let account_ids: Vec<AccountId> =
+            vec!["alice.near".parse().unwrap(), "bob.near".parse().unwrap()];
+        assert_eq!(args.ok().unwrap().account_ids, account_ids);
+        let too_many = format!(
+            r#"{{"account_ids": [{}]}}"#,
+            vec![r#""alice.near""#; MAX_BALANCES_OF_LEN + 1].join(",")
+        );
+        let args =
+            BalancesOfCallArgs::try_from(crate::json::parse_json(too_many.as_bytes()).unwrap());
+        assert!(matches!(
+            args,
+            Err(error::ParseTypeFromJsonError::TooManyItems)
+        ));
+    }
+
     #[test]
     fn test_call_args_deserialize() {
         let new_input = FunctionCallArgsV2 {
@@ -0,0 +1,294 @@
+//! Builders of the arguments of the eth-connector functions, for the Rust relayers and tests.
+//!
+//! A builder takes the required fields in its constructor and the optional ones with its
+//! setters, then checks the arguments the contract would reject before they are sent: an
+//! invalid payload fails to build instead of failing on chain after the gas was spent.
+//! `build_input` returns the Borsh encoded input of the function.
+use crate::connector;
+use crate::eth_rlp::{self, LogEntry};
+use crate::fungible_token::FungibleTokenMetadata;
+use crate::parameters::InitCallArgs;
+use crate::prelude::{AccountId, Address, BorshSerialize, TryFrom, Vec, WithdrawCallArgs};
+use crate::proof::Proof;
+
+/// Builds the arguments of `new_eth_connector` and `set_eth_connector_contract_data`.
+#[derive(Clone)]
+pub struct InitCallArgsBuilder {
+    prover_account: AccountId,
+    eth_custodian_address: Address,
+    metadata: FungibleTokenMetadata,
+}
+
+impl InitCallArgsBuilder {
+    pub fn new(prover_account: AccountId, eth_custodian_address: Address) -> Self {
+        Self {
+            prover_account,
+            eth_custodian_address,
+            metadata: FungibleTokenMetadata::default(),
+        }
+    }
+
+    /// Metadata of nETH, the one of Ether by default.
+    pub fn metadata(mut self, metadata: FungibleTokenMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn build(self) -> Result<InitCallArgs, error::BuilderError> {
+        if self.eth_custodian_address == Address::zero() {
+            return Err(error::BuilderError::ZeroCustodianAddress);
+        }
+        connector::validate_metadata(&self.metadata).map_err(error::BuilderError::Metadata)?;
+        Ok(InitCallArgs {
+            prover_account: self.prover_account,
+            eth_custodian_address: hex::encode(self.eth_custodian_address.as_bytes()),
+            metadata: self.metadata,
+        })
+    }
+
+    pub fn build_input(self) -> Result<Vec<u8>, error::BuilderError> {
+        encode(&self.build()?)
+    }
+}
+
+/// Builds the arguments of `withdraw`, a withdrawal of nETH to Ethereum.
+#[derive(Debug, Clone)]
+pub struct WithdrawCallArgsBuilder {
+    recipient_address: Address,
+    amount: u128,
+}
+
+impl WithdrawCallArgsBuilder {
+    pub fn new(recipient_address: Address, amount: u128) -> Self {
+        Self {
+            recipient_address,
+            amount,
+        }
+    }
+
+    pub fn build(self) -> Result<WithdrawCallArgs, error::BuilderError> {
+        // The ETH unlocked for the zero address could never be spent.
+        if self.recipient_address == Address::zero() {
+            return Err(error::BuilderError::ZeroRecipient);
+        }
+        if self.amount == 0 {
+            return Err(error::BuilderError::ZeroAmount);
+        }
+        Ok(WithdrawCallArgs {
+            recipient_address: self.recipient_address.0,
+            amount: self.amount,
+        })
+    }
+
+    pub fn build_input(self) -> Result<Vec<u8>, error::BuilderError> {
+        encode(&self.build()?)
+    }
+}
+
+/// Builds the proof `deposit` takes: the log entry of the deposit event, the receipt holding
+/// it and the block header the receipt is proven in.
+#[derive(Debug, Clone, Default)]
+pub struct ProofBuilder {
+    proof: Proof,
+}
+
+impl ProofBuilder {
+    pub fn new(header_data: Vec<u8>) -> Self {
+        Self {
+            proof: Proof {
+                header_data,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Receipt at the index in the block.
+    pub fn receipt(mut self, receipt_index: u64, receipt_data: Vec<u8>) -> Self {
+        self.proof.receipt_index = receipt_index;
+        self.proof.receipt_data = receipt_data;
+        self
+    }
+
+    /// Log entry of the deposit event at the index in the logs of the receipt.
+    pub fn log_entry(mut self, log_index: u64, log_entry_data: Vec<u8>) -> Self {
+        self.proof.log_index = log_index;
+        self.proof.log_entry_data = log_entry_data;
+        self
+    }
+
+    /// Nodes of the receipts trie from its root down to the receipt.
+    pub fn trie_proof(mut self, nodes: Vec<Vec<u8>>) -> Self {
+        self.proof.proof = nodes;
+        self
+    }
+
+    pub fn build(self) -> Result<Proof, error::BuilderError> {
+        let proof = self.proof;
+        rlp::decode::<LogEntry>(&proof.log_entry_data)
+            .map_err(|_| error::BuilderError::InvalidLogEntry)?;
+        let receipt_log = usize::try_from(proof.log_index)
+            .ok()
+            .and_then(|log_index| eth_rlp::receipt_log_at(&proof.receipt_data, log_index));
+        if receipt_log != Some(proof.log_entry_data.as_slice()) {
+            return Err(error::BuilderError::LogEntryNotInReceipt);
+        }
+        if !proof.is_receipt_in_header() {
+            return Err(error::BuilderError::ReceiptNotInHeader);
+        }
+        Ok(proof)
+    }
+
+    pub fn build_input(self) -> Result<Vec<u8>, error::BuilderError> {
+        encode(&self.build()?)
+    }
+}
+
+fn encode<T: BorshSerialize>(args: &T) -> Result<Vec<u8>, error::BuilderError> {
+    args.try_to_vec()
+        .map_err(|_| error::BuilderError::SerializationFailed)
+}
+
+pub mod error {
+    use crate::connector::error::MetadataError;
+
+    const ZERO_CUSTODIAN_ADDRESS: &[u8; 26] = b"ERR_ZERO_CUSTODIAN_ADDRESS";
+    const ZERO_RECIPIENT: &[u8; 18] = b"ERR_ZERO_RECIPIENT";
+    const ZERO_AMOUNT: &[u8; 15] = b"ERR_ZERO_AMOUNT";
+    const INVALID_LOG_ENTRY: &[u8; 21] = b"ERR_INVALID_LOG_ENTRY";
+    const LOG_ENTRY_NOT_IN_RECEIPT: &[u8; 28] = b"ERR_LOG_ENTRY_NOT_IN_RECEIPT";
+    const RECEIPT_NOT_IN_HEADER: &[u8; 25] = b"ERR_RECEIPT_NOT_IN_HEADER";
+    const SERIALIZATION_FAILED: &[u8; 13] = b"ERR_SERIALIZE";
+
+    #[cfg_attr(not(target_arch = "wasm32"), derive(Debug))]
+    pub enum BuilderError {
+        ZeroCustodianAddress,
+        Metadata(MetadataError),
+        ZeroRecipient,
+        ZeroAmount,
+        /// The log entry isn't RLP encoded.
+        InvalidLogEntry,
+        /// The log entry isn't the one at its index in the receipt.
+        LogEntryNotInReceipt,
+        /// The receipt isn't proven in the receipts trie of the block header.
+        ReceiptNotInHeader,
+        SerializationFailed,
+    }
+
+    impl AsRef<[u8]> for BuilderError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::ZeroCustodianAddress => ZERO_CUSTODIAN_ADDRESS,
+                Self::Metadata(e) => e.as_ref(),
+                Self::ZeroRecipient => ZERO_RECIPIENT,
+                Self::ZeroAmount => ZERO_AMOUNT,
+                Self::InvalidLogEntry => INVALID_LOG_ENTRY,
+                Self::LogEntryNotInReceipt => LOG_ENTRY_NOT_IN_RECEIPT,
+                Self::ReceiptNotInHeader => RECEIPT_NOT_IN_HEADER,
+                Self::SerializationFailed => SERIALIZATION_FAILED,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{vec, BorshDeserialize, String, H160, H256};
+    use rlp::RlpStream;
+
+    #[test]
+    fn test_init_call_args_builder() {
+        let prover: AccountId = "prover.near".parse().unwrap();
+        let custodian = Address::from([0x11; 20]);
+        let input = InitCallArgsBuilder::new(prover.clone(), custodian)
+            .build_input()
+            .unwrap();
+        let args = InitCallArgs::try_from_slice(&input).unwrap();
+        assert_eq!(args.prover_account, prover);
+        assert_eq!(args.eth_custodian_address, "11".repeat(20));
+        assert_eq!(
+            args.metadata.symbol,
+            FungibleTokenMetadata::default().symbol
+        );
+
+        assert!(matches!(
+            InitCallArgsBuilder::new(prover.clone(), Address::zero()).build(),
+            Err(error::BuilderError::ZeroCustodianAddress)
+        ));
+        let metadata = FungibleTokenMetadata {
+            symbol: String::new(),
+            ..Default::default()
+        };
+        let error = InitCallArgsBuilder::new(prover, custodian)
+            .metadata(metadata)
+            .build()
+            .unwrap_err();
+        assert_eq!(error.as_ref(), b"ERR_METADATA_EMPTY_SYMBOL");
+    }
+
+    #[test]
+    fn test_withdraw_call_args_builder() {
+        let recipient = Address::from([0x22; 20]);
+        let input = WithdrawCallArgsBuilder::new(recipient, 100)
+            .build_input()
+            .unwrap();
+        let args = WithdrawCallArgs::try_from_slice(&input).unwrap();
+        assert_eq!(args.recipient_address, recipient.0);
+        assert_eq!(args.amount, 100);
+
+        assert!(matches!(
+            WithdrawCallArgsBuilder::new(recipient, 0).build(),
+            Err(error::BuilderError::ZeroAmount)
+        ));
+        assert!(matches!(
+            WithdrawCallArgsBuilder::new(Address::zero(), 100).build(),
+            Err(error::BuilderError::ZeroRecipient)
+        ));
+    }
+
+    fn log(data: u8) -> LogEntry {
+        LogEntry {
+            address: H160([data; 20]),
+            topics: vec![H256([data; 32])],
+            data: vec![data],
+        }
+    }
+
+    fn receipt(logs: &[LogEntry]) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&vec![1u8]);
+        stream.append(&21_000u64);
+        stream.append(&vec![0u8; 256]);
+        stream.append_list::<LogEntry, _>(logs);
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_proof_builder() {
+        let receipt_data = receipt(&[log(1), log(2)]);
+        let build = |log_index, log_entry_data| {
+            ProofBuilder::new(Vec::new())
+                .receipt(0, receipt_data.clone())
+                .log_entry(log_index, log_entry_data)
+                .build()
+        };
+
+        assert!(matches!(
+            build(0, vec![0xff]),
+            Err(error::BuilderError::InvalidLogEntry)
+        ));
+        assert!(matches!(
+            build(0, rlp::encode(&log(2)).to_vec()),
+            Err(error::BuilderError::LogEntryNotInReceipt)
+        ));
+        assert!(matches!(
+            build(2, rlp::encode(&log(2)).to_vec()),
+            Err(error::BuilderError::LogEntryNotInReceipt)
+        ));
+        // The log entry is in the receipt, which isn't proven without a block header.
+        assert!(matches!(
+            build(1, rlp::encode(&log(2)).to_vec()),
+            Err(error::BuilderError::ReceiptNotInHeader)
+        ));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::prelude::{sdk, BorshDeserialize, BorshSerialize, String, ToString, Vec};
+use crate::eth_rlp;
+pub use crate::eth_rlp::header_receipts_root;
+use crate::prelude::{sdk, BorshDeserialize, BorshSerialize, String, ToString, TryFrom, Vec, H256};
 
 #[derive(Debug, Default, BorshDeserialize, BorshSerialize, Clone)]
 #[cfg_attr(test, derive(serde::Deserialize, serde::Serialize))]
@@ -12,14 +14,462 @@ pub struct Proof {
 }
 
 impl Proof {
+    /// Key of the used event of the proof: hex encoded hash of the block hash,
+    /// the receipt index and the log index, each encoded with a fixed length.
     pub fn get_key(&self) -> String {
-        let mut data = self.log_index.try_to_vec().unwrap();
-        data.extend(self.receipt_index.try_to_vec().unwrap());
-        data.extend(self.header_data.clone());
-        sdk::sha256(&data[..])
-            .0
-            .iter()
-            .map(|n| n.to_string())
-            .collect()
+        key(&self.header_data, self.receipt_index, self.log_index)
+    }
+
+    /// Whether the receipt of the proof is in the receipts trie of its block header, as proven
+    /// by the nodes of the proof, from the root of the trie down to the receipt.
+    pub fn is_receipt_in_header(&self) -> bool {
+        let receipts_root = match header_receipts_root(&self.header_data) {
+            Some(root) => root,
+            None => return false,
+        };
+        // The receipts are keyed by the RLP encoding of their index in the block.
+        let key = rlp::encode(&self.receipt_index);
+        verify_trie_proof(&receipts_root, &key, &self.proof)
+            .map(|receipt| receipt == self.receipt_data)
+            .unwrap_or(false)
+    }
+
+    /// Key of the used event used before `get_key`. It concatenates the decimal
+    /// representation of the hash bytes, so different hashes can map to the same key.
+    /// Only used to detect proofs which were recorded before the key derivation changed.
+    pub fn get_legacy_key(&self) -> String {
+        legacy_key(&self.header_data, self.receipt_index, self.log_index)
+    }
+}
+
+/// Borrowed view of a Borsh-encoded `Proof`, decoded without copying its byte vectors.
+///
+/// The deposits only read the log entry and the keys of a proof, then forward the raw proof to
+/// the prover, so decoding it into a `Proof` would copy the receipt, the header and the nodes of
+/// the proof for nothing. The view references the input buffer instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofView<'a> {
+    pub log_index: u64,
+    pub log_entry_data: &'a [u8],
+    pub receipt_index: u64,
+    pub receipt_data: &'a [u8],
+    pub header_data: &'a [u8],
+    /// Number of the nodes of the proof.
+    pub proof_len: u32,
+}
+
+impl<'a> ProofView<'a> {
+    /// Decodes the view, failing as `Proof::try_from_slice` would on the same input.
+    pub fn decode(input: &'a [u8]) -> Result<Self, error::ProofDecodeError> {
+        let mut reader = Reader(input);
+        let log_index = reader.u64()?;
+        let log_entry_data = reader.bytes()?;
+        let receipt_index = reader.u64()?;
+        let receipt_data = reader.bytes()?;
+        let header_data = reader.bytes()?;
+        let proof_len = reader.u32()?;
+        for _ in 0..proof_len {
+            reader.bytes()?;
+        }
+        if !reader.0.is_empty() {
+            return Err(error::ProofDecodeError);
+        }
+        Ok(Self {
+            log_index,
+            log_entry_data,
+            receipt_index,
+            receipt_data,
+            header_data,
+            proof_len,
+        })
+    }
+
+    /// Same as `Proof::get_key`.
+    pub fn get_key(&self) -> String {
+        key(self.header_data, self.receipt_index, self.log_index)
+    }
+
+    /// Same as `Proof::get_legacy_key`.
+    pub fn get_legacy_key(&self) -> String {
+        legacy_key(self.header_data, self.receipt_index, self.log_index)
+    }
+
+    /// Hash identifying the receipt of the proof in its block, whatever the log of the
+    /// receipt the proof is about.
+    pub fn receipt_hash(&self) -> H256 {
+        let mut data = Vec::with_capacity(72);
+        data.extend_from_slice(sdk::keccak(self.header_data).as_bytes());
+        data.extend_from_slice(&self.receipt_index.to_be_bytes());
+        data.extend_from_slice(sdk::keccak(self.receipt_data).as_bytes());
+        sdk::keccak(&data)
+    }
+
+    /// Whether the log entry of the proof is the log at its index in the receipt, so that a
+    /// verified receipt proves the log entry as well.
+    pub fn is_log_entry_in_receipt(&self) -> bool {
+        usize::try_from(self.log_index)
+            .ok()
+            .and_then(|log_index| eth_rlp::receipt_log_at(self.receipt_data, log_index))
+            .map(|log| log == self.log_entry_data)
+            .unwrap_or(false)
+    }
+}
+
+/// Remaining input of a `ProofView` being decoded.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], error::ProofDecodeError> {
+        if self.0.len() < len {
+            return Err(error::ProofDecodeError);
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, error::ProofDecodeError> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64, error::ProofDecodeError> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// A `Vec<u8>`: its length as a `u32`, then its bytes.
+    fn bytes(&mut self) -> Result<&'a [u8], error::ProofDecodeError> {
+        let len = self.u32()?;
+        self.take(len as usize)
+    }
+}
+
+/// Value at the key of a Merkle-Patricia trie, proven by the nodes on its path from the root.
+/// The first node hashes to the root, each following one to the reference the previous one
+/// has to it, except for the nodes shorter than a hash, embedded in their parent. `None` if
+/// the nodes don't prove a value at the key.
+pub fn verify_trie_proof(root: &H256, key: &[u8], nodes: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let key: Vec<u8> = key
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect();
+    let mut nodes = nodes.iter();
+    let mut next = TrieNodeRef::Hash(*root);
+    let mut pos = 0;
+    loop {
+        let node = match next {
+            TrieNodeRef::Hash(hash) => {
+                let node = nodes.next()?;
+                if sdk::keccak(node) != hash {
+                    return None;
+                }
+                node.clone()
+            }
+            TrieNodeRef::Embedded(node) => node,
+        };
+        let node = rlp::Rlp::new(&node);
+        match node.item_count().ok()? {
+            // Branch: a child for each nibble, then the value of the path ending at the node.
+            17 => {
+                if pos == key.len() {
+                    return proven_value(node.at(16).ok()?.data().ok()?, nodes);
+                }
+                next = TrieNodeRef::decode(&node.at(usize::from(key[pos])).ok()?)?;
+                pos += 1;
+            }
+            // Leaf or extension: the nibbles of the path, then the value or the child.
+            2 => {
+                let (path, is_leaf) = decode_hex_prefix(node.at(0).ok()?.data().ok()?)?;
+                if !key[pos..].starts_with(&path) {
+                    return None;
+                }
+                pos += path.len();
+                if is_leaf {
+                    if pos != key.len() {
+                        return None;
+                    }
+                    return proven_value(node.at(1).ok()?.data().ok()?, nodes);
+                }
+                next = TrieNodeRef::decode(&node.at(1).ok()?)?;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// The value found at the key, the proof must have no nodes beyond it.
+fn proven_value(value: &[u8], mut remaining: core::slice::Iter<Vec<u8>>) -> Option<Vec<u8>> {
+    if value.is_empty() || remaining.next().is_some() {
+        None
+    } else {
+        Some(value.to_vec())
+    }
+}
+
+/// Reference of a trie node to a child.
+enum TrieNodeRef {
+    Hash(H256),
+    Embedded(Vec<u8>),
+}
+
+impl TrieNodeRef {
+    /// `None` for an empty reference, the key has no value.
+    fn decode(item: &rlp::Rlp) -> Option<Self> {
+        if item.is_list() {
+            return Some(Self::Embedded(item.as_raw().to_vec()));
+        }
+        let data = item.data().ok()?;
+        if data.len() == 32 {
+            Some(Self::Hash(H256::from_slice(data)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Nibbles of the hex-prefix encoded path of a leaf or an extension, and whether it is a leaf.
+fn decode_hex_prefix(path: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let flags = path.first()? >> 4;
+    if flags > 3 {
+        return None;
+    }
+    let mut nibbles = Vec::with_capacity(path.len() * 2);
+    // An odd path has its first nibble in the flags byte.
+    if flags & 1 == 1 {
+        nibbles.push(path[0] & 0xf);
+    }
+    nibbles.extend(path[1..].iter().flat_map(|byte| [byte >> 4, byte & 0xf]));
+    Some((nibbles, flags & 2 == 2))
+}
+
+fn key(header_data: &[u8], receipt_index: u64, log_index: u64) -> String {
+    let block_hash = sdk::keccak(header_data);
+    let mut data = Vec::with_capacity(48);
+    data.extend_from_slice(block_hash.as_bytes());
+    data.extend_from_slice(&receipt_index.to_be_bytes());
+    data.extend_from_slice(&log_index.to_be_bytes());
+    hex::encode(sdk::sha256(&data).as_bytes())
+}
+
+fn legacy_key(header_data: &[u8], receipt_index: u64, log_index: u64) -> String {
+    let mut data = log_index.try_to_vec().unwrap();
+    data.extend(receipt_index.try_to_vec().unwrap());
+    data.extend_from_slice(header_data);
+    sdk::sha256(&data[..])
+        .0
+        .iter()
+        .map(|n| n.to_string())
+        .collect()
+}
+
+pub mod error {
+    const PROOF_DECODE: &[u8; 16] = b"ERR_FAILED_PARSE";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ProofDecodeError;
+
+    impl AsRef<[u8]> for ProofDecodeError {
+        fn as_ref(&self) -> &[u8] {
+            PROOF_DECODE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::vec;
+
+    fn proof(log_index: u64, receipt_index: u64, header_data: Vec<u8>) -> Proof {
+        Proof {
+            log_index,
+            receipt_index,
+            header_data,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_key_commits_to_all_indices() {
+        let key = proof(0, 0, vec![1, 2, 3]).get_key();
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, proof(1, 0, vec![1, 2, 3]).get_key());
+        assert_ne!(key, proof(0, 1, vec![1, 2, 3]).get_key());
+        assert_ne!(key, proof(0, 0, vec![1, 2, 4]).get_key());
+        // Swapping the receipt and the log indices gives a different key
+        assert_ne!(
+            proof(1, 2, vec![1, 2, 3]).get_key(),
+            proof(2, 1, vec![1, 2, 3]).get_key()
+        );
+    }
+
+    #[test]
+    fn test_key_differs_from_legacy_key() {
+        let proof = proof(1, 2, vec![1, 2, 3]);
+        assert_ne!(proof.get_key(), proof.get_legacy_key());
+    }
+
+    #[test]
+    fn test_proof_view() {
+        let proof = Proof {
+            log_index: 1,
+            log_entry_data: vec![1, 2],
+            receipt_index: 2,
+            receipt_data: vec![3],
+            header_data: vec![4, 5, 6],
+            proof: vec![vec![7], vec![], vec![8, 9]],
+        };
+        let input = proof.try_to_vec().unwrap();
+        let view = ProofView::decode(&input).unwrap();
+        assert_eq!(view.log_index, 1);
+        assert_eq!(view.log_entry_data, &[1, 2]);
+        assert_eq!(view.receipt_index, 2);
+        assert_eq!(view.receipt_data, &[3]);
+        assert_eq!(view.header_data, &[4, 5, 6]);
+        assert_eq!(view.proof_len, 3);
+        assert_eq!(view.get_key(), proof.get_key());
+        assert_eq!(view.get_legacy_key(), proof.get_legacy_key());
+
+        assert_eq!(
+            ProofView::decode(&input[..input.len() - 1]),
+            Err(error::ProofDecodeError)
+        );
+        let mut trailing = input;
+        trailing.push(0);
+        assert_eq!(ProofView::decode(&trailing), Err(error::ProofDecodeError));
+    }
+
+    #[test]
+    fn test_is_log_entry_in_receipt() {
+        let log = |data: u8| {
+            let mut stream = rlp::RlpStream::new_list(3);
+            stream.append(&vec![data; 20]);
+            stream.begin_list(0);
+            stream.append(&vec![data]);
+            stream.out().to_vec()
+        };
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream.append(&1u8);
+        stream.append(&21_000u64);
+        stream.append(&vec![0u8; 256]);
+        stream.begin_list(2);
+        stream.append_raw(&log(1), 1);
+        stream.append_raw(&log(2), 1);
+        let receipt_data = stream.out().to_vec();
+
+        let input = |log_index: u64, log_entry_data: Vec<u8>, receipt_data: Vec<u8>| {
+            Proof {
+                log_index,
+                log_entry_data,
+                receipt_data,
+                ..Default::default()
+            }
+            .try_to_vec()
+            .unwrap()
+        };
+        let in_receipt =
+            |input: Vec<u8>| ProofView::decode(&input).unwrap().is_log_entry_in_receipt();
+        assert!(in_receipt(input(1, log(2), receipt_data.clone())));
+        assert!(!in_receipt(input(0, log(2), receipt_data.clone())));
+        assert!(!in_receipt(input(2, log(2), receipt_data.clone())));
+        // Typed receipt
+        let typed_receipt = [&[2u8], receipt_data.as_slice()].concat();
+        assert!(in_receipt(input(0, log(1), typed_receipt)));
+        assert!(!in_receipt(input(0, log(1), vec![1, 2, 3])));
+    }
+
+    fn leaf(path: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(2);
+        stream.append(&path.to_vec());
+        stream.append(&value.to_vec());
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn test_verify_trie_proof() {
+        let receipt_0 = vec![1u8; 40];
+        let receipt_1 = vec![2u8; 40];
+        // The keys 0x80 and 0x01 of the receipts 0 and 1 diverge at their first nibble, their
+        // leaves hold the odd remaining paths [0] and [1].
+        let leaf_0 = leaf(&[0x30], &receipt_0);
+        let leaf_1 = leaf(&[0x31], &receipt_1);
+        let mut stream = rlp::RlpStream::new_list(17);
+        for nibble in 0..16 {
+            match nibble {
+                0 => stream.append(&sdk::keccak(&leaf_1).as_bytes().to_vec()),
+                8 => stream.append(&sdk::keccak(&leaf_0).as_bytes().to_vec()),
+                _ => stream.append_empty_data(),
+            };
+        }
+        stream.append_empty_data();
+        let branch = stream.out().to_vec();
+        let root = sdk::keccak(&branch);
+
+        let key_0 = rlp::encode(&0u64);
+        let key_1 = rlp::encode(&1u64);
+        assert_eq!(
+            verify_trie_proof(&root, &key_0, &[branch.clone(), leaf_0.clone()]),
+            Some(receipt_0.clone())
+        );
+        assert_eq!(
+            verify_trie_proof(&root, &key_1, &[branch.clone(), leaf_1.clone()]),
+            Some(receipt_1)
+        );
+        // The leaf of another key, a missing or an extra node, or another root
+        assert_eq!(
+            verify_trie_proof(&root, &key_0, &[branch.clone(), leaf_1]),
+            None
+        );
+        assert_eq!(verify_trie_proof(&root, &key_0, &[branch.clone()]), None);
+        assert_eq!(
+            verify_trie_proof(
+                &root,
+                &key_0,
+                &[branch.clone(), leaf_0.clone(), leaf_0.clone()]
+            ),
+            None
+        );
+        assert_eq!(
+            verify_trie_proof(&H256::zero(), &key_0, &[branch, leaf_0]),
+            None
+        );
+        assert_eq!(verify_trie_proof(&root, &rlp::encode(&2u64), &[]), None);
+    }
+
+    #[test]
+    fn test_is_receipt_in_header() {
+        let receipt_data = vec![3u8; 40];
+        // Single receipt trie, the root is the leaf with the whole key 0x80.
+        let root_leaf = leaf(&[0x20, 0x80], &receipt_data);
+        let receipts_root = sdk::keccak(&root_leaf);
+        let header = |receipts_root: H256| {
+            let mut stream = rlp::RlpStream::new_list(7);
+            for _ in 0..5 {
+                stream.append(&vec![0u8; 32]);
+            }
+            stream.append(&receipts_root.as_bytes().to_vec());
+            stream.append(&vec![0u8; 256]);
+            stream.out().to_vec()
+        };
+        assert_eq!(
+            header_receipts_root(&header(receipts_root)),
+            Some(receipts_root)
+        );
+
+        let mut proof = Proof {
+            receipt_index: 0,
+            receipt_data,
+            header_data: header(receipts_root),
+            proof: vec![root_leaf],
+            ..Default::default()
+        };
+        assert!(proof.is_receipt_in_header());
+        proof.header_data = header(H256::zero());
+        assert!(!proof.is_receipt_in_header());
+        proof.header_data = vec![1, 2, 3];
+        assert!(!proof.is_receipt_in_header());
     }
 }
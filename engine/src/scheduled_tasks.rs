@@ -0,0 +1,179 @@
+//! Periodic maintenance tasks of the engine.
+//!
+//! The owner whitelists the tasks with `set_scheduled_tasks`, then anyone can call
+//! `run_due_tasks` to run the ones which are due. Every run making progress is rewarded from the
+//! treasury receiving its share of the priority fees, so the maintenance doesn't depend on an
+//! operator remembering to do it. The reward is only paid if the treasury can afford all of it.
+use crate::engine;
+use crate::parameters::{RunDueTasksResult, ScheduledTask, ScheduledTaskKind, ScheduledTaskStatus};
+use crate::prelude::{Address, ConfigKey, Vec, Wei};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Maximum number of scheduled tasks.
+pub const MAX_SCHEDULED_TASKS: usize = 16;
+/// Maximum number of blocks whose logs bloom filter is pruned in a single run.
+pub const MAX_BLOCKS_PER_RUN: u64 = 1_000;
+
+pub fn get_scheduled_tasks<I: IO>(io: &I) -> Vec<ScheduledTaskStatus> {
+    storage_key::read_config(io, ConfigKey::ScheduledTasks, &[])
+        .and_then(|value| value.to_value().ok())
+        .unwrap_or_default()
+}
+
+fn set_statuses<I: IO>(io: &mut I, statuses: &[ScheduledTaskStatus]) {
+    if statuses.is_empty() {
+        storage_key::remove_config(io, ConfigKey::ScheduledTasks, &[]);
+    } else {
+        storage_key::write_config_borsh(io, ConfigKey::ScheduledTasks, &[], &statuses);
+    }
+}
+
+fn validate_task(task: &ScheduledTask) -> Result<(), error::ScheduledTaskError> {
+    if task.interval_blocks == 0 {
+        return Err(error::ScheduledTaskError::InvalidInterval);
+    }
+    match task.kind {
+        ScheduledTaskKind::PruneLogsBlooms {
+            max_blocks_per_run, ..
+        } if max_blocks_per_run == 0 || max_blocks_per_run > MAX_BLOCKS_PER_RUN => {
+            Err(error::ScheduledTaskError::InvalidBatchSize)
+        }
+        ScheduledTaskKind::PruneLogsBlooms { .. } => Ok(()),
+    }
+}
+
+fn same_kind(a: &ScheduledTaskKind, b: &ScheduledTaskKind) -> bool {
+    core::mem::discriminant(a) == core::mem::discriminant(b)
+}
+
+/// Replaces the scheduled tasks, they are all due from the given height. The progress of
+/// a kind of task which stays scheduled is kept.
+pub fn set_scheduled_tasks<I: IO>(
+    io: &mut I,
+    tasks: Vec<ScheduledTask>,
+    block_height: u64,
+) -> Result<(), error::ScheduledTaskError> {
+    if tasks.len() > MAX_SCHEDULED_TASKS {
+        return Err(error::ScheduledTaskError::TooManyTasks);
+    }
+    for task in tasks.iter() {
+        validate_task(task)?;
+    }
+    let previous = get_scheduled_tasks(io);
+    let statuses: Vec<ScheduledTaskStatus> = tasks
+        .into_iter()
+        .map(|task| {
+            let cursor = previous
+                .iter()
+                .find(|status| same_kind(&status.task.kind, &task.kind))
+                .map(|status| status.cursor)
+                .unwrap_or(0);
+            ScheduledTaskStatus {
+                task,
+                next_run_height: block_height,
+                cursor,
+            }
+        })
+        .collect();
+    set_statuses(io, &statuses);
+    Ok(())
+}
+
+/// Runs the task and returns its new cursor, and whether it made any progress.
+fn run_task<I: IO>(
+    io: &mut I,
+    kind: &ScheduledTaskKind,
+    cursor: u64,
+    block_height: u64,
+) -> (u64, bool) {
+    match kind {
+        ScheduledTaskKind::PruneLogsBlooms {
+            retention_blocks,
+            max_blocks_per_run,
+        } => {
+            // Nothing to prune before a bloom is recorded, nor below the first one.
+            let start = match engine::get_first_logs_bloom_height(io) {
+                Some(first_height) => cursor.max(first_height),
+                None => return (cursor, false),
+            };
+            let prune_until = block_height.saturating_sub(*retention_blocks);
+            let end = prune_until.min(start.saturating_add(*max_blocks_per_run));
+            for height in start..end {
+                engine::remove_logs_bloom(io, height);
+            }
+            (end.max(start), end > start)
+        }
+    }
+}
+
+/// Runs at most `limit` due tasks and pays the reward of the ones which made progress from
+/// the treasury to `caller`.
+pub fn run_due_tasks<I: IO>(
+    io: &mut I,
+    block_height: u64,
+    caller: &Address,
+    limit: u32,
+) -> RunDueTasksResult {
+    let mut statuses = get_scheduled_tasks(io);
+    let mut executed = 0;
+    let mut reward = Some(Wei::zero());
+    for status in statuses
+        .iter_mut()
+        .filter(|status| status.next_run_height <= block_height)
+        .take(limit as usize)
+    {
+        let (cursor, progressed) = run_task(io, &status.task.kind, status.cursor, block_height);
+        status.cursor = cursor;
+        status.next_run_height = block_height.saturating_add(status.task.interval_blocks);
+        executed += 1;
+        if progressed {
+            reward = reward.and_then(|reward| reward.checked_add(Wei::from(status.task.reward)));
+        }
+    }
+    if executed > 0 {
+        set_statuses(io, &statuses);
+    }
+
+    let paid = reward
+        .filter(|reward| !reward.is_zero())
+        .and_then(|reward| engine::get_priority_fee_split(io).map(|split| (reward, split)))
+        .filter(|(_, split)| Address(split.treasury) != *caller)
+        .and_then(|(reward, split)| {
+            let treasury = Address(split.treasury);
+            let treasury_balance = engine::get_balance(io, &treasury).checked_sub(reward)?;
+            let caller_balance = engine::get_balance(io, caller).checked_add(reward)?;
+            engine::set_balance(io, &treasury, &treasury_balance);
+            engine::set_balance(io, caller, &caller_balance);
+            Some(reward)
+        })
+        .unwrap_or_else(Wei::zero);
+
+    RunDueTasksResult {
+        executed,
+        reward: paid.to_bytes(),
+    }
+}
+
+pub mod error {
+    const TOO_MANY_TASKS: &[u8; 28] = b"ERR_TOO_MANY_SCHEDULED_TASKS";
+    const INVALID_INTERVAL: &[u8; 25] = b"ERR_INVALID_TASK_INTERVAL";
+    const INVALID_BATCH_SIZE: &[u8; 27] = b"ERR_INVALID_TASK_BATCH_SIZE";
+
+    #[derive(Debug)]
+    pub enum ScheduledTaskError {
+        TooManyTasks,
+        InvalidInterval,
+        InvalidBatchSize,
+    }
+
+    impl AsRef<[u8]> for ScheduledTaskError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::TooManyTasks => TOO_MANY_TASKS,
+                Self::InvalidInterval => INVALID_INTERVAL,
+                Self::InvalidBatchSize => INVALID_BATCH_SIZE,
+            }
+        }
+    }
+}
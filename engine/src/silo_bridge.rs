@@ -0,0 +1,195 @@
+//! Direct transfers of bridged ETH between two engine deployments (silos) on NEAR.
+//!
+//! A transfer burns nETH of the sender in the local silo and calls `silo_bridge_receive`
+//! on the remote silo, which mints the same amount to the recipient. Both silos must
+//! allowlist each other. Amounts sent to and received from every remote silo are tracked
+//! so the bridged supply can be reconciled between deployments.
+use crate::connector::EthConnectorContract;
+use crate::parameters::{
+    SiloBridgeReceiveArgs, SiloBridgeResolveTransferArgs, SiloBridgeTransferArgs,
+};
+use crate::prelude::{
+    bytes_to_key, format, sdk, AccountId, Balance, BorshDeserialize, BorshSerialize, KeyPrefix,
+    NEP141Wei, NearGas, PromiseCreateArgs, PromiseResult, PromiseWithCallbackArgs, ToString, Vec,
+};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// NEAR Gas for calling `silo_bridge_receive` on the remote silo.
+const GAS_FOR_SILO_BRIDGE_RECEIVE: NearGas = NearGas::new(10_000_000_000_000);
+/// NEAR Gas for calling `silo_bridge_resolve_transfer` callback.
+const GAS_FOR_SILO_BRIDGE_RESOLVE_TRANSFER: NearGas = NearGas::new(10_000_000_000_000);
+
+/// State of a remote silo as seen by the current engine.
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSilo {
+    /// Transfers to and from the remote silo are only possible while it is allowed.
+    pub allowed: bool,
+    /// Total amount of nETH sent to the remote silo.
+    pub total_sent: Balance,
+    /// Total amount of nETH received from the remote silo.
+    pub total_received: Balance,
+}
+
+fn remote_silo_key(account_id: &AccountId) -> Vec<u8> {
+    bytes_to_key(KeyPrefix::SiloBridge, account_id.as_bytes())
+}
+
+pub fn get_remote_silo<I: IO>(io: &I, account_id: &AccountId) -> Option<RemoteSilo> {
+    io.read_storage(&remote_silo_key(account_id))
+        .and_then(|bytes| bytes.to_value().ok())
+}
+
+fn set_remote_silo<I: IO>(io: &mut I, account_id: &AccountId, remote_silo: &RemoteSilo) {
+    io.write_borsh(&remote_silo_key(account_id), remote_silo);
+}
+
+fn get_allowed_remote_silo<I: IO>(
+    io: &I,
+    account_id: &AccountId,
+) -> Result<RemoteSilo, error::SiloBridgeError> {
+    match get_remote_silo(io, account_id) {
+        Some(remote_silo) if remote_silo.allowed => Ok(remote_silo),
+        _ => Err(error::SiloBridgeError::RemoteSiloNotAllowed),
+    }
+}
+
+/// Allow or disallow transfers with the remote silo. Accounting data is kept when
+/// the remote silo is disallowed.
+pub fn set_remote_silo_allowed<I: IO>(io: &mut I, account_id: &AccountId, allowed: bool) {
+    let mut remote_silo = get_remote_silo(io, account_id).unwrap_or_default();
+    remote_silo.allowed = allowed;
+    set_remote_silo(io, account_id, &remote_silo);
+}
+
+/// Burn nETH of the sender and create the promise which mints it on the remote silo.
+pub fn transfer<I: IO + Copy>(
+    mut io: I,
+    sender_id: AccountId,
+    current_account_id: AccountId,
+    args: SiloBridgeTransferArgs,
+) -> Result<PromiseWithCallbackArgs, error::SiloBridgeError> {
+    if args.amount == 0 {
+        return Err(error::SiloBridgeError::ZeroAmount);
+    }
+    let mut remote_silo = get_allowed_remote_silo(&io, &args.remote_silo)?;
+    remote_silo.total_sent = remote_silo
+        .total_sent
+        .checked_add(args.amount)
+        .ok_or(error::SiloBridgeError::SupplyOverflow)?;
+
+    EthConnectorContract::init_instance(io)
+        .internal_burn_eth_on_near(&sender_id, NEP141Wei::new(args.amount))
+        .map_err(error::SiloBridgeError::Withdraw)?;
+    set_remote_silo(&mut io, &args.remote_silo, &remote_silo);
+
+    sdk::log!(&format!(
+        "Silo bridge transfer {} from {} to {} on {}",
+        args.amount, sender_id, args.recipient_id, args.remote_silo
+    ));
+
+    let receive_args = SiloBridgeReceiveArgs {
+        sender_id: sender_id.clone(),
+        recipient_id: args.recipient_id,
+        amount: args.amount,
+    };
+    let resolve_args = SiloBridgeResolveTransferArgs {
+        remote_silo: args.remote_silo.clone(),
+        sender_id,
+        amount: args.amount,
+    };
+
+    Ok(PromiseWithCallbackArgs {
+        base: PromiseCreateArgs {
+            target_account_id: args.remote_silo,
+            method: "silo_bridge_receive".to_string(),
+            args: receive_args.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_SILO_BRIDGE_RECEIVE.into_u64(),
+        },
+        callback: PromiseCreateArgs {
+            target_account_id: current_account_id,
+            method: "silo_bridge_resolve_transfer".to_string(),
+            args: resolve_args.try_to_vec().unwrap(),
+            attached_balance: 0,
+            attached_gas: GAS_FOR_SILO_BRIDGE_RESOLVE_TRANSFER.into_u64(),
+        },
+    })
+}
+
+/// Mint nETH sent by an allowed remote silo.
+pub fn receive<I: IO + Copy>(
+    mut io: I,
+    remote_silo_id: AccountId,
+    args: SiloBridgeReceiveArgs,
+) -> Result<(), error::SiloBridgeError> {
+    let mut remote_silo = get_allowed_remote_silo(&io, &remote_silo_id)?;
+    remote_silo.total_received = remote_silo
+        .total_received
+        .checked_add(args.amount)
+        .ok_or(error::SiloBridgeError::SupplyOverflow)?;
+
+    EthConnectorContract::init_instance(io)
+        .internal_mint_eth_on_near(args.recipient_id.clone(), NEP141Wei::new(args.amount))
+        .map_err(error::SiloBridgeError::Deposit)?;
+    set_remote_silo(&mut io, &remote_silo_id, &remote_silo);
+
+    sdk::log!(&format!(
+        "Silo bridge received {} from {} on {} for {}",
+        args.amount, args.sender_id, remote_silo_id, args.recipient_id
+    ));
+    Ok(())
+}
+
+/// Refund the sender if the remote silo did not accept the transfer.
+pub fn resolve_transfer<I: IO + Copy>(
+    mut io: I,
+    args: SiloBridgeResolveTransferArgs,
+    promise_result: PromiseResult,
+) -> Result<(), error::SiloBridgeError> {
+    if let PromiseResult::Successful(_) = promise_result {
+        return Ok(());
+    }
+
+    let mut remote_silo = get_remote_silo(&io, &args.remote_silo).unwrap_or_default();
+    remote_silo.total_sent = remote_silo.total_sent.saturating_sub(args.amount);
+
+    EthConnectorContract::init_instance(io)
+        .internal_mint_eth_on_near(args.sender_id.clone(), NEP141Wei::new(args.amount))
+        .map_err(error::SiloBridgeError::Deposit)?;
+    set_remote_silo(&mut io, &args.remote_silo, &remote_silo);
+
+    sdk::log!(&format!(
+        "Silo bridge transfer to {} failed, refund {} to {}",
+        args.remote_silo, args.amount, args.sender_id
+    ));
+    Ok(())
+}
+
+pub mod error {
+    use crate::fungible_token;
+
+    const REMOTE_SILO_NOT_ALLOWED: &[u8; 27] = b"ERR_REMOTE_SILO_NOT_ALLOWED";
+    const ZERO_AMOUNT: &[u8; 15] = b"ERR_ZERO_AMOUNT";
+    const SUPPLY_OVERFLOW: &[u8; 31] = b"ERR_SILO_BRIDGE_SUPPLY_OVERFLOW";
+
+    #[derive(Debug)]
+    pub enum SiloBridgeError {
+        RemoteSiloNotAllowed,
+        ZeroAmount,
+        SupplyOverflow,
+        Withdraw(fungible_token::error::WithdrawError),
+        Deposit(fungible_token::error::DepositError),
+    }
+
+    impl AsRef<[u8]> for SiloBridgeError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::RemoteSiloNotAllowed => REMOTE_SILO_NOT_ALLOWED,
+                Self::ZeroAmount => ZERO_AMOUNT,
+                Self::SupplyOverflow => SUPPLY_OVERFLOW,
+                Self::Withdraw(e) => e.as_ref(),
+                Self::Deposit(e) => e.as_ref(),
+            }
+        }
+    }
+}
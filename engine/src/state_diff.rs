@@ -0,0 +1,168 @@
+//! Bulk application of raw storage changes by the owner.
+//!
+//! Migrations between versions of the state schema often touch many keys which have no
+//! setter of their own. The owner submits all the changes of a migration at once, optionally
+//! as a dry run first, and the digest of the diff is logged so the applied changes can be
+//! matched with the migration script which produced them.
+//!
+//! A diff can't change the code of the engine nor its ownership, which have their own methods
+//! enforcing the upgrade delay and the two-step ownership transfer.
+use crate::engine::{CODE_KEY, CODE_STAGE_KEY, STATE_KEY};
+use crate::parameters::{ApplyStateDiffArgs, StateDiffResult};
+use crate::prelude::storage::{bytes_to_key, config_key, legacy_config_key, KeyPrefix};
+use crate::prelude::{sdk, BorshSerialize, ConfigKey, Vec, H256};
+use aurora_engine_sdk::io::IO;
+
+/// Maximum number of keys changed by a single diff.
+pub const MAX_STATE_DIFF_ENTRIES: usize = 1_000;
+
+/// Maximum total size of the keys and values of a single diff.
+pub const MAX_STATE_DIFF_BYTES: usize = 1_048_576;
+
+type StateDiff = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// Whether the key holds the code or the ownership of the engine.
+fn is_protected_key(key: &[u8]) -> bool {
+    let protected_keys = [
+        bytes_to_key(KeyPrefix::Config, STATE_KEY),
+        bytes_to_key(KeyPrefix::Config, CODE_KEY),
+        bytes_to_key(KeyPrefix::Config, CODE_STAGE_KEY),
+        config_key(ConfigKey::CodeStageDelay, &[]),
+        config_key(ConfigKey::PendingOwner, &[]),
+    ];
+    protected_keys
+        .iter()
+        .chain(legacy_config_key(ConfigKey::CodeStageDelay, &[]).iter())
+        .any(|protected_key| protected_key.as_slice() == key)
+}
+
+fn validate(diff: &StateDiff) -> Result<(), error::StateDiffError> {
+    if diff.len() > MAX_STATE_DIFF_ENTRIES {
+        return Err(error::StateDiffError::TooManyEntries);
+    }
+    if diff.iter().any(|(key, _)| key.is_empty()) {
+        return Err(error::StateDiffError::EmptyKey);
+    }
+    if diff.iter().any(|(key, _)| is_protected_key(key)) {
+        return Err(error::StateDiffError::ProtectedKey);
+    }
+    let size: usize = diff
+        .iter()
+        .map(|(key, value)| key.len() + value.as_ref().map(Vec::len).unwrap_or(0))
+        .sum();
+    if size > MAX_STATE_DIFF_BYTES {
+        return Err(error::StateDiffError::TooLarge);
+    }
+    Ok(())
+}
+
+pub fn digest(diff: &StateDiff) -> H256 {
+    sdk::sha256(&diff.try_to_vec().unwrap())
+}
+
+/// Applies the changes in order, so a later change of the same key wins. The whole diff is
+/// validated before anything is written.
+pub fn apply_state_diff<I: IO>(
+    io: &mut I,
+    args: ApplyStateDiffArgs,
+) -> Result<StateDiffResult, error::StateDiffError> {
+    validate(&args.diff)?;
+    let digest = digest(&args.diff);
+    let removed = args
+        .diff
+        .iter()
+        .filter(|(_, value)| value.is_none())
+        .count();
+    let result = StateDiffResult {
+        written: (args.diff.len() - removed) as u32,
+        removed: removed as u32,
+        digest: digest.0,
+    };
+    if args.dry_run {
+        return Ok(result);
+    }
+
+    for (key, value) in args.diff.iter() {
+        match value {
+            Some(value) => {
+                io.write_storage(key, value);
+            }
+            None => {
+                io.remove_storage(key);
+            }
+        }
+    }
+    sdk::log!(&crate::events::state_diff_log(&result));
+    Ok(result)
+}
+
+pub mod error {
+    const TOO_MANY_ENTRIES: &[u8; 31] = b"ERR_STATE_DIFF_TOO_MANY_ENTRIES";
+    const TOO_LARGE: &[u8; 24] = b"ERR_STATE_DIFF_TOO_LARGE";
+    const EMPTY_KEY: &[u8; 24] = b"ERR_STATE_DIFF_EMPTY_KEY";
+    const PROTECTED_KEY: &[u8; 28] = b"ERR_STATE_DIFF_PROTECTED_KEY";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum StateDiffError {
+        TooManyEntries,
+        TooLarge,
+        EmptyKey,
+        /// The diff changes the code or the ownership of the engine.
+        ProtectedKey,
+    }
+
+    impl AsRef<[u8]> for StateDiffError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::TooManyEntries => TOO_MANY_ENTRIES,
+                Self::TooLarge => TOO_LARGE,
+                Self::EmptyKey => EMPTY_KEY,
+                Self::ProtectedKey => PROTECTED_KEY,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::vec;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&vec![(vec![1], Some(vec![2])), (vec![3], None)]).is_ok());
+        assert_eq!(
+            validate(&vec![(Vec::new(), None)]),
+            Err(error::StateDiffError::EmptyKey)
+        );
+        assert_eq!(
+            validate(&vec![(vec![1], None); MAX_STATE_DIFF_ENTRIES + 1]),
+            Err(error::StateDiffError::TooManyEntries)
+        );
+        assert_eq!(
+            validate(&vec![(vec![1], Some(vec![0; MAX_STATE_DIFF_BYTES]))]),
+            Err(error::StateDiffError::TooLarge)
+        );
+        assert_eq!(
+            validate(&vec![(bytes_to_key(KeyPrefix::Config, STATE_KEY), None)]),
+            Err(error::StateDiffError::ProtectedKey)
+        );
+        assert_eq!(
+            validate(&vec![(
+                bytes_to_key(KeyPrefix::Config, CODE_KEY),
+                Some(vec![0])
+            )]),
+            Err(error::StateDiffError::ProtectedKey)
+        );
+    }
+
+    #[test]
+    fn test_digest_depends_on_order() {
+        let write = (vec![1], Some(vec![2]));
+        let remove = (vec![1], None);
+        assert_ne!(
+            digest(&vec![write.clone(), remove.clone()]),
+            digest(&vec![remove, write])
+        );
+    }
+}
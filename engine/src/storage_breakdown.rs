@@ -0,0 +1,441 @@
+//! Approximate amount of storage used by each subsystem of the engine.
+//!
+//! Storage writes of the contract go through `TrackedRuntime`, which classifies every key
+//! by its prefix and keeps a persistent counter per subsystem up to date. Like NEAR, a record
+//! is counted as its key, its value and a fixed overhead. The tracking is enabled by the owner
+//! (it costs additional storage reads and writes) and only the data written since then is
+//! accounted for.
+use crate::parameters::StorageBreakdown;
+use crate::prelude::{config_key, legacy_config_key, ConfigKey, KeyPrefix, Vec, VersionPrefix};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Number of bytes NEAR accounts for every record in addition to its key and value.
+const RECORD_OVERHEAD_BYTES: u64 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageSubsystem {
+    /// Fungible token data, proofs and settings of the eth-connector.
+    EthConnector,
+    /// Nonces, balances, code and storage of EVM accounts.
+    EvmAccounts,
+    /// Data recorded per transaction or block: submitted transaction hashes and logs blooms.
+    Receipts,
+    /// Relayers, NEP-141 <-> ERC-20 mapping, ABI schemas, silos and frozen accounts.
+    Registries,
+    /// Engine state, configuration and everything else.
+    Other,
+}
+
+pub fn subsystem_of(key: &[u8]) -> StorageSubsystem {
+    let prefix = match key {
+        [version, prefix, ..] if *version == VersionPrefix::V1 as u8 => *prefix,
+        _ => return StorageSubsystem::Other,
+    };
+    let is_any = |prefixes: &[KeyPrefix]| prefixes.iter().any(|p| *p as u8 == prefix);
+
+    if is_any(&[KeyPrefix::EthConnector]) {
+        StorageSubsystem::EthConnector
+    } else if is_any(&[
+        KeyPrefix::Nonce,
+        KeyPrefix::Balance,
+        KeyPrefix::Code,
+        KeyPrefix::Storage,
+        KeyPrefix::Generation,
+    ]) {
+        StorageSubsystem::EvmAccounts
+    } else if is_any(&[KeyPrefix::SubmittedTxHash, KeyPrefix::LogBloom]) {
+        StorageSubsystem::Receipts
+    } else if is_any(&[
+        KeyPrefix::RelayerEvmAddressMap,
+        KeyPrefix::Nep141Erc20Map,
+        KeyPrefix::Erc20Nep141Map,
+        KeyPrefix::AbiSchema,
+        KeyPrefix::SiloBridge,
+        KeyPrefix::FrozenAccount,
+    ]) {
+        StorageSubsystem::Registries
+    } else {
+        StorageSubsystem::Other
+    }
+}
+
+fn storage_breakdown_key() -> Vec<u8> {
+    config_key(ConfigKey::StorageBreakdown, &[])
+}
+
+/// Returns the storage breakdown, if the tracking is enabled.
+pub fn get_storage_breakdown<I: IO>(io: &I) -> Option<StorageBreakdown> {
+    storage_key::read_config(io, ConfigKey::StorageBreakdown, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+/// Doesn't use any register, unlike reading the counters.
+pub fn is_storage_breakdown_enabled<I: IO>(io: &I) -> bool {
+    storage_key::has_config(io, ConfigKey::StorageBreakdown, &[])
+}
+
+/// Enabling the tracking resets all the counters to zero, disabling it removes them.
+pub fn set_storage_breakdown_enabled<I: IO>(io: &mut I, enabled: bool) {
+    if enabled {
+        storage_key::write_config_borsh(
+            io,
+            ConfigKey::StorageBreakdown,
+            &[],
+            &StorageBreakdown::default(),
+        );
+    } else {
+        storage_key::remove_config(io, ConfigKey::StorageBreakdown, &[]);
+    }
+}
+
+fn record_size(key: &[u8], value_len: Option<usize>) -> u64 {
+    value_len
+        .map(|len| (key.len() + len) as u64 + RECORD_OVERHEAD_BYTES)
+        .unwrap_or(0)
+}
+
+/// Whether the key holds the counters, whose writes aren't counted.
+fn is_storage_breakdown_key(key: &[u8]) -> bool {
+    key == storage_breakdown_key().as_slice()
+        || Some(key) == legacy_config_key(ConfigKey::StorageBreakdown, &[]).as_deref()
+}
+
+/// Updates the counter of the subsystem the key belongs to before the value stored under
+/// the key is replaced by one of `new_value_len` bytes (`None` if the value is removed).
+/// Does nothing if the tracking is disabled, the callers check it once per call rather than
+/// on every write, see `TrackedIO`.
+pub fn record_write<I: IO>(io: &mut I, key: &[u8], new_value_len: Option<usize>) {
+    if is_storage_breakdown_key(key) {
+        return;
+    }
+    let mut breakdown = match get_storage_breakdown(io) {
+        Some(breakdown) => breakdown,
+        None => return,
+    };
+    let old_value_len = io.read_storage_len(key);
+    // Overwriting a value with one of the same size (e.g. a balance) is the most common case.
+    if old_value_len == new_value_len {
+        return;
+    }
+
+    let counter = match subsystem_of(key) {
+        StorageSubsystem::EthConnector => &mut breakdown.eth_connector,
+        StorageSubsystem::EvmAccounts => &mut breakdown.evm_accounts,
+        StorageSubsystem::Receipts => &mut breakdown.receipts,
+        StorageSubsystem::Registries => &mut breakdown.registries,
+        StorageSubsystem::Other => &mut breakdown.other,
+    };
+    *counter = counter
+        .saturating_add(record_size(key, new_value_len))
+        .saturating_sub(record_size(key, old_value_len));
+    io.write_borsh(&storage_breakdown_key(), &breakdown);
+}
+
+/// `IO` recording every storage write in the storage breakdown, as `TrackedRuntime` does in
+/// the contract, so that the standalone engine keeps the same counters. Whether the tracking
+/// is enabled is read once, when the `IO` of the call is created. The values of the wrapped
+/// `IO` must be owned (e.g. the standalone storage), since the counters are read and written
+/// before every write.
+#[derive(Debug, Clone, Copy)]
+pub struct TrackedIO<I> {
+    io: I,
+    is_enabled: bool,
+}
+
+impl<I: IO> TrackedIO<I> {
+    pub fn new(io: I) -> Self {
+        let is_enabled = is_storage_breakdown_enabled(&io);
+        Self { io, is_enabled }
+    }
+
+    fn record_write(&mut self, key: &[u8], new_value_len: Option<usize>) {
+        if self.is_enabled {
+            record_write(&mut self.io, key, new_value_len);
+        }
+    }
+}
+
+impl<I: IO> IO for TrackedIO<I> {
+    type StorageValue = I::StorageValue;
+
+    fn read_input(&self) -> Self::StorageValue {
+        self.io.read_input()
+    }
+
+    fn return_output(&mut self, value: &[u8]) {
+        self.io.return_output(value)
+    }
+
+    fn read_storage(&self, key: &[u8]) -> Option<Self::StorageValue> {
+        self.io.read_storage(key)
+    }
+
+    fn storage_has_key(&self, key: &[u8]) -> bool {
+        self.io.storage_has_key(key)
+    }
+
+    fn write_storage(&mut self, key: &[u8], value: &[u8]) -> Option<Self::StorageValue> {
+        self.record_write(key, Some(value.len()));
+        self.io.write_storage(key, value)
+    }
+
+    fn write_storage_direct(
+        &mut self,
+        key: &[u8],
+        value: Self::StorageValue,
+    ) -> Option<Self::StorageValue> {
+        self.record_write(key, Some(value.len()));
+        self.io.write_storage_direct(key, value)
+    }
+
+    fn remove_storage(&mut self, key: &[u8]) -> Option<Self::StorageValue> {
+        self.record_write(key, None);
+        self.io.remove_storage(key)
+    }
+
+    fn read_storage_len(&self, key: &[u8]) -> Option<usize> {
+        self.io.read_storage_len(key)
+    }
+}
+
+#[cfg(feature = "contract")]
+pub use runtime::TrackedRuntime;
+
+#[cfg(feature = "contract")]
+mod runtime {
+    use crate::engine_sequence;
+    use crate::events;
+    use crate::prelude::{
+        sdk, AccountId, NearGas, PromiseBatchAction, PromiseCreateArgs, PromiseResult, H256,
+    };
+    use aurora_engine_sdk::env::{Env, Timestamp};
+    use aurora_engine_sdk::io::{StorageIntermediate, IO};
+    use aurora_engine_sdk::near_runtime::{BookkeepingRuntime, RegisterIndex, Runtime};
+    use aurora_engine_sdk::promise::{PromiseHandler, PromiseId};
+    use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+    /// Whether the engine sequence was incremented by the current call. The statics of the
+    /// contract live as long as the call.
+    static ENGINE_SEQUENCE_INCREMENTED: AtomicBool = AtomicBool::new(false);
+
+    const TRACKING_UNKNOWN: u8 = 0;
+    const TRACKING_DISABLED: u8 = 1;
+    const TRACKING_ENABLED: u8 = 2;
+
+    /// Whether the storage breakdown is enabled, read on the first write of the call.
+    static TRACKING: AtomicU8 = AtomicU8::new(TRACKING_UNKNOWN);
+
+    /// Increments the engine sequence on the first write of the call.
+    fn increment_engine_sequence_once() {
+        if !ENGINE_SEQUENCE_INCREMENTED.swap(true, Ordering::Relaxed) {
+            let sequence = engine_sequence::increment(&mut BookkeepingRuntime);
+            sdk::log!(&events::engine_sequence_log(sequence));
+        }
+    }
+
+    fn is_tracking_enabled() -> bool {
+        match TRACKING.load(Ordering::Relaxed) {
+            TRACKING_UNKNOWN => {
+                let enabled = super::is_storage_breakdown_enabled(&Runtime);
+                let tracking = if enabled {
+                    TRACKING_ENABLED
+                } else {
+                    TRACKING_DISABLED
+                };
+                TRACKING.store(tracking, Ordering::Relaxed);
+                enabled
+            }
+            tracking => tracking == TRACKING_ENABLED,
+        }
+    }
+
+    /// Called before every write of the contract.
+    fn record_write(key: &[u8], new_value_len: Option<usize>) {
+        increment_engine_sequence_once();
+        if super::is_storage_breakdown_key(key) {
+            // Enabled or disabled by the call, read again on the next write.
+            TRACKING.store(TRACKING_UNKNOWN, Ordering::Relaxed);
+        } else if is_tracking_enabled() {
+            super::record_write(&mut BookkeepingRuntime, key, new_value_len);
+        }
+    }
+
+    /// NEAR runtime of the contract which records every storage write in the storage breakdown,
+    /// and increments the engine sequence on the first write of a call.
+    ///
+    /// Whether the tracking is enabled is only read once per call, so a call doesn't pay for it
+    /// while it is disabled. The bookkeeping goes through `BookkeepingRuntime`, so the values
+    /// the engine read or evicted are still in their registers after a write.
+    #[derive(Copy, Clone, Default)]
+    pub struct TrackedRuntime;
+
+    impl IO for TrackedRuntime {
+        type StorageValue = RegisterIndex;
+
+        fn read_input(&self) -> Self::StorageValue {
+            Runtime.read_input()
+        }
+
+        fn return_output(&mut self, value: &[u8]) {
+            Runtime.return_output(value)
+        }
+
+        fn read_storage(&self, key: &[u8]) -> Option<Self::StorageValue> {
+            Runtime.read_storage(key)
+        }
+
+        fn storage_has_key(&self, key: &[u8]) -> bool {
+            Runtime.storage_has_key(key)
+        }
+
+        fn write_storage(&mut self, key: &[u8], value: &[u8]) -> Option<Self::StorageValue> {
+            record_write(key, Some(value.len()));
+            Runtime.write_storage(key, value)
+        }
+
+        fn write_storage_direct(
+            &mut self,
+            key: &[u8],
+            value: Self::StorageValue,
+        ) -> Option<Self::StorageValue> {
+            record_write(key, Some(value.len()));
+            Runtime.write_storage_direct(key, value)
+        }
+
+        fn remove_storage(&mut self, key: &[u8]) -> Option<Self::StorageValue> {
+            record_write(key, None);
+            Runtime.remove_storage(key)
+        }
+    }
+
+    impl Env for TrackedRuntime {
+        fn signer_account_id(&self) -> AccountId {
+            Runtime.signer_account_id()
+        }
+
+        fn current_account_id(&self) -> AccountId {
+            Runtime.current_account_id()
+        }
+
+        fn predecessor_account_id(&self) -> AccountId {
+            Runtime.predecessor_account_id()
+        }
+
+        fn block_height(&self) -> u64 {
+            Runtime.block_height()
+        }
+
+        fn block_timestamp(&self) -> Timestamp {
+            Runtime.block_timestamp()
+        }
+
+        fn attached_deposit(&self) -> u128 {
+            Runtime.attached_deposit()
+        }
+
+        fn random_seed(&self) -> H256 {
+            Runtime.random_seed()
+        }
+
+        fn prepaid_gas(&self) -> NearGas {
+            Runtime.prepaid_gas()
+        }
+
+        fn used_gas(&self) -> NearGas {
+            Runtime.used_gas()
+        }
+    }
+
+    impl PromiseHandler for TrackedRuntime {
+        fn promise_results_count(&self) -> u64 {
+            Runtime.promise_results_count()
+        }
+
+        fn promise_result(&self, index: u64) -> Option<PromiseResult> {
+            Runtime.promise_result(index)
+        }
+
+        fn promise_create_call(&mut self, args: &PromiseCreateArgs) -> PromiseId {
+            Runtime.promise_create_call(args)
+        }
+
+        fn promise_attach_callback(
+            &mut self,
+            base: PromiseId,
+            callback: &PromiseCreateArgs,
+        ) -> PromiseId {
+            Runtime.promise_attach_callback(base, callback)
+        }
+
+        fn promise_create_batch(&mut self, args: &PromiseBatchAction) -> PromiseId {
+            Runtime.promise_create_batch(args)
+        }
+
+        fn promise_return(&mut self, promise: PromiseId) {
+            Runtime.promise_return(promise)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{address_to_key, bytes_to_key, Address};
+    use aurora_engine_sdk::memory;
+    use core::cell::RefCell;
+
+    #[test]
+    fn test_subsystem_of() {
+        let address = Address([1u8; 20]);
+        assert_eq!(
+            subsystem_of(&address_to_key(KeyPrefix::Balance, &address)),
+            StorageSubsystem::EvmAccounts
+        );
+        assert_eq!(
+            subsystem_of(&bytes_to_key(KeyPrefix::EthConnector, &[1])),
+            StorageSubsystem::EthConnector
+        );
+        assert_eq!(
+            subsystem_of(&bytes_to_key(KeyPrefix::LogBloom, &[1])),
+            StorageSubsystem::Receipts
+        );
+        assert_eq!(
+            subsystem_of(&bytes_to_key(KeyPrefix::Nep141Erc20Map, b"token.near")),
+            StorageSubsystem::Registries
+        );
+        assert_eq!(
+            subsystem_of(&bytes_to_key(KeyPrefix::Config, b"STATE")),
+            StorageSubsystem::Other
+        );
+        assert_eq!(subsystem_of(b"CODE"), StorageSubsystem::Other);
+        assert_eq!(subsystem_of(&[]), StorageSubsystem::Other);
+    }
+
+    #[test]
+    fn test_tracked_io() {
+        let storage = RefCell::new(memory::Storage::default());
+        let mut io = memory::StoragePointer(&storage);
+        let key = address_to_key(KeyPrefix::Balance, &Address([1u8; 20]));
+
+        // The tracking is read when the `IO` is created.
+        let mut tracked_io = TrackedIO::new(io);
+        set_storage_breakdown_enabled(&mut io, true);
+        tracked_io.write_storage(&key, &[0u8; 32]);
+        assert_eq!(get_storage_breakdown(&io).unwrap().evm_accounts, 0);
+
+        let mut tracked_io = TrackedIO::new(io);
+        tracked_io.remove_storage(&key);
+        tracked_io.write_storage(&key, &[0u8; 32]);
+        tracked_io.write_storage(&key, &[1u8; 32]);
+        assert_eq!(get_storage_breakdown(&io).unwrap().evm_accounts, 94);
+        tracked_io.remove_storage(&key);
+        assert_eq!(get_storage_breakdown(&io).unwrap().evm_accounts, 0);
+    }
+
+    #[test]
+    fn test_record_size() {
+        assert_eq!(record_size(&[0u8; 22], Some(32)), 94);
+        assert_eq!(record_size(&[0u8; 22], None), 0);
+    }
+}
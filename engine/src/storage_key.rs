@@ -0,0 +1,102 @@
+//! Versioned access to the configuration values of the engine subsystems.
+//!
+//! The values are stored under the one-byte ids of `ConfigKey`. Values written before the keys
+//! were namespaced are still read from their legacy string key until they are overwritten or
+//! moved with `migrate_config_keys`. A removal removes both keys, so a legacy value can't
+//! resurface.
+use crate::prelude::{config_key, legacy_config_key, BorshSerialize, ConfigKey, Vec};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+pub fn read_config<I: IO>(io: &I, key: ConfigKey, suffix: &[u8]) -> Option<I::StorageValue> {
+    io.read_storage(&config_key(key, suffix)).or_else(|| {
+        legacy_config_key(key, suffix).and_then(|legacy_key| io.read_storage(&legacy_key))
+    })
+}
+
+pub fn read_config_u64<I: IO>(io: &I, key: ConfigKey, suffix: &[u8]) -> Option<u64> {
+    let mut value = [0u8; 8];
+    let stored = read_config(io, key, suffix)?;
+    if stored.len() != value.len() {
+        return None;
+    }
+    stored.copy_to_slice(&mut value);
+    Some(u64::from_le_bytes(value))
+}
+
+pub fn has_config<I: IO>(io: &I, key: ConfigKey, suffix: &[u8]) -> bool {
+    io.storage_has_key(&config_key(key, suffix))
+        || legacy_config_key(key, suffix)
+            .map(|legacy_key| io.storage_has_key(&legacy_key))
+            .unwrap_or(false)
+}
+
+pub fn write_config<I: IO>(io: &mut I, key: ConfigKey, suffix: &[u8], value: &[u8]) {
+    io.write_storage(&config_key(key, suffix), value);
+}
+
+pub fn write_config_borsh<I: IO, T: BorshSerialize>(
+    io: &mut I,
+    key: ConfigKey,
+    suffix: &[u8],
+    value: &T,
+) {
+    io.write_borsh(&config_key(key, suffix), value);
+}
+
+pub fn remove_config<I: IO>(io: &mut I, key: ConfigKey, suffix: &[u8]) {
+    io.remove_storage(&config_key(key, suffix));
+    if let Some(legacy_key) = legacy_config_key(key, suffix) {
+        if io.storage_has_key(&legacy_key) {
+            io.remove_storage(&legacy_key);
+        }
+    }
+}
+
+/// Moves the configuration values without a suffix from their legacy key to the namespaced
+/// one. Values with a suffix (e.g. the pending executions) can't be enumerated, they are
+/// still read from the legacy key until consumed. Returns the number of moved values.
+pub fn migrate_config_keys<I: IO>(io: &mut I) -> u32 {
+    let mut migrated = 0;
+    for key in ConfigKey::ALL.iter().copied() {
+        if key == ConfigKey::PendingExecution {
+            continue;
+        }
+        let legacy_key = match legacy_config_key(key, &[]) {
+            Some(legacy_key) => legacy_key,
+            None => continue,
+        };
+        let value: Vec<u8> = match io.read_storage(&legacy_key) {
+            Some(value) => value.to_vec(),
+            None => continue,
+        };
+        let new_key = config_key(key, &[]);
+        // A value written after the upgrade is more recent than the legacy one.
+        if !io.storage_has_key(&new_key) {
+            io.write_storage(&new_key, &value);
+        }
+        io.remove_storage(&legacy_key);
+        migrated += 1;
+    }
+    migrated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{bytes_to_key, KeyPrefix};
+
+    #[test]
+    fn test_config_keys_do_not_collide() {
+        for key in ConfigKey::ALL.iter().copied() {
+            let new_key = config_key(key, &[]);
+            assert_eq!(new_key.len(), 3);
+            for other in ConfigKey::ALL.iter().copied() {
+                if let Some(legacy_key) = legacy_config_key(other, &[]) {
+                    assert!(!legacy_key.starts_with(&new_key));
+                }
+            }
+        }
+        assert!(!bytes_to_key(KeyPrefix::Config, b"STATE")
+            .starts_with(&config_key(ConfigKey::DuplicateSubmitWindow, &[])));
+    }
+}
@@ -0,0 +1,109 @@
+//! Address book of the EVM addresses reserved by the engine.
+//!
+//! No code can be deployed at these addresses, neither by `deploy_code` nor by a CREATE of a
+//! contract, so a user deployment can't shadow a precompile or an account the engine relies on.
+//! The precompiles are taken from the set the EVM runs with, so new ones are reserved as soon
+//! as they are added.
+use crate::engine::current_address;
+use crate::engine_logs::ENGINE_LOGS_ADDRESS;
+use crate::parameters::{SystemAddress, SystemAddressKind};
+use crate::prelude::precompiles::native::{ExitToEthereum, ExitToNear};
+use crate::prelude::precompiles::{HostFunctions, PrecompileConstructorContext, Precompiles};
+use crate::prelude::{AccountId, Address, NearGas, Vec, H256};
+use aurora_engine_sdk::{env, memory};
+use core::cell::RefCell;
+
+pub fn system_addresses(current_account_id: &AccountId) -> Vec<SystemAddress> {
+    // Only the addresses of the precompiles are used, not their context.
+    let storage = RefCell::new(memory::Storage::default());
+    let env = env::Fixed {
+        signer_account_id: current_account_id.clone(),
+        current_account_id: current_account_id.clone(),
+        predecessor_account_id: current_account_id.clone(),
+        block_height: 0,
+        block_timestamp: env::Timestamp::new(0),
+        attached_deposit: 0,
+        random_seed: H256::default(),
+        prepaid_gas: NearGas::new(0),
+    };
+    let precompiles = Precompiles::new_london(PrecompileConstructorContext {
+        current_account_id: current_account_id.clone(),
+        random_seed: H256::default(),
+        host_functions: HostFunctions::default(),
+        io: memory::StoragePointer(&storage),
+        env: &env,
+    });
+    let mut addresses: Vec<SystemAddress> = precompiles
+        .0
+        .keys()
+        .map(|address| {
+            let kind = if *address == ExitToNear::ADDRESS || *address == ExitToEthereum::ADDRESS {
+                SystemAddressKind::ExitRouter
+            } else {
+                SystemAddressKind::Precompile
+            };
+            SystemAddress {
+                address: address.0,
+                kind,
+            }
+        })
+        .collect();
+    addresses.push(SystemAddress {
+        address: current_address(current_account_id).0,
+        kind: SystemAddressKind::EngineAdmin,
+    });
+    addresses.push(SystemAddress {
+        address: ENGINE_LOGS_ADDRESS.0,
+        kind: SystemAddressKind::EngineLogs,
+    });
+    addresses
+}
+
+pub fn is_system_address(current_account_id: &AccountId, address: &Address) -> bool {
+    system_addresses(current_account_id)
+        .iter()
+        .any(|system_address| system_address.address == address.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_addresses() {
+        let current_account_id: AccountId = "aurora".parse().unwrap();
+        let addresses = system_addresses(&current_account_id);
+
+        let kind_of = |address: Address| {
+            addresses
+                .iter()
+                .find(|system_address| system_address.address == address.0)
+                .map(|system_address| system_address.kind)
+        };
+        assert_eq!(
+            kind_of(ExitToNear::ADDRESS),
+            Some(SystemAddressKind::ExitRouter)
+        );
+        assert_eq!(
+            kind_of(ExitToEthereum::ADDRESS),
+            Some(SystemAddressKind::ExitRouter)
+        );
+        // ecrecover
+        assert_eq!(
+            kind_of(Address::from_low_u64_be(1)),
+            Some(SystemAddressKind::Precompile)
+        );
+        assert_eq!(
+            kind_of(current_address(&current_account_id)),
+            Some(SystemAddressKind::EngineAdmin)
+        );
+        assert_eq!(
+            kind_of(ENGINE_LOGS_ADDRESS),
+            Some(SystemAddressKind::EngineLogs)
+        );
+        assert!(!is_system_address(
+            &current_account_id,
+            &Address([0x42; 20])
+        ));
+    }
+}
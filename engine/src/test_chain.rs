@@ -0,0 +1,192 @@
+//! Engine blocks controlled by the integration tests.
+//!
+//! The engine has no blocks of its own, its transactions are in the block of the NEAR receipt
+//! executing them, so a test can't choose which transactions share a block. With
+//! `begin_block` and `end_block` the owner opens an engine block, which the transactions
+//! submitted until its end are part of: they see its number and timestamp, and the behaviors
+//! depending on the block, e.g. the gas cap of the block or the logs bloom index, are the ones
+//! of the same block for all of them, whatever NEAR block they are included in.
+//!
+//! Every block commits to its transactions with a hashchain, which starts from the one of the
+//! previous block and is updated with the hash of each transaction and of its result, and to
+//! their results with the hash of the hashes of the results. The same transactions give the
+//! same hashes in the contract and in the standalone engine, so a test can compare them.
+use crate::parameters::{BeginBlockArgs, SubmitResult, TestBlock, TestBlockSummary};
+use crate::prelude::{sdk, AccountId, BorshSerialize, ConfigKey, NearGas, Vec, H256, U256};
+use crate::storage_key;
+use aurora_engine_sdk::env::{Env, Timestamp};
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+pub fn get_open_block<I: IO>(io: &I) -> Option<TestBlock> {
+    storage_key::read_config(io, ConfigKey::TestBlock, &[]).and_then(|value| value.to_value().ok())
+}
+
+/// Last block ended, the parent of the next one.
+pub fn get_last_block<I: IO>(io: &I) -> Option<TestBlockSummary> {
+    storage_key::read_config(io, ConfigKey::LastTestBlock, &[])
+        .and_then(|value| value.to_value().ok())
+}
+
+fn to_u64(value: &[u8; 32]) -> Option<u64> {
+    let value = U256::from_big_endian(value);
+    if value > U256::from(u64::MAX) {
+        None
+    } else {
+        Some(value.low_u64())
+    }
+}
+
+/// Opens the block, its number must be above the one of the last block.
+pub fn begin_block<I: IO>(io: &mut I, args: &BeginBlockArgs) -> Result<(), error::TestChainError> {
+    let number = to_u64(&args.number).ok_or(error::TestChainError::InvalidBlock)?;
+    let timestamp = to_u64(&args.timestamp)
+        .and_then(|secs| secs.checked_mul(1_000_000_000))
+        .ok_or(error::TestChainError::InvalidBlock)?;
+    if get_open_block(io).is_some() {
+        return Err(error::TestChainError::BlockAlreadyOpen);
+    }
+    let parent_hashchain = match get_last_block(io) {
+        Some(last) if number <= last.number => {
+            return Err(error::TestChainError::BlockNumberNotIncreasing)
+        }
+        Some(last) => last.hashchain,
+        None => [0u8; 32],
+    };
+    let block = TestBlock {
+        number,
+        timestamp,
+        parent_hashchain,
+        hashchain: parent_hashchain,
+        receipts: Vec::new(),
+        gas_used: 0,
+    };
+    storage_key::write_config_borsh(io, ConfigKey::TestBlock, &[], &block);
+    Ok(())
+}
+
+pub fn receipt_hash(result: &SubmitResult) -> H256 {
+    sdk::keccak(&result.try_to_vec().unwrap_or_default())
+}
+
+/// Hashchain after the transaction with the given result.
+pub fn next_hashchain(hashchain: &[u8; 32], transaction_bytes: &[u8], receipt: &H256) -> H256 {
+    let transaction_hash = sdk::keccak(transaction_bytes);
+    let mut data = Vec::with_capacity(3 * 32);
+    data.extend_from_slice(hashchain);
+    data.extend_from_slice(transaction_hash.as_bytes());
+    data.extend_from_slice(receipt.as_bytes());
+    sdk::keccak(&data)
+}
+
+/// Adds the submitted transaction to the open block, if there is one.
+pub fn record_transaction<I: IO>(io: &mut I, transaction_bytes: &[u8], result: &SubmitResult) {
+    let mut block = match get_open_block(io) {
+        Some(block) => block,
+        None => return,
+    };
+    let receipt = receipt_hash(result);
+    block.hashchain = next_hashchain(&block.hashchain, transaction_bytes, &receipt).0;
+    block.receipts.push(receipt.0);
+    block.gas_used = block.gas_used.saturating_add(result.gas_used);
+    storage_key::write_config_borsh(io, ConfigKey::TestBlock, &[], &block);
+}
+
+/// Closes the open block, which becomes the parent of the next one.
+pub fn end_block<I: IO>(io: &mut I) -> Result<TestBlockSummary, error::TestChainError> {
+    let block = get_open_block(io).ok_or(error::TestChainError::NoOpenBlock)?;
+    let summary = TestBlockSummary {
+        number: block.number,
+        timestamp: block.timestamp,
+        parent_hashchain: block.parent_hashchain,
+        hashchain: block.hashchain,
+        receipts_root: sdk::keccak(&block.receipts.concat()).0,
+        transactions: block.receipts.len() as u32,
+        gas_used: block.gas_used,
+    };
+    storage_key::remove_config(io, ConfigKey::TestBlock, &[]);
+    storage_key::write_config_borsh(io, ConfigKey::LastTestBlock, &[], &summary);
+    Ok(summary)
+}
+
+/// Environment of the transactions of the open block: the one they are executed in, with the
+/// number and the timestamp of the block.
+pub struct TestBlockEnv<'a, E> {
+    env: &'a E,
+    block: Option<(u64, Timestamp)>,
+}
+
+impl<'a, E: Env> TestBlockEnv<'a, E> {
+    pub fn new(env: &'a E, block: Option<&TestBlock>) -> Self {
+        Self {
+            env,
+            block: block.map(|block| (block.number, Timestamp::new(block.timestamp))),
+        }
+    }
+}
+
+impl<'a, E: Env> Env for TestBlockEnv<'a, E> {
+    fn signer_account_id(&self) -> AccountId {
+        self.env.signer_account_id()
+    }
+
+    fn current_account_id(&self) -> AccountId {
+        self.env.current_account_id()
+    }
+
+    fn predecessor_account_id(&self) -> AccountId {
+        self.env.predecessor_account_id()
+    }
+
+    fn block_height(&self) -> u64 {
+        self.block
+            .map_or_else(|| self.env.block_height(), |(number, _)| number)
+    }
+
+    fn block_timestamp(&self) -> Timestamp {
+        self.block
+            .map_or_else(|| self.env.block_timestamp(), |(_, timestamp)| timestamp)
+    }
+
+    fn attached_deposit(&self) -> u128 {
+        self.env.attached_deposit()
+    }
+
+    fn random_seed(&self) -> H256 {
+        self.env.random_seed()
+    }
+
+    fn prepaid_gas(&self) -> NearGas {
+        self.env.prepaid_gas()
+    }
+
+    fn used_gas(&self) -> NearGas {
+        self.env.used_gas()
+    }
+}
+
+pub mod error {
+    const NO_OPEN_BLOCK: &[u8; 17] = b"ERR_NO_OPEN_BLOCK";
+    const BLOCK_ALREADY_OPEN: &[u8; 22] = b"ERR_BLOCK_ALREADY_OPEN";
+    const BLOCK_NUMBER_NOT_INCREASING: &[u8; 31] = b"ERR_BLOCK_NUMBER_NOT_INCREASING";
+    const INVALID_BLOCK: &[u8; 17] = b"ERR_INVALID_BLOCK";
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum TestChainError {
+        NoOpenBlock,
+        BlockAlreadyOpen,
+        BlockNumberNotIncreasing,
+        /// The number or the timestamp of the block doesn't fit in 64 bits.
+        InvalidBlock,
+    }
+
+    impl AsRef<[u8]> for TestChainError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::NoOpenBlock => NO_OPEN_BLOCK,
+                Self::BlockAlreadyOpen => BLOCK_ALREADY_OPEN,
+                Self::BlockNumberNotIncreasing => BLOCK_NUMBER_NOT_INCREASING,
+                Self::InvalidBlock => INVALID_BLOCK,
+            }
+        }
+    }
+}
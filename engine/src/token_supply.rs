@@ -0,0 +1,138 @@
+//! Reconciliation of the supplies of the NEP-141 tokens bridged to Aurora with the ones of
+//! their ERC-20s.
+//!
+//! The NEP-141 tokens transferred to the engine are locked in its account and minted as
+//! ERC-20, the exits burn the ERC-20 and unlock the NEP-141, so the amount locked is the total
+//! supply of the ERC-20. The engine counts the locked amount at these crossings, and a
+//! difference with the total supply means that one side changed without the other, e.g. the
+//! ERC-20 was minted by a compromised admin. The supplies are compared before every deposit,
+//! the token is paused on a mismatch: its deposits are refunded and its exits fail until the
+//! owner resumes it.
+//!
+//! The tokens bridged before the counters were introduced are tracked from their next
+//! deposit, starting from the total supply of their ERC-20 at that moment.
+use crate::engine::{self, Erc20ReadError};
+use crate::events;
+use crate::json::parse_json;
+use crate::parameters::TokenSupplyCheck;
+use crate::prelude::types::u256_to_arr;
+use crate::prelude::{sdk, AccountId, Address, ConfigKey, PromiseCreateArgs, U256};
+use crate::storage_key;
+use aurora_engine_sdk::env::Env;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+
+/// Amount of the NEP-141 token locked in the engine, `None` if it isn't tracked yet.
+pub fn get_locked<I: IO>(io: &I, nep141: &AccountId) -> Option<u128> {
+    storage_key::read_config(io, ConfigKey::TokenSupply, nep141.as_bytes())
+        .and_then(|value| value.to_value().ok())
+}
+
+fn set_locked<I: IO>(io: &mut I, nep141: &AccountId, locked: u128) {
+    storage_key::write_config_borsh(io, ConfigKey::TokenSupply, nep141.as_bytes(), &locked);
+}
+
+/// Starts tracking the supply of a newly deployed ERC-20, which has nothing locked.
+pub fn init_locked<I: IO>(io: &mut I, nep141: &AccountId) {
+    set_locked(io, nep141, 0);
+}
+
+/// Records the NEP-141 tokens locked for the ERC-20 minted by a deposit.
+pub fn record_mint<I: IO>(io: &mut I, nep141: &AccountId, amount: u128) {
+    if let Some(locked) = get_locked(io, nep141) {
+        set_locked(io, nep141, locked.saturating_add(amount));
+    }
+}
+
+/// Records the NEP-141 tokens unlocked for the ERC-20 burned by an exit.
+pub fn record_burn<I: IO>(io: &mut I, nep141: &AccountId, amount: u128) {
+    if let Some(locked) = get_locked(io, nep141) {
+        set_locked(io, nep141, locked.saturating_sub(amount));
+    }
+}
+
+/// Records the burn of the ERC-20 exit scheduling the promise, if it is one. The exits of a
+/// NEP-141 token are a transfer of the token to the NEAR recipient, or its withdrawal to
+/// Ethereum, with the amount in their JSON arguments.
+pub fn record_exit_promise<I: IO>(
+    io: &mut I,
+    current_account_id: &AccountId,
+    promise: &PromiseCreateArgs,
+) {
+    if promise.target_account_id == *current_account_id
+        || (promise.method != "ft_transfer" && promise.method != "withdraw")
+    {
+        return;
+    }
+    let amount = parse_json(&promise.args).and_then(|args| args.u128("amount").ok());
+    if let Some(amount) = amount {
+        record_burn(io, &promise.target_account_id, amount);
+    }
+}
+
+pub fn is_paused<I: IO>(io: &I, nep141: &AccountId) -> bool {
+    storage_key::has_config(io, ConfigKey::PausedToken, nep141.as_bytes())
+}
+
+pub fn set_paused<I: IO>(io: &mut I, nep141: &AccountId, paused: bool) {
+    if paused {
+        storage_key::write_config(io, ConfigKey::PausedToken, nep141.as_bytes(), &[1]);
+    } else {
+        storage_key::remove_config(io, ConfigKey::PausedToken, nep141.as_bytes());
+    }
+}
+
+/// Resumes the token, which is tracked from its next deposit as a token bridged before the
+/// counters.
+pub fn resume<I: IO>(io: &mut I, nep141: &AccountId) {
+    set_paused(io, nep141, false);
+    storage_key::remove_config(io, ConfigKey::TokenSupply, nep141.as_bytes());
+}
+
+/// Compares the amount of the NEP-141 token locked with the total supply of its ERC-20.
+pub fn check<I: IO + Copy, E: Env>(
+    io: I,
+    env: &E,
+    nep141: &AccountId,
+) -> Result<TokenSupplyCheck, Erc20ReadError> {
+    let erc20 = engine::get_erc20_from_nep141(&io, nep141)
+        .ok()
+        .filter(|erc20| erc20.len() == 20)
+        .map(|erc20| Address::from_slice(&erc20))
+        .ok_or(Erc20ReadError::NotBridged)?;
+    let total_supply = engine::get_erc20_total_supply(io, env, erc20)?;
+    let locked = get_locked(&io, nep141);
+    Ok(TokenSupplyCheck {
+        erc20: erc20.0,
+        locked,
+        erc20_total_supply: u256_to_arr(&total_supply),
+        diverged: locked.map_or(false, |locked| U256::from(locked) != total_supply),
+        paused: is_paused(&io, nep141),
+    })
+}
+
+/// Checks the supplies of the token before a deposit, pausing it on a mismatch. Returns
+/// whether the deposit can proceed. An untracked token is tracked from the total supply of its
+/// ERC-20.
+pub fn reconcile<I: IO + Copy, E: Env>(mut io: I, env: &E, nep141: &AccountId) -> bool {
+    if is_paused(&io, nep141) {
+        return false;
+    }
+    let check = match check(io, env, nep141) {
+        Ok(check) => check,
+        Err(_) => return false,
+    };
+    let total_supply = U256::from_big_endian(&check.erc20_total_supply);
+    if check.locked.is_none() {
+        if total_supply > U256::from(u128::MAX) {
+            return false;
+        }
+        set_locked(&mut io, nep141, total_supply.as_u128());
+        return true;
+    }
+    if check.diverged {
+        set_paused(&mut io, nep141, true);
+        sdk::log!(&events::token_supply_mismatch_log(nep141, &check));
+        return false;
+    }
+    true
+}
@@ -13,6 +13,9 @@ pub struct AccessTuple {
 
 impl Decodable for AccessTuple {
     fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        if rlp.item_count() != Ok(2) {
+            return Err(rlp::DecoderError::RlpIncorrectListLen);
+        }
         let address = rlp.val_at(0)?;
         let storage_keys = rlp.list_at(1)?;
 
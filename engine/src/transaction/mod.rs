@@ -20,7 +20,9 @@ impl TryFrom<&[u8]> for EthTransactionKind {
     type Error = ParseTransactionError;
 
     fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes[0] == eip_2930::TYPE_BYTE {
+        if bytes.is_empty() {
+            Err(ParseTransactionError::EmptyInput)
+        } else if bytes[0] == eip_2930::TYPE_BYTE {
             Ok(Self::Eip2930(eip_2930::SignedTransaction2930::decode(
                 &Rlp::new(&bytes[1..]),
             )?))
@@ -119,6 +121,18 @@ impl From<EthTransactionKind> for NormalizedEthTransaction {
 }
 
 impl NormalizedEthTransaction {
+    /// Fee per gas paid on top of the base fee, see EIP-1559. The maximum fee of the
+    /// transaction must be at least the base fee.
+    pub fn priority_fee_per_gas(&self, base_fee_per_gas: U256) -> U256 {
+        self.max_priority_fee_per_gas
+            .min(self.max_fee_per_gas.saturating_sub(base_fee_per_gas))
+    }
+
+    /// Gas price paid by the transaction in a block with the given base fee.
+    pub fn effective_gas_price(&self, base_fee_per_gas: U256) -> U256 {
+        self.priority_fee_per_gas(base_fee_per_gas) + base_fee_per_gas
+    }
+
     pub fn intrinsic_gas(&self, config: &evm::Config) -> Option<u64> {
         let is_contract_creation = self.to.is_none();
 
@@ -158,6 +172,7 @@ impl NormalizedEthTransaction {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum ParseTransactionError {
+    EmptyInput,
     UnknownTransactionType,
     // Per the EIP-2718 spec 0xff is a reserved value
     ReservedSentinel,
@@ -173,6 +188,7 @@ impl From<DecoderError> for ParseTransactionError {
 impl AsRef<[u8]> for ParseTransactionError {
     fn as_ref(&self) -> &[u8] {
         match self {
+            Self::EmptyInput => b"ERR_EMPTY_TX",
             Self::UnknownTransactionType => b"ERR_UNKNOWN_TX_TYPE",
             Self::ReservedSentinel => b"ERR_RESERVED_LEADING_TX_BYTE",
             Self::RlpDecodeError(_) => b"ERR_TX_RLP_DECODE",
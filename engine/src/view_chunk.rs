@@ -0,0 +1,84 @@
+//! Chunked reading of the results of the views, for the ones too large to return at once.
+//!
+//! NEAR limits the size of the value a call returns, and the RPC nodes limit the size of their
+//! responses further, so the views returning contract code or the output of an EVM call can
+//! fail with no usable error. `view_chunk` computes the result of the requested view again on
+//! every call, and returns only the bytes of one chunk of it, with the number of chunks. A
+//! client calls it with the index `0` first, then for the following indices, and concatenates
+//! the data of the chunks. The state has to stay the same between the calls for the chunks to
+//! be consistent, so the calls should be done at the same block.
+use crate::parameters::ViewChunk;
+use crate::prelude::{TryFrom, Vec};
+
+/// Maximum number of bytes of a chunk.
+pub const VIEW_CHUNK_SIZE: usize = 32 * 1024;
+
+/// Number of chunks of a result of the given size, an empty result is a single empty chunk.
+pub fn total_chunks(len: usize) -> usize {
+    if len == 0 {
+        1
+    } else {
+        (len + VIEW_CHUNK_SIZE - 1) / VIEW_CHUNK_SIZE
+    }
+}
+
+/// Returns the chunk of the result at the index.
+pub fn get_chunk(result: &[u8], index: u32) -> Result<ViewChunk, error::ViewChunkError> {
+    let total_chunks = total_chunks(result.len());
+    let index = index as usize;
+    if index >= total_chunks {
+        return Err(error::ViewChunkError::InvalidIndex);
+    }
+    let start = index * VIEW_CHUNK_SIZE;
+    let end = result.len().min(start + VIEW_CHUNK_SIZE);
+    Ok(ViewChunk {
+        total_chunks: u32::try_from(total_chunks).map_err(|_| error::ViewChunkError::TooLarge)?,
+        data: result[start..end].to_vec(),
+    })
+}
+
+pub mod error {
+    const INVALID_INDEX: &[u8; 23] = b"ERR_INVALID_CHUNK_INDEX";
+    const TOO_LARGE: &[u8; 20] = b"ERR_RESULT_TOO_LARGE";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum ViewChunkError {
+        /// The index is not lower than the number of chunks of the result.
+        InvalidIndex,
+        /// The number of chunks doesn't fit in a `u32`.
+        TooLarge,
+    }
+
+    impl AsRef<[u8]> for ViewChunkError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::InvalidIndex => INVALID_INDEX,
+                Self::TooLarge => TOO_LARGE,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_chunk() {
+        let empty = get_chunk(&[], 0).unwrap();
+        assert_eq!(empty.total_chunks, 1);
+        assert!(empty.data.is_empty());
+        assert_eq!(get_chunk(&[], 1), Err(error::ViewChunkError::InvalidIndex));
+
+        let result: Vec<u8> = (0..2 * VIEW_CHUNK_SIZE + 1).map(|i| i as u8).collect();
+        let chunks: Vec<ViewChunk> = (0..3).map(|i| get_chunk(&result, i).unwrap()).collect();
+        assert!(chunks.iter().all(|chunk| chunk.total_chunks == 3));
+        assert_eq!(chunks[2].data.len(), 1);
+        let joined: Vec<u8> = chunks.into_iter().flat_map(|chunk| chunk.data).collect();
+        assert_eq!(joined, result);
+        assert_eq!(
+            get_chunk(&result, 3),
+            Err(error::ViewChunkError::InvalidIndex)
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Accounting of the storage a transaction touches, the state witness of its receipt.
+//!
+//! With stateless validation, the chunk producers of NEAR have to send the values a receipt
+//! reads and writes to the validators, and a receipt touching too much storage fails at the
+//! protocol level, with no trace of the cause. `WitnessTracker` counts the storage operations
+//! of the transactions of a receipt and the bytes of their keys and values, which the entry
+//! points executing transactions report in the `witness_size` event of the receipt. The owner
+//! can set a limit on the touched bytes, above which the receipt fails with an engine error
+//! instead.
+use crate::prelude::{BorshDeserialize, BorshSerialize, ConfigKey};
+use crate::storage_key;
+use aurora_engine_sdk::io::{StorageIntermediate, IO};
+use core::cell::Cell;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WitnessSize {
+    /// Number of storage reads, including the checks for a key.
+    pub reads: u64,
+    /// Number of storage writes and removals.
+    pub writes: u64,
+    /// Bytes of the keys and values read and written.
+    pub touched_bytes: u64,
+}
+
+impl WitnessSize {
+    fn add_read(&mut self, key: &[u8], value_len: usize) {
+        self.reads += 1;
+        self.touched_bytes = self
+            .touched_bytes
+            .saturating_add((key.len() + value_len) as u64);
+    }
+
+    fn add_write(&mut self, key: &[u8], value_len: usize) {
+        self.writes += 1;
+        self.touched_bytes = self
+            .touched_bytes
+            .saturating_add((key.len() + value_len) as u64);
+    }
+}
+
+/// IO counting the storage operations done through it in the given `WitnessSize`. The copies
+/// of the tracker share the same counters.
+pub struct WitnessTracker<'a, I> {
+    io: I,
+    size: &'a Cell<WitnessSize>,
+}
+
+impl<'a, I: Copy> Clone for WitnessTracker<'a, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, I: Copy> Copy for WitnessTracker<'a, I> {}
+
+impl<'a, I: IO> WitnessTracker<'a, I> {
+    pub fn new(io: I, size: &'a Cell<WitnessSize>) -> Self {
+        Self { io, size }
+    }
+
+    fn update<F: FnOnce(&mut WitnessSize)>(&self, f: F) {
+        let mut size = self.size.get();
+        f(&mut size);
+        self.size.set(size);
+    }
+}
+
+impl<'a, I: IO> IO for WitnessTracker<'a, I> {
+    type StorageValue = I::StorageValue;
+
+    fn read_input(&self) -> Self::StorageValue {
+        self.io.read_input()
+    }
+
+    fn return_output(&mut self, value: &[u8]) {
+        self.io.return_output(value)
+    }
+
+    fn read_storage(&self, key: &[u8]) -> Option<Self::StorageValue> {
+        let value = self.io.read_storage(key);
+        let value_len = value.as_ref().map(|value| value.len()).unwrap_or(0);
+        self.update(|size| size.add_read(key, value_len));
+        value
+    }
+
+    fn storage_has_key(&self, key: &[u8]) -> bool {
+        self.update(|size| size.add_read(key, 0));
+        self.io.storage_has_key(key)
+    }
+
+    fn write_storage(&mut self, key: &[u8], value: &[u8]) -> Option<Self::StorageValue> {
+        self.update(|size| size.add_write(key, value.len()));
+        self.io.write_storage(key, value)
+    }
+
+    fn write_storage_direct(
+        &mut self,
+        key: &[u8],
+        value: Self::StorageValue,
+    ) -> Option<Self::StorageValue> {
+        let value_len = value.len();
+        self.update(|size| size.add_write(key, value_len));
+        self.io.write_storage_direct(key, value)
+    }
+
+    fn remove_storage(&mut self, key: &[u8]) -> Option<Self::StorageValue> {
+        self.update(|size| size.add_write(key, 0));
+        self.io.remove_storage(key)
+    }
+
+    fn read_storage_len(&self, key: &[u8]) -> Option<usize> {
+        let value_len = self.io.read_storage_len(key);
+        self.update(|size| size.add_read(key, value_len.unwrap_or(0)));
+        value_len
+    }
+}
+
+/// Returns the maximum number of bytes the transactions of a receipt can touch, if any.
+pub fn get_witness_size_limit<I: IO>(io: &I) -> Option<u64> {
+    storage_key::read_config_u64(io, ConfigKey::WitnessSizeLimit, &[])
+}
+
+pub fn set_witness_size_limit<I: IO>(io: &mut I, limit: Option<u64>) {
+    match limit {
+        Some(limit) => {
+            storage_key::write_config(io, ConfigKey::WitnessSizeLimit, &[], &limit.to_le_bytes())
+        }
+        None => storage_key::remove_config(io, ConfigKey::WitnessSizeLimit, &[]),
+    }
+}
+
+pub fn check_witness_size(
+    size: &WitnessSize,
+    limit: Option<u64>,
+) -> Result<(), error::WitnessSizeError> {
+    match limit {
+        Some(limit) if size.touched_bytes > limit => Err(error::WitnessSizeError::LimitExceeded),
+        _ => Ok(()),
+    }
+}
+
+pub mod error {
+    const LIMIT_EXCEEDED: &[u8; 25] = b"ERR_WITNESS_SIZE_EXCEEDED";
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum WitnessSizeError {
+        /// The transaction touched more storage bytes than the limit set by the owner.
+        LimitExceeded,
+    }
+
+    impl AsRef<[u8]> for WitnessSizeError {
+        fn as_ref(&self) -> &[u8] {
+            match self {
+                Self::LimitExceeded => LIMIT_EXCEEDED,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_witness_size() {
+        let mut size = WitnessSize::default();
+        size.add_read(&[0u8; 22], 32);
+        size.add_write(&[0u8; 22], 32);
+        size.add_write(&[0u8; 22], 0);
+        assert_eq!(
+            size,
+            WitnessSize {
+                reads: 1,
+                writes: 2,
+                touched_bytes: 130,
+            }
+        );
+        assert_eq!(check_witness_size(&size, None), Ok(()));
+        assert_eq!(check_witness_size(&size, Some(130)), Ok(()));
+        assert_eq!(
+            check_witness_size(&size, Some(129)),
+            Err(error::WitnessSizeError::LimitExceeded)
+        );
+    }
+}